@@ -113,7 +113,7 @@ fn test_plugin(path: &Path) -> Row {
     let info = plugin.info().clone();
     row.vendor = info.vendor.clone();
     row.version = info.version.clone();
-    row.category = info.category.clone();
+    row.category = info.category();
     row.midi_out = if info.has_midi_output {
         Cell::Yes(String::new())
     } else {
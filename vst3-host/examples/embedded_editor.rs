@@ -103,17 +103,13 @@ impl eframe::App for App {
         });
 
         egui::CentralPanel::default().show(ctx, |ui| {
-            // Reserve the editor's area; the native editor view overlays exactly this rect.
-            let desired = egui::vec2(self.editor_size.0 as f32, self.editor_size.1 as f32);
-            let (rect, _) = ui.allocate_exact_size(desired, egui::Sense::hover());
-            if let Some(editor) = &self.editor {
-                editor.set_rect(EditorRect {
-                    x: rect.min.x,
-                    y: rect.min.y,
-                    width: rect.width(),
-                    height: rect.height(),
-                });
+            if let Some(editor) = &mut self.editor {
+                // Offer the editor the whole panel; it is resized (within its own limits) as
+                // the window is, and the native view overlays the area it settles on.
+                editor.show(ui);
             } else {
+                let desired = egui::vec2(self.editor_size.0 as f32, self.editor_size.1 as f32);
+                let (rect, _) = ui.allocate_exact_size(desired, egui::Sense::hover());
                 ui.painter()
                     .rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
                 ui.painter().text(
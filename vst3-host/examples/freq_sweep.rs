@@ -0,0 +1,86 @@
+//! # Frequency Response Sweep Example
+//!
+//! Sweeps one parameter of an effect (typically a filter cutoff) across its range, plays an
+//! impulse through the plugin at every step and prints the measured magnitude response at a
+//! handful of octave-spaced frequencies — one row per parameter value.
+//!
+//! ## Usage
+//! ```bash
+//! # Sweep parameter 0 in 9 steps
+//! cargo run --example freq_sweep -- /path/to/filter.vst3 0
+//!
+//! # Sweep parameter 3 in 17 steps
+//! cargo run --example freq_sweep -- /path/to/filter.vst3 3 17
+//! ```
+//!
+//! ## Example Output
+//! ```text
+//! Sweeping 'Cutoff' (id 0) of SomeFilter in 9 steps
+//!
+//! value           63 Hz   125 Hz   250 Hz ...
+//! 20 Hz           -10.2    -15.9    -21.8 ...
+//! ...
+//! ```
+
+use vst3_host::{ParameterSweep, Vst3Host};
+
+/// Impulse length: 4096 samples gives ~12 Hz bins at 48 kHz.
+const IMPULSE_LEN: usize = 4096;
+
+fn main() -> vst3_host::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let (Some(path), Some(param)) = (args.next(), args.next()) else {
+        eprintln!("usage: freq_sweep <plugin.vst3> <param_id> [steps]");
+        std::process::exit(1);
+    };
+    let param_id: u32 = param
+        .parse()
+        .map_err(|e| vst3_host::Error::InvalidParameter(format!("param id: {e}")))?;
+    let steps: usize = args.next().and_then(|s| s.parse().ok()).unwrap_or(9);
+
+    let mut host = Vst3Host::builder().sample_rate(48000.0).build()?;
+    let mut plugin = host.load_plugin(&path)?;
+    let name = plugin
+        .get_parameters()?
+        .into_iter()
+        .find(|p| p.id == param_id)
+        .map(|p| p.name)
+        .ok_or_else(|| vst3_host::Error::InvalidParameter(format!("no parameter {param_id}")))?;
+    println!(
+        "Sweeping '{name}' (id {param_id}) of {} in {steps} steps\n",
+        plugin.info().name
+    );
+
+    let mut impulse = vec![0.0; IMPULSE_LEN];
+    impulse[0] = 1.0;
+    let points = ParameterSweep::frequency_response(&mut plugin, param_id, &impulse, steps)?;
+
+    // Report the bins nearest to each octave from 63 Hz up to 16 kHz.
+    let freqs = ParameterSweep::bin_frequencies(IMPULSE_LEN, plugin.sample_rate());
+    let columns: Vec<usize> = (0..9)
+        .map(|octave| 62.5 * 2f32.powi(octave))
+        .map(|target| {
+            (0..freqs.len())
+                .min_by(|&a, &b| {
+                    (freqs[a] - target)
+                        .abs()
+                        .total_cmp(&(freqs[b] - target).abs())
+                })
+                .unwrap_or(0)
+        })
+        .collect();
+
+    print!("{:<14}", "value");
+    for &bin in &columns {
+        print!("{:>9}", format!("{:.0} Hz", freqs[bin]));
+    }
+    println!();
+    for point in &points {
+        print!("{:<14}", point.display_value);
+        for &bin in &columns {
+            print!("{:>9.1}", point.magnitude_db[bin]);
+        }
+        println!();
+    }
+    Ok(())
+}
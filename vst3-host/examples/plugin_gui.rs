@@ -200,6 +200,7 @@ impl PluginGuiApp {
                                     let sample_rate = 48000.0; // This should come from the app state
                                     let mut buffers = AudioBuffers {
                                         inputs: vec![], // No inputs for instruments
+                                        sidechain_inputs: vec![],
                                         outputs: vec![
                                             vec![0.0; block_size], // Left
                                             vec![0.0; block_size], // Right
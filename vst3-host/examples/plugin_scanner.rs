@@ -62,83 +62,33 @@ fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
                 current, total, plugin.name, plugin.vendor
             );
         }
+        DiscoveryProgress::Scanned { path, elapsed } if elapsed.as_secs() >= 1 => {
+            println!("Slow: {} took {:.1?}", path.display(), elapsed);
+        }
         DiscoveryProgress::Error { path, error } => {
             println!("Error scanning {}: {}", path, error);
         }
         DiscoveryProgress::Completed { total_found } => {
             println!("\nScan complete! Found {} plugins.", total_found);
         }
+        _ => {}
     })?;
 
     // Display results
     println!("\n{} plugins found:\n", plugins.len());
 
-    // Group by category
-    let mut by_category: std::collections::HashMap<String, Vec<&PluginInfo>> =
-        std::collections::HashMap::new();
-
-    for plugin in &plugins {
-        by_category
-            .entry(plugin.category.clone())
-            .or_default()
-            .push(plugin);
-    }
-
-    // Display by category
-    let mut categories: Vec<_> = by_category.keys().collect();
-    categories.sort();
-
-    for category in categories {
-        let plugins_in_category = &by_category[category];
-        println!("{} ({} plugins)", category, plugins_in_category.len());
-        println!("{}", "-".repeat(category.len() + 12));
-
-        for plugin in plugins_in_category {
-            println!(
-                "  • {} v{} by {}",
-                plugin.name, plugin.version, plugin.vendor
-            );
-
-            // Show capabilities
-            let mut caps = Vec::new();
-
-            if plugin.audio_inputs > 0 {
-                caps.push(format!("{}in", plugin.audio_inputs));
-            }
-            if plugin.audio_outputs > 0 {
-                caps.push(format!("{}out", plugin.audio_outputs));
-            }
-            if plugin.has_midi_input {
-                caps.push("MIDI".to_string());
-            }
-            if plugin.has_gui {
-                caps.push("GUI".to_string());
-            }
-
-            if !caps.is_empty() {
-                println!("    [{}]", caps.join(", "));
-            }
-
-            println!("    Path: {}", plugin.path.display());
-            println!();
-        }
-        println!();
-    }
+    // Display by category, one indentation level per sub-category
+    let tree = host.plugins_by_category_tree();
+    print_category(&tree, "Uncategorized", 0);
 
     // Summary statistics
     println!("Summary");
     println!("-------");
     println!("Total plugins: {}", plugins.len());
-    println!("Categories: {}", by_category.len());
+    println!("Top-level categories: {}", tree.children.len());
 
-    let instruments = plugins
-        .iter()
-        .filter(|p| p.category.contains("Instrument"))
-        .count();
-    let effects = plugins
-        .iter()
-        .filter(|p| p.category.contains("Fx") || p.category.contains("Effect"))
-        .count();
+    let instruments = host.plugins_in_category(&["Instrument"]).len();
+    let effects = host.plugins_in_category(&["Fx"]).len();
     let midi_plugins = plugins.iter().filter(|p| p.has_midi_input).count();
     let gui_plugins = plugins.iter().filter(|p| p.has_gui).count();
 
@@ -150,6 +100,43 @@ fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Print `tree`'s own plugins under `name`, then each sub-category indented below it.
+fn print_category(tree: &CategoryTree, name: &str, depth: usize) {
+    let indent = "  ".repeat(depth);
+    if !tree.plugins.is_empty() || depth > 0 {
+        println!("{}{} ({} plugins)", indent, name, tree.plugin_count());
+    }
+    for plugin in &tree.plugins {
+        // Show capabilities
+        let mut caps = Vec::new();
+        if plugin.audio_inputs > 0 {
+            caps.push(format!("{}in", plugin.audio_inputs));
+        }
+        if plugin.audio_outputs > 0 {
+            caps.push(format!("{}out", plugin.audio_outputs));
+        }
+        if plugin.has_midi_input {
+            caps.push("MIDI".to_string());
+        }
+        if plugin.has_gui {
+            caps.push("GUI".to_string());
+        }
+
+        println!(
+            "{}  • {} v{} by {} [{}]",
+            indent,
+            plugin.name,
+            plugin.version,
+            plugin.vendor,
+            caps.join(", ")
+        );
+        println!("{}    Path: {}", indent, plugin.path.display());
+    }
+    for (child, subtree) in &tree.children {
+        print_category(subtree, child, depth + 1);
+    }
+}
+
 fn usage() {
     eprintln!("Usage: plugin_scanner [OPTIONS] [additional_paths...]");
     eprintln!();
@@ -122,7 +122,7 @@ fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     println!("Name:           {}", info.name);
     println!("Vendor:         {}", info.vendor);
     println!("Version:        {}", info.version);
-    println!("Category:       {}", info.category);
+    println!("Category:       {}", info.category());
     println!(
         "Has GUI:        {}",
         if info.has_gui { "Yes" } else { "No" }
@@ -0,0 +1,752 @@
+//! Offline measurements of a plugin's audio behaviour.
+//!
+//! [`ParameterSweep::frequency_response`] steps one parameter across its range, plays the same
+//! test signal through the plugin at every step and compares the output spectrum with the
+//! input's. The result is a matrix of parameter value × frequency bin magnitudes — e.g. how a
+//! filter's response moves as its cutoff is swept — ready to plot as a color map.
+//!
+//! [`BlockStatistics`] runs alongside live processing instead: it accumulates the distribution
+//! of the output samples (mean, variance, kurtosis and a histogram) block by block.
+//! [`SpectrumAnalyzer`] does the same for the output's magnitude spectrum.
+
+use crate::{
+    audio::{AudioBuffers, WindowFunction},
+    error::{Error, Result},
+    plugin::Plugin,
+};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Magnitudes are clamped to this floor, in dB, so silent bins don't become `-inf`.
+pub const MIN_MAGNITUDE_DB: f32 = -120.0;
+
+/// The plugin's response at one parameter value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyResponsePoint {
+    /// The parameter's normalized value (`0.0..=1.0`)
+    pub normalized_param: f64,
+    /// The value as the plugin displays it (e.g. "1.2 kHz")
+    pub display_value: String,
+    /// Output over input magnitude per FFT bin, in dB. Bin `k` is at
+    /// `k * sample_rate / fft_len`; see [`ParameterSweep::bin_frequencies`].
+    pub magnitude_db: Vec<f32>,
+}
+
+/// Measure how a plugin's frequency response changes across one parameter's range.
+pub struct ParameterSweep;
+
+impl ParameterSweep {
+    /// Set `param_id` to `num_steps` evenly spaced normalized values from 0.0 to 1.0 and, at
+    /// each, feed `input_signal` to every audio input and compare the first output channel's
+    /// spectrum with the input's.
+    ///
+    /// Before each measurement the plugin processes as much silence as the signal is long, so
+    /// the previous step's tail doesn't leak into the next. An impulse (a single `1.0` then
+    /// zeros) or white noise makes a good input; the FFT covers the signal zero-padded to the
+    /// next power of two. Processing is started for the sweep if needed (and stopped again),
+    /// and the parameter is restored afterwards.
+    pub fn frequency_response(
+        plugin: &mut Plugin,
+        param_id: u32,
+        input_signal: &[f32],
+        num_steps: usize,
+    ) -> Result<Vec<FrequencyResponsePoint>> {
+        if input_signal.is_empty() {
+            return Err(Error::InvalidParameter(
+                "sweep input signal is empty".to_string(),
+            ));
+        }
+        if num_steps == 0 {
+            return Err(Error::InvalidParameter(
+                "sweep needs at least one step".to_string(),
+            ));
+        }
+        let fft_len = input_signal.len().next_power_of_two();
+        let input_spectrum = magnitude_spectrum(input_signal, fft_len);
+        let original = plugin.get_parameter(param_id)?;
+        let was_processing = plugin.is_processing();
+        plugin.start_processing()?;
+
+        let silence = vec![0.0; input_signal.len()];
+        let measure = |plugin: &mut Plugin, value: f64| -> Result<FrequencyResponsePoint> {
+            plugin.set_parameter(param_id, value)?;
+            render(plugin, &silence)?;
+            let output = render(plugin, input_signal)?;
+            let output_spectrum = magnitude_spectrum(&output, fft_len);
+            let magnitude_db = output_spectrum
+                .iter()
+                .zip(&input_spectrum)
+                .map(|(&y, &x)| {
+                    let db = 20.0 * (y.max(1e-12) / x.max(1e-12)).log10();
+                    (db as f32).max(MIN_MAGNITUDE_DB)
+                })
+                .collect();
+            Ok(FrequencyResponsePoint {
+                normalized_param: value,
+                display_value: plugin
+                    .format_parameter(param_id, value)
+                    .unwrap_or_else(|_| format!("{value:.3}")),
+                magnitude_db,
+            })
+        };
+        let points = (0..num_steps)
+            .map(|step| {
+                let value = if num_steps == 1 {
+                    0.0
+                } else {
+                    step as f64 / (num_steps - 1) as f64
+                };
+                measure(plugin, value)
+            })
+            .collect::<Result<Vec<_>>>();
+
+        let restored = plugin.set_parameter(param_id, original);
+        if !was_processing {
+            plugin.stop_processing()?;
+        }
+        let points = points?;
+        restored?;
+        Ok(points)
+    }
+
+    /// The center frequency of each bin [`Self::frequency_response`] reports for an input of
+    /// `input_len` samples at `sample_rate`.
+    pub fn bin_frequencies(input_len: usize, sample_rate: f64) -> Vec<f32> {
+        let fft_len = input_len.max(1).next_power_of_two();
+        (0..=fft_len / 2)
+            .map(|bin| (bin as f64 * sample_rate / fft_len as f64) as f32)
+            .collect()
+    }
+}
+
+/// Play `input` through every audio input of `plugin` block by block and return the first
+/// output channel.
+fn render(plugin: &mut Plugin, input: &[f32]) -> Result<Vec<f32>> {
+    let sample_rate = plugin.sample_rate();
+    let block = plugin.block_size().max(1);
+    let inputs = plugin.info().audio_inputs as usize;
+    let outputs = plugin.output_channel_count().max(1);
+    let mut rendered = Vec::with_capacity(input.len());
+    for chunk in input.chunks(block) {
+        let mut buffers = AudioBuffers::new(inputs, outputs, chunk.len(), sample_rate);
+        for channel in &mut buffers.inputs {
+            channel[..chunk.len()].copy_from_slice(chunk);
+        }
+        plugin.process_audio(&mut buffers)?;
+        let out = &buffers.outputs[0];
+        let n = chunk.len().min(out.len());
+        rendered.extend_from_slice(&out[..n]);
+        rendered.resize(rendered.len() + chunk.len() - n, 0.0);
+    }
+    Ok(rendered)
+}
+
+/// `|X[k]|` for bins `0..=len / 2` of `signal` zero-padded to `len` (a power of two).
+fn magnitude_spectrum(signal: &[f32], len: usize) -> Vec<f64> {
+    let mut re: Vec<f64> = signal.iter().map(|&s| s as f64).collect();
+    re.resize(len, 0.0);
+    let mut im = vec![0.0; len];
+    fft(&mut re, &mut im);
+    re.iter()
+        .zip(&im)
+        .take(len / 2 + 1)
+        .map(|(r, i)| r.hypot(*i))
+        .collect()
+}
+
+/// In-place iterative radix-2 FFT; `re.len()` must be a power of two.
+fn fft(re: &mut [f64], im: &mut [f64]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut size = 2;
+    while size <= n {
+        let angle = -2.0 * std::f64::consts::PI / size as f64;
+        for start in (0..n).step_by(size) {
+            for k in 0..size / 2 {
+                let (sin, cos) = (angle * k as f64).sin_cos();
+                let (a, b) = (start + k, start + k + size / 2);
+                let t_re = re[b] * cos - im[b] * sin;
+                let t_im = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        size *= 2;
+    }
+}
+
+/// FFT length of a [`SpectrumAnalyzer::default`], and of the one a
+/// [`Vst3Host`](crate::Vst3Host) feeds ([`crate::Vst3Host::spectrum`]).
+pub const DEFAULT_SPECTRUM_SIZE: usize = 2048;
+
+/// A magnitude spectrum of audio fed in as it plays.
+///
+/// Samples are gathered into non-overlapping frames of [`size`](Self::size), each multiplied
+/// by the [window](Self::set_window) and transformed. [`spectrum_db`](Self::spectrum_db) is the
+/// linear average of the last [`averaging`](Self::averaging) frames' magnitudes, in dB, scaled
+/// so a full-scale sine reads 0 dB whichever window is used.
+///
+/// All buffers are allocated up front (and again by [`Self::set_averaging`]), so
+/// [`Self::push`] can run on the audio thread.
+#[derive(Debug, Clone)]
+pub struct SpectrumAnalyzer {
+    size: usize,
+    window: WindowFunction,
+    coefficients: Vec<f32>,
+    // 2 / Σw: undoes the window's coherent gain and folds in the negative frequencies.
+    scale: f64,
+    // Linear magnitudes of the last `frames.len()` frames, used as a ring.
+    frames: Vec<Vec<f64>>,
+    next: usize,
+    filled: usize,
+    pending: Vec<f32>,
+    re: Vec<f64>,
+    im: Vec<f64>,
+    spectrum_db: Vec<f32>,
+}
+
+impl Default for SpectrumAnalyzer {
+    fn default() -> Self {
+        Self::new(DEFAULT_SPECTRUM_SIZE)
+    }
+}
+
+impl SpectrumAnalyzer {
+    /// An analyzer with frames of `size` samples (rounded up to a power of two, at least 16),
+    /// a rectangular window and no averaging.
+    pub fn new(size: usize) -> Self {
+        let size = size.max(16).next_power_of_two();
+        let mut analyzer = Self {
+            size,
+            window: WindowFunction::Rectangular,
+            coefficients: Vec::new(),
+            scale: 0.0,
+            frames: Vec::new(),
+            next: 0,
+            filled: 0,
+            pending: Vec::with_capacity(size),
+            re: vec![0.0; size],
+            im: vec![0.0; size],
+            spectrum_db: vec![MIN_MAGNITUDE_DB; size / 2 + 1],
+        };
+        analyzer.set_window(WindowFunction::Rectangular);
+        analyzer.set_averaging(1);
+        analyzer
+    }
+
+    /// Multiply every frame by `window` before the FFT, from the next frame on.
+    pub fn set_window(&mut self, window: WindowFunction) {
+        self.window = window;
+        self.coefficients = window.coefficients(self.size);
+        let sum: f64 = self.coefficients.iter().map(|&w| w as f64).sum();
+        self.scale = 2.0 / sum;
+    }
+
+    /// The window applied before the FFT.
+    pub fn window(&self) -> WindowFunction {
+        self.window
+    }
+
+    /// Average the magnitudes of the last `frames` frames (at least 1, meaning no averaging).
+    /// Forgets the frames kept so far.
+    pub fn set_averaging(&mut self, frames: usize) {
+        self.frames = vec![vec![0.0; self.size / 2 + 1]; frames.max(1)];
+        self.next = 0;
+        self.filled = 0;
+    }
+
+    /// How many frames are averaged.
+    pub fn averaging(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Samples per frame (the FFT length).
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Feed `samples`, analysing every frame they complete. Returns whether the spectrum
+    /// changed.
+    pub fn push(&mut self, mut samples: &[f32]) -> bool {
+        let mut updated = false;
+        while !samples.is_empty() {
+            let take = (self.size - self.pending.len()).min(samples.len());
+            self.pending.extend_from_slice(&samples[..take]);
+            samples = &samples[take..];
+            if self.pending.len() == self.size {
+                let frame = std::mem::take(&mut self.pending);
+                self.analyze(&frame);
+                self.pending = frame;
+                self.pending.clear();
+                updated = true;
+            }
+        }
+        updated
+    }
+
+    /// Analyse one frame (zero-padded or cut to [`Self::size`]) and return the updated
+    /// spectrum.
+    pub fn analyze(&mut self, frame: &[f32]) -> &[f32] {
+        for (i, (re, im)) in self.re.iter_mut().zip(&mut self.im).enumerate() {
+            let sample = frame
+                .get(i)
+                .copied()
+                .filter(|s| s.is_finite())
+                .unwrap_or(0.0);
+            *re = sample as f64 * self.coefficients[i] as f64;
+            *im = 0.0;
+        }
+        fft(&mut self.re, &mut self.im);
+        let slot = &mut self.frames[self.next];
+        for (bin, magnitude) in slot.iter_mut().enumerate() {
+            *magnitude = self.re[bin].hypot(self.im[bin]) * self.scale;
+        }
+        self.next = (self.next + 1) % self.frames.len();
+        self.filled = (self.filled + 1).min(self.frames.len());
+
+        let frames = &self.frames[..self.filled];
+        for (bin, db) in self.spectrum_db.iter_mut().enumerate() {
+            let mean = frames.iter().map(|f| f[bin]).sum::<f64>() / frames.len() as f64;
+            *db = ((20.0 * mean.max(1e-12).log10()) as f32).max(MIN_MAGNITUDE_DB);
+        }
+        &self.spectrum_db
+    }
+
+    /// The magnitude per bin in dB (relative to a full-scale sine), bins `0..=size / 2`.
+    /// Bin `k` is at `k * sample_rate / size`; see [`Self::bin_frequencies`].
+    pub fn spectrum_db(&self) -> &[f32] {
+        &self.spectrum_db
+    }
+
+    /// The center frequency of each bin at `sample_rate`.
+    pub fn bin_frequencies(&self, sample_rate: f64) -> Vec<f32> {
+        (0..=self.size / 2)
+            .map(|bin| (bin as f64 * sample_rate / self.size as f64) as f32)
+            .collect()
+    }
+
+    /// Forget the samples and frames seen so far; the settings are kept.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.next = 0;
+        self.filled = 0;
+        self.spectrum_db.fill(MIN_MAGNITUDE_DB);
+    }
+}
+
+/// Feeds a plugin's output, mixed to mono, into the host's shared [`SpectrumAnalyzer`]
+/// ([`crate::Vst3Host::spectrum`]).
+pub(crate) struct SpectrumTap {
+    analyzer: Arc<Mutex<SpectrumAnalyzer>>,
+    mix: Vec<f32>,
+}
+
+impl SpectrumTap {
+    pub(crate) fn new(analyzer: Arc<Mutex<SpectrumAnalyzer>>) -> Self {
+        Self {
+            analyzer,
+            mix: Vec::new(),
+        }
+    }
+
+    /// Mix the output channels down and feed them in. Skips the block (never waits) while the
+    /// UI holds the analyzer, since this runs on the audio thread.
+    pub(crate) fn process(&mut self, outputs: &[Vec<f32>]) {
+        let frames = outputs.iter().map(Vec::len).max().unwrap_or(0);
+        self.mix.clear();
+        self.mix.resize(frames, 0.0);
+        let gain = 1.0 / outputs.len().max(1) as f32;
+        for channel in outputs {
+            for (mix, &sample) in self.mix.iter_mut().zip(channel) {
+                *mix += sample * gain;
+            }
+        }
+        if let Ok(mut analyzer) = self.analyzer.try_lock() {
+            analyzer.push(&self.mix);
+        }
+    }
+
+    /// Start over, e.g. when processing restarts.
+    pub(crate) fn reset(&mut self) {
+        if let Ok(mut analyzer) = self.analyzer.try_lock() {
+            analyzer.reset();
+        }
+    }
+}
+
+/// Histogram bins in a [`BlockStatsSnapshot`], spread evenly over `-1.0..=1.0`.
+pub const HISTOGRAM_BINS: usize = 256;
+
+/// The output distribution accumulated by [`BlockStatistics`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockStatsSnapshot {
+    /// Samples accumulated
+    pub samples: u64,
+    /// Mean sample value (DC offset)
+    pub mean: f32,
+    /// Variance of the samples (the square of their RMS about the mean)
+    pub variance: f32,
+    /// Kurtosis (not excess): 3.0 for Gaussian noise, 1.8 for uniform noise, 1.5 for a sine
+    pub kurtosis: f32,
+    /// Sample counts per bin from -1.0 to +1.0; samples outside that land in the end bins
+    pub histogram: [u32; HISTOGRAM_BINS],
+}
+
+impl Default for BlockStatsSnapshot {
+    fn default() -> Self {
+        Self {
+            samples: 0,
+            mean: 0.0,
+            variance: 0.0,
+            kurtosis: 0.0,
+            histogram: [0; HISTOGRAM_BINS],
+        }
+    }
+}
+
+impl BlockStatsSnapshot {
+    /// Whether the output looks far from Gaussian (`|kurtosis - 3| > 1`), e.g. a plain tone
+    /// or hard clipping where noise was expected.
+    pub fn kurtosis_alert(&self) -> bool {
+        self.samples > 0 && (self.kurtosis - 3.0).abs() > 1.0
+    }
+}
+
+/// Running statistics over every sample of the blocks passed to [`Self::accumulate`].
+///
+/// Raw power sums are kept in `f64`, so the moments stay accurate over long runs of audio.
+#[derive(Debug, Clone)]
+pub struct BlockStatistics {
+    count: u64,
+    sums: [f64; 4],
+    histogram: [u32; HISTOGRAM_BINS],
+}
+
+impl Default for BlockStatistics {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            sums: [0.0; 4],
+            histogram: [0; HISTOGRAM_BINS],
+        }
+    }
+}
+
+impl BlockStatistics {
+    /// Statistics with nothing accumulated.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add every sample of `block`. Non-finite samples are skipped.
+    pub fn accumulate(&mut self, block: &[f32]) {
+        for &x in block.iter().filter(|x| x.is_finite()) {
+            let x64 = f64::from(x);
+            let x2 = x64 * x64;
+            self.count += 1;
+            self.sums[0] += x64;
+            self.sums[1] += x2;
+            self.sums[2] += x2 * x64;
+            self.sums[3] += x2 * x2;
+            let bin = ((x + 1.0) * 0.5 * HISTOGRAM_BINS as f32) as isize;
+            let bin = bin.clamp(0, HISTOGRAM_BINS as isize - 1) as usize;
+            self.histogram[bin] = self.histogram[bin].saturating_add(1);
+        }
+    }
+
+    /// The statistics of everything accumulated so far.
+    pub fn snapshot(&self) -> BlockStatsSnapshot {
+        if self.count == 0 {
+            return BlockStatsSnapshot::default();
+        }
+        let n = self.count as f64;
+        let [m1, m2, m3, m4] = self.sums.map(|sum| sum / n);
+        let variance = (m2 - m1 * m1).max(0.0);
+        let fourth = m4 - 4.0 * m1 * m3 + 6.0 * m1 * m1 * m2 - 3.0 * m1 * m1 * m1 * m1;
+        let kurtosis = if variance > 1e-12 {
+            fourth / (variance * variance)
+        } else {
+            0.0
+        };
+        BlockStatsSnapshot {
+            samples: self.count,
+            mean: m1 as f32,
+            variance: variance as f32,
+            kurtosis: kurtosis as f32,
+            histogram: self.histogram,
+        }
+    }
+
+    /// Forget everything accumulated.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// A plugin's [`BlockStatistics`] plus the snapshot it publishes after every block
+/// ([`crate::Vst3Host::block_statistics`]).
+pub(crate) struct BlockStatisticsTap {
+    stats: BlockStatistics,
+    shared: Arc<RwLock<BlockStatsSnapshot>>,
+}
+
+impl BlockStatisticsTap {
+    pub(crate) fn new(shared: Arc<RwLock<BlockStatsSnapshot>>) -> Self {
+        Self {
+            stats: BlockStatistics::new(),
+            shared,
+        }
+    }
+
+    /// Accumulate every output channel and publish the result. Skips publishing (never waits)
+    /// while a reader holds the snapshot, since this runs on the audio thread.
+    pub(crate) fn process(&mut self, outputs: &[Vec<f32>]) {
+        for channel in outputs {
+            self.stats.accumulate(channel);
+        }
+        self.publish();
+    }
+
+    /// Start over, e.g. when processing restarts.
+    pub(crate) fn reset(&mut self) {
+        self.stats.reset();
+        self.publish();
+    }
+
+    fn publish(&self) {
+        if let Ok(mut shared) = self.shared.try_write() {
+            *shared = self.stats.snapshot();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginInternal;
+
+    const SAMPLE_RATE: f64 = 48000.0;
+
+    /// A one-pole low-pass whose only parameter (id 0) sweeps the cutoff from 20 Hz to 20 kHz.
+    struct LowPass {
+        cutoff: f64,
+        state: f32,
+    }
+
+    impl PluginInternal for LowPass {
+        fn set_parameter(&mut self, _id: u32, value: f64) -> Result<()> {
+            self.cutoff = value;
+            Ok(())
+        }
+        fn get_parameter(&self, _id: u32) -> Result<f64> {
+            Ok(self.cutoff)
+        }
+        fn format_parameter(&self, _id: u32, normalized: f64) -> Result<String> {
+            Ok(format!("{:.0} Hz", 20.0 + normalized * 19_980.0))
+        }
+        fn process(&mut self, buffers: &mut AudioBuffers) -> Result<()> {
+            let hz = 20.0 + self.cutoff * 19_980.0;
+            let a = (-2.0 * std::f64::consts::PI * hz / SAMPLE_RATE).exp() as f32;
+            for (out, &x) in buffers.outputs[0].iter_mut().zip(&buffers.inputs[0]) {
+                self.state = (1.0 - a) * x + a * self.state;
+                *out = self.state;
+            }
+            Ok(())
+        }
+        crate::plugin::stub_plugin_internal!(
+            except set_parameter, get_parameter, format_parameter, process
+        );
+    }
+
+    fn low_pass() -> Plugin {
+        let mut plugin = crate::plugin::plugin_with(Box::new(LowPass {
+            cutoff: 0.75,
+            state: 0.0,
+        }));
+        plugin.info.name = "LowPass".to_string();
+        plugin.info.audio_inputs = 1;
+        plugin.info.audio_outputs = 1;
+        plugin.info.has_midi_input = false;
+        plugin.sample_rate = SAMPLE_RATE;
+        plugin.block_size = 256;
+        plugin
+    }
+
+    #[test]
+    fn fft_of_an_impulse_is_flat() {
+        let mut impulse = vec![0.0; 64];
+        impulse[0] = 1.0;
+        let spectrum = magnitude_spectrum(&impulse, 64);
+        assert_eq!(spectrum.len(), 33);
+        assert!(spectrum.iter().all(|m| (m - 1.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn fft_finds_a_sine_in_its_bin() {
+        let signal: Vec<f32> = (0..256)
+            .map(|i| (2.0 * std::f32::consts::PI * 8.0 * i as f32 / 256.0).sin())
+            .collect();
+        let spectrum = magnitude_spectrum(&signal, 256);
+        let peak = (0..spectrum.len())
+            .max_by(|&a, &b| spectrum[a].total_cmp(&spectrum[b]))
+            .unwrap();
+        assert_eq!(peak, 8);
+    }
+
+    /// Bins within 60 dB of the peak.
+    fn bandwidth_at_minus_60_dbc(spectrum: &[f32]) -> usize {
+        let peak = spectrum.iter().copied().fold(f32::MIN, f32::max);
+        spectrum.iter().filter(|&&db| db > peak - 60.0).count()
+    }
+
+    #[test]
+    fn a_hann_window_narrows_a_sine_peak() {
+        // 20.3 cycles per frame: between bins, where a rectangular window leaks most.
+        let size = 1024;
+        let sine: Vec<f32> = (0..size)
+            .map(|i| (2.0 * std::f64::consts::PI * 20.3 * i as f64 / size as f64).sin() as f32)
+            .collect();
+        let mut analyzer = SpectrumAnalyzer::new(size);
+        let rectangular = bandwidth_at_minus_60_dbc(analyzer.analyze(&sine));
+        analyzer.set_window(WindowFunction::Hann);
+        let hann = bandwidth_at_minus_60_dbc(analyzer.analyze(&sine));
+        assert!(
+            hann < rectangular,
+            "hann {hann} bins, rectangular {rectangular}"
+        );
+        assert!(hann < 40, "{hann}");
+    }
+
+    #[test]
+    fn a_full_scale_sine_reads_zero_db_through_any_window() {
+        let size = 1024;
+        let sine: Vec<f32> = (0..size)
+            .map(|i| (2.0 * std::f64::consts::PI * 64.0 * i as f64 / size as f64).sin() as f32)
+            .collect();
+        for window in WindowFunction::ALL {
+            let mut analyzer = SpectrumAnalyzer::new(size);
+            analyzer.set_window(window);
+            assert!(analyzer.push(&sine));
+            let peak = analyzer.spectrum_db()[64];
+            assert!(peak.abs() < 0.01, "{window:?}: {peak} dB");
+        }
+    }
+
+    #[test]
+    fn averaging_is_a_linear_mean_of_the_last_frames() {
+        let mut analyzer = SpectrumAnalyzer::new(64);
+        analyzer.set_averaging(2);
+        let tone = |amplitude: f32| -> Vec<f32> {
+            (0..64)
+                .map(|i| amplitude * (2.0 * std::f32::consts::PI * 4.0 * i as f32 / 64.0).sin())
+                .collect()
+        };
+        analyzer.analyze(&tone(1.0));
+        analyzer.analyze(&tone(0.5));
+        // (1.0 + 0.5) / 2 = 0.75
+        assert!((analyzer.spectrum_db()[4] - 20.0 * 0.75f32.log10()).abs() < 0.01);
+        analyzer.analyze(&tone(0.5));
+        assert!((analyzer.spectrum_db()[4] - 20.0 * 0.5f32.log10()).abs() < 0.01);
+    }
+
+    #[test]
+    fn lowering_a_low_pass_cutoff_lowers_the_highs() {
+        let mut plugin = low_pass();
+        let mut impulse = vec![0.0; 2048];
+        impulse[0] = 1.0;
+        let points = ParameterSweep::frequency_response(&mut plugin, 0, &impulse, 5).unwrap();
+        assert_eq!(points.len(), 5);
+        assert_eq!(points[0].normalized_param, 0.0);
+        assert_eq!(points[4].normalized_param, 1.0);
+        assert_eq!(points[0].display_value, "20 Hz");
+
+        let freqs = ParameterSweep::bin_frequencies(impulse.len(), SAMPLE_RATE);
+        let high = freqs.iter().position(|&f| f >= 12_000.0).unwrap();
+        for pair in points.windows(2) {
+            assert!(
+                pair[0].magnitude_db[high] < pair[1].magnitude_db[high],
+                "{} dB at {} vs {} dB at {}",
+                pair[0].magnitude_db[high],
+                pair[0].display_value,
+                pair[1].magnitude_db[high],
+                pair[1].display_value
+            );
+        }
+        // DC passes at any cutoff.
+        assert!(points.iter().all(|p| p.magnitude_db[0].abs() < 0.5));
+        // The sweep leaves the parameter and processing state as it found them.
+        assert_eq!(plugin.get_parameter(0).unwrap(), 0.75);
+        assert!(!plugin.is_processing());
+    }
+
+    #[test]
+    fn empty_sweeps_are_rejected() {
+        let mut plugin = low_pass();
+        assert!(ParameterSweep::frequency_response(&mut plugin, 0, &[], 4).is_err());
+        assert!(ParameterSweep::frequency_response(&mut plugin, 0, &[1.0], 0).is_err());
+    }
+
+    #[test]
+    fn uniform_noise_has_a_kurtosis_of_1_8() {
+        // A full, evenly spaced sweep of -1..1 is as uniform as noise gets.
+        let ramp: Vec<f32> = (0..48_000).map(|i| i as f32 / 24_000.0 - 1.0).collect();
+        let mut stats = BlockStatistics::new();
+        for block in ramp.chunks(512) {
+            stats.accumulate(block);
+        }
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.samples, 48_000);
+        assert!(snapshot.mean.abs() < 1e-3);
+        assert!((snapshot.variance - 1.0 / 3.0).abs() < 1e-3);
+        assert!(
+            (snapshot.kurtosis - 1.8).abs() < 0.01,
+            "{}",
+            snapshot.kurtosis
+        );
+        assert!(snapshot.kurtosis_alert());
+        // Every bin gets an even share.
+        let expected = 48_000 / HISTOGRAM_BINS as u32;
+        assert!(snapshot
+            .histogram
+            .iter()
+            .all(|&n| n.abs_diff(expected) <= 1));
+    }
+
+    #[test]
+    fn a_sine_has_a_kurtosis_of_1_5() {
+        let sine: Vec<f32> = (0..48_000)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 48_000.0).sin() * 0.5)
+            .collect();
+        let mut stats = BlockStatistics::new();
+        stats.accumulate(&sine);
+        let snapshot = stats.snapshot();
+        assert!((snapshot.variance - 0.125).abs() < 1e-3);
+        assert!(
+            (snapshot.kurtosis - 1.5).abs() < 0.01,
+            "{}",
+            snapshot.kurtosis
+        );
+        // Half amplitude: nothing in the outer quarters of the histogram.
+        assert_eq!(
+            snapshot.histogram[..HISTOGRAM_BINS / 4].iter().sum::<u32>(),
+            0
+        );
+
+        stats.reset();
+        assert_eq!(stats.snapshot(), BlockStatsSnapshot::default());
+        assert!(!stats.snapshot().kurtosis_alert());
+    }
+}
@@ -3,8 +3,14 @@
 /// Audio buffers for plugin processing
 #[derive(Debug)]
 pub struct AudioBuffers {
-    /// Input audio buffers, indexed `[channel][sample]`.
+    /// Input audio buffers, indexed `[channel][sample]`: the channels of the plugin's main
+    /// input buses, in bus order.
     pub inputs: Vec<Vec<f32>>,
+    /// Sidechain audio buffers, indexed `[channel][sample]`: the channels of the plugin's
+    /// auxiliary (`kAux`) input buses, in bus order. By VST3 convention input bus 0 is the
+    /// main input and the sidechain is input bus 1, so for a typical compressor these are
+    /// bus 1's channels. Empty leaves the sidechain silent.
+    pub sidechain_inputs: Vec<Vec<f32>>,
     /// Output audio buffers, indexed `[channel][sample]`.
     pub outputs: Vec<Vec<f32>>,
     /// Sample rate in Hz
@@ -26,17 +32,27 @@ impl AudioBuffers {
 
         Self {
             inputs,
+            sidechain_inputs: Vec::new(),
             outputs,
             sample_rate,
             block_size,
         }
     }
 
+    /// Add `channels` silent sidechain channels (see [`Self::sidechain_inputs`]).
+    pub fn with_sidechain(mut self, channels: usize) -> Self {
+        self.sidechain_inputs = vec![vec![0.0; self.block_size]; channels];
+        self
+    }
+
     /// Clear all buffers to silence
     pub fn clear(&mut self) {
         for buffer in &mut self.inputs {
             buffer.fill(0.0);
         }
+        for buffer in &mut self.sidechain_inputs {
+            buffer.fill(0.0);
+        }
         for buffer in &mut self.outputs {
             buffer.fill(0.0);
         }
@@ -51,6 +67,11 @@ impl AudioBuffers {
     pub fn output_channels(&self) -> usize {
         self.outputs.len()
     }
+
+    /// Get the number of sidechain channels
+    pub fn sidechain_channels(&self) -> usize {
+        self.sidechain_inputs.len()
+    }
 }
 
 /// Audio level information for a single channel
@@ -99,11 +120,33 @@ impl ChannelLevel {
     }
 }
 
+/// When a meter's peak hold lets go of the loudest value
+/// ([`AudioConfig::meter_hold_mode`]).
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum MeterHoldMode {
+    /// Hold for `seconds`, then follow the signal again
+    AutoReset {
+        /// How long a peak is held
+        seconds: f64,
+    },
+    /// Hold until reset, e.g. with [`Plugin::reset_peak_hold`](crate::Plugin::reset_peak_hold)
+    ManualReset,
+}
+
+impl Default for MeterHoldMode {
+    fn default() -> Self {
+        MeterHoldMode::AutoReset { seconds: 3.0 }
+    }
+}
+
 /// Audio level information for all channels
 #[derive(Debug, Clone)]
 pub struct AudioLevels {
     /// Level information for each channel
     pub channels: Vec<ChannelLevel>,
+    hold_mode: MeterHoldMode,
+    /// When each channel's peak hold was last set
+    held_at: Vec<Option<std::time::Instant>>,
 }
 
 impl AudioLevels {
@@ -111,11 +154,30 @@ impl AudioLevels {
     pub fn new(channel_count: usize) -> Self {
         Self {
             channels: vec![ChannelLevel::default(); channel_count],
+            hold_mode: MeterHoldMode::default(),
+            held_at: vec![None; channel_count],
         }
     }
 
+    /// How long peak holds last.
+    pub fn hold_mode(&self) -> MeterHoldMode {
+        self.hold_mode
+    }
+
+    /// Set how long peak holds last, from the next update on.
+    pub fn set_hold_mode(&mut self, mode: MeterHoldMode) {
+        self.hold_mode = mode;
+    }
+
     /// Update levels from audio buffers
     pub fn update_from_buffers(&mut self, buffers: &[Vec<f32>]) {
+        self.update_from_buffers_at(buffers, std::time::Instant::now());
+    }
+
+    /// Update levels from audio buffers rendered at `now`. A louder peak replaces the hold; in
+    /// [`MeterHoldMode::AutoReset`] an expired hold falls back to the current peak.
+    pub fn update_from_buffers_at(&mut self, buffers: &[Vec<f32>], now: std::time::Instant) {
+        self.held_at.resize(self.channels.len(), None);
         for (i, buffer) in buffers.iter().enumerate() {
             if i >= self.channels.len() {
                 break;
@@ -138,17 +200,24 @@ impl AudioLevels {
             channel.rms = rms;
 
             // Update peak hold if necessary
-            if peak > channel.peak_hold {
+            let expired = match self.hold_mode {
+                MeterHoldMode::AutoReset { seconds } => self.held_at[i]
+                    .is_some_and(|at| now.saturating_duration_since(at).as_secs_f64() > seconds),
+                MeterHoldMode::ManualReset => false,
+            };
+            if peak > channel.peak_hold || expired {
                 channel.peak_hold = peak;
+                self.held_at[i] = Some(now);
             }
         }
     }
 
-    /// Reset peak hold values
-    pub fn reset_peak_hold(&mut self) {
+    /// Reset peak hold values to silence as of `now`; the next update holds its peak again.
+    pub fn reset_peak_hold(&mut self, now: std::time::Instant) {
         for channel in &mut self.channels {
-            channel.peak_hold = channel.peak;
+            channel.peak_hold = 0.0;
         }
+        self.held_at = vec![Some(now); self.channels.len()];
     }
 
     /// Check if any channel is clipping
@@ -218,6 +287,40 @@ pub struct BusArrangements {
     pub outputs: Vec<SpeakerArrangement>,
 }
 
+/// Which of a plugin's buses are active and how its audio buses are laid out: a routing setup
+/// (stereo out, multi-out, mono plus sidechain...) to save under a name and restore later
+/// ([`Plugin::save_bus_configuration`](crate::Plugin::save_bus_configuration)).
+#[derive(Debug, Clone, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct BusConfiguration {
+    /// Whether each audio input bus is active, in bus-index order.
+    pub active_audio_inputs: Vec<bool>,
+    /// Whether each audio output bus is active, in bus-index order.
+    pub active_audio_outputs: Vec<bool>,
+    /// Whether each event input bus is active, in bus-index order.
+    pub active_event_inputs: Vec<bool>,
+    /// Whether each event output bus is active, in bus-index order.
+    pub active_event_outputs: Vec<bool>,
+    /// The audio input buses' arrangements followed by the audio output buses', or empty if
+    /// the plugin couldn't report them.
+    pub speaker_arrangements: Vec<SpeakerArrangement>,
+}
+
+impl BusConfiguration {
+    /// [`Self::speaker_arrangements`] split into inputs and outputs, or `None` if there isn't
+    /// one per audio bus.
+    pub fn bus_arrangements(&self) -> Option<BusArrangements> {
+        let inputs = self.active_audio_inputs.len();
+        if self.speaker_arrangements.len() != inputs + self.active_audio_outputs.len() {
+            return None;
+        }
+        let (ins, outs) = self.speaker_arrangements.split_at(inputs);
+        Some(BusArrangements {
+            inputs: ins.to_vec(),
+            outputs: outs.to_vec(),
+        })
+    }
+}
+
 /// A single-channel peak meter with falling ballistics and a timed peak-hold marker —
 /// the behaviour a level meter UI wants but [`AudioLevels`]'s sticky `peak_hold` doesn't give.
 ///
@@ -323,6 +426,22 @@ impl PeakMeter {
         self.peak_hold
     }
 
+    /// Follow `mode`: hold for its `seconds`, or until [`Self::reset_peak_hold`].
+    pub fn set_hold_mode(&mut self, mode: MeterHoldMode) {
+        self.hold = match mode {
+            MeterHoldMode::AutoReset { seconds } => {
+                std::time::Duration::from_secs_f64(seconds.max(0.0))
+            }
+            MeterHoldMode::ManualReset => std::time::Duration::MAX,
+        };
+    }
+
+    /// Drop the held peak to silence as of `now`, leaving the level as is.
+    pub fn reset_peak_hold(&mut self, now: std::time::Instant) {
+        self.peak_hold = 0.0;
+        self.peak_hold_at = Some(now);
+    }
+
     /// Reset the meter to silence.
     pub fn reset(&mut self) {
         self.level = 0.0;
@@ -332,6 +451,77 @@ impl PeakMeter {
     }
 }
 
+/// Two `f32`s (a stereo left/right pair) packed into one `AtomicU64`, so both halves are always
+/// written and read together — a reader can never see the left channel of one block with the
+/// right channel of another. Lock-free, for handing meter levels from the audio thread to the UI.
+///
+/// ```
+/// use vst3_host::audio::AtomicF32Pair;
+///
+/// let peak = AtomicF32Pair::default();
+/// peak.fetch_max(0.5, 0.25);
+/// peak.fetch_max(0.25, 0.75);
+/// assert_eq!(peak.swap(0.0, 0.0), (0.5, 0.75));
+/// assert_eq!(peak.load(), (0.0, 0.0));
+/// ```
+#[derive(Debug, Default)]
+pub struct AtomicF32Pair(std::sync::atomic::AtomicU64);
+
+impl AtomicF32Pair {
+    fn pack(left: f32, right: f32) -> u64 {
+        (u64::from(left.to_bits()) << 32) | u64::from(right.to_bits())
+    }
+
+    fn unpack(bits: u64) -> (f32, f32) {
+        (
+            f32::from_bits((bits >> 32) as u32),
+            f32::from_bits(bits as u32),
+        )
+    }
+
+    /// A pair holding `left` and `right`.
+    pub fn new(left: f32, right: f32) -> Self {
+        Self(std::sync::atomic::AtomicU64::new(Self::pack(left, right)))
+    }
+
+    /// Both values, from the same write.
+    pub fn load(&self) -> (f32, f32) {
+        Self::unpack(self.0.load(std::sync::atomic::Ordering::Relaxed))
+    }
+
+    /// Replace both values.
+    pub fn store(&self, left: f32, right: f32) {
+        self.0.store(
+            Self::pack(left, right),
+            std::sync::atomic::Ordering::Relaxed,
+        );
+    }
+
+    /// Replace both values, returning the previous pair — e.g. `swap(0.0, 0.0)` to read and
+    /// reset a peak.
+    pub fn swap(&self, left: f32, right: f32) -> (f32, f32) {
+        Self::unpack(self.0.swap(
+            Self::pack(left, right),
+            std::sync::atomic::Ordering::Relaxed,
+        ))
+    }
+
+    /// Raise each half to at least the given value (NaN never wins), returning the previous
+    /// pair. Retries on contention instead of blocking.
+    pub fn fetch_max(&self, left: f32, right: f32) -> (f32, f32) {
+        let result = self.0.fetch_update(
+            std::sync::atomic::Ordering::Relaxed,
+            std::sync::atomic::Ordering::Relaxed,
+            |bits| {
+                let (l, r) = Self::unpack(bits);
+                Some(Self::pack(l.max(left), r.max(right)))
+            },
+        );
+        // The closure always returns `Some`, so the update always succeeds.
+        Self::unpack(result.unwrap_or_else(|bits| bits))
+    }
+}
+
 /// A moving-window RMS estimator over the most recent `N` samples.
 ///
 /// Unlike [`AudioLevels`]'s per-block RMS (which resets every buffer), this gives a smooth
@@ -415,8 +605,854 @@ impl RmsWindow {
     }
 }
 
+/// Input level auto-gain aims for: −18 dBFS RMS, the nominal studio operating level.
+pub const NOMINAL_INPUT_LEVEL_DB: f64 = -18.0;
+
+/// Most gain (either way) auto-gain will apply, so near-silence isn't boosted into noise.
+const MAX_AUTO_GAIN_DB: f64 = 24.0;
+
+/// Input below this RMS level (dBFS) is treated as silence and leaves the auto-gain alone.
+const AUTO_GAIN_GATE_DB: f64 = -70.0;
+
+/// Time constant of the auto-gain's glide towards its target gain, in seconds.
+const AUTO_GAIN_SMOOTHING_SECS: f64 = 0.5;
+
+/// A gain stage on captured input, before it reaches an effect plugin's input buses.
+///
+/// With `auto_gain` off it applies a fixed `gain_db`. With it on, it measures the incoming
+/// (pre-gain) RMS over the last second and glides `gain_db` towards whatever brings that level
+/// to `target_db` (by default [`NOMINAL_INPUT_LEVEL_DB`]), within ±24 dB. Gain changes ramp
+/// across the block so they don't click.
+///
+/// The window holds one second's worth of samples across every channel fed through
+/// [`process_block`](Self::process_block), so a multi-channel input is measured as a whole.
+///
+/// ```
+/// use vst3_host::audio::InputGainStage;
+///
+/// let mut stage = InputGainStage::new(48_000.0);
+/// stage.gain_db = 6.0;
+/// let mut block = vec![0.25f32; 64];
+/// stage.process_block(&mut block);
+/// assert!((block[63] - 0.5).abs() < 0.01); // +6 dB ≈ ×2
+/// ```
+#[derive(Debug, Clone)]
+pub struct InputGainStage {
+    /// Gain applied to the input, in dB. Updated by the stage itself while `auto_gain` is on.
+    pub gain_db: f64,
+    /// Adjust `gain_db` automatically to bring the input to `target_db`.
+    pub auto_gain: bool,
+    /// RMS level (dBFS) auto-gain aims for.
+    pub target_db: f64,
+    sample_rate: f64,
+    rms: RmsWindow,
+    // Linear gain the last block ended on; the next block ramps from here.
+    applied: f32,
+}
+
+impl InputGainStage {
+    /// A unity-gain stage for input at `sample_rate` Hz, with auto-gain off.
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            gain_db: 0.0,
+            auto_gain: false,
+            target_db: NOMINAL_INPUT_LEVEL_DB,
+            sample_rate,
+            rms: RmsWindow::from_duration(1.0, sample_rate),
+            applied: 1.0,
+        }
+    }
+
+    /// A stage that auto-gains towards `target_db` dBFS RMS.
+    pub fn with_auto_gain(sample_rate: f64, target_db: f64) -> Self {
+        Self {
+            auto_gain: true,
+            target_db,
+            ..Self::new(sample_rate)
+        }
+    }
+
+    /// Apply the gain to `input` in place, first updating the auto-gain estimate from it.
+    pub fn process_block(&mut self, input: &mut [f32]) {
+        if input.is_empty() {
+            return;
+        }
+        if self.auto_gain {
+            self.rms.push_block(input);
+            self.update_auto_gain(input.len());
+        }
+        let target = self.linear_gain();
+        let start = self.applied;
+        let step = (target - start) / input.len() as f32;
+        for (i, sample) in input.iter_mut().enumerate() {
+            *sample *= start + step * (i + 1) as f32;
+        }
+        self.applied = target;
+    }
+
+    /// Current RMS of the measured (pre-gain) input over the last second, in dBFS;
+    /// `-inf` before any input or while auto-gain is off.
+    pub fn input_rms_db(&self) -> f64 {
+        20.0 * (self.rms.rms() as f64).log10()
+    }
+
+    /// `gain_db` as a linear factor.
+    pub fn linear_gain(&self) -> f32 {
+        10f64.powf(self.gain_db / 20.0) as f32
+    }
+
+    /// Forget the measured level (e.g. after switching input device). The gain is kept.
+    pub fn reset(&mut self) {
+        self.rms.clear();
+    }
+
+    /// The sample rate the stage measures at.
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    fn update_auto_gain(&mut self, samples: usize) {
+        let level_db = self.input_rms_db();
+        if level_db <= AUTO_GAIN_GATE_DB {
+            return;
+        }
+        let wanted = (self.target_db - level_db).clamp(-MAX_AUTO_GAIN_DB, MAX_AUTO_GAIN_DB);
+        let glide = 1.0 - (-(samples as f64) / (AUTO_GAIN_SMOOTHING_SECS * self.sample_rate)).exp();
+        self.gain_db += (wanted - self.gain_db) * glide;
+    }
+}
+
+/// Number of one-microsecond bins in [`CallbackJitterTracker::jitter_histogram`].
+pub const JITTER_BINS: usize = 64;
+/// Width of each bin of [`CallbackJitterTracker::overflow_histogram`], in microseconds.
+pub const JITTER_OVERFLOW_BIN_US: u64 = 64;
+/// p99 callback jitter above which [`crate::Vst3Host::timing_stats`] warns, in microseconds.
+pub const JITTER_WARN_P99_US: f64 = 500.0;
+
+/// How regularly audio callbacks arrive. Each [`Self::record`] takes the time since the
+/// previous callback and files its distance from the expected period
+/// (`block_size / sample_rate`, or the frames the callback actually got with
+/// [`Self::record_interval`]) in a histogram: jitter under 64 µs in 1 µs bins
+/// ([`Self::jitter_histogram`]), larger jitter in [`JITTER_OVERFLOW_BIN_US`]-wide bins
+/// ([`Self::overflow_histogram`]), whose last bin takes everything beyond. Fixed-size and
+/// allocation-free, so the audio thread can record every callback.
+#[derive(Debug, Clone)]
+pub struct CallbackJitterTracker {
+    sample_rate: f64,
+    expected_period: std::time::Duration,
+    last_callback: Option<std::time::Instant>,
+    jitter_histogram: [u64; JITTER_BINS],
+    overflow_histogram: [u64; JITTER_BINS],
+    max_jitter_us: u64,
+}
+
+impl CallbackJitterTracker {
+    /// A tracker for callbacks of `block_size` frames at `sample_rate` Hz.
+    pub fn new(block_size: usize, sample_rate: f64) -> Self {
+        let period = if sample_rate > 0.0 {
+            block_size as f64 / sample_rate
+        } else {
+            0.0
+        };
+        Self {
+            sample_rate,
+            expected_period: std::time::Duration::from_secs_f64(period),
+            last_callback: None,
+            jitter_histogram: [0; JITTER_BINS],
+            overflow_histogram: [0; JITTER_BINS],
+            max_jitter_us: 0,
+        }
+    }
+
+    /// Record a callback starting now.
+    pub fn record(&mut self) {
+        self.record_at(std::time::Instant::now());
+    }
+
+    /// Record a callback that started at `now`. The first one only starts the clock.
+    pub fn record_at(&mut self, now: std::time::Instant) {
+        let Some(last) = self.last_callback.replace(now) else {
+            return;
+        };
+        self.file(now.saturating_duration_since(last));
+    }
+
+    /// Record a callback that came `delta` after the previous one and was asked for `frames`
+    /// frames, which set the expected period from then on. For callers that keep the
+    /// previous callback's time themselves.
+    pub fn record_interval(&mut self, delta: std::time::Duration, frames: usize) {
+        if self.sample_rate > 0.0 {
+            self.expected_period =
+                std::time::Duration::from_secs_f64(frames as f64 / self.sample_rate);
+        }
+        self.file(delta);
+    }
+
+    /// File one inter-callback time in the histograms.
+    fn file(&mut self, delta: std::time::Duration) {
+        let jitter = delta.abs_diff(self.expected_period);
+        let jitter_us = u64::try_from(jitter.as_micros()).unwrap_or(u64::MAX);
+        self.max_jitter_us = self.max_jitter_us.max(jitter_us);
+        match usize::try_from(jitter_us) {
+            Ok(bin) if bin < JITTER_BINS => self.jitter_histogram[bin] += 1,
+            _ => {
+                let bin = (jitter_us - JITTER_BINS as u64) / JITTER_OVERFLOW_BIN_US;
+                self.overflow_histogram[(bin as usize).min(JITTER_BINS - 1)] += 1;
+            }
+        }
+    }
+
+    /// Counts of callbacks by jitter: bin `i` holds jitter of `i` to `i + 1` µs.
+    pub fn jitter_histogram(&self) -> &[u64; JITTER_BINS] {
+        &self.jitter_histogram
+    }
+
+    /// Counts of callbacks with 64 µs of jitter or more: bin `i` starts at
+    /// `64 + i * JITTER_OVERFLOW_BIN_US` µs, and the last bin has no upper end.
+    pub fn overflow_histogram(&self) -> &[u64; JITTER_BINS] {
+        &self.overflow_histogram
+    }
+
+    /// Callbacks measured so far (one fewer than recorded: the first has no predecessor).
+    pub fn samples(&self) -> u64 {
+        self.jitter_histogram.iter().sum::<u64>() + self.overflow_histogram.iter().sum::<u64>()
+    }
+
+    /// The expected time between callbacks.
+    pub fn expected_period(&self) -> std::time::Duration {
+        self.expected_period
+    }
+
+    /// The largest jitter seen, in µs.
+    pub fn max_jitter_us(&self) -> u64 {
+        self.max_jitter_us
+    }
+
+    /// The jitter 99% of callbacks stay within, in µs, to the resolution of its bin.
+    pub fn p99_jitter_us(&self) -> f64 {
+        self.percentile_us(0.99)
+    }
+
+    /// The jitter 99.9% of callbacks stay within, in µs, to the resolution of its bin.
+    pub fn p999_jitter_us(&self) -> f64 {
+        self.percentile_us(0.999)
+    }
+
+    /// Lower edge of the bin holding the `fraction` quantile; 0 with nothing measured.
+    fn percentile_us(&self, fraction: f64) -> f64 {
+        let total = self.samples();
+        if total == 0 {
+            return 0.0;
+        }
+        let rank = ((total as f64 * fraction).ceil() as u64).max(1);
+        let fine = self
+            .jitter_histogram
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| (i as u64, n));
+        let coarse = self
+            .overflow_histogram
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| (JITTER_BINS as u64 + i as u64 * JITTER_OVERFLOW_BIN_US, n));
+        let mut seen = 0;
+        for (lower_us, count) in fine.chain(coarse) {
+            seen += count;
+            if seen >= rank {
+                return lower_us as f64;
+            }
+        }
+        self.max_jitter_us as f64
+    }
+
+    /// Forget every measurement, e.g. when a new stream starts.
+    pub fn reset(&mut self) {
+        *self = Self {
+            sample_rate: self.sample_rate,
+            expected_period: self.expected_period,
+            ..Self::default()
+        };
+    }
+
+    /// The measurements so far.
+    pub fn stats(&self) -> TimingStats {
+        TimingStats {
+            expected_period_us: self.expected_period.as_secs_f64() * 1e6,
+            callbacks: self.samples(),
+            jitter_histogram: self.jitter_histogram,
+            overflow_histogram: self.overflow_histogram,
+            p99_jitter_us: self.p99_jitter_us(),
+            p999_jitter_us: self.p999_jitter_us(),
+            max_jitter_us: self.max_jitter_us,
+        }
+    }
+}
+
+impl Default for CallbackJitterTracker {
+    /// A tracker with no expected period, until [`CallbackJitterTracker::new`] gives one.
+    fn default() -> Self {
+        Self::new(0, 0.0)
+    }
+}
+
+/// A [`CallbackJitterTracker`]'s measurements at one moment
+/// ([`crate::Vst3Host::timing_stats`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimingStats {
+    /// Expected time between callbacks, in µs
+    pub expected_period_us: f64,
+    /// Callbacks measured
+    pub callbacks: u64,
+    /// See [`CallbackJitterTracker::jitter_histogram`]
+    pub jitter_histogram: [u64; JITTER_BINS],
+    /// See [`CallbackJitterTracker::overflow_histogram`]
+    pub overflow_histogram: [u64; JITTER_BINS],
+    /// See [`CallbackJitterTracker::p99_jitter_us`]
+    pub p99_jitter_us: f64,
+    /// See [`CallbackJitterTracker::p999_jitter_us`]
+    pub p999_jitter_us: f64,
+    /// The largest jitter seen, in µs
+    pub max_jitter_us: u64,
+}
+
+impl Default for TimingStats {
+    fn default() -> Self {
+        CallbackJitterTracker::default().stats()
+    }
+}
+
+impl TimingStats {
+    /// Whether p99 jitter is past [`JITTER_WARN_P99_US`].
+    pub fn is_jittery(&self) -> bool {
+        self.p99_jitter_us > JITTER_WARN_P99_US
+    }
+}
+
+/// Default release time of an [`AutoLimiter`], in milliseconds.
+pub const DEFAULT_LIMITER_RELEASE_MS: f64 = 200.0;
+
+/// A safety limiter for a plugin's output, so a clipping plugin can't send full-scale
+/// transients to the speakers.
+///
+/// It works per block, across all channels at once: when a block's peak would exceed
+/// `threshold_db`, the gain drops instantly to bring the peak exactly to the threshold (there
+/// is no lookahead). Between overs the gain recovers towards unity in dB, exponentially with
+/// time constant `release_ms`.
+///
+/// ```
+/// use vst3_host::audio::AutoLimiter;
+///
+/// let mut limiter = AutoLimiter::new(48_000.0);
+/// let mut block = vec![vec![2.0f32, -1.0, 0.5]];
+/// limiter.process_block(&mut block);
+/// assert_eq!(block[0], [1.0, -0.5, 0.25]);
+/// assert!((limiter.gain_reduction_db() - 6.02).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AutoLimiter {
+    /// Highest peak let through, in dBFS (default 0).
+    pub threshold_db: f64,
+    /// Time constant of the recovery towards unity gain, in ms
+    /// (default [`DEFAULT_LIMITER_RELEASE_MS`]).
+    pub release_ms: f64,
+    sample_rate: f64,
+    // Linear gain applied to the last block.
+    gain: f64,
+}
+
+impl AutoLimiter {
+    /// A limiter at 0 dBFS with the default release, for output at `sample_rate` Hz.
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            threshold_db: 0.0,
+            release_ms: DEFAULT_LIMITER_RELEASE_MS,
+            sample_rate,
+            gain: 1.0,
+        }
+    }
+
+    /// Limit `channels` (one buffer per output channel) in place.
+    pub fn process_block(&mut self, channels: &mut [Vec<f32>]) {
+        let frames = channels.iter().map(Vec::len).max().unwrap_or(0);
+        if frames == 0 {
+            return;
+        }
+        // Recover first, so a steady over settles on exactly the gain it needs.
+        let release_samples = self.release_ms.max(0.0) / 1000.0 * self.sample_rate;
+        self.gain = self.gain.powf((-(frames as f64) / release_samples).exp());
+
+        let peak = channels
+            .iter()
+            .flatten()
+            .filter(|s| s.is_finite())
+            .fold(0.0f32, |peak, s| peak.max(s.abs())) as f64;
+        let threshold = 10f64.powf(self.threshold_db / 20.0);
+        if peak * self.gain > threshold {
+            self.gain = threshold / peak;
+        }
+        if self.gain < 1.0 {
+            let gain = self.gain as f32;
+            for sample in channels.iter_mut().flatten() {
+                *sample *= gain;
+            }
+        }
+    }
+
+    /// How far the last block was turned down, in dB (`0.0` when nothing was limited).
+    pub fn gain_reduction_db(&self) -> f32 {
+        (-20.0 * self.gain.log10()) as f32
+    }
+
+    /// Return to unity gain at once (e.g. after a panic).
+    pub fn reset(&mut self) {
+        self.gain = 1.0;
+    }
+
+    /// The sample rate the release is timed at.
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+}
+
+/// The window a [`SpectrumAnalyzer`](crate::analysis::SpectrumAnalyzer) multiplies each frame
+/// by before its FFT. Without one (`Rectangular`) a tone between two bins leaks across the
+/// whole spectrum; the others trade a wider main peak for much lower leakage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum WindowFunction {
+    /// No window: the narrowest peak, but leakage only falls 6 dB per octave.
+    #[default]
+    Rectangular,
+    /// Raised cosine; a good general-purpose choice.
+    Hann,
+    /// Like Hann, with a lower first sidelobe but slower-falling leakage.
+    Hamming,
+    /// Three-term Blackman: sidelobes below −58 dB.
+    Blackman,
+    /// Four-term Blackman-Harris: sidelobes below −92 dB.
+    BlackmanHarris,
+    /// Flat top: the widest peak, but reads a tone's level accurately wherever it falls.
+    FlatTop,
+}
+
+impl WindowFunction {
+    /// Every window, in menu order.
+    pub const ALL: [WindowFunction; 6] = [
+        WindowFunction::Rectangular,
+        WindowFunction::Hann,
+        WindowFunction::Hamming,
+        WindowFunction::Blackman,
+        WindowFunction::BlackmanHarris,
+        WindowFunction::FlatTop,
+    ];
+
+    /// Display name (e.g. `"Blackman-Harris"`).
+    pub fn label(self) -> &'static str {
+        match self {
+            WindowFunction::Rectangular => "Rectangular",
+            WindowFunction::Hann => "Hann",
+            WindowFunction::Hamming => "Hamming",
+            WindowFunction::Blackman => "Blackman",
+            WindowFunction::BlackmanHarris => "Blackman-Harris",
+            WindowFunction::FlatTop => "Flat Top",
+        }
+    }
+
+    /// The window's `n` coefficients, in the periodic form used for spectral analysis.
+    pub fn coefficients(self, n: usize) -> Vec<f32> {
+        // Cosine-sum windows: w[i] = Σ (−1)^k a_k cos(2πki / n).
+        let terms: &[f64] = match self {
+            WindowFunction::Rectangular => &[1.0],
+            WindowFunction::Hann => &[0.5, 0.5],
+            WindowFunction::Hamming => &[0.54, 0.46],
+            WindowFunction::Blackman => &[0.42, 0.5, 0.08],
+            WindowFunction::BlackmanHarris => &[0.35875, 0.48829, 0.14128, 0.01168],
+            WindowFunction::FlatTop => &[
+                0.215_578_95,
+                0.416_631_58,
+                0.277_263_158,
+                0.083_578_947,
+                0.006_947_368,
+            ],
+        };
+        (0..n)
+            .map(|i| {
+                let x = 2.0 * std::f64::consts::PI * i as f64 / n as f64;
+                terms
+                    .iter()
+                    .enumerate()
+                    .map(|(k, a)| {
+                        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+                        sign * a * (k as f64 * x).cos()
+                    })
+                    .sum::<f64>() as f32
+            })
+            .collect()
+    }
+}
+
+/// Standard level-meter ballistics: how fast a meter rises to a new level and falls back.
+///
+/// Attack is the time constant of the rise; decay is the time for the reading to fall
+/// 20 dB. [`VU`](Self::VU) is the exception: it integrates symmetrically (rise and fall)
+/// over 300 ms and responds to the average level rather than the peak.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum BallisticsPreset {
+    /// Digital sample-peak meter: instant attack, 20 dB fall in 1.7 s.
+    #[default]
+    Peak,
+    /// VU meter (ANSI C16.5): 300 ms symmetric integration, average-responding, calibrated
+    /// so a sine reads its RMS level.
+    VU,
+    /// DIN 45406 PPM: 10 ms attack, 20 dB fall in 1.5 s.
+    PpmType1,
+    /// BBC PPM (IEC 60268-10 Type IIa): 10 ms attack, 24 dB fall in 2.8 s.
+    PpmType2,
+    /// Nordic PPM (IEC 60268-10 Type I): 5 ms attack, 20 dB fall in 1.7 s.
+    IecType1,
+    /// IEC Type II: 10 ms attack, 20 dB fall in 300 ms.
+    IecType2,
+}
+
+impl BallisticsPreset {
+    /// Every preset, in menu order.
+    pub const ALL: [BallisticsPreset; 6] = [
+        BallisticsPreset::Peak,
+        BallisticsPreset::VU,
+        BallisticsPreset::PpmType1,
+        BallisticsPreset::PpmType2,
+        BallisticsPreset::IecType1,
+        BallisticsPreset::IecType2,
+    ];
+
+    /// Short display name (e.g. `"PPM Type I"`).
+    pub fn label(self) -> &'static str {
+        match self {
+            BallisticsPreset::Peak => "Peak",
+            BallisticsPreset::VU => "VU",
+            BallisticsPreset::PpmType1 => "PPM Type I (DIN)",
+            BallisticsPreset::PpmType2 => "PPM Type II (BBC)",
+            BallisticsPreset::IecType1 => "IEC Type I",
+            BallisticsPreset::IecType2 => "IEC Type II",
+        }
+    }
+
+    /// Rise time constant in seconds (0 = instant).
+    fn attack_secs(self) -> f64 {
+        match self {
+            BallisticsPreset::Peak => 0.0,
+            // 99% of the final reading after 300 ms: 300 ms / ln(100).
+            BallisticsPreset::VU => 0.3 / std::f64::consts::LN_10 / 2.0,
+            BallisticsPreset::PpmType1
+            | BallisticsPreset::PpmType2
+            | BallisticsPreset::IecType2 => 0.010,
+            BallisticsPreset::IecType1 => 0.005,
+        }
+    }
+
+    /// Fall rate in dB per second (unused by VU, which falls at its attack constant).
+    fn decay_db_per_sec(self) -> f64 {
+        match self {
+            BallisticsPreset::Peak | BallisticsPreset::IecType1 => 20.0 / 1.7,
+            BallisticsPreset::VU => 0.0,
+            BallisticsPreset::PpmType1 => 20.0 / 1.5,
+            BallisticsPreset::PpmType2 => 24.0 / 2.8,
+            BallisticsPreset::IecType2 => 20.0 / 0.3,
+        }
+    }
+}
+
+/// A sine's RMS over its rectified average (`π / 2√2`): scales an average-responding (VU)
+/// detector so a sine reads its RMS level.
+const SINE_FORM_FACTOR: f64 = std::f64::consts::PI / (2.0 * std::f64::consts::SQRT_2);
+
+/// One channel of a level meter with [`BallisticsPreset`] ballistics, run per sample.
+///
+/// ```
+/// use vst3_host::audio::{BallisticsPreset, MeterBallistics};
+///
+/// let mut meter = MeterBallistics::new(BallisticsPreset::Peak, 48000.0);
+/// assert_eq!(meter.process_sample(0.5), 0.5); // peak meters attack instantly
+/// assert!(meter.process_sample(0.0) < 0.5); // ...and fall back gradually
+/// ```
+#[derive(Debug, Clone)]
+pub struct MeterBallistics {
+    preset: BallisticsPreset,
+    /// Per-sample one-pole coefficient for the rise (0 = instant).
+    attack: f64,
+    /// Per-sample gain applied while falling.
+    release: f64,
+    level: f64,
+}
+
+impl MeterBallistics {
+    /// Reading at which a VU meter shows 0 VU: a +4 dBu sine, taken as 0.3548 RMS
+    /// (−9 dBFS) in the digital domain.
+    pub const VU_REFERENCE_RMS: f32 = 0.3548;
+
+    /// Create a meter for `preset` running at `sample_rate` Hz.
+    pub fn new(preset: BallisticsPreset, sample_rate: f64) -> Self {
+        let sample_rate = if sample_rate.is_finite() && sample_rate > 0.0 {
+            sample_rate
+        } else {
+            44100.0
+        };
+        let attack = match preset.attack_secs() {
+            t if t > 0.0 => (-1.0 / (t * sample_rate)).exp(),
+            _ => 0.0,
+        };
+        let release = 10f64.powf(-preset.decay_db_per_sec() / 20.0 / sample_rate);
+        Self {
+            preset,
+            attack,
+            release,
+            level: 0.0,
+        }
+    }
+
+    /// The preset this meter follows.
+    pub fn preset(&self) -> BallisticsPreset {
+        self.preset
+    }
+
+    /// Feed one sample and return the meter reading (linear, `0.0..`). Non-finite samples
+    /// count as silence.
+    pub fn process_sample(&mut self, sample: f32) -> f32 {
+        let x = if sample.is_finite() {
+            (sample as f64).abs()
+        } else {
+            0.0
+        };
+        if self.preset == BallisticsPreset::VU {
+            let x = x * SINE_FORM_FACTOR;
+            self.level = x + (self.level - x) * self.attack;
+        } else if x > self.level {
+            self.level = x + (self.level - x) * self.attack;
+        } else {
+            self.level = (self.level * self.release).max(x);
+        }
+        self.level as f32
+    }
+
+    /// Feed a block of samples and return the reading after the last one.
+    pub fn process_block(&mut self, block: &[f32]) -> f32 {
+        for &s in block {
+            self.process_sample(s);
+        }
+        self.level()
+    }
+
+    /// The current reading (linear, `0.0..`).
+    pub fn level(&self) -> f32 {
+        self.level as f32
+    }
+
+    /// The current reading in dBFS (`-inf` at silence).
+    pub fn level_db(&self) -> f32 {
+        20.0 * self.level().log10()
+    }
+
+    /// The current reading in VU, relative to [`Self::VU_REFERENCE_RMS`]. Meaningful for
+    /// [`BallisticsPreset::VU`]; other presets report peak-relative values.
+    pub fn vu(&self) -> f32 {
+        20.0 * (self.level() / Self::VU_REFERENCE_RMS).log10()
+    }
+
+    /// Reset the reading to silence.
+    pub fn reset(&mut self) {
+        self.level = 0.0;
+    }
+}
+
+/// Samples below this level (−100 dBFS) count as silence for [`OutputAnalyzer`].
+pub const SILENCE_THRESHOLD: f32 = 1e-5;
+
+/// What [`OutputAnalyzer::analyze`] found in one block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockHealth {
+    /// Audible, finite and within ±1.0
+    Ok,
+    /// Every sample below [`SILENCE_THRESHOLD`]
+    Silent,
+    /// At least one sample beyond ±1.0
+    Clipping,
+    /// At least one NaN or infinite sample; the block was silenced
+    NonFinite,
+}
+
+/// Counters collected by an [`OutputAnalyzer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnalysisReport {
+    /// Blocks analyzed
+    pub blocks: u64,
+    /// Blocks where every sample was below −100 dBFS
+    pub silence_blocks: u64,
+    /// Blocks with a sample beyond ±1.0
+    pub clipping_blocks: u64,
+    /// Blocks with a NaN or infinite sample
+    pub nan_blocks: u64,
+    /// Frame index of the first non-finite sample in the most recent such block
+    pub last_nan_sample_index: Option<usize>,
+    /// Set on the first non-finite block: the plugin's state is suspect and it should be
+    /// recovered or reloaded. Cleared by [`OutputAnalyzer::reset`].
+    pub needs_recovery: bool,
+}
+
+/// Checks each processed output block for silence, clipping and NaN/Inf.
+///
+/// A block containing a non-finite sample is zeroed on the spot (so it never reaches the
+/// device or a downstream plugin) and the analyzer flags the plugin as needing recovery.
+///
+/// ```
+/// use vst3_host::audio::{BlockHealth, OutputAnalyzer};
+///
+/// let mut analyzer = OutputAnalyzer::new();
+/// let mut block = vec![vec![0.5, f32::NAN, 0.5]];
+/// assert_eq!(analyzer.analyze(&mut block), BlockHealth::NonFinite);
+/// assert_eq!(block, vec![vec![0.0; 3]]);
+/// assert_eq!(analyzer.report().last_nan_sample_index, Some(1));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OutputAnalyzer {
+    report: AnalysisReport,
+}
+
+impl OutputAnalyzer {
+    /// An analyzer with all counters at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Analyze one block of output (`[channel][sample]`), silencing it if it contains a
+    /// non-finite sample.
+    pub fn analyze(&mut self, outputs: &mut [Vec<f32>]) -> BlockHealth {
+        self.report.blocks += 1;
+        let first_bad = outputs
+            .iter()
+            .filter_map(|ch| ch.iter().position(|x| !x.is_finite()))
+            .min();
+        if let Some(index) = first_bad {
+            for ch in outputs.iter_mut() {
+                ch.fill(0.0);
+            }
+            self.report.nan_blocks += 1;
+            self.report.last_nan_sample_index = Some(index);
+            self.report.needs_recovery = true;
+            return BlockHealth::NonFinite;
+        }
+
+        let peak = outputs
+            .iter()
+            .flatten()
+            .fold(0.0_f32, |peak, x| peak.max(x.abs()));
+        if peak > 1.0 {
+            self.report.clipping_blocks += 1;
+            BlockHealth::Clipping
+        } else if peak < SILENCE_THRESHOLD {
+            self.report.silence_blocks += 1;
+            BlockHealth::Silent
+        } else {
+            BlockHealth::Ok
+        }
+    }
+
+    /// The counters so far.
+    pub fn report(&self) -> AnalysisReport {
+        self.report
+    }
+
+    /// Zero the counters and clear [`AnalysisReport::needs_recovery`].
+    pub fn reset(&mut self) {
+        self.report = AnalysisReport::default();
+    }
+}
+
+/// Number of consecutive quiet blocks [`Plugin::measure_actual_tail`] waits for before it
+/// considers a tail ended.
+///
+/// [`Plugin::measure_actual_tail`]: crate::Plugin::measure_actual_tail
+pub const TAIL_WINDOW_BLOCKS: usize = 8;
+
+/// Measures where a plugin's tail actually ends, by watching its output once it has been
+/// released — useful for plugins that report an infinite tail
+/// ([`Plugin::tail_samples`](crate::Plugin::tail_samples) returning `u32::MAX`).
+///
+/// ```
+/// use vst3_host::audio::TailDetector;
+///
+/// let mut detector = TailDetector::new();
+/// detector.start();
+/// detector.push_block(&[vec![0.5; 64]]);
+/// detector.push_block(&[vec![0.0; 64]]);
+/// detector.push_block(&[vec![0.0; 64]]);
+/// assert!(detector.is_tail_ended(-60.0, 2));
+/// assert!(!detector.is_tail_ended(-60.0, 3));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TailDetector {
+    /// Peak of each block seen since [`start`](Self::start), in dBFS.
+    block_peaks_db: Vec<f64>,
+    monitoring: bool,
+}
+
+impl TailDetector {
+    /// A detector that is not monitoring yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Begin monitoring (forgetting any earlier blocks). Call when the plugin stops receiving
+    /// new events.
+    pub fn start(&mut self) {
+        self.block_peaks_db.clear();
+        self.monitoring = true;
+    }
+
+    /// Record one output block (`[channel][sample]`). Ignored until [`start`](Self::start).
+    pub fn push_block(&mut self, outputs: &[Vec<f32>]) {
+        if !self.monitoring {
+            return;
+        }
+        let peak = outputs
+            .iter()
+            .flatten()
+            .fold(0.0_f32, |peak, x| peak.max(x.abs()));
+        self.block_peaks_db.push(20.0 * (peak as f64).log10());
+    }
+
+    /// Blocks recorded since [`start`](Self::start).
+    pub fn blocks_monitored(&self) -> usize {
+        self.block_peaks_db.len()
+    }
+
+    /// Whether the last `window_blocks` blocks all peaked below `threshold_db` (dBFS). `false`
+    /// until that many blocks have been recorded.
+    pub fn is_tail_ended(&self, threshold_db: f64, window_blocks: usize) -> bool {
+        let window_blocks = window_blocks.max(1);
+        self.block_peaks_db.len() >= window_blocks
+            && self.block_peaks_db[self.block_peaks_db.len() - window_blocks..]
+                .iter()
+                .all(|&db| db < threshold_db)
+    }
+}
+
+/// The sample precision plugins process in (`ProcessSetup.symbolicSampleSize`).
+///
+/// Host-side audio stays `f32` either way: with [`SampleSize::F64`] the plugin reads and writes
+/// double-precision buffers, converted from and to [`AudioBuffers`] at each block. A plugin
+/// that can't process 64-bit samples (`canProcessSampleSize`) runs at 32 bits instead, with a
+/// warning logged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum SampleSize {
+    /// 32-bit float samples (`kSample32`)
+    #[default]
+    F32,
+    /// 64-bit float samples (`kSample64`)
+    F64,
+}
+
 /// Audio processing configuration
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct AudioConfig {
     /// Sample rate in Hz
     pub sample_rate: f64,
@@ -434,6 +1470,53 @@ pub struct AudioConfig {
     /// Time signature denominator (note value of one beat), advertised in the
     /// `ProcessContext`.
     pub time_sig_denominator: i32,
+    /// Ballistics for the playback output meters
+    /// ([`AudioHandle::meter_levels`](crate::AudioHandle::meter_levels)).
+    pub meter_ballistics: BallisticsPreset,
+    /// How long loaded plugins' output peak holds last
+    /// ([`Plugin::get_output_levels`](crate::Plugin::get_output_levels)).
+    pub meter_hold_mode: MeterHoldMode,
+    /// Run an [`OutputAnalyzer`] over every block a loaded plugin processes
+    /// ([`Plugin::output_analysis`](crate::Plugin::output_analysis)).
+    pub output_analysis: bool,
+    /// Note played by [`Vst3Host::measure_tail`](crate::Vst3Host::measure_tail).
+    pub tail_measurement_note: u8,
+    /// Level (dBFS) below which [`Vst3Host::measure_tail`](crate::Vst3Host::measure_tail)
+    /// considers a tail ended.
+    pub tail_threshold_db: f64,
+    /// Run loaded plugins at this multiple of `sample_rate` (1, 2, 4 or 8) to reduce
+    /// aliasing; see [`OversamplingAdapter`].
+    pub oversampling_factor: u32,
+    /// Stack size in bytes for the thread plugins process on, for plugins with deep call
+    /// stacks. When set, the CPAL backend runs the stream's data callback (and so every loaded
+    /// plugin) on a thread of its own with this much stack, fed through a ring buffer from
+    /// CPAL's callback thread, whose stack it can't size. That adds up to two device buffers
+    /// of output latency, and the thread runs at normal rather than real-time priority.
+    /// `None` processes on CPAL's callback thread. Defaults to 2 MB on macOS, where CoreAudio's
+    /// I/O thread gets only 512 KB, and to `None` elsewhere.
+    pub audio_thread_stack_size: Option<usize>,
+    /// Sample precision loaded plugins process in; see [`SampleSize`].
+    pub sample_size: SampleSize,
+}
+
+impl AudioConfig {
+    /// The sample rate and maximum block size a loaded plugin is set up with
+    /// (`setupProcessing`): the host's, scaled by [`Self::oversampling_factor`].
+    pub(crate) fn plugin_setup(&self) -> (f64, usize) {
+        let factor = self.oversampling_factor.max(1);
+        (
+            self.sample_rate * factor as f64,
+            self.block_size * factor as usize,
+        )
+    }
+
+    /// The [`Oversampler`] plugins loaded with this config run behind, if oversampling is on.
+    pub(crate) fn oversampler(&self) -> Option<Oversampler> {
+        (self.oversampling_factor > 1)
+            .then(|| OversamplingAdapter::new(self.oversampling_factor).ok())
+            .flatten()
+            .map(Oversampler::new)
+    }
 }
 
 impl Default for AudioConfig {
@@ -446,7 +1529,121 @@ impl Default for AudioConfig {
             tempo: 120.0,
             time_sig_numerator: 4,
             time_sig_denominator: 4,
+            meter_ballistics: BallisticsPreset::Peak,
+            meter_hold_mode: MeterHoldMode::default(),
+            output_analysis: true,
+            tail_measurement_note: 60,
+            tail_threshold_db: -60.0,
+            oversampling_factor: 1,
+            audio_thread_stack_size: cfg!(target_os = "macos").then_some(2 * 1024 * 1024),
+            sample_size: SampleSize::F32,
+        }
+    }
+}
+
+/// An audio output device's capabilities, as listed by
+/// [`Vst3Host::list_audio_output_devices`](crate::Vst3Host::list_audio_output_devices).
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AudioDeviceInfo {
+    /// Device name, as passed to [`Vst3Host::set_audio_device`](crate::Vst3Host::set_audio_device)
+    pub name: String,
+    /// Most output channels the device offers
+    pub max_channels: u32,
+    /// Common sample rates (Hz) the device supports, ascending
+    pub sample_rates: Vec<f64>,
+    /// Smallest buffer size in frames (0 when the device doesn't say)
+    pub min_buffer_size: u32,
+    /// Largest buffer size in frames (0 when the device doesn't say)
+    pub max_buffer_size: u32,
+}
+
+impl AudioDeviceInfo {
+    /// Whether the device can play `config`: enough output channels, the sample rate, and (if
+    /// the device reports its buffer range) the block size.
+    pub fn supports_config(&self, config: &AudioConfig) -> bool {
+        let buffer_ok = self.max_buffer_size == 0
+            || (self.min_buffer_size as usize..=self.max_buffer_size as usize)
+                .contains(&config.block_size);
+        config.output_channels <= self.max_channels as usize
+            && self.sample_rates.contains(&config.sample_rate)
+            && buffer_ok
+    }
+
+    /// The sample rates (Hz) the device supports, ascending.
+    pub fn supported_sample_rates(&self) -> &[f64] {
+        &self.sample_rates
+    }
+
+    /// The supported rate nearest `requested` (`requested` itself when supported), or `None`
+    /// when the device lists no rates. Ties go to the higher rate.
+    pub fn closest_supported_rate(&self, requested: f64) -> Option<f64> {
+        self.rates_by_closeness(requested).first().copied()
+    }
+
+    /// The supported rates ordered nearest `requested` first, ties going to the higher rate.
+    pub fn rates_by_closeness(&self, requested: f64) -> Vec<f64> {
+        let mut rates = self.sample_rates.clone();
+        rates.sort_by(|a, b| {
+            (a - requested)
+                .abs()
+                .total_cmp(&(b - requested).abs())
+                .then(b.total_cmp(a))
+        });
+        rates
+    }
+
+    /// Settle on the rate to run at: try each supported rate nearest `requested` first with
+    /// `try_rate` (typically re-running the plugin's `setupProcessing` at that rate) and return
+    /// the first that succeeds. A device that lists no rates is trusted to run at `requested`.
+    /// Returns [`Error::SampleRateNotSupported`](crate::Error::SampleRateNotSupported) when
+    /// every rate fails.
+    pub fn negotiate_sample_rate(
+        &self,
+        requested: f64,
+        mut try_rate: impl FnMut(f64) -> crate::Result<()>,
+    ) -> crate::Result<f64> {
+        if self.sample_rates.is_empty() {
+            return Ok(requested);
         }
+        for rate in self.rates_by_closeness(requested) {
+            match try_rate(rate) {
+                Ok(()) => return Ok(rate),
+                Err(e) => log::warn!("Sample rate {rate} Hz rejected on {}: {e}", self.name),
+            }
+        }
+        Err(crate::Error::SampleRateNotSupported {
+            requested,
+            available: self.sample_rates.clone(),
+        })
+    }
+}
+
+/// A sample-accurate transport position shared by several plugins, so that every plugin in a
+/// [`PluginChain`](crate::PluginChain) sees the same `continousTimeSamples`/`projectTimeSamples`
+/// in a block instead of each keeping its own count.
+///
+/// Clones share the counter. It only moves forward, by [`Self::advance`] once per block.
+#[derive(Debug, Clone, Default)]
+pub struct TransportClock {
+    sample_counter: std::sync::Arc<std::sync::atomic::AtomicI64>,
+}
+
+impl TransportClock {
+    /// A clock at sample 0
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Samples rendered so far: the position of the block about to be processed
+    pub fn position(&self) -> i64 {
+        self.sample_counter
+            .load(std::sync::atomic::Ordering::Acquire)
+    }
+
+    /// Move past a processed block of `frames` samples
+    pub fn advance(&self, frames: usize) {
+        self.sample_counter
+            .fetch_add(frames as i64, std::sync::atomic::Ordering::AcqRel);
     }
 }
 
@@ -459,6 +1656,18 @@ pub trait AudioStream: Send {
     fn pause(&self) -> Result<(), Box<dyn std::error::Error>>;
 }
 
+/// Which device a backend's live input ([`play_with_input_backend`]) records from.
+///
+/// [`play_with_input_backend`]: crate::playback::play_with_input_backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum CaptureSource {
+    /// The OS default input device
+    #[default]
+    Default,
+    /// No input device (live input is unavailable)
+    None,
+}
+
 /// Audio backend trait for creating audio streams
 #[allow(clippy::type_complexity)] // Box<dyn FnMut...> callbacks are intrinsic to the API
 pub trait AudioBackend: Send + Sync {
@@ -478,54 +1687,495 @@ pub trait AudioBackend: Send + Sync {
     /// Get the default output device
     fn default_output_device(&self) -> Option<Self::Device>;
 
-    /// Get the default input device
-    fn default_input_device(&self) -> Option<Self::Device>;
+    /// Get the default input device
+    fn default_input_device(&self) -> Option<Self::Device>;
+
+    /// Create an output stream
+    fn create_output_stream(
+        &self,
+        device: &Self::Device,
+        config: AudioConfig,
+        data_callback: Box<dyn FnMut(&mut [f32]) + Send>,
+        error_callback: Box<dyn FnMut(Self::Error) + Send>,
+    ) -> Result<Self::Stream, Self::Error>;
+
+    /// Create an input stream
+    fn create_input_stream(
+        &self,
+        device: &Self::Device,
+        config: AudioConfig,
+        data_callback: Box<dyn FnMut(&[f32]) + Send>,
+        error_callback: Box<dyn FnMut(Self::Error) + Send>,
+    ) -> Result<Self::Stream, Self::Error>;
+
+    /// Choose the device [`Self::default_input_device`] returns. Backends without a
+    /// selectable capture device ignore this.
+    fn set_input_source(&mut self, _source: CaptureSource) {}
+
+    /// The rate to capture from `device` at: `requested` if the device supports it,
+    /// otherwise its native rate (the live-input bridge resamples to `requested`).
+    fn input_sample_rate(&self, _device: &Self::Device, requested: f64) -> f64 {
+        requested
+    }
+}
+
+/// Linear-interpolating sample-rate converter for a stream of interleaved frames, used to
+/// bridge an input device running at a different rate from the output.
+///
+/// ```
+/// use vst3_host::audio::LinearResampler;
+///
+/// // 24 kHz in, 48 kHz out: every input frame yields two output frames.
+/// let mut resampler = LinearResampler::new(1, 24_000.0, 48_000.0);
+/// let mut input = [0.0_f32, 1.0, 2.0, 3.0].into_iter();
+/// let mut out = vec![vec![0.0; 6]];
+/// resampler.process(&mut out, 6, |frame| input.next().map(|s| frame[0] = s).is_some());
+/// assert_eq!(out[0], [0.0, 0.5, 1.0, 1.5, 2.0, 2.5]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LinearResampler {
+    step: f64,
+    pos: f64,
+    prev: Vec<f32>,
+    next: Vec<f32>,
+}
+
+impl LinearResampler {
+    /// A converter for `channels` interleaved channels from `input_rate` to `output_rate`.
+    pub fn new(channels: usize, input_rate: f64, output_rate: f64) -> Self {
+        Self {
+            step: input_rate / output_rate.max(1.0),
+            // Two frames behind: the first call pulls the first pair of input frames.
+            pos: 2.0,
+            prev: vec![0.0; channels],
+            next: vec![0.0; channels],
+        }
+    }
+
+    /// Input frames consumed per output frame
+    pub fn ratio(&self) -> f64 {
+        self.step
+    }
+
+    /// Write `frames` output frames to `out[ch][..frames]`, pulling input frames through
+    /// `next_frame`, which fills one interleaved frame and returns `false` when no input is
+    /// available (the missing frame reads as silence).
+    pub fn process(
+        &mut self,
+        out: &mut [Vec<f32>],
+        frames: usize,
+        mut next_frame: impl FnMut(&mut [f32]) -> bool,
+    ) {
+        for f in 0..frames {
+            // Advance until the output position lies between `prev` and `next`.
+            while self.pos >= 1.0 {
+                self.pos -= 1.0;
+                std::mem::swap(&mut self.prev, &mut self.next);
+                if !next_frame(&mut self.next) {
+                    self.next.fill(0.0);
+                }
+            }
+            let t = self.pos as f32;
+            for (ch, buf) in out.iter_mut().enumerate() {
+                if let (Some(&a), Some(&b)) = (self.prev.get(ch), self.next.get(ch)) {
+                    buf[f] = a + (b - a) * t;
+                }
+            }
+            self.pos += self.step;
+        }
+    }
+}
+
+/// Oversampling factors [`OversamplingAdapter`] accepts (1 = off).
+pub const OVERSAMPLING_FACTORS: [u32; 4] = [1, 2, 4, 8];
+
+/// Linear-phase FIR half-length per unit of oversampling factor: the filter has
+/// `2 * OVERSAMPLING_HALF_TAPS * factor + 1` taps.
+const OVERSAMPLING_HALF_TAPS: usize = 8;
+
+/// Polyphase FIR up/downsampler for running a plugin at a multiple of the host rate, so
+/// distortion and saturation alias less.
+///
+/// [`upsample`](Self::upsample) zero-stuffs and low-passes one channel by `factor`;
+/// [`downsample`](Self::downsample) applies the same anti-aliasing low-pass and decimates.
+/// Both treat the block as standalone (zero outside it) and are aligned with their input;
+/// [`Vst3HostBuilder::oversampling_factor`](crate::Vst3HostBuilder::oversampling_factor) runs
+/// a streaming version around a plugin, which delays the output by [`latency`](Self::latency).
+///
+/// ```
+/// use vst3_host::audio::OversamplingAdapter;
+///
+/// let adapter = OversamplingAdapter::new(2)?;
+/// let block = vec![0.5_f32; 64];
+/// let up = adapter.upsample(&block);
+/// assert_eq!(up.len(), 128);
+/// assert_eq!(adapter.downsample(&up).len(), 64);
+/// # Ok::<(), vst3_host::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct OversamplingAdapter {
+    factor: u32,
+    taps: Vec<f32>,
+}
+
+impl OversamplingAdapter {
+    /// An adapter for `factor` (one of [`OVERSAMPLING_FACTORS`]).
+    pub fn new(factor: u32) -> crate::error::Result<Self> {
+        if !OVERSAMPLING_FACTORS.contains(&factor) {
+            return Err(crate::error::Error::InvalidParameter(format!(
+                "oversampling factor must be one of {OVERSAMPLING_FACTORS:?}, got {factor}"
+            )));
+        }
+        if factor == 1 {
+            return Ok(Self {
+                factor,
+                taps: vec![1.0],
+            });
+        }
+        // Blackman-windowed sinc, cut off at the host rate's Nyquist, normalized to unity DC gain.
+        let f = factor as usize;
+        let half = OVERSAMPLING_HALF_TAPS * f;
+        let len = 2 * half + 1;
+        let mut taps: Vec<f32> = (0..len)
+            .map(|k| {
+                let x = (k as f64 - half as f64) / factor as f64;
+                let sinc = if x == 0.0 {
+                    1.0
+                } else {
+                    (std::f64::consts::PI * x).sin() / (std::f64::consts::PI * x)
+                };
+                let phase = 2.0 * std::f64::consts::PI * k as f64 / (len - 1) as f64;
+                let window = 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+                (sinc * window) as f32
+            })
+            .collect();
+        let sum: f32 = taps.iter().sum();
+        taps.iter_mut().for_each(|t| *t /= sum);
+        Ok(Self { factor, taps })
+    }
+
+    /// The oversampling factor
+    pub fn factor(&self) -> u32 {
+        self.factor
+    }
+
+    /// Delay, in host-rate samples, the streaming up- and downsampling filters add together.
+    pub fn latency(&self) -> usize {
+        (self.taps.len() - 1) / self.factor as usize
+    }
+
+    /// Upsample one channel: `input.len() * factor` samples at the oversampled rate.
+    pub fn upsample(&self, input: &[f32]) -> Vec<f32> {
+        let mut out = vec![0.0; input.len() * self.factor as usize];
+        let sample = |i: isize| usize::try_from(i).ok().and_then(|i| input.get(i)).copied();
+        self.interpolate(self.half() as isize, |i| sample(i).unwrap_or(0.0), &mut out);
+        out
+    }
+
+    /// Low-pass and decimate one oversampled channel: `oversampled.len() / factor` samples.
+    pub fn downsample(&self, oversampled: &[f32]) -> Vec<f32> {
+        let mut out = vec![0.0; oversampled.len() / self.factor as usize];
+        let sample = |i: isize| {
+            usize::try_from(i)
+                .ok()
+                .and_then(|i| oversampled.get(i))
+                .copied()
+        };
+        self.decimate(self.half() as isize, |i| sample(i).unwrap_or(0.0), &mut out);
+        out
+    }
+
+    fn half(&self) -> usize {
+        (self.taps.len() - 1) / 2
+    }
+
+    /// `out[m] = factor · Σₖ h[k] · z[m + shift − k]`, where `z` is the input zero-stuffed by
+    /// `factor` (`z[i] = sample(i / factor)` when `factor` divides `i`, else 0). Only the taps
+    /// that land on input samples are visited — the polyphase form.
+    fn interpolate(&self, shift: isize, sample: impl Fn(isize) -> f32, out: &mut [f32]) {
+        let f = self.factor as isize;
+        for (m, y) in out.iter_mut().enumerate() {
+            let pos = m as isize + shift;
+            let mut acc = 0.0;
+            let mut k = pos.rem_euclid(f) as usize;
+            while k < self.taps.len() {
+                acc += self.taps[k] * sample((pos - k as isize) / f);
+                k += f as usize;
+            }
+            *y = acc * f as f32;
+        }
+    }
+
+    /// `out[n] = Σₖ h[k] · sample(n · factor + shift − k)`.
+    fn decimate(&self, shift: isize, sample: impl Fn(isize) -> f32, out: &mut [f32]) {
+        let f = self.factor as isize;
+        for (n, y) in out.iter_mut().enumerate() {
+            let pos = n as isize * f + shift;
+            *y = self
+                .taps
+                .iter()
+                .enumerate()
+                .map(|(k, h)| h * sample(pos - k as isize))
+                .sum();
+        }
+    }
+
+    /// Streaming upsample: `history` holds the previous input samples (any length will be
+    /// resized to what the filter needs).
+    fn upsample_stream(&self, history: &mut Vec<f32>, input: &[f32], out: &mut [f32]) {
+        history.resize((self.taps.len() - 1).div_ceil(self.factor as usize), 0.0);
+        let past = history.len() as isize;
+        self.interpolate(
+            0,
+            |i| match usize::try_from(i) {
+                Ok(i) => input[i],
+                Err(_) => history[(past + i) as usize],
+            },
+            out,
+        );
+        push_history(history, input);
+    }
+
+    /// Streaming downsample: `history` holds the previous oversampled samples.
+    fn downsample_stream(&self, history: &mut Vec<f32>, input: &[f32], out: &mut [f32]) {
+        history.resize(self.taps.len() - 1, 0.0);
+        let past = history.len() as isize;
+        self.decimate(
+            0,
+            |i| match usize::try_from(i) {
+                Ok(i) => input[i],
+                Err(_) => history[(past + i) as usize],
+            },
+            out,
+        );
+        push_history(history, input);
+    }
+}
+
+/// Keep the last `history.len()` samples of `history ++ input` in `history`.
+fn push_history(history: &mut [f32], input: &[f32]) {
+    let len = history.len();
+    if input.len() >= len {
+        history.copy_from_slice(&input[input.len() - len..]);
+    } else {
+        history.rotate_left(input.len());
+        history[len - input.len()..].copy_from_slice(input);
+    }
+}
+
+/// Runs a plugin at `factor` × the host rate: upsamples each block's inputs, renders them
+/// through the plugin, and downsamples its outputs back into the host's buffers.
+#[derive(Debug)]
+pub(crate) struct Oversampler {
+    adapter: OversamplingAdapter,
+    input_history: Vec<Vec<f32>>,
+    sidechain_history: Vec<Vec<f32>>,
+    output_history: Vec<Vec<f32>>,
+    buffers: AudioBuffers,
+}
+
+impl Oversampler {
+    pub(crate) fn new(adapter: OversamplingAdapter) -> Self {
+        Self {
+            adapter,
+            input_history: Vec::new(),
+            sidechain_history: Vec::new(),
+            output_history: Vec::new(),
+            buffers: AudioBuffers::new(0, 0, 0, 0.0),
+        }
+    }
+
+    pub(crate) fn factor(&self) -> u32 {
+        self.adapter.factor
+    }
+
+    pub(crate) fn latency(&self) -> usize {
+        self.adapter.latency()
+    }
+
+    /// Process `buffers` (at the host rate) by calling `render` on oversampled buffers.
+    pub(crate) fn process(
+        &mut self,
+        buffers: &mut AudioBuffers,
+        render: impl FnOnce(&mut AudioBuffers) -> crate::error::Result<()>,
+    ) -> crate::error::Result<()> {
+        let frames = buffers.block_size;
+        let oversampled = frames * self.adapter.factor as usize;
+        let scratch = &mut self.buffers;
+        scratch.inputs.resize_with(buffers.inputs.len(), Vec::new);
+        scratch
+            .sidechain_inputs
+            .resize_with(buffers.sidechain_inputs.len(), Vec::new);
+        scratch.outputs.resize_with(buffers.outputs.len(), Vec::new);
+        self.input_history
+            .resize_with(buffers.inputs.len(), Vec::new);
+        self.sidechain_history
+            .resize_with(buffers.sidechain_inputs.len(), Vec::new);
+        self.output_history
+            .resize_with(buffers.outputs.len(), Vec::new);
+        scratch.block_size = oversampled;
+        scratch.sample_rate = buffers.sample_rate * self.adapter.factor as f64;
+
+        let main = buffers
+            .inputs
+            .iter()
+            .zip(&mut scratch.inputs)
+            .zip(&mut self.input_history);
+        let sidechain = buffers
+            .sidechain_inputs
+            .iter()
+            .zip(&mut scratch.sidechain_inputs)
+            .zip(&mut self.sidechain_history);
+        for ((input, up), history) in main.chain(sidechain) {
+            up.resize(oversampled, 0.0);
+            let n = frames.min(input.len());
+            self.adapter.upsample_stream(
+                history,
+                &input[..n],
+                &mut up[..n * self.adapter.factor as usize],
+            );
+        }
+        for out in &mut scratch.outputs {
+            out.clear();
+            out.resize(oversampled, 0.0);
+        }
+
+        render(scratch)?;
+
+        for ((output, down), history) in buffers
+            .outputs
+            .iter_mut()
+            .zip(&scratch.outputs)
+            .zip(&mut self.output_history)
+        {
+            let n = frames.min(output.len());
+            self.adapter.downsample_stream(
+                history,
+                &down[..n * self.adapter.factor as usize],
+                &mut output[..n],
+            );
+        }
+        Ok(())
+    }
+}
+
+/// How samples are encoded in a recorded WAV file. This is separate from the processing
+/// format: plugins always process 32-bit float, and recording converts on the way out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum SampleFormat {
+    /// 32-bit IEEE float (the default; what the audio path runs in)
+    #[default]
+    F32,
+    /// 64-bit IEEE float
+    F64,
+    /// 16-bit signed integer PCM
+    I16,
+    /// 24-bit signed integer PCM, packed into 3 bytes per sample
+    I24,
+    /// 32-bit signed integer PCM
+    I32,
+}
+
+impl SampleFormat {
+    /// Bits each sample takes in the file.
+    pub const fn bits_per_sample(self) -> u16 {
+        match self {
+            SampleFormat::F32 | SampleFormat::I32 => 32,
+            SampleFormat::F64 => 64,
+            SampleFormat::I16 => 16,
+            SampleFormat::I24 => 24,
+        }
+    }
+
+    /// Whether samples are IEEE float rather than integer PCM.
+    pub const fn is_float(self) -> bool {
+        matches!(self, SampleFormat::F32 | SampleFormat::F64)
+    }
+
+    /// The WAV `fmt ` chunk's format tag: 3 (`WAVE_FORMAT_IEEE_FLOAT`) or 1 (PCM).
+    fn wav_format_tag(self) -> u16 {
+        if self.is_float() {
+            3
+        } else {
+            1
+        }
+    }
+
+    /// The format a WAV `fmt ` chunk with this tag and bit depth describes, if supported.
+    fn from_wav(format_tag: u16, bits_per_sample: u16) -> Option<Self> {
+        match (format_tag, bits_per_sample) {
+            (3, 32) => Some(SampleFormat::F32),
+            (3, 64) => Some(SampleFormat::F64),
+            (1, 16) => Some(SampleFormat::I16),
+            (1, 24) => Some(SampleFormat::I24),
+            (1, 32) => Some(SampleFormat::I32),
+            _ => None,
+        }
+    }
 
-    /// Create an output stream
-    fn create_output_stream(
-        &self,
-        device: &Self::Device,
-        config: AudioConfig,
-        data_callback: Box<dyn FnMut(&mut [f32]) + Send>,
-        error_callback: Box<dyn FnMut(Self::Error) + Send>,
-    ) -> Result<Self::Stream, Self::Error>;
+    /// Append `sample` to `out`, little-endian. Integer formats map ±1.0 to full scale and
+    /// clip beyond it; float formats store the value as is.
+    pub fn encode(self, sample: f32, out: &mut Vec<u8>) {
+        // Full scale is 2^(bits-1): -1.0 reaches the most negative code, +1.0 clips one short.
+        let quantize = |bits: u32| {
+            let full_scale = f64::from(1u32 << (bits - 1));
+            (f64::from(sample) * full_scale)
+                .round()
+                .clamp(-full_scale, full_scale - 1.0) as i32
+        };
+        match self {
+            SampleFormat::F32 => out.extend_from_slice(&sample.to_le_bytes()),
+            SampleFormat::F64 => out.extend_from_slice(&f64::from(sample).to_le_bytes()),
+            SampleFormat::I16 => out.extend_from_slice(&(quantize(16) as i16).to_le_bytes()),
+            SampleFormat::I24 => {
+                let value = quantize(24);
+                out.extend_from_slice(&[value as u8, (value >> 8) as u8, (value >> 16) as u8]);
+            }
+            SampleFormat::I32 => out.extend_from_slice(&quantize(32).to_le_bytes()),
+        }
+    }
 
-    /// Create an input stream
-    fn create_input_stream(
-        &self,
-        device: &Self::Device,
-        config: AudioConfig,
-        data_callback: Box<dyn FnMut(&[f32]) + Send>,
-        error_callback: Box<dyn FnMut(Self::Error) + Send>,
-    ) -> Result<Self::Stream, Self::Error>;
+    /// Decode one sample from the first `bits_per_sample / 8` bytes of `bytes`, the inverse of
+    /// [`Self::encode`].
+    pub fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            SampleFormat::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            SampleFormat::F64 => f64::from_le_bytes([
+                bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+            ]) as f32,
+            SampleFormat::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+            SampleFormat::I24 => {
+                let packed =
+                    i32::from(bytes[0]) | i32::from(bytes[1]) << 8 | i32::from(bytes[2]) << 16;
+                // Shift the sign bit up to bit 31 and back down to sign-extend.
+                ((packed << 8) >> 8) as f32 / 8_388_608.0
+            }
+            SampleFormat::I32 => {
+                (f64::from(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                    / 2_147_483_648.0) as f32
+            }
+        }
+    }
 }
 
-/// Write deinterleaved channel buffers to a 32-bit float WAV file (`WAVE_FORMAT_IEEE_FLOAT`).
-///
-/// `channels[ch][frame]`; all channels must be the same length. Used by offline rendering
-/// (e.g. [`crate::simple::render_to_wav`]) and audio export. No external dependency.
-pub fn write_wav<P: AsRef<std::path::Path>>(
-    path: P,
-    channels: &[Vec<f32>],
+/// The 44-byte header of a WAV file holding `data_size` bytes of `format` samples.
+fn wav_header(
+    format: SampleFormat,
+    num_channels: u16,
     sample_rate: u32,
-) -> crate::error::Result<()> {
-    use crate::error::Error;
-    use std::io::Write;
-
-    let num_channels = channels.len().max(1) as u16;
-    let frames = channels.iter().map(|c| c.len()).min().unwrap_or(0);
-    let bits_per_sample: u16 = 32;
+    data_size: u32,
+) -> Vec<u8> {
+    let bits_per_sample = format.bits_per_sample();
     let block_align = num_channels * (bits_per_sample / 8);
     let byte_rate = sample_rate * block_align as u32;
-    let data_size = (frames * num_channels as usize * (bits_per_sample / 8) as usize) as u32;
 
-    let mut buf: Vec<u8> = Vec::with_capacity(44 + data_size as usize);
+    let mut buf: Vec<u8> = Vec::with_capacity(44);
     buf.extend_from_slice(b"RIFF");
     buf.extend_from_slice(&(36 + data_size).to_le_bytes());
     buf.extend_from_slice(b"WAVE");
     buf.extend_from_slice(b"fmt ");
     buf.extend_from_slice(&16u32.to_le_bytes());
-    buf.extend_from_slice(&3u16.to_le_bytes()); // IEEE float
+    buf.extend_from_slice(&format.wav_format_tag().to_le_bytes());
     buf.extend_from_slice(&num_channels.to_le_bytes());
     buf.extend_from_slice(&sample_rate.to_le_bytes());
     buf.extend_from_slice(&byte_rate.to_le_bytes());
@@ -533,10 +2183,42 @@ pub fn write_wav<P: AsRef<std::path::Path>>(
     buf.extend_from_slice(&bits_per_sample.to_le_bytes());
     buf.extend_from_slice(b"data");
     buf.extend_from_slice(&data_size.to_le_bytes());
+    buf
+}
+
+/// Write deinterleaved channel buffers to a 32-bit float WAV file (`WAVE_FORMAT_IEEE_FLOAT`).
+///
+/// `channels[ch][frame]`; all channels must be the same length. Used by offline rendering
+/// (e.g. [`crate::simple::render_to_wav`]) and audio export. No external dependency.
+pub fn write_wav<P: AsRef<std::path::Path>>(
+    path: P,
+    channels: &[Vec<f32>],
+    sample_rate: u32,
+) -> crate::error::Result<()> {
+    write_wav_with_format(path, channels, sample_rate, SampleFormat::F32)
+}
+
+/// [`write_wav`] with the samples encoded as `format`, e.g. 24-bit PCM for a master.
+pub fn write_wav_with_format<P: AsRef<std::path::Path>>(
+    path: P,
+    channels: &[Vec<f32>],
+    sample_rate: u32,
+    format: SampleFormat,
+) -> crate::error::Result<()> {
+    use crate::error::Error;
+    use std::io::Write;
+
+    let num_channels = channels.len().max(1) as u16;
+    let frames = channels.iter().map(|c| c.len()).min().unwrap_or(0);
+    let data_size =
+        (frames * num_channels as usize * (format.bits_per_sample() / 8) as usize) as u32;
+
+    let mut buf = wav_header(format, num_channels, sample_rate, data_size);
+    buf.reserve(data_size as usize);
     // Interleave channels frame by frame.
     for f in 0..frames {
         for ch in channels {
-            buf.extend_from_slice(&ch[f].to_le_bytes());
+            format.encode(ch[f], &mut buf);
         }
     }
 
@@ -547,8 +2229,147 @@ pub fn write_wav<P: AsRef<std::path::Path>>(
     Ok(())
 }
 
-/// Read a WAV file written as 32-bit float (`WAVE_FORMAT_IEEE_FLOAT`) or 16-bit PCM, returning
-/// deinterleaved channels (`channels[ch][frame]`) and the sample rate. The inverse of
+/// Records audio to a WAV file as it arrives, block by block, in a chosen [`SampleFormat`].
+///
+/// The header's sizes are filled in by [`Self::finish`] (or, best effort, when the recorder is
+/// dropped), so a recording of any length never has to be held in memory.
+pub struct AudioRecorder {
+    file: std::io::BufWriter<std::fs::File>,
+    format: SampleFormat,
+    channels: u16,
+    sample_rate: u32,
+    frames: u64,
+    /// Encoded bytes of the block being written, reused across blocks.
+    scratch: Vec<u8>,
+    finished: bool,
+}
+
+impl AudioRecorder {
+    /// Start recording `channels` channels at `sample_rate` to a 32-bit float WAV at `path`.
+    pub fn open<P: AsRef<std::path::Path>>(
+        path: P,
+        channels: u16,
+        sample_rate: u32,
+    ) -> crate::error::Result<Self> {
+        Self::open_with_format(path, SampleFormat::F32, channels, sample_rate)
+    }
+
+    /// Start recording `channels` channels at `sample_rate` to a WAV at `path`, with samples
+    /// encoded as `format`.
+    pub fn open_with_format<P: AsRef<std::path::Path>>(
+        path: P,
+        format: SampleFormat,
+        channels: u16,
+        sample_rate: u32,
+    ) -> crate::error::Result<Self> {
+        use crate::error::Error;
+        use std::io::Write;
+
+        if channels == 0 {
+            return Err(Error::InvalidParameter(
+                "a recording needs at least one channel".to_string(),
+            ));
+        }
+        let file =
+            std::fs::File::create(path).map_err(|e| Error::Other(format!("create wav: {e}")))?;
+        let mut file = std::io::BufWriter::new(file);
+        // Sizes are placeholders until finish().
+        file.write_all(&wav_header(format, channels, sample_rate, 0))
+            .map_err(|e| Error::Other(format!("write wav: {e}")))?;
+        Ok(Self {
+            file,
+            format,
+            channels,
+            sample_rate,
+            frames: 0,
+            scratch: Vec::new(),
+            finished: false,
+        })
+    }
+
+    /// The format samples are written in.
+    pub fn format(&self) -> SampleFormat {
+        self.format
+    }
+
+    /// Frames written so far.
+    pub fn frames_written(&self) -> u64 {
+        self.frames
+    }
+
+    /// Append deinterleaved channel buffers (`channels[ch][frame]`), one per recorded
+    /// channel. Missing channels are written as silence, extra ones are ignored, and the
+    /// shortest buffer decides how many frames are written.
+    pub fn write(&mut self, channels: &[Vec<f32>]) -> crate::error::Result<()> {
+        let frames = channels.iter().map(|c| c.len()).min().unwrap_or(0);
+        self.scratch.clear();
+        for f in 0..frames {
+            for ch in 0..self.channels as usize {
+                let sample = channels.get(ch).map_or(0.0, |c| c[f]);
+                self.format.encode(sample, &mut self.scratch);
+            }
+        }
+        self.append(frames)
+    }
+
+    /// Append interleaved samples, e.g. an output stream's callback buffer. A trailing partial
+    /// frame is dropped.
+    pub fn write_interleaved(&mut self, samples: &[f32]) -> crate::error::Result<()> {
+        let channels = self.channels as usize;
+        let frames = samples.len() / channels;
+        self.scratch.clear();
+        for &sample in &samples[..frames * channels] {
+            self.format.encode(sample, &mut self.scratch);
+        }
+        self.append(frames)
+    }
+
+    fn append(&mut self, frames: usize) -> crate::error::Result<()> {
+        use std::io::Write;
+        self.file
+            .write_all(&self.scratch)
+            .map_err(|e| crate::error::Error::Other(format!("write wav: {e}")))?;
+        self.frames += frames as u64;
+        Ok(())
+    }
+
+    /// Fill in the header's sizes and flush the file.
+    pub fn finish(mut self) -> crate::error::Result<()> {
+        self.finalize()
+    }
+
+    fn finalize(&mut self) -> crate::error::Result<()> {
+        use std::io::{Seek, SeekFrom, Write};
+        let err = |e: std::io::Error| crate::error::Error::Other(format!("write wav: {e}"));
+        self.finished = true;
+        let data_size =
+            self.frames * u64::from(self.channels) * u64::from(self.format.bits_per_sample() / 8);
+        let data_size = u32::try_from(data_size).map_err(|_| {
+            crate::error::Error::Other("recording exceeds the 4 GB WAV limit".to_string())
+        })?;
+        self.file.seek(SeekFrom::Start(0)).map_err(err)?;
+        self.file
+            .write_all(&wav_header(
+                self.format,
+                self.channels,
+                self.sample_rate,
+                data_size,
+            ))
+            .map_err(err)?;
+        self.file.flush().map_err(err)
+    }
+}
+
+impl Drop for AudioRecorder {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.finalize();
+        }
+    }
+}
+
+/// Read a WAV file written in any [`SampleFormat`] (32/64-bit float, 16/24/32-bit PCM),
+/// returning deinterleaved channels (`channels[ch][frame]`) and the sample rate. The inverse of
 /// [`write_wav`]; used to feed a recorded signal into a plugin's input.
 pub fn read_wav<P: AsRef<std::path::Path>>(path: P) -> crate::error::Result<(Vec<Vec<f32>>, u32)> {
     use crate::error::Error;
@@ -588,24 +2409,13 @@ pub fn read_wav<P: AsRef<std::path::Path>>(path: P) -> crate::error::Result<(Vec
     let nch = channels as usize;
     let mut out: Vec<Vec<f32>> = vec![Vec::new(); nch];
     let bytes = &data[ds..de];
-    match (fmt_tag, bits) {
-        (3, 32) => {
-            for (i, frame) in bytes.chunks_exact(4 * nch).enumerate() {
-                let _ = i;
-                for (ch, s) in frame.chunks_exact(4).enumerate() {
-                    out[ch].push(f32::from_le_bytes([s[0], s[1], s[2], s[3]]));
-                }
-            }
+    let format = SampleFormat::from_wav(fmt_tag, bits)
+        .ok_or_else(|| err("unsupported format (need 32/64-bit float or 16/24/32-bit PCM)"))?;
+    let width = (format.bits_per_sample() / 8) as usize;
+    for frame in bytes.chunks_exact(width * nch) {
+        for (ch, s) in frame.chunks_exact(width).enumerate() {
+            out[ch].push(format.decode(s));
         }
-        (1, 16) => {
-            for frame in bytes.chunks_exact(2 * nch) {
-                for (ch, s) in frame.chunks_exact(2).enumerate() {
-                    let v = i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0;
-                    out[ch].push(v);
-                }
-            }
-        }
-        _ => return Err(err("unsupported format (need 32-bit float or 16-bit PCM)")),
     }
     Ok((out, sample_rate))
 }
@@ -794,6 +2604,75 @@ mod wav_tests {
             }
         }
     }
+
+    #[test]
+    fn i24_samples_pack_into_three_little_endian_bytes() {
+        let mut bytes = Vec::new();
+        SampleFormat::I24.encode(1.0, &mut bytes);
+        assert_eq!(bytes, [0xFF, 0xFF, 0x7F]); // 8388607
+        assert_eq!(SampleFormat::I24.decode(&bytes), 8_388_607.0 / 8_388_608.0);
+
+        bytes.clear();
+        SampleFormat::I24.encode(-1.0, &mut bytes);
+        assert_eq!(bytes, [0x00, 0x00, 0x80]); // -8388608
+        assert_eq!(SampleFormat::I24.decode(&bytes), -1.0);
+    }
+
+    #[test]
+    fn f32_to_i24_round_trip_noise_is_below_minus_144_dbfs() {
+        let mut bytes = Vec::new();
+        let mut error_energy = 0.0f64;
+        let n = 48_000;
+        for i in 0..n {
+            let x = 0.9 * (i as f32 * 0.0173).sin();
+            bytes.clear();
+            SampleFormat::I24.encode(x, &mut bytes);
+            let err = f64::from(SampleFormat::I24.decode(&bytes) - x);
+            error_energy += err * err;
+        }
+        let noise_dbfs = 10.0 * (error_energy / n as f64).log10();
+        assert!(
+            noise_dbfs < -144.0,
+            "quantization noise {noise_dbfs:.1} dBFS"
+        );
+    }
+
+    #[test]
+    fn recorder_writes_a_wav_read_wav_reads_back_in_every_format() {
+        let ch = [vec![0.0f32, 0.5, -0.5, 0.25], vec![0.1, -0.2, 0.3, -1.0]];
+        for format in [
+            SampleFormat::F32,
+            SampleFormat::F64,
+            SampleFormat::I16,
+            SampleFormat::I24,
+            SampleFormat::I32,
+        ] {
+            let path = std::env::temp_dir()
+                .join(format!("vh_recorder_{format:?}_{}.wav", std::process::id()));
+            let mut recorder = AudioRecorder::open_with_format(&path, format, 2, 48_000).unwrap();
+            recorder
+                .write(&[ch[0][..2].to_vec(), ch[1][..2].to_vec()])
+                .unwrap();
+            recorder
+                .write_interleaved(&[ch[0][2], ch[1][2], ch[0][3], ch[1][3]])
+                .unwrap();
+            assert_eq!(recorder.frames_written(), 4);
+            recorder.finish().unwrap();
+
+            let bytes = std::fs::read(&path).unwrap();
+            let (back, sr) = read_wav(&path).unwrap();
+            let _ = std::fs::remove_file(&path);
+            let width = (format.bits_per_sample() / 8) as usize;
+            assert_eq!(bytes.len(), 44 + 4 * 2 * width, "{format:?}");
+            assert_eq!(sr, 48_000);
+            for (a, b) in ch.iter().zip(&back) {
+                assert_eq!(b.len(), 4);
+                for (x, y) in a.iter().zip(b) {
+                    assert!((x - y).abs() < 1e-4, "{format:?}: {x} vs {y}");
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -884,6 +2763,111 @@ mod speaker_arrangement_tests {
     }
 }
 
+#[cfg(test)]
+mod output_analyzer_tests {
+    use super::*;
+
+    #[test]
+    fn classifies_silence_clipping_and_ok_blocks() {
+        let mut analyzer = OutputAnalyzer::new();
+        assert_eq!(analyzer.analyze(&mut [vec![0.0; 8]]), BlockHealth::Silent);
+        assert_eq!(
+            analyzer.analyze(&mut [vec![0.2; 8], vec![-1.5; 8]]),
+            BlockHealth::Clipping
+        );
+        assert_eq!(analyzer.analyze(&mut [vec![0.5; 8]]), BlockHealth::Ok);
+        // Exactly full scale is not clipping.
+        assert_eq!(analyzer.analyze(&mut [vec![-1.0; 8]]), BlockHealth::Ok);
+
+        let report = analyzer.report();
+        assert_eq!(report.blocks, 4);
+        assert_eq!(report.silence_blocks, 1);
+        assert_eq!(report.clipping_blocks, 1);
+        assert_eq!(report.nan_blocks, 0);
+        assert!(!report.needs_recovery);
+    }
+
+    #[test]
+    fn non_finite_block_is_silenced_and_flags_recovery() {
+        let mut analyzer = OutputAnalyzer::new();
+        let mut block = [vec![0.1, 0.1, 0.1, 0.1], vec![0.1, 0.1, f32::INFINITY, 0.1]];
+        assert_eq!(analyzer.analyze(&mut block), BlockHealth::NonFinite);
+        assert!(block.iter().flatten().all(|&x| x == 0.0));
+
+        let report = analyzer.report();
+        assert_eq!(report.nan_blocks, 1);
+        assert_eq!(report.last_nan_sample_index, Some(2));
+        assert!(report.needs_recovery);
+
+        analyzer.reset();
+        assert_eq!(analyzer.report(), AnalysisReport::default());
+    }
+}
+
+#[cfg(test)]
+mod atomic_pair_tests {
+    use super::AtomicF32Pair;
+    use std::sync::{Arc, Barrier};
+
+    #[test]
+    fn halves_round_trip_exactly() {
+        let pair = AtomicF32Pair::new(-0.0, f32::MAX);
+        assert_eq!(pair.load().1, f32::MAX);
+        assert!(pair.load().0.is_sign_negative());
+        pair.store(0.123_456_7, 1e-30);
+        assert_eq!(pair.load(), (0.123_456_7, 1e-30));
+    }
+
+    #[test]
+    fn peaks_cross_threads_without_loss() {
+        // Each round the "audio" thread publishes a peak and the "UI" thread reads and resets
+        // it; the barriers order them, so every published value must be read back exactly.
+        const ROUNDS: usize = 1000;
+        let pair = Arc::new(AtomicF32Pair::default());
+        let barrier = Arc::new(Barrier::new(2));
+        let writer = {
+            let (pair, barrier) = (Arc::clone(&pair), Arc::clone(&barrier));
+            std::thread::spawn(move || {
+                for i in 0..ROUNDS {
+                    let level = i as f32 / ROUNDS as f32;
+                    pair.fetch_max(level, 1.0 - level);
+                    barrier.wait(); // published
+                    barrier.wait(); // read
+                }
+            })
+        };
+        for i in 0..ROUNDS {
+            barrier.wait();
+            let level = i as f32 / ROUNDS as f32;
+            assert_eq!(pair.swap(0.0, 0.0), (level, 1.0 - level));
+            barrier.wait();
+        }
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn concurrent_fetch_max_keeps_the_loudest() {
+        let pair = Arc::new(AtomicF32Pair::default());
+        let barrier = Arc::new(Barrier::new(4));
+        let writers: Vec<_> = (0..4)
+            .map(|t| {
+                let (pair, barrier) = (Arc::clone(&pair), Arc::clone(&barrier));
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..10_000 {
+                        let v = (t * 10_000 + i) as f32;
+                        pair.fetch_max(v, -v);
+                    }
+                })
+            })
+            .collect();
+        for w in writers {
+            w.join().unwrap();
+        }
+        assert_eq!(pair.load(), (39_999.0, 0.0));
+    }
+}
+
 #[cfg(test)]
 mod meter_tests {
     use super::*;
@@ -934,6 +2918,39 @@ mod meter_tests {
         );
     }
 
+    #[test]
+    fn manual_reset_holds_through_silence_until_reset() {
+        let t0 = Instant::now();
+        let mut levels = AudioLevels::new(2);
+        levels.set_hold_mode(MeterHoldMode::ManualReset);
+        levels.update_from_buffers_at(&[vec![0.8; 64], vec![-0.5; 64]], t0);
+        let silence = [vec![0.0; 64], vec![0.0; 64]];
+        // Ten seconds of silence, a block every 100 ms.
+        for block in 1..=100 {
+            levels.update_from_buffers_at(&silence, t0 + Duration::from_millis(block * 100));
+        }
+        let holds: Vec<f32> = levels.channels.iter().map(|c| c.peak_hold).collect();
+        assert_eq!(holds, [0.8, 0.5]);
+        levels.reset_peak_hold(t0 + Duration::from_secs(10));
+        assert!(levels.channels.iter().all(|c| c.peak_hold == 0.0));
+
+        // The default auto reset lets go after 3 s.
+        let mut auto = AudioLevels::new(1);
+        auto.update_from_buffers_at(&[vec![0.8; 64]], t0);
+        auto.update_from_buffers_at(&[vec![0.0; 64]], t0 + Duration::from_secs(2));
+        assert_eq!(auto.channels[0].peak_hold, 0.8);
+        auto.update_from_buffers_at(&[vec![0.0; 64]], t0 + Duration::from_secs(4));
+        assert_eq!(auto.channels[0].peak_hold, 0.0);
+
+        let mut meter = PeakMeter::new(20.0, Duration::from_secs(3));
+        meter.set_hold_mode(MeterHoldMode::ManualReset);
+        meter.push(0.8, t0);
+        meter.push(0.0, t0 + Duration::from_secs(10));
+        assert_eq!(meter.peak_hold(), 0.8);
+        meter.reset_peak_hold(t0 + Duration::from_secs(10));
+        assert_eq!(meter.peak_hold(), 0.0);
+    }
+
     #[test]
     fn peak_meter_reaches_silence_floor() {
         let mut m = PeakMeter::new(60.0, Duration::from_millis(0));
@@ -984,3 +3001,380 @@ mod meter_tests {
         assert_eq!(r.capacity, 480);
     }
 }
+
+#[cfg(test)]
+mod oversampling_tests {
+    use super::*;
+
+    fn sine(len: usize, hz: f64, rate: f64) -> Vec<f32> {
+        (0..len)
+            .map(|n| (2.0 * std::f64::consts::PI * hz * n as f64 / rate).sin() as f32)
+            .collect()
+    }
+
+    #[test]
+    fn factor_two_output_matches_the_input_sample_count() {
+        let adapter = OversamplingAdapter::new(2).unwrap();
+        let input = sine(100, 1000.0, 48_000.0);
+        let up = adapter.upsample(&input);
+        assert_eq!(up.len(), 200);
+        let down = adapter.downsample(&up);
+        assert_eq!(down.len(), input.len());
+        // Well inside the passband the round trip gives the signal back (away from the
+        // zero-padded block edges).
+        for (a, b) in input.iter().zip(&down).skip(20).take(60) {
+            assert!((a - b).abs() < 1e-2, "{a} vs {b}");
+        }
+
+        let mut oversampler = Oversampler::new(adapter);
+        let mut buffers = AudioBuffers::new(1, 2, 64, 48_000.0);
+        oversampler
+            .process(&mut buffers, |hi| {
+                assert_eq!((hi.block_size, hi.sample_rate), (128, 96_000.0));
+                assert!(hi.inputs[0].len() == 128 && hi.outputs.iter().all(|o| o.len() == 128));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(buffers.block_size, 64);
+        assert!(buffers.outputs.iter().all(|o| o.len() == 64));
+    }
+
+    #[test]
+    fn streaming_oversampler_delays_by_its_latency() {
+        for factor in [2, 4, 8] {
+            let adapter = OversamplingAdapter::new(factor).unwrap();
+            let latency = adapter.latency();
+            let mut oversampler = Oversampler::new(adapter);
+            let signal = sine(1024, 440.0, 48_000.0);
+            let mut out = Vec::new();
+            for block in signal.chunks(128) {
+                let mut buffers = AudioBuffers::new(1, 1, block.len(), 48_000.0);
+                buffers.inputs[0].copy_from_slice(block);
+                // An "effect" that passes its input straight through.
+                oversampler
+                    .process(&mut buffers, |hi| {
+                        hi.outputs[0].copy_from_slice(&hi.inputs[0]);
+                        Ok(())
+                    })
+                    .unwrap();
+                out.extend_from_slice(&buffers.outputs[0]);
+            }
+            for n in 2 * latency..signal.len() {
+                let expected = signal[n - latency];
+                assert!(
+                    (out[n] - expected).abs() < 1e-2,
+                    "factor {factor}, sample {n}: {} vs {expected}",
+                    out[n]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn only_supported_factors_are_accepted() {
+        assert!(OversamplingAdapter::new(3).is_err());
+        assert!(OversamplingAdapter::new(0).is_err());
+        assert!(crate::Vst3Host::builder()
+            .oversampling_factor(16)
+            .build()
+            .is_err());
+        let one = OversamplingAdapter::new(1).unwrap();
+        assert_eq!(one.upsample(&[0.25, -0.5]), [0.25, -0.5]);
+        assert_eq!(one.latency(), 0);
+    }
+
+    #[test]
+    fn plugin_is_set_up_at_the_oversampled_rate_and_block_size() {
+        let config = AudioConfig {
+            sample_rate: 48_000.0,
+            block_size: 512,
+            oversampling_factor: 4,
+            ..AudioConfig::default()
+        };
+        assert_eq!(config.plugin_setup(), (192_000.0, 2048));
+        assert_eq!(config.oversampler().unwrap().factor(), 4);
+        assert_eq!(AudioConfig::default().plugin_setup(), (44_100.0, 512));
+        assert!(AudioConfig::default().oversampler().is_none());
+    }
+}
+
+#[cfg(test)]
+mod input_gain_tests {
+    use super::*;
+
+    fn sine_blocks(stage: &mut InputGainStage, amplitude: f32, seconds: f64) -> Vec<f32> {
+        let rate = stage.sample_rate();
+        let total = (seconds * rate) as usize;
+        let mut last = Vec::new();
+        for start in (0..total).step_by(256) {
+            let mut block: Vec<f32> = (start..start + 256)
+                .map(|n| {
+                    let t = n as f64 / rate;
+                    amplitude * (2.0 * std::f64::consts::PI * 440.0 * t).sin() as f32
+                })
+                .collect();
+            stage.process_block(&mut block);
+            last = block;
+        }
+        last
+    }
+
+    fn rms_db(block: &[f32]) -> f64 {
+        let mean = block.iter().map(|s| (*s as f64).powi(2)).sum::<f64>() / block.len() as f64;
+        10.0 * mean.log10()
+    }
+
+    #[test]
+    fn auto_gain_converges_on_a_sine() {
+        // A quiet sine (−29 dBFS RMS) is brought up to −18 dBFS, a hot one (−9) down.
+        for amplitude in [0.05f32, 0.5] {
+            let mut stage = InputGainStage::with_auto_gain(48_000.0, NOMINAL_INPUT_LEVEL_DB);
+            let out = sine_blocks(&mut stage, amplitude, 5.0);
+            let level = rms_db(&out);
+            assert!(
+                (level - NOMINAL_INPUT_LEVEL_DB).abs() < 0.5,
+                "amplitude {amplitude}: settled at {level:.2} dBFS"
+            );
+        }
+    }
+
+    #[test]
+    fn fixed_gain_ignores_the_input_level() {
+        let mut stage = InputGainStage::new(48_000.0);
+        stage.gain_db = -6.0;
+        sine_blocks(&mut stage, 0.05, 2.0);
+        assert_eq!(stage.gain_db, -6.0);
+    }
+
+    #[test]
+    fn auto_gain_leaves_silence_alone() {
+        let mut stage = InputGainStage::with_auto_gain(48_000.0, NOMINAL_INPUT_LEVEL_DB);
+        let mut block = vec![0.0f32; 4800];
+        for _ in 0..20 {
+            stage.process_block(&mut block);
+        }
+        assert_eq!(stage.gain_db, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod auto_limiter_tests {
+    use super::*;
+
+    fn sine(amplitude: f32, frames: usize, offset: usize) -> Vec<f32> {
+        (offset..offset + frames)
+            .map(|n| {
+                amplitude * (2.0 * std::f64::consts::PI * 440.0 * n as f64 / 48_000.0).sin() as f32
+            })
+            .collect()
+    }
+
+    fn peak(channels: &[Vec<f32>]) -> f64 {
+        channels
+            .iter()
+            .flatten()
+            .fold(0.0f32, |p, s| p.max(s.abs())) as f64
+    }
+
+    #[test]
+    fn a_double_amplitude_sine_comes_out_at_the_threshold() {
+        for threshold_db in [0.0, -6.0] {
+            let mut limiter = AutoLimiter::new(48_000.0);
+            limiter.threshold_db = threshold_db;
+            let threshold = 10f64.powf(threshold_db / 20.0);
+            for block in 0..50 {
+                let mut channels = vec![sine(2.0, 512, block * 512); 2];
+                let input = peak(&channels);
+                limiter.process_block(&mut channels);
+                assert!(
+                    (peak(&channels) - threshold).abs() < 1e-6,
+                    "threshold {threshold_db} dB, block {block}: peak {}",
+                    peak(&channels)
+                );
+                let expected_gr = 20.0 * (input / threshold).log10();
+                assert!((limiter.gain_reduction_db() as f64 - expected_gr).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn quiet_audio_passes_untouched_and_the_gain_recovers() {
+        let mut limiter = AutoLimiter::new(48_000.0);
+        let mut quiet = vec![sine(0.5, 512, 0)];
+        let original = quiet.clone();
+        limiter.process_block(&mut quiet);
+        assert_eq!(quiet, original);
+        assert_eq!(limiter.gain_reduction_db(), 0.0);
+
+        limiter.process_block(&mut [vec![4.0f32; 480]]);
+        assert!((limiter.gain_reduction_db() - 12.04).abs() < 0.01);
+        // Two release time constants of silence: 12 dB × e⁻² ≈ 1.6 dB left.
+        limiter.process_block(&mut [vec![0.0f32; 19_200]]);
+        let left = limiter.gain_reduction_db();
+        assert!((left - 12.04 * (-2.0f32).exp()).abs() < 0.01, "{left}");
+    }
+}
+
+#[cfg(test)]
+mod device_info_tests {
+    use super::*;
+
+    #[test]
+    fn supports_config_checks_channels_rate_and_buffer() {
+        let device = AudioDeviceInfo {
+            name: "Interface".into(),
+            max_channels: 2,
+            sample_rates: vec![44_100.0, 48_000.0],
+            min_buffer_size: 64,
+            max_buffer_size: 2048,
+        };
+        let config = AudioConfig {
+            sample_rate: 48_000.0,
+            block_size: 512,
+            output_channels: 2,
+            ..AudioConfig::default()
+        };
+        assert!(device.supports_config(&config));
+        assert!(!device.supports_config(&AudioConfig {
+            sample_rate: 96_000.0,
+            ..config
+        }));
+        assert!(!device.supports_config(&AudioConfig {
+            output_channels: 8,
+            ..config
+        }));
+        assert!(!device.supports_config(&AudioConfig {
+            block_size: 4096,
+            ..config
+        }));
+        let unknown_buffers = AudioDeviceInfo {
+            min_buffer_size: 0,
+            max_buffer_size: 0,
+            ..device
+        };
+        assert!(unknown_buffers.supports_config(&AudioConfig {
+            block_size: 4096,
+            ..config
+        }));
+    }
+
+    #[test]
+    fn sample_rate_falls_back_to_the_closest_rate_the_plugin_accepts() {
+        let device = AudioDeviceInfo {
+            name: "Interface".into(),
+            max_channels: 2,
+            sample_rates: vec![44_100.0, 48_000.0],
+            min_buffer_size: 0,
+            max_buffer_size: 0,
+        };
+        assert_eq!(device.supported_sample_rates(), [44_100.0, 48_000.0]);
+        assert_eq!(device.closest_supported_rate(48_000.0), Some(48_000.0));
+        assert_eq!(device.closest_supported_rate(96_000.0), Some(48_000.0));
+        assert_eq!(device.closest_supported_rate(22_050.0), Some(44_100.0));
+
+        let mut tried = Vec::new();
+        let rate = device.negotiate_sample_rate(96_000.0, |rate| {
+            tried.push(rate);
+            if rate == 44_100.0 {
+                Ok(())
+            } else {
+                Err(crate::Error::Other("setupProcessing failed".into()))
+            }
+        });
+        assert_eq!(rate.unwrap(), 44_100.0);
+        assert_eq!(tried, [48_000.0, 44_100.0]);
+
+        let err = device
+            .negotiate_sample_rate(96_000.0, |_| Err(crate::Error::Other("no".into())))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            crate::Error::SampleRateNotSupported { requested, ref available }
+                if requested == 96_000.0 && available == &[44_100.0, 48_000.0]
+        ));
+
+        let silent = AudioDeviceInfo {
+            sample_rates: Vec::new(),
+            ..device
+        };
+        assert_eq!(silent.closest_supported_rate(96_000.0), None);
+        assert_eq!(
+            silent
+                .negotiate_sample_rate(96_000.0, |_| unreachable!())
+                .unwrap(),
+            96_000.0
+        );
+    }
+}
+
+#[cfg(test)]
+mod jitter_tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn synthetic_callbacks_land_in_their_jitter_bins() {
+        // 480 frames at 48 kHz: one callback every 10 ms.
+        let mut tracker = CallbackJitterTracker::new(480, 48_000.0);
+        assert_eq!(tracker.expected_period(), Duration::from_millis(10));
+        let start = Instant::now();
+        let mut at = start;
+        tracker.record_at(at);
+        for delta_us in [10_000, 10_005, 9_990, 10_063, 10_064, 10_200, 9_000, 30_000] {
+            at += Duration::from_micros(delta_us);
+            tracker.record_at(at);
+        }
+        let fine = tracker.jitter_histogram();
+        assert_eq!((fine[0], fine[5], fine[10], fine[63]), (1, 1, 1, 1));
+        let coarse = tracker.overflow_histogram();
+        // 64 µs starts the overflow bins; 200 µs is in [192, 256); 1 ms and 20 ms pin to
+        // their bins and the last.
+        assert_eq!((coarse[0], coarse[2], coarse[14], coarse[63]), (1, 1, 1, 1));
+        assert_eq!(tracker.samples(), 8);
+        assert_eq!(tracker.max_jitter_us(), 20_000);
+
+        tracker.reset();
+        assert_eq!(tracker.samples(), 0);
+        assert_eq!(tracker.expected_period(), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn record_interval_takes_the_period_from_the_delivered_frames() {
+        // Configured for 480 frames, but the device delivers 512 (10.667 ms) every time.
+        let mut tracker = CallbackJitterTracker::new(480, 48_000.0);
+        for _ in 0..10 {
+            tracker.record_interval(Duration::from_nanos(10_666_667), 512);
+        }
+        assert_eq!(tracker.jitter_histogram()[0], 10);
+        assert_eq!(tracker.expected_period(), Duration::from_nanos(10_666_667));
+    }
+
+    #[test]
+    fn percentiles_read_the_histogram() {
+        let mut tracker = CallbackJitterTracker::new(480, 48_000.0);
+        let mut at = Instant::now();
+        tracker.record_at(at);
+        // 990 steady callbacks 2 µs off, 9 at 40 µs off and one 1 ms late.
+        for i in 0..1_000 {
+            let late = match i {
+                0..990 => 2,
+                990..999 => 40,
+                _ => 1_000,
+            };
+            at += Duration::from_micros(10_000 + late);
+            tracker.record_at(at);
+        }
+        assert_eq!(tracker.p99_jitter_us(), 2.0);
+        assert_eq!(tracker.p999_jitter_us(), 40.0);
+        assert!(!tracker.stats().is_jittery());
+
+        for _ in 0..20 {
+            at += Duration::from_micros(10_700);
+            tracker.record_at(at);
+        }
+        let stats = tracker.stats();
+        // 700 µs falls in the overflow bin starting at 64 + 9 * 64 = 640 µs.
+        assert_eq!(stats.p99_jitter_us, 640.0);
+        assert!(stats.is_jittery());
+    }
+}
@@ -0,0 +1,448 @@
+//! Aggregate backend: render once and play the same audio on several backends at the same
+//! time (monitors + headphones, a live output + a loopback recorder, …).
+//!
+//! The first backend whose stream opens leads: its device callback renders the block and
+//! copies it into a lock-free ring per follower, and each follower's callback plays from its
+//! ring. The device clocks are independent, so a follower that runs ahead hears silence and
+//! one that falls behind drops samples, like [`play_with_input_backend`]'s bridge.
+//!
+//! [`play_with_input_backend`]: crate::playback::play_with_input_backend
+
+use crate::audio::{AudioBackend, AudioConfig, AudioStream};
+use crate::error::{Error, Result};
+use rtrb::{Consumer, Producer, RingBuffer};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// An [`AudioBackend`] reduced to its default output device, with the associated types
+/// erased so backends of different kinds can share one [`AggregateBackend`]. Implemented for
+/// every [`AudioBackend`].
+#[allow(clippy::type_complexity)] // Box<dyn FnMut...> callbacks are intrinsic to the API
+pub trait DynAudioBackend: Send + Sync {
+    /// Open an output stream on the backend's default output device. Stream errors reach
+    /// `error_callback` as text.
+    fn open_default_output(
+        &self,
+        config: AudioConfig,
+        data_callback: Box<dyn FnMut(&mut [f32]) + Send>,
+        error_callback: Box<dyn FnMut(String) + Send>,
+    ) -> Result<Box<dyn AudioStream>>;
+}
+
+impl<B: AudioBackend> DynAudioBackend for B {
+    fn open_default_output(
+        &self,
+        config: AudioConfig,
+        data_callback: Box<dyn FnMut(&mut [f32]) + Send>,
+        mut error_callback: Box<dyn FnMut(String) + Send>,
+    ) -> Result<Box<dyn AudioStream>> {
+        let device = self
+            .default_output_device()
+            .ok_or_else(|| Error::AudioBackendError("No default output device available".into()))?;
+        let stream = self
+            .create_output_stream(
+                &device,
+                config,
+                data_callback,
+                Box::new(move |e: B::Error| error_callback(e.to_string())),
+            )
+            .map_err(|e| {
+                Error::AudioBackendError(format!("Failed to create output stream: {}", e))
+            })?;
+        Ok(Box::new(stream))
+    }
+}
+
+/// Identifies a backend within one [`AggregateBackend`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BackendHandle(pub u32);
+
+/// The aggregate's only device: every member's default output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AggregateDevice;
+
+/// A device callback filling an interleaved output block.
+type DataCallback = Box<dyn FnMut(&mut [f32]) + Send>;
+
+type Members = Arc<Mutex<Vec<(BackendHandle, Arc<dyn DynAudioBackend>)>>>;
+
+/// Remove `handle` from `members`, logging why.
+fn drop_member(members: &Members, handle: BackendHandle, reason: &str) {
+    log::error!("aggregate backend {:?} removed: {}", handle, reason);
+    members
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .retain(|(h, _)| *h != handle);
+}
+
+/// An [`AudioBackend`] that fans one rendered stream out to several backends at once.
+///
+/// A backend whose stream fails to open, or later reports an error (e.g. its device was
+/// unplugged), is logged and removed; the others keep playing. Adding or removing backends
+/// affects streams created afterwards.
+#[derive(Default)]
+pub struct AggregateBackend {
+    members: Members,
+    next_handle: u32,
+}
+
+impl AggregateBackend {
+    /// An aggregate of `backends`; the first one leads.
+    pub fn new(backends: Vec<Box<dyn DynAudioBackend>>) -> Self {
+        let mut aggregate = Self::default();
+        for backend in backends {
+            aggregate.add_backend(backend);
+        }
+        aggregate
+    }
+
+    /// Add a backend after the existing ones.
+    pub fn add_backend(&mut self, backend: Box<dyn DynAudioBackend>) -> BackendHandle {
+        let handle = BackendHandle(self.next_handle);
+        self.next_handle += 1;
+        self.lock_members().push((handle, Arc::from(backend)));
+        handle
+    }
+
+    /// Remove a backend. Unknown handles (including backends already dropped after an
+    /// error) are ignored.
+    pub fn remove_backend(&mut self, handle: BackendHandle) {
+        self.lock_members().retain(|(h, _)| *h != handle);
+    }
+
+    /// Handles of the current backends, leader first
+    pub fn handles(&self) -> Vec<BackendHandle> {
+        self.lock_members().iter().map(|(h, _)| *h).collect()
+    }
+
+    /// Number of backends
+    pub fn len(&self) -> usize {
+        self.lock_members().len()
+    }
+
+    /// Whether there are no backends
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock_members(
+        &self,
+    ) -> std::sync::MutexGuard<'_, Vec<(BackendHandle, Arc<dyn DynAudioBackend>)>> {
+        self.members.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+/// One follower's end of the fan-out.
+struct Tap {
+    producer: Producer<f32>,
+    failed: Arc<AtomicBool>,
+}
+
+/// The leader's callback state: the render callback and the followers' rings.
+struct Fanout {
+    render: DataCallback,
+    taps: Vec<Tap>,
+}
+
+impl Fanout {
+    fn run(&mut self, data: &mut [f32]) {
+        (self.render)(data);
+        self.taps.retain(|tap| !tap.failed.load(Ordering::Relaxed));
+        for tap in &mut self.taps {
+            // Drop on full (the follower fell behind) rather than block the leader.
+            for &s in data.iter() {
+                let _ = tap.producer.push(s);
+            }
+        }
+    }
+}
+
+/// The streams of every member of an [`AggregateBackend`], played and paused together.
+pub struct AggregateStream {
+    streams: Vec<Box<dyn AudioStream>>,
+}
+
+impl AudioStream for AggregateStream {
+    fn play(&self) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        for stream in &self.streams {
+            stream.play()?;
+        }
+        Ok(())
+    }
+
+    fn pause(&self) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        for stream in &self.streams {
+            stream.pause()?;
+        }
+        Ok(())
+    }
+}
+
+impl AudioBackend for AggregateBackend {
+    type Stream = AggregateStream;
+    type Device = AggregateDevice;
+    type Error = Error;
+
+    fn enumerate_output_devices(&self) -> Result<Vec<AggregateDevice>> {
+        Ok(self.default_output_device().into_iter().collect())
+    }
+
+    fn enumerate_input_devices(&self) -> Result<Vec<AggregateDevice>> {
+        Ok(Vec::new())
+    }
+
+    fn default_output_device(&self) -> Option<AggregateDevice> {
+        (!self.is_empty()).then_some(AggregateDevice)
+    }
+
+    fn default_input_device(&self) -> Option<AggregateDevice> {
+        None
+    }
+
+    fn create_output_stream(
+        &self,
+        _device: &AggregateDevice,
+        config: AudioConfig,
+        data_callback: Box<dyn FnMut(&mut [f32]) + Send>,
+        error_callback: Box<dyn FnMut(Error) + Send>,
+    ) -> Result<AggregateStream> {
+        let members: Vec<_> = self.lock_members().clone();
+        let ring_cap = (config.block_size * config.output_channels * 8).max(2048);
+        let fanout = Arc::new(Mutex::new(Fanout {
+            render: data_callback,
+            taps: Vec::new(),
+        }));
+        let error_callback = Arc::new(Mutex::new(error_callback));
+        let mut streams = Vec::with_capacity(members.len());
+
+        for (handle, backend) in members {
+            let opened = if streams.is_empty() {
+                // No leader yet: this one renders.
+                let fanout = Arc::clone(&fanout);
+                let (members, error_callback) =
+                    (Arc::clone(&self.members), Arc::clone(&error_callback));
+                backend.open_default_output(
+                    config,
+                    Box::new(move |data: &mut [f32]| {
+                        fanout.lock().unwrap_or_else(|p| p.into_inner()).run(data);
+                    }),
+                    Box::new(move |e: String| {
+                        drop_member(&members, handle, &e);
+                        let mut callback = error_callback.lock().unwrap_or_else(|p| p.into_inner());
+                        callback(Error::AudioBackendError(e));
+                    }),
+                )
+            } else {
+                let (producer, mut consumer): (Producer<f32>, Consumer<f32>) =
+                    RingBuffer::new(ring_cap);
+                let failed = Arc::new(AtomicBool::new(false));
+                let (members, flag) = (Arc::clone(&self.members), Arc::clone(&failed));
+                let opened = backend.open_default_output(
+                    config,
+                    Box::new(move |data: &mut [f32]| {
+                        for s in data.iter_mut() {
+                            *s = consumer.pop().unwrap_or(0.0);
+                        }
+                    }),
+                    Box::new(move |e: String| {
+                        flag.store(true, Ordering::Relaxed);
+                        drop_member(&members, handle, &e);
+                    }),
+                );
+                if opened.is_ok() {
+                    fanout
+                        .lock()
+                        .unwrap_or_else(|p| p.into_inner())
+                        .taps
+                        .push(Tap { producer, failed });
+                }
+                opened
+            };
+            match opened {
+                Ok(stream) => streams.push(stream),
+                Err(e) => drop_member(&self.members, handle, &e.to_string()),
+            }
+        }
+
+        if streams.is_empty() {
+            return Err(Error::AudioBackendError(
+                "No backend in the aggregate could open an output stream".into(),
+            ));
+        }
+        Ok(AggregateStream { streams })
+    }
+
+    fn create_input_stream(
+        &self,
+        _device: &AggregateDevice,
+        _config: AudioConfig,
+        _data_callback: Box<dyn FnMut(&[f32]) + Send>,
+        _error_callback: Box<dyn FnMut(Error) + Send>,
+    ) -> Result<AggregateStream> {
+        Err(Error::AudioBackendError(
+            "The aggregate backend has no inputs".into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type ErrorCallback = Box<dyn FnMut(std::fmt::Error) + Send>;
+
+    /// A backend whose single stream is pulled by hand.
+    #[derive(Clone, Default)]
+    struct ManualOutput {
+        data: Arc<Mutex<Option<DataCallback>>>,
+        error: Arc<Mutex<Option<ErrorCallback>>>,
+        refuse_to_open: bool,
+    }
+
+    impl ManualOutput {
+        fn pull(&self, samples: usize) -> Vec<f32> {
+            let mut block = vec![f32::NAN; samples];
+            (self.data.lock().unwrap().as_mut().expect("stream open"))(&mut block);
+            block
+        }
+
+        fn fail(&self) {
+            (self.error.lock().unwrap().as_mut().expect("stream open"))(std::fmt::Error);
+        }
+    }
+
+    struct ManualStream;
+
+    impl AudioStream for ManualStream {
+        fn play(&self) -> std::result::Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+
+        fn pause(&self) -> std::result::Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+    }
+
+    impl AudioBackend for ManualOutput {
+        type Stream = ManualStream;
+        type Device = ();
+        type Error = std::fmt::Error;
+
+        fn enumerate_output_devices(&self) -> std::result::Result<Vec<()>, std::fmt::Error> {
+            Ok(vec![()])
+        }
+
+        fn enumerate_input_devices(&self) -> std::result::Result<Vec<()>, std::fmt::Error> {
+            Ok(Vec::new())
+        }
+
+        fn default_output_device(&self) -> Option<()> {
+            Some(())
+        }
+
+        fn default_input_device(&self) -> Option<()> {
+            None
+        }
+
+        fn create_output_stream(
+            &self,
+            _device: &(),
+            _config: AudioConfig,
+            data_callback: DataCallback,
+            error_callback: ErrorCallback,
+        ) -> std::result::Result<ManualStream, std::fmt::Error> {
+            if self.refuse_to_open {
+                return Err(std::fmt::Error);
+            }
+            *self.data.lock().unwrap() = Some(data_callback);
+            *self.error.lock().unwrap() = Some(error_callback);
+            Ok(ManualStream)
+        }
+
+        fn create_input_stream(
+            &self,
+            _device: &(),
+            _config: AudioConfig,
+            _data_callback: Box<dyn FnMut(&[f32]) + Send>,
+            _error_callback: ErrorCallback,
+        ) -> std::result::Result<ManualStream, std::fmt::Error> {
+            Err(std::fmt::Error)
+        }
+    }
+
+    /// A render callback producing a ramp that continues across blocks.
+    fn ramp() -> DataCallback {
+        let mut next = 0.0;
+        Box::new(move |data: &mut [f32]| {
+            for s in data {
+                *s = next;
+                next += 0.01;
+            }
+        })
+    }
+
+    #[test]
+    fn every_backend_receives_the_same_samples() {
+        let (monitors, headphones) = (ManualOutput::default(), ManualOutput::default());
+        let aggregate = AggregateBackend::new(vec![
+            Box::new(monitors.clone()),
+            Box::new(headphones.clone()),
+        ]);
+        let stream = aggregate
+            .create_output_stream(
+                &AggregateDevice,
+                AudioConfig::default(),
+                ramp(),
+                Box::new(|_| {}),
+            )
+            .unwrap();
+        stream.play().unwrap();
+
+        let leader: Vec<f32> = [monitors.pull(64), monitors.pull(64)].concat();
+        let follower: Vec<f32> = [headphones.pull(64), headphones.pull(64)].concat();
+        assert_eq!(leader, follower);
+        assert!((leader[100] - 1.0).abs() < 1e-4);
+        // Nothing rendered yet: the follower plays silence instead of repeating audio.
+        assert_eq!(headphones.pull(8), vec![0.0; 8]);
+    }
+
+    #[test]
+    fn failing_backends_are_removed() {
+        let leader = ManualOutput::default();
+        let unplugged = ManualOutput::default();
+        let broken = ManualOutput {
+            refuse_to_open: true,
+            ..Default::default()
+        };
+        let mut aggregate = AggregateBackend::new(vec![
+            Box::new(leader.clone()),
+            Box::new(broken),
+            Box::new(unplugged.clone()),
+        ]);
+        let spare = aggregate.add_backend(Box::new(ManualOutput::default()));
+        assert_eq!(aggregate.len(), 4);
+
+        let _stream = aggregate
+            .create_output_stream(
+                &AggregateDevice,
+                AudioConfig::default(),
+                ramp(),
+                Box::new(|_| {}),
+            )
+            .unwrap();
+        assert_eq!(
+            aggregate.handles(),
+            vec![BackendHandle(0), BackendHandle(2), spare],
+            "the backend that could not open is dropped"
+        );
+
+        unplugged.fail();
+        assert_eq!(aggregate.handles(), vec![BackendHandle(0), spare]);
+        leader.pull(16);
+        assert_eq!(unplugged.pull(16), vec![0.0; 16], "no longer fed");
+
+        aggregate.remove_backend(spare);
+        aggregate.remove_backend(spare);
+        assert_eq!(aggregate.handles(), vec![BackendHandle(0)]);
+    }
+}
@@ -1,13 +1,18 @@
 //! CPAL audio backend implementation
 
 use crate::{
-    audio::{AudioBackend, AudioConfig, AudioStream},
+    audio::{AudioBackend, AudioConfig, AudioDeviceInfo, AudioStream, CaptureSource},
     error::{Error, Result},
+    stream_monitor::StreamError,
 };
 use cpal::{
     traits::{DeviceTrait, HostTrait, StreamTrait},
-    BufferSize, Device, Stream, StreamConfig, SupportedBufferSize,
+    BufferSize, Device, ErrorKind, Stream, StreamConfig, SupportedBufferSize,
 };
+use rtrb::RingBuffer;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
 
 /// Clamp a requested `block_size` into a device-advertised supported buffer range.
 ///
@@ -87,6 +92,8 @@ fn resolve_input_buffer_size(device: &Device, config: &AudioConfig) -> BufferSiz
 pub struct CpalStream {
     // We use Option to allow moving the stream in drop
     stream: Option<Stream>,
+    // Runs the data callback when `AudioConfig::audio_thread_stack_size` is set.
+    render: Option<RenderThread>,
 }
 
 // Manually implement Send for CpalStream
@@ -117,24 +124,289 @@ impl AudioStream for CpalStream {
 
 impl Drop for CpalStream {
     fn drop(&mut self) {
-        // Drop the stream
+        // Drop the stream, then the render thread it was feeding
         self.stream.take();
+        self.render.take();
+    }
+}
+
+/// Output data callback, as handed to [`AudioBackend::create_output_stream`].
+type OutputCallback = Box<dyn FnMut(&mut [f32]) + Send>;
+/// Input data callback, as handed to [`AudioBackend::create_input_stream`].
+type InputCallback = Box<dyn FnMut(&[f32]) + Send>;
+
+/// Samples the ring between a device callback and its [`RenderThread`] holds: eight buffers
+/// of the larger of `block_size` and the device's fixed buffer size.
+fn render_ring_len(buffer_size: &BufferSize, block_size: usize, channels: usize) -> usize {
+    let device_frames = match buffer_size {
+        BufferSize::Fixed(frames) => *frames as usize,
+        BufferSize::Default => 0,
+    };
+    block_size.max(device_frames).max(1) * channels.max(1) * 8
+}
+
+/// A thread with a chosen stack size (`AudioConfig::audio_thread_stack_size`) that runs a
+/// stream's data callback in place of CPAL's callback thread, trading samples with the device
+/// callback through an rtrb ring. The device callback wakes it after every buffer; it runs
+/// `step` until that has nothing left to do, then sleeps until woken again or dropped.
+struct RenderThread {
+    stop: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl RenderThread {
+    /// Spawn the thread with `stack_size` bytes of stack.
+    fn spawn(stack_size: usize, mut step: impl FnMut() -> bool + Send + 'static) -> Result<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let thread = thread::Builder::new()
+            .name("vst3-host audio render".into())
+            .stack_size(stack_size)
+            .spawn(move || {
+                while !stopped.load(Ordering::Acquire) {
+                    if !step() {
+                        thread::park();
+                    }
+                }
+            })
+            .map_err(|e| {
+                Error::AudioBackendError(format!("Failed to spawn audio render thread: {}", e))
+            })?;
+        Ok(Self {
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Render output with `render` on a new thread, `block` samples at a time, keeping about
+    /// two device buffers queued in a ring of `ring_len` samples. Returns the thread and the
+    /// device callback, which plays from the ring and fills with silence whatever the thread
+    /// hasn't rendered in time.
+    fn output(
+        stack_size: usize,
+        ring_len: usize,
+        block: usize,
+        mut render: OutputCallback,
+    ) -> Result<(Self, OutputCallback)> {
+        let (mut producer, mut consumer) = RingBuffer::<f32>::new(ring_len.max(block * 2));
+        let capacity = producer.buffer().capacity();
+        // The most the device has asked for in one callback.
+        let wanted = Arc::new(AtomicUsize::new(block));
+        let mut buffer = vec![0.0; block];
+        let thread = {
+            let wanted = Arc::clone(&wanted);
+            Self::spawn(stack_size, move || {
+                let queued = capacity - producer.slots();
+                if queued >= 2 * wanted.load(Ordering::Relaxed).max(block) {
+                    return false;
+                }
+                let Ok(chunk) = producer.write_chunk_uninit(block) else {
+                    return false;
+                };
+                render(&mut buffer);
+                chunk.fill_from_iter(buffer.iter().copied());
+                true
+            })?
+        };
+        let waker = thread.waker();
+        let callback = move |data: &mut [f32]| {
+            wanted.fetch_max(data.len(), Ordering::Relaxed);
+            let n = consumer.slots().min(data.len());
+            if let Ok(chunk) = consumer.read_chunk(n) {
+                for (out, sample) in data.iter_mut().zip(chunk) {
+                    *out = sample;
+                }
+            }
+            data[n..].fill(0.0);
+            waker.unpark();
+        };
+        Ok((thread, Box::new(callback)))
+    }
+
+    /// Consume input with `consume` on a new thread, through a ring of `ring_len` samples.
+    /// Returns the thread and the device callback, which drops a buffer that doesn't fit
+    /// (the thread fell behind) rather than block.
+    fn input(
+        stack_size: usize,
+        ring_len: usize,
+        mut consume: InputCallback,
+    ) -> Result<(Self, InputCallback)> {
+        let (mut producer, mut consumer) = RingBuffer::<f32>::new(ring_len);
+        let mut buffer = Vec::with_capacity(ring_len);
+        let thread = Self::spawn(stack_size, move || {
+            let Ok(chunk) = consumer.read_chunk(consumer.slots()) else {
+                return false;
+            };
+            if chunk.is_empty() {
+                return false;
+            }
+            buffer.clear();
+            buffer.extend(chunk);
+            consume(&buffer);
+            true
+        })?;
+        let waker = thread.waker();
+        let callback = move |data: &[f32]| {
+            if let Ok(chunk) = producer.write_chunk_uninit(data.len()) {
+                chunk.fill_from_iter(data.iter().copied());
+            }
+            waker.unpark();
+        };
+        Ok((thread, Box::new(callback)))
+    }
+
+    /// A handle the device callback wakes the thread with.
+    fn waker(&self) -> thread::Thread {
+        self.thread
+            .as_ref()
+            .map(|thread| thread.thread().clone())
+            .expect("render thread is joined only on drop")
+    }
+}
+
+impl Drop for RenderThread {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The OS default input device and its name.
+fn default_input(host: &cpal::Host) -> Option<(Device, String)> {
+    host.default_input_device().map(|device| {
+        let name = device.to_string();
+        (device, name)
+    })
+}
+
+/// Whether `device` advertises capture at `sample_rate` (in any channel layout).
+fn supports_input_rate(device: &Device, sample_rate: u32) -> bool {
+    device.supported_input_configs().is_ok_and(|mut ranges| {
+        ranges.any(|r| (r.min_sample_rate()..=r.max_sample_rate()).contains(&sample_rate))
+    })
+}
+
+/// Sample rates checked against a device's advertised ranges for [`AudioDeviceInfo`].
+const COMMON_SAMPLE_RATES: [u32; 6] = [44_100, 48_000, 88_200, 96_000, 176_400, 192_000];
+
+/// Summarize an output device's advertised config ranges.
+fn output_device_info(device: &Device) -> AudioDeviceInfo {
+    let ranges: Vec<_> = device
+        .supported_output_configs()
+        .map(|ranges| ranges.collect())
+        .unwrap_or_default();
+    let sample_rates = COMMON_SAMPLE_RATES
+        .iter()
+        .filter(|&&rate| {
+            ranges
+                .iter()
+                .any(|r| (r.min_sample_rate()..=r.max_sample_rate()).contains(&rate))
+        })
+        .map(|&rate| rate as f64)
+        .collect();
+    let buffer_range = ranges
+        .iter()
+        .filter_map(|r| match r.buffer_size() {
+            SupportedBufferSize::Range { min, max } => Some((*min, *max)),
+            SupportedBufferSize::Unknown => None,
+        })
+        .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)));
+    let (min_buffer_size, max_buffer_size) = buffer_range.unwrap_or((0, 0));
+    AudioDeviceInfo {
+        name: device.to_string(),
+        max_channels: ranges
+            .iter()
+            .map(|r| r.channels() as u32)
+            .max()
+            .unwrap_or(0),
+        sample_rates,
+        min_buffer_size,
+        max_buffer_size,
     }
 }
 
 /// CPAL-based audio backend
 pub struct CpalBackend {
     host: cpal::Host,
+    /// Capture device for live input (and its name); `None` when input is off.
+    input: Option<(Device, String)>,
+    /// Playback device chosen with [`Self::with_output_device`]; the OS default when `None`.
+    output: Option<Device>,
 }
 
 impl CpalBackend {
-    /// Create a new CPAL backend
+    /// Create a new CPAL backend. Live input records from the OS default input device.
     pub fn new() -> Result<Self> {
+        let host = cpal::default_host();
+        let input = default_input(&host);
         Ok(Self {
-            host: cpal::default_host(),
+            host,
+            input,
+            output: None,
         })
     }
 
+    /// Play through `device` instead of the OS default output.
+    pub fn with_output_device(mut self, device: Device) -> Self {
+        self.output = Some(device);
+        self
+    }
+
+    /// The output device named `name` (as listed by [`Self::list_output_devices`])
+    pub fn find_output_device(&self, name: &str) -> Option<Device> {
+        self.host
+            .output_devices()
+            .ok()?
+            .find(|d| d.to_string() == name)
+    }
+
+    /// Capabilities of every available output device
+    pub fn output_device_info(&self) -> Result<Vec<AudioDeviceInfo>> {
+        Ok(self
+            .enumerate_output_devices()?
+            .iter()
+            .map(output_device_info)
+            .collect())
+    }
+
+    /// Capabilities of the device playback goes to (see [`Self::with_output_device`]).
+    pub fn current_output_device_info(&self) -> Result<AudioDeviceInfo> {
+        self.default_output_device()
+            .map(|device| output_device_info(&device))
+            .ok_or_else(|| Error::AudioBackendError("No output device available".to_string()))
+    }
+
+    /// Record live input from `device` instead of the OS default (e.g. one picked from
+    /// [`AudioBackend::enumerate_input_devices`] or [`Self::find_input_device`]).
+    pub fn with_input_device(mut self, device: Device) -> Self {
+        let name = device.to_string();
+        self.input = Some((device, name));
+        self
+    }
+
+    /// The sample format output streams are negotiated in. Streams are always built with
+    /// `f32` samples, matching the processing format; recordings pick their own format (see
+    /// [`crate::audio::AudioRecorder::open_with_format`]).
+    pub fn output_format(&self) -> crate::audio::SampleFormat {
+        crate::audio::SampleFormat::F32
+    }
+
+    /// Name of the device live input records from, if any
+    pub fn input_device_name(&self) -> Option<&str> {
+        self.input.as_ref().map(|(_, name)| name.as_str())
+    }
+
+    /// The input device named `name` (as listed by [`Self::list_input_devices`])
+    pub fn find_input_device(&self, name: &str) -> Option<Device> {
+        self.host
+            .input_devices()
+            .ok()?
+            .find(|d| d.to_string() == name)
+    }
+
     /// List all available output devices
     pub fn list_output_devices(&self) -> Result<Vec<String>> {
         let devices: Vec<String> = self
@@ -158,6 +430,34 @@ impl CpalBackend {
     }
 }
 
+/// Which way a stream moves audio.
+#[derive(Clone, Copy)]
+enum Direction {
+    Input,
+    Output,
+}
+
+/// What a cpal stream error means for the stream: a lost device, an xrun (an underrun on
+/// output, an overrun on input), or something else. cpal doesn't say how many frames an xrun
+/// lost, so it counts as one buffer of `block_size` frames.
+fn stream_error(err: &cpal::Error, block_size: usize, direction: Direction) -> StreamError {
+    match (err.kind(), direction) {
+        (
+            ErrorKind::DeviceNotAvailable
+            | ErrorKind::StreamInvalidated
+            | ErrorKind::HostUnavailable,
+            _,
+        ) => StreamError::DeviceDisconnected,
+        (ErrorKind::Xrun, Direction::Output) => StreamError::Dropout {
+            missed_samples: block_size as u64,
+        },
+        (ErrorKind::Xrun, Direction::Input) => StreamError::Overrun {
+            frames: block_size as u64,
+        },
+        _ => StreamError::Unknown(format!("Stream error: {err}")),
+    }
+}
+
 impl AudioBackend for CpalBackend {
     type Stream = CpalStream;
     type Device = Device;
@@ -185,19 +485,25 @@ impl AudioBackend for CpalBackend {
         Ok(devices)
     }
 
+    /// The device playback goes to: the OS default unless changed with
+    /// [`CpalBackend::with_output_device`].
     fn default_output_device(&self) -> Option<Self::Device> {
-        self.host.default_output_device()
+        self.output
+            .clone()
+            .or_else(|| self.host.default_output_device())
     }
 
+    /// The capture device live input records from: the OS default unless changed with
+    /// [`CpalBackend::with_input_device`] or [`AudioBackend::set_input_source`].
     fn default_input_device(&self) -> Option<Self::Device> {
-        self.host.default_input_device()
+        self.input.as_ref().map(|(device, _)| device.clone())
     }
 
     fn create_output_stream(
         &self,
         device: &Self::Device,
         config: AudioConfig,
-        mut data_callback: Box<dyn FnMut(&mut [f32]) + Send>,
+        data_callback: Box<dyn FnMut(&mut [f32]) + Send>,
         mut error_callback: Box<dyn FnMut(Self::Error) + Send>,
     ) -> Result<Self::Stream> {
         let stream_config = StreamConfig {
@@ -206,6 +512,19 @@ impl AudioBackend for CpalBackend {
             buffer_size: resolve_output_buffer_size(device, &config),
         };
 
+        let (render, mut data_callback) = match config.audio_thread_stack_size {
+            Some(stack_size) => {
+                let channels = config.output_channels.max(1);
+                let ring_len =
+                    render_ring_len(&stream_config.buffer_size, config.block_size, channels);
+                let block = config.block_size.max(1) * channels;
+                let (thread, callback) =
+                    RenderThread::output(stack_size, ring_len, block, data_callback)?;
+                (Some(thread), callback)
+            }
+            None => (None, data_callback),
+        };
+
         let stream = device
             .build_output_stream(
                 stream_config,
@@ -213,7 +532,11 @@ impl AudioBackend for CpalBackend {
                     data_callback(data);
                 },
                 move |err| {
-                    error_callback(Error::AudioBackendError(format!("Stream error: {}", err)));
+                    error_callback(Error::AudioStream(stream_error(
+                        &err,
+                        config.block_size,
+                        Direction::Output,
+                    )));
                 },
                 None,
             )
@@ -223,6 +546,7 @@ impl AudioBackend for CpalBackend {
 
         Ok(CpalStream {
             stream: Some(stream),
+            render,
         })
     }
 
@@ -230,7 +554,7 @@ impl AudioBackend for CpalBackend {
         &self,
         device: &Self::Device,
         config: AudioConfig,
-        mut data_callback: Box<dyn FnMut(&[f32]) + Send>,
+        data_callback: Box<dyn FnMut(&[f32]) + Send>,
         mut error_callback: Box<dyn FnMut(Self::Error) + Send>,
     ) -> Result<Self::Stream> {
         let stream_config = StreamConfig {
@@ -239,6 +563,19 @@ impl AudioBackend for CpalBackend {
             buffer_size: resolve_input_buffer_size(device, &config),
         };
 
+        let (render, mut data_callback) = match config.audio_thread_stack_size {
+            Some(stack_size) => {
+                let ring_len = render_ring_len(
+                    &stream_config.buffer_size,
+                    config.block_size,
+                    config.input_channels,
+                );
+                let (thread, callback) = RenderThread::input(stack_size, ring_len, data_callback)?;
+                (Some(thread), callback)
+            }
+            None => (None, data_callback),
+        };
+
         let stream = device
             .build_input_stream(
                 stream_config,
@@ -246,7 +583,11 @@ impl AudioBackend for CpalBackend {
                     data_callback(data);
                 },
                 move |err| {
-                    error_callback(Error::AudioBackendError(format!("Stream error: {}", err)));
+                    error_callback(Error::AudioStream(stream_error(
+                        &err,
+                        config.block_size,
+                        Direction::Input,
+                    )));
                 },
                 None,
             )
@@ -256,8 +597,26 @@ impl AudioBackend for CpalBackend {
 
         Ok(CpalStream {
             stream: Some(stream),
+            render,
         })
     }
+
+    fn set_input_source(&mut self, source: CaptureSource) {
+        self.input = match source {
+            CaptureSource::Default => default_input(&self.host),
+            CaptureSource::None => None,
+        };
+    }
+
+    fn input_sample_rate(&self, device: &Self::Device, requested: f64) -> f64 {
+        // Many capture devices only run at their native rate (44.1 kHz USB mics, …).
+        if supports_input_rate(device, requested as u32) {
+            return requested;
+        }
+        device
+            .default_input_config()
+            .map_or(requested, |c| c.sample_rate() as f64)
+    }
 }
 
 impl Default for CpalBackend {
@@ -291,6 +650,130 @@ mod tests {
         )
     }
 
+    /// Wait up to a second for `done`, polling every millisecond.
+    fn eventually(mut done: impl FnMut() -> bool) -> bool {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(1);
+        while std::time::Instant::now() < deadline {
+            if done() {
+                return true;
+            }
+            thread::sleep(std::time::Duration::from_millis(1));
+        }
+        false
+    }
+
+    /// Use 3 MB of stack, more than a thread gets by default.
+    fn deep_stack() {
+        let mut frame = [0u8; 3 * 1024 * 1024];
+        std::hint::black_box(&mut frame);
+    }
+
+    #[test]
+    fn output_renders_on_a_4_mb_render_thread() {
+        let threads = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = Arc::clone(&threads);
+        let (_thread, mut device) = RenderThread::output(
+            4 * 1024 * 1024,
+            64,
+            8,
+            Box::new(move |data: &mut [f32]| {
+                deep_stack();
+                let name = thread::current().name().unwrap_or_default().to_string();
+                seen.lock().unwrap().push(name);
+                data.fill(0.5);
+            }),
+        )
+        .unwrap();
+
+        let mut data = [1.0; 8];
+        assert!(eventually(|| {
+            device(&mut data);
+            data == [0.5; 8]
+        }));
+        assert!(threads
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|name| name == "vst3-host audio render"));
+    }
+
+    #[test]
+    fn output_underruns_play_silence() {
+        // The render callback blocks until `release` is dropped, so nothing is ever queued.
+        let (release, blocked) = std::sync::mpsc::channel::<()>();
+        let (_thread, mut device) = RenderThread::output(
+            4 * 1024 * 1024,
+            64,
+            8,
+            Box::new(move |_: &mut [f32]| {
+                let _ = blocked.recv();
+            }),
+        )
+        .unwrap();
+        let mut data = [1.0; 8];
+        device(&mut data);
+        assert_eq!(data, [0.0; 8]);
+        drop(release);
+    }
+
+    #[test]
+    fn input_is_consumed_on_a_4_mb_render_thread() {
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        let (_thread, mut device) = RenderThread::input(
+            4 * 1024 * 1024,
+            64,
+            Box::new(move |data: &[f32]| {
+                deep_stack();
+                assert_eq!(thread::current().name(), Some("vst3-host audio render"));
+                sink.lock().unwrap().extend_from_slice(data);
+            }),
+        )
+        .unwrap();
+
+        device(&[0.25; 8]);
+        device(&[0.75; 8]);
+        assert!(eventually(|| received.lock().unwrap().len() == 16));
+        assert_eq!(received.lock().unwrap()[..8], [0.25; 8]);
+        assert_eq!(received.lock().unwrap()[8..], [0.75; 8]);
+    }
+
+    #[test]
+    fn render_ring_holds_eight_of_the_larger_buffer() {
+        assert_eq!(render_ring_len(&BufferSize::Default, 512, 2), 8 * 512 * 2);
+        assert_eq!(
+            render_ring_len(&BufferSize::Fixed(1024), 512, 2),
+            8 * 1024 * 2
+        );
+    }
+
+    #[test]
+    fn cpal_errors_map_to_stream_errors() {
+        let error = |kind| cpal::Error::new(kind);
+        assert_eq!(
+            stream_error(
+                &error(ErrorKind::DeviceNotAvailable),
+                512,
+                Direction::Output
+            ),
+            StreamError::DeviceDisconnected
+        );
+        assert_eq!(
+            stream_error(&error(ErrorKind::Xrun), 512, Direction::Output),
+            StreamError::Dropout {
+                missed_samples: 512
+            }
+        );
+        assert_eq!(
+            stream_error(&error(ErrorKind::Xrun), 256, Direction::Input),
+            StreamError::Overrun { frames: 256 }
+        );
+        assert!(matches!(
+            stream_error(&error(ErrorKind::DeviceBusy), 512, Direction::Output),
+            StreamError::Unknown(_)
+        ));
+    }
+
     #[test]
     fn resolve_exact_channel_and_sr_match_clamps() {
         let ranges = vec![range(2, 44_100, 48_000, 64, 2048)];
@@ -1,5 +1,11 @@
 //! Audio backend implementations
 
+pub mod aggregate;
+
+pub use aggregate::{
+    AggregateBackend, AggregateDevice, AggregateStream, BackendHandle, DynAudioBackend,
+};
+
 #[cfg(feature = "cpal-backend")]
 pub mod cpal_backend;
 
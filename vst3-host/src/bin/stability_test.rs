@@ -0,0 +1,212 @@
+//! Soak-test a VST3 plugin for CI: process in real time for a while and fail on crashes or
+//! NaN output.
+//!
+//! ```text
+//! stability_test <path> [--duration-minutes <n>] [--report-every-s <n>] [--max-memory-growth-mb <n>]
+//! ```
+//!
+//! The plugin processes silence at the host's default sample rate and block size, paced like
+//! an audio device, with a Note On every two seconds. A progress line is printed every
+//! reporting interval (10 s by default) and the full report at the end. Exit codes: `0`
+//! passed, `1` NaN/Inf output, memory grew past the limit (64 MiB by default) or another
+//! error, `2` the plugin crashed.
+//!
+//! As with `vst3-inspect`, the test runs in a child copy of this binary so a plugin that
+//! takes the process down is still reported as exit code `2`.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use vst3_host::{StabilityReport, Vst3Host};
+
+/// Set in the child process that actually runs the test.
+const CHILD_ENV: &str = "VST3_STABILITY_TEST_CHILD";
+
+const USAGE: &str = "usage:
+  stability_test <path> [--duration-minutes <n>] [--report-every-s <n>] [--max-memory-growth-mb <n>]";
+
+/// A parsed command line.
+#[derive(Debug, PartialEq)]
+struct Options {
+    path: PathBuf,
+    duration: Duration,
+    report_every: Duration,
+    max_memory_growth_mb: f64,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let (path, rest) = args.split_first().ok_or("missing plugin path")?;
+    let mut options = Options {
+        path: PathBuf::from(path),
+        duration: Duration::from_secs(60),
+        report_every: Duration::from_secs(10),
+        max_memory_growth_mb: 64.0,
+    };
+    let mut rest = rest.iter();
+    while let Some(option) = rest.next() {
+        let value: f64 = rest
+            .next()
+            .ok_or_else(|| format!("{option} needs a value"))?
+            .parse()
+            .map_err(|e| format!("{option}: {e}"))?;
+        if value.is_nan() || value < 0.0 {
+            return Err(format!("{option} must be non-negative"));
+        }
+        match option.as_str() {
+            "--duration-minutes" => options.duration = Duration::from_secs_f64(value * 60.0),
+            "--report-every-s" => options.report_every = Duration::from_secs_f64(value),
+            "--max-memory-growth-mb" => options.max_memory_growth_mb = value,
+            other => return Err(format!("unknown option '{other}'")),
+        }
+    }
+    Ok(options)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if std::env::var_os(CHILD_ENV).is_some() {
+        return run(&options);
+    }
+    supervise(&args)
+}
+
+/// Run the test in a child copy of this binary and map a crash to exit code 2.
+fn supervise(args: &[String]) -> ExitCode {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("error: locate stability_test: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match std::process::Command::new(exe)
+        .args(args)
+        .env(CHILD_ENV, "1")
+        .status()
+    {
+        Ok(status) if crashed(&status) => {
+            eprintln!("FAIL: plugin crashed ({status})");
+            ExitCode::from(2)
+        }
+        Ok(status) => match status.code() {
+            Some(0) => ExitCode::SUCCESS,
+            Some(2) => ExitCode::from(2),
+            _ => ExitCode::FAILURE,
+        },
+        Err(e) => {
+            eprintln!("error: run stability_test: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Whether the child died abnormally rather than exiting: killed by a signal on Unix, or with
+/// an NTSTATUS error code on Windows.
+fn crashed(status: &std::process::ExitStatus) -> bool {
+    match status.code() {
+        None => true,
+        Some(code) => cfg!(windows) && code < 0,
+    }
+}
+
+fn run(options: &Options) -> ExitCode {
+    let mut host = match Vst3Host::new() {
+        Ok(host) => host,
+        Err(e) => {
+            eprintln!("error: build host: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!(
+        "Soak-testing {} for {:.1} min",
+        options.path.display(),
+        options.duration.as_secs_f64() / 60.0
+    );
+    let report = match host.run_stability_test(
+        &options.path,
+        options.duration,
+        options.report_every,
+        |progress| println!("{}", progress_line(progress)),
+    ) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: load {}: {e}", options.path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("\n{report}");
+
+    let max_growth = (options.max_memory_growth_mb * 1024.0 * 1024.0) as i64;
+    if !report.crash_events.is_empty() {
+        println!("FAIL: plugin crashed");
+        ExitCode::from(2)
+    } else if !report.passed() {
+        println!("FAIL: plugin produced NaN/Inf output");
+        ExitCode::FAILURE
+    } else if report.memory_growth_bytes.is_some_and(|b| b > max_growth) {
+        println!(
+            "FAIL: memory grew by more than {} MiB",
+            options.max_memory_growth_mb
+        );
+        ExitCode::FAILURE
+    } else {
+        println!("PASS");
+        ExitCode::SUCCESS
+    }
+}
+
+fn progress_line(report: &StabilityReport) -> String {
+    format!(
+        "[{:>6.0} s] {} blocks, {} NaN, {} dropouts, CPU {:.1}% avg / {:.1}% peak",
+        report.duration.as_secs_f64(),
+        report.blocks_processed,
+        report.nan_events,
+        report.dropout_events,
+        report.average_cpu_percent,
+        report.peak_cpu_percent
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn options_parse() {
+        assert_eq!(
+            parse_args(&args(
+                "/p/S.vst3 --duration-minutes 0.5 --report-every-s 5 --max-memory-growth-mb 8"
+            )),
+            Ok(Options {
+                path: PathBuf::from("/p/S.vst3"),
+                duration: Duration::from_secs(30),
+                report_every: Duration::from_secs(5),
+                max_memory_growth_mb: 8.0,
+            })
+        );
+        assert_eq!(
+            parse_args(&args("/p/S.vst3")).map(|o| o.duration),
+            Ok(Duration::from_secs(60))
+        );
+    }
+
+    #[test]
+    fn bad_usage_is_rejected() {
+        assert!(parse_args(&[]).is_err());
+        assert!(parse_args(&args("x --duration-minutes")).is_err());
+        assert!(parse_args(&args("x --duration-minutes -1")).is_err());
+        assert!(parse_args(&args("x --frobnicate 1")).is_err());
+    }
+}
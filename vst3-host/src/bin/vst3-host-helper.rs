@@ -16,15 +16,28 @@
 //! processing moves to a worker thread; the plugin is shared behind an
 //! `Arc<Mutex<Option<Plugin>>>`. `CreateGui`/`CloseGui` are forwarded from the worker to
 //! the main thread (which owns the `NSWindow`) over a channel. Audio/control commands run
-//! exactly as before, just on the worker thread. On other platforms the helper stays a
-//! single-threaded stdin loop and GUI is not yet supported.
+//! exactly as before, just on the worker thread.
+//!
+//! ## Threading (Windows, Linux)
+//!
+//! Commands run on the main thread. The editor is embedded into the host's own window
+//! (`OpenGui`, see [`GuiBridge`]) rather than a helper-owned one. Its windows belong to the
+//! thread that attached it, so on Windows stdin is read on a worker thread and the main
+//! thread pumps Win32 messages between commands.
+//!
+//! ## Crash reports
+//!
+//! When the host names a file in `VST3_HOST_CRASH_REPORT`, a fatal `SIGSEGV`/`SIGABRT`/`SIGBUS`
+//! (an unhandled exception on Windows) writes a [`CrashReport`] there before the helper dies:
+//! the plugin path, the last command, the signal and a backtrace. The host reads it once the
+//! helper's stdout closes.
 
 use std::io::{self, BufRead, Write};
 use std::sync::{Arc, Mutex};
 
 use vst3_host::{
     audio::AudioBuffers,
-    process_isolation::{HostCommand, HostResponse},
+    process_isolation::{CrashReport, GuiBridge, HostCommand, HostResponse},
     Plugin, Vst3Host,
 };
 
@@ -33,6 +46,7 @@ type SharedPlugin = Arc<Mutex<Option<Plugin>>>;
 
 fn main() {
     eprintln!("VST3 Host Helper Process Started");
+    crash::install();
 
     let plugin: SharedPlugin = Arc::new(Mutex::new(None));
 
@@ -43,11 +57,11 @@ fn main() {
 
     #[cfg(not(target_os = "macos"))]
     {
-        // No UI run loop needed: process commands on this (main) thread directly.
-        let stdin = io::stdin();
-        let mut stdout = io::stdout();
+        // Process commands on this (main) thread, which also owns an embedded editor.
+        let lines = stdin_lines();
+        let mut stdout = protocol_writer();
         let mut sample_rate = 44100.0;
-        for line in stdin.lock().lines() {
+        while let Some(line) = next_line(&lines) {
             let Some(command) = parse_line(line, &mut stdout) else {
                 continue;
             };
@@ -61,8 +75,76 @@ fn main() {
     }
 }
 
+/// Read stdin lines on a worker thread, so the main thread is free to pump window messages.
+#[cfg(not(target_os = "macos"))]
+fn stdin_lines() -> std::sync::mpsc::Receiver<io::Result<String>> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// The next stdin line, or `None` once stdin closes. On Windows this keeps dispatching the
+/// embedded editor's window messages while it waits.
+#[cfg(not(target_os = "macos"))]
+fn next_line(lines: &std::sync::mpsc::Receiver<io::Result<String>>) -> Option<io::Result<String>> {
+    #[cfg(target_os = "windows")]
+    loop {
+        pump_window_messages();
+        match lines.recv_timeout(std::time::Duration::from_millis(10)) {
+            Ok(line) => return Some(line),
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return None,
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    lines.recv().ok()
+}
+
+/// Dispatch every queued Win32 message for this thread's windows.
+#[cfg(target_os = "windows")]
+fn pump_window_messages() {
+    use winapi::um::winuser::{DispatchMessageW, PeekMessageW, TranslateMessage, MSG, PM_REMOVE};
+    // SAFETY: a standard message pump on the thread that owns the editor's windows; `msg`
+    // is a valid out-parameter for each call.
+    unsafe {
+        let mut msg: MSG = std::mem::zeroed();
+        while PeekMessageW(&mut msg, std::ptr::null_mut(), 0, 0, PM_REMOVE) != 0 {
+            TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+}
+
+/// The writer responses go to. On Unix the protocol moves to a private duplicate of stdout
+/// and stdout itself is pointed at stderr, so a plugin that prints (`printf`, `std::cout`)
+/// ends up in the host's per-plugin log instead of corrupting the JSON response stream.
+fn protocol_writer() -> Box<dyn Write + Send> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::io::FromRawFd;
+        // SAFETY: plain fd juggling on the process's own standard descriptors; the
+        // duplicate is owned by the returned `File` and closed on every failure path.
+        unsafe {
+            let protocol = libc::dup(libc::STDOUT_FILENO);
+            if protocol >= 0 {
+                if libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) >= 0 {
+                    return Box::new(io::BufWriter::new(std::fs::File::from_raw_fd(protocol)));
+                }
+                libc::close(protocol);
+            }
+        }
+    }
+    Box::new(io::stdout())
+}
+
 /// Parse one stdin line into a command, reporting (and skipping) blank/invalid lines.
-fn parse_line(line: io::Result<String>, stdout: &mut io::Stdout) -> Option<HostCommand> {
+fn parse_line(line: io::Result<String>, stdout: &mut dyn Write) -> Option<HostCommand> {
     let line = match line {
         Ok(l) => l,
         Err(e) => {
@@ -73,6 +155,7 @@ fn parse_line(line: io::Result<String>, stdout: &mut io::Stdout) -> Option<HostC
     if line.trim().is_empty() {
         return None;
     }
+    crash::record_command(&line);
     match serde_json::from_str(&line) {
         Ok(cmd) => Some(cmd),
         Err(e) => {
@@ -87,7 +170,7 @@ fn parse_line(line: io::Result<String>, stdout: &mut io::Stdout) -> Option<HostC
     }
 }
 
-fn respond(stdout: &mut io::Stdout, response: &HostResponse) {
+fn respond(stdout: &mut dyn Write, response: &HostResponse) {
     if let Ok(json) = serde_json::to_string(response) {
         let _ = writeln!(stdout, "{}", json);
         let _ = stdout.flush();
@@ -141,13 +224,16 @@ fn handle(
             tempo,
             time_sig_numerator,
             time_sig_denominator,
+            sample_size,
         } => {
             *sample_rate = sr;
+            crash::record_plugin(&path);
             let mut host = match Vst3Host::builder()
                 .sample_rate(sr)
                 .block_size(block_size as usize)
                 .tempo(tempo)
                 .time_signature(time_sig_numerator, time_sig_denominator)
+                .with_sample_size(sample_size)
                 .build()
             {
                 Ok(h) => h,
@@ -159,10 +245,10 @@ fn handle(
                     let output_channels = p.output_channel_count() as i32;
                     *plugin.lock().unwrap() = Some(p);
                     HostResponse::PluginInfo {
+                        category: info.category(),
                         vendor: info.vendor,
                         name: info.name,
                         version: info.version,
-                        category: info.category,
                         uid: info.uid,
                         has_gui: info.has_gui,
                         audio_inputs: info.audio_inputs as i32,
@@ -252,6 +338,14 @@ fn handle(
             },
             Err(e) => err("SetPlaying", e),
         }),
+        HostCommand::SetTransportPosition { samples } => {
+            with(plugin, |p| match p.set_transport_position(samples) {
+                Ok(()) => HostResponse::Success {
+                    message: "transport position set".to_string(),
+                },
+                Err(e) => err("SetTransportPosition", e),
+            })
+        }
         HostCommand::GetParameter { id } => with(plugin, |p| match p.get_parameter(id) {
             Ok(value) => HostResponse::ParameterValue { value },
             Err(e) => err("GetParameter", e),
@@ -266,6 +360,24 @@ fn handle(
                 Err(e) => err("FormatParameter", e),
             })
         }
+        HostCommand::ParseParameter { id, text } => {
+            with(plugin, |p| match p.parse_parameter(id, &text) {
+                Ok(value) => HostResponse::ParameterValue { value },
+                Err(e) => err("ParseParameter", e),
+            })
+        }
+        HostCommand::NormalizedToPlain { id, normalized } => {
+            with(plugin, |p| match p.normalized_to_plain(id, normalized) {
+                Ok(value) => HostResponse::ParameterValue { value },
+                Err(e) => err("NormalizedToPlain", e),
+            })
+        }
+        HostCommand::PlainToNormalized { id, plain } => {
+            with(plugin, |p| match p.plain_to_normalized(id, plain) {
+                Ok(value) => HostResponse::ParameterValue { value },
+                Err(e) => err("PlainToNormalized", e),
+            })
+        }
         HostCommand::SendMidi { event } => with(plugin, |p| match p.send_midi_event(event) {
             Ok(()) => HostResponse::Success {
                 message: "midi sent".to_string(),
@@ -283,6 +395,12 @@ fn handle(
                 Err(e) => err("SendMidiAt", e),
             }
         }),
+        HostCommand::SendSysEx { data } => with(plugin, |p| match p.send_sysex(&data) {
+            Ok(()) => HostResponse::Success {
+                message: "sysex sent".to_string(),
+            },
+            Err(e) => err("SendSysEx", e),
+        }),
         HostCommand::SetBusActive {
             media_type,
             direction,
@@ -312,6 +430,12 @@ fn handle(
             Ok(units) => HostResponse::Units { units },
             Err(e) => err("GetUnits", e),
         }),
+        HostCommand::GetPrograms { program_list_id } => {
+            with(plugin, |p| match p.get_programs(program_list_id) {
+                Ok(programs) => HostResponse::Programs { programs },
+                Err(e) => err("GetPrograms", e),
+            })
+        }
         HostCommand::LatencySamples => with(plugin, |p| HostResponse::LatencySamples {
             samples: p.latency_samples(),
         }),
@@ -323,7 +447,11 @@ fn handle(
                 id: p.midi_cc_to_parameter(bus, channel, cc),
             })
         }
-        HostCommand::Process { inputs, frames } => {
+        HostCommand::Process {
+            inputs,
+            sidechain,
+            frames,
+        } => {
             let sr = *sample_rate;
             with(plugin, |p| {
                 // Live channel count (sums getBusInfo across output buses), so a negotiated
@@ -331,6 +459,7 @@ fn handle(
                 let out_channels = p.output_channel_count().max(1);
                 let mut buffers = AudioBuffers {
                     inputs,
+                    sidechain_inputs: sidechain,
                     outputs: vec![vec![0.0; frames as usize]; out_channels],
                     sample_rate: sr,
                     block_size: frames as usize,
@@ -421,13 +550,49 @@ fn handle(
             edits: p.take_parameter_edits(),
         }),
         HostCommand::CreateGui => gui_request(gui, true),
+        #[cfg(target_os = "macos")]
         HostCommand::CloseGui => gui_request(gui, false),
+        // Off macOS the only editor is an embedded one, attached on this thread.
+        #[cfg(not(target_os = "macos"))]
+        HostCommand::CloseGui => with(plugin, |p| match p.close_editor() {
+            Ok(()) => HostResponse::Success {
+                message: "editor closed".to_string(),
+            },
+            Err(e) => err("CloseGui", e),
+        }),
+        HostCommand::OpenGui { parent_handle } => {
+            with(plugin, |p| open_embedded_editor(p, parent_handle))
+        }
         HostCommand::Shutdown => HostResponse::Success {
             message: "shutting down".to_string(),
         },
     }
 }
 
+/// Attach the editor into the host's window `parent_handle` ([`GuiBridge::Embedded`]).
+fn open_embedded_editor(p: &mut Plugin, parent_handle: usize) -> HostResponse {
+    // SAFETY: the host sends the handle of its own live window, of the bridge's platform type
+    // (it only sends `OpenGui` when `GuiBridge::can_embed` accepted its window).
+    let Some(parent) = (unsafe { GuiBridge::current().parent_window(parent_handle) }) else {
+        return HostResponse::Error {
+            message: "Embedding an editor across processes is not supported on this platform"
+                .to_string(),
+        };
+    };
+    if !p.has_editor() {
+        return HostResponse::Error {
+            message: "Plugin does not have a GUI editor".to_string(),
+        };
+    }
+    match p.open_editor(parent) {
+        Ok(()) => {
+            let (width, height) = p.get_editor_size().unwrap_or((800, 600));
+            HostResponse::GuiOpened { width, height }
+        }
+        Err(e) => err("OpenGui", e),
+    }
+}
+
 /// The worker's handle to the main thread's GUI loop (macOS only).
 #[cfg(target_os = "macos")]
 struct GuiChannel(std::sync::mpsc::Sender<GuiRequest>);
@@ -469,6 +634,126 @@ fn gui_request(gui: Option<&GuiChannel>, open: bool) -> HostResponse {
     }
 }
 
+/// The fatal-signal (Windows: unhandled-exception) hook that leaves a [`CrashReport`] for the
+/// host. Capturing a backtrace and writing a file aren't async-signal-safe, so the report is
+/// best-effort: the process is going down either way.
+mod crash {
+    use super::CrashReport;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Mutex, OnceLock};
+
+    /// What the helper is doing, kept current for the handler.
+    struct Context {
+        plugin_path: String,
+        last_command: String,
+    }
+
+    static CONTEXT: Mutex<Context> = Mutex::new(Context {
+        plugin_path: String::new(),
+        last_command: String::new(),
+    });
+    static REPORT_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+    /// Hook fatal signals if the host asked for a crash report.
+    pub fn install() {
+        let Some(path) = std::env::var_os(vst3_host::process_isolation::CRASH_REPORT_ENV) else {
+            return;
+        };
+        let _ = REPORT_PATH.set(PathBuf::from(path));
+
+        #[cfg(unix)]
+        for signal in [libc::SIGSEGV, libc::SIGABRT, libc::SIGBUS] {
+            // SAFETY: the handler only touches state set up before it's registered; it isn't
+            // async-signal-safe, which is acceptable for a process that's about to die.
+            let registered = unsafe {
+                signal_hook_registry::register_unchecked(signal, move |_| on_signal(signal))
+            };
+            if let Err(e) = registered {
+                eprintln!("Failed to hook signal {signal} for crash reports: {e}");
+            }
+        }
+
+        #[cfg(windows)]
+        // SAFETY: installs a process-wide filter; `on_exception` has the required signature.
+        unsafe {
+            winapi::um::errhandlingapi::SetUnhandledExceptionFilter(Some(on_exception));
+        }
+    }
+
+    /// Remember the command on `line` (its JSON tag) as the last one received.
+    pub fn record_command(line: &str) {
+        if let Ok(mut context) = CONTEXT.lock() {
+            context.last_command = command_name(line).to_string();
+        }
+    }
+
+    /// Remember the plugin being loaded, before loading it in case that's what crashes.
+    pub fn record_plugin(path: &str) {
+        if let Ok(mut context) = CONTEXT.lock() {
+            context.plugin_path = path.to_string();
+        }
+    }
+
+    /// The variant name of a serialized [`super::HostCommand`]: `"Shutdown"` or
+    /// `{"LoadPlugin":{...}}`. Cheaper than keeping the whole command around.
+    fn command_name(line: &str) -> &str {
+        line.trim_start()
+            .trim_start_matches('{')
+            .trim_start()
+            .strip_prefix('"')
+            .and_then(|rest| rest.split('"').next())
+            .unwrap_or("unknown")
+    }
+
+    /// Write the report for `signal`, once, even if several threads crash together.
+    fn write_report(signal: i32) {
+        static WRITTEN: AtomicBool = AtomicBool::new(false);
+        if WRITTEN.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let Some(path) = REPORT_PATH.get() else {
+            return;
+        };
+        // `try_lock`: the crash may have happened while this thread held the lock.
+        let (plugin_path, last_command) = match CONTEXT.try_lock() {
+            Ok(context) => (context.plugin_path.clone(), context.last_command.clone()),
+            Err(_) => (String::new(), "unknown".to_string()),
+        };
+        let report = CrashReport {
+            plugin_path,
+            last_command,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            signal,
+            backtrace: format!("{:?}", backtrace::Backtrace::new()),
+        };
+        if let Ok(json) = serde_json::to_string(&report) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+
+    #[cfg(unix)]
+    fn on_signal(signal: i32) {
+        write_report(signal);
+        // Die the way the signal would have without us, so the host still sees it.
+        let _ = signal_hook::low_level::emulate_default_handler(signal);
+    }
+
+    #[cfg(windows)]
+    unsafe extern "system" fn on_exception(
+        info: *mut winapi::um::winnt::EXCEPTION_POINTERS,
+    ) -> i32 {
+        // SAFETY: Windows passes a valid EXCEPTION_POINTERS to the filter.
+        let code = unsafe { (*(*info).ExceptionRecord).ExceptionCode };
+        write_report(code as i32);
+        // EXCEPTION_CONTINUE_SEARCH: let the default handling terminate the process.
+        0
+    }
+}
+
 #[cfg(target_os = "macos")]
 mod macos {
     use super::*;
@@ -490,9 +775,9 @@ mod macos {
         // Worker: read stdin and process commands; GUI verbs are delegated to the main loop.
         {
             let plugin = plugin.clone();
+            let mut stdout = protocol_writer();
             std::thread::spawn(move || {
                 let stdin = io::stdin();
-                let mut stdout = io::stdout();
                 let mut sample_rate = 44100.0;
                 let gui = GuiChannel(gui_tx);
                 for line in stdin.lock().lines() {
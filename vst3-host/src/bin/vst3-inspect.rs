@@ -0,0 +1,337 @@
+//! Headless VST3 plugin inspection from the shell, for CI and scripting.
+//!
+//! ```text
+//! vst3-inspect info <path>
+//! vst3-inspect params <path>
+//! vst3-inspect preset-list <path>
+//! vst3-inspect set-param <path> <param_id> <value> [--output-wav <path>] [--duration-s <n>]
+//! vst3-inspect validate <path>
+//! ```
+//!
+//! Tables are printed as tab-separated values with a header row. Exit codes: `0` success,
+//! `1` error (including usage errors), `2` the plugin crashed.
+//!
+//! Plugins are loaded in-process, so a crashing plugin would take the CLI down with it. To
+//! still report the crash as exit code `2`, the command runs in a child copy of this binary
+//! and the parent maps the child dying from a signal (or, on Windows, an exception) to `2`.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use vst3_host::{AudioBuffers, Vst3Host};
+
+/// Set in the child process that actually runs the command.
+const CHILD_ENV: &str = "VST3_INSPECT_CHILD";
+
+const USAGE: &str = "usage:
+  vst3-inspect info <path>
+  vst3-inspect params <path>
+  vst3-inspect preset-list <path>
+  vst3-inspect set-param <path> <param_id> <value> [--output-wav <path>] [--duration-s <n>]
+  vst3-inspect validate <path>";
+
+/// A parsed command line.
+#[derive(Debug, PartialEq)]
+enum Command {
+    Info(PathBuf),
+    Params(PathBuf),
+    PresetList(PathBuf),
+    SetParam {
+        path: PathBuf,
+        param_id: u32,
+        value: f64,
+        output_wav: Option<PathBuf>,
+        duration_s: f64,
+    },
+    Validate(PathBuf),
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (command, rest) = args.split_first().ok_or("missing command")?;
+    let path = || {
+        rest.first()
+            .map(PathBuf::from)
+            .ok_or_else(|| format!("{command}: missing plugin path"))
+    };
+    let no_extra = |n: usize| match rest.get(n) {
+        Some(extra) => Err(format!("{command}: unexpected argument '{extra}'")),
+        None => Ok(()),
+    };
+    match command.as_str() {
+        "info" => no_extra(1).and(path().map(Command::Info)),
+        "params" => no_extra(1).and(path().map(Command::Params)),
+        "preset-list" => no_extra(1).and(path().map(Command::PresetList)),
+        "validate" => no_extra(1).and(path().map(Command::Validate)),
+        "set-param" => {
+            let path = path()?;
+            let param_id = rest
+                .get(1)
+                .ok_or("set-param: missing parameter id")?
+                .parse()
+                .map_err(|e| format!("set-param: bad parameter id: {e}"))?;
+            let value = rest
+                .get(2)
+                .ok_or("set-param: missing value")?
+                .parse()
+                .map_err(|e| format!("set-param: bad value: {e}"))?;
+            let mut output_wav = None;
+            let mut duration_s: f64 = 1.0;
+            let mut options = rest[3..].iter();
+            while let Some(option) = options.next() {
+                let mut operand = || {
+                    options
+                        .next()
+                        .ok_or_else(|| format!("set-param: {option} needs a value"))
+                };
+                match option.as_str() {
+                    "--output-wav" => output_wav = Some(PathBuf::from(operand()?)),
+                    "--duration-s" => {
+                        duration_s = operand()?
+                            .parse()
+                            .map_err(|e| format!("set-param: bad duration: {e}"))?;
+                    }
+                    other => return Err(format!("set-param: unknown option '{other}'")),
+                }
+            }
+            if duration_s.is_nan() || duration_s < 0.0 {
+                return Err("set-param: duration must be non-negative".to_string());
+            }
+            Ok(Command::SetParam {
+                path,
+                param_id,
+                value,
+                output_wav,
+                duration_s,
+            })
+        }
+        other => Err(format!("unknown command '{other}'")),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if std::env::var_os(CHILD_ENV).is_some() {
+        return run(&args);
+    }
+    // Reject bad usage here rather than spawning a child just to do it.
+    if let Err(e) = parse_args(&args) {
+        eprintln!("error: {e}\n\n{USAGE}");
+        return ExitCode::FAILURE;
+    }
+    supervise(&args)
+}
+
+/// Run the command in a child copy of this binary and map a crash to exit code 2.
+fn supervise(args: &[String]) -> ExitCode {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("error: locate vst3-inspect: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match std::process::Command::new(exe)
+        .args(args)
+        .env(CHILD_ENV, "1")
+        .status()
+    {
+        Ok(status) if status.success() => ExitCode::SUCCESS,
+        Ok(status) if crashed(&status) => {
+            eprintln!("error: plugin crashed ({status})");
+            ExitCode::from(2)
+        }
+        Ok(_) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: run vst3-inspect: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Whether the child died abnormally rather than exiting: killed by a signal on Unix, or with
+/// an NTSTATUS error code (e.g. `0xC0000005`, an access violation) on Windows.
+fn crashed(status: &std::process::ExitStatus) -> bool {
+    match status.code() {
+        None => true,
+        Some(code) => cfg!(windows) && code < 0,
+    }
+}
+
+fn run(args: &[String]) -> ExitCode {
+    let result = parse_args(args).and_then(|command| match command {
+        Command::Info(path) => info(&path),
+        Command::Params(path) => params(&path),
+        Command::PresetList(path) => preset_list(&path),
+        Command::SetParam {
+            path,
+            param_id,
+            value,
+            output_wav,
+            duration_s,
+        } => set_param(&path, param_id, value, output_wav.as_deref(), duration_s),
+        Command::Validate(path) => validate(&path),
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn load(path: &Path) -> Result<vst3_host::Plugin, String> {
+    let mut host = Vst3Host::new().map_err(|e| format!("build host: {e}"))?;
+    host.load_plugin(path)
+        .map_err(|e| format!("load {}: {e}", path.display()))
+}
+
+fn info(path: &Path) -> Result<(), String> {
+    let detail = vst3_host::get_detailed_plugin_info(path)
+        .map_err(|e| format!("inspect {}: {e}", path.display()))?;
+    println!("name\t{}", detail.info.name);
+    println!("vendor\t{}", detail.factory.vendor);
+    println!("version\t{}", detail.info.version);
+    for class in &detail.classes {
+        println!(
+            "class\t{}\t{}\t{}",
+            class.class_id, class.category, class.name
+        );
+    }
+    Ok(())
+}
+
+fn params(path: &Path) -> Result<(), String> {
+    let plugin = load(path)?;
+    let params = plugin.get_parameters().map_err(|e| e.to_string())?;
+    println!("id\tname\tdefault\tunits");
+    for p in params {
+        println!("{}\t{}\t{}\t{}", p.id, p.name, p.default, p.unit);
+    }
+    Ok(())
+}
+
+fn preset_list(path: &Path) -> Result<(), String> {
+    let plugin = load(path)?;
+    let units = plugin.get_units().map_err(|e| e.to_string())?;
+    println!("unit\tindex\tname");
+    for unit in units {
+        for (index, name) in unit.programs.iter().enumerate() {
+            println!("{}\t{index}\t{name}", unit.id);
+        }
+    }
+    Ok(())
+}
+
+fn set_param(
+    path: &Path,
+    param_id: u32,
+    value: f64,
+    output_wav: Option<&Path>,
+    duration_s: f64,
+) -> Result<(), String> {
+    let mut plugin = load(path)?;
+    plugin
+        .set_parameter(param_id, value)
+        .map_err(|e| format!("set parameter {param_id}: {e}"))?;
+    let channels = render(&mut plugin, duration_s).map_err(|e| format!("render: {e}"))?;
+    let peak = channels
+        .iter()
+        .flatten()
+        .map(|s| s.abs())
+        .fold(0.0_f32, f32::max);
+    println!(
+        "param\t{param_id}\t{}",
+        plugin.get_parameter(param_id).unwrap_or(value)
+    );
+    println!("peak\t{peak}");
+    if let Some(wav) = output_wav {
+        vst3_host::audio::write_wav(wav, &channels, plugin.sample_rate() as u32)
+            .map_err(|e| format!("write {}: {e}", wav.display()))?;
+        println!("wav\t{}", wav.display());
+    }
+    Ok(())
+}
+
+/// Render `duration_s` seconds of output (silent input, no MIDI), one block at a time.
+fn render(plugin: &mut vst3_host::Plugin, duration_s: f64) -> vst3_host::Result<Vec<Vec<f32>>> {
+    let sample_rate = plugin.sample_rate();
+    let block = plugin.block_size().max(1);
+    let out_channels = plugin.output_channel_count().max(1);
+    let total = (duration_s * sample_rate).round() as usize;
+    let mut channels = vec![Vec::with_capacity(total); out_channels];
+    plugin.start_processing()?;
+    let mut rendered = 0;
+    while rendered < total {
+        let frames = block.min(total - rendered);
+        let mut buffers = AudioBuffers::new(
+            plugin.info().audio_inputs as usize,
+            out_channels,
+            frames,
+            sample_rate,
+        );
+        plugin.process_audio(&mut buffers)?;
+        for (dst, src) in channels.iter_mut().zip(&buffers.outputs) {
+            dst.extend_from_slice(&src[..frames.min(src.len())]);
+        }
+        rendered += frames;
+    }
+    plugin.stop_processing()?;
+    Ok(channels)
+}
+
+fn validate(path: &Path) -> Result<(), String> {
+    let binary =
+        vst3_host::discovery::get_vst3_binary_path(path).map_err(|e| format!("bundle: {e}"))?;
+    println!("binary\t{}", binary.display());
+    let detail = vst3_host::get_detailed_plugin_info(path).map_err(|e| format!("factory: {e}"))?;
+    if !detail
+        .classes
+        .iter()
+        .any(|c| c.category == "Audio Module Class")
+    {
+        return Err("factory exports no audio processor class".to_string());
+    }
+    println!("classes\t{}", detail.classes.len());
+    let plugin = load(path)?;
+    let params = plugin.get_parameters().map_err(|e| e.to_string())?;
+    println!("parameters\t{}", params.len());
+    println!("ok");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn set_param_options_parse() {
+        assert_eq!(
+            parse_args(&args(
+                "set-param /p/S.vst3 7 0.5 --duration-s 2.5 --output-wav out.wav"
+            )),
+            Ok(Command::SetParam {
+                path: PathBuf::from("/p/S.vst3"),
+                param_id: 7,
+                value: 0.5,
+                output_wav: Some(PathBuf::from("out.wav")),
+                duration_s: 2.5,
+            })
+        );
+    }
+
+    #[test]
+    fn bad_usage_is_rejected() {
+        assert!(parse_args(&[]).is_err());
+        assert!(parse_args(&args("info")).is_err());
+        assert!(parse_args(&args("info a b")).is_err());
+        assert!(parse_args(&args("frobnicate x")).is_err());
+        assert!(parse_args(&args("set-param x 1")).is_err());
+        assert!(parse_args(&args("set-param x 1 0.5 --duration-s")).is_err());
+        assert!(parse_args(&args("set-param x 1 0.5 --duration-s -1")).is_err());
+    }
+}
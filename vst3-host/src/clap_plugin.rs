@@ -0,0 +1,802 @@
+//! A minimal CLAP host (`clap-support` feature).
+//!
+//! [`ClapPlugin`] loads one plugin from a `.clap` file (or macOS bundle) through the CLAP C
+//! API and offers the core of [`Plugin`](crate::Plugin)'s surface: start/stop processing,
+//! process audio, send notes and set parameters. It has no editor, state or process isolation,
+//! and its audio goes to the plugin's first input and output port only.
+//!
+//! ```no_run
+//! # fn main() -> vst3_host::Result<()> {
+//! use vst3_host::{clap_plugin::ClapPlugin, AudioBuffers, MidiChannel};
+//!
+//! let mut synth = ClapPlugin::load("/usr/lib/clap/Surge XT.clap".as_ref(), None, 48000.0, 512)?;
+//! synth.start_processing()?;
+//! synth.send_midi_note(60, 100, MidiChannel::Ch1)?;
+//! let mut buffers = AudioBuffers::new(0, 2, 512, 48000.0);
+//! synth.process_audio(&mut buffers)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+use clap_sys::{
+    audio_buffer::clap_audio_buffer,
+    entry::clap_plugin_entry,
+    events::{
+        clap_event_header, clap_event_note, clap_event_param_value, clap_input_events,
+        clap_output_events, CLAP_CORE_EVENT_SPACE_ID, CLAP_EVENT_NOTE_OFF, CLAP_EVENT_NOTE_ON,
+        CLAP_EVENT_PARAM_VALUE,
+    },
+    ext::{
+        audio_ports::{clap_audio_port_info, clap_plugin_audio_ports, CLAP_EXT_AUDIO_PORTS},
+        gui::CLAP_EXT_GUI,
+        note_ports::{clap_plugin_note_ports, CLAP_EXT_NOTE_PORTS},
+        params::{clap_plugin_params, CLAP_EXT_PARAMS},
+    },
+    factory::plugin_factory::{clap_plugin_factory, CLAP_PLUGIN_FACTORY_ID},
+    host::clap_host,
+    plugin::{clap_plugin, clap_plugin_descriptor},
+    process::{clap_process, CLAP_PROCESS_ERROR},
+    version::{clap_version_is_compatible, CLAP_VERSION},
+};
+
+use crate::audio::AudioBuffers;
+use crate::discovery::CategoryPath;
+use crate::error::{Error, Result};
+use crate::midi::MidiChannel;
+use crate::plugin::{PluginFormat, PluginInfo};
+
+/// Host version string handed to plugins, nul-terminated for C.
+const HOST_VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "\0");
+
+/// A loaded CLAP plugin instance.
+///
+/// Not `Send`: CLAP ties most calls to the thread that created the plugin.
+pub struct ClapPlugin {
+    info: PluginInfo,
+    plugin: *const clap_plugin,
+    params: Option<*const clap_plugin_params>,
+    sample_rate: f64,
+    block_size: usize,
+    is_active: bool,
+    is_processing: bool,
+    /// Events delivered with the next processed block
+    pending: Vec<ClapEvent>,
+    /// One `block_size` buffer per channel of the first input port: stand-ins for channels
+    /// the caller doesn't supply, silenced before each block
+    input_scratch: Vec<Vec<f32>>,
+    /// One `block_size` buffer per channel of the first output port, written for channels the
+    /// caller doesn't take and then discarded
+    output_scratch: Vec<Vec<f32>>,
+    steady_time: i64,
+    // Must outlive `plugin`, which keeps a pointer to it.
+    _host: Box<clap_host>,
+    // Dropped last: deinitializes and unloads the library.
+    _library: ClapLibrary,
+}
+
+impl ClapPlugin {
+    /// Load the plugin with CLAP id `plugin_id` from the `.clap` file at `path`, or its first
+    /// plugin when `plugin_id` is `None`, ready to run at `sample_rate` in blocks of up to
+    /// `block_size` frames.
+    pub fn load(
+        path: &Path,
+        plugin_id: Option<&str>,
+        sample_rate: f64,
+        block_size: usize,
+    ) -> Result<Self> {
+        if !path.exists() {
+            return Err(Error::PluginNotFound(path.display().to_string()));
+        }
+        let library = ClapLibrary::open(path)?;
+        let descriptor = library
+            .descriptors()
+            .into_iter()
+            .find(|&d| plugin_id.is_none_or(|id| unsafe { c_string((*d).id) } == id))
+            .ok_or_else(|| {
+                Error::PluginNotFound(format!(
+                    "{} has no CLAP plugin {}",
+                    path.display(),
+                    plugin_id.unwrap_or("at all")
+                ))
+            })?;
+        let host = new_host();
+        unsafe {
+            let plugin = library.instantiate(descriptor, &host)?;
+            let info = plugin_info(path, descriptor, Some(plugin));
+            let params = extension::<clap_plugin_params>(plugin, CLAP_EXT_PARAMS);
+            // Without the audio-ports extension, assume a stereo port wherever `plugin_info`
+            // guessed one.
+            let channels = |is_input: bool, ports: u32| {
+                main_port_channels(plugin, is_input).unwrap_or(if ports > 0 { 2 } else { 0 })
+            };
+            let scratch = |channels: u32| vec![vec![0.0; block_size]; channels as usize];
+            let input_scratch = scratch(channels(true, info.audio_inputs));
+            let output_scratch = scratch(channels(false, info.audio_outputs));
+            Ok(Self {
+                info,
+                plugin,
+                params,
+                sample_rate,
+                block_size,
+                is_active: false,
+                is_processing: false,
+                pending: Vec::new(),
+                input_scratch,
+                output_scratch,
+                steady_time: 0,
+                _host: host,
+                _library: library,
+            })
+        }
+    }
+
+    /// The plugin's metadata.
+    pub fn info(&self) -> &PluginInfo {
+        &self.info
+    }
+
+    /// Whether [`Self::start_processing`] has been called (and not stopped since).
+    pub fn is_processing(&self) -> bool {
+        self.is_processing
+    }
+
+    /// Activate the plugin (on first use) and start processing.
+    pub fn start_processing(&mut self) -> Result<()> {
+        if self.is_processing {
+            return Ok(());
+        }
+        unsafe {
+            if !self.is_active {
+                let activate = (*self.plugin).activate.ok_or_else(missing("activate"))?;
+                if !activate(self.plugin, self.sample_rate, 1, self.block_size as u32) {
+                    return Err(Error::ProcessError("CLAP activate() failed".to_string()));
+                }
+                self.is_active = true;
+            }
+            if let Some(start) = (*self.plugin).start_processing {
+                if !start(self.plugin) {
+                    return Err(Error::ProcessError(
+                        "CLAP start_processing() failed".to_string(),
+                    ));
+                }
+            }
+        }
+        self.is_processing = true;
+        Ok(())
+    }
+
+    /// Stop processing. The plugin stays active until it's dropped.
+    pub fn stop_processing(&mut self) -> Result<()> {
+        if !self.is_processing {
+            return Ok(());
+        }
+        unsafe {
+            if let Some(stop) = (*self.plugin).stop_processing {
+                stop(self.plugin);
+            }
+        }
+        self.is_processing = false;
+        Ok(())
+    }
+
+    /// Process one block: `buffers.inputs` feed the plugin's first input port and its first
+    /// output port writes `buffers.outputs`. Notes and parameter changes sent since the last
+    /// block are delivered at its start.
+    ///
+    /// The ports always get the channel count the plugin declared: input channels the caller
+    /// doesn't supply are silent, and output channels past the plugin's are silenced. A block
+    /// longer than the `block_size` given to [`Self::load`] is processed in `block_size` chunks.
+    pub fn process_audio(&mut self, buffers: &mut AudioBuffers) -> Result<()> {
+        if !self.is_processing {
+            return Err(Error::Other("Plugin is not processing".to_string()));
+        }
+        let frames = buffers
+            .inputs
+            .iter()
+            .chain(&buffers.outputs)
+            .map(Vec::len)
+            .fold(buffers.block_size, usize::min);
+        for channel in buffers.outputs.iter_mut().skip(self.output_scratch.len()) {
+            channel.fill(0.0);
+        }
+
+        let mut start = 0;
+        while start < frames {
+            let chunk = (frames - start).min(self.block_size);
+            let result = self.process_chunk(buffers, start, chunk);
+            // Queued events belong to the first chunk only.
+            self.pending.clear();
+            result?;
+            start += chunk;
+        }
+        Ok(())
+    }
+
+    /// Run `process` over `frames` frames of `buffers` starting at `start`.
+    fn process_chunk(
+        &mut self,
+        buffers: &mut AudioBuffers,
+        start: usize,
+        frames: usize,
+    ) -> Result<()> {
+        for channel in self.input_scratch.iter_mut().skip(buffers.inputs.len()) {
+            channel.fill(0.0);
+        }
+        let mut inputs = channel_ptrs(&mut buffers.inputs, &mut self.input_scratch, start);
+        let mut outputs = channel_ptrs(&mut buffers.outputs, &mut self.output_scratch, start);
+        let input_port = audio_buffer(&mut inputs);
+        let mut output_port = audio_buffer(&mut outputs);
+        let has_inputs = !inputs.is_empty();
+        let has_outputs = !outputs.is_empty();
+
+        let in_events = input_events(&self.pending);
+        let out_events = discarded_output_events();
+        let process = clap_process {
+            steady_time: self.steady_time,
+            frames_count: frames as u32,
+            transport: ptr::null(),
+            audio_inputs: if has_inputs { &input_port } else { ptr::null() },
+            audio_outputs: if has_outputs {
+                &mut output_port
+            } else {
+                ptr::null_mut()
+            },
+            audio_inputs_count: has_inputs as u32,
+            audio_outputs_count: has_outputs as u32,
+            in_events: &in_events,
+            out_events: &out_events,
+        };
+        let status = unsafe {
+            let process_fn = (*self.plugin).process.ok_or_else(missing("process"))?;
+            process_fn(self.plugin, &process)
+        };
+        self.steady_time += frames as i64;
+        if status == CLAP_PROCESS_ERROR {
+            return Err(Error::ProcessError("CLAP process() failed".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Send a note on, delivered with the next processed block. A velocity of 0 is a note off.
+    pub fn send_midi_note(&mut self, note: u8, velocity: u8, channel: MidiChannel) -> Result<()> {
+        if note > 127 {
+            return Err(Error::MidiError(format!("Invalid note number: {}", note)));
+        }
+        if velocity > 127 {
+            return Err(Error::MidiError(format!("Invalid velocity: {}", velocity)));
+        }
+        let kind = if velocity == 0 {
+            CLAP_EVENT_NOTE_OFF
+        } else {
+            CLAP_EVENT_NOTE_ON
+        };
+        self.pending
+            .push(ClapEvent::note(kind, note, velocity, channel));
+        Ok(())
+    }
+
+    /// Send a note off, delivered with the next processed block.
+    pub fn send_midi_note_off(&mut self, note: u8, channel: MidiChannel) -> Result<()> {
+        if note > 127 {
+            return Err(Error::MidiError(format!("Invalid note number: {}", note)));
+        }
+        self.pending
+            .push(ClapEvent::note(CLAP_EVENT_NOTE_OFF, note, 0, channel));
+        Ok(())
+    }
+
+    /// Set parameter `id` to `value`, in the parameter's own (plain) units as CLAP uses them.
+    /// While processing, the change is delivered with the next block; otherwise it's flushed
+    /// to the plugin straight away.
+    pub fn set_parameter_value(&mut self, id: u32, value: f64) -> Result<()> {
+        let params = self.params.ok_or_else(|| {
+            Error::InvalidParameter(format!("{} has no parameters", self.info.name))
+        })?;
+        let event = ClapEvent::param(id, value);
+        let flush = unsafe { (*params).flush };
+        match flush {
+            Some(flush) if !self.is_processing => {
+                let events = vec![event];
+                let in_events = input_events(&events);
+                let out_events = discarded_output_events();
+                unsafe { flush(self.plugin, &in_events, &out_events) };
+            }
+            _ => self.pending.push(event),
+        }
+        Ok(())
+    }
+
+    /// The current (plain) value of parameter `id`.
+    pub fn get_parameter_value(&self, id: u32) -> Result<f64> {
+        let mut value = 0.0;
+        let read = unsafe {
+            self.params
+                .and_then(|params| (*params).get_value)
+                .is_some_and(|get_value| get_value(self.plugin, id, &mut value))
+        };
+        if read {
+            Ok(value)
+        } else {
+            Err(Error::InvalidParameter(format!("Parameter {id} not found")))
+        }
+    }
+}
+
+impl Drop for ClapPlugin {
+    fn drop(&mut self) {
+        let _ = self.stop_processing();
+        unsafe {
+            if self.is_active {
+                if let Some(deactivate) = (*self.plugin).deactivate {
+                    deactivate(self.plugin);
+                }
+            }
+            if let Some(destroy) = (*self.plugin).destroy {
+                destroy(self.plugin);
+            }
+        }
+    }
+}
+
+/// Metadata for every plugin in the `.clap` file at `path`. Each plugin is created just long
+/// enough to count its ports; one that won't instantiate is described from its descriptor.
+pub(crate) fn clap_plugin_info(path: &Path) -> Result<Vec<PluginInfo>> {
+    let library = ClapLibrary::open(path)?;
+    let host = new_host();
+    let infos = library
+        .descriptors()
+        .into_iter()
+        .map(|descriptor| unsafe {
+            match library.instantiate(descriptor, &host) {
+                Ok(plugin) => {
+                    let info = plugin_info(path, descriptor, Some(plugin));
+                    if let Some(destroy) = (*plugin).destroy {
+                        destroy(plugin);
+                    }
+                    info
+                }
+                Err(e) => {
+                    log::debug!("{}: describing without instantiating: {e}", path.display());
+                    plugin_info(path, descriptor, None)
+                }
+            }
+        })
+        .collect();
+    Ok(infos)
+}
+
+/// The shared library inside a `.clap`: the file itself, or `Contents/MacOS/<name>` in a
+/// macOS bundle.
+fn binary_path(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        let name = path.file_stem().unwrap_or_default();
+        path.join("Contents").join("MacOS").join(name)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// An open CLAP library with its entry point initialized.
+struct ClapLibrary {
+    entry: *const clap_plugin_entry,
+    factory: *const clap_plugin_factory,
+    _library: libloading::Library,
+}
+
+impl ClapLibrary {
+    fn open(path: &Path) -> Result<Self> {
+        let failed = |what: String| Error::PluginLoadFailed(format!("{}: {what}", path.display()));
+        let library = unsafe { libloading::Library::new(binary_path(path)) }
+            .map_err(|e| failed(e.to_string()))?;
+        let entry = unsafe { library.get::<*const clap_plugin_entry>(b"clap_entry\0") }
+            .map(|symbol| *symbol)
+            .map_err(|_| failed("not a CLAP plugin (no clap_entry)".to_string()))?;
+        if entry.is_null() {
+            return Err(failed("null clap_entry".to_string()));
+        }
+        unsafe {
+            let version = (*entry).clap_version;
+            if !clap_version_is_compatible(version) {
+                return Err(failed(format!(
+                    "unsupported CLAP version {}.{}.{}",
+                    version.major, version.minor, version.revision
+                )));
+            }
+            let plugin_path = CString::new(path.to_string_lossy().as_bytes())
+                .map_err(|_| failed("path contains a nul byte".to_string()))?;
+            let init = (*entry)
+                .init
+                .ok_or_else(|| failed("no init()".to_string()))?;
+            if !init(plugin_path.as_ptr()) {
+                return Err(failed("clap_entry.init() failed".to_string()));
+            }
+            let factory = (*entry)
+                .get_factory
+                .map(|get_factory| get_factory(CLAP_PLUGIN_FACTORY_ID.as_ptr()))
+                .unwrap_or(ptr::null())
+                .cast::<clap_plugin_factory>();
+            // Build `Self` before checking so the entry is deinitialized on failure.
+            let library = Self {
+                entry,
+                factory,
+                _library: library,
+            };
+            if factory.is_null() {
+                return Err(failed("no plugin factory".to_string()));
+            }
+            Ok(library)
+        }
+    }
+
+    fn descriptors(&self) -> Vec<*const clap_plugin_descriptor> {
+        unsafe {
+            let factory = &*self.factory;
+            let (Some(count), Some(get)) =
+                (factory.get_plugin_count, factory.get_plugin_descriptor)
+            else {
+                return Vec::new();
+            };
+            (0..count(self.factory))
+                .map(|i| get(self.factory, i))
+                .filter(|d| !d.is_null())
+                .collect()
+        }
+    }
+
+    /// Create and initialize the plugin `descriptor` describes.
+    unsafe fn instantiate(
+        &self,
+        descriptor: *const clap_plugin_descriptor,
+        host: &clap_host,
+    ) -> Result<*const clap_plugin> {
+        let create = (*self.factory)
+            .create_plugin
+            .ok_or_else(missing("create_plugin"))?;
+        let plugin = create(self.factory, host, (*descriptor).id);
+        if plugin.is_null() {
+            return Err(Error::PluginLoadFailed(format!(
+                "CLAP factory could not create {}",
+                c_string((*descriptor).id)
+            )));
+        }
+        if !(*plugin).init.is_some_and(|init| init(plugin)) {
+            if let Some(destroy) = (*plugin).destroy {
+                destroy(plugin);
+            }
+            return Err(Error::PluginLoadFailed(format!(
+                "CLAP plugin {} failed to initialize",
+                c_string((*descriptor).id)
+            )));
+        }
+        Ok(plugin)
+    }
+}
+
+impl Drop for ClapLibrary {
+    fn drop(&mut self) {
+        unsafe {
+            if let Some(deinit) = (*self.entry).deinit {
+                deinit();
+            }
+        }
+    }
+}
+
+/// Describe the plugin. With an instance, port counts and editor support come from its
+/// extensions; without one they're guessed from the descriptor's features.
+unsafe fn plugin_info(
+    path: &Path,
+    descriptor: *const clap_plugin_descriptor,
+    plugin: Option<*const clap_plugin>,
+) -> PluginInfo {
+    let d = &*descriptor;
+    let features = c_string_list(d.features);
+    let instrument = features.iter().any(|f| f == "instrument");
+    let note_effect = features.iter().any(|f| f == "note-effect");
+
+    let audio_ports = plugin.and_then(|p| {
+        let count = (*extension::<clap_plugin_audio_ports>(p, CLAP_EXT_AUDIO_PORTS)?).count?;
+        Some((count(p, true), count(p, false)))
+    });
+    let note_ports = plugin.and_then(|p| {
+        let count = (*extension::<clap_plugin_note_ports>(p, CLAP_EXT_NOTE_PORTS)?).count?;
+        Some((count(p, true), count(p, false)))
+    });
+    let (audio_inputs, audio_outputs) = audio_ports.unwrap_or((!instrument as u32, 1));
+    let (note_inputs, note_outputs) =
+        note_ports.unwrap_or(((instrument || note_effect) as u32, note_effect as u32));
+
+    PluginInfo {
+        path: path.to_path_buf(),
+        name: c_string(d.name),
+        vendor: c_string(d.vendor),
+        version: c_string(d.version),
+        category_path: category_from_features(&features),
+        uid: c_string(d.id),
+        audio_inputs,
+        audio_outputs,
+        has_midi_input: note_inputs > 0,
+        has_midi_output: note_outputs > 0,
+        has_gui: plugin.is_some_and(|p| extension::<c_void>(p, CLAP_EXT_GUI).is_some()),
+        format: PluginFormat::Clap,
+        duplicates: Vec::new(),
+    }
+}
+
+/// A VST3-style category path from CLAP feature strings: the main category
+/// (`"instrument"` → `Instrument`, `"audio-effect"` → `Fx`, …) and then the first more specific
+/// feature, e.g. `["Instrument", "Synth"]`.
+fn category_from_features(features: &[String]) -> CategoryPath {
+    let main = features.iter().find_map(|f| match f.as_str() {
+        "instrument" => Some("Instrument"),
+        "audio-effect" => Some("Fx"),
+        "note-effect" => Some("Note Effect"),
+        "note-detector" => Some("Note Detector"),
+        "analyzer" => Some("Analyzer"),
+        _ => None,
+    });
+    let detail = features.iter().find_map(|f| match f.as_str() {
+        "instrument" | "audio-effect" | "note-effect" | "note-detector" | "analyzer" | "mono"
+        | "stereo" | "surround" | "ambisonic" => None,
+        "synthesizer" => Some("Synth".to_string()),
+        "drum" | "drum-machine" => Some("Drum".to_string()),
+        "equalizer" => Some("EQ".to_string()),
+        other => {
+            let mut words = other.replace('-', " ");
+            if let Some(first) = words.get_mut(..1) {
+                first.make_ascii_uppercase();
+            }
+            Some(words)
+        }
+    });
+    main.into_iter().map(str::to_string).chain(detail).collect()
+}
+
+/// The plugin's extension `id`, if it implements it.
+unsafe fn extension<T>(plugin: *const clap_plugin, id: &CStr) -> Option<*const T> {
+    let get = (*plugin).get_extension?;
+    let ext = get(plugin, id.as_ptr());
+    (!ext.is_null()).then(|| ext.cast::<T>())
+}
+
+unsafe fn c_string(s: *const c_char) -> String {
+    if s.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(s).to_string_lossy().into_owned()
+    }
+}
+
+/// A null-terminated array of C strings.
+unsafe fn c_string_list(mut list: *const *const c_char) -> Vec<String> {
+    let mut strings = Vec::new();
+    while !list.is_null() && !(*list).is_null() {
+        strings.push(c_string(*list));
+        list = list.add(1);
+    }
+    strings
+}
+
+fn missing(function: &'static str) -> impl FnOnce() -> Error {
+    move || Error::InterfaceError(format!("CLAP plugin has no {function}()"))
+}
+
+fn new_host() -> Box<clap_host> {
+    Box::new(clap_host {
+        clap_version: CLAP_VERSION,
+        host_data: ptr::null_mut(),
+        name: c"vst3-host".as_ptr(),
+        vendor: c"vst3-host".as_ptr(),
+        url: c"https://github.com/HelgeSverre/rust-vst3-host".as_ptr(),
+        version: HOST_VERSION.as_ptr().cast(),
+        get_extension: Some(host_get_extension),
+        request_restart: Some(host_request),
+        request_process: Some(host_request),
+        request_callback: Some(host_request),
+    })
+}
+
+/// The host offers no extensions.
+unsafe extern "C" fn host_get_extension(
+    _host: *const clap_host,
+    _id: *const c_char,
+) -> *const c_void {
+    ptr::null()
+}
+
+/// Restart, process and main-thread callback requests are ignored: the host processes when
+/// it's told to.
+unsafe extern "C" fn host_request(_host: *const clap_host) {}
+
+/// The channel count of the plugin's first input or output port, `Some(0)` if it has none, or
+/// `None` without the audio-ports extension.
+unsafe fn main_port_channels(plugin: *const clap_plugin, is_input: bool) -> Option<u32> {
+    let ports = &*extension::<clap_plugin_audio_ports>(plugin, CLAP_EXT_AUDIO_PORTS)?;
+    if ports.count?(plugin, is_input) == 0 {
+        return Some(0);
+    }
+    let mut info: clap_audio_port_info = std::mem::zeroed();
+    ports.get?(plugin, 0, is_input, &mut info).then_some(info.channel_count)
+}
+
+/// One pointer per port channel (`scratch.len()` of them), `start` frames in: the caller's
+/// channel where it has one, the scratch buffer otherwise.
+fn channel_ptrs(caller: &mut [Vec<f32>], scratch: &mut [Vec<f32>], start: usize) -> Vec<*mut f32> {
+    scratch
+        .iter_mut()
+        .enumerate()
+        .map(|(channel, fallback)| match caller.get_mut(channel) {
+            Some(buffer) => buffer[start..].as_mut_ptr(),
+            None => fallback.as_mut_ptr(),
+        })
+        .collect()
+}
+
+fn audio_buffer(channels: &mut [*mut f32]) -> clap_audio_buffer {
+    clap_audio_buffer {
+        data32: channels.as_mut_ptr(),
+        data64: ptr::null_mut(),
+        channel_count: channels.len() as u32,
+        latency: 0,
+        constant_mask: 0,
+    }
+}
+
+/// An event queued for the plugin.
+#[derive(Clone, Copy)]
+enum ClapEvent {
+    Note(clap_event_note),
+    Param(clap_event_param_value),
+}
+
+impl ClapEvent {
+    fn note(kind: u16, note: u8, velocity: u8, channel: MidiChannel) -> Self {
+        ClapEvent::Note(clap_event_note {
+            header: event_header::<clap_event_note>(kind),
+            note_id: -1,
+            port_index: 0,
+            channel: channel.as_index() as i16,
+            key: note as i16,
+            velocity: velocity as f64 / 127.0,
+        })
+    }
+
+    fn param(id: u32, value: f64) -> Self {
+        ClapEvent::Param(clap_event_param_value {
+            header: event_header::<clap_event_param_value>(CLAP_EVENT_PARAM_VALUE),
+            param_id: id,
+            cookie: ptr::null_mut(),
+            note_id: -1,
+            port_index: -1,
+            channel: -1,
+            key: -1,
+            value,
+        })
+    }
+
+    fn header(&self) -> *const clap_event_header {
+        match self {
+            ClapEvent::Note(e) => &e.header,
+            ClapEvent::Param(e) => &e.header,
+        }
+    }
+}
+
+fn event_header<T>(kind: u16) -> clap_event_header {
+    clap_event_header {
+        size: std::mem::size_of::<T>() as u32,
+        time: 0,
+        space_id: CLAP_CORE_EVENT_SPACE_ID,
+        type_: kind,
+        flags: 0,
+    }
+}
+
+/// An input event list reading `events`, which must outlive it.
+fn input_events(events: &Vec<ClapEvent>) -> clap_input_events {
+    unsafe extern "C" fn size(list: *const clap_input_events) -> u32 {
+        let events = &*(*list).ctx.cast::<Vec<ClapEvent>>();
+        events.len() as u32
+    }
+    unsafe extern "C" fn get(
+        list: *const clap_input_events,
+        index: u32,
+    ) -> *const clap_event_header {
+        let events = &*(*list).ctx.cast::<Vec<ClapEvent>>();
+        events
+            .get(index as usize)
+            .map_or(ptr::null(), ClapEvent::header)
+    }
+    clap_input_events {
+        ctx: (events as *const Vec<ClapEvent>).cast_mut().cast(),
+        size: Some(size),
+        get: Some(get),
+    }
+}
+
+/// An output event list that accepts and drops everything the plugin emits.
+fn discarded_output_events() -> clap_output_events {
+    unsafe extern "C" fn try_push(
+        _list: *const clap_output_events,
+        _event: *const clap_event_header,
+    ) -> bool {
+        true
+    }
+    clap_output_events {
+        ctx: ptr::null_mut(),
+        try_push: Some(try_push),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features(list: &[&str]) -> Vec<String> {
+        list.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn features_map_to_a_category_path() {
+        assert_eq!(
+            category_from_features(&features(&["instrument", "synthesizer", "stereo"])),
+            ["Instrument", "Synth"]
+        );
+        assert_eq!(
+            category_from_features(&features(&["stereo", "audio-effect", "pitch-correction"])),
+            ["Fx", "Pitch correction"]
+        );
+        assert!(category_from_features(&[]).is_empty());
+    }
+
+    #[test]
+    fn non_clap_files_fail_to_load() {
+        let path = std::env::temp_dir().join(format!("vh_fake_{}.clap", std::process::id()));
+        std::fs::write(&path, b"not a real plugin binary").unwrap();
+        assert!(matches!(
+            ClapPlugin::load(&path, None, 48000.0, 512),
+            Err(Error::PluginLoadFailed(_))
+        ));
+        assert!(clap_plugin_info(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn ports_get_the_declared_channel_count() {
+        // A stereo port fed one caller channel: the second channel comes from scratch, and a
+        // third caller channel is left out.
+        let mut caller = vec![vec![0.0; 8]];
+        let mut scratch = vec![vec![0.0; 4]; 2];
+        let ptrs = channel_ptrs(&mut caller, &mut scratch, 4);
+        assert_eq!(ptrs, [caller[0][4..].as_mut_ptr(), scratch[1].as_mut_ptr()]);
+
+        caller.push(vec![0.0; 8]);
+        caller.push(vec![0.0; 8]);
+        assert_eq!(channel_ptrs(&mut caller, &mut scratch, 0).len(), 2);
+        assert!(channel_ptrs(&mut caller, &mut [], 0).is_empty());
+    }
+
+    #[test]
+    fn input_events_list_the_queued_events() {
+        let events = vec![
+            ClapEvent::note(CLAP_EVENT_NOTE_ON, 60, 127, MidiChannel::Ch2),
+            ClapEvent::param(7, 0.25),
+        ];
+        let list = input_events(&events);
+        unsafe {
+            assert_eq!(list.size.unwrap()(&list), 2);
+            let note = list.get.unwrap()(&list, 0).cast::<clap_event_note>();
+            assert_eq!(
+                ((*note).key, (*note).channel, (*note).velocity),
+                (60, 1, 1.0)
+            );
+            let param = list.get.unwrap()(&list, 1).cast::<clap_event_param_value>();
+            assert_eq!((*param).header.type_, CLAP_EVENT_PARAM_VALUE);
+            assert!(list.get.unwrap()(&list, 2).is_null());
+        }
+    }
+}
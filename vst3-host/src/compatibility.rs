@@ -0,0 +1,311 @@
+//! Known plugin quirks and the workarounds for them.
+//!
+//! Some plugins only load or process correctly with particular [`PluginInitFlags`] or host
+//! behavior — skipping the editor probe, receiving a CC before their first block, running out
+//! of process. A [`CompatibilityDb`] records these per plugin, matched by vendor and name, and
+//! [`Vst3Host::load_plugin`](crate::Vst3Host::load_plugin) applies the matching entry's flags
+//! and [`Workaround`]s automatically. The crate bundles a database
+//! (`compatibility_db.toml`, [`CompatibilityDb::bundled`]); hosts can replace it with their
+//! own through [`Vst3HostBuilder::compatibility_db`](crate::Vst3HostBuilder::compatibility_db).
+
+use crate::error::{Error, Result};
+use crate::plugin::{PluginInfo, PluginInitFlags};
+
+/// The database shipped with the crate.
+const BUNDLED_DB: &str = include_str!("../compatibility_db.toml");
+
+/// Host behavior a plugin needs beyond its [`PluginInitFlags`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Workaround {
+    /// Send this control change on channel 1 ahead of the first processed block
+    SendCcBeforeProcessing {
+        /// Controller number (0-127)
+        cc: u8,
+        /// Controller value (0-127)
+        value: u8,
+    },
+    /// Wait this many milliseconds before processing starts for the first time
+    DelayStartProcessingMs(u64),
+    /// Load the plugin in a separate process, even if the host loads in-process. If the
+    /// isolated load fails, the plugin is not loaded.
+    UseProcessIsolation,
+    /// Don't push restored component state to the controller
+    /// ([`PluginInitFlags::SKIP_STATE_TRANSFER`])
+    SkipStateTransfer,
+}
+
+/// What's known about one plugin (or a vendor's plugins) and how to work around it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CompatibilityEntry {
+    /// Vendor the entry applies to, compared case-insensitively
+    pub match_vendor: Option<String>,
+    /// Text the plugin's name must contain, compared case-insensitively
+    pub match_name_contains: Option<String>,
+    /// Notes on what goes wrong, for showing to the user
+    pub known_issues: Vec<String>,
+    /// Flags to load the plugin with, on top of any the host sets
+    pub recommended_flags: PluginInitFlags,
+    /// Host behavior to apply
+    pub workarounds: Vec<Workaround>,
+}
+
+impl CompatibilityEntry {
+    /// Whether the entry applies to a plugin from `vendor` named `name`. Both criteria must
+    /// hold when both are given; an entry with neither matches nothing. With the vendor unknown
+    /// (`None`, e.g. before a plugin has been loaded or scanned) only the name is compared, so
+    /// an entry without a name criterion doesn't match.
+    pub fn matches(&self, vendor: Option<&str>, name: &str) -> bool {
+        if self.match_name_contains.is_none() && (vendor.is_none() || self.match_vendor.is_none()) {
+            return false;
+        }
+        let vendor_ok = match (&self.match_vendor, vendor) {
+            (Some(wanted), Some(vendor)) => wanted.eq_ignore_ascii_case(vendor),
+            _ => true,
+        };
+        let name_ok = self
+            .match_name_contains
+            .as_ref()
+            .is_none_or(|n| name.to_lowercase().contains(&n.to_lowercase()));
+        vendor_ok && name_ok
+    }
+
+    /// The flags to load with: the recommended ones plus any a workaround implies.
+    pub fn init_flags(&self) -> PluginInitFlags {
+        let mut flags = self.recommended_flags;
+        if self.workarounds.contains(&Workaround::SkipStateTransfer) {
+            flags |= PluginInitFlags::SKIP_STATE_TRANSFER;
+        }
+        flags
+    }
+}
+
+/// A list of [`CompatibilityEntry`]s, looked up by plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CompatibilityDb {
+    entries: Vec<CompatibilityEntry>,
+}
+
+impl CompatibilityDb {
+    /// The database bundled with the crate.
+    pub fn bundled() -> Self {
+        Self::from_toml(BUNDLED_DB).expect("bundled compatibility_db.toml is valid")
+    }
+
+    /// A database of `entries`, e.g. a host's own additions.
+    pub fn new(entries: Vec<CompatibilityEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// Read a database in the `compatibility_db.toml` format from `path`.
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| Error::Other(format!("read compatibility db: {e}")))?;
+        Self::from_toml(&text)
+    }
+
+    /// Parse a database in the `compatibility_db.toml` format: one `[[plugin]]` table per
+    /// entry.
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let err = |m: String| Error::Other(format!("invalid compatibility db: {m}"));
+        let doc: toml_edit::DocumentMut = text.parse().map_err(|e| err(format!("{e}")))?;
+        let Some(plugins) = doc.get("plugin") else {
+            return Ok(Self::default());
+        };
+        let tables = plugins
+            .as_array_of_tables()
+            .ok_or_else(|| err("`plugin` must be an array of tables".to_string()))?;
+        let entries = tables
+            .iter()
+            .enumerate()
+            .map(|(i, table)| parse_entry(table).map_err(|m| err(format!("plugin {i}: {m}"))))
+            .collect::<Result<_>>()?;
+        Ok(Self { entries })
+    }
+
+    /// Every entry, in file order.
+    pub fn entries(&self) -> &[CompatibilityEntry] {
+        &self.entries
+    }
+
+    /// The first entry that applies to `plugin_info`.
+    pub fn lookup(&self, plugin_info: &PluginInfo) -> Option<&CompatibilityEntry> {
+        self.lookup_by(Some(&plugin_info.vendor), &plugin_info.name)
+    }
+
+    /// The first entry that applies to a plugin from `vendor` (`None` if unknown) named `name`.
+    pub fn lookup_by(&self, vendor: Option<&str>, name: &str) -> Option<&CompatibilityEntry> {
+        self.entries.iter().find(|e| e.matches(vendor, name))
+    }
+}
+
+/// One `[[plugin]]` table as an entry, or what's wrong with it.
+fn parse_entry(table: &toml_edit::Table) -> std::result::Result<CompatibilityEntry, String> {
+    let string = |key: &str| -> std::result::Result<Option<String>, String> {
+        match table.get(key) {
+            None => Ok(None),
+            Some(item) => item
+                .as_str()
+                .map(|s| Some(s.to_string()))
+                .ok_or_else(|| format!("`{key}` must be a string")),
+        }
+    };
+    let strings = |key: &str| -> std::result::Result<Vec<String>, String> {
+        let Some(item) = table.get(key) else {
+            return Ok(Vec::new());
+        };
+        let array = item
+            .as_array()
+            .ok_or_else(|| format!("`{key}` must be an array of strings"))?;
+        array
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| format!("`{key}` must be an array of strings"))
+            })
+            .collect()
+    };
+
+    let mut recommended_flags = PluginInitFlags::empty();
+    for name in strings("recommended_flags")? {
+        let (flag, _) = PluginInitFlags::ALL
+            .iter()
+            .find(|(_, flag_name)| *flag_name == name)
+            .ok_or_else(|| format!("unknown init flag `{name}`"))?;
+        recommended_flags |= *flag;
+    }
+
+    let workarounds = match table.get("workarounds") {
+        None => Vec::new(),
+        Some(item) => item
+            .as_array()
+            .ok_or_else(|| "`workarounds` must be an array".to_string())?
+            .iter()
+            .map(parse_workaround)
+            .collect::<std::result::Result<_, _>>()?,
+    };
+
+    Ok(CompatibilityEntry {
+        match_vendor: string("match_vendor")?,
+        match_name_contains: string("match_name_contains")?,
+        known_issues: strings("known_issues")?,
+        recommended_flags,
+        workarounds,
+    })
+}
+
+/// A workaround: a bare name, or a one-key inline table for those that carry values.
+fn parse_workaround(value: &toml_edit::Value) -> std::result::Result<Workaround, String> {
+    if let Some(name) = value.as_str() {
+        return match name {
+            "use_process_isolation" => Ok(Workaround::UseProcessIsolation),
+            "skip_state_transfer" => Ok(Workaround::SkipStateTransfer),
+            _ => Err(format!("unknown workaround `{name}`")),
+        };
+    }
+    let table = value
+        .as_inline_table()
+        .ok_or_else(|| "a workaround must be a name or an inline table".to_string())?;
+    let mut pairs = table.iter();
+    let (Some((name, value)), None) = (pairs.next(), pairs.next()) else {
+        return Err("a workaround table must have exactly one key".to_string());
+    };
+    let integer = |v: Option<&toml_edit::Value>, what: &str, max: i64| {
+        v.and_then(|v| v.as_integer())
+            .filter(|n| (0..=max).contains(n))
+            .ok_or_else(|| format!("`{what}` must be an integer from 0 to {max}"))
+    };
+    match name {
+        "delay_start_processing_ms" => {
+            Ok(Workaround::DelayStartProcessingMs(
+                integer(Some(value), name, i64::MAX)? as u64,
+            ))
+        }
+        "send_cc_before_processing" => {
+            let cc = value
+                .as_inline_table()
+                .ok_or_else(|| format!("`{name}` must be a table with `cc` and `value`"))?;
+            Ok(Workaround::SendCcBeforeProcessing {
+                cc: integer(cc.get("cc"), "cc", 127)? as u8,
+                value: integer(cc.get("value"), "value", 127)? as u8,
+            })
+        }
+        _ => Err(format!("unknown workaround `{name}`")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(vendor: &str, name: &str) -> PluginInfo {
+        PluginInfo {
+            path: format!("/vst3/{name}.vst3").into(),
+            name: name.to_string(),
+            vendor: vendor.to_string(),
+            audio_outputs: 2,
+            has_midi_input: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lookup_matches_by_name_and_by_vendor() {
+        let db = CompatibilityDb::from_toml(
+            r#"
+            [[plugin]]
+            match_name_contains = "shell"
+            known_issues = ["hangs on load"]
+            recommended_flags = ["SKIP_GUI_CHECK", "SKIP_BUS_NEGOTIATION"]
+
+            [[plugin]]
+            match_vendor = "Acme"
+            workarounds = [
+                "skip_state_transfer",
+                { delay_start_processing_ms = 250 },
+                { send_cc_before_processing = { cc = 7, value = 100 } },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        let by_name = db.lookup(&info("Waves", "WaveShell1-VST3 14.0")).unwrap();
+        assert_eq!(by_name.known_issues, ["hangs on load"]);
+        assert_eq!(
+            by_name.init_flags(),
+            PluginInitFlags::SKIP_GUI_CHECK | PluginInitFlags::SKIP_BUS_NEGOTIATION
+        );
+
+        let by_vendor = db.lookup(&info("ACME", "Reverb")).unwrap();
+        assert_eq!(
+            by_vendor.workarounds,
+            [
+                Workaround::SkipStateTransfer,
+                Workaround::DelayStartProcessingMs(250),
+                Workaround::SendCcBeforeProcessing { cc: 7, value: 100 },
+            ]
+        );
+        assert_eq!(by_vendor.init_flags(), PluginInitFlags::SKIP_STATE_TRANSFER);
+
+        assert!(db.lookup(&info("Other", "Synth")).is_none());
+        // Before a plugin is identified only name criteria can match.
+        assert!(db.lookup_by(None, "WaveShell1-VST3 14.0").is_some());
+        assert!(db.lookup_by(None, "Reverb").is_none());
+    }
+
+    #[test]
+    fn the_bundled_database_parses_and_rejects_bad_entries() {
+        let bundled = CompatibilityDb::bundled();
+        assert!(bundled
+            .lookup(&info("Waves", "WaveShell1-VST3 14.0"))
+            .is_some());
+
+        for bad in [
+            "[[plugin]]\nrecommended_flags = [\"SKIP_EVERYTHING\"]",
+            "[[plugin]]\nworkarounds = [\"reboot\"]",
+            "[[plugin]]\nworkarounds = [{ send_cc_before_processing = { cc = 300, value = 1 } }]",
+        ] {
+            assert!(CompatibilityDb::from_toml(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::{error::Result, plugin::PluginInfo};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::ptr;
 use std::time::Duration;
@@ -10,6 +11,306 @@ use std::time::Duration;
 /// treating it as hung and killing the child process.
 pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// A plugin's category split into its levels, most general first — VST3's
+/// `"Instrument|Synth|Virtual Analog"` becomes `["Instrument", "Synth", "Virtual Analog"]`.
+pub type CategoryPath = Vec<String>;
+
+/// Split a VST3 sub-category string at its `|` separators. Surrounding whitespace and
+/// empty levels are dropped, so `""` parses to an empty path.
+pub fn parse_category(raw: &str) -> CategoryPath {
+    raw.split('|')
+        .map(str::trim)
+        .filter(|level| !level.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Deserialize a [`CategoryPath`] from either a list of levels or a raw `A|B|C` string,
+/// so metadata saved before categories were parsed still loads.
+pub(crate) fn deserialize_category_path<'de, D>(
+    deserializer: D,
+) -> std::result::Result<CategoryPath, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Levels(CategoryPath),
+        Joined(String),
+    }
+    Ok(match Raw::deserialize(deserializer)? {
+        Raw::Levels(levels) => levels,
+        Raw::Joined(raw) => parse_category(&raw),
+    })
+}
+
+/// Plugins grouped by category: one sub-tree per level of their [`CategoryPath`]. The root's
+/// children are the top-level categories (Instrument, Fx, …); a plugin sits in the node its
+/// full path ends at, so uncategorized plugins sit in the root.
+#[derive(Debug, Clone, Default)]
+pub struct CategoryTree {
+    /// Plugins whose category path ends at this node
+    pub plugins: Vec<PluginInfo>,
+    /// Sub-categories by name, sorted
+    pub children: BTreeMap<String, CategoryTree>,
+}
+
+impl CategoryTree {
+    /// Build a tree from `plugins`.
+    pub fn from_plugins<'a>(plugins: impl IntoIterator<Item = &'a PluginInfo>) -> Self {
+        let mut tree = Self::default();
+        for plugin in plugins {
+            tree.insert(plugin.clone());
+        }
+        tree
+    }
+
+    /// Add `plugin` under its category path.
+    pub fn insert(&mut self, plugin: PluginInfo) {
+        let mut node = self;
+        for level in &plugin.category_path {
+            node = node.children.entry(level.clone()).or_default();
+        }
+        node.plugins.push(plugin);
+    }
+
+    /// The sub-tree at `path` (relative to this node), if any plugin lives there.
+    pub fn get(&self, path: &[&str]) -> Option<&CategoryTree> {
+        path.iter()
+            .try_fold(self, |node, level| node.children.get(*level))
+    }
+
+    /// Number of plugins in this node and everything below it
+    pub fn plugin_count(&self) -> usize {
+        self.plugins.len()
+            + self
+                .children
+                .values()
+                .map(Self::plugin_count)
+                .sum::<usize>()
+    }
+
+    /// Every plugin in this node and below it, depth first
+    pub fn iter_plugins(&self) -> Box<dyn Iterator<Item = &PluginInfo> + '_> {
+        Box::new(
+            self.plugins
+                .iter()
+                .chain(self.children.values().flat_map(Self::iter_plugins)),
+        )
+    }
+}
+
+/// The user's own notes on a plugin (a license reminder, what it's good for), kept by
+/// [`Vst3Host::annotate_plugin`](crate::Vst3Host::annotate_plugin) and keyed by plugin path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PluginAnnotation {
+    /// Free-form notes
+    pub notes: String,
+    /// The user's tags, in the order added
+    pub custom_tags: Vec<String>,
+    /// Star rating, 0 (unrated) to 5
+    pub rating: u8,
+    /// Marked as a favorite
+    pub favorite: bool,
+}
+
+impl PluginAnnotation {
+    /// Highest [`Self::rating`]
+    pub const MAX_RATING: u8 = 5;
+
+    /// Whether any tag contains `query`, ignoring case. An empty query matches everything.
+    pub fn has_tag(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self
+                .custom_tags
+                .iter()
+                .any(|tag| tag.to_lowercase().contains(&query))
+    }
+
+    /// Add `tag` (trimmed) unless it's empty or already there, ignoring case. Returns whether
+    /// it was added.
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        let tag = tag.trim();
+        if tag.is_empty() || self.custom_tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            return false;
+        }
+        self.custom_tags.push(tag.to_string());
+        true
+    }
+
+    /// Whether there's nothing to keep (no notes, tags, rating or favorite mark).
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// BLAKE3 hash of a plugin's binary (the shared library inside a `.vst3` bundle, or the file
+/// itself), read in chunks so large plugins aren't loaded into memory whole.
+pub fn hash_bundle(path: &Path) -> Result<[u8; 32]> {
+    let binary = get_vst3_binary_path(path)?;
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(std::fs::File::open(&binary)?)?;
+    Ok(*hasher.finalize().as_bytes())
+}
+
+/// A plugin's scanned metadata, with what its binary looked like when it was scanned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedPluginEntry {
+    /// The metadata the scan found
+    pub info: PluginInfo,
+    /// Modification time of the plugin binary
+    pub mtime: std::time::SystemTime,
+    /// Size of the plugin binary in bytes
+    pub size: u64,
+    /// [`hash_bundle`] of the plugin
+    pub content_hash: [u8; 32],
+}
+
+impl CachedPluginEntry {
+    /// Record `info` against the current state of the binary at `info.path`.
+    pub fn new(info: PluginInfo) -> Result<Self> {
+        let (mtime, size) = binary_stamp(&info.path)?;
+        let content_hash = hash_bundle(&info.path)?;
+        Ok(Self {
+            info,
+            mtime,
+            size,
+            content_hash,
+        })
+    }
+}
+
+fn binary_stamp(path: &Path) -> Result<(std::time::SystemTime, u64)> {
+    let metadata = std::fs::metadata(get_vst3_binary_path(path)?)?;
+    Ok((metadata.modified()?, metadata.len()))
+}
+
+/// Scanned plugin metadata keyed by bundle path, so a re-scan only loads plugins that changed.
+///
+/// An entry is fresh while the binary's size, mtime and content hash all still match. Some
+/// filesystems (SMB shares, Docker volumes) report unreliable mtimes; [`Self::hash_only_mode`]
+/// ignores the mtime there and trusts the hash alone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanCache {
+    /// Skip the mtime comparison in [`Self::is_fresh`]
+    pub hash_only_mode: bool,
+    entries: BTreeMap<PathBuf, CachedPluginEntry>,
+}
+
+impl ScanCache {
+    /// An empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty cache with [`Self::hash_only_mode`] set to `hash_only`.
+    pub fn with_hash_only_mode(hash_only: bool) -> Self {
+        Self {
+            hash_only_mode: hash_only,
+            ..Self::default()
+        }
+    }
+
+    /// Read a cache written by [`Self::save`].
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| crate::Error::Other(format!("parse scan cache: {e}")))
+    }
+
+    /// Write the cache as JSON.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| crate::Error::Other(format!("serialize scan cache: {e}")))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// The entry for the plugin at `path`, fresh or not.
+    pub fn get(&self, path: &Path) -> Option<&CachedPluginEntry> {
+        self.entries.get(path)
+    }
+
+    /// Number of cached plugins.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no plugins are cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Cache `info` for its plugin, hashing the binary as it is now.
+    pub fn insert(&mut self, info: PluginInfo) -> Result<()> {
+        let entry = CachedPluginEntry::new(info)?;
+        self.entries.insert(entry.info.path.clone(), entry);
+        Ok(())
+    }
+
+    /// Forget the plugin at `path`.
+    pub fn remove(&mut self, path: &Path) -> Option<CachedPluginEntry> {
+        self.entries.remove(path)
+    }
+
+    /// Move the entry for `from` to `to`, after the plugin was renamed on disk. The binary is
+    /// unchanged, so the entry stays fresh without hashing it again.
+    pub fn rename(&mut self, from: &Path, to: &Path) {
+        if let Some(mut entry) = self.entries.remove(from) {
+            entry.info.path = to.to_path_buf();
+            self.entries.insert(to.to_path_buf(), entry);
+        }
+    }
+
+    /// The cached metadata for `path` if its entry is still fresh.
+    pub fn fresh_info(&self, path: &Path) -> Option<&PluginInfo> {
+        self.get(path)
+            .filter(|entry| self.is_fresh(path, entry))
+            .map(|entry| &entry.info)
+    }
+
+    /// Whether the binary at `path` still matches `entry`: same size and content hash, and
+    /// (unless [`Self::hash_only_mode`]) the same mtime. A missing or unreadable plugin is
+    /// never fresh.
+    pub fn is_fresh(&self, path: &Path, entry: &CachedPluginEntry) -> bool {
+        let Ok((mtime, size)) = binary_stamp(path) else {
+            return false;
+        };
+        if size != entry.size || (!self.hash_only_mode && mtime != entry.mtime) {
+            return false;
+        }
+        hash_bundle(path).is_ok_and(|hash| hash == entry.content_hash)
+    }
+
+    /// Check which of `paths` need re-scanning (uncached or stale) on a background thread,
+    /// since hashing every plugin binary can take a while. The list arrives on the returned
+    /// channel once all are checked.
+    pub fn stale_paths_in_background(
+        &self,
+        paths: Vec<PathBuf>,
+    ) -> std::sync::mpsc::Receiver<Vec<PathBuf>> {
+        let cache = self.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(cache.stale_paths(&paths));
+        });
+        rx
+    }
+
+    /// Which of `paths` need re-scanning: not cached, or changed since they were.
+    pub fn stale_paths(&self, paths: &[PathBuf]) -> Vec<PathBuf> {
+        paths
+            .iter()
+            .filter(|path| self.fresh_info(path).is_none())
+            .cloned()
+            .collect()
+    }
+}
+
 /// Factory-level metadata (the plugin vendor's identity).
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FactoryInfo {
@@ -108,6 +409,135 @@ impl PluginReport {
     pub fn to_json(&self) -> serde_json::Result<String> {
         serde_json::to_string_pretty(self)
     }
+
+    /// Render the report as a Markdown document: the [`PluginInfo::to_markdown`] summary,
+    /// then factory, class, bus and parameter sections.
+    ///
+    /// Parameter ranges and defaults are normalized (`0.0..=1.0`), as VST3 reports them.
+    pub fn to_markdown(&self) -> String {
+        let detailed = &self.detailed;
+        let mut md = detailed.info.to_markdown();
+
+        let factory = &detailed.factory;
+        md.push_str("\n## Factory\n\n| Field | Value |\n|---|---|\n");
+        md.push_str(&format!(
+            "| Vendor | {} |\n",
+            markdown_cell(&factory.vendor)
+        ));
+        md.push_str(&format!("| URL | {} |\n", markdown_cell(&factory.url)));
+        md.push_str(&format!("| Email | {} |\n", markdown_cell(&factory.email)));
+        md.push_str(&format!("| Flags | {:#x} |\n", factory.flags));
+
+        md.push_str("\n## Classes\n\n");
+        if detailed.classes.is_empty() {
+            md.push_str("_None reported._\n");
+        } else {
+            md.push_str(
+                "| Name | Category | Class ID | Version | Cardinality |\n|---|---|---|---|---|\n",
+            );
+            for class in &detailed.classes {
+                md.push_str(&format!(
+                    "| {} | {} | `{}` | {} | {} |\n",
+                    markdown_cell(&class.name),
+                    markdown_cell(&class.category),
+                    class.class_id,
+                    markdown_cell(&class.version),
+                    class.cardinality
+                ));
+            }
+        }
+
+        let buses = &detailed.buses;
+        let all_buses = [
+            ("Audio", "Input", &buses.audio_inputs),
+            ("Audio", "Output", &buses.audio_outputs),
+            ("MIDI", "Input", &buses.event_inputs),
+            ("MIDI", "Output", &buses.event_outputs),
+        ];
+        md.push_str("\n## Buses\n\n");
+        if all_buses.iter().all(|(_, _, list)| list.is_empty()) {
+            md.push_str("_None reported._\n");
+        } else {
+            md.push_str("| Kind | Direction | Name | Type | Channels |\n|---|---|---|---|---|\n");
+            for (kind, direction, list) in all_buses {
+                for bus in list.iter() {
+                    let bus_type = if bus.bus_type == 0 { "Main" } else { "Aux" };
+                    md.push_str(&format!(
+                        "| {kind} | {direction} | {} | {bus_type} | {} |\n",
+                        markdown_cell(&bus.name),
+                        bus.channel_count
+                    ));
+                }
+            }
+        }
+
+        md.push_str("\n## Parameters\n\n");
+        if self.parameters.is_empty() {
+            md.push_str("_None reported._\n");
+        } else {
+            md.push_str("| Name | ID | Range | Default | Units |\n|---|---|---|---|---|\n");
+            for param in &self.parameters {
+                md.push_str(&format!(
+                    "| {} | {} | {:.3}–{:.3} | {:.3} | {} |\n",
+                    markdown_cell(&param.name),
+                    param.id,
+                    param.min,
+                    param.max,
+                    param.default,
+                    markdown_cell(&param.unit)
+                ));
+            }
+        }
+        md
+    }
+}
+
+/// Make `text` safe inside a Markdown table cell (or heading): escape `|`, flatten newlines.
+pub(crate) fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A standalone HTML report of `plugins`: one table per plugin listing every
+/// [`PluginInfo`] field. See [`crate::Vst3Host::export_all_plugins_html`].
+pub fn plugins_to_html(plugins: &[PluginInfo]) -> String {
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>VST3 Plugins</title>\n\
+         <style>body{font-family:sans-serif}table{border-collapse:collapse;margin-bottom:1.5em}\
+         th,td{border:1px solid #ccc;padding:2px 8px;text-align:left}</style>\n</head>\n<body>\n",
+    );
+    html.push_str(&format!("<h1>VST3 Plugins ({})</h1>\n", plugins.len()));
+    for plugin in plugins {
+        html.push_str(&format!(
+            "<h2>{}</h2>\n<table>\n",
+            escape_html(&plugin.name)
+        ));
+        let rows = [
+            ("Vendor", escape_html(&plugin.vendor)),
+            ("Version", escape_html(&plugin.version)),
+            ("Category", escape_html(&plugin.category())),
+            ("UID", escape_html(&plugin.uid)),
+            ("Path", escape_html(&plugin.path.to_string_lossy())),
+            ("Audio inputs", plugin.audio_inputs.to_string()),
+            ("Audio outputs", plugin.audio_outputs.to_string()),
+            ("MIDI input", yes_no(plugin.has_midi_input).to_string()),
+            ("MIDI output", yes_no(plugin.has_midi_output).to_string()),
+            ("Editor", yes_no(plugin.has_gui).to_string()),
+        ];
+        for (field, value) in rows {
+            html.push_str(&format!("<tr><th>{field}</th><td>{value}</td></tr>\n"));
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
 }
 
 /// Scan standard VST3 directories for plugins
@@ -143,6 +573,52 @@ pub fn scan_standard_paths() -> Vec<PathBuf> {
     paths
 }
 
+/// Directories plugins are installed into for every user of the machine. A copy of a plugin
+/// outside them (in the home directory or a custom scan path) is the user's own.
+const SYSTEM_PLUGIN_ROOTS: &[&str] = &["/Library/", "/usr/", "/opt/", r"C:\Program Files"];
+
+/// Merge plugins found more than once — the same vendor, name, version and format installed in
+/// several scan paths, copied or symlinked — into one entry each, in the order they were first
+/// found. The entry kept is the most specific copy: one in a user directory rather than a
+/// system one, then the deepest path, then the first found. The paths of the others go into
+/// its [`PluginInfo::duplicates`].
+pub fn deduplicate_plugins(plugins: Vec<PluginInfo>) -> Vec<PluginInfo> {
+    type Key = (String, String, String, crate::plugin::PluginFormat);
+    let mut merged: Vec<PluginInfo> = Vec::new();
+    let mut index: std::collections::HashMap<Key, usize> = std::collections::HashMap::new();
+    for plugin in plugins {
+        let key = (
+            plugin.vendor.clone(),
+            plugin.name.clone(),
+            plugin.version.clone(),
+            plugin.format,
+        );
+        let Some(&i) = index.get(&key) else {
+            index.insert(key, merged.len());
+            merged.push(plugin);
+            continue;
+        };
+        let kept = &mut merged[i];
+        let mut other = plugin;
+        if path_specificity(&other.path) > path_specificity(&kept.path) {
+            std::mem::swap(kept, &mut other);
+        }
+        kept.duplicates.push(other.path);
+        kept.duplicates.append(&mut other.duplicates);
+    }
+    merged
+}
+
+/// How specific a plugin path is, for [`deduplicate_plugins`]: user directories beat system
+/// ones, then deeper paths beat shallower ones.
+fn path_specificity(path: &Path) -> (bool, usize) {
+    let text = path.to_string_lossy();
+    let system = SYSTEM_PLUGIN_ROOTS
+        .iter()
+        .any(|root| text.starts_with(root));
+    (!system, path.components().count())
+}
+
 /// Scan directories for VST3 plugins
 pub fn scan_directories(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
     let mut plugins = Vec::new();
@@ -183,6 +659,90 @@ fn scan_directory(dir: &Path, plugins: &mut Vec<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+/// Standard CLAP directories for this OS, after any listed in the `CLAP_PATH` environment
+/// variable (searched first, as the CLAP spec asks).
+pub fn clap_standard_paths() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::env::var_os("CLAP_PATH")
+        .map(|list| std::env::split_paths(&list).collect())
+        .unwrap_or_default();
+
+    #[cfg(target_os = "macos")]
+    {
+        paths.push(PathBuf::from("/Library/Audio/Plug-Ins/CLAP"));
+        if let Ok(home) = std::env::var("HOME") {
+            paths.push(PathBuf::from(format!("{home}/Library/Audio/Plug-Ins/CLAP")));
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        paths.push(PathBuf::from(r"C:\Program Files\Common Files\CLAP"));
+        if let Ok(local) = std::env::var("LOCALAPPDATA") {
+            paths.push(PathBuf::from(format!(r"{local}\Programs\Common\CLAP")));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        paths.push(PathBuf::from("/usr/lib/clap"));
+        if let Ok(home) = std::env::var("HOME") {
+            paths.push(PathBuf::from(format!("{home}/.clap")));
+        }
+    }
+
+    paths
+}
+
+/// List the `.clap` plugins (files, or bundles on macOS) under `paths`, sorted and without
+/// duplicates. `paths` may also name `.clap` plugins directly. Only walks the filesystem;
+/// nothing is loaded. `.vst3` bundles and anything else that isn't a directory to search are
+/// skipped.
+pub fn scan_clap_directories(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut plugins = Vec::new();
+    for path in paths {
+        if path.extension().is_some_and(|ext| ext == "clap") && path.exists() {
+            plugins.push(path.clone());
+        } else {
+            scan_clap_directory(path, &mut plugins);
+        }
+    }
+    plugins.sort();
+    plugins.dedup();
+    plugins
+}
+
+fn scan_clap_directory(dir: &Path, plugins: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("clap") => plugins.push(path),
+            Some("vst3") => {}
+            _ if path.is_dir() => scan_clap_directory(&path, plugins),
+            _ => {}
+        }
+    }
+}
+
+/// Metadata for every plugin in the `.clap` files under `dirs` (see
+/// [`scan_clap_directories`]). A file can hold several plugins; each gets its own entry,
+/// with its CLAP id as [`PluginInfo::uid`]. Files that fail to load are logged and skipped.
+///
+/// Each file is loaded in-process to read its descriptors, so a crashing plugin takes the
+/// caller down with it.
+#[cfg(feature = "clap-support")]
+pub fn scan_for_clap_plugins(dirs: &[PathBuf]) -> Vec<PluginInfo> {
+    let mut plugins = Vec::new();
+    for path in scan_clap_directories(dirs) {
+        match crate::clap_plugin::clap_plugin_info(&path) {
+            Ok(infos) => plugins.extend(infos),
+            Err(e) => log::warn!("Failed to get info for CLAP plugin {}: {e}", path.display()),
+        }
+    }
+    plugins
+}
+
 /// Get metadata for a VST3 plugin without fully loading it
 pub fn get_plugin_info(path: &Path) -> Result<PluginInfo> {
     use vst3::Steinberg::Vst::BusDirections_::*;
@@ -320,13 +880,15 @@ pub fn get_plugin_info(path: &Path) -> Result<PluginInfo> {
             },
             vendor,
             version,
-            category,
+            category_path: parse_category(&category),
             uid,
             audio_inputs,
             audio_outputs,
             has_midi_input,
             has_midi_output,
             has_gui,
+            format: crate::plugin::PluginFormat::Vst3,
+            duplicates: Vec::new(),
         })
     }
 }
@@ -509,6 +1071,43 @@ pub struct SafeDiscoveryReport {
     pub skipped: Vec<SafeDiscoverySkip>,
 }
 
+/// Result of an in-process scan by
+/// [`Vst3Host::discover_plugins_detailed`](crate::Vst3Host::discover_plugins_detailed): the
+/// plugins that were inspected, the ones that failed and why, and how long each one took.
+#[derive(Debug, Default)]
+pub struct DiscoveryResult {
+    /// Plugins inspected successfully.
+    pub successes: Vec<PluginInfo>,
+    /// Plugins that could not be inspected, with the error.
+    pub failures: Vec<(PathBuf, crate::Error)>,
+    /// How long each plugin took to inspect (or fail), in scan order. Plugins served from the
+    /// scan cache show up here too, with near-zero times.
+    pub timings: Vec<(PathBuf, Duration)>,
+}
+
+impl DiscoveryResult {
+    /// The `n` plugins that took longest to inspect, slowest first.
+    pub fn slowest_plugins(&self, n: usize) -> Vec<(&PathBuf, Duration)> {
+        let mut timings: Vec<(&PathBuf, Duration)> = self
+            .timings
+            .iter()
+            .map(|(path, took)| (path, *took))
+            .collect();
+        timings.sort_by_key(|&(_, took)| std::cmp::Reverse(took));
+        timings.truncate(n);
+        timings
+    }
+
+    /// How many plugins failed with each distinct error message.
+    pub fn error_summary(&self) -> std::collections::HashMap<String, usize> {
+        let mut summary = std::collections::HashMap::new();
+        for (_, error) in &self.failures {
+            *summary.entry(error.to_string()).or_insert(0) += 1;
+        }
+        summary
+    }
+}
+
 /// Locate the `vst3-host-probe` binary that does the risky introspection out-of-process.
 ///
 /// Mirrors the heuristic the isolation layer uses to find `vst3-host-helper` (same exe
@@ -728,6 +1327,75 @@ pub fn discover_plugins_safe(paths: &[PathBuf], timeout: Duration) -> SafeDiscov
     report
 }
 
+/// Plugins whose load hung in [`load_plugin_with_timeout`], refused until cleared.
+static QUARANTINE: std::sync::Mutex<std::collections::BTreeSet<PathBuf>> =
+    std::sync::Mutex::new(std::collections::BTreeSet::new());
+
+fn quarantine() -> std::sync::MutexGuard<'static, std::collections::BTreeSet<PathBuf>> {
+    QUARANTINE.lock().unwrap_or_else(|p| p.into_inner())
+}
+
+/// [`get_plugin_info`], giving up after `timeout` for plugins that hang in `dlopen` or
+/// `GetPluginFactory`.
+///
+/// The load runs on its own thread. If it doesn't finish in time the thread is left behind
+/// (it can't be stopped safely), the path is quarantined and [`Error::LoadTimeout`] is
+/// returned; later calls for that path fail at once with [`Error::Quarantined`] until
+/// [`clear_quarantine`]. For a plugin that might crash rather than hang, use
+/// [`probe_plugin_info_isolated`].
+///
+/// [`Error::LoadTimeout`]: crate::Error::LoadTimeout
+/// [`Error::Quarantined`]: crate::Error::Quarantined
+pub fn load_plugin_with_timeout(path: &Path, timeout: Duration) -> Result<PluginInfo> {
+    load_with_timeout(path, timeout, get_plugin_info)
+}
+
+fn load_with_timeout<F>(path: &Path, timeout: Duration, load: F) -> Result<PluginInfo>
+where
+    F: FnOnce(&Path) -> Result<PluginInfo> + Send + 'static,
+{
+    if is_quarantined(path) {
+        return Err(crate::Error::Quarantined(path.display().to_string()));
+    }
+    let (tx, rx) = std::sync::mpsc::channel();
+    let owned = path.to_path_buf();
+    std::thread::Builder::new()
+        .name("vst3-plugin-load".into())
+        .spawn(move || {
+            let _ = tx.send(load(&owned));
+        })?;
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+            log::warn!(
+                "Plugin {} did not load within {:?}; quarantined",
+                path.display(),
+                timeout
+            );
+            quarantine().insert(path.to_path_buf());
+            Err(crate::Error::LoadTimeout(path.display().to_string()))
+        }
+        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => Err(
+            crate::Error::PluginLoadFailed(format!("{}: loader thread panicked", path.display())),
+        ),
+    }
+}
+
+/// Whether `path` is quarantined after a hung [`load_plugin_with_timeout`].
+pub fn is_quarantined(path: &Path) -> bool {
+    quarantine().contains(path)
+}
+
+/// All plugin paths currently quarantined.
+pub fn quarantined_plugins() -> Vec<PathBuf> {
+    quarantine().iter().cloned().collect()
+}
+
+/// Let [`load_plugin_with_timeout`] try `path` again. Returns whether it was quarantined.
+pub fn clear_quarantine(path: &Path) -> bool {
+    quarantine().remove(path)
+}
+
 /// Platform-specific VST3 binary path resolution
 pub fn get_vst3_binary_path(bundle_path: &Path) -> Result<PathBuf> {
     // If it's already pointing to the binary, use it
@@ -817,26 +1485,149 @@ pub fn get_vst3_binary_path(bundle_path: &Path) -> Result<PathBuf> {
     )))
 }
 
+/// What a VST3 binary exports, from [`check_vst3_abi`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiInfo {
+    /// Exports the platform's module entry point (`ModuleEntry` on Linux, `bundleEntry` on
+    /// macOS, `InitDll` on Windows)
+    pub has_module_entry: bool,
+    /// Exports the matching exit point (`ModuleExit`, `bundleExit` or `ExitDll`)
+    pub has_module_exit: bool,
+    /// Declares a `Plugin Compatibility Class` (`IPluginCompatibility`, SDK 3.7.5 and later)
+    pub has_compat_interface: bool,
+    /// The SDK version string the plugin was built with (`VST 3.7.9`), when it embeds one
+    pub sdk_version: Option<String>,
+}
+
+/// Module entry points by platform, as (entry, exit) pairs. Every name is looked up so
+/// `check_vst3_abi` reports the same thing for a binary wherever it runs.
+const MODULE_ENTRY_POINTS: [(&[u8], &[u8]); 3] = [
+    (b"ModuleEntry", b"ModuleExit"),
+    (b"bundleEntry", b"bundleExit"),
+    (b"InitDll", b"ExitDll"),
+];
+
+/// The category string of an `IPluginCompatibility` class (`kPluginCompatibilityClass`).
+const COMPAT_CLASS_CATEGORY: &[u8] = b"Plugin Compatibility Class";
+
+/// Check that the VST3 binary at `path` (a bundle or the binary inside it) exports what a
+/// host needs before loading it: `GetPluginFactory` (required) and the optional module
+/// entry and exit points. The binary is `dlopen`ed but none of its exports are called; the
+/// compatibility class and SDK version are read from the strings it embeds, so nothing runs
+/// beyond the library's static initializers.
+///
+/// Fails with [`Error::InvalidPlugin`] when there is no `GetPluginFactory` export.
+///
+/// [`Error::InvalidPlugin`]: crate::Error::InvalidPlugin
+pub fn check_vst3_abi(path: &str) -> Result<AbiInfo> {
+    let binary = plugin_binary(Path::new(path));
+    let library = open_library(&binary)?;
+    let exports = |name: &[u8]| unsafe { library.get::<*const std::ffi::c_void>(name).is_ok() };
+    let bytes = std::fs::read(&binary).unwrap_or_default();
+    abi_info(exports, &bytes)
+}
+
+/// `dlopen` the VST3 binary at `path` (a bundle or the binary inside it) and check it exports
+/// `GetPluginFactory`, returning the library so a caller about to load the plugin can keep it
+/// mapped rather than having it unloaded and loaded again. Unlike [`check_vst3_abi`], the
+/// binary's contents aren't read.
+pub(crate) fn open_checked_library(path: &Path) -> Result<libloading::Library> {
+    let library = open_library(&plugin_binary(path))?;
+    if unsafe { library.get::<*const std::ffi::c_void>(b"GetPluginFactory") }.is_err() {
+        return Err(no_factory_export());
+    }
+    Ok(library)
+}
+
+/// The binary inside the bundle at `path`, or `path` itself.
+fn plugin_binary(path: &Path) -> PathBuf {
+    get_vst3_binary_path(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn open_library(binary: &Path) -> Result<libloading::Library> {
+    // SAFETY: loading a plugin binary runs its static initializers, as loading it to host it
+    // would; no symbol is called.
+    unsafe { libloading::Library::new(binary) }
+        .map_err(|e| crate::Error::PluginLoadFailed(format!("{}: {}", binary.display(), e)))
+}
+
+fn no_factory_export() -> crate::Error {
+    crate::Error::InvalidPlugin("No GetPluginFactory export".to_string())
+}
+
+/// The [`AbiInfo`] of a binary whose exports `exports` answers for and whose bytes are
+/// `data`.
+fn abi_info(exports: impl Fn(&[u8]) -> bool, data: &[u8]) -> Result<AbiInfo> {
+    if !exports(b"GetPluginFactory") {
+        return Err(no_factory_export());
+    }
+    Ok(AbiInfo {
+        has_module_entry: MODULE_ENTRY_POINTS.iter().any(|(entry, _)| exports(entry)),
+        has_module_exit: MODULE_ENTRY_POINTS.iter().any(|(_, exit)| exports(exit)),
+        has_compat_interface: memchr::memmem::find(data, COMPAT_CLASS_CATEGORY).is_some(),
+        sdk_version: embedded_sdk_version(data),
+    })
+}
+
+/// The first `VST 3.x.y` string in `data`: the SDK's `kVstVersionString`, which plugins
+/// report as their classes' `sdkVersion`.
+fn embedded_sdk_version(data: &[u8]) -> Option<String> {
+    const PREFIX: &[u8] = b"VST 3.";
+    memchr::memmem::find_iter(data, PREFIX).find_map(|start| {
+        let rest = &data[start + PREFIX.len()..];
+        let len = rest
+            .iter()
+            .take_while(|b| b.is_ascii_digit() || **b == b'.')
+            .count();
+        let version = &rest[..len];
+        if !version.first().is_some_and(u8::is_ascii_digit) {
+            return None;
+        }
+        let version = std::str::from_utf8(version).ok()?.trim_end_matches('.');
+        Some(format!("VST 3.{version}"))
+    })
+}
+
+#[cfg(test)]
+mod abi_tests {
+    use super::*;
+
+    #[test]
+    fn embedded_strings_give_the_sdk_version_and_compat_class() {
+        let exports = |name: &[u8]| name == b"GetPluginFactory" || name == b"bundleEntry";
+        let data = b"\0VST 3.x\0VST 3.7.12.\0Plugin Compatibility Class\0";
+        let info = abi_info(exports, data).unwrap();
+        assert!(info.has_module_entry);
+        assert!(!info.has_module_exit);
+        assert!(info.has_compat_interface);
+        assert_eq!(info.sdk_version.as_deref(), Some("VST 3.7.12"));
+
+        assert!(matches!(
+            abi_info(|_| false, data),
+            Err(crate::Error::InvalidPlugin(_))
+        ));
+    }
+}
+
 #[cfg(test)]
 mod report_tests {
     use super::*;
     use crate::plugin::PluginInfo;
 
-    #[test]
-    fn plugin_report_serializes_and_round_trips() {
-        let detail = DetailedPluginInfo {
+    fn dexed() -> DetailedPluginInfo {
+        DetailedPluginInfo {
             info: PluginInfo {
                 path: std::path::PathBuf::from("/x/Dexed.vst3"),
                 name: "Dexed".into(),
                 vendor: "Digital Suburban".into(),
                 version: "1.0.0".into(),
-                category: "Instrument|Synth".into(),
+                category_path: parse_category("Instrument|Synth"),
                 uid: "ABCD".into(),
-                audio_inputs: 0,
                 audio_outputs: 1,
                 has_midi_input: true,
                 has_midi_output: true,
                 has_gui: true,
+                ..Default::default()
             },
             factory: FactoryInfo {
                 vendor: "Digital Suburban".into(),
@@ -847,14 +1638,365 @@ mod report_tests {
                 ..Default::default()
             }],
             buses: BusLayout::default(),
-        };
-        let report = PluginReport::new(detail, Vec::new());
+        }
+    }
+
+    #[test]
+    fn plugin_report_serializes_and_round_trips() {
+        let report = PluginReport::new(dexed(), Vec::new());
         let json = report.to_json().expect("to_json");
         // The export round-trips and preserves the accurate metadata.
         let back: PluginReport = serde_json::from_str(&json).expect("round-trip");
         assert_eq!(back.detailed.info.name, "Dexed");
-        assert_eq!(back.detailed.info.category, "Instrument|Synth");
+        assert_eq!(back.detailed.info.category(), "Instrument|Synth");
         assert!(back.detailed.info.has_midi_output);
         assert_eq!(back.detailed.classes.len(), 1);
     }
+
+    #[test]
+    fn markdown_export_has_name_parameter_rows_and_closed_headings() {
+        let cutoff = crate::parameters::Parameter {
+            id: 7,
+            name: "Cutoff".into(),
+            value: 0.5,
+            min: 0.0,
+            max: 1.0,
+            default: 0.75,
+            unit: "Hz".into(),
+            step_count: 0,
+            can_automate: true,
+            is_read_only: false,
+            is_bypass: false,
+            flags: 0,
+            unit_id: 0,
+        };
+        let md = PluginReport::new(dexed(), vec![cutoff]).to_markdown();
+
+        assert!(md.starts_with("# Dexed\n"));
+        assert!(md.contains("| Cutoff | 7 | 0.000–1.000 | 0.750 | Hz |"));
+        // The category's `|` separators don't break the table.
+        assert!(md.contains("| Category | Instrument\\|Synth |"));
+        for line in md.lines().filter(|l| l.starts_with('#')) {
+            let text = line.trim_start_matches('#');
+            assert!(text.starts_with(' ') && !text.trim().is_empty(), "{line:?}");
+        }
+    }
+
+    #[test]
+    fn html_export_escapes_every_plugin() {
+        let mut other = dexed().info;
+        other.name = "A<B> & C".into();
+        let html = plugins_to_html(&[dexed().info, other]);
+        assert!(html.contains("<h2>Dexed</h2>"));
+        assert!(html.contains("<h2>A&lt;B&gt; &amp; C</h2>"));
+        assert_eq!(html.matches("<table>").count(), 2);
+        assert!(html.contains("<tr><th>Category</th><td>Instrument|Synth</td></tr>"));
+    }
+}
+
+#[cfg(test)]
+mod category_tests {
+    use super::*;
+
+    fn plugin(name: &str, category: &str) -> PluginInfo {
+        PluginInfo {
+            path: PathBuf::from(format!("/x/{name}.vst3")),
+            name: name.into(),
+            category_path: parse_category(category),
+            audio_outputs: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn three_level_category_parses_into_its_levels() {
+        assert_eq!(
+            parse_category("Instrument|Synth|Virtual Analog"),
+            ["Instrument", "Synth", "Virtual Analog"]
+        );
+        assert_eq!(parse_category(" Fx | |Reverb|"), ["Fx", "Reverb"]);
+        assert!(parse_category("").is_empty());
+    }
+
+    #[test]
+    fn tree_nests_plugins_by_category_level() {
+        let plugins = [
+            plugin("Diva", "Instrument|Synth|Virtual Analog"),
+            plugin("Dexed", "Instrument|Synth"),
+            plugin("Valhalla", "Fx|Reverb"),
+            plugin("Mystery", ""),
+        ];
+        let tree = CategoryTree::from_plugins(&plugins);
+        assert_eq!(tree.plugin_count(), 4);
+        assert_eq!(
+            tree.children.keys().collect::<Vec<_>>(),
+            ["Fx", "Instrument"]
+        );
+        assert_eq!(tree.plugins[0].name, "Mystery");
+
+        let synths = tree.get(&["Instrument", "Synth"]).unwrap();
+        assert_eq!(synths.plugins[0].name, "Dexed");
+        assert_eq!(synths.plugin_count(), 2);
+        let analog: Vec<_> = synths.children["Virtual Analog"].iter_plugins().collect();
+        assert_eq!(analog[0].name, "Diva");
+        assert!(tree.get(&["Fx", "Delay"]).is_none());
+    }
+
+    #[test]
+    fn flat_category_string_still_deserializes() {
+        let mut json = serde_json::to_value(plugin("Dexed", "Instrument|Synth")).unwrap();
+        let fields = json.as_object_mut().unwrap();
+        fields.remove("category_path");
+        fields.insert("category".into(), "Instrument|Synth".into());
+        let info: PluginInfo = serde_json::from_value(json).unwrap();
+        assert_eq!(info.category_path, ["Instrument", "Synth"]);
+    }
+}
+
+#[cfg(test)]
+mod deduplicate_tests {
+    use super::*;
+
+    fn plugin(path: &str, name: &str, version: &str) -> PluginInfo {
+        PluginInfo {
+            path: PathBuf::from(path),
+            name: name.into(),
+            vendor: "Acme".into(),
+            version: version.into(),
+            category_path: parse_category("Fx"),
+            audio_inputs: 1,
+            audio_outputs: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn two_copies_of_a_plugin_become_one_entry() {
+        let plugins = deduplicate_plugins(vec![
+            plugin("/usr/lib/vst3/Delay.vst3", "Delay", "1.0"),
+            plugin("/home/ada/.vst3/Delay.vst3", "Delay", "1.0"),
+            plugin("/usr/lib/vst3/Reverb.vst3", "Reverb", "1.0"),
+        ]);
+        assert_eq!(plugins.len(), 2);
+        assert_eq!(plugins[0].name, "Delay");
+        // The copy in the user directory wins over the system one.
+        assert_eq!(plugins[0].path, PathBuf::from("/home/ada/.vst3/Delay.vst3"));
+        assert_eq!(plugins[0].duplicates.len(), 1);
+        assert_eq!(
+            plugins[0].duplicates[0],
+            PathBuf::from("/usr/lib/vst3/Delay.vst3")
+        );
+        assert!(plugins[1].duplicates.is_empty());
+    }
+
+    #[test]
+    fn different_versions_are_kept_apart() {
+        let plugins = deduplicate_plugins(vec![
+            plugin("/usr/lib/vst3/Delay.vst3", "Delay", "1.0"),
+            plugin("/home/ada/.vst3/Delay.vst3", "Delay", "2.0"),
+        ]);
+        assert_eq!(plugins.len(), 2);
+    }
+}
+
+#[cfg(test)]
+mod scan_cache_tests {
+    use super::*;
+
+    fn fake_plugin(name: &str, contents: &[u8]) -> PluginInfo {
+        let path = std::env::temp_dir().join(format!("vh_{name}_{}.vst3", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        PluginInfo {
+            path,
+            name: name.into(),
+            category_path: parse_category("Fx"),
+            audio_inputs: 1,
+            audio_outputs: 1,
+            ..Default::default()
+        }
+    }
+
+    fn rewrite_keeping_mtime(path: &Path, contents: &[u8]) {
+        let mtime = std::fs::metadata(path).unwrap().modified().unwrap();
+        std::fs::write(path, contents).unwrap();
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(mtime).unwrap();
+    }
+
+    #[test]
+    fn changed_contents_with_the_same_mtime_need_a_rescan() {
+        let info = fake_plugin("rescan", b"version one");
+        let path = info.path.clone();
+        let mut cache = ScanCache::new();
+        cache.insert(info).unwrap();
+        assert!(cache.fresh_info(&path).is_some());
+
+        // Same size, same mtime, different bytes: only the hash notices.
+        rewrite_keeping_mtime(&path, b"version two");
+        let entry = cache.get(&path).unwrap();
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().modified().unwrap(),
+            entry.mtime
+        );
+        assert!(!cache.is_fresh(&path, entry));
+        let checked = vec![path.clone()];
+        assert_eq!(cache.stale_paths(&checked), checked);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn hash_only_mode_ignores_a_touched_mtime() {
+        let info = fake_plugin("touched", b"same bytes");
+        let path = info.path.clone();
+        let mut cache = ScanCache::new();
+        cache.insert(info).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap();
+
+        assert!(cache.fresh_info(&path).is_none());
+        cache.hash_only_mode = true;
+        assert!(cache.fresh_info(&path).is_some());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn stale_paths_are_found_in_the_background() {
+        let info = fake_plugin("background", b"cached");
+        let path = info.path.clone();
+        let uncached = std::env::temp_dir().join("vh_not_cached.vst3");
+        let mut cache = ScanCache::new();
+        cache.insert(info).unwrap();
+        let stale = cache
+            .stale_paths_in_background(vec![path.clone(), uncached.clone()])
+            .recv()
+            .unwrap();
+        assert_eq!(stale, [uncached]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn scan_cache_round_trips_through_a_file() {
+        let info = fake_plugin("saved", b"saved plugin");
+        let plugin_path = info.path.clone();
+        let mut cache = ScanCache::with_hash_only_mode(true);
+        cache.insert(info).unwrap();
+        let file = std::env::temp_dir().join(format!("vh_scan_cache_{}.json", std::process::id()));
+        cache.save(&file).unwrap();
+        let back = ScanCache::load(&file).unwrap();
+        let _ = std::fs::remove_file(&file);
+
+        assert!(back.hash_only_mode);
+        assert_eq!(back.len(), 1);
+        assert_eq!(
+            back.get(&plugin_path).unwrap().content_hash,
+            hash_bundle(&plugin_path).unwrap()
+        );
+        assert!(back.fresh_info(&plugin_path).is_some());
+        let _ = std::fs::remove_file(&plugin_path);
+    }
+}
+
+#[cfg(test)]
+mod load_timeout_tests {
+    use super::*;
+
+    fn info(path: &Path) -> PluginInfo {
+        PluginInfo {
+            path: path.to_path_buf(),
+            name: "Slow".into(),
+            audio_outputs: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn hung_factory_times_out_and_is_quarantined() {
+        let path = PathBuf::from("/x/hangs-in-get-plugin-factory.vst3");
+        let slow_factory = |path: &Path| {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok(info(path))
+        };
+        let started = std::time::Instant::now();
+        let result = load_with_timeout(&path, Duration::from_millis(50), slow_factory);
+        assert!(
+            matches!(result, Err(crate::Error::LoadTimeout(_))),
+            "{result:?}"
+        );
+        assert!(started.elapsed() < Duration::from_millis(400));
+        assert!(is_quarantined(&path));
+
+        // Refused straight away, without running the loader again.
+        let result = load_with_timeout(&path, Duration::from_secs(1), |_| unreachable!());
+        assert!(
+            matches!(result, Err(crate::Error::Quarantined(_))),
+            "{result:?}"
+        );
+
+        assert!(clear_quarantine(&path));
+        let loaded = load_with_timeout(&path, Duration::from_secs(1), |p| Ok(info(p))).unwrap();
+        assert_eq!(loaded.path, path);
+    }
+
+    #[test]
+    fn panicking_loader_is_a_load_failure() {
+        let path = PathBuf::from("/x/panics-while-loading.vst3");
+        let result = load_with_timeout(&path, Duration::from_secs(1), |_| panic!("bad plugin"));
+        assert!(matches!(result, Err(crate::Error::PluginLoadFailed(_))));
+        assert!(!is_quarantined(&path));
+    }
+}
+
+#[cfg(test)]
+mod discovery_result_tests {
+    use super::*;
+    use crate::Error;
+
+    fn result() -> DiscoveryResult {
+        DiscoveryResult {
+            successes: Vec::new(),
+            failures: vec![
+                (
+                    PathBuf::from("/a.vst3"),
+                    Error::PluginLoadFailed("no factory".into()),
+                ),
+                (
+                    PathBuf::from("/b.vst3"),
+                    Error::PluginLoadFailed("no factory".into()),
+                ),
+                (
+                    PathBuf::from("/c.vst3"),
+                    Error::PluginNotFound("/c.vst3".into()),
+                ),
+            ],
+            timings: vec![
+                (PathBuf::from("/a.vst3"), Duration::from_millis(20)),
+                (PathBuf::from("/b.vst3"), Duration::from_millis(900)),
+                (PathBuf::from("/c.vst3"), Duration::from_millis(5)),
+                (PathBuf::from("/d.vst3"), Duration::from_millis(150)),
+            ],
+        }
+    }
+
+    #[test]
+    fn slowest_plugins_come_first() {
+        let result = result();
+        let slowest = result.slowest_plugins(2);
+        assert_eq!(
+            slowest,
+            vec![
+                (&PathBuf::from("/b.vst3"), Duration::from_millis(900)),
+                (&PathBuf::from("/d.vst3"), Duration::from_millis(150)),
+            ]
+        );
+        assert_eq!(result.slowest_plugins(10).len(), 4);
+    }
+
+    #[test]
+    fn error_summary_counts_each_message() {
+        let summary = result().error_summary();
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary["Failed to load plugin: no factory"], 2);
+        assert_eq!(summary["Plugin not found: /c.vst3"], 1);
+    }
 }
@@ -6,7 +6,8 @@
 //! region you allocate. You provide the parent window's [`RawWindowHandle`] and a target
 //! rectangle (in logical points, top-left origin — the egui convention) each frame.
 //!
-//! Implemented on macOS (verified), Windows, and Linux/X11. Other platforms return an error
+//! Implemented on macOS (verified), Windows, and Linux (X11, and Wayland for plugins that
+//! support the `WaylandSurfaceID` platform type). Other platforms return an error
 //! from [`EmbeddedEditor::embed`]. Requires the `egui-widgets` feature.
 #![cfg(feature = "egui-widgets")]
 
@@ -32,6 +33,10 @@ pub struct EditorRect {
 /// the editor and remove the child view.
 pub struct EmbeddedEditor {
     plugin: Arc<Mutex<Plugin>>,
+    /// The area last offered to the editor by [`Self::show`], in points.
+    offered: Option<(i32, i32)>,
+    /// The editor's size: negotiated by the last resize, or its own size before that.
+    size: Option<(i32, i32)>,
     #[cfg(target_os = "macos")]
     inner: macos::MacEmbed,
     #[cfg(target_os = "windows")]
@@ -53,17 +58,17 @@ impl EmbeddedEditor {
         #[cfg(target_os = "macos")]
         {
             let inner = macos::MacEmbed::new(&plugin, parent, rect)?;
-            Ok(Self { plugin, inner })
+            Ok(Self::new(plugin, inner))
         }
         #[cfg(target_os = "windows")]
         {
             let inner = windows::WinEmbed::new(&plugin, parent, rect)?;
-            Ok(Self { plugin, inner })
+            Ok(Self::new(plugin, inner))
         }
         #[cfg(target_os = "linux")]
         {
             let inner = linux::LinuxEmbed::new(&plugin, parent, rect)?;
-            Ok(Self { plugin, inner })
+            Ok(Self::new(plugin, inner))
         }
         #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
         {
@@ -74,6 +79,66 @@ impl EmbeddedEditor {
         }
     }
 
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+    fn new(
+        plugin: Arc<Mutex<Plugin>>,
+        #[cfg(target_os = "macos")] inner: macos::MacEmbed,
+        #[cfg(target_os = "windows")] inner: windows::WinEmbed,
+        #[cfg(target_os = "linux")] inner: linux::LinuxEmbed,
+    ) -> Self {
+        let size = plugin.lock().ok().and_then(|p| p.get_editor_size().ok());
+        Self {
+            plugin,
+            offered: None,
+            size,
+            inner,
+        }
+    }
+
+    /// Tell the editor its area is now `width`×`height` points. The plugin may adjust that to
+    /// a size it supports; the size actually applied is returned, and is what
+    /// [`Self::show`] allocates. See [`Plugin::resize_editor`].
+    pub fn notify_resize(&mut self, width: i32, height: i32) -> Result<(i32, i32)> {
+        let size = self
+            .plugin
+            .lock()
+            .map_err(|_| Error::Other("plugin lock poisoned".to_string()))?
+            .resize_editor(width, height)?;
+        self.size = Some(size);
+        Ok(size)
+    }
+
+    /// Lay the editor out in the space left in `ui`. Call it every frame: when the available
+    /// area changes size (e.g. the host window was resized) the editor is asked to resize to
+    /// it with [`Self::notify_resize`], and the editor is positioned over the area it ends up
+    /// with.
+    ///
+    /// The area only senses hover, so egui never claims the clicks and drags meant for the
+    /// native editor view on top of it.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> egui::Response {
+        let available = ui.available_size();
+        let offered = (available.x.floor() as i32, available.y.floor() as i32);
+        if offered.0 > 0 && offered.1 > 0 && self.offered != Some(offered) {
+            self.offered = Some(offered);
+            if let Err(e) = self.notify_resize(offered.0, offered.1) {
+                // Fixed-size or isolated editors keep their size.
+                log::debug!("editor resize to {}x{}: {e}", offered.0, offered.1);
+            }
+        }
+        let (width, height) = self.size.unwrap_or(offered);
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(width.max(1) as f32, height.max(1) as f32),
+            egui::Sense::hover(),
+        );
+        self.set_rect(EditorRect {
+            x: rect.left(),
+            y: rect.top(),
+            width: rect.width(),
+            height: rect.height(),
+        });
+        response
+    }
+
     /// Reposition/resize the embedded editor to track `rect`. Call each frame so the editor
     /// follows the host layout (scroll, window resize). No-op off macOS.
     pub fn set_rect(&self, rect: EditorRect) {
@@ -275,10 +340,14 @@ mod linux {
     use super::*;
     use xcb::{x, Xid, XidNew};
 
-    /// A plugin editor embedded as a child X11 window of the host window.
-    pub struct LinuxEmbed {
-        connection: xcb::Connection,
-        child: x::Window,
+    /// A plugin editor embedded into the host window: as a child X11 window, or directly
+    /// onto the host's Wayland surface (the plugin creates and owns its own subsurface).
+    pub enum LinuxEmbed {
+        X11 {
+            connection: xcb::Connection,
+            child: x::Window,
+        },
+        Wayland,
     }
 
     impl LinuxEmbed {
@@ -290,12 +359,21 @@ mod linux {
             let parent_id: u32 = match parent {
                 RawWindowHandle::Xcb(h) => h.window.get(),
                 RawWindowHandle::Xlib(h) => h.window as u32,
+                RawWindowHandle::Wayland(h) => return Self::new_wayland(plugin, h.surface),
                 _ => {
                     return Err(Error::Other(
-                        "expected an X11 (Xcb/Xlib) window handle for the parent".to_string(),
+                        "expected an X11 (Xcb/Xlib) or Wayland window handle for the parent"
+                            .to_string(),
                     ))
                 }
             };
+            let mut guard = plugin
+                .lock()
+                .map_err(|_| Error::Other("plugin lock poisoned".to_string()))?;
+            crate::window::ensure_linux_platform_type(
+                &guard,
+                crate::window::LinuxPlatformType::X11EmbedWindowID,
+            )?;
 
             let (connection, screen_number) = xcb::Connection::connect(None)
                 .map_err(|e| Error::Other(format!("Failed to connect to X server: {e}")))?;
@@ -330,22 +408,41 @@ mod linux {
             let _ = connection.flush();
 
             let handle = crate::plugin::WindowHandle::from_x11(child.resource_id());
-            if let Err(e) = plugin
-                .lock()
-                .map_err(|_| Error::Other("plugin lock poisoned".to_string()))?
-                .open_editor(handle)
-            {
+            if let Err(e) = guard.open_editor(handle) {
                 connection.send_request(&x::DestroyWindow { window: child });
                 let _ = connection.flush();
                 return Err(e);
             }
 
-            Ok(Self { connection, child })
+            Ok(Self::X11 { connection, child })
         }
 
+        fn new_wayland(
+            plugin: &Arc<Mutex<Plugin>>,
+            surface: std::ptr::NonNull<std::ffi::c_void>,
+        ) -> Result<Self> {
+            let mut guard = plugin
+                .lock()
+                .map_err(|_| Error::Other("plugin lock poisoned".to_string()))?;
+            crate::window::ensure_linux_platform_type(
+                &guard,
+                crate::window::LinuxPlatformType::WaylandSurfaceID,
+            )?;
+            // `surface` is the host's live `wl_surface*`; it outlives the editor because the
+            // host window owns both.
+            guard.open_editor(crate::plugin::WindowHandle::from_wayland_surface(
+                surface.as_ptr(),
+            ))?;
+            Ok(Self::Wayland)
+        }
+
+        /// On Wayland the plugin places its own subsurface, so only X11 tracks `rect`.
         pub fn set_rect(&self, rect: EditorRect) {
-            self.connection.send_request(&x::ConfigureWindow {
-                window: self.child,
+            let Self::X11 { connection, child } = self else {
+                return;
+            };
+            connection.send_request(&x::ConfigureWindow {
+                window: *child,
                 value_list: &[
                     x::ConfigWindow::X(rect.x as i32),
                     x::ConfigWindow::Y(rect.y as i32),
@@ -353,15 +450,16 @@ mod linux {
                     x::ConfigWindow::Height((rect.height as u32).max(1)),
                 ],
             });
-            let _ = self.connection.flush();
+            let _ = connection.flush();
         }
     }
 
     impl Drop for LinuxEmbed {
         fn drop(&mut self) {
-            self.connection
-                .send_request(&x::DestroyWindow { window: self.child });
-            let _ = self.connection.flush();
+            if let Self::X11 { connection, child } = self {
+                connection.send_request(&x::DestroyWindow { window: *child });
+                let _ = connection.flush();
+            }
         }
     }
 }
@@ -17,10 +17,21 @@ pub enum Error {
     #[error("Failed to load plugin: {0}")]
     PluginLoadFailed(String),
 
+    /// The file isn't a loadable VST3 plugin, e.g. it lacks the `GetPluginFactory` export
+    #[error("Invalid plugin: {0}")]
+    InvalidPlugin(String),
+
     /// Plugin crashed during operation
     #[error("Plugin crashed")]
     PluginCrashed,
 
+    /// The isolation helper died on a fatal signal and left a crash report behind
+    #[error("Helper process crashed: {report}")]
+    HelperCrash {
+        /// What the helper recorded as it went down.
+        report: crate::process_isolation::CrashReport,
+    },
+
     /// Plugin operation timed out
     #[error("Plugin operation timed out")]
     PluginTimeout,
@@ -33,6 +44,10 @@ pub enum Error {
     #[error("Audio backend error: {0}")]
     AudioBackendError(String),
 
+    /// A running audio stream reported a problem
+    #[error("Audio stream error: {0}")]
+    AudioStream(crate::stream_monitor::StreamError),
+
     /// MIDI error
     #[error("MIDI error: {0}")]
     MidiError(String),
@@ -41,10 +56,35 @@ pub enum Error {
     #[error("VST3 interface error: {0}")]
     InterfaceError(String),
 
+    /// The plugin has no edit controller, so nothing that needs one (parameters) is available
+    #[error("Plugin has no edit controller")]
+    NoController,
+
     /// Process isolation error
     #[error("Process isolation error: {0}")]
     ProcessError(String),
 
+    /// A plugin routing graph loops back on itself, so it has no processing order
+    #[error("Plugin graph contains a cycle")]
+    GraphCycle,
+
+    /// Loading a plugin didn't finish in time; it is quarantined from then on
+    #[error("Plugin load timed out: {0}")]
+    LoadTimeout(String),
+
+    /// A plugin quarantined after a hung load, refused until its quarantine is cleared
+    #[error("Plugin is quarantined after a hung load: {0}")]
+    Quarantined(String),
+
+    /// Neither the requested sample rate nor any the output device offers could be used
+    #[error("Sample rate {requested} Hz not supported; device offers {available:?}")]
+    SampleRateNotSupported {
+        /// The rate the host was configured with, in Hz.
+        requested: f64,
+        /// The rates the device offers, in Hz.
+        available: Vec<f64>,
+    },
+
     /// IO error
     #[error(transparent)]
     IoError(#[from] std::io::Error),
@@ -0,0 +1,385 @@
+//! General MIDI mode: one plugin instance per MIDI channel, mixed to a single output.
+//!
+//! [`Vst3Host::enable_gm_mode`](crate::Vst3Host::enable_gm_mode) loads sixteen instances of a
+//! plugin, each filtered to its own channel (see [`Plugin::set_midi_channel_filter`]), so a
+//! General MIDI keyboard or sequencer can drive a different sound per channel. The resulting
+//! [`GmBus`] routes incoming MIDI to the right instance and sums their outputs through a
+//! [`MixBus`] with per-channel volume, mute and solo.
+
+use crate::audio::AudioBuffers;
+use crate::error::{Error, Result};
+use crate::midi::{MidiChannel, MidiEvent};
+use crate::plugin::Plugin;
+
+/// Number of channels (and plugin instances) in a [`GmBus`].
+pub const GM_CHANNELS: usize = 16;
+
+/// A gain-summing mixer: each input has a gain, a mute and a solo switch. When any input is
+/// soloed, only soloed (and unmuted) inputs are heard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixBus {
+    gains: Vec<f32>,
+    muted: Vec<bool>,
+    soloed: Vec<bool>,
+}
+
+impl MixBus {
+    /// A mixer for `inputs` inputs, all at unity gain.
+    pub fn new(inputs: usize) -> Self {
+        Self {
+            gains: vec![1.0; inputs],
+            muted: vec![false; inputs],
+            soloed: vec![false; inputs],
+        }
+    }
+
+    /// Number of inputs
+    pub fn inputs(&self) -> usize {
+        self.gains.len()
+    }
+
+    /// Linear gain of `input` (1.0 if out of range)
+    pub fn gain(&self, input: usize) -> f32 {
+        self.gains.get(input).copied().unwrap_or(1.0)
+    }
+
+    /// Set the linear gain of `input` (negative values are treated as 0). Out-of-range
+    /// inputs are ignored.
+    pub fn set_gain(&mut self, input: usize, gain: f32) {
+        if let Some(g) = self.gains.get_mut(input) {
+            *g = gain.max(0.0);
+        }
+    }
+
+    /// Whether `input` is muted
+    pub fn is_muted(&self, input: usize) -> bool {
+        self.muted.get(input).copied().unwrap_or(false)
+    }
+
+    /// Mute or unmute `input`
+    pub fn set_muted(&mut self, input: usize, muted: bool) {
+        if let Some(m) = self.muted.get_mut(input) {
+            *m = muted;
+        }
+    }
+
+    /// Flip `input`'s mute switch, returning whether it is now muted.
+    pub fn toggle_mute(&mut self, input: usize) -> bool {
+        let muted = !self.is_muted(input);
+        self.set_muted(input, muted);
+        self.is_muted(input)
+    }
+
+    /// Whether `input` is soloed
+    pub fn is_soloed(&self, input: usize) -> bool {
+        self.soloed.get(input).copied().unwrap_or(false)
+    }
+
+    /// Solo or unsolo `input`
+    pub fn set_soloed(&mut self, input: usize, soloed: bool) {
+        if let Some(s) = self.soloed.get_mut(input) {
+            *s = soloed;
+        }
+    }
+
+    /// Whether `input` currently reaches the output, given the mute and solo switches.
+    pub fn is_audible(&self, input: usize) -> bool {
+        let any_solo = self.soloed.iter().any(|&s| s);
+        !self.is_muted(input) && (!any_solo || self.is_soloed(input))
+    }
+
+    /// Sum `inputs` (one `[channel][sample]` buffer set per mixer input) into `output`,
+    /// which is cleared first. Channels and samples beyond `output`'s size are dropped.
+    pub fn mix<'a>(
+        &self,
+        inputs: impl IntoIterator<Item = &'a [Vec<f32>]>,
+        output: &mut [Vec<f32>],
+    ) {
+        for ch in output.iter_mut() {
+            ch.fill(0.0);
+        }
+        for (input, channels) in inputs.into_iter().enumerate() {
+            if !self.is_audible(input) {
+                continue;
+            }
+            let gain = self.gain(input);
+            for (out, src) in output.iter_mut().zip(channels) {
+                for (o, &s) in out.iter_mut().zip(src) {
+                    *o += s * gain;
+                }
+            }
+        }
+    }
+
+    /// Render one `block_size`-frame block from each audible plugin (`plugins[i]` is input
+    /// `i`) and sum them into a new stereo buffer. Muted and un-soloed plugins are not
+    /// processed at all. The plugins receive silence on their audio inputs.
+    ///
+    /// Allocates each call; for a fixed set of instances on the audio thread, keep the
+    /// plugins in a [`GmBus`] instead, which reuses its buffers.
+    pub fn process<'a>(
+        &self,
+        plugins: impl IntoIterator<Item = &'a mut Plugin>,
+        block_size: usize,
+    ) -> Result<AudioBuffers> {
+        let mut sample_rate = None;
+        let mut rendered = Vec::new();
+        for (input, plugin) in plugins.into_iter().enumerate() {
+            sample_rate.get_or_insert(plugin.sample_rate());
+            if !self.is_audible(input) {
+                rendered.push(Vec::new());
+                continue;
+            }
+            let mut buffers = AudioBuffers::new(
+                plugin.info().audio_inputs as usize,
+                plugin.output_channel_count(),
+                block_size,
+                plugin.sample_rate(),
+            );
+            plugin.process_audio(&mut buffers)?;
+            rendered.push(buffers.outputs);
+        }
+        let sample_rate = sample_rate.unwrap_or(crate::audio::AudioConfig::default().sample_rate);
+        let mut output = AudioBuffers::new(0, 2, block_size, sample_rate);
+        self.mix(rendered.iter().map(Vec::as_slice), &mut output.outputs);
+        Ok(output)
+    }
+}
+
+/// One channel of a [`GmBus`]: its plugin instance and the buffers it renders into.
+struct GmStrip {
+    name: String,
+    plugin: Plugin,
+    buffers: AudioBuffers,
+    peak: f32,
+}
+
+/// Sixteen plugin instances, one per MIDI channel, mixed to one output. Built by
+/// [`Vst3Host::enable_gm_mode`](crate::Vst3Host::enable_gm_mode).
+///
+/// Channels are addressed by their 0-based index (0 = MIDI channel 1), like
+/// [`MidiChannel::from_index`].
+pub struct GmBus {
+    strips: Vec<GmStrip>,
+    mix: MixBus,
+}
+
+impl GmBus {
+    /// Wrap one plugin per channel; `plugins[i]` plays MIDI channel `i + 1`.
+    pub(crate) fn new(plugins: Vec<Plugin>) -> Self {
+        let strips: Vec<GmStrip> = plugins
+            .into_iter()
+            .enumerate()
+            .map(|(i, plugin)| {
+                let buffers = AudioBuffers::new(
+                    plugin.info().audio_inputs as usize,
+                    plugin.output_channel_count(),
+                    plugin.block_size(),
+                    plugin.sample_rate(),
+                );
+                GmStrip {
+                    name: format!("Ch {}", i + 1),
+                    plugin,
+                    buffers,
+                    peak: 0.0,
+                }
+            })
+            .collect();
+        let mix = MixBus::new(strips.len());
+        Self { strips, mix }
+    }
+
+    fn strip_mut(&mut self, channel: u8) -> Result<&mut GmStrip> {
+        self.strips
+            .get_mut(channel as usize)
+            .ok_or_else(|| Error::InvalidParameter(format!("no GM channel {channel}")))
+    }
+
+    /// Number of channels
+    pub fn channel_count(&self) -> usize {
+        self.strips.len()
+    }
+
+    /// The plugin instance playing `channel`
+    pub fn plugin(&self, channel: u8) -> Option<&Plugin> {
+        self.strips.get(channel as usize).map(|s| &s.plugin)
+    }
+
+    /// Mutable access to the plugin instance playing `channel`
+    pub fn plugin_mut(&mut self, channel: u8) -> Option<&mut Plugin> {
+        self.strips.get_mut(channel as usize).map(|s| &mut s.plugin)
+    }
+
+    /// Display name of `channel` ("Ch 1" … "Ch 16" until renamed)
+    pub fn name(&self, channel: u8) -> Option<&str> {
+        self.strips.get(channel as usize).map(|s| s.name.as_str())
+    }
+
+    /// Rename `channel`
+    pub fn set_name(&mut self, channel: u8, name: impl Into<String>) -> Result<()> {
+        self.strip_mut(channel)?.name = name.into();
+        Ok(())
+    }
+
+    /// The mixer summing the channels (volume, mute, solo per channel index)
+    pub fn mix_bus(&self) -> &MixBus {
+        &self.mix
+    }
+
+    /// Mutable access to the mixer
+    pub fn mix_bus_mut(&mut self) -> &mut MixBus {
+        &mut self.mix
+    }
+
+    /// Send a program change to the instance playing `channel`.
+    pub fn set_program(&mut self, channel: u8, program: u8) -> Result<()> {
+        let midi_channel = MidiChannel::from_index(channel)
+            .ok_or_else(|| Error::InvalidParameter(format!("no GM channel {channel}")))?;
+        self.strip_mut(channel)?
+            .plugin
+            .send_midi_event(MidiEvent::ProgramChange {
+                channel: midi_channel,
+                program: program.min(127),
+            })
+    }
+
+    /// Route `event` to the instance playing its channel.
+    pub fn send_midi_event(&mut self, event: MidiEvent) -> Result<()> {
+        self.send_midi_event_at(event, 0)
+    }
+
+    /// Route `event` to the instance playing its channel, `sample_offset` frames into the
+    /// next block.
+    pub fn send_midi_event_at(&mut self, event: MidiEvent, sample_offset: i32) -> Result<()> {
+        self.strip_mut(event.channel().as_index())?
+            .plugin
+            .send_midi_event_at(event, sample_offset)
+    }
+
+    /// Silence every instance (see [`Plugin::midi_panic`]).
+    pub fn midi_panic(&mut self) -> Result<()> {
+        for strip in &mut self.strips {
+            // Each instance only listens to its own channel, so bypass the filter.
+            let filter = strip.plugin.midi_channel_filter();
+            strip.plugin.set_midi_channel_filter(None);
+            let result = strip.plugin.midi_panic();
+            strip.plugin.set_midi_channel_filter(filter);
+            result?;
+        }
+        Ok(())
+    }
+
+    /// Start processing on every instance.
+    pub fn start_processing(&mut self) -> Result<()> {
+        for strip in &mut self.strips {
+            strip.plugin.start_processing()?;
+        }
+        Ok(())
+    }
+
+    /// Stop processing on every instance.
+    pub fn stop_processing(&mut self) -> Result<()> {
+        for strip in &mut self.strips {
+            strip.plugin.stop_processing()?;
+        }
+        Ok(())
+    }
+
+    /// Render one block from every instance and mix them into `buffers.outputs`. Input audio
+    /// in `buffers.inputs` is passed to every instance.
+    pub fn process_audio(&mut self, buffers: &mut AudioBuffers) -> Result<()> {
+        let frames = buffers.block_size;
+        for strip in &mut self.strips {
+            let own = &mut strip.buffers;
+            for (ch, dst) in own.inputs.iter_mut().enumerate() {
+                dst.resize(frames, 0.0);
+                let src = buffers.inputs.get(ch).map(Vec::as_slice).unwrap_or(&[]);
+                let n = frames.min(src.len());
+                dst[..n].copy_from_slice(&src[..n]);
+                dst[n..].fill(0.0);
+            }
+            for ch in &mut own.outputs {
+                ch.resize(frames, 0.0);
+                ch.fill(0.0);
+            }
+            own.block_size = frames;
+            strip.plugin.process_audio(own)?;
+            strip.peak = own
+                .outputs
+                .iter()
+                .flatten()
+                .map(|&x| if x.is_finite() { x.abs() } else { 0.0 })
+                .fold(0.0, f32::max);
+        }
+        self.mix.mix(
+            self.strips.iter().map(|s| s.buffers.outputs.as_slice()),
+            &mut buffers.outputs,
+        );
+        Ok(())
+    }
+
+    /// Each channel's output peak (linear, pre-mixer) from the last processed block.
+    pub fn levels(&self) -> Vec<f32> {
+        self.strips.iter().map(|s| s.peak).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(value: f32) -> Vec<Vec<f32>> {
+        vec![vec![value; 4]; 2]
+    }
+
+    #[test]
+    fn mix_sums_inputs_with_gain() {
+        let mut bus = MixBus::new(2);
+        bus.set_gain(1, 0.5);
+        let inputs = [input(0.25), input(1.0)];
+        let mut output = vec![vec![9.0; 4]; 2];
+        bus.mix(inputs.iter().map(Vec::as_slice), &mut output);
+        assert_eq!(output, vec![vec![0.75; 4]; 2]);
+    }
+
+    #[test]
+    fn two_half_scale_inputs_sum_to_full_scale() {
+        let bus = MixBus::new(2);
+        let inputs = [input(0.5), input(0.5)];
+        let mut output = vec![vec![0.0; 4]; 2];
+        bus.mix(inputs.iter().map(Vec::as_slice), &mut output);
+        assert_eq!(output, vec![vec![1.0; 4]; 2]);
+    }
+
+    #[test]
+    fn toggle_mute_flips_the_switch() {
+        let mut bus = MixBus::new(2);
+        assert!(bus.toggle_mute(1));
+        assert!(!bus.is_audible(1));
+        assert!(!bus.toggle_mute(1));
+        assert!(bus.is_audible(1));
+        // Out-of-range inputs have no switch to flip.
+        assert!(!bus.toggle_mute(5));
+    }
+
+    #[test]
+    fn mute_and_solo_pick_the_audible_inputs() {
+        let mut bus = MixBus::new(3);
+        bus.set_muted(0, true);
+        assert!(!bus.is_audible(0));
+        assert!(bus.is_audible(1) && bus.is_audible(2));
+
+        bus.set_soloed(2, true);
+        assert!(!bus.is_audible(1));
+        assert!(bus.is_audible(2));
+
+        // A muted input stays silent even when soloed.
+        bus.set_soloed(0, true);
+        assert!(!bus.is_audible(0));
+
+        let inputs = [input(1.0), input(1.0), input(0.5)];
+        let mut output = vec![vec![0.0; 4]; 2];
+        bus.mix(inputs.iter().map(Vec::as_slice), &mut output);
+        assert_eq!(output, vec![vec![0.5; 4]; 2]);
+    }
+}
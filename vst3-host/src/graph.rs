@@ -0,0 +1,559 @@
+//! Plugin routing graphs.
+//!
+//! An [`AudioGraph`] describes how several plugin instances are wired together: each
+//! [`GraphNode`] is one plugin with audio and MIDI connectors, and each [`GraphConnection`]
+//! runs from one node's output to another node's input of the same kind. The graph is plain
+//! serializable data (node positions included, so an editor can restore its layout);
+//! [`PluginChain::from_graph`] turns it into the order the plugins must be processed in, and
+//! [`PluginChain::process`] renders blocks through it on one shared [`TransportClock`].
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::{AudioBuffers, TransportClock};
+use crate::error::{Error, Result};
+use crate::plugin::Plugin;
+
+/// Identifies a node within one [`AudioGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct NodeId(pub u32);
+
+/// What a connector carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PortKind {
+    /// Audio buffers
+    Audio,
+    /// MIDI events
+    Midi,
+}
+
+/// What a node does with its inputs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NodeKind {
+    /// Runs the plugin at the node's `plugin_path`
+    #[default]
+    Plugin,
+    /// Sums its audio inputs through a [`MixBus`](crate::MixBus); no plugin is loaded
+    MixBus,
+}
+
+/// Which connectors a node exposes, usually derived from the plugin's bus layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodePorts {
+    /// Has an audio input
+    pub audio_in: bool,
+    /// Has an audio output
+    pub audio_out: bool,
+    /// Has a MIDI (event) input
+    pub midi_in: bool,
+    /// Has a MIDI (event) output
+    pub midi_out: bool,
+}
+
+impl NodePorts {
+    /// Every connector present; use when the plugin's buses aren't known.
+    pub const ALL: Self = Self {
+        audio_in: true,
+        audio_out: true,
+        midi_in: true,
+        midi_out: true,
+    };
+
+    /// Ports of a plugin with the given bus layout.
+    pub fn from_bus_layout(layout: &crate::discovery::BusLayout) -> Self {
+        Self {
+            audio_in: !layout.audio_inputs.is_empty(),
+            audio_out: !layout.audio_outputs.is_empty(),
+            midi_in: !layout.event_inputs.is_empty(),
+            midi_out: !layout.event_outputs.is_empty(),
+        }
+    }
+
+    /// Whether the input (`input = true`) or output connector of `kind` exists.
+    pub fn has(&self, kind: PortKind, input: bool) -> bool {
+        match (kind, input) {
+            (PortKind::Audio, true) => self.audio_in,
+            (PortKind::Audio, false) => self.audio_out,
+            (PortKind::Midi, true) => self.midi_in,
+            (PortKind::Midi, false) => self.midi_out,
+        }
+    }
+}
+
+impl Default for NodePorts {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// One plugin instance in the graph.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphNode {
+    /// Unique within the graph
+    pub id: NodeId,
+    /// Path of the `.vst3` bundle this node runs
+    pub plugin_path: String,
+    /// Display name
+    pub name: String,
+    /// The node's connectors
+    pub ports: NodePorts,
+    /// Editor position (top-left corner), in editor units
+    pub position: (f32, f32),
+    /// Plugin or mix bus (graphs saved before mix buses load as plugins)
+    #[serde(default)]
+    pub kind: NodeKind,
+}
+
+/// A wire from `from`'s output to `to`'s input, both of kind `kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GraphConnection {
+    /// Source node (its output connector)
+    pub from: NodeId,
+    /// Destination node (its input connector)
+    pub to: NodeId,
+    /// Audio or MIDI
+    pub kind: PortKind,
+}
+
+/// Plugin nodes and the connections between them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AudioGraph {
+    /// The nodes, in insertion order
+    pub nodes: Vec<GraphNode>,
+    /// The wires
+    pub connections: Vec<GraphConnection>,
+}
+
+impl AudioGraph {
+    /// Create an empty graph
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a node for the plugin at `plugin_path` and return its id.
+    pub fn add_node(
+        &mut self,
+        plugin_path: impl Into<String>,
+        name: impl Into<String>,
+        ports: NodePorts,
+        position: (f32, f32),
+    ) -> NodeId {
+        let id = NodeId(self.nodes.iter().map(|n| n.id.0 + 1).max().unwrap_or(0));
+        self.nodes.push(GraphNode {
+            id,
+            plugin_path: plugin_path.into(),
+            name: name.into(),
+            ports,
+            position,
+            kind: NodeKind::Plugin,
+        });
+        id
+    }
+
+    /// Add a mix bus node, which sums every audio wire into it, and return its id.
+    pub fn add_mix_bus(&mut self, name: impl Into<String>, position: (f32, f32)) -> NodeId {
+        let ports = NodePorts {
+            audio_in: true,
+            audio_out: true,
+            midi_in: false,
+            midi_out: false,
+        };
+        let id = self.add_node("", name, ports, position);
+        if let Some(node) = self.node_mut(id) {
+            node.kind = NodeKind::MixBus;
+        }
+        id
+    }
+
+    /// Remove a node and every connection touching it.
+    pub fn remove_node(&mut self, id: NodeId) {
+        self.nodes.retain(|n| n.id != id);
+        self.connections.retain(|c| c.from != id && c.to != id);
+    }
+
+    /// Look up a node
+    pub fn node(&self, id: NodeId) -> Option<&GraphNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    /// Look up a node for editing (e.g. to move it)
+    pub fn node_mut(&mut self, id: NodeId) -> Option<&mut GraphNode> {
+        self.nodes.iter_mut().find(|n| n.id == id)
+    }
+
+    /// Wire `from`'s `kind` output to `to`'s `kind` input.
+    ///
+    /// Fails with [`Error::InvalidParameter`] if either node is missing, lacks that
+    /// connector, or the wire would loop a node into itself, and with [`Error::GraphCycle`]
+    /// if it would close a feedback loop. Connecting an existing wire again is a no-op.
+    pub fn connect(&mut self, from: NodeId, to: NodeId, kind: PortKind) -> Result<()> {
+        let ports = |id: NodeId| {
+            self.node(id)
+                .map(|n| n.ports)
+                .ok_or_else(|| Error::InvalidParameter(format!("no graph node {}", id.0)))
+        };
+        if !ports(from)?.has(kind, false) {
+            return Err(Error::InvalidParameter(format!(
+                "node {} has no {kind:?} output",
+                from.0
+            )));
+        }
+        if !ports(to)?.has(kind, true) {
+            return Err(Error::InvalidParameter(format!(
+                "node {} has no {kind:?} input",
+                to.0
+            )));
+        }
+        if from == to {
+            return Err(Error::InvalidParameter(
+                "a node cannot be connected to itself".to_string(),
+            ));
+        }
+        let connection = GraphConnection { from, to, kind };
+        if self.connections.contains(&connection) {
+            return Ok(());
+        }
+        self.connections.push(connection);
+        if let Err(e) = self.topological_order() {
+            self.connections.pop();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Remove a wire, if present.
+    pub fn disconnect(&mut self, connection: &GraphConnection) {
+        self.connections.retain(|c| c != connection);
+    }
+
+    /// Node ids ordered so every node comes after all the nodes feeding it (ties broken by
+    /// id, so the order is stable). [`Error::GraphCycle`] if the connections loop.
+    pub fn topological_order(&self) -> Result<Vec<NodeId>> {
+        let mut incoming: BTreeMap<NodeId, usize> = self.nodes.iter().map(|n| (n.id, 0)).collect();
+        // Parallel audio + MIDI wires between the same pair count once.
+        let edges: BTreeSet<(NodeId, NodeId)> = self
+            .connections
+            .iter()
+            .filter(|c| incoming.contains_key(&c.from) && incoming.contains_key(&c.to))
+            .map(|c| (c.from, c.to))
+            .collect();
+        for (_, to) in &edges {
+            *incoming.entry(*to).or_default() += 1;
+        }
+
+        let mut ready: VecDeque<NodeId> = incoming
+            .iter()
+            .filter(|(_, n)| **n == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(id) = ready.pop_front() {
+            order.push(id);
+            for (_, to) in edges.iter().filter(|(from, _)| *from == id) {
+                let n = incoming.get_mut(to).expect("edge endpoints are nodes");
+                *n -= 1;
+                if *n == 0 {
+                    ready.push_back(*to);
+                }
+            }
+        }
+        if order.len() != self.nodes.len() {
+            return Err(Error::GraphCycle);
+        }
+        Ok(order)
+    }
+}
+
+/// One plugin in a [`PluginChain`], with the nodes whose output it consumes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainStep {
+    /// The graph node
+    pub node: NodeId,
+    /// Whether the step runs a plugin or sums its inputs
+    pub kind: NodeKind,
+    /// Path of the plugin to run (empty for a mix bus)
+    pub plugin_path: String,
+    /// Nodes whose audio output is summed into this step's input
+    pub audio_inputs: Vec<NodeId>,
+    /// Nodes whose MIDI output is merged into this step's input
+    pub midi_inputs: Vec<NodeId>,
+}
+
+/// The processing list for an [`AudioGraph`]: every plugin, in an order where each one's
+/// inputs have already been rendered.
+#[derive(Debug, Clone, Default)]
+pub struct PluginChain {
+    steps: Vec<ChainStep>,
+    clock: TransportClock,
+}
+
+/// Chains are equal when they process the same steps, whatever their clocks read.
+impl PartialEq for PluginChain {
+    fn eq(&self, other: &Self) -> bool {
+        self.steps == other.steps
+    }
+}
+
+impl PluginChain {
+    /// Build the processing list from `graph`'s topological order. [`Error::GraphCycle`] if
+    /// the graph loops.
+    pub fn from_graph(graph: &AudioGraph) -> Result<PluginChain> {
+        let sources = |node: NodeId, kind: PortKind| -> Vec<NodeId> {
+            graph
+                .connections
+                .iter()
+                .filter(|c| c.to == node && c.kind == kind)
+                .map(|c| c.from)
+                .collect()
+        };
+        let steps = graph
+            .topological_order()?
+            .into_iter()
+            .filter_map(|id| graph.node(id))
+            .map(|node| ChainStep {
+                node: node.id,
+                kind: node.kind,
+                plugin_path: node.plugin_path.clone(),
+                audio_inputs: sources(node.id, PortKind::Audio),
+                midi_inputs: sources(node.id, PortKind::Midi),
+            })
+            .collect();
+        Ok(PluginChain {
+            steps,
+            clock: TransportClock::new(),
+        })
+    }
+
+    /// The steps, in processing order
+    pub fn steps(&self) -> &[ChainStep] {
+        &self.steps
+    }
+
+    /// Just the node ids, in processing order
+    pub fn order(&self) -> Vec<NodeId> {
+        self.steps.iter().map(|s| s.node).collect()
+    }
+
+    /// The transport every plugin in the chain is positioned on
+    pub fn shared_transport(&self) -> &TransportClock {
+        &self.clock
+    }
+
+    /// Render one `frames`-sample block through every step in order and return each node's
+    /// output channels. A step's input is the sum of its audio sources' outputs; a mix bus
+    /// passes that sum straight on. `plugins` holds the processing plugin for each plugin node.
+    ///
+    /// Every plugin is moved to the [shared transport](Self::shared_transport) position before
+    /// it runs, so all of them see the same `continousTimeSamples`/`projectTimeSamples` for the
+    /// block; the clock then advances by `frames`.
+    pub fn process(
+        &self,
+        plugins: &mut BTreeMap<NodeId, Plugin>,
+        frames: usize,
+    ) -> Result<BTreeMap<NodeId, Vec<Vec<f32>>>> {
+        let position = self.clock.position();
+        let mut outputs: BTreeMap<NodeId, Vec<Vec<f32>>> = BTreeMap::new();
+        for step in &self.steps {
+            let input = mix(
+                step.audio_inputs.iter().filter_map(|id| outputs.get(id)),
+                frames,
+            );
+            let output = match step.kind {
+                NodeKind::MixBus => input,
+                NodeKind::Plugin => {
+                    let plugin = plugins.get_mut(&step.node).ok_or_else(|| {
+                        Error::Other(format!("No plugin for graph node {}", step.node.0))
+                    })?;
+                    let mut buffers = AudioBuffers::new(
+                        plugin.info().audio_inputs.max(1) as usize,
+                        plugin.output_channel_count().max(1),
+                        frames,
+                        plugin.sample_rate(),
+                    );
+                    for (dst, src) in buffers.inputs.iter_mut().zip(&input) {
+                        dst.copy_from_slice(src);
+                    }
+                    plugin.set_transport_position(position)?;
+                    plugin.process_audio(&mut buffers)?;
+                    buffers.outputs
+                }
+            };
+            outputs.insert(step.node, output);
+        }
+        self.clock.advance(frames);
+        Ok(outputs)
+    }
+}
+
+/// Sum `sources` channel by channel into `frames`-sample channels (as many as the widest
+/// source has).
+fn mix<'a>(sources: impl Iterator<Item = &'a Vec<Vec<f32>>>, frames: usize) -> Vec<Vec<f32>> {
+    let mut sum: Vec<Vec<f32>> = Vec::new();
+    for source in sources {
+        if sum.len() < source.len() {
+            sum.resize(source.len(), vec![0.0; frames]);
+        }
+        for (dst, src) in sum.iter_mut().zip(source) {
+            for (d, s) in dst.iter_mut().zip(src) {
+                *d += s;
+            }
+        }
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear() -> (AudioGraph, [NodeId; 3]) {
+        let mut graph = AudioGraph::new();
+        let synth = graph.add_node("/p/synth.vst3", "Synth", NodePorts::ALL, (0.0, 0.0));
+        let eq = graph.add_node("/p/eq.vst3", "EQ", NodePorts::ALL, (200.0, 0.0));
+        let verb = graph.add_node("/p/verb.vst3", "Reverb", NodePorts::ALL, (400.0, 0.0));
+        graph.connect(synth, eq, PortKind::Audio).unwrap();
+        graph.connect(eq, verb, PortKind::Audio).unwrap();
+        (graph, [synth, eq, verb])
+    }
+
+    #[test]
+    fn linear_graph_round_trips_through_json() {
+        let (graph, ids) = linear();
+        let json = serde_json::to_string(&graph).unwrap();
+        let restored: AudioGraph = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, graph);
+        assert_eq!(restored.topological_order().unwrap(), ids.to_vec());
+
+        let chain = PluginChain::from_graph(&restored).unwrap();
+        assert_eq!(chain.order(), ids.to_vec());
+        assert_eq!(chain.steps()[1].audio_inputs, vec![ids[0]]);
+        assert!(chain.steps()[0].audio_inputs.is_empty());
+    }
+
+    #[test]
+    fn cycles_are_rejected() {
+        let (mut graph, [synth, _, verb]) = linear();
+        assert!(matches!(
+            graph.connect(verb, synth, PortKind::Audio),
+            Err(Error::GraphCycle)
+        ));
+        // The rejected wire isn't kept; a hand-edited cyclic graph still fails to chain.
+        assert_eq!(graph.connections.len(), 2);
+        graph.connections.push(GraphConnection {
+            from: verb,
+            to: synth,
+            kind: PortKind::Midi,
+        });
+        assert!(matches!(
+            PluginChain::from_graph(&graph),
+            Err(Error::GraphCycle)
+        ));
+    }
+
+    #[test]
+    fn connections_must_match_existing_ports() {
+        let mut graph = AudioGraph::new();
+        let fx_ports = NodePorts {
+            midi_in: false,
+            midi_out: false,
+            ..NodePorts::ALL
+        };
+        let synth = graph.add_node("/p/synth.vst3", "Synth", NodePorts::ALL, (0.0, 0.0));
+        let fx = graph.add_node("/p/fx.vst3", "FX", fx_ports, (200.0, 0.0));
+        assert!(graph.connect(synth, fx, PortKind::Midi).is_err());
+        assert!(graph.connect(synth, fx, PortKind::Audio).is_ok());
+        assert!(graph.connect(fx, fx, PortKind::Audio).is_err());
+        assert!(graph.connect(synth, NodeId(99), PortKind::Audio).is_err());
+    }
+
+    #[test]
+    fn mix_bus_nodes_sum_parallel_plugins() {
+        let mut graph = AudioGraph::new();
+        let a = graph.add_node("/p/a.vst3", "A", NodePorts::ALL, (0.0, 0.0));
+        let b = graph.add_node("/p/b.vst3", "B", NodePorts::ALL, (0.0, 80.0));
+        let bus = graph.add_mix_bus("Bus", (200.0, 40.0));
+        graph.connect(a, bus, PortKind::Audio).unwrap();
+        graph.connect(b, bus, PortKind::Audio).unwrap();
+        assert!(graph.connect(a, bus, PortKind::Midi).is_err());
+
+        let chain = PluginChain::from_graph(&graph).unwrap();
+        let last = chain.steps().last().unwrap();
+        assert_eq!(last.node, bus);
+        assert_eq!(last.kind, NodeKind::MixBus);
+        assert_eq!(last.audio_inputs, vec![a, b]);
+    }
+
+    /// Records the `continousTimeSamples` it saw each block, then advances it the way the
+    /// host's process context does. Outputs its summed input plus `offset`.
+    struct Clocked {
+        time: i64,
+        offset: f32,
+        seen: std::sync::Arc<std::sync::Mutex<Vec<i64>>>,
+    }
+
+    impl crate::plugin::PluginInternal for Clocked {
+        fn set_transport_position(&mut self, samples: i64) -> Result<()> {
+            self.time = samples;
+            Ok(())
+        }
+        fn process(&mut self, buffers: &mut AudioBuffers) -> Result<()> {
+            self.seen.lock().unwrap().push(self.time);
+            let input: f32 = buffers.inputs.iter().map(|c| c[0]).sum();
+            for channel in &mut buffers.outputs {
+                channel.fill(input + self.offset);
+            }
+            self.time += buffers.block_size as i64;
+            Ok(())
+        }
+        crate::plugin::stub_plugin_internal!(except process);
+    }
+
+    fn clocked(time: i64, offset: f32) -> (Plugin, std::sync::Arc<std::sync::Mutex<Vec<i64>>>) {
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut plugin = crate::plugin::plugin_with(Box::new(Clocked {
+            time,
+            offset,
+            seen: seen.clone(),
+        }));
+        plugin.info.name = "Clocked".to_string();
+        plugin.info.audio_inputs = 1;
+        plugin.info.audio_outputs = 1;
+        plugin.info.has_midi_input = false;
+        plugin.is_processing = true;
+        plugin.block_size = 64;
+        (plugin, seen)
+    }
+
+    #[test]
+    fn chained_plugins_share_one_transport_position() {
+        let mut graph = AudioGraph::new();
+        let synth = graph.add_node("/p/synth.vst3", "Synth", NodePorts::ALL, (0.0, 0.0));
+        let fx = graph.add_node("/p/fx.vst3", "FX", NodePorts::ALL, (200.0, 0.0));
+        graph.connect(synth, fx, PortKind::Audio).unwrap();
+        let chain = PluginChain::from_graph(&graph).unwrap();
+
+        // The effect starts out with its own, drifted idea of the time.
+        let (synth_plugin, synth_seen) = clocked(0, 0.25);
+        let (fx_plugin, fx_seen) = clocked(5_000, 0.5);
+        let mut plugins = BTreeMap::from([(synth, synth_plugin), (fx, fx_plugin)]);
+        for _ in 0..3 {
+            let outputs = chain.process(&mut plugins, 64).unwrap();
+            assert_eq!(outputs[&fx][0][0], 0.75);
+        }
+
+        assert_eq!(*synth_seen.lock().unwrap(), [0, 64, 128]);
+        assert_eq!(*fx_seen.lock().unwrap(), [0, 64, 128]);
+        assert_eq!(chain.shared_transport().position(), 192);
+        assert!(chain.process(&mut BTreeMap::new(), 64).is_err());
+    }
+
+    #[test]
+    fn graphs_saved_before_mix_buses_load_plugin_nodes() {
+        let json = r#"{"nodes":[{"id":0,"plugin_path":"/p/a.vst3","name":"A",
+            "ports":{"audio_in":true,"audio_out":true,"midi_in":true,"midi_out":true},
+            "position":[0.0,0.0]}],"connections":[]}"#;
+        let graph: AudioGraph = serde_json::from_str(json).unwrap();
+        assert_eq!(graph.nodes[0].kind, NodeKind::Plugin);
+    }
+}
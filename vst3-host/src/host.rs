@@ -1,12 +1,23 @@
 //! VST3 host implementation
 
 use crate::{
-    audio::AudioConfig,
+    analysis::{BlockStatisticsTap, BlockStatsSnapshot, SpectrumAnalyzer, SpectrumTap},
+    audio::{AudioConfig, AutoLimiter, BusConfiguration, InputGainStage, WindowFunction},
+    backends::{AggregateBackend, DynAudioBackend},
+    discovery::{CategoryTree, DiscoveryResult, PluginAnnotation, ScanCache},
     error::{Error, Result},
-    plugin::{Plugin, PluginInfo, PluginInternal},
+    midi::{
+        Arpeggiator, CcBinding, ControllerPreset, KeyboardPreset, KeyboardSplit, MidiBinding,
+        MidiChannel, MidiEvent, PluginHandle, RoutingMatrix, DEFAULT_KEYBOARD_VELOCITY,
+    },
+    plugin::{LoadPhase, Plugin, PluginInfo, PluginInitFlags, PluginInternal},
+    preset::{MigrationStep, PresetLoader},
+    session::Session,
+    stream_monitor::{AudioStreamErrorHandler, AudioStreamStats, StreamMonitor},
 };
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 
 /// VST3 host instance
 pub struct Vst3Host {
@@ -28,6 +39,83 @@ pub struct Vst3Host {
     pub(crate) auto_recover_max_retries: u32,
     /// Per-plugin timeout for the crash-resistant discovery probe ([`Self::discover_plugins_safe`]).
     pub(crate) probe_timeout: std::time::Duration,
+    /// State migrations registered per plugin name ([`Self::register_migration`]).
+    pub(crate) migrations: HashMap<String, Vec<MigrationStep>>,
+    /// Key-range routing applied by [`Self::route_midi`] ([`Self::set_keyboard_split`]).
+    pub(crate) keyboard_split: Option<KeyboardSplit>,
+    /// Channel fan-out applied by [`Self::route_midi`] ([`Self::set_midi_routing`]).
+    pub(crate) midi_routing: Option<RoutingMatrix>,
+    /// Output statistics published by the plugins this host loads ([`Self::block_statistics`]).
+    pub(crate) block_statistics: Arc<RwLock<BlockStatsSnapshot>>,
+    /// Output spectrum fed by the plugins this host loads ([`Self::spectrum`]).
+    pub(crate) spectrum: Arc<Mutex<SpectrumAnalyzer>>,
+    /// Errors and timing of the streams [`Self::play`] starts ([`Self::audio_stream_stats`]).
+    pub(crate) stream_monitor: Arc<StreamMonitor>,
+    /// Backends [`Self::play`] fans out to instead of the default device
+    /// ([`Vst3HostBuilder::with_aggregate_backend`]).
+    pub(crate) aggregate_backend: Option<AggregateBackend>,
+    /// Plugins found by the last [`Self::discover_plugins`]
+    pub(crate) discovered: Vec<PluginInfo>,
+    /// The plugin [`Self::auto_load_last_plugin`] loads: the last one [`Self::load_plugin`]
+    /// loaded, or [`Vst3HostBuilder::last_loaded_plugin`].
+    pub(crate) last_loaded_plugin: Option<PathBuf>,
+    /// How plugins loaded in-process are told who's hosting them.
+    pub(crate) host_identity: HostIdentity,
+    /// Whether [`Self::discover_plugins`] merges copies of the same plugin
+    /// ([`Vst3HostBuilder::deduplicate_plugins`]).
+    pub(crate) deduplicate_plugins: bool,
+    /// Arpeggiator [`Self::play`] runs over incoming notes ([`Self::enable_arpeggiator`]).
+    pub(crate) arpeggiator: Option<Arpeggiator>,
+    /// CC → parameter bindings ([`Self::load_controller_preset`], [`Self::set_cc_bindings`]).
+    pub(crate) cc_bindings: Vec<CcBinding>,
+    /// MIDI port name the CC bindings were made for (empty = any controller).
+    pub(crate) controller_device: String,
+    /// Channel the virtual keyboard plays on ([`Self::set_keyboard_channel`]).
+    pub(crate) keyboard_channel: MidiChannel,
+    /// Octave shift of the virtual keyboard ([`Self::set_keyboard_octave_shift`]).
+    pub(crate) keyboard_octave_shift: i8,
+    /// Velocity of notes played on the virtual keyboard ([`Self::set_keyboard_velocity`]).
+    pub(crate) keyboard_velocity: u8,
+    /// Saved virtual keyboard setups ([`Self::save_keyboard_preset`]).
+    pub(crate) keyboard_presets: Vec<KeyboardPreset>,
+    /// The user's notes per plugin path ([`Self::annotate_plugin`]).
+    pub(crate) annotations: HashMap<PathBuf, PluginAnnotation>,
+    /// Where annotations are saved ([`Vst3HostBuilder::annotations_file`]); in memory only if
+    /// `None`.
+    pub(crate) annotations_path: Option<PathBuf>,
+    /// Fixed input gain for [`Self::play_with_input`] ([`Self::set_input_gain`]).
+    pub(crate) input_gain_db: f64,
+    /// Auto-gain target level when auto-gain is on ([`Self::set_auto_gain`]).
+    pub(crate) auto_gain_target_db: Option<f64>,
+    /// Put an [`AutoLimiter`] after played plugins ([`Vst3HostBuilder::auto_limiter`]).
+    pub(crate) auto_limiter: bool,
+    /// The limiter's threshold in dBFS ([`Self::set_limiter_threshold`]).
+    pub(crate) limiter_threshold_db: f64,
+    /// Metadata of plugins already scanned, reused while their binaries are unchanged.
+    pub(crate) scan_cache: ScanCache,
+    /// Output device [`Self::play`] uses instead of the system default
+    /// ([`Self::set_audio_device`]).
+    pub(crate) audio_device: Option<String>,
+    /// The rate [`Self::play`] settled on with the output device ([`Self::actual_sample_rate`]);
+    /// `None` until something has played.
+    pub(crate) actual_sample_rate: RwLock<Option<f64>>,
+    /// Initialization steps to skip, by plugin path substring
+    /// ([`Self::set_plugin_init_flags`]).
+    pub(crate) init_flags: HashMap<String, PluginInitFlags>,
+    /// Where init flags are saved ([`Vst3HostBuilder::init_flags_file`]); in memory only if
+    /// `None`.
+    pub(crate) init_flags_path: Option<PathBuf>,
+    /// Where in-process loads record their current phase
+    /// ([`Vst3HostBuilder::load_journal`]).
+    pub(crate) load_journal: Option<PathBuf>,
+    /// Named bus configurations per plugin path ([`Self::add_bus_config_preset`]).
+    pub(crate) bus_config_presets: HashMap<PathBuf, Vec<(String, BusConfiguration)>>,
+    /// The preset applied to each plugin loaded ([`Self::set_bus_config_preset`]).
+    pub(crate) bus_config_preset: Option<String>,
+    /// How [`Self::open_recorder`] encodes samples ([`Vst3HostBuilder::recording_format`]).
+    pub(crate) recording_format: crate::audio::SampleFormat,
+    /// Known plugin quirks applied on load ([`Vst3HostBuilder::compatibility_db`]).
+    pub(crate) compatibility: crate::compatibility::CompatibilityDb,
 }
 
 impl Vst3Host {
@@ -60,30 +148,92 @@ impl Vst3Host {
     }
 
     /// Discover VST3 plugins in configured scan paths
+    ///
+    /// Plugins whose binaries haven't changed since an earlier scan come from the
+    /// [scan cache](Self::scan_cache) instead of being loaded again. Copies of a plugin found
+    /// in several paths are merged into one entry unless
+    /// [`Vst3HostBuilder::deduplicate_plugins`] turned that off.
     pub fn discover_plugins(&mut self) -> Result<Vec<PluginInfo>> {
-        let mut all_paths = self.custom_paths.clone();
+        self.discover_plugins_with_callback(|_| {})
+    }
+
+    /// Like [`Self::discover_plugins`], but always merges copies of the same plugin found in
+    /// several scan paths ([`deduplicate_plugins`](crate::discovery::deduplicate_plugins)): the
+    /// copy kept lists the others in [`PluginInfo::duplicates`].
+    pub fn discover_plugins_deduplicated(&mut self) -> Result<Vec<PluginInfo>> {
+        let result = self.scan_plugins(&mut |_| {})?;
+        let plugins = crate::discovery::deduplicate_plugins(result.successes);
+        self.discovered = plugins.clone();
+        Ok(plugins)
+    }
+
+    /// Like [`Self::discover_plugins`], but also reports which plugins failed (with the
+    /// error) and how long each one took to inspect, so slow or broken plugins can be found.
+    pub fn discover_plugins_detailed(&mut self) -> Result<DiscoveryResult> {
+        let mut result = self.scan_plugins(&mut |_| {})?;
+        result.successes = self.finish_discovery(result.successes);
+        Ok(result)
+    }
 
-        // Add system paths if enabled
+    /// Inspect every plugin in the scan paths, copies included, reporting each one to
+    /// `on_progress` as `Started`, then `Scanned` and `Found` or `Error` per plugin.
+    fn scan_plugins(
+        &mut self,
+        on_progress: &mut dyn FnMut(DiscoveryProgress),
+    ) -> Result<DiscoveryResult> {
+        let mut all_paths = self.custom_paths.clone();
         if self.scan_default_paths {
             all_paths.extend(crate::discovery::scan_standard_paths());
         }
-
-        // Scan directories for VST3 plugins
         let plugin_paths = crate::discovery::scan_directories(&all_paths)?;
+        let total = plugin_paths.len();
+
+        on_progress(DiscoveryProgress::Started {
+            total_plugins: total,
+        });
 
-        // Get plugin info for each found plugin
-        let mut plugins = Vec::new();
-        for path in plugin_paths {
-            match crate::discovery::get_plugin_info(&path) {
-                Ok(info) => plugins.push(info),
+        let mut result = DiscoveryResult::default();
+        for (index, path) in plugin_paths.into_iter().enumerate() {
+            let started = std::time::Instant::now();
+            let info = self.scanned_plugin_info(&path);
+            let elapsed = started.elapsed();
+            result.timings.push((path.clone(), elapsed));
+            on_progress(DiscoveryProgress::Scanned {
+                path: path.clone(),
+                elapsed,
+            });
+            match info {
+                Ok(info) => {
+                    on_progress(DiscoveryProgress::Found {
+                        plugin: info.clone(),
+                        current: index + 1,
+                        total,
+                    });
+                    result.successes.push(info);
+                }
                 Err(e) => {
                     log::warn!("Failed to get info for plugin {}: {}", path.display(), e);
-                    // Continue with other plugins
+                    on_progress(DiscoveryProgress::Error {
+                        path: path.display().to_string(),
+                        error: e.to_string(),
+                    });
+                    result.failures.push((path, e));
                 }
             }
         }
+        Ok(result)
+    }
 
-        Ok(plugins)
+    /// Merge copies of the same plugin unless [`Vst3HostBuilder::deduplicate_plugins`] turned
+    /// that off, and remember the result as the discovered plugins.
+    fn finish_discovery(&mut self, plugins: Vec<PluginInfo>) -> Vec<PluginInfo> {
+        let plugins = if self.deduplicate_plugins {
+            crate::discovery::deduplicate_plugins(plugins)
+        } else {
+            plugins
+        };
+        self.discovered = plugins.clone();
+        plugins
     }
 
     /// List VST3 bundle paths in the configured scan locations **without loading them**.
@@ -100,11 +250,70 @@ impl Vst3Host {
         crate::discovery::scan_directories(&all_paths).unwrap_or_default()
     }
 
+    /// List `.clap` plugin paths in the configured scan locations and the standard CLAP
+    /// directories ([`clap_standard_paths`](crate::discovery::clap_standard_paths)) **without
+    /// loading them**. The CLAP counterpart of [`Self::scan_plugin_paths`]; works without the
+    /// `clap-support` feature.
+    pub fn scan_clap_plugin_paths(&self) -> Vec<std::path::PathBuf> {
+        crate::discovery::scan_clap_directories(&self.clap_scan_dirs())
+    }
+
+    /// Discover VST3 plugins ([`Self::discover_plugins`]) and, with the `clap-support`
+    /// feature, CLAP plugins ([`scan_for_clap_plugins`](crate::discovery::scan_for_clap_plugins))
+    /// in the same locations plus the standard CLAP directories. Tell them apart by
+    /// [`PluginInfo::format`]; load CLAP ones with [`Self::load_clap_plugin`].
+    ///
+    /// Without the feature, CLAP plugins are only counted in the log.
+    pub fn discover_all_plugins(&mut self) -> Result<Vec<PluginInfo>> {
+        #[cfg(feature = "clap-support")]
+        let clap = crate::discovery::scan_for_clap_plugins(&self.clap_scan_dirs());
+        #[cfg(not(feature = "clap-support"))]
+        let clap = {
+            let skipped = self.scan_clap_plugin_paths().len();
+            if skipped > 0 {
+                log::info!("Skipped {skipped} CLAP plugin(s): built without clap-support");
+            }
+            Vec::new()
+        };
+        let mut plugins = self.discover_plugins()?;
+        plugins.extend(clap);
+        self.discovered = plugins.clone();
+        Ok(plugins)
+    }
+
+    /// Load a CLAP plugin at the host's sample rate and block size: the one with id
+    /// `plugin_id` ([`PluginInfo::uid`] from discovery), or the file's first plugin.
+    #[cfg(feature = "clap-support")]
+    pub fn load_clap_plugin(
+        &self,
+        path: &Path,
+        plugin_id: Option<&str>,
+    ) -> Result<crate::clap_plugin::ClapPlugin> {
+        crate::clap_plugin::ClapPlugin::load(
+            path,
+            plugin_id,
+            self.config.sample_rate,
+            self.config.block_size,
+        )
+    }
+
+    fn clap_scan_dirs(&self) -> Vec<std::path::PathBuf> {
+        let mut dirs = self.custom_paths.clone();
+        if self.scan_default_paths {
+            dirs.extend(crate::discovery::clap_standard_paths());
+        }
+        dirs
+    }
+
     /// Discover VST3 plugins, reporting progress through a callback.
     ///
     /// The callback receives [`DiscoveryProgress`] events: one `Started` at the
-    /// beginning, a `Found` or `Error` per candidate, and a final `Completed`.
-    /// Returns the successfully-inspected plugins, same as [`Self::discover_plugins`].
+    /// beginning, then per candidate a `Scanned` with how long it took to inspect followed by
+    /// its `Found` or `Error`, and a final `Completed`. Collecting the `Error` events gives a
+    /// UI its list of broken plugins as the scan goes.
+    /// Returns the successfully-inspected plugins, deduplicated the same way as
+    /// [`Self::discover_plugins`].
+    #[doc(alias = "discover_plugins_with_progress")]
     pub fn discover_plugins_with_callback<F>(
         &mut self,
         mut on_progress: F,
@@ -112,45 +321,71 @@ impl Vst3Host {
     where
         F: FnMut(DiscoveryProgress),
     {
-        let mut all_paths = self.custom_paths.clone();
+        let result = self.scan_plugins(&mut on_progress)?;
+        let plugins = self.finish_discovery(result.successes);
+        on_progress(DiscoveryProgress::Completed {
+            total_found: plugins.len(),
+        });
+        Ok(plugins)
+    }
 
-        if self.scan_default_paths {
-            all_paths.extend(crate::discovery::scan_standard_paths());
+    /// `path`'s metadata from the scan cache if still fresh, otherwise from the plugin itself
+    /// (and then cached).
+    fn scanned_plugin_info(&mut self, path: &Path) -> Result<PluginInfo> {
+        if let Some(info) = self.scan_cache.fresh_info(path) {
+            return Ok(info.clone());
         }
+        let info = crate::discovery::get_plugin_info(path)?;
+        if let Err(e) = self.scan_cache.insert(info.clone()) {
+            log::warn!("Failed to cache scan of {}: {}", path.display(), e);
+        }
+        Ok(info)
+    }
 
-        let plugin_paths = crate::discovery::scan_directories(&all_paths)?;
-        let total = plugin_paths.len();
+    /// Plugins scanned so far, with the binary stamps that decide when to scan them again.
+    /// Persist it with [`ScanCache::save`] and bring it back with [`Self::set_scan_cache`].
+    pub fn scan_cache(&self) -> &ScanCache {
+        &self.scan_cache
+    }
 
-        on_progress(DiscoveryProgress::Started {
-            total_plugins: total,
-        });
+    /// Replace the scan cache (e.g. with one loaded from disk). Keeps the host's
+    /// [hash-only setting](Vst3HostBuilder::scan_cache_hash_only).
+    pub fn set_scan_cache(&mut self, cache: ScanCache) {
+        let hash_only_mode = self.scan_cache.hash_only_mode;
+        self.scan_cache = cache;
+        self.scan_cache.hash_only_mode = hash_only_mode;
+    }
 
-        let mut plugins = Vec::new();
-        for (index, path) in plugin_paths.into_iter().enumerate() {
-            match crate::discovery::get_plugin_info(&path) {
-                Ok(info) => {
-                    on_progress(DiscoveryProgress::Found {
-                        plugin: info.clone(),
-                        current: index + 1,
-                        total,
-                    });
-                    plugins.push(info);
-                }
-                Err(e) => {
-                    log::warn!("Failed to get info for plugin {}: {}", path.display(), e);
-                    on_progress(DiscoveryProgress::Error {
-                        path: path.display().to_string(),
-                        error: e.to_string(),
-                    });
-                }
-            }
-        }
+    /// Move, link, disable or back up plugin bundles, keeping the scan cache in step.
+    pub fn plugin_manager(&mut self) -> crate::PluginManager<'_> {
+        crate::PluginManager::new(&mut self.scan_cache)
+    }
 
-        on_progress(DiscoveryProgress::Completed {
-            total_found: plugins.len(),
-        });
+    /// The plugins found by the last [`Self::discover_plugins`] (or
+    /// [`Self::discover_plugins_with_callback`]), grouped by category level. For a
+    /// [`Self::discover_plugins_safe`] report, use
+    /// [`CategoryTree::from_plugins`](crate::discovery::CategoryTree::from_plugins).
+    pub fn plugins_by_category_tree(&self) -> CategoryTree {
+        CategoryTree::from_plugins(&self.discovered)
+    }
 
-        Ok(plugins)
+    /// Write an HTML report of the discovered plugins to `path`: one table per plugin with
+    /// every [`PluginInfo`] field (see [`crate::discovery::plugins_to_html`]).
+    pub fn export_all_plugins_html(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, crate::discovery::plugins_to_html(&self.discovered))
+            .map_err(|e| Error::Other(format!("write plugin report: {e}")))
+    }
+
+    /// The discovered plugins in category `path` or below it — `&["Instrument"]` matches
+    /// every instrument, `&["Instrument", "Synth"]` only synths.
+    pub fn plugins_in_category(&self, path: &[&str]) -> Vec<&PluginInfo> {
+        self.discovered
+            .iter()
+            .filter(|p| {
+                p.category_path.len() >= path.len()
+                    && p.category_path.iter().zip(path).all(|(a, b)| a == b)
+            })
+            .collect()
     }
 
     /// Crash-resistantly discover plugins in the configured scan paths.
@@ -178,19 +413,132 @@ impl Vst3Host {
         crate::discovery::discover_plugins_safe(&all_paths, self.probe_timeout)
     }
 
-    /// Load a VST3 plugin
+    /// Let [`load_plugin_with_timeout`](crate::discovery::load_plugin_with_timeout) try a
+    /// plugin quarantined after a hung load again.
+    pub fn clear_quarantine(&mut self, path: &Path) {
+        crate::discovery::clear_quarantine(path);
+    }
+
+    /// Plugins quarantined after a hung load, refused until [`Self::clear_quarantine`].
+    pub fn quarantined_plugins(&self) -> Vec<PathBuf> {
+        crate::discovery::quarantined_plugins()
+    }
+
+    /// Load a VST3 plugin. If the [compatibility database](Self::compatibility_db) has an
+    /// entry for it, its recommended flags and workarounds are applied.
     pub fn load_plugin<P: AsRef<Path>>(&mut self, path: P) -> Result<Plugin> {
         let path = path.as_ref();
 
         if !path.exists() {
             return Err(Error::PluginNotFound(path.display().to_string()));
         }
+        if path.extension().is_some_and(|ext| ext == "clap") {
+            return Err(Error::PluginLoadFailed(format!(
+                "{} is a CLAP plugin; load it with load_clap_plugin (clap-support feature)",
+                path.display()
+            )));
+        }
 
-        if self.use_process_isolation {
+        let entry = self.compatibility_entry(path).cloned();
+        let workarounds = entry.as_ref().map_or(&[][..], |e| e.workarounds.as_slice());
+        let isolate = workarounds.contains(&crate::compatibility::Workaround::UseProcessIsolation);
+        let compat_flags = entry
+            .as_ref()
+            .map_or(PluginInitFlags::empty(), |e| e.init_flags());
+        if let Some(entry) = &entry {
+            for issue in &entry.known_issues {
+                log::info!("{}: known issue: {}", path.display(), issue);
+            }
+        }
+
+        // A plugin the database isolates is known to crash the host, so a failed isolated
+        // load is reported rather than retried in-process.
+        let mut plugin = if self.use_process_isolation || isolate {
             self.load_plugin_isolated(path)
         } else {
-            self.load_plugin_internal(path)
+            self.load_plugin_internal(path, compat_flags)
+        }?;
+        // The real name and vendor may match an entry the path alone didn't.
+        plugin.pending_workarounds = self
+            .compatibility
+            .lookup(&plugin.info)
+            .or(entry.as_ref())
+            .map(|e| e.workarounds.clone())
+            .unwrap_or_default();
+        plugin.factory_default_mismatches = plugin.verify_factory_defaults();
+        self.apply_bus_config_preset(&mut plugin);
+        plugin.set_meter_hold_mode(self.config.meter_hold_mode);
+        for m in &plugin.factory_default_mismatches {
+            log::warn!(
+                "{}: parameter {} ({}) loaded at {:.6}, factory default {:.6}",
+                plugin.info.name,
+                m.param_id,
+                m.title,
+                m.actual_normalized,
+                m.expected_normalized
+            );
+        }
+        self.last_loaded_plugin = Some(path.to_path_buf());
+        Ok(plugin)
+    }
+
+    /// How this host introduces itself to the plugins it loads in-process.
+    pub fn host_identity(&self) -> &HostIdentity {
+        &self.host_identity
+    }
+
+    /// The plugin [`Self::auto_load_last_plugin`] would load, if any.
+    pub fn last_loaded_plugin(&self) -> Option<&Path> {
+        self.last_loaded_plugin.as_deref()
+    }
+
+    /// Load the plugin loaded last ([`Self::last_loaded_plugin`]), e.g. to pick up where the
+    /// previous run left off. `Ok(None)` when there is none. If it's no longer at its path,
+    /// it's forgotten and [`Error::PluginNotFound`] returned.
+    pub fn auto_load_last_plugin(&mut self) -> Result<Option<Plugin>> {
+        let Some(path) = self.last_loaded_plugin.clone() else {
+            return Ok(None);
+        };
+        if !path.exists() {
+            self.last_loaded_plugin = None;
+            return Err(Error::PluginNotFound(path.display().to_string()));
         }
+        self.load_plugin(&path).map(Some)
+    }
+
+    /// General MIDI mode: load [`GM_CHANNELS`](crate::gm::GM_CHANNELS) instances of the
+    /// plugin at `plugin_path`, each listening only to its own MIDI channel, and mix them
+    /// into one [`GmBus`](crate::gm::GmBus). Drive it with a GM keyboard or sequencer by
+    /// passing every event to [`GmBus::send_midi_event`](crate::gm::GmBus::send_midi_event).
+    pub fn enable_gm_mode(&mut self, plugin_path: &Path) -> Result<crate::gm::GmBus> {
+        let plugins = (0..crate::gm::GM_CHANNELS as u8)
+            .map(|index| {
+                let mut plugin = self.load_plugin(plugin_path)?;
+                plugin.set_midi_channel_filter(crate::midi::MidiChannel::from_index(index));
+                Ok(plugin)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(crate::gm::GmBus::new(plugins))
+    }
+
+    /// Load the plugin at `path` and soak-test it for `duration`: process in real time, play
+    /// a note every two seconds and count crashes, NaN output, dropouts and memory growth.
+    /// `progress` gets the report so far every `report_every`. See
+    /// [`stability::run_stability_test`](crate::stability::run_stability_test).
+    pub fn run_stability_test<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        duration: std::time::Duration,
+        report_every: std::time::Duration,
+        progress: impl FnMut(&crate::StabilityReport),
+    ) -> Result<crate::StabilityReport> {
+        let mut plugin = self.load_plugin(path)?;
+        Ok(crate::stability::run_stability_test(
+            &mut plugin,
+            duration,
+            report_every,
+            progress,
+        ))
     }
 
     /// Probe whether a plugin loads safely, **without risking the host process** — it is
@@ -218,6 +566,7 @@ impl Vst3Host {
             tempo: self.config.tempo,
             time_sig_numerator: self.config.time_sig_numerator,
             time_sig_denominator: self.config.time_sig_denominator,
+            sample_size: self.config.sample_size,
         }) {
             Ok(HostResponse::PluginInfo { .. }) => ProbeResult::Ok,
             Ok(HostResponse::Error { message }) => ProbeResult::Failed(message),
@@ -229,13 +578,33 @@ impl Vst3Host {
     }
 
     /// Load a plugin in-process
-    fn load_plugin_internal(&mut self, path: &Path) -> Result<Plugin> {
+    fn load_plugin_internal(
+        &mut self,
+        path: &Path,
+        extra_flags: PluginInitFlags,
+    ) -> Result<Plugin> {
         // Load the plugin implementation directly - it will handle path resolution
-        let mut plugin_impl = crate::internal::plugin_impl::PluginImpl::load(path)?;
+        let journal = self.load_journal.as_deref();
+        let loaded = crate::internal::plugin_impl::PluginImpl::load(
+            path,
+            self.plugin_init_flags(path) | extra_flags,
+            &self.host_identity,
+            &mut |phase| {
+                if let Some(journal) = journal {
+                    write_load_journal(journal, path, phase);
+                }
+            },
+        );
+        if let Some(journal) = journal {
+            let _ = std::fs::remove_file(journal);
+        }
+        let mut plugin_impl = loaded?;
 
         // Apply the builder's audio config (sample rate / block size) so the plugin actually
         // processes at the requested settings, not the internal defaults.
-        plugin_impl.set_audio_config(self.config.sample_rate, self.config.block_size);
+        let (setup_rate, setup_block) = self.config.plugin_setup();
+        plugin_impl.set_audio_config(setup_rate, setup_block);
+        plugin_impl.set_sample_size(self.config.sample_size);
 
         // Thread the configured transport into the plugin's host ProcessContext so
         // tempo-synced DSP sees the host tempo / time signature.
@@ -263,6 +632,26 @@ impl Vst3Host {
             audio_levels: Arc::new(Mutex::new(crate::audio::AudioLevels::new(output_channels))),
             parameter_change_callback: None,
             audio_callback: None,
+            aftertouch_bindings: Vec::new(),
+            channel_pressure_bindings: Vec::new(),
+            midi_channel_filter: None,
+            output_analyzer: self
+                .config
+                .output_analysis
+                .then(crate::audio::OutputAnalyzer::new),
+            oversampler: self.config.oversampler(),
+            polyphony_limiter: None,
+            factory_default_mismatches: Vec::new(),
+            channel_strips: Vec::new(),
+            soft_takeover: None,
+            debouncer: None,
+            velocity_curve: None,
+            block_statistics: Some(BlockStatisticsTap::new(Arc::clone(&self.block_statistics))),
+            spectrum: Some(SpectrumTap::new(Arc::clone(&self.spectrum))),
+            slider_curves: std::collections::HashMap::new(),
+            bus_activation: std::collections::HashMap::new(),
+            sustain: crate::midi::SustainTracker::new(),
+            pending_workarounds: Vec::new(),
             internal: Some(Box::new(plugin_impl)),
         };
 
@@ -274,21 +663,33 @@ impl Vst3Host {
         use crate::process_isolation::{HostCommand, HostResponse, PluginHostProcess};
 
         // Create and start the isolated plugin process
-        let mut process =
-            PluginHostProcess::new(self.helper_path.clone(), self.response_timeout)
-                .map_err(|e| Error::Other(format!("Failed to create isolated process: {}", e)))?;
+        let log_name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "plugin".to_string());
+        let mut process = PluginHostProcess::new_for_plugin(
+            self.helper_path.clone(),
+            self.response_timeout,
+            &log_name,
+        )
+        .map_err(|e| Error::Other(format!("Failed to create isolated process: {}", e)))?;
 
         // Load the plugin in the isolated process
+        let (setup_rate, setup_block) = self.config.plugin_setup();
         let response = process
             .send_command(HostCommand::LoadPlugin {
                 path: path.display().to_string(),
-                sample_rate: self.config.sample_rate,
-                block_size: self.config.block_size as u32,
+                sample_rate: setup_rate,
+                block_size: setup_block as u32,
                 tempo: self.config.tempo,
                 time_sig_numerator: self.config.time_sig_numerator,
                 time_sig_denominator: self.config.time_sig_denominator,
+                sample_size: self.config.sample_size,
             })
-            .map_err(|e| Error::Other(format!("Failed to load plugin in isolation: {}", e)))?;
+            .map_err(|e| match process.take_crash_report() {
+                Some(report) => Error::HelperCrash { report },
+                None => Error::Other(format!("Failed to load plugin in isolation: {}", e)),
+            })?;
 
         // Verify the plugin loaded successfully. Metadata comes straight from the helper's
         // accurate introspection, so the isolated path matches the in-process one.
@@ -311,13 +712,15 @@ impl Vst3Host {
                     name,
                     vendor,
                     version,
-                    category,
+                    category_path: crate::discovery::parse_category(&category),
                     uid,
                     has_gui,
                     audio_inputs: audio_inputs as u32,
                     audio_outputs: audio_outputs as u32,
                     has_midi_input,
                     has_midi_output,
+                    format: crate::plugin::PluginFormat::Vst3,
+                    duplicates: Vec::new(),
                 };
                 let channels = if output_channels > 0 {
                     output_channels as usize
@@ -340,11 +743,12 @@ impl Vst3Host {
         let plugin_impl = crate::internal::isolated_plugin_impl::IsolatedPluginImpl::new(
             process,
             loaded_info.clone(),
-            self.config.sample_rate,
-            self.config.block_size,
+            setup_rate,
+            setup_block,
             self.config.tempo,
             self.config.time_sig_numerator,
             self.config.time_sig_denominator,
+            self.config.sample_size,
             output_channels,
             self.helper_path.clone(),
             self.response_timeout,
@@ -360,162 +764,1096 @@ impl Vst3Host {
             audio_levels: Arc::new(Mutex::new(crate::audio::AudioLevels::new(output_channels))),
             parameter_change_callback: None,
             audio_callback: None,
+            aftertouch_bindings: Vec::new(),
+            channel_pressure_bindings: Vec::new(),
+            midi_channel_filter: None,
+            output_analyzer: self
+                .config
+                .output_analysis
+                .then(crate::audio::OutputAnalyzer::new),
+            oversampler: self.config.oversampler(),
+            polyphony_limiter: None,
+            factory_default_mismatches: Vec::new(),
+            channel_strips: Vec::new(),
+            soft_takeover: None,
+            debouncer: None,
+            velocity_curve: None,
+            block_statistics: Some(BlockStatisticsTap::new(Arc::clone(&self.block_statistics))),
+            spectrum: Some(SpectrumTap::new(Arc::clone(&self.spectrum))),
+            slider_curves: std::collections::HashMap::new(),
+            bus_activation: std::collections::HashMap::new(),
+            sustain: crate::midi::SustainTracker::new(),
+            pending_workarounds: Vec::new(),
             internal: Some(Box::new(plugin_impl)),
         };
 
         Ok(plugin)
     }
 
-    /// Get audio configuration
-    pub fn config(&self) -> &AudioConfig {
-        &self.config
+    /// Save `config` as the bus preset `name` for the plugin at `plugin_path`, replacing a
+    /// preset of that name.
+    pub fn add_bus_config_preset(
+        &mut self,
+        plugin_path: impl AsRef<Path>,
+        name: impl Into<String>,
+        config: BusConfiguration,
+    ) {
+        let name = name.into();
+        let presets = self
+            .bus_config_presets
+            .entry(plugin_path.as_ref().to_path_buf())
+            .or_default();
+        match presets.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => *existing = config,
+            None => presets.push((name, config)),
+        }
     }
-}
 
-impl Default for Vst3Host {
-    fn default() -> Self {
-        Self {
-            config: AudioConfig::default(),
-            custom_paths: Vec::new(),
-            use_process_isolation: false,
-            scan_default_paths: true,
-            helper_path: None,
-            response_timeout: crate::process_isolation::DEFAULT_RESPONSE_TIMEOUT,
-            auto_recover_plugins: false,
-            auto_recover_max_retries: 1,
-            probe_timeout: crate::discovery::DEFAULT_PROBE_TIMEOUT,
+    /// The bus presets saved for the plugin at `plugin_path`, in the order they were added.
+    pub fn bus_config_presets(
+        &self,
+        plugin_path: impl AsRef<Path>,
+    ) -> &[(String, BusConfiguration)] {
+        self.bus_config_presets
+            .get(plugin_path.as_ref())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Apply the bus preset `name` to every plugin loaded from now on that has a preset of
+    /// that name. Errors if no plugin has one.
+    pub fn set_bus_config_preset(&mut self, name: &str) -> Result<()> {
+        let known = self
+            .bus_config_presets
+            .values()
+            .flatten()
+            .any(|(n, _)| n == name);
+        if !known {
+            return Err(Error::InvalidParameter(format!(
+                "no bus configuration preset named {name:?}"
+            )));
         }
+        self.bus_config_preset = Some(name.to_string());
+        Ok(())
     }
-}
 
-/// Builder for VST3 host configuration
-///
-/// All fields default to their type defaults; notably `scan_default_paths` defaults to
-/// `false`, requiring explicit opt-in (unlike `Vst3Host`, which defaults it to `true`).
-#[derive(Default)]
-pub struct Vst3HostBuilder {
-    config: AudioConfig,
-    custom_paths: Vec<PathBuf>,
-    use_process_isolation: bool,
-    scan_default_paths: bool,
-    helper_path: Option<PathBuf>,
-    response_timeout: Option<std::time::Duration>,
-    auto_recover_plugins: bool,
-    auto_recover_max_retries: Option<u32>,
-    probe_timeout: Option<std::time::Duration>,
-}
+    /// Stop applying a bus preset at load; plugins keep their default buses.
+    pub fn clear_bus_config_preset(&mut self) {
+        self.bus_config_preset = None;
+    }
 
-impl Vst3HostBuilder {
-    /// Set the sample rate
-    pub fn sample_rate(mut self, rate: f64) -> Self {
-        self.config.sample_rate = rate;
-        self
+    /// The bus preset applied at load, if any.
+    pub fn bus_config_preset(&self) -> Option<&str> {
+        self.bus_config_preset.as_deref()
     }
 
-    /// Set the block size
-    pub fn block_size(mut self, size: usize) -> Self {
-        self.config.block_size = size;
-        self
+    /// Apply the selected bus preset to a freshly loaded plugin, if it has one. A preset that
+    /// no longer fits the plugin is logged and skipped rather than failing the load.
+    fn apply_bus_config_preset(&self, plugin: &mut Plugin) {
+        let Some(name) = &self.bus_config_preset else {
+            return;
+        };
+        let Some((_, config)) = self
+            .bus_config_presets(&plugin.info.path)
+            .iter()
+            .find(|(n, _)| n == name)
+        else {
+            return;
+        };
+        if let Err(e) = plugin.apply_bus_configuration(config) {
+            log::warn!(
+                "{}: couldn't apply bus preset {name:?}: {e}",
+                plugin.info.name
+            );
+        }
     }
 
-    /// Set the number of input channels
-    pub fn input_channels(mut self, channels: usize) -> Self {
-        self.config.input_channels = channels;
-        self
+    /// Open the minimal parameter panel ([`Plugin::open_minimal_gui`]) for the plugin the
+    /// application numbers `handle`. Each handle gets its own window, so several plugins can
+    /// have their panels open at once.
+    #[cfg(feature = "egui-widgets")]
+    pub fn open_minimal_gui(
+        &self,
+        handle: PluginHandle,
+        plugin: &mut Plugin,
+    ) -> Result<crate::minimal_gui::MinimalGuiHandle> {
+        plugin.minimal_gui(egui::ViewportId::from_hash_of(("minimal_gui", handle)))
     }
 
-    /// Set the number of output channels
-    pub fn output_channels(mut self, channels: usize) -> Self {
-        self.config.output_channels = channels;
-        self
+    /// Split the keyboard across plugins: from now on [`Self::route_midi`] sends each note to
+    /// the plugin(s) whose region contains it.
+    pub fn set_keyboard_split(&mut self, split: KeyboardSplit) {
+        self.keyboard_split = Some(split);
     }
 
-    /// Set the transport tempo (beats per minute) advertised to plugins in the host
-    /// `ProcessContext`. Drives tempo-synced DSP (LFOs, synced delays, arpeggiators).
-    /// Defaults to `120.0`. Non-finite or non-positive values are ignored (a tempo of 0 or
-    /// less would freeze/reverse the derived musical playhead), keeping the previous tempo.
-    pub fn tempo(mut self, bpm: f64) -> Self {
-        if bpm.is_finite() && bpm > 0.0 {
-            self.config.tempo = bpm;
-        }
-        self
+    /// Remove the keyboard split; [`Self::route_midi`] returns `None` again.
+    pub fn clear_keyboard_split(&mut self) {
+        self.keyboard_split = None;
     }
 
-    /// Set the transport time signature advertised to plugins in the host
-    /// `ProcessContext` (`num`/`den`, e.g. `4, 4`). Defaults to `4/4`. Non-positive values
-    /// are ignored (a malformed time signature), keeping the previous setting.
-    pub fn time_signature(mut self, num: i32, den: i32) -> Self {
-        if num > 0 && den > 0 {
-            self.config.time_sig_numerator = num;
-            self.config.time_sig_denominator = den;
-        }
-        self
+    /// The current keyboard split, if any.
+    pub fn keyboard_split(&self) -> Option<&KeyboardSplit> {
+        self.keyboard_split.as_ref()
     }
 
-    /// Enable or disable process isolation for plugin loading
-    pub fn with_process_isolation(mut self, enabled: bool) -> Self {
-        self.use_process_isolation = enabled;
-        self
+    /// Fan MIDI out across plugins by channel: from now on [`Self::route_midi`] sends each
+    /// event to every destination of its channel in `matrix`. Takes precedence over the
+    /// keyboard split while set.
+    pub fn set_midi_routing(&mut self, matrix: RoutingMatrix) {
+        self.midi_routing = Some(matrix);
     }
 
-    /// Add a custom plugin scan path
-    pub fn add_scan_path<P: AsRef<Path>>(mut self, path: P) -> Self {
-        self.custom_paths.push(path.as_ref().to_path_buf());
-        self
+    /// Remove the routing matrix set by [`Self::set_midi_routing`].
+    pub fn clear_midi_routing(&mut self) {
+        self.midi_routing = None;
     }
 
-    /// Enable scanning of default system VST3 paths
-    pub fn scan_default_paths(mut self) -> Self {
-        self.scan_default_paths = true;
-        self
+    /// The current routing matrix, if any.
+    pub fn midi_routing(&self) -> Option<&RoutingMatrix> {
+        self.midi_routing.as_ref()
     }
 
-    /// How long to wait for an isolated helper to respond before treating the plugin as hung
-    /// (and killing the helper). Defaults to 5 seconds. Only affects process-isolated loads.
-    pub fn response_timeout(mut self, timeout: std::time::Duration) -> Self {
-        self.response_timeout = Some(timeout);
-        self
+    /// Output statistics (mean, variance, kurtosis and histogram) of the plugins this host
+    /// loaded, updated after every processed block and reset when processing starts. Plugins
+    /// loaded by the same host share it, so run one at a time when reading it.
+    pub fn block_statistics(&self) -> Arc<RwLock<BlockStatsSnapshot>> {
+        Arc::clone(&self.block_statistics)
     }
 
-    /// Override the path to the `vst3-host-helper` binary used for process isolation, instead
-    /// of the default heuristic search. The `VST3_HOST_HELPER_PATH` environment variable does
-    /// the same. Useful when the helper ships in a non-standard location.
-    pub fn helper_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
-        self.helper_path = Some(path.into());
-        self
+    /// The spectrum of the output of plugins loaded by this host, fed (mixed to mono) after
+    /// every processed block. Lock it to read [`SpectrumAnalyzer::spectrum_db`] or change its
+    /// window and averaging; the audio thread skips a block rather than wait while it's held,
+    /// so keep the lock short. The window starts as [`Vst3HostBuilder::spectrum_window`].
+    pub fn spectrum(&self) -> Arc<Mutex<SpectrumAnalyzer>> {
+        Arc::clone(&self.spectrum)
     }
 
-    /// Transparently respawn + reload a process-isolated plugin and retry the command when the
-    /// helper crashes or hangs, instead of surfacing `Error::PluginCrashed`/`PluginTimeout` for
-    /// the caller to handle via [`Plugin::recover`](crate::Plugin::recover).
-    ///
-    /// Only affects isolated loads and only the control plane — the audio-thread `process`
-    /// path never recovers inline (a respawn would stall the callback). **Recovery reloads the
-    /// plugin from defaults**: parameter values / state are NOT replayed, so snapshot with
-    /// `save_state`/`load_state` if you need them preserved. Off by default.
-    pub fn auto_recover_plugins(mut self, enabled: bool) -> Self {
-        self.auto_recover_plugins = enabled;
-        self
+    /// Dropouts, xruns and the slowest audio callback of the streams [`Self::play`] and
+    /// [`Self::play_with_input`] started, since the host was built or
+    /// [`StreamMonitor::reset`].
+    pub fn audio_stream_stats(&self) -> AudioStreamStats {
+        self.stream_monitor.stats()
     }
 
-    /// Max respawn+retry cycles per command when [`Self::auto_recover_plugins`] is on
-    /// (default 1). `0` disables retries even if auto-recover is enabled.
-    pub fn auto_recover_max_retries(mut self, retries: u32) -> Self {
-        self.auto_recover_max_retries = Some(retries);
-        self
+    /// How regularly the output callbacks of the last stream [`Self::play`] or
+    /// [`Self::play_with_input`] started have arrived: a jitter histogram and percentiles.
+    /// Logs a warning once per stream when p99 jitter passes
+    /// [`JITTER_WARN_P99_US`](crate::audio::JITTER_WARN_P99_US).
+    pub fn timing_stats(&self) -> crate::audio::TimingStats {
+        self.stream_monitor.timing_stats()
     }
 
-    /// Per-plugin timeout for the crash-resistant discovery probe used by
-    /// [`Vst3Host::discover_plugins_safe`] (default
-    /// [`DEFAULT_PROBE_TIMEOUT`](crate::discovery::DEFAULT_PROBE_TIMEOUT)). A plugin whose
-    /// probe exceeds this is killed and skipped.
-    pub fn probe_timeout(mut self, timeout: std::time::Duration) -> Self {
-        self.probe_timeout = Some(timeout);
-        self
+    /// The monitor behind [`Self::audio_stream_stats`], e.g. to keep reading it after the
+    /// host is gone.
+    pub fn stream_monitor(&self) -> Arc<StreamMonitor> {
+        Arc::clone(&self.stream_monitor)
     }
 
-    /// Build the configured host.
-    pub fn build(self) -> Result<Vst3Host> {
+    /// Call `handler` with every problem the host's streams report (see
+    /// [`StreamMonitor::set_handler`]); `None` removes it.
+    pub fn set_stream_error_handler(&self, handler: Option<AudioStreamErrorHandler>) {
+        self.stream_monitor.set_handler(handler);
+    }
+
+    /// Route an incoming MIDI event through the routing matrix (see [`RoutingMatrix::route`])
+    /// or, without one, the keyboard split (see [`KeyboardSplit::route_event`]) before it is
+    /// sent to a plugin. `None` when neither is set, meaning the event should be delivered
+    /// unchanged; an empty list means the event was dropped.
+    pub fn route_midi(&self, event: MidiEvent) -> Option<Vec<(PluginHandle, MidiEvent)>> {
+        if let Some(matrix) = &self.midi_routing {
+            return Some(matrix.route(event));
+        }
+        self.keyboard_split
+            .as_ref()
+            .map(|split| split.route_event(event))
+    }
+
+    /// Arpeggiate the notes sent to plugins played from now on ([`Self::play`],
+    /// [`Self::play_with_input`]): held notes are stepped through by `arp` instead of reaching
+    /// the plugin directly. For a plugin already playing use
+    /// [`AudioHandle::set_arpeggiator`](crate::AudioHandle::set_arpeggiator).
+    pub fn enable_arpeggiator(&mut self, arp: Arpeggiator) {
+        self.arpeggiator = Some(arp);
+    }
+
+    /// Stop arpeggiating plugins played from now on.
+    pub fn disable_arpeggiator(&mut self) {
+        self.arpeggiator = None;
+    }
+
+    /// The arpeggiator set by [`Self::enable_arpeggiator`], if any.
+    pub fn arpeggiator(&self) -> Option<&Arpeggiator> {
+        self.arpeggiator.as_ref()
+    }
+
+    /// How long the output peak holds of plugins loaded from now on last; for one already
+    /// loaded use [`Plugin::set_meter_hold_mode`]. With
+    /// [`MeterHoldMode::ManualReset`](crate::audio::MeterHoldMode::ManualReset) they only drop
+    /// on [`Plugin::reset_peak_hold`].
+    pub fn set_meter_hold_mode(&mut self, mode: crate::audio::MeterHoldMode) {
+        self.config.meter_hold_mode = mode;
+    }
+
+    /// Apply a fixed `db` of gain to live input before it reaches plugins played from now on
+    /// with [`Self::play_with_input`], turning auto-gain off. For a plugin already playing use
+    /// [`AudioHandle::set_input_gain`](crate::AudioHandle::set_input_gain).
+    pub fn set_input_gain(&mut self, db: f64) {
+        self.input_gain_db = db;
+        self.auto_gain_target_db = None;
+    }
+
+    /// Have [`Self::play_with_input`] adjust the input gain by itself, bringing the input's
+    /// RMS level to `target_db` dBFS ([`NOMINAL_INPUT_LEVEL_DB`] is the usual choice).
+    ///
+    /// [`NOMINAL_INPUT_LEVEL_DB`]: crate::audio::NOMINAL_INPUT_LEVEL_DB
+    pub fn set_auto_gain(&mut self, target_db: f64) {
+        self.auto_gain_target_db = Some(target_db);
+    }
+
+    /// The input gain stage [`Self::play_with_input`] will use, at the host's sample rate.
+    pub fn input_gain_stage(&self) -> InputGainStage {
+        let mut stage = match self.auto_gain_target_db {
+            Some(target_db) => InputGainStage::with_auto_gain(self.config.sample_rate, target_db),
+            None => InputGainStage::new(self.config.sample_rate),
+        };
+        stage.gain_db = self.input_gain_db;
+        stage
+    }
+
+    /// Limit the output of plugins played from now on to `db` dBFS, when the auto-limiter
+    /// is on ([`Vst3HostBuilder::auto_limiter`]). For a plugin already playing use
+    /// [`AudioHandle::set_auto_limiter`](crate::AudioHandle::set_auto_limiter).
+    pub fn set_limiter_threshold(&mut self, db: f64) {
+        self.limiter_threshold_db = db;
+    }
+
+    /// The limiter [`Self::play`] and [`Self::play_with_input`] put after the plugin, at the
+    /// host's sample rate, or `None` with the auto-limiter off.
+    pub fn auto_limiter(&self) -> Option<AutoLimiter> {
+        self.auto_limiter.then(|| {
+            let mut limiter = AutoLimiter::new(self.config.sample_rate);
+            limiter.threshold_db = self.limiter_threshold_db;
+            limiter
+        })
+    }
+
+    /// Create the virtual MIDI port `name` for a plugin's output events; hand it to
+    /// [`AudioHandle::set_virtual_midi_out`] to start mirroring. On Windows this opens the
+    /// loopMIDI port of that name instead (see [`crate::midi_output`]).
+    ///
+    /// [`AudioHandle::set_virtual_midi_out`]: crate::AudioHandle::set_virtual_midi_out
+    #[cfg(feature = "midi-output")]
+    pub fn enable_virtual_midi_out(&self, name: &str) -> Result<crate::VirtualMidiOutput> {
+        crate::VirtualMidiOutput::create(name)
+    }
+
+    /// The output device chosen with `set_audio_device`, or `None` for the system default.
+    pub fn audio_device(&self) -> Option<&str> {
+        self.audio_device.as_deref()
+    }
+
+    /// Play through the system default output device again.
+    pub fn use_default_audio_device(&mut self) {
+        self.audio_device = None;
+    }
+
+    /// Replace the CC bindings with `preset`'s and remember the controller it was made for.
+    pub fn apply_controller_preset(&mut self, preset: &ControllerPreset) {
+        self.cc_bindings = preset.bindings.clone();
+        self.controller_device = preset.device_name.clone();
+    }
+
+    /// Read a [`ControllerPreset`] file and apply it ([`Self::apply_controller_preset`]).
+    pub fn load_controller_preset(&mut self, path: &Path) -> Result<()> {
+        let preset = ControllerPreset::load(path)?;
+        self.apply_controller_preset(&preset);
+        Ok(())
+    }
+
+    /// Save the current CC bindings to `path` as a [`ControllerPreset`] called `name`.
+    pub fn save_controller_preset(&self, path: &Path, name: &str) -> Result<()> {
+        ControllerPreset {
+            name: name.to_string(),
+            device_name: self.controller_device.clone(),
+            bindings: self.cc_bindings.clone(),
+        }
+        .save(path)
+    }
+
+    /// Play the virtual keyboard on `channel`.
+    pub fn set_keyboard_channel(&mut self, channel: MidiChannel) {
+        self.keyboard_channel = channel;
+    }
+
+    /// The channel the virtual keyboard plays on.
+    pub fn keyboard_channel(&self) -> MidiChannel {
+        self.keyboard_channel
+    }
+
+    /// Shift the virtual keyboard by `octaves` (0 = as labeled).
+    pub fn set_keyboard_octave_shift(&mut self, octaves: i8) {
+        self.keyboard_octave_shift = octaves;
+    }
+
+    /// The virtual keyboard's octave shift.
+    pub fn keyboard_octave_shift(&self) -> i8 {
+        self.keyboard_octave_shift
+    }
+
+    /// Play virtual keyboard notes at `velocity`, clamped to `1..=127`.
+    pub fn set_keyboard_velocity(&mut self, velocity: u8) {
+        self.keyboard_velocity = velocity.clamp(1, 127);
+    }
+
+    /// The velocity of virtual keyboard notes ([`DEFAULT_KEYBOARD_VELOCITY`] until set).
+    pub fn keyboard_velocity(&self) -> u8 {
+        self.keyboard_velocity
+    }
+
+    /// Save the virtual keyboard's channel, octave shift, velocity, arpeggiator and CC bindings
+    /// as the keyboard preset `name`, replacing a preset of that name. Errors on an empty
+    /// name.
+    pub fn save_keyboard_preset(&mut self, name: &str) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(Error::InvalidParameter(
+                "keyboard preset name is empty".to_string(),
+            ));
+        }
+        let preset = KeyboardPreset {
+            name: name.to_string(),
+            channel: self.keyboard_channel,
+            octave_shift: self.keyboard_octave_shift,
+            velocity: self.keyboard_velocity,
+            arpeggiator: self.arpeggiator.clone(),
+            cc_bindings: self.cc_bindings.clone(),
+        };
+        match self.keyboard_presets.iter_mut().find(|p| p.name == name) {
+            Some(existing) => *existing = preset,
+            None => self.keyboard_presets.push(preset),
+        }
+        Ok(())
+    }
+
+    /// Restore the keyboard preset `name` saved by [`Self::save_keyboard_preset`]: its
+    /// channel, octave shift, velocity, arpeggiator (or none) and CC bindings. The
+    /// controller the bindings were made for is left as is. Errors if there is no such
+    /// preset.
+    pub fn load_keyboard_preset(&mut self, name: &str) -> Result<()> {
+        let preset = self
+            .keyboard_presets
+            .iter()
+            .find(|p| p.name == name)
+            .cloned()
+            .ok_or_else(|| Error::InvalidParameter(format!("no keyboard preset named {name:?}")))?;
+        self.keyboard_channel = preset.channel;
+        self.keyboard_octave_shift = preset.octave_shift;
+        self.set_keyboard_velocity(preset.velocity);
+        self.arpeggiator = preset.arpeggiator;
+        self.cc_bindings = preset.cc_bindings;
+        Ok(())
+    }
+
+    /// The names of the saved keyboard presets, in the order they were first saved.
+    pub fn list_keyboard_presets(&self) -> Vec<String> {
+        self.keyboard_presets
+            .iter()
+            .map(|p| p.name.clone())
+            .collect()
+    }
+
+    /// The saved keyboard presets, e.g. to persist them.
+    pub fn keyboard_presets(&self) -> &[KeyboardPreset] {
+        &self.keyboard_presets
+    }
+
+    /// Replace the saved keyboard presets, e.g. with ones persisted earlier.
+    pub fn set_keyboard_presets(&mut self, presets: Vec<KeyboardPreset>) {
+        self.keyboard_presets = presets;
+    }
+
+    /// The built-in controller presets (see [`crate::midi::bundled_controller_presets`]).
+    pub fn list_bundled_presets() -> Vec<&'static ControllerPreset> {
+        crate::midi::bundled_controller_presets().iter().collect()
+    }
+
+    /// Set the CC bindings directly (e.g. after MIDI learn), for `device_name` (empty = any
+    /// controller).
+    pub fn set_cc_bindings(&mut self, bindings: Vec<CcBinding>, device_name: &str) {
+        self.cc_bindings = bindings;
+        self.controller_device = device_name.to_string();
+    }
+
+    /// The current CC bindings.
+    pub fn cc_bindings(&self) -> &[CcBinding] {
+        &self.cc_bindings
+    }
+
+    /// The parameter changes the CC bindings make for `event`, as `(param_id, normalized)`.
+    pub fn cc_parameter_changes(&self, event: &MidiEvent) -> Vec<(u32, f64)> {
+        let MidiEvent::ControlChange {
+            channel,
+            controller,
+            value,
+        } = *event
+        else {
+            return Vec::new();
+        };
+        self.cc_bindings
+            .iter()
+            .filter(|b| b.matches(channel, controller))
+            .map(|b| (b.param_id, b.value_for(value)))
+            .collect()
+    }
+
+    /// Set the user's annotation for the plugin at `path`, replacing any previous one (an
+    /// empty annotation removes it). With an [annotations
+    /// file](Vst3HostBuilder::annotations_file) the change is saved straight away; the error
+    /// is from writing it.
+    pub fn annotate_plugin(&mut self, path: &Path, annotation: PluginAnnotation) -> Result<()> {
+        if annotation.is_empty() {
+            self.annotations.remove(path);
+        } else {
+            self.annotations.insert(path.to_path_buf(), annotation);
+        }
+        let Some(file) = &self.annotations_path else {
+            return Ok(());
+        };
+        let json = serde_json::to_vec_pretty(&self.annotations)
+            .map_err(|e| Error::Other(format!("serialize annotations: {e}")))?;
+        std::fs::write(file, json).map_err(|e| Error::Other(format!("write annotations: {e}")))
+    }
+
+    /// Skip the initialization steps in `flags` whenever a plugin whose path contains `path`
+    /// is loaded in-process — pass a full path, or just a name such as `"WaveShell"` to
+    /// cover every plugin matching it. Empty flags remove the entry. With an [init flags
+    /// file](Vst3HostBuilder::init_flags_file) the change is saved straight away; the error
+    /// is from writing it.
+    pub fn set_plugin_init_flags(&mut self, path: &Path, flags: PluginInitFlags) -> Result<()> {
+        let key = path.to_string_lossy().to_string();
+        if flags.is_empty() {
+            self.init_flags.remove(&key);
+        } else {
+            self.init_flags.insert(key, flags);
+        }
+        let Some(file) = &self.init_flags_path else {
+            return Ok(());
+        };
+        let json = serde_json::to_vec_pretty(&self.init_flags)
+            .map_err(|e| Error::Other(format!("serialize init flags: {e}")))?;
+        std::fs::write(file, json).map_err(|e| Error::Other(format!("write init flags: {e}")))
+    }
+
+    /// The initialization steps skipped when loading the plugin at `path`: every flag set
+    /// for a pattern its path contains.
+    pub fn plugin_init_flags(&self, path: &Path) -> PluginInitFlags {
+        let path = path.to_string_lossy();
+        self.init_flags
+            .iter()
+            .filter(|(pattern, _)| !pattern.is_empty() && path.contains(pattern.as_str()))
+            .fold(PluginInitFlags::empty(), |all, (_, &flags)| all | flags)
+    }
+
+    /// Every init flags entry, by path or name pattern.
+    pub fn init_flag_rules(&self) -> &HashMap<String, PluginInitFlags> {
+        &self.init_flags
+    }
+
+    /// The plugin and [`LoadPhase`] a previous run was in when it died mid-load, read from
+    /// the [load journal](Vst3HostBuilder::load_journal). Suggest the phase's
+    /// [`skip_flag`](LoadPhase::skip_flag) to the user, then [`Self::clear_interrupted_load`].
+    pub fn interrupted_load(&self) -> Option<(PathBuf, LoadPhase)> {
+        let bytes = std::fs::read(self.load_journal.as_ref()?).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// Forget the load reported by [`Self::interrupted_load`].
+    pub fn clear_interrupted_load(&self) {
+        if let Some(journal) = &self.load_journal {
+            let _ = std::fs::remove_file(journal);
+        }
+    }
+
+    /// The annotation for the plugin at `path`, if any.
+    pub fn plugin_annotation(&self, path: &Path) -> Option<&PluginAnnotation> {
+        self.annotations.get(path)
+    }
+
+    /// Every annotated plugin path and its annotation.
+    pub fn plugin_annotations(&self) -> &HashMap<PathBuf, PluginAnnotation> {
+        &self.annotations
+    }
+
+    /// The discovered plugins with a tag containing `query` (ignoring case).
+    pub fn plugins_tagged(&self, query: &str) -> Vec<&PluginInfo> {
+        self.discovered
+            .iter()
+            .filter(|p| {
+                self.annotations
+                    .get(&p.path)
+                    .is_some_and(|a| a.has_tag(query))
+            })
+            .collect()
+    }
+
+    /// Register state migrations for the plugin named `plugin_name` ([`PluginInfo::name`]).
+    /// Steps accumulate across calls; [`Self::load_state_with_migration`] applies them.
+    pub fn register_migration(&mut self, plugin_name: &str, steps: Vec<MigrationStep>) {
+        self.migrations
+            .entry(plugin_name.to_string())
+            .or_default()
+            .extend(steps);
+    }
+
+    /// Load `bytes` into `plugin`, migrating them with the steps registered for its name if
+    /// they don't load as-is. See [`PresetLoader::load_with_migration`].
+    pub fn load_state_with_migration(&self, plugin: &mut Plugin, bytes: &[u8]) -> Result<()> {
+        let steps = self
+            .migrations
+            .get(&plugin.info().name)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        PresetLoader::load_with_migration(plugin, bytes, steps)
+    }
+
+    /// Save `plugin` as a [`Session`] at `path`: its path and parameter values, the host's
+    /// CC bindings plus the plugin's aftertouch bindings, and the audio configuration and
+    /// transport.
+    pub fn save_session(&self, plugin: &Plugin, path: &Path) -> Result<()> {
+        let mut session = Session::capture(plugin, &self.config)?;
+        session
+            .midi_bindings
+            .extend(self.cc_bindings.iter().copied().map(MidiBinding::Cc));
+        session.save(path)
+    }
+
+    /// Restore the [`Session`] at `path`: adopt its audio configuration and transport, load its
+    /// plugin, set the saved parameter values and reinstate the MIDI bindings. CC bindings go
+    /// to the host and aftertouch bindings to the plugin; pitch-bend and note bindings have no
+    /// home in the host, so applications that use them read [`Session::midi_bindings`]
+    /// themselves.
+    ///
+    /// Returns the loaded plugin, like [`Self::load_plugin`].
+    pub fn load_session(&mut self, path: &Path) -> Result<Plugin> {
+        let session = Session::load(path)?;
+        self.config = AudioConfig {
+            tempo: session.transport.tempo,
+            time_sig_numerator: session.transport.time_sig_numerator,
+            time_sig_denominator: session.transport.time_sig_denominator,
+            ..session.audio_config
+        };
+        let mut plugin = self.load_plugin(&session.plugin_path)?;
+        session.apply_parameters(&mut plugin);
+        session.transport.apply(&mut plugin)?;
+
+        self.cc_bindings.clear();
+        for binding in &session.midi_bindings {
+            match *binding {
+                MidiBinding::Cc(cc) => self.cc_bindings.push(cc),
+                MidiBinding::Aftertouch(b) => plugin.add_aftertouch_binding(b),
+                MidiBinding::ChannelPressure(b) => plugin.add_channel_pressure_binding(b),
+                MidiBinding::PitchBend(_) | MidiBinding::Note(_) => {}
+            }
+        }
+        Ok(plugin)
+    }
+
+    /// Measure `plugin`'s actual tail in samples with the configured note and threshold
+    /// ([`Vst3HostBuilder::tail_measurement_note`], [`Vst3HostBuilder::tail_threshold_db`]).
+    /// See [`Plugin::measure_actual_tail`].
+    pub fn measure_tail(&self, plugin: &mut Plugin, note_length_blocks: usize) -> Result<u64> {
+        plugin.measure_actual_tail(
+            self.config.tail_measurement_note,
+            note_length_blocks,
+            self.config.tail_threshold_db,
+        )
+    }
+
+    /// Copy parameter `param_id`'s current value from `src` to `dst`.
+    ///
+    /// Two instances of the same plugin (same [`PluginInfo::uid`]) share a value mapping, so
+    /// the normalized value is copied as-is. Between different plugins it is carried over by
+    /// its display text instead — `src` formats it (`getParamStringByValue`) and `dst` parses
+    /// it (`getParamValueByString`) — so "440 Hz" stays 440 Hz even when the two map it to
+    /// different normalized values.
+    pub fn copy_parameter(&self, src: &Plugin, dst: &mut Plugin, param_id: u32) -> Result<()> {
+        copy_parameter_value(src, param_id, dst, param_id)
+    }
+
+    /// Copy every parameter of `src` to the `dst` parameter with the same title (compared
+    /// case-insensitively), converting units as [`Self::copy_parameter`] does. Parameters with
+    /// no match in `dst`, or a read-only one, are skipped.
+    pub fn copy_all_parameters_by_name(
+        &self,
+        src: &Plugin,
+        dst: &mut Plugin,
+    ) -> Result<CopyReport> {
+        let targets: HashMap<String, (u32, bool)> = dst
+            .get_parameters()?
+            .into_iter()
+            .map(|p| (p.name.to_lowercase(), (p.id, p.is_read_only)))
+            .collect();
+        let mut report = CopyReport::default();
+        for param in src.get_parameters()? {
+            match targets.get(&param.name.to_lowercase()) {
+                Some(&(dst_id, false)) => match copy_parameter_value(src, param.id, dst, dst_id) {
+                    Ok(()) => report.copied += 1,
+                    Err(e) => {
+                        log::debug!("copy of '{}' failed: {e}", param.name);
+                        report.failed += 1;
+                    }
+                },
+                _ => report.skipped += 1,
+            }
+        }
+        Ok(report)
+    }
+
+    /// The backends playback fans out to, if configured with
+    /// [`Vst3HostBuilder::with_aggregate_backend`]
+    pub fn aggregate_backend(&self) -> Option<&AggregateBackend> {
+        self.aggregate_backend.as_ref()
+    }
+
+    /// Mutable access to the aggregate backend, to add or remove backends
+    pub fn aggregate_backend_mut(&mut self) -> Option<&mut AggregateBackend> {
+        self.aggregate_backend.as_mut()
+    }
+
+    /// The sample format recordings are written in ([`Vst3HostBuilder::recording_format`]).
+    pub fn recording_format(&self) -> crate::audio::SampleFormat {
+        self.recording_format
+    }
+
+    /// Start recording to a WAV file at `path` with the host's output channel count and sample
+    /// rate, in its [recording format](Self::recording_format). Feed it the rendered output
+    /// with [`AudioRecorder::write`](crate::audio::AudioRecorder::write).
+    pub fn open_recorder<P: AsRef<Path>>(&self, path: P) -> Result<crate::audio::AudioRecorder> {
+        crate::audio::AudioRecorder::open_with_format(
+            path,
+            self.recording_format,
+            self.config.output_channels.max(1) as u16,
+            self.config.sample_rate as u32,
+        )
+    }
+
+    /// The known plugin quirks [`Self::load_plugin`] works around
+    /// ([`Vst3HostBuilder::compatibility_db`]).
+    pub fn compatibility_db(&self) -> &crate::compatibility::CompatibilityDb {
+        &self.compatibility
+    }
+
+    /// The compatibility entry for the plugin at `path`, identified by its discovered or cached
+    /// scan info, or by the bundle's file name before it's been scanned.
+    pub fn compatibility_entry(
+        &self,
+        path: &Path,
+    ) -> Option<&crate::compatibility::CompatibilityEntry> {
+        let info = self
+            .discovered
+            .iter()
+            .find(|info| info.path == path)
+            .or_else(|| self.scan_cache.get(path).map(|entry| &entry.info));
+        match info {
+            Some(info) => self.compatibility.lookup(info),
+            None => {
+                let name = path.file_stem()?.to_string_lossy();
+                self.compatibility.lookup_by(None, &name)
+            }
+        }
+    }
+
+    /// Get audio configuration
+    pub fn config(&self) -> &AudioConfig {
+        &self.config
+    }
+
+    /// The sample rate audio actually runs at: the configured rate unless the output device
+    /// didn't support it and [`Self::play`] fell back to the closest rate it (and the plugin)
+    /// did.
+    pub fn actual_sample_rate(&self) -> f64 {
+        self.actual_sample_rate
+            .read()
+            .ok()
+            .and_then(|rate| *rate)
+            .unwrap_or(self.config.sample_rate)
+    }
+}
+
+impl Default for Vst3Host {
+    fn default() -> Self {
+        Self {
+            config: AudioConfig::default(),
+            custom_paths: Vec::new(),
+            use_process_isolation: false,
+            scan_default_paths: true,
+            helper_path: None,
+            response_timeout: crate::process_isolation::DEFAULT_RESPONSE_TIMEOUT,
+            auto_recover_plugins: false,
+            auto_recover_max_retries: 1,
+            probe_timeout: crate::discovery::DEFAULT_PROBE_TIMEOUT,
+            migrations: HashMap::new(),
+            keyboard_split: None,
+            midi_routing: None,
+            block_statistics: Arc::default(),
+            spectrum: Arc::default(),
+            stream_monitor: Arc::default(),
+            aggregate_backend: None,
+            discovered: Vec::new(),
+            last_loaded_plugin: None,
+            host_identity: HostIdentity::default(),
+            deduplicate_plugins: true,
+            arpeggiator: None,
+            cc_bindings: Vec::new(),
+            controller_device: String::new(),
+            keyboard_channel: MidiChannel::Ch1,
+            keyboard_octave_shift: 0,
+            keyboard_velocity: DEFAULT_KEYBOARD_VELOCITY,
+            keyboard_presets: Vec::new(),
+            annotations: HashMap::new(),
+            annotations_path: None,
+            input_gain_db: 0.0,
+            auto_gain_target_db: None,
+            auto_limiter: false,
+            limiter_threshold_db: 0.0,
+            scan_cache: ScanCache::new(),
+            audio_device: None,
+            actual_sample_rate: RwLock::new(None),
+            init_flags: HashMap::new(),
+            init_flags_path: None,
+            load_journal: None,
+            bus_config_presets: HashMap::new(),
+            bus_config_preset: None,
+            recording_format: crate::audio::SampleFormat::default(),
+            compatibility: crate::compatibility::CompatibilityDb::bundled(),
+        }
+    }
+}
+
+/// Builder for VST3 host configuration
+///
+/// All fields default to their type defaults; notably `scan_default_paths` defaults to
+/// `false`, requiring explicit opt-in (unlike `Vst3Host`, which defaults it to `true`).
+#[derive(Default)]
+pub struct Vst3HostBuilder {
+    config: AudioConfig,
+    custom_paths: Vec<PathBuf>,
+    use_process_isolation: bool,
+    scan_default_paths: bool,
+    helper_path: Option<PathBuf>,
+    response_timeout: Option<std::time::Duration>,
+    auto_recover_plugins: bool,
+    auto_recover_max_retries: Option<u32>,
+    probe_timeout: Option<std::time::Duration>,
+    aggregate_backend: Option<AggregateBackend>,
+    annotations_path: Option<PathBuf>,
+    scan_cache_hash_only: bool,
+    init_flags: HashMap<String, PluginInitFlags>,
+    init_flags_path: Option<PathBuf>,
+    load_journal: Option<PathBuf>,
+    auto_limiter: bool,
+    spectrum_window: WindowFunction,
+    deduplicate_plugins: Option<bool>,
+    last_loaded_plugin: Option<PathBuf>,
+    host_identity: HostIdentity,
+    recording_format: crate::audio::SampleFormat,
+    compatibility_db: Option<crate::compatibility::CompatibilityDb>,
+}
+
+impl Vst3HostBuilder {
+    /// Set the sample rate
+    pub fn sample_rate(mut self, rate: f64) -> Self {
+        self.config.sample_rate = rate;
+        self
+    }
+
+    /// Set the block size
+    pub fn block_size(mut self, size: usize) -> Self {
+        self.config.block_size = size;
+        self
+    }
+
+    /// Set the sample precision plugins process in. [`SampleSize::F64`] is for plugins with a
+    /// double-precision path (mastering, analysis); a plugin without one falls back to 32-bit
+    /// with a warning rather than failing to load. Defaults to [`SampleSize::F32`].
+    ///
+    /// [`SampleSize::F64`]: crate::audio::SampleSize::F64
+    /// [`SampleSize::F32`]: crate::audio::SampleSize::F32
+    pub fn with_sample_size(mut self, size: crate::audio::SampleSize) -> Self {
+        self.config.sample_size = size;
+        self
+    }
+
+    /// Set the number of input channels
+    pub fn input_channels(mut self, channels: usize) -> Self {
+        self.config.input_channels = channels;
+        self
+    }
+
+    /// Set the number of output channels
+    pub fn output_channels(mut self, channels: usize) -> Self {
+        self.config.output_channels = channels;
+        self
+    }
+
+    /// Process plugins on an audio thread with `bytes` of stack; see
+    /// [`AudioConfig::audio_thread_stack_size`].
+    pub fn audio_thread_stack_size(mut self, bytes: usize) -> Self {
+        self.config.audio_thread_stack_size = Some(bytes);
+        self
+    }
+
+    /// Set the transport tempo (beats per minute) advertised to plugins in the host
+    /// `ProcessContext`. Drives tempo-synced DSP (LFOs, synced delays, arpeggiators).
+    /// Defaults to `120.0`. Non-finite or non-positive values are ignored (a tempo of 0 or
+    /// less would freeze/reverse the derived musical playhead), keeping the previous tempo.
+    pub fn tempo(mut self, bpm: f64) -> Self {
+        if bpm.is_finite() && bpm > 0.0 {
+            self.config.tempo = bpm;
+        }
+        self
+    }
+
+    /// Set the transport time signature advertised to plugins in the host
+    /// `ProcessContext` (`num`/`den`, e.g. `4, 4`). Defaults to `4/4`. Non-positive values
+    /// are ignored (a malformed time signature), keeping the previous setting.
+    pub fn time_signature(mut self, num: i32, den: i32) -> Self {
+        if num > 0 && den > 0 {
+            self.config.time_sig_numerator = num;
+            self.config.time_sig_denominator = den;
+        }
+        self
+    }
+
+    /// Set the ballistics of the playback output meters read through
+    /// [`AudioHandle::meter_levels`](crate::AudioHandle::meter_levels). Defaults to
+    /// [`BallisticsPreset::Peak`](crate::audio::BallisticsPreset::Peak).
+    pub fn meter_ballistics(mut self, preset: crate::audio::BallisticsPreset) -> Self {
+        self.config.meter_ballistics = preset;
+        self
+    }
+
+    /// Check every processed block for silence, clipping and NaN/Inf (default on). See
+    /// [`Plugin::output_analysis`].
+    pub fn enable_output_analysis(mut self, enabled: bool) -> Self {
+        self.config.output_analysis = enabled;
+        self
+    }
+
+    /// Note [`Vst3Host::measure_tail`] plays (default 60).
+    pub fn tail_measurement_note(mut self, note: u8) -> Self {
+        self.config.tail_measurement_note = note.min(127);
+        self
+    }
+
+    /// Level (dBFS) below which [`Vst3Host::measure_tail`] considers a tail ended
+    /// (default -60).
+    pub fn tail_threshold_db(mut self, threshold_db: f64) -> Self {
+        self.config.tail_threshold_db = threshold_db;
+        self
+    }
+
+    /// Run loaded plugins at `factor` × the sample rate (1, 2, 4 or 8; default 1 = off) to
+    /// reduce aliasing in distortion and saturation. Each block is upsampled, processed by a
+    /// plugin set up at the scaled rate and block size, then downsampled, adding
+    /// [`OversamplingAdapter::latency`](crate::audio::OversamplingAdapter::latency) samples
+    /// of delay. [`Self::build`] rejects other factors.
+    pub fn oversampling_factor(mut self, factor: u32) -> Self {
+        self.config.oversampling_factor = factor;
+        self
+    }
+
+    /// Play through all of `backends` at once: the plugin renders once and every backend
+    /// receives the same samples (e.g. monitors and headphones). See [`AggregateBackend`].
+    pub fn with_aggregate_backend(mut self, backends: Vec<Box<dyn DynAudioBackend>>) -> Self {
+        self.aggregate_backend = Some(AggregateBackend::new(backends));
+        self
+    }
+
+    /// Enable or disable process isolation for plugin loading
+    pub fn with_process_isolation(mut self, enabled: bool) -> Self {
+        self.use_process_isolation = enabled;
+        self
+    }
+
+    /// Add a custom plugin scan path
+    pub fn add_scan_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.custom_paths.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Enable scanning of default system VST3 paths
+    pub fn scan_default_paths(mut self) -> Self {
+        self.scan_default_paths = true;
+        self
+    }
+
+    /// How long to wait for an isolated helper to respond before treating the plugin as hung
+    /// (and killing the helper). Defaults to 5 seconds. Only affects process-isolated loads.
+    pub fn response_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.response_timeout = Some(timeout);
+        self
+    }
+
+    /// Override the path to the `vst3-host-helper` binary used for process isolation, instead
+    /// of the default heuristic search. The `VST3_HOST_HELPER_PATH` environment variable does
+    /// the same. Useful when the helper ships in a non-standard location.
+    pub fn helper_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.helper_path = Some(path.into());
+        self
+    }
+
+    /// Transparently respawn + reload a process-isolated plugin and retry the command when the
+    /// helper crashes or hangs, instead of surfacing `Error::PluginCrashed`/`PluginTimeout` for
+    /// the caller to handle via [`Plugin::recover`](crate::Plugin::recover).
+    ///
+    /// Only affects isolated loads and only the control plane — the audio-thread `process`
+    /// path never recovers inline (a respawn would stall the callback). **Recovery reloads the
+    /// plugin from defaults**: parameter values / state are NOT replayed, so snapshot with
+    /// `save_state`/`load_state` if you need them preserved. Off by default.
+    pub fn auto_recover_plugins(mut self, enabled: bool) -> Self {
+        self.auto_recover_plugins = enabled;
+        self
+    }
+
+    /// Max respawn+retry cycles per command when [`Self::auto_recover_plugins`] is on
+    /// (default 1). `0` disables retries even if auto-recover is enabled.
+    pub fn auto_recover_max_retries(mut self, retries: u32) -> Self {
+        self.auto_recover_max_retries = Some(retries);
+        self
+    }
+
+    /// Per-plugin timeout for the crash-resistant discovery probe used by
+    /// [`Vst3Host::discover_plugins_safe`] (default
+    /// [`DEFAULT_PROBE_TIMEOUT`](crate::discovery::DEFAULT_PROBE_TIMEOUT)). A plugin whose
+    /// probe exceeds this is killed and skipped.
+    pub fn probe_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.probe_timeout = Some(timeout);
+        self
+    }
+
+    /// Keep plugin annotations ([`Vst3Host::annotate_plugin`]) in the JSON file at `path`:
+    /// `build` loads it if it exists, and every annotation change rewrites it.
+    pub fn annotations_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.annotations_path = Some(path.into());
+        self
+    }
+
+    /// Trust only content hashes when deciding whether a cached plugin scan is still valid,
+    /// ignoring mtimes — for plugins on filesystems with unreliable mtimes (SMB shares,
+    /// Docker volumes). See [`ScanCache::hash_only_mode`].
+    pub fn scan_cache_hash_only(mut self, hash_only: bool) -> Self {
+        self.scan_cache_hash_only = hash_only;
+        self
+    }
+
+    /// Skip the initialization steps in `flags` for plugins whose path contains `pattern`
+    /// (a plugin name such as `"WaveShell"`, or a full path). See
+    /// [`Vst3Host::set_plugin_init_flags`].
+    pub fn plugin_init_flags(mut self, pattern: impl Into<String>, flags: PluginInitFlags) -> Self {
+        self.init_flags.insert(pattern.into(), flags);
+        self
+    }
+
+    /// Keep plugin init flags ([`Vst3Host::set_plugin_init_flags`]) in the JSON file at
+    /// `path`: `build` loads it if it exists, and every change rewrites it. Flags set with
+    /// [`Self::plugin_init_flags`] replace the file's for the same pattern, and are written
+    /// along with the rest on the next change.
+    pub fn init_flags_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.init_flags_path = Some(path.into());
+        self
+    }
+
+    /// Record each in-process load's current [`LoadPhase`] in the file at `path`, removed
+    /// again once the load returns. If the plugin crashes the process mid-load the file is
+    /// left behind, and [`Vst3Host::interrupted_load`] reports where it stopped on the next
+    /// run.
+    pub fn load_journal(mut self, path: impl Into<PathBuf>) -> Self {
+        self.load_journal = Some(path.into());
+        self
+    }
+
+    /// Put an [`AutoLimiter`] (0 dBFS unless [`Vst3Host::set_limiter_threshold`] says
+    /// otherwise) after every plugin the host plays, so a clipping plugin can't reach the
+    /// speakers at full scale. Off by default.
+    pub fn auto_limiter(mut self, enabled: bool) -> Self {
+        self.auto_limiter = enabled;
+        self
+    }
+
+    /// Whether [`Vst3Host::discover_plugins`] merges copies of the same plugin installed in
+    /// several scan paths into one entry (see
+    /// [`deduplicate_plugins`](crate::discovery::deduplicate_plugins)). On by default.
+    pub fn deduplicate_plugins(mut self, deduplicate: bool) -> Self {
+        self.deduplicate_plugins = Some(deduplicate);
+        self
+    }
+
+    /// The name plugins see from `IHostApplication::getName` (default "vst3-host"), e.g.
+    /// "Ableton Live" to check a plugin's Live-specific behavior.
+    pub fn host_name(mut self, name: &str) -> Self {
+        self.host_identity.name = name.to_string();
+        self
+    }
+
+    /// The host version as free text (e.g. "12.1b3"), for versions that aren't
+    /// `major.minor.patch`. See [`HostIdentity::version_text`].
+    pub fn host_version_string(mut self, version: &str) -> Self {
+        self.host_identity.version_string = Some(version.to_string());
+        self
+    }
+
+    /// The host version, encoded per the SDK as `major << 16 | minor << 8 | patch`
+    /// ([`HostIdentity::encode_version`]).
+    pub fn host_version_semver(mut self, major: u32, minor: u32, patch: u32) -> Self {
+        self.host_identity.version = HostIdentity::encode_version(major, minor, patch);
+        self
+    }
+
+    /// The plugin [`Vst3Host::auto_load_last_plugin`] loads until [`Vst3Host::load_plugin`]
+    /// loads another, typically the path an application saved when it last quit.
+    pub fn last_loaded_plugin(mut self, path: impl Into<PathBuf>) -> Self {
+        self.last_loaded_plugin = Some(path.into());
+        self
+    }
+
+    /// How [`Vst3Host::open_recorder`] encodes samples (32-bit float unless set). Independent
+    /// of processing, which always runs in 32-bit float.
+    pub fn recording_format(mut self, format: crate::audio::SampleFormat) -> Self {
+        self.recording_format = format;
+        self
+    }
+
+    /// The known plugin quirks [`Vst3Host::load_plugin`] works around, replacing the bundled
+    /// database ([`CompatibilityDb::bundled`](crate::compatibility::CompatibilityDb::bundled)).
+    pub fn compatibility_db(mut self, db: crate::compatibility::CompatibilityDb) -> Self {
+        self.compatibility_db = Some(db);
+        self
+    }
+
+    /// The window [`Vst3Host::spectrum`] starts with (rectangular unless set).
+    pub fn spectrum_window(mut self, window: WindowFunction) -> Self {
+        self.spectrum_window = window;
+        self
+    }
+
+    /// Build the configured host. Fails if the oversampling factor isn't 1, 2, 4 or 8, or if
+    /// the [annotations file](Self::annotations_file) or [init flags
+    /// file](Self::init_flags_file) exists but can't be read.
+    pub fn build(self) -> Result<Vst3Host> {
+        crate::audio::OversamplingAdapter::new(self.config.oversampling_factor)?;
+        let annotations = match &self.annotations_path {
+            Some(path) if path.exists() => load_annotations(path)?,
+            _ => HashMap::new(),
+        };
+        let mut init_flags: HashMap<String, PluginInitFlags> = match &self.init_flags_path {
+            Some(path) if path.exists() => {
+                let bytes = std::fs::read(path)
+                    .map_err(|e| Error::Other(format!("read init flags: {e}")))?;
+                serde_json::from_slice(&bytes)
+                    .map_err(|e| Error::Other(format!("parse init flags: {e}")))?
+            }
+            _ => HashMap::new(),
+        };
+        init_flags.extend(self.init_flags);
         Ok(Vst3Host {
             config: self.config,
             custom_paths: self.custom_paths,
@@ -530,10 +1868,77 @@ impl Vst3HostBuilder {
             probe_timeout: self
                 .probe_timeout
                 .unwrap_or(crate::discovery::DEFAULT_PROBE_TIMEOUT),
+            migrations: HashMap::new(),
+            keyboard_split: None,
+            midi_routing: None,
+            block_statistics: Arc::default(),
+            spectrum: Arc::new(Mutex::new({
+                let mut spectrum = SpectrumAnalyzer::default();
+                spectrum.set_window(self.spectrum_window);
+                spectrum
+            })),
+            stream_monitor: Arc::default(),
+            aggregate_backend: self.aggregate_backend,
+            discovered: Vec::new(),
+            last_loaded_plugin: self.last_loaded_plugin,
+            host_identity: self.host_identity,
+            deduplicate_plugins: self.deduplicate_plugins.unwrap_or(true),
+            arpeggiator: None,
+            cc_bindings: Vec::new(),
+            controller_device: String::new(),
+            keyboard_channel: MidiChannel::Ch1,
+            keyboard_octave_shift: 0,
+            keyboard_velocity: DEFAULT_KEYBOARD_VELOCITY,
+            keyboard_presets: Vec::new(),
+            annotations,
+            annotations_path: self.annotations_path,
+            input_gain_db: 0.0,
+            auto_gain_target_db: None,
+            auto_limiter: self.auto_limiter,
+            limiter_threshold_db: 0.0,
+            scan_cache: ScanCache::with_hash_only_mode(self.scan_cache_hash_only),
+            audio_device: None,
+            actual_sample_rate: RwLock::new(None),
+            init_flags,
+            init_flags_path: self.init_flags_path,
+            load_journal: self.load_journal,
+            bus_config_presets: HashMap::new(),
+            bus_config_preset: None,
+            recording_format: self.recording_format,
+            compatibility: self
+                .compatibility_db
+                .unwrap_or_else(crate::compatibility::CompatibilityDb::bundled),
         })
     }
 }
 
+/// Copy `src_id` on `src` to `dst_id` on `dst`: normalized for the same plugin type, by display
+/// text otherwise (see [`Vst3Host::copy_parameter`]).
+fn copy_parameter_value(src: &Plugin, src_id: u32, dst: &mut Plugin, dst_id: u32) -> Result<()> {
+    let normalized = src.get_parameter(src_id)?;
+    let same_type = !src.info().uid.is_empty() && src.info().uid == dst.info().uid;
+    let value = if same_type {
+        normalized
+    } else {
+        let text = src.format_parameter(src_id, normalized)?;
+        dst.parse_parameter(dst_id, &text)?
+    };
+    dst.set_parameter(dst_id, value)
+}
+
+/// Record that the load of `plugin` has reached `phase` (best effort: a journal that can't
+/// be written only loses the crash diagnosis).
+fn write_load_journal(journal: &Path, plugin: &Path, phase: LoadPhase) {
+    if let Ok(json) = serde_json::to_vec(&(plugin, phase)) {
+        let _ = std::fs::write(journal, json);
+    }
+}
+
+fn load_annotations(path: &Path) -> Result<HashMap<PathBuf, PluginAnnotation>> {
+    let bytes = std::fs::read(path).map_err(|e| Error::Other(format!("read annotations: {e}")))?;
+    serde_json::from_slice(&bytes).map_err(|e| Error::Other(format!("parse annotations: {e}")))
+}
+
 /// The outcome of [`Vst3Host::probe_plugin`] — whether a plugin can be loaded safely.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ProbeResult {
@@ -547,8 +1952,73 @@ pub enum ProbeResult {
     Failed(String),
 }
 
+/// The outcome of [`Vst3Host::copy_all_parameters_by_name`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CopyReport {
+    /// Parameters whose value was copied.
+    pub copied: u32,
+    /// Parameters with no writable same-named counterpart in the destination.
+    pub skipped: u32,
+    /// Parameters that matched but could not be read, converted or written.
+    pub failed: u32,
+}
+
+/// How the host introduces itself to plugins. Some plugins change behavior depending on the
+/// DAW hosting them, so posing as one (see [`Vst3HostBuilder::host_name`]) reproduces what they
+/// do there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostIdentity {
+    /// The name `IHostApplication::getName` returns ("vst3-host" by default)
+    pub name: String,
+    /// The version in the SDK's encoding, `major << 16 | minor << 8 | patch`
+    pub version: u32,
+    /// The version as text, when it isn't `major.minor.patch` of [`Self::version`]
+    pub version_string: Option<String>,
+}
+
+impl HostIdentity {
+    /// Encode `major.minor.patch` the way the SDK does (`major << 16 | minor << 8 | patch`).
+    /// Minor and patch keep their low 8 bits.
+    pub const fn encode_version(major: u32, minor: u32, patch: u32) -> u32 {
+        major << 16 | (minor & 0xFF) << 8 | (patch & 0xFF)
+    }
+
+    /// The version as text: [`Self::version_string`] if set, else the decoded
+    /// [`Self::version`] (`"1.2.3"`).
+    pub fn version_text(&self) -> String {
+        self.version_string.clone().unwrap_or_else(|| {
+            format!(
+                "{}.{}.{}",
+                self.version >> 16,
+                (self.version >> 8) & 0xFF,
+                self.version & 0xFF
+            )
+        })
+    }
+}
+
+impl Default for HostIdentity {
+    /// This crate, at its own version.
+    fn default() -> Self {
+        let part = |s: &str| s.parse().unwrap_or(0);
+        Self {
+            name: "vst3-host".to_string(),
+            version: Self::encode_version(
+                part(env!("CARGO_PKG_VERSION_MAJOR")),
+                part(env!("CARGO_PKG_VERSION_MINOR")),
+                part(env!("CARGO_PKG_VERSION_PATCH")),
+            ),
+            version_string: None,
+        }
+    }
+}
+
 /// Plugin discovery progress information
+///
+/// Marked `#[non_exhaustive]`: match with a wildcard arm, as new kinds of progress may be
+/// reported in future versions.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum DiscoveryProgress {
     /// Discovery has started
     Started {
@@ -564,6 +2034,13 @@ pub enum DiscoveryProgress {
         /// Total number of plugins
         total: usize,
     },
+    /// A plugin was inspected (or failed to be); sent just before its `Found` or `Error`
+    Scanned {
+        /// The plugin's path
+        path: std::path::PathBuf,
+        /// How long inspecting it took (near zero for plugins served from the scan cache)
+        elapsed: std::time::Duration,
+    },
     /// An error occurred while scanning a plugin
     Error {
         /// Path that failed
@@ -580,13 +2057,81 @@ pub enum DiscoveryProgress {
 
 #[cfg(feature = "cpal-backend")]
 impl Vst3Host {
+    /// Every audio output device with its channels, sample rates and buffer range.
+    pub fn list_audio_output_devices(&self) -> Result<Vec<crate::audio::AudioDeviceInfo>> {
+        crate::backends::CpalBackend::new()?.output_device_info()
+    }
+
+    /// Play through the output device named `device_name` (as listed by
+    /// [`Self::list_audio_output_devices`]) from the next [`Self::play`] on. Fails if no
+    /// such device is connected.
+    pub fn set_audio_device(&mut self, device_name: &str) -> Result<()> {
+        crate::backends::CpalBackend::new()?
+            .find_output_device(device_name)
+            .ok_or_else(|| {
+                Error::AudioBackendError(format!("Output device not found: {device_name}"))
+            })?;
+        self.audio_device = Some(device_name.to_string());
+        Ok(())
+    }
+
+    /// A cpal backend playing through the [chosen device](Self::set_audio_device).
+    fn output_backend(&self) -> Result<crate::backends::CpalBackend> {
+        let backend = crate::backends::CpalBackend::new()?;
+        let Some(name) = &self.audio_device else {
+            return Ok(backend);
+        };
+        let device = backend
+            .find_output_device(name)
+            .ok_or_else(|| Error::AudioBackendError(format!("Output device not found: {name}")))?;
+        Ok(backend.with_output_device(device))
+    }
+
+    /// Settle the rate `plugin` plays at on `backend`'s output device: the configured rate when
+    /// the device supports it, otherwise the closest one the plugin accepts (see
+    /// [`AudioDeviceInfo::negotiate_sample_rate`]), reconfiguring the plugin to match. Logs a
+    /// warning on fallback and records the result for [`Self::actual_sample_rate`].
+    ///
+    /// [`AudioDeviceInfo::negotiate_sample_rate`]: crate::audio::AudioDeviceInfo::negotiate_sample_rate
+    fn negotiate_sample_rate(
+        &self,
+        backend: &crate::backends::CpalBackend,
+        plugin: &mut Plugin,
+    ) -> Result<f64> {
+        let requested = self.config.sample_rate;
+        let device = backend.current_output_device_info()?;
+        if plugin.is_processing() && !device.sample_rates.contains(&requested) {
+            plugin.stop_processing()?;
+        }
+        let rate = device.negotiate_sample_rate(requested, |rate| {
+            if rate == plugin.sample_rate() {
+                return Ok(());
+            }
+            plugin.reconfigure(rate, plugin.block_size())
+        })?;
+        if rate != requested {
+            log::warn!(
+                "{} doesn't support {requested} Hz; running at {rate} Hz instead",
+                device.name
+            );
+        }
+        if let Ok(mut actual) = self.actual_sample_rate.write() {
+            *actual = Some(rate);
+        }
+        Ok(rate)
+    }
+
     /// Load a plugin and immediately start playing it through the default audio
     /// output device, using the host's configured sample rate and block size.
     ///
-    /// This is the "batteries-included" path: it wires a [`CpalBackend`] to the
+    /// This is the "batteries-included" path: it wires a [`CpalBackend`] (or the
+    /// [`Vst3HostBuilder::with_aggregate_backend`] backends, when configured) to the
     /// plugin and pumps audio for you. The returned [`AudioHandle`] keeps the stream
     /// alive — drop it to stop — and lets you keep sending MIDI / changing parameters
-    /// while it plays:
+    /// while it plays. Stream errors reach [`Self::audio_stream_stats`] and the
+    /// [`Self::set_stream_error_handler`] handler; when the output device disconnects, playback
+    /// moves to the default output device a second later (see
+    /// [`play_monitored`](crate::playback::play_monitored)):
     ///
     /// ```no_run
     /// # use vst3_host::Vst3Host;
@@ -603,31 +2148,83 @@ impl Vst3Host {
     ///
     /// [`CpalBackend`]: crate::backends::CpalBackend
     /// [`AudioHandle`]: crate::AudioHandle
-    pub fn play(&self, plugin: Plugin) -> Result<crate::AudioHandle> {
-        let backend = crate::backends::CpalBackend::new()?;
-        let config = crate::audio::AudioConfig {
+    pub fn play(&self, mut plugin: Plugin) -> Result<crate::AudioHandle> {
+        let mut config = crate::audio::AudioConfig {
             output_channels: 2,
             input_channels: 0,
             ..self.config
         };
-        crate::playback::play_with_backend(&backend, plugin, config)
+        let handle = match &self.aggregate_backend {
+            Some(aggregate) => crate::playback::play_with_backend(aggregate, plugin, config)?,
+            None => {
+                let backend = self.output_backend()?;
+                config.sample_rate = self.negotiate_sample_rate(&backend, &mut plugin)?;
+                crate::playback::play_monitored(
+                    &backend,
+                    plugin,
+                    config,
+                    self.stream_monitor(),
+                    crate::backends::CpalBackend::new,
+                )?
+            }
+        };
+        if let Some(arp) = &self.arpeggiator {
+            handle.set_arpeggiator(Some(arp.clone()));
+        }
+        if let Some(limiter) = self.auto_limiter() {
+            handle.set_auto_limiter(Some(limiter));
+        }
+        Ok(handle)
     }
 
     /// Host a plugin on **live audio input** (effect hosting): capture from the default input
-    /// device, process through the plugin, and play the result on the default output device.
+    /// device, process through the plugin, and play the result on the output device
+    /// ([`Self::set_audio_device`], the system default unless set).
     ///
     /// Use this for effect plugins (EQ, reverb, compressor); for instruments use
     /// [`Self::play`]. Control the plugin via the returned [`AudioHandle`].
     ///
     /// [`AudioHandle`]: crate::AudioHandle
-    pub fn play_with_input(&self, plugin: Plugin) -> Result<crate::AudioHandle> {
-        let backend = crate::backends::CpalBackend::new()?;
+    pub fn play_with_input(&self, mut plugin: Plugin) -> Result<crate::AudioHandle> {
+        let backend = self.output_backend()?;
         let config = crate::audio::AudioConfig {
             input_channels: 2,
             output_channels: 2,
+            sample_rate: self.negotiate_sample_rate(&backend, &mut plugin)?,
+            ..self.config
+        };
+        let handle = crate::playback::play_with_input_monitored(
+            &backend,
+            plugin,
+            config,
+            self.stream_monitor(),
+        )?;
+        if let Some(arp) = &self.arpeggiator {
+            handle.set_arpeggiator(Some(arp.clone()));
+        }
+        handle.set_input_gain(Some(self.input_gain_stage()));
+        if let Some(limiter) = self.auto_limiter() {
+            handle.set_auto_limiter(Some(limiter));
+        }
+        Ok(handle)
+    }
+
+    /// Play a [`GmBus`](crate::gm::GmBus) (see [`Self::enable_gm_mode`]) through the default
+    /// output device. Control it through the returned handle's
+    /// [`lock`](crate::playback::GmAudioHandle::lock).
+    pub fn play_gm(&self, bus: crate::gm::GmBus) -> Result<crate::playback::GmAudioHandle> {
+        let config = crate::audio::AudioConfig {
+            output_channels: 2,
+            input_channels: 0,
             ..self.config
         };
-        crate::playback::play_with_input_backend(&backend, plugin, config)
+        match &self.aggregate_backend {
+            Some(aggregate) => crate::playback::play_gm_with_backend(aggregate, bus, config),
+            None => {
+                let backend = self.output_backend()?;
+                crate::playback::play_gm_with_backend(&backend, bus, config)
+            }
+        }
     }
 
     /// Play a plugin through the default device using the **lock-free** real-time path
@@ -654,13 +2251,28 @@ impl Vst3Host {
         plugin: Plugin,
         command_capacity: usize,
     ) -> Result<crate::playback::RtAudioHandle> {
-        let backend = crate::backends::CpalBackend::new()?;
         let config = crate::audio::AudioConfig {
             output_channels: 2,
             input_channels: 0,
             ..self.config
         };
-        crate::playback::play_realtime_with_backend(&backend, plugin, config, command_capacity)
+        match &self.aggregate_backend {
+            Some(aggregate) => crate::playback::play_realtime_with_backend(
+                aggregate,
+                plugin,
+                config,
+                command_capacity,
+            ),
+            None => {
+                let backend = self.output_backend()?;
+                crate::playback::play_realtime_with_backend(
+                    &backend,
+                    plugin,
+                    config,
+                    command_capacity,
+                )
+            }
+        }
     }
 }
 
@@ -687,4 +2299,585 @@ mod tests {
         assert_eq!(host.config().time_sig_numerator, 7);
         assert_eq!(host.config().time_sig_denominator, 8);
     }
+
+    #[test]
+    fn recordings_use_the_builder_s_format_and_output_layout() {
+        use crate::audio::SampleFormat;
+        assert_eq!(
+            Vst3HostBuilder::default()
+                .build()
+                .unwrap()
+                .recording_format(),
+            SampleFormat::F32
+        );
+        let host = Vst3HostBuilder::default()
+            .sample_rate(48_000.0)
+            .output_channels(2)
+            .recording_format(SampleFormat::I24)
+            .build()
+            .unwrap();
+        let path = std::env::temp_dir().join(format!("vh_host_rec_{}.wav", std::process::id()));
+        let mut recorder = host.open_recorder(&path).unwrap();
+        assert_eq!(recorder.format(), SampleFormat::I24);
+        recorder.write(&[vec![0.5; 8], vec![-0.5; 8]]).unwrap();
+        recorder.finish().unwrap();
+        let (channels, sample_rate) = crate::audio::read_wav(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(sample_rate, 48_000);
+        assert_eq!(channels, vec![vec![0.5; 8], vec![-0.5; 8]]);
+    }
+
+    #[test]
+    fn compatibility_entries_are_found_by_scan_info_or_bundle_name() {
+        use crate::compatibility::{CompatibilityDb, CompatibilityEntry};
+        let entry = |vendor: Option<&str>, name: Option<&str>| CompatibilityEntry {
+            match_vendor: vendor.map(str::to_string),
+            match_name_contains: name.map(str::to_string),
+            known_issues: vec![format!("{vendor:?} {name:?}")],
+            ..Default::default()
+        };
+        let mut host = Vst3HostBuilder::default()
+            .compatibility_db(CompatibilityDb::new(vec![
+                entry(Some("Acme"), None),
+                entry(None, Some("Shell")),
+            ]))
+            .build()
+            .unwrap();
+        let unscanned = Path::new("/vst3/WaveShell.vst3");
+        assert_eq!(
+            host.compatibility_entry(unscanned),
+            Some(&entry(None, Some("Shell")))
+        );
+        // Vendor-only entries need the vendor, which only a scan provides.
+        let scanned = Path::new("/vst3/Reverb.vst3");
+        assert!(host.compatibility_entry(scanned).is_none());
+        host.discovered = vec![PluginInfo {
+            path: scanned.to_path_buf(),
+            name: "Reverb".to_string(),
+            vendor: "Acme".to_string(),
+            audio_inputs: 2,
+            audio_outputs: 2,
+            ..Default::default()
+        }];
+        assert_eq!(
+            host.compatibility_entry(scanned),
+            Some(&entry(Some("Acme"), None))
+        );
+    }
+
+    #[test]
+    fn plugins_in_category_matches_the_path_prefix() {
+        let mut host = Vst3HostBuilder::default().build().unwrap();
+        host.discovered = [
+            "Instrument|Synth|Virtual Analog",
+            "Instrument|Drum",
+            "Fx|Reverb",
+        ]
+        .iter()
+        .map(|category| PluginInfo {
+            name: category.to_string(),
+            category_path: crate::discovery::parse_category(category),
+            audio_outputs: 1,
+            ..Default::default()
+        })
+        .collect();
+        assert_eq!(host.plugins_in_category(&["Instrument"]).len(), 2);
+        assert_eq!(host.plugins_in_category(&["Instrument", "Synth"]).len(), 1);
+        assert!(host.plugins_in_category(&["Synth"]).is_empty());
+        assert_eq!(host.plugins_in_category(&[]).len(), 3);
+        assert_eq!(host.plugins_by_category_tree().children.len(), 2);
+    }
+
+    #[test]
+    fn arpeggiator_is_enabled_until_disabled() {
+        use crate::midi::ArpPattern;
+        use crate::parameters::NoteValue;
+        let mut host = Vst3HostBuilder::default().build().unwrap();
+        assert!(host.arpeggiator().is_none());
+        host.enable_arpeggiator(Arpeggiator::new(ArpPattern::UpDown, NoteValue::Eighth));
+        assert_eq!(host.arpeggiator().unwrap().pattern, ArpPattern::UpDown);
+        host.disable_arpeggiator();
+        assert!(host.arpeggiator().is_none());
+    }
+
+    #[test]
+    fn keyboard_presets_round_trip_through_json() {
+        use crate::midi::ArpPattern;
+        use crate::parameters::NoteValue;
+        let mut host = Vst3HostBuilder::default().build().unwrap();
+        host.set_keyboard_channel(MidiChannel::Ch3);
+        host.set_keyboard_octave_shift(-2);
+        host.set_keyboard_velocity(0);
+        assert_eq!(host.keyboard_velocity(), 1);
+        host.set_keyboard_velocity(90);
+        let mut arp = Arpeggiator::new(ArpPattern::UpDown, NoteValue::Sixteenth);
+        arp.octave_range = 3;
+        arp.gate = 0.25;
+        for note in [60, 64, 67] {
+            arp.handle_event(MidiEvent::NoteOn {
+                channel: MidiChannel::Ch3,
+                note,
+                velocity: 90,
+            });
+        }
+        host.enable_arpeggiator(arp);
+        host.set_cc_bindings(vec![CcBinding::new(1, 74), CcBinding::new(2, 71)], "");
+        host.save_keyboard_preset("Pads").unwrap();
+        assert!(host.save_keyboard_preset(" ").is_err());
+
+        let json = serde_json::to_string(host.keyboard_presets()).unwrap();
+        let mut other = Vst3HostBuilder::default().build().unwrap();
+        other.set_keyboard_presets(serde_json::from_str(&json).unwrap());
+        assert_eq!(other.list_keyboard_presets(), ["Pads"]);
+        assert!(other.load_keyboard_preset("Leads").is_err());
+        other.load_keyboard_preset("Pads").unwrap();
+        assert_eq!(other.keyboard_channel(), MidiChannel::Ch3);
+        assert_eq!(other.keyboard_octave_shift(), -2);
+        assert_eq!(other.keyboard_velocity(), 90);
+        let arp = other.arpeggiator().unwrap();
+        assert_eq!(
+            (arp.pattern, arp.rate, arp.octave_range, arp.gate),
+            (ArpPattern::UpDown, NoteValue::Sixteenth, 3, 0.25)
+        );
+        // Held notes are playing state, not part of the preset.
+        assert!(arp.active_notes.is_empty());
+        assert_eq!(
+            other.cc_bindings(),
+            [CcBinding::new(1, 74), CcBinding::new(2, 71)]
+        );
+    }
+
+    #[test]
+    fn builder_sets_the_advertised_host_identity() {
+        let host = Vst3HostBuilder::default().build().unwrap();
+        assert_eq!(host.host_identity().name, "vst3-host");
+
+        let host = Vst3HostBuilder::default()
+            .host_name("Ableton Live")
+            .host_version_semver(12, 1, 5)
+            .build()
+            .unwrap();
+        let identity = host.host_identity();
+        assert_eq!(identity.name, "Ableton Live");
+        assert_eq!(identity.version, 0x000C_0105);
+        assert_eq!(identity.version_text(), "12.1.5");
+
+        let host = Vst3HostBuilder::default()
+            .host_version_string("12.1b3")
+            .build()
+            .unwrap();
+        assert_eq!(host.host_identity().version_text(), "12.1b3");
+    }
+
+    #[test]
+    fn auto_loading_a_plugin_that_moved_forgets_it() {
+        let mut host = Vst3HostBuilder::default().build().unwrap();
+        assert!(host.auto_load_last_plugin().unwrap().is_none());
+
+        let mut host = Vst3HostBuilder::default()
+            .last_loaded_plugin("/nonexistent/Gone.vst3")
+            .build()
+            .unwrap();
+        assert_eq!(
+            host.last_loaded_plugin(),
+            Some(Path::new("/nonexistent/Gone.vst3"))
+        );
+        assert!(matches!(
+            host.auto_load_last_plugin(),
+            Err(Error::PluginNotFound(_))
+        ));
+        assert_eq!(host.last_loaded_plugin(), None);
+        assert!(host.auto_load_last_plugin().unwrap().is_none());
+    }
+
+    #[test]
+    fn bus_presets_are_kept_per_plugin_and_selected_by_name() {
+        let mut host = Vst3HostBuilder::default().build().unwrap();
+        let stereo = BusConfiguration {
+            active_audio_outputs: vec![true, false],
+            ..Default::default()
+        };
+        host.add_bus_config_preset("/a.vst3", "Stereo", BusConfiguration::default());
+        host.add_bus_config_preset("/a.vst3", "Stereo", stereo.clone());
+        assert_eq!(
+            host.bus_config_presets("/a.vst3"),
+            [("Stereo".to_string(), stereo)]
+        );
+        assert!(host.bus_config_presets("/b.vst3").is_empty());
+
+        assert!(host.set_bus_config_preset("Multi-out").is_err());
+        host.set_bus_config_preset("Stereo").unwrap();
+        assert_eq!(host.bus_config_preset(), Some("Stereo"));
+        host.clear_bus_config_preset();
+        assert_eq!(host.bus_config_preset(), None);
+    }
+
+    #[test]
+    fn discovery_progress_times_and_reports_a_broken_plugin() {
+        let dir = std::env::temp_dir().join(format!("vh_broken_scan_{}", std::process::id()));
+        let broken = dir.join("Broken.vst3");
+        std::fs::create_dir_all(&broken).unwrap();
+        let mut host = Vst3HostBuilder::default()
+            .add_scan_path(&dir)
+            .build()
+            .unwrap();
+
+        let mut events = Vec::new();
+        let found = host
+            .discover_plugins_with_callback(|progress| events.push(progress))
+            .unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(found.is_empty());
+        assert!(matches!(
+            events.as_slice(),
+            [
+                DiscoveryProgress::Started { total_plugins: 1 },
+                DiscoveryProgress::Scanned { path, .. },
+                DiscoveryProgress::Error { .. },
+                DiscoveryProgress::Completed { total_found: 0 },
+            ] if *path == broken
+        ));
+    }
+
+    #[test]
+    fn scan_cache_hash_only_carries_into_the_host() {
+        let mut host = Vst3HostBuilder::default()
+            .scan_cache_hash_only(true)
+            .build()
+            .unwrap();
+        assert!(host.scan_cache().hash_only_mode);
+        host.set_scan_cache(ScanCache::new());
+        assert!(host.scan_cache().hash_only_mode);
+        assert!(!Vst3Host::default().scan_cache().hash_only_mode);
+    }
+
+    #[test]
+    fn init_flags_match_by_path_substring_and_persist() {
+        let file = std::env::temp_dir().join(format!("init-flags-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&file);
+        let mut host = Vst3HostBuilder::default()
+            .init_flags_file(&file)
+            .plugin_init_flags("WaveShell", PluginInitFlags::SKIP_GUI_CHECK)
+            .build()
+            .unwrap();
+        let shell = Path::new("/vst3/WaveShell1-VST3 14.0.vst3");
+        host.set_plugin_init_flags(shell, PluginInitFlags::SKIP_BUS_NEGOTIATION)
+            .unwrap();
+        assert_eq!(
+            host.plugin_init_flags(shell),
+            PluginInitFlags::SKIP_GUI_CHECK | PluginInitFlags::SKIP_BUS_NEGOTIATION
+        );
+        assert!(host
+            .plugin_init_flags(Path::new("/vst3/Diva.vst3"))
+            .is_empty());
+
+        let reloaded = Vst3HostBuilder::default()
+            .init_flags_file(&file)
+            .build()
+            .unwrap();
+        let _ = std::fs::remove_file(&file);
+        assert_eq!(reloaded.init_flag_rules(), host.init_flag_rules());
+    }
+
+    #[test]
+    fn a_leftover_load_journal_reports_the_interrupted_phase() {
+        let journal = std::env::temp_dir().join(format!("load-journal-{}", std::process::id()));
+        let host = Vst3HostBuilder::default()
+            .load_journal(&journal)
+            .build()
+            .unwrap();
+        assert_eq!(host.interrupted_load(), None);
+        let plugin = Path::new("/vst3/Crashy.vst3");
+        write_load_journal(&journal, plugin, LoadPhase::ControllerInit);
+
+        let (path, phase) = host.interrupted_load().unwrap();
+        assert_eq!(path, plugin);
+        assert_eq!(
+            phase.skip_flag(),
+            Some(PluginInitFlags::SKIP_CONTROLLER_INIT)
+        );
+        host.clear_interrupted_load();
+        assert_eq!(host.interrupted_load(), None);
+    }
+
+    #[test]
+    fn input_gain_settings_shape_the_input_stage() {
+        let mut host = Vst3HostBuilder::default().build().unwrap();
+        host.set_auto_gain(-20.0);
+        let stage = host.input_gain_stage();
+        assert!(stage.auto_gain);
+        assert_eq!(stage.target_db, -20.0);
+        host.set_input_gain(3.0);
+        let stage = host.input_gain_stage();
+        assert!(!stage.auto_gain);
+        assert_eq!(stage.gain_db, 3.0);
+    }
+
+    #[test]
+    fn auto_limiter_follows_the_builder_and_threshold() {
+        assert!(Vst3HostBuilder::default()
+            .build()
+            .unwrap()
+            .auto_limiter()
+            .is_none());
+        let mut host = Vst3HostBuilder::default()
+            .sample_rate(48_000.0)
+            .auto_limiter(true)
+            .build()
+            .unwrap();
+        host.set_limiter_threshold(-1.0);
+        let limiter = host.auto_limiter().unwrap();
+        assert_eq!(limiter.threshold_db, -1.0);
+        assert_eq!(limiter.sample_rate(), 48_000.0);
+    }
+
+    #[test]
+    fn controller_preset_round_trips_through_a_file() {
+        use crate::midi::MidiChannel;
+        let mut host = Vst3HostBuilder::default().build().unwrap();
+        let generic = Vst3Host::list_bundled_presets()[0];
+        assert_eq!(generic.bindings.len(), 8);
+        host.apply_controller_preset(generic);
+        let fader = MidiEvent::ControlChange {
+            channel: MidiChannel::Ch5,
+            controller: 23,
+            value: 127,
+        };
+        assert_eq!(host.cc_parameter_changes(&fader), [(3, 1.0)]);
+
+        let path = std::env::temp_dir().join(format!("cc-preset-{}.json", std::process::id()));
+        host.save_controller_preset(&path, "Mine").unwrap();
+        let mut other = Vst3HostBuilder::default().build().unwrap();
+        other.load_controller_preset(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(other.cc_bindings(), generic.bindings);
+        assert!(other
+            .load_controller_preset(Path::new("/nonexistent/preset.json"))
+            .is_err());
+    }
+
+    #[test]
+    fn annotations_persist_to_the_annotations_file() {
+        let file = std::env::temp_dir().join(format!("annotations-{}.json", std::process::id()));
+        let plugin = Path::new("/vst3/Diva.vst3");
+        let mut annotation = PluginAnnotation {
+            notes: "license expires 2025-12".to_string(),
+            rating: 4,
+            favorite: true,
+            ..Default::default()
+        };
+        assert!(annotation.add_tag("Drums"));
+        assert!(!annotation.add_tag(" drums "));
+
+        let mut host = Vst3HostBuilder::default()
+            .annotations_file(&file)
+            .build()
+            .unwrap();
+        host.annotate_plugin(plugin, annotation.clone()).unwrap();
+
+        let reopened = Vst3HostBuilder::default()
+            .annotations_file(&file)
+            .build()
+            .unwrap();
+        assert_eq!(reopened.plugin_annotation(plugin), Some(&annotation));
+        assert!(reopened.plugin_annotation(plugin).unwrap().has_tag("DRU"));
+
+        // An empty annotation removes the entry (and the file follows).
+        host.annotate_plugin(plugin, PluginAnnotation::default())
+            .unwrap();
+        let reopened = Vst3HostBuilder::default()
+            .annotations_file(&file)
+            .build()
+            .unwrap();
+        std::fs::remove_file(&file).ok();
+        assert!(reopened.plugin_annotations().is_empty());
+    }
+
+    #[test]
+    fn route_midi_applies_the_keyboard_split() {
+        use crate::midi::{MidiChannel, SplitRegion};
+        let mut host = Vst3HostBuilder::default().build().unwrap();
+        let on = MidiEvent::NoteOn {
+            channel: MidiChannel::Ch1,
+            note: 60,
+            velocity: 90,
+        };
+        assert_eq!(host.route_midi(on), None);
+
+        host.set_keyboard_split(KeyboardSplit {
+            splits: vec![SplitRegion {
+                note_range: 60..=72,
+                target_plugin: PluginHandle(3),
+                channel: MidiChannel::Ch4,
+            }],
+        });
+        let routed = host.route_midi(on).unwrap();
+        assert_eq!(
+            routed,
+            vec![(PluginHandle(3), on.with_channel(MidiChannel::Ch4))]
+        );
+
+        host.clear_keyboard_split();
+        assert!(host.keyboard_split().is_none());
+    }
+
+    #[test]
+    fn route_midi_prefers_the_routing_matrix() {
+        use crate::midi::MidiChannel;
+        let mut host = Vst3HostBuilder::default().build().unwrap();
+        let on = MidiEvent::NoteOn {
+            channel: MidiChannel::Ch1,
+            note: 60,
+            velocity: 90,
+        };
+        host.set_keyboard_split(KeyboardSplit::default());
+        let mut matrix = RoutingMatrix::new();
+        matrix.connect(MidiChannel::Ch1, PluginHandle(0), MidiChannel::Ch1);
+        matrix.connect(MidiChannel::Ch1, PluginHandle(1), MidiChannel::Ch2);
+        host.set_midi_routing(matrix);
+        assert_eq!(
+            host.route_midi(on).unwrap(),
+            vec![
+                (PluginHandle(0), on),
+                (PluginHandle(1), on.with_channel(MidiChannel::Ch2)),
+            ]
+        );
+
+        // Back to the (empty) split, which drops everything.
+        host.clear_midi_routing();
+        assert_eq!(host.route_midi(on), Some(Vec::new()));
+    }
+}
+
+#[cfg(test)]
+mod copy_parameter_tests {
+    use super::*;
+    use crate::parameters::Parameter;
+
+    /// A plugin whose parameters are all frequencies from 0 to `max_hz`, displayed as "N Hz".
+    struct Knobs {
+        titles: Vec<(u32, &'static str, bool)>,
+        values: HashMap<u32, f64>,
+        max_hz: f64,
+    }
+
+    impl PluginInternal for Knobs {
+        fn set_parameter(&mut self, id: u32, value: f64) -> Result<()> {
+            self.values.insert(id, value);
+            Ok(())
+        }
+        fn get_parameter(&self, id: u32) -> Result<f64> {
+            Ok(self.values.get(&id).copied().unwrap_or(0.0))
+        }
+        fn get_all_parameters(&self) -> Result<Vec<Parameter>> {
+            Ok(self
+                .titles
+                .iter()
+                .map(|&(id, title, is_read_only)| Parameter {
+                    id,
+                    name: title.to_string(),
+                    value: self.get_parameter(id).unwrap(),
+                    min: 0.0,
+                    max: 1.0,
+                    default: 0.0,
+                    unit: "Hz".to_string(),
+                    step_count: 0,
+                    can_automate: true,
+                    is_read_only,
+                    is_bypass: false,
+                    flags: 0,
+                    unit_id: 0,
+                })
+                .collect())
+        }
+        fn format_parameter(&self, _id: u32, normalized: f64) -> Result<String> {
+            Ok(format!("{:.0} Hz", normalized * self.max_hz))
+        }
+        fn parse_parameter(&self, _id: u32, text: &str) -> Result<f64> {
+            let hz: f64 = text
+                .trim_end_matches(" Hz")
+                .parse()
+                .map_err(|_| Error::InvalidParameter(text.to_string()))?;
+            Ok((hz / self.max_hz).clamp(0.0, 1.0))
+        }
+        crate::plugin::stub_plugin_internal!(
+            except set_parameter, get_parameter, get_all_parameters, format_parameter
+        );
+    }
+
+    fn knobs(uid: &str, max_hz: f64, titles: Vec<(u32, &'static str, bool)>) -> Plugin {
+        let mut plugin = crate::plugin::plugin_with(Box::new(Knobs {
+            titles,
+            values: HashMap::new(),
+            max_hz,
+        }));
+        plugin.info.name = uid.to_string();
+        plugin.info.uid = uid.to_string();
+        plugin.info.audio_outputs = 2;
+        plugin
+    }
+
+    #[test]
+    fn same_plugin_copies_the_normalized_value_exactly() {
+        let host = Vst3HostBuilder::default().build().unwrap();
+        let mut src = knobs("synth", 1000.0, vec![(1, "Cutoff", false)]);
+        let mut dst = knobs("synth", 1000.0, vec![(1, "Cutoff", false)]);
+        for value in [0.0, 0.123456789, 0.5, 1.0] {
+            src.set_parameter(1, value).unwrap();
+            host.copy_parameter(&src, &mut dst, 1).unwrap();
+            assert_eq!(dst.get_parameter(1).unwrap(), value);
+            // And back again, unchanged.
+            dst.set_parameter(1, value).unwrap();
+            host.copy_parameter(&dst, &mut src, 1).unwrap();
+            assert_eq!(src.get_parameter(1).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn different_plugins_convert_through_the_display_value() {
+        let host = Vst3HostBuilder::default().build().unwrap();
+        let mut src = knobs("a", 1000.0, vec![(1, "Cutoff", false)]);
+        let mut dst = knobs("b", 4000.0, vec![(1, "Cutoff", false)]);
+        src.set_parameter(1, 0.5).unwrap();
+        host.copy_parameter(&src, &mut dst, 1).unwrap();
+        // 500 Hz on a 0–4000 Hz range.
+        assert_eq!(dst.get_parameter(1).unwrap(), 0.125);
+        assert_eq!(dst.format_parameter(1, 0.125).unwrap(), "500 Hz");
+    }
+
+    #[test]
+    fn copy_by_name_matches_titles_case_insensitively() {
+        let host = Vst3HostBuilder::default().build().unwrap();
+        let mut src = knobs(
+            "a",
+            1000.0,
+            vec![
+                (1, "Cutoff", false),
+                (2, "Resonance", false),
+                (3, "Drive", false),
+            ],
+        );
+        let mut dst = knobs(
+            "b",
+            2000.0,
+            vec![
+                (10, "CUTOFF", false),
+                (11, "resonance", true),
+                (12, "Mix", false),
+            ],
+        );
+        src.set_parameter(1, 0.5).unwrap();
+        let report = host.copy_all_parameters_by_name(&src, &mut dst).unwrap();
+        assert_eq!(
+            report,
+            CopyReport {
+                copied: 1,
+                skipped: 2,
+                failed: 0,
+            }
+        );
+        assert_eq!(dst.get_parameter(10).unwrap(), 0.25);
+        assert_eq!(dst.get_parameter(11).unwrap(), 0.0);
+    }
 }
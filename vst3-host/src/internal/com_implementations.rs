@@ -1,9 +1,12 @@
 //! Internal COM interface implementations for VST3
 
+use crate::host::HostIdentity;
+use crate::plugin::AttributeValue;
 use std::collections::HashMap;
 use std::ffi::CStr;
 use std::ptr;
-use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use vst3::{Class, ComWrapper, Interface, Steinberg::Vst::*, Steinberg::*};
 
@@ -11,9 +14,37 @@ use vst3::{Class, ComWrapper, Interface, Steinberg::Vst::*, Steinberg::*};
 //
 // Many plugins (u-he, Waves, ...) query the context passed to `IComponent::initialize`
 // for `IHostApplication` and dereference it. Passing a null context makes them crash.
-// Providing a real host-application object that at least answers `getName` lets them
-// initialize. (We don't yet vend host-created objects like IMessage/IAttributeList.)
-pub struct HostApplication;
+// Providing a real host-application object that answers `getName` (with the configured
+// `HostIdentity`) and vends IMessage/IAttributeList lets them initialize.
+pub struct HostApplication {
+    identity: HostIdentity,
+}
+
+impl HostApplication {
+    pub fn new(identity: HostIdentity) -> Self {
+        Self { identity }
+    }
+
+    /// The object `createInstance` hands out for class id `cid`, as a raw COM pointer the
+    /// caller owns; `kNoInterface` for classes the host doesn't make.
+    pub fn create_instance(
+        &self,
+        cid: &[u8],
+    ) -> std::result::Result<*mut std::ffi::c_void, tresult> {
+        let raw = if cid == &IMessage::IID[..] {
+            create_host_message()
+                .to_com_ptr::<IMessage>()
+                .map(|p| p.into_raw() as *mut std::ffi::c_void)
+        } else if cid == &IAttributeList::IID[..] {
+            create_host_attribute_list()
+                .to_com_ptr::<IAttributeList>()
+                .map(|p| p.into_raw() as *mut std::ffi::c_void)
+        } else {
+            None
+        };
+        raw.ok_or(kNoInterface)
+    }
+}
 
 impl Class for HostApplication {
     // The standard SDK host context implements both IHostApplication and
@@ -44,7 +75,7 @@ impl IHostApplicationTrait for HostApplication {
         }
         let dst = &mut *name;
         let mut i = 0;
-        for ch in "vst3-host".encode_utf16() {
+        for ch in self.identity.name.encode_utf16() {
             if i + 1 >= dst.len() {
                 break;
             }
@@ -63,7 +94,7 @@ impl IHostApplicationTrait for HostApplication {
     ) -> tresult {
         // Vend the host-created objects plugins ask for (the SDK's HostApplication does
         // this): IMessage and IAttributeList, used to pass data between a plugin's
-        // component and controller halves. Anything else fails cleanly.
+        // component and controller halves. Anything else is kNoInterface.
         if obj.is_null() || cid.is_null() {
             return kResultFalse;
         }
@@ -72,43 +103,30 @@ impl IHostApplicationTrait for HostApplication {
         // Compare the requested class id to a known IID by raw bytes (TUID element type
         // is platform-dependent, so avoid a typed array compare).
         let cid_bytes = std::slice::from_raw_parts(cid as *const u8, 16);
-        let matches = |iid: &[u8; 16]| cid_bytes == &iid[..];
-
-        if matches(&IMessage::IID) {
-            if let Some(p) = create_host_message().to_com_ptr::<IMessage>() {
-                *obj = p.into_raw() as *mut std::ffi::c_void;
-                return kResultTrue;
-            }
-        } else if matches(&IAttributeList::IID) {
-            if let Some(p) = create_host_attribute_list().to_com_ptr::<IAttributeList>() {
-                *obj = p.into_raw() as *mut std::ffi::c_void;
-                return kResultTrue;
+        match self.create_instance(cid_bytes) {
+            Ok(instance) => {
+                *obj = instance;
+                kResultTrue
             }
+            Err(result) => result,
         }
-        kResultFalse
     }
 }
 
-/// Create a host-application context to pass to `IComponent::initialize`.
+/// Create a host-application context to pass to `IComponent::initialize`, introducing the
+/// host as the default [`HostIdentity`].
 pub fn create_host_application() -> ComWrapper<HostApplication> {
-    ComWrapper::new(HostApplication)
+    ComWrapper::new(HostApplication::new(HostIdentity::default()))
 }
 
 // A host-side IAttributeList: a typed key/value bag plugins use (via the host's
-// createInstance) to pass data between their component and controller halves.
-#[derive(Debug, Clone, PartialEq)]
-enum AttrValue {
-    Int(i64),
-    Float(f64),
-    /// UTF-16 (TChar) string, not null-terminated.
-    Str(Vec<u16>),
-    Bin(Vec<u8>),
-}
+// createInstance) to pass data between their component and controller halves, and (via
+// IStreamAttributes on an AttributeStream) to save state as key/value pairs.
 
 /// Host implementation of `IAttributeList`.
 #[derive(Default)]
 pub struct HostAttributeList {
-    attrs: Mutex<HashMap<String, AttrValue>>,
+    attrs: Mutex<HashMap<String, AttributeValue>>,
 }
 
 impl HostAttributeList {
@@ -116,13 +134,25 @@ impl HostAttributeList {
         Self::default()
     }
 
+    /// A list pre-filled with `values`, for a plugin to read back in `setState`.
+    pub fn from_values(values: HashMap<String, AttributeValue>) -> Self {
+        Self {
+            attrs: Mutex::new(values),
+        }
+    }
+
+    /// A copy of every attribute set so far.
+    pub fn values(&self) -> HashMap<String, AttributeValue> {
+        self.attrs.lock().map(|m| m.clone()).unwrap_or_default()
+    }
+
     // Safe inner API (also the unit-test surface).
-    fn put(&self, key: String, value: AttrValue) {
+    fn put(&self, key: String, value: AttributeValue) {
         if let Ok(mut m) = self.attrs.lock() {
             m.insert(key, value);
         }
     }
-    fn get_value(&self, key: &str) -> Option<AttrValue> {
+    fn get_value(&self, key: &str) -> Option<AttributeValue> {
         self.attrs.lock().ok().and_then(|m| m.get(key).cloned())
     }
 }
@@ -141,12 +171,12 @@ impl Class for HostAttributeList {
 
 impl IAttributeListTrait for HostAttributeList {
     unsafe fn setInt(&self, id: *const std::os::raw::c_char, value: i64) -> tresult {
-        self.put(attr_key(id), AttrValue::Int(value));
+        self.put(attr_key(id), AttributeValue::Int(value));
         kResultOk
     }
     unsafe fn getInt(&self, id: *const std::os::raw::c_char, value: *mut i64) -> tresult {
         match self.get_value(&attr_key(id)) {
-            Some(AttrValue::Int(v)) if !value.is_null() => {
+            Some(AttributeValue::Int(v)) if !value.is_null() => {
                 *value = v;
                 kResultOk
             }
@@ -154,12 +184,12 @@ impl IAttributeListTrait for HostAttributeList {
         }
     }
     unsafe fn setFloat(&self, id: *const std::os::raw::c_char, value: f64) -> tresult {
-        self.put(attr_key(id), AttrValue::Float(value));
+        self.put(attr_key(id), AttributeValue::Float(value));
         kResultOk
     }
     unsafe fn getFloat(&self, id: *const std::os::raw::c_char, value: *mut f64) -> tresult {
         match self.get_value(&attr_key(id)) {
-            Some(AttrValue::Float(v)) if !value.is_null() => {
+            Some(AttributeValue::Float(v)) if !value.is_null() => {
                 *value = v;
                 kResultOk
             }
@@ -176,7 +206,10 @@ impl IAttributeListTrait for HostAttributeList {
             buf.push(*p);
             p = p.add(1);
         }
-        self.put(attr_key(id), AttrValue::Str(buf));
+        self.put(
+            attr_key(id),
+            AttributeValue::String(String::from_utf16_lossy(&buf)),
+        );
         kResultOk
     }
     unsafe fn getString(
@@ -186,7 +219,8 @@ impl IAttributeListTrait for HostAttributeList {
         size_in_bytes: u32,
     ) -> tresult {
         match self.get_value(&attr_key(id)) {
-            Some(AttrValue::Str(v)) if !string.is_null() => {
+            Some(AttributeValue::String(v)) if !string.is_null() => {
+                let v: Vec<u16> = v.encode_utf16().collect();
                 // Copy up to capacity-1 chars, then null-terminate.
                 let cap_chars = (size_in_bytes as usize / 2).saturating_sub(1);
                 let n = v.len().min(cap_chars);
@@ -209,7 +243,7 @@ impl IAttributeListTrait for HostAttributeList {
             return kResultFalse;
         }
         let bytes = std::slice::from_raw_parts(data as *const u8, size_in_bytes as usize).to_vec();
-        self.put(attr_key(id), AttrValue::Bin(bytes));
+        self.put(attr_key(id), AttributeValue::Binary(bytes));
         kResultOk
     }
     unsafe fn getBinary(
@@ -224,7 +258,7 @@ impl IAttributeListTrait for HostAttributeList {
             return kResultFalse;
         }
         if let Ok(m) = self.attrs.lock() {
-            if let Some(AttrValue::Bin(v)) = m.get(&attr_key(id)) {
+            if let Some(AttributeValue::Binary(v)) = m.get(&attr_key(id)) {
                 *data = v.as_ptr() as *const std::ffi::c_void;
                 *size_in_bytes = v.len() as u32;
                 return kResultOk;
@@ -444,6 +478,85 @@ pub fn create_memory_stream_from(data: Vec<u8>) -> ComWrapper<MemoryStream> {
     ComWrapper::new(MemoryStream::new(data))
 }
 
+// A state stream that also carries an attribute list. Plugins that keep their state as
+// key/value pairs query the stream they get in `getState`/`setState` for
+// `IStreamAttributes` and read or write the list from `getAttributes`.
+pub struct AttributeStream {
+    stream: MemoryStream,
+    attributes: ComWrapper<HostAttributeList>,
+}
+
+impl AttributeStream {
+    /// A copy of everything written to the stream (used after `getState`).
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.stream.to_vec()
+    }
+
+    /// A copy of the attributes the plugin set (used after `getState`).
+    pub fn attributes(&self) -> HashMap<String, AttributeValue> {
+        self.attributes.values()
+    }
+}
+
+impl Class for AttributeStream {
+    type Interfaces = (IBStream, IStreamAttributes);
+}
+
+impl IBStreamTrait for AttributeStream {
+    unsafe fn read(
+        &self,
+        buffer: *mut std::ffi::c_void,
+        num_bytes: i32,
+        num_bytes_read: *mut i32,
+    ) -> tresult {
+        self.stream.read(buffer, num_bytes, num_bytes_read)
+    }
+
+    unsafe fn write(
+        &self,
+        buffer: *mut std::ffi::c_void,
+        num_bytes: i32,
+        num_bytes_written: *mut i32,
+    ) -> tresult {
+        self.stream.write(buffer, num_bytes, num_bytes_written)
+    }
+
+    unsafe fn seek(&self, pos: i64, mode: i32, result: *mut i64) -> tresult {
+        self.stream.seek(pos, mode, result)
+    }
+
+    unsafe fn tell(&self, pos: *mut i64) -> tresult {
+        self.stream.tell(pos)
+    }
+}
+
+impl IStreamAttributesTrait for AttributeStream {
+    unsafe fn getFileName(&self, _name: *mut String128) -> tresult {
+        // State streams are in memory; there's no file behind them.
+        kNotImplemented
+    }
+
+    unsafe fn getAttributes(&self) -> *mut IAttributeList {
+        // Borrowed pointer to the stream's own attribute list (kept alive by `self`).
+        self.attributes
+            .to_com_ptr::<IAttributeList>()
+            .map(|p| p.as_ptr())
+            .unwrap_or(ptr::null_mut())
+    }
+}
+
+/// Create a state stream over `data` whose attribute list starts out as `values`. Pass
+/// empty ones for `getState`.
+pub fn create_attribute_stream(
+    data: Vec<u8>,
+    values: HashMap<String, AttributeValue>,
+) -> ComWrapper<AttributeStream> {
+    ComWrapper::new(AttributeStream {
+        stream: MemoryStream::new(data),
+        attributes: ComWrapper::new(HostAttributeList::from_values(values)),
+    })
+}
+
 // Host implementation of `IPlugFrame`. A plugin editor calls `resizeView` to ask the host
 // to resize the window hosting its view. We record the requested size; the host polls it
 // (take_editor_resize_request) and resizes its container on the UI thread.
@@ -486,22 +599,69 @@ pub fn create_host_plug_frame(
 
 // Component Handler implementation
 pub struct ComponentHandler {
-    // Track parameter changes from the plugin
-    pub parameter_changes: Arc<Mutex<Vec<(u32, f64)>>>,
+    // Parameter changes from the plugin's editor, sent on to whoever feeds them to the DSP
+    // (the plugin's `process()` drains the receiver). A channel rather than a locked Vec so the
+    // editor thread never waits on the audio thread.
+    parameter_changes: Sender<(u32, f64)>,
+    // Further consumers for the host's display: every performEdit is forwarded to each as it
+    // happens, stamped with the time it arrived (see `subscribe`).
+    display: Mutex<Vec<Sender<crate::parameters::ParameterChange>>>,
     // Ordered log of begin/change/end gestures the editor reports, preserving their order so
     // the host can reconstruct each gesture (drained via `take_parameter_edits`). This is the
     // richer superset of `parameter_changes` (which keeps only the value changes for the DSP).
     edits: Arc<Mutex<Vec<crate::plugin::ParameterEdit>>>,
+    // Set when the plugin calls restartComponent with kIoChanged: its bus layout changed and
+    // the host's cached copy is stale (see `take_io_changed`).
+    io_changed: AtomicBool,
 }
 
 impl ComponentHandler {
-    pub fn new(parameter_changes: Arc<Mutex<Vec<(u32, f64)>>>) -> Self {
+    pub fn new(parameter_changes: Sender<(u32, f64)>) -> Self {
         ComponentHandler {
             parameter_changes,
+            display: Mutex::new(Vec::new()),
             edits: Arc::new(Mutex::new(Vec::new())),
+            io_changed: AtomicBool::new(false),
         }
     }
 
+    /// Whether the plugin has reported an I/O change since the last call, clearing the flag.
+    pub fn take_io_changed(&self) -> bool {
+        self.io_changed.swap(false, Ordering::AcqRel)
+    }
+
+    /// Start forwarding every performEdit to the returned receiver the moment it arrives,
+    /// alongside any earlier subscribers.
+    pub fn subscribe(&self) -> Receiver<crate::parameters::ParameterChange> {
+        let (tx, rx) = mpsc::channel();
+        self.display
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .push(tx);
+        rx
+    }
+
+    /// Move the display subscribers to `successor`, so receivers handed out by
+    /// [`subscribe`](Self::subscribe) keep receiving edits from a replacement instance.
+    pub fn hand_over_subscriber(&self, successor: &ComponentHandler) {
+        let subscribers =
+            std::mem::take(&mut *self.display.lock().unwrap_or_else(|p| p.into_inner()));
+        successor
+            .display
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .extend(subscribers);
+    }
+
+    /// Whether any display subscriber is still listening.
+    pub fn is_subscribed(&self) -> bool {
+        !self
+            .display
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .is_empty()
+    }
+
     /// Drain the ordered parameter-edit gesture log accumulated since the last call.
     pub fn take_parameter_edits(&self) -> Vec<crate::plugin::ParameterEdit> {
         // A COM FFI callback could be mid-push when a previous one panicked; recover the lock
@@ -541,10 +701,21 @@ impl IComponentHandlerTrait for ComponentHandler {
             id,
             value_normalized
         );
-        // Store the parameter change for the DSP-feeding drain...
-        if let Ok(mut changes) = self.parameter_changes.lock() {
-            changes.push((id, value_normalized));
+        // Send the parameter change on for the DSP (a gone receiver means the plugin is being
+        // torn down; nothing to feed)...
+        let _ = self.parameter_changes.send((id, value_normalized));
+        // ...straight to the display subscribers, dropping each once its receiver is gone...
+        let mut display = self.display.lock().unwrap_or_else(|p| p.into_inner());
+        if !display.is_empty() {
+            let change = crate::parameters::ParameterChange {
+                id,
+                value: value_normalized,
+                sample_offset: 0,
+                timestamp: std::time::Instant::now(),
+            };
+            display.retain(|tx| tx.send(change.clone()).is_ok());
         }
+        drop(display);
         // ...and as an ordered gesture event for the richer `take_parameter_edits` drain.
         self.push_edit(crate::plugin::ParameterEdit {
             id,
@@ -566,6 +737,9 @@ impl IComponentHandlerTrait for ComponentHandler {
 
     unsafe fn restartComponent(&self, flags: i32) -> i32 {
         log::debug!("Host: Restart component requested with flags: {}", flags);
+        if flags & RestartFlags_::kIoChanged != 0 {
+            self.io_changed.store(true, Ordering::Release);
+        }
         kResultOk
     }
 }
@@ -595,12 +769,16 @@ impl IComponentHandler2Trait for ComponentHandler {
 // Event List implementation
 pub struct HostEventList {
     pub events: Mutex<Vec<Event>>,
+    /// Backing bytes for queued `kDataEvent`s. A `DataEvent` only points at its payload, so
+    /// the list owns each payload until [`clear`](Self::clear) drops it with the events.
+    data: Mutex<Vec<Box<[u8]>>>,
 }
 
 impl HostEventList {
     pub fn new() -> Self {
         Self {
             events: Mutex::new(Vec::new()),
+            data: Mutex::new(Vec::new()),
         }
     }
 
@@ -614,6 +792,35 @@ impl HostEventList {
                 log::error!("HostEventList: Failed to lock events for clear");
             }
         }
+        if let Ok(mut data) = self.data.lock() {
+            data.clear();
+        }
+    }
+
+    /// Queue a `kDataEvent` of type `kMidiSysEx` carrying `bytes`, which the list keeps alive
+    /// until the next [`clear`](Self::clear).
+    pub fn add_sysex(&self, bytes: Vec<u8>, sample_offset: i32) {
+        let bytes = bytes.into_boxed_slice();
+        // SAFETY: `Event` is a plain C struct; all-zero is a valid value for every field.
+        let mut event: Event = unsafe { std::mem::zeroed() };
+        event.busIndex = 0;
+        event.sampleOffset = sample_offset.max(0);
+        event.flags = Event_::EventFlags_::kIsLive as u16;
+        event.r#type = Event_::EventTypes_::kDataEvent as u16;
+        event.__field0.data = DataEvent {
+            size: bytes.len() as u32,
+            r#type: DataEvent_::DataTypes_::kMidiSysEx,
+            bytes: bytes.as_ptr(),
+        };
+        // Moving the box into the list leaves its heap allocation, and so `bytes`, in place.
+        match self.data.lock() {
+            Ok(mut data) => data.push(bytes),
+            Err(_) => {
+                log::error!("HostEventList: Failed to lock data for add_sysex");
+                return;
+            }
+        }
+        self.add_event(event);
     }
 
     /// Clamp every queued event's `sampleOffset` into `[0, max_offset]`, so an event scheduled
@@ -1036,17 +1243,108 @@ mod host_attr_tests {
     #[test]
     fn attribute_list_round_trips_each_type() {
         let list = HostAttributeList::new();
-        list.put("i".into(), AttrValue::Int(42));
-        list.put("f".into(), AttrValue::Float(1.5));
-        list.put("s".into(), AttrValue::Str(vec![72, 105])); // "Hi"
-        list.put("b".into(), AttrValue::Bin(vec![1, 2, 3]));
-
-        assert_eq!(list.get_value("i"), Some(AttrValue::Int(42)));
-        assert_eq!(list.get_value("f"), Some(AttrValue::Float(1.5)));
-        assert_eq!(list.get_value("s"), Some(AttrValue::Str(vec![72, 105])));
-        assert_eq!(list.get_value("b"), Some(AttrValue::Bin(vec![1, 2, 3])));
+        list.put("i".into(), AttributeValue::Int(42));
+        list.put("f".into(), AttributeValue::Float(1.5));
+        list.put("s".into(), AttributeValue::String("Hi".into()));
+        list.put("b".into(), AttributeValue::Binary(vec![1, 2, 3]));
+
+        assert_eq!(list.get_value("i"), Some(AttributeValue::Int(42)));
+        assert_eq!(list.get_value("f"), Some(AttributeValue::Float(1.5)));
+        assert_eq!(
+            list.get_value("s"),
+            Some(AttributeValue::String("Hi".into()))
+        );
+        assert_eq!(
+            list.get_value("b"),
+            Some(AttributeValue::Binary(vec![1, 2, 3]))
+        );
         assert_eq!(list.get_value("missing"), None);
     }
+
+    // What a plugin does in getState/setState: write and read bytes on the IBStream, and
+    // key/value pairs on the list it gets from IStreamAttributes.
+    #[test]
+    fn attribute_stream_round_trips_attributes_alongside_bytes() {
+        let saved = create_attribute_stream(Vec::new(), HashMap::new());
+        unsafe {
+            let stream = saved.to_com_ptr::<IBStream>().unwrap();
+            let mut bytes = [7u8, 8, 9];
+            stream.write(bytes.as_mut_ptr().cast(), 3, ptr::null_mut());
+            let attrs = stream.cast::<IStreamAttributes>().unwrap();
+            let list = attrs.getAttributes();
+            assert!(!list.is_null());
+            let list = vst3::ComRef::from_raw(list).unwrap();
+            list.setInt(c"gain".as_ptr(), -3);
+            list.setFloat(c"cutoff".as_ptr(), 0.25);
+            let name: Vec<u16> = "Lead \u{00E9}".encode_utf16().chain([0]).collect();
+            list.setString(c"name".as_ptr(), name.as_ptr());
+        }
+        let values = saved.attributes();
+        assert_eq!(values.len(), 3);
+        assert_eq!(saved.to_vec(), [7, 8, 9]);
+
+        let restored = create_attribute_stream(saved.to_vec(), values);
+        unsafe {
+            let stream = restored.to_com_ptr::<IBStream>().unwrap();
+            let mut bytes = [0u8; 3];
+            let mut read = 0;
+            stream.read(bytes.as_mut_ptr().cast(), 3, &mut read);
+            assert_eq!((read, bytes), (3, [7, 8, 9]));
+            let attrs = stream.cast::<IStreamAttributes>().unwrap();
+            let list = vst3::ComRef::from_raw(attrs.getAttributes()).unwrap();
+            let mut gain = 0i64;
+            assert_eq!(list.getInt(c"gain".as_ptr(), &mut gain), kResultOk);
+            assert_eq!(gain, -3);
+            let mut cutoff = 0f64;
+            assert_eq!(list.getFloat(c"cutoff".as_ptr(), &mut cutoff), kResultOk);
+            assert_eq!(cutoff, 0.25);
+            let mut name = [0u16; 32];
+            assert_eq!(
+                list.getString(c"name".as_ptr(), name.as_mut_ptr(), 64),
+                kResultOk
+            );
+            let len = name.iter().position(|&c| c == 0).unwrap();
+            assert_eq!(String::from_utf16_lossy(&name[..len]), "Lead \u{00E9}");
+        }
+        assert_eq!(restored.attributes(), saved.attributes());
+    }
+}
+
+#[cfg(test)]
+mod host_application_tests {
+    use super::*;
+    use vst3::ComPtr;
+
+    #[test]
+    fn creates_messages_and_declines_other_classes() {
+        let app = HostApplication::new(HostIdentity::default());
+        let message = app.create_instance(&IMessage::IID).unwrap();
+        assert!(!message.is_null());
+        // Hand the reference back so the message is freed.
+        drop(unsafe { ComPtr::<IMessage>::from_raw(message as *mut IMessage) });
+        assert_eq!(
+            app.create_instance(&FUnknown::IID).unwrap_err(),
+            kNoInterface
+        );
+
+        // FUnknown is the SDK's IUnknown.
+        let mut obj = ptr::null_mut();
+        let mut cid: TUID = FUnknown::IID.map(|b| b as _);
+        let result = unsafe { app.createInstance(&mut cid, &mut cid, &mut obj) };
+        assert_eq!((result, obj), (kNoInterface, ptr::null_mut()));
+    }
+
+    #[test]
+    fn get_name_returns_the_configured_name() {
+        let app = HostApplication::new(HostIdentity {
+            name: "Ableton Live".into(),
+            ..HostIdentity::default()
+        });
+        let mut name: String128 = [0; 128];
+        assert_eq!(unsafe { app.getName(&mut name) }, kResultOk);
+        let len = name.iter().position(|&c| c == 0).unwrap();
+        assert_eq!(String::from_utf16_lossy(&name[..len]), "Ableton Live");
+    }
 }
 
 #[cfg(test)]
@@ -1054,9 +1352,24 @@ mod component_handler_tests {
     use super::*;
     use crate::plugin::{ParameterEdit, ParameterEditKind};
 
+    #[test]
+    fn a_subscriber_handed_over_hears_the_successor() {
+        let old = ComponentHandler::new(mpsc::channel().0);
+        let rx = old.subscribe();
+        let fresh = ComponentHandler::new(mpsc::channel().0);
+        old.hand_over_subscriber(&fresh);
+        drop(old);
+
+        unsafe { fresh.performEdit(3, 0.75) };
+        let change = rx.try_recv().unwrap();
+        assert_eq!((change.id, change.value), (3, 0.75));
+        assert!(fresh.is_subscribed());
+    }
+
     #[test]
     fn captures_begin_perform_end_in_order_and_drains() {
-        let handler = ComponentHandler::new(Arc::new(Mutex::new(Vec::new())));
+        let (tx, rx) = mpsc::channel();
+        let handler = ComponentHandler::new(tx);
 
         // Drive a full gesture: mouse-down, two drag values, mouse-up.
         unsafe {
@@ -1095,10 +1408,49 @@ mod component_handler_tests {
 
         // The drain empties the buffer; the value-change sink still mirrors the performEdits.
         assert!(handler.take_parameter_edits().is_empty());
-        assert_eq!(
-            *handler.parameter_changes.lock().unwrap(),
-            vec![(5, 0.25), (5, 0.5)]
-        );
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![(5, 0.25), (5, 0.5)]);
+    }
+
+    #[test]
+    fn perform_edit_reaches_the_display_subscriber_immediately() {
+        let (tx, dsp) = mpsc::channel();
+        let handler = ComponentHandler::new(tx);
+        unsafe { handler.performEdit(1, 0.1) }; // before anyone listens
+        let display = handler.subscribe();
+        assert!(handler.is_subscribed());
+
+        let before = std::time::Instant::now();
+        unsafe { handler.performEdit(7, 0.75) };
+        let change = display
+            .try_recv()
+            .expect("delivered without waiting for a block");
+        assert_eq!((change.id, change.value), (7, 0.75));
+        assert!(change.timestamp >= before);
+        assert!(display.try_recv().is_err());
+        // The DSP feed still sees every edit.
+        assert_eq!(dsp.try_iter().count(), 2);
+
+        // A dropped receiver unsubscribes on the next edit.
+        drop(display);
+        unsafe { handler.performEdit(7, 0.8) };
+        assert!(!handler.is_subscribed());
+    }
+
+    #[test]
+    fn a_second_subscriber_does_not_disconnect_the_first() {
+        let handler = ComponentHandler::new(mpsc::channel().0);
+        let first = handler.subscribe();
+        let second = handler.subscribe();
+
+        unsafe { handler.performEdit(2, 0.5) };
+        assert_eq!(first.try_recv().map(|c| (c.id, c.value)), Ok((2, 0.5)));
+        assert_eq!(second.try_recv().map(|c| (c.id, c.value)), Ok((2, 0.5)));
+
+        // Dropping one leaves the other listening.
+        drop(first);
+        unsafe { handler.performEdit(2, 0.25) };
+        assert!(handler.is_subscribed());
+        assert_eq!(second.try_recv().map(|c| c.value), Ok(0.25));
     }
 }
 
@@ -1176,6 +1528,31 @@ mod parameter_changes_tests {
     }
 }
 
+#[cfg(test)]
+mod event_list_tests {
+    use super::*;
+
+    #[test]
+    fn sysex_is_queued_as_a_data_event_until_cleared() {
+        let list = HostEventList::new();
+        list.add_sysex(vec![0xF0, 0x7E, 0x7F, 0x09, 0x01, 0xF7], 32);
+
+        let mut event: Event = unsafe { std::mem::zeroed() };
+        assert_eq!(unsafe { list.getEventCount() }, 1);
+        assert_eq!(unsafe { list.getEvent(0, &mut event) }, kResultOk);
+        assert_eq!(event.r#type, Event_::EventTypes_::kDataEvent as u16);
+        assert_eq!(event.sampleOffset, 32);
+        let data = unsafe { event.__field0.data };
+        assert_eq!(data.r#type, DataEvent_::DataTypes_::kMidiSysEx);
+        let bytes = unsafe { std::slice::from_raw_parts(data.bytes, data.size as usize) };
+        assert_eq!(bytes, [0xF0, 0x7E, 0x7F, 0x09, 0x01, 0xF7]);
+
+        list.clear();
+        assert!(list.is_empty());
+        assert!(list.data.lock().unwrap().is_empty());
+    }
+}
+
 #[cfg(test)]
 mod memory_stream_tests {
     use super::*;
@@ -1214,4 +1591,64 @@ mod memory_stream_tests {
         // SEEK_CUR is relative.
         assert_eq!(s.seek_to(-3, SEEK_CUR), 0);
     }
+
+    /// A plugin keeping its parameters in a map, saving them as `getState`/`setState` would:
+    /// little-endian `(id: u32, value: f64)` records written to and read from the IBStream
+    /// wrapper.
+    #[derive(Default)]
+    struct StatefulParams(std::collections::BTreeMap<u32, f64>);
+
+    impl crate::plugin::PluginInternal for StatefulParams {
+        fn set_parameter(&mut self, id: u32, value: f64) -> crate::Result<()> {
+            self.0.insert(id, value);
+            Ok(())
+        }
+        fn get_parameter(&self, id: u32) -> crate::Result<f64> {
+            Ok(self.0.get(&id).copied().unwrap_or(0.0))
+        }
+        fn save_state(&self) -> crate::Result<Vec<u8>> {
+            let stream = create_memory_stream();
+            let ibstream = stream.to_com_ptr::<IBStream>().unwrap();
+            for (&id, &value) in &self.0 {
+                let mut record = [id.to_le_bytes().as_slice(), &value.to_le_bytes()].concat();
+                let mut written = 0;
+                let result =
+                    unsafe { ibstream.write(record.as_mut_ptr().cast(), 12, &mut written) };
+                assert_eq!((result, written), (kResultOk, 12));
+            }
+            Ok(stream.to_vec())
+        }
+        fn load_state(&mut self, data: &[u8]) -> crate::Result<()> {
+            let stream = create_memory_stream_from(data.to_vec());
+            let ibstream = stream.to_com_ptr::<IBStream>().unwrap();
+            let mut record = [0u8; 12];
+            let mut read = 0;
+            while unsafe { ibstream.read(record.as_mut_ptr().cast(), 12, &mut read) } == kResultOk
+                && read == 12
+            {
+                let (id, value) = record.split_at(4);
+                self.0.insert(
+                    u32::from_le_bytes(id.try_into().unwrap()),
+                    f64::from_le_bytes(value.try_into().unwrap()),
+                );
+            }
+            Ok(())
+        }
+        crate::plugin::stub_plugin_internal!(except set_parameter, get_parameter);
+    }
+
+    #[test]
+    fn parameter_value_survives_save_state_then_load_state() {
+        let mut plugin = crate::plugin::plugin_with(Box::new(StatefulParams::default()));
+        plugin.set_parameter(3, 0.625).unwrap();
+        plugin.set_parameter(9, 0.25).unwrap();
+        let saved = plugin.save_state().unwrap();
+        assert_eq!(saved.len(), 24);
+
+        plugin.set_parameter(3, 0.1).unwrap();
+        assert_eq!(plugin.get_parameter(3).unwrap(), 0.1);
+        plugin.load_state(&saved).unwrap();
+        assert_eq!(plugin.get_parameter(3).unwrap(), 0.625);
+        assert_eq!(plugin.get_parameter(9).unwrap(), 0.25);
+    }
 }
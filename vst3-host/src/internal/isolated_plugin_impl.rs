@@ -9,7 +9,7 @@ use crate::{
     midi::MidiEvent,
     parameters::Parameter,
     plugin::{PluginInfo, PluginInternal},
-    process_isolation::{HostCommand, HostResponse, PluginHostProcess},
+    process_isolation::{GuiBridge, HostCommand, HostResponse, PluginHostProcess},
 };
 use std::path::PathBuf;
 use std::sync::Mutex;
@@ -35,6 +35,8 @@ pub struct IsolatedPluginImpl {
     time_sig_numerator: i32,
     /// Time signature denominator advertised in the helper's host `ProcessContext`.
     time_sig_denominator: i32,
+    /// Sample precision requested of the helper's plugin (also used to reload after a crash).
+    sample_size: crate::audio::SampleSize,
     /// Whether the plugin is currently processing
     is_processing: bool,
     /// Whether the plugin has an open editor
@@ -75,6 +77,7 @@ impl IsolatedPluginImpl {
         tempo: f64,
         time_sig_numerator: i32,
         time_sig_denominator: i32,
+        sample_size: crate::audio::SampleSize,
         output_channels: usize,
         helper_path: Option<PathBuf>,
         response_timeout: Duration,
@@ -90,6 +93,7 @@ impl IsolatedPluginImpl {
             tempo,
             time_sig_numerator,
             time_sig_denominator,
+            sample_size,
             is_processing: false,
             has_open_editor: false,
             editor_size: None,
@@ -106,7 +110,8 @@ impl IsolatedPluginImpl {
     /// Send a command once, with NO recovery.
     ///
     /// Maps a dead/crashed/hung helper to a typed [`Error::PluginCrashed`] /
-    /// [`Error::PluginTimeout`] (the host process stays alive). This is the path used by
+    /// [`Error::PluginTimeout`] (the host process stays alive), or to
+    /// [`Error::HelperCrash`] when the helper left a crash report. This is the path used by
     /// `process()` on the audio thread, where a synchronous respawn+reload would stall it for
     /// hundreds of milliseconds — so it never recovers inline.
     fn send_command_once(&self, command: HostCommand) -> Result<HostResponse> {
@@ -117,7 +122,10 @@ impl IsolatedPluginImpl {
 
         process
             .send_command(command)
-            .map_err(|e| classify_ipc_error(&e))
+            .map_err(|e| match process.take_crash_report() {
+                Some(report) => Error::HelperCrash { report },
+                None => classify_ipc_error(&e),
+            })
     }
 
     /// Send a command, transparently respawning + reloading the helper and retrying on a
@@ -134,7 +142,10 @@ impl IsolatedPluginImpl {
             match self.send_command_once(command.clone()) {
                 Ok(resp) => return Ok(resp),
                 Err(e) => {
-                    let recoverable = matches!(e, Error::PluginCrashed | Error::PluginTimeout);
+                    let recoverable = matches!(
+                        e,
+                        Error::PluginCrashed | Error::PluginTimeout | Error::HelperCrash { .. }
+                    );
                     if !recoverable || attempt >= self.auto_recover_max_retries {
                         return Err(e);
                     }
@@ -215,6 +226,13 @@ impl PluginInternal for IsolatedPluginImpl {
         self.expect_success(HostCommand::SetPlaying { playing }, "SetPlaying")
     }
 
+    fn set_transport_position(&mut self, samples: i64) -> Result<()> {
+        self.expect_success(
+            HostCommand::SetTransportPosition { samples },
+            "SetTransportPosition",
+        )
+    }
+
     fn get_parameter(&self, id: u32) -> Result<f64> {
         match self.send_command(HostCommand::GetParameter { id })? {
             HostResponse::ParameterValue { value } => Ok(value),
@@ -251,6 +269,43 @@ impl PluginInternal for IsolatedPluginImpl {
         }
     }
 
+    fn parse_parameter(&self, id: u32, text: &str) -> Result<f64> {
+        let text = text.to_string();
+        match self.send_command(HostCommand::ParseParameter { id, text })? {
+            HostResponse::ParameterValue { value } => Ok(value),
+            HostResponse::Error { message } => {
+                Err(Error::Other(format!("ParseParameter: {message}")))
+            }
+            _ => Err(Error::Other(
+                "ParseParameter: unexpected response".to_string(),
+            )),
+        }
+    }
+
+    fn normalized_to_plain(&self, id: u32, normalized: f64) -> Result<f64> {
+        match self.send_command(HostCommand::NormalizedToPlain { id, normalized })? {
+            HostResponse::ParameterValue { value } => Ok(value),
+            HostResponse::Error { message } => {
+                Err(Error::Other(format!("NormalizedToPlain: {message}")))
+            }
+            _ => Err(Error::Other(
+                "NormalizedToPlain: unexpected response".to_string(),
+            )),
+        }
+    }
+
+    fn plain_to_normalized(&self, id: u32, plain: f64) -> Result<f64> {
+        match self.send_command(HostCommand::PlainToNormalized { id, plain })? {
+            HostResponse::ParameterValue { value } => Ok(value),
+            HostResponse::Error { message } => {
+                Err(Error::Other(format!("PlainToNormalized: {message}")))
+            }
+            _ => Err(Error::Other(
+                "PlainToNormalized: unexpected response".to_string(),
+            )),
+        }
+    }
+
     fn process(&mut self, buffers: &mut AudioBuffers) -> Result<()> {
         let frames = buffers
             .outputs
@@ -261,6 +316,7 @@ impl PluginInternal for IsolatedPluginImpl {
         // Audio-thread path: never auto-recover inline (a respawn would stall the callback).
         let response = self.send_command_once(HostCommand::Process {
             inputs: buffers.inputs.clone(),
+            sidechain: buffers.sidechain_inputs.clone(),
             frames: frames as u32,
         })?;
 
@@ -315,6 +371,15 @@ impl PluginInternal for IsolatedPluginImpl {
         )
     }
 
+    fn send_sysex(&mut self, data: &[u8]) -> Result<()> {
+        self.expect_success(
+            HostCommand::SendSysEx {
+                data: data.to_vec(),
+            },
+            "SendSysEx",
+        )
+    }
+
     fn set_bus_active(
         &mut self,
         media_type: crate::audio::MediaType,
@@ -451,6 +516,14 @@ impl PluginInternal for IsolatedPluginImpl {
         }
     }
 
+    fn get_programs(&self, program_list_id: i32) -> Result<Vec<crate::plugin::ProgramInfo>> {
+        match self.send_command(HostCommand::GetPrograms { program_list_id })? {
+            HostResponse::Programs { programs } => Ok(programs),
+            HostResponse::Error { message } => Err(Error::Other(format!("GetPrograms: {message}"))),
+            _ => Err(Error::Other("GetPrograms: unexpected response".to_string())),
+        }
+    }
+
     fn latency_samples(&self) -> u32 {
         match self.send_command(HostCommand::LatencySamples) {
             Ok(HostResponse::LatencySamples { samples }) => samples,
@@ -509,12 +582,26 @@ impl PluginInternal for IsolatedPluginImpl {
         self.info.has_gui
     }
 
-    fn open_editor(&mut self, _parent: *mut std::ffi::c_void) -> Result<()> {
-        let response = self.send_command(HostCommand::CreateGui)?;
+    fn open_editor(
+        &mut self,
+        parent: *mut std::ffi::c_void,
+        platform_type: &'static std::ffi::CStr,
+    ) -> Result<()> {
+        // Embed into the host's window where handles cross processes; otherwise the helper
+        // opens a window of its own.
+        let command = if !parent.is_null() && GuiBridge::current().can_embed(platform_type) {
+            HostCommand::OpenGui {
+                parent_handle: parent as usize,
+            }
+        } else {
+            HostCommand::CreateGui
+        };
+        let response = self.send_command(command)?;
 
         match response {
-            // The helper owns the window and reports its real size.
-            HostResponse::GuiCreated { width, height } => {
+            // The helper reports the editor's real size, whichever window it is in.
+            HostResponse::GuiCreated { width, height }
+            | HostResponse::GuiOpened { width, height } => {
                 self.editor_size = Some((width, height));
                 self.has_open_editor = true;
                 Ok(())
@@ -527,7 +614,7 @@ impl PluginInternal for IsolatedPluginImpl {
                 Err(Error::Other(format!("Failed to open editor: {}", message)))
             }
             _ => Err(Error::Other(
-                "Unexpected response from CreateGui command".to_string(),
+                "Unexpected response from the open-editor command".to_string(),
             )),
         }
     }
@@ -590,6 +677,10 @@ impl PluginInternal for IsolatedPluginImpl {
         )
     }
 
+    fn log_lines(&self) -> Option<std::sync::mpsc::Receiver<String>> {
+        self.process.lock().ok().map(|p| p.log_lines())
+    }
+
     fn take_output_events(&self) -> Vec<MidiEvent> {
         self.output_midi
             .lock()
@@ -613,6 +704,14 @@ impl PluginInternal for IsolatedPluginImpl {
     fn recover(&mut self) -> Result<()> {
         self.recover_locked()
     }
+
+    fn factory_reset(&mut self) -> Result<()> {
+        // The editor lives in the helper being replaced.
+        if self.has_open_editor {
+            let _ = self.close_editor();
+        }
+        self.reload_locked()
+    }
 }
 
 impl IsolatedPluginImpl {
@@ -620,14 +719,27 @@ impl IsolatedPluginImpl {
     /// internally and only reads immutable fields), so the auto-recover retry path in
     /// `send_command` — which has only `&self` — can call it too.
     fn recover_locked(&self) -> Result<()> {
+        self.reload_locked()?;
+        self.recovery_count
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Replace the helper with a fresh one running a newly loaded instance of the plugin
+    /// (original path + settings, default state). Shared by recovery and factory reset.
+    fn reload_locked(&self) -> Result<()> {
         let mut process = self
             .process
             .lock()
             .map_err(|e| Error::Other(format!("Failed to lock process: {}", e)))?;
 
         // Spawn a fresh helper and reload the plugin from the original path + settings.
-        let mut fresh = PluginHostProcess::new(self.helper_path.clone(), self.response_timeout)
-            .map_err(|e| Error::ProcessError(format!("Failed to respawn helper: {e}")))?;
+        let mut fresh = PluginHostProcess::new_for_plugin(
+            self.helper_path.clone(),
+            self.response_timeout,
+            &self.info.name,
+        )
+        .map_err(|e| Error::ProcessError(format!("Failed to respawn helper: {e}")))?;
         match fresh.send_command(HostCommand::LoadPlugin {
             path: self.info.path.display().to_string(),
             sample_rate: self.sample_rate,
@@ -635,6 +747,7 @@ impl IsolatedPluginImpl {
             tempo: self.tempo,
             time_sig_numerator: self.time_sig_numerator,
             time_sig_denominator: self.time_sig_denominator,
+            sample_size: self.sample_size,
         }) {
             Ok(HostResponse::PluginInfo { .. }) => {}
             Ok(HostResponse::Error { message }) => {
@@ -642,7 +755,12 @@ impl IsolatedPluginImpl {
             }
             Ok(_) => return Err(Error::Other("unexpected response while reloading".into())),
             // The reload itself crashed the fresh helper — the plugin is unrecoverable.
-            Err(e) => return Err(classify_ipc_error(&e)),
+            Err(e) => {
+                return Err(match fresh.take_crash_report() {
+                    Some(report) => Error::HelperCrash { report },
+                    None => classify_ipc_error(&e),
+                })
+            }
         }
 
         // Re-apply a non-default process mode before (re)starting processing — the fresh
@@ -659,8 +777,6 @@ impl IsolatedPluginImpl {
         }
 
         *process = fresh;
-        self.recovery_count
-            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
         Ok(())
     }
 }
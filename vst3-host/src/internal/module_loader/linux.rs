@@ -1,10 +1,10 @@
 //! Linux-specific VST3 module loading
 //!
 //! According to VST3 specification:
-//! - ModuleEntry/ModuleExit functions are REQUIRED on Linux
-//! - Must call ModuleEntry after dlopen and before GetPluginFactory
+//! - ModuleEntry/ModuleExit functions are required on Linux since SDK 3.6.x; plugins built
+//!   against older SDKs only export GetPluginFactory, so both are looked up optionally
+//! - Must call ModuleEntry(dlopen handle) after dlopen and before GetPluginFactory
 //! - Must call ModuleExit before dlclose or on program termination
-//! - Host must reject plugins not exporting these functions
 
 use super::{ModuleLoader, VstModule};
 use crate::error::{Error, Result};
@@ -14,8 +14,8 @@ use vst3::Steinberg::IPluginFactory;
 #[cfg(any(target_os = "linux", target_os = "android"))]
 use libloading::{Library, Symbol};
 
-/// Function signature for ModuleEntry
-type ModuleEntryFunc = unsafe extern "C" fn() -> bool;
+/// Function signature for ModuleEntry, which takes the `dlopen` handle of the module
+type ModuleEntryFunc = unsafe extern "C" fn(*mut std::ffi::c_void) -> bool;
 
 /// Function signature for ModuleExit
 type ModuleExitFunc = unsafe extern "C" fn() -> bool;
@@ -32,8 +32,8 @@ pub struct LinuxModule {
     library: Library,
     /// Path to the module
     path: std::path::PathBuf,
-    /// ModuleExit function pointer (for cleanup)
-    module_exit: Symbol<'static, ModuleExitFunc>,
+    /// ModuleExit function pointer (for cleanup), when the module exports one
+    module_exit: Option<Symbol<'static, ModuleExitFunc>>,
     /// GetPluginFactory function pointer
     get_factory_fn: Symbol<'static, GetPluginFactoryFunc>,
 }
@@ -73,52 +73,42 @@ impl LinuxModule {
             })?;
             log::debug!("Shared object loaded successfully");
 
-            // Step 2: Get ModuleEntry function (REQUIRED)
-            log::debug!("Step 2: Getting ModuleEntry function...");
-            let module_entry = library
-                .get::<ModuleEntryFunc>(b"ModuleEntry")
-                .map_err(|_| {
-                    Error::PluginLoadFailed(
-                        "Shared object does not export the required 'ModuleEntry' function"
-                            .to_string(),
-                    )
-                })?;
-            log::debug!("ModuleEntry function found");
-
-            // Step 3: Get ModuleExit function (REQUIRED)
-            log::debug!("Step 3: Getting ModuleExit function...");
-            let module_exit = library.get::<ModuleExitFunc>(b"ModuleExit").map_err(|_| {
-                Error::PluginLoadFailed(
-                    "Shared object does not export the required 'ModuleExit' function".to_string(),
-                )
-            })?;
-            log::debug!("ModuleExit function found");
+            // ModuleEntry wants the dlopen handle; take it out of libloading and back.
+            let handle = libloading::os::unix::Library::from(library).into_raw();
+            let library = Library::from(libloading::os::unix::Library::from_raw(handle));
 
-            // Step 4: Call ModuleEntry (MUST be called before GetPluginFactory)
-            log::debug!("Step 4: Calling ModuleEntry...");
-            let entry_result = module_entry();
-            if !entry_result {
-                return Err(Error::PluginLoadFailed(
-                    "ModuleEntry function returned false".to_string(),
-                ));
-            }
-            log::debug!("ModuleEntry called successfully");
-
-            // Step 5: Get GetPluginFactory function (REQUIRED)
-            log::debug!("Step 5: Getting GetPluginFactory function...");
+            // Step 2: Get GetPluginFactory function (REQUIRED), before running any plugin code
+            log::debug!("Step 2: Getting GetPluginFactory function...");
             let get_factory_fn = library
                 .get::<GetPluginFactoryFunc>(b"GetPluginFactory")
-                .map_err(|e| {
-                    // Cleanup on failure
-                    let _ = module_exit();
-                    Error::PluginLoadFailed(format!("Failed to find GetPluginFactory: {}", e))
-                })?;
-
+                .map_err(|_| Error::InvalidPlugin("No GetPluginFactory export".to_string()))?;
             log::debug!("GetPluginFactory function found");
 
+            // Step 3: Get ModuleExit function (optional for pre-3.6 plugins)
+            log::debug!("Step 3: Looking for ModuleExit function...");
+            let module_exit = library.get::<ModuleExitFunc>(b"ModuleExit").ok();
+            if module_exit.is_none() {
+                log::warn!("Shared object does not export 'ModuleExit' (pre-3.6 SDK?)");
+            }
+
+            // Step 4: Call ModuleEntry (MUST be called before GetPluginFactory)
+            log::debug!("Step 4: Looking for ModuleEntry function...");
+            match library.get::<ModuleEntryFunc>(b"ModuleEntry") {
+                Ok(module_entry) => {
+                    if !module_entry(handle) {
+                        return Err(Error::PluginLoadFailed(
+                            "ModuleEntry function returned false".to_string(),
+                        ));
+                    }
+                    log::debug!("ModuleEntry called successfully");
+                }
+                Err(_) => log::warn!("Shared object does not export 'ModuleEntry' (pre-3.6 SDK?)"),
+            }
+
             // SAFETY: We extend the lifetime to 'static because we're storing these in the struct
             // and will ensure they're dropped before the library is unloaded
-            let module_exit: Symbol<'static, ModuleExitFunc> = std::mem::transmute(module_exit);
+            let module_exit: Option<Symbol<'static, ModuleExitFunc>> =
+                module_exit.map(|exit| std::mem::transmute(exit));
             let get_factory_fn: Symbol<'static, GetPluginFactoryFunc> =
                 std::mem::transmute(get_factory_fn);
 
@@ -157,13 +147,14 @@ impl Drop for LinuxModule {
         unsafe {
             log::debug!("=== Linux VST3 MODULE CLEANUP START ===");
 
-            // Step 1: Call ModuleExit (REQUIRED)
-            log::debug!("Calling ModuleExit...");
-            let exit_result = (self.module_exit)();
-            if exit_result {
-                log::debug!("ModuleExit called successfully");
-            } else {
-                log::warn!("ModuleExit returned false");
+            // Step 1: Call ModuleExit, when exported
+            if let Some(module_exit) = &self.module_exit {
+                log::debug!("Calling ModuleExit...");
+                if module_exit() {
+                    log::debug!("ModuleExit called successfully");
+                } else {
+                    log::warn!("ModuleExit returned false");
+                }
             }
 
             // Step 2: Library will be automatically unloaded when dropped
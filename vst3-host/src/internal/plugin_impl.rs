@@ -1,14 +1,20 @@
 //! Internal VST3 plugin implementation
 
 use crate::{
-    audio::AudioBuffers,
+    audio::{AudioBuffers, SampleSize},
     error::{Error, Result},
+    host::HostIdentity,
     midi::{MidiChannel, MidiEvent},
     parameters::{Parameter, ParameterChange},
-    plugin::{PluginInfo, PluginInternal},
+    plugin::{
+        AttributeValue, LoadPhase, PluginInfo, PluginInitFlags, PluginInternal,
+        STATE_STREAM_ATTRIBUTE,
+    },
 };
 use crossbeam_queue::ArrayQueue;
+use std::collections::{HashMap, HashSet};
 use std::ptr;
+use std::sync::mpsc::{self, Receiver};
 use std::sync::{Arc, Mutex};
 use vst3::Steinberg::Vst::BusDirections_::*;
 use vst3::Steinberg::Vst::Event_::EventTypes_::*;
@@ -18,7 +24,7 @@ use vst3::{ComPtr, ComWrapper, Interface, Steinberg::Vst::*, Steinberg::*};
 
 use super::{
     com_implementations::{
-        create_event_list, create_host_application, create_host_plug_frame, create_memory_stream,
+        create_attribute_stream, create_event_list, create_host_plug_frame, create_memory_stream,
         create_memory_stream_from, ComponentHandler, HostApplication, HostEventList, HostPlugFrame,
         ParameterChanges,
     },
@@ -39,6 +45,10 @@ pub struct PluginImpl {
     /// plugin). Then `IComponent::setState` already restores the controller, and calling
     /// `setComponentState` on top of it would double-apply and corrupt parameters.
     single_component: bool,
+    /// Initialization steps skipped for this plugin (kept for `factory_reset` reloads).
+    init_flags: PluginInitFlags,
+    /// Who the host said it was at `initialize` (kept for `factory_reset` reloads).
+    host_identity: HostIdentity,
 
     // Plugin metadata
     pub(crate) info: PluginInfo,
@@ -58,12 +68,24 @@ pub struct PluginImpl {
     playing: bool,
     /// Real-time vs offline processing, baked into `ProcessSetup`/`process_data` at setup.
     process_mode: crate::plugin::ProcessMode,
+    /// Sample precision baked into `ProcessSetup`/`process_data` at setup; drops to `F32` when
+    /// the plugin turns 64-bit processing down.
+    sample_size: SampleSize,
     /// Monotonic allocator for per-voice note ids (note_on); 0/-1 reserved for "unset".
     next_note_id: i32,
+    /// Audio buses deactivated through `set_bus_active`, as `(direction, index)`. They get no
+    /// channel buffers and aren't reactivated when processing starts.
+    inactive_audio_buses: HashSet<(i32, i32)>,
+    /// Audio bus counts and channel counts, queried at load so buffer layout needn't ask the
+    /// component again.
+    bus_layout: CachedBusLayout,
 
     // Host data structures
     process_data: Option<Box<HostProcessData>>,
     component_handler: Option<ComWrapper<ComponentHandler>>,
+    // The component handler's performEdit feed: what the plugin's own editor changed, drained
+    // by `process()` into the processor's input queue.
+    editor_changes: Receiver<(u32, f64)>,
 
     // Parameter changes queued by the host (set_parameter / automation) to be fed into the
     // processor's input parameter queue at the start of the next process() block. Serialized
@@ -72,9 +94,10 @@ pub struct PluginImpl {
 
     // Parameter edits the plugin's *own editor* reported via `IComponentHandler::performEdit`,
     // after `process()` has routed them into the processor's input queue. Drained by the host
-    // via `get_parameter_changes()` to update its UI. Separate from the raw performEdit sink
-    // (`component_handler.parameter_changes`) so feeding the DSP and updating the display are
-    // not two consumers racing to drain the same buffer.
+    // via `get_parameter_changes()` to update its UI. Separate from the raw performEdit feed
+    // (`editor_changes`) so feeding the DSP and updating the display are not two consumers
+    // racing to drain the same channel. Left empty while a display subscriber
+    // (`subscribe_parameter_changes`) gets the edits directly.
     gui_param_changes_for_host: Arc<Mutex<Vec<(u32, f64)>>>,
 
     // Event handling
@@ -109,6 +132,9 @@ struct HostProcessData {
     output_buffers: Vec<Vec<f32>>,
     input_bus_buffers: Vec<AudioBusBuffers>,
     output_bus_buffers: Vec<AudioBusBuffers>,
+    // Whether each of `input_buffers` belongs to an aux (sidechain) bus, so process() can
+    // fill it from `AudioBuffers::sidechain_inputs` rather than `inputs`.
+    input_channel_aux: Vec<bool>,
     process_context: ProcessContext,
     input_param_changes: ComWrapper<ParameterChanges>,
     output_param_changes: ComWrapper<ParameterChanges>,
@@ -117,6 +143,103 @@ struct HostProcessData {
     // them every block — keeping the steady-state audio path allocation-free.
     input_channel_ptrs: SendChannelPtrs,
     output_channel_ptrs: SendChannelPtrs,
+    // Double-precision twins of the buffers above, handed to the plugin instead when it
+    // processes 64-bit samples (empty otherwise). The f32 buffers then only stage the copy.
+    input_buffers64: Vec<Vec<f64>>,
+    output_buffers64: Vec<Vec<f64>>,
+    input_channel_ptrs64: SendChannelPtrs<f64>,
+    output_channel_ptrs64: SendChannelPtrs<f64>,
+    // The bus layout the buffers above were laid out from.
+    bus_layout: CachedBusLayout,
+}
+
+impl HostProcessData {
+    /// Process data with no buffers yet, to be laid out from `bus_layout` by `prepare_buffers`.
+    fn new(bus_layout: CachedBusLayout) -> Box<Self> {
+        Box::new(HostProcessData {
+            // SAFETY: ProcessData and ProcessContext are plain C structs for which all-zero
+            // (null pointers, zero counts) is valid.
+            process_data: unsafe { std::mem::zeroed() },
+            input_buffers: Vec::new(),
+            output_buffers: Vec::new(),
+            input_bus_buffers: Vec::new(),
+            output_bus_buffers: Vec::new(),
+            input_channel_aux: Vec::new(),
+            process_context: unsafe { std::mem::zeroed() },
+            input_param_changes: ComWrapper::new(ParameterChanges::default()),
+            output_param_changes: ComWrapper::new(ParameterChanges::default()),
+            input_channel_ptrs: SendChannelPtrs(Vec::new()),
+            output_channel_ptrs: SendChannelPtrs(Vec::new()),
+            input_buffers64: Vec::new(),
+            output_buffers64: Vec::new(),
+            input_channel_ptrs64: SendChannelPtrs(Vec::new()),
+            output_channel_ptrs64: SendChannelPtrs(Vec::new()),
+            bus_layout,
+        })
+    }
+}
+
+/// A plugin's audio buses as the component last described them: how many there are in each
+/// direction, how many channels each has, and which inputs are auxiliary (sidechain) buses.
+///
+/// Queried once at load, and again on [`PluginInternal::refresh_bus_layout`], after a bus
+/// arrangement change, or when the plugin has reported `kIoChanged` through
+/// `restartComponent`. Laying out buffers from the cache keeps `getBusCount`/`getBusInfo`
+/// calls off the processing path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct CachedBusLayout {
+    pub audio_input_count: i32,
+    pub audio_output_count: i32,
+    /// Channel count of each audio bus: the inputs in bus order, then the outputs. Negative
+    /// for a bus the plugin failed to describe, which is left out of the process data.
+    pub channel_counts: Vec<i32>,
+    /// Whether each input bus, in bus order, is an auxiliary (`kAux`) bus such as a sidechain.
+    pub aux_inputs: Vec<bool>,
+}
+
+impl CachedBusLayout {
+    /// Ask `component` for its audio bus counts and each bus's channel count.
+    unsafe fn query(component: &ComPtr<IComponent>) -> Self {
+        let mut channel_counts = Vec::new();
+        let mut aux_inputs = Vec::new();
+        let mut read = |dir: i32| {
+            let count = component.getBusCount(kAudio as i32, dir).max(0);
+            for idx in 0..count {
+                let mut bus_info: BusInfo = std::mem::zeroed();
+                let described =
+                    component.getBusInfo(kAudio as i32, dir, idx, &mut bus_info) == kResultOk;
+                channel_counts.push(if described {
+                    bus_info.channelCount.max(0)
+                } else {
+                    -1
+                });
+                if dir == kInput as i32 {
+                    aux_inputs.push(described && bus_info.busType == BusTypes_::kAux as i32);
+                }
+            }
+            count
+        };
+        let audio_input_count = read(kInput as i32);
+        let audio_output_count = read(kOutput as i32);
+        CachedBusLayout {
+            audio_input_count,
+            audio_output_count,
+            channel_counts,
+            aux_inputs,
+        }
+    }
+
+    /// Channel counts of the input buses, in bus order.
+    fn inputs(&self) -> &[i32] {
+        let split = (self.audio_input_count.max(0) as usize).min(self.channel_counts.len());
+        &self.channel_counts[..split]
+    }
+
+    /// Channel counts of the output buses, in bus order.
+    fn outputs(&self) -> &[i32] {
+        let split = (self.audio_input_count.max(0) as usize).min(self.channel_counts.len());
+        &self.channel_counts[split..]
+    }
 }
 
 /// Per-bus channel pointers into the (audio-thread-owned) audio buffers.
@@ -124,8 +247,8 @@ struct HostProcessData {
 /// `Send` because the pointers are only ever dereferenced on the one thread that owns the
 /// `HostProcessData` (and thus the buffers they point into); the `Plugin` is moved to that
 /// thread as a unit. The raw pointers never escape to another thread.
-struct SendChannelPtrs(Vec<Vec<*mut f32>>);
-unsafe impl Send for SendChannelPtrs {}
+struct SendChannelPtrs<T = f32>(Vec<Vec<*mut T>>);
+unsafe impl<T> Send for SendChannelPtrs<T> {}
 
 impl PluginImpl {
     /// Configure the transport advertised to the plugin in the host `ProcessContext`.
@@ -182,6 +305,11 @@ impl PluginImpl {
         self.block_size = block_size;
     }
 
+    /// Request the sample precision `setupProcessing` uses (applied at the next setup).
+    pub fn set_sample_size(&mut self, sample_size: SampleSize) {
+        self.sample_size = sample_size;
+    }
+
     /// Get parameter changes the plugin's editor made (for the host to update its UI).
     ///
     /// Returns edits that `process()` has already routed into the processor's input queue, so
@@ -194,23 +322,39 @@ impl PluginImpl {
             }
         }
         // Not processing yet (process() hasn't run to move edits into the stash): drain the raw
-        // performEdit sink directly so the host UI still reflects editor changes.
-        if !self.is_processing {
-            if let Some(ref handler) = self.component_handler {
-                if let Ok(mut changes) = handler.parameter_changes.lock() {
-                    return std::mem::take(&mut *changes);
-                }
-            }
+        // performEdit feed directly so the host UI still reflects editor changes.
+        if !self.is_processing && !self.display_subscribed() {
+            return self.editor_changes.try_iter().collect();
         }
         Vec::new()
     }
 
-    /// Load a VST3 plugin from the given path
-    pub fn load(path: &std::path::Path) -> Result<Self> {
+    /// Whether the host's display gets editor edits from `subscribe_parameter_changes`
+    /// (rather than `get_parameter_changes`).
+    fn display_subscribed(&self) -> bool {
+        self.component_handler
+            .as_ref()
+            .is_some_and(|h| h.is_subscribed())
+    }
+
+    /// Load a VST3 plugin from the given path, skipping the initialization steps in `flags`
+    /// and calling `on_phase` as each step starts.
+    pub fn load(
+        path: &std::path::Path,
+        flags: PluginInitFlags,
+        host: &HostIdentity,
+        on_phase: &mut dyn FnMut(LoadPhase),
+    ) -> Result<Self> {
         unsafe {
             log::info!("=== PLUGIN LOADING START ===");
+            on_phase(LoadPhase::Component);
             log::info!("Loading plugin from: {}", path.display());
 
+            // Refuse binaries that don't export a factory before calling into them (opening the
+            // library already runs its static initializers). The check's handle stays open
+            // across the load so the module isn't mapped twice.
+            let _checked_library = crate::discovery::open_checked_library(path)?;
+
             // Load the VST3 module using platform-specific loader
             log::debug!("Step 1: Loading VST3 module...");
             let module = load_module(path)?;
@@ -239,8 +383,12 @@ impl PluginImpl {
 
             // Initialize component with a host-application context. Passing null here
             // crashes plugins that query the host (u-he, Waves, ...); see HostApplication.
-            log::debug!("Step 5: Initializing component...");
-            let host_app = create_host_application();
+            log::debug!(
+                "Step 5: Initializing component as {} {}...",
+                host.name,
+                host.version_text()
+            );
+            let host_app = ComWrapper::new(HostApplication::new(host.clone()));
             let host_ctx = host_app.to_com_ptr::<IHostApplication>();
             let context = host_ctx
                 .as_ref()
@@ -250,9 +398,14 @@ impl PluginImpl {
             log::debug!("Component initialized with result: {:#x}", init_result);
 
             // CRITICAL: Activate event buses for MIDI processing
-            log::debug!("Step 6: Activating event buses...");
-            Self::activate_event_buses(&component)?;
-            log::debug!("Event buses activated");
+            if flags.contains(PluginInitFlags::SKIP_BUS_NEGOTIATION) {
+                log::debug!("Step 6: Skipping event bus activation (SKIP_BUS_NEGOTIATION)");
+            } else {
+                log::debug!("Step 6: Activating event buses...");
+                on_phase(LoadPhase::BusNegotiation);
+                Self::activate_event_buses(&component)?;
+                log::debug!("Event buses activated");
+            }
 
             // Get processor interface
             log::debug!("Step 7: Getting IAudioProcessor interface...");
@@ -263,9 +416,8 @@ impl PluginImpl {
 
             // Create component handler for parameter change notifications
             log::debug!("Step 8: Creating component handler...");
-            let parameter_changes = Arc::new(Mutex::new(Vec::new()));
-            let component_handler =
-                ComWrapper::new(ComponentHandler::new(parameter_changes.clone()));
+            let (parameter_changes, editor_changes) = mpsc::channel();
+            let component_handler = ComWrapper::new(ComponentHandler::new(parameter_changes));
             log::debug!("Component handler created");
 
             // Get or create controller (handles both single-component and separate controller)
@@ -273,11 +425,27 @@ impl PluginImpl {
             // A component that directly implements IEditController is a single-component
             // plugin; this distinction matters for state restore (see `single_component`).
             let single_component = component.cast::<IEditController>().is_some();
-            let controller = Self::get_or_create_controller(&component, &factory, context)?;
+            let controller = if flags.contains(PluginInitFlags::SKIP_CONTROLLER_INIT) {
+                log::debug!("Skipping controller creation (SKIP_CONTROLLER_INIT)");
+                None
+            } else {
+                on_phase(LoadPhase::ControllerInit);
+                Self::get_or_create_controller(&component, &factory, context)?
+            };
             log::debug!(
                 "Controller obtained: {} (single_component: {single_component})",
                 controller.is_some()
             );
+            if controller.is_none() {
+                if flags.contains(PluginInitFlags::REQUIRE_CONTROLLER) {
+                    return Err(Error::NoController);
+                }
+                if !flags.contains(PluginInitFlags::SKIP_CONTROLLER_INIT) {
+                    log::warn!(
+                        "Plugin has no edit controller; loading it without parameters or editor"
+                    );
+                }
+            }
 
             // Connect component and controller if they are separate
             if let Some(ref ctrl) = controller {
@@ -334,7 +502,11 @@ impl PluginImpl {
             // Extract plugin info from the factory and component
             let info = Self::extract_plugin_info(path, &factory, &component, &controller)?;
 
-            let has_gui = controller.is_some() && {
+            let probe_gui = !flags.contains(PluginInitFlags::SKIP_GUI_CHECK);
+            if probe_gui && controller.is_some() {
+                on_phase(LoadPhase::GuiCheck);
+            }
+            let has_gui = probe_gui && {
                 if let Some(ref ctrl) = controller {
                     let view_type = c"editor".as_ptr();
                     let view_ptr = ctrl.createView(view_type);
@@ -363,11 +535,15 @@ impl PluginImpl {
             );
             log::info!("Has GUI: {}, Active: {}", updated_info.has_gui, is_active);
 
+            let bus_layout = CachedBusLayout::query(&component);
+
             Ok(Self {
                 component,
                 processor,
                 controller,
                 single_component,
+                init_flags: flags,
+                host_identity: host.clone(),
                 info: updated_info,
                 is_active,
                 is_processing: false,
@@ -378,9 +554,13 @@ impl PluginImpl {
                 time_sig_denominator: 4,
                 playing: true,
                 process_mode: crate::plugin::ProcessMode::Realtime,
+                sample_size: SampleSize::F32,
                 next_note_id: 1,
+                inactive_audio_buses: HashSet::new(),
+                bus_layout,
                 process_data: None,
                 component_handler: Some(component_handler),
+                editor_changes,
                 pending_param_changes: Vec::new(),
                 gui_param_changes_for_host: Arc::new(Mutex::new(Vec::new())),
                 input_events,
@@ -482,13 +662,15 @@ impl PluginImpl {
                             name,
                             vendor,
                             version,
-                            category,
+                            category_path: crate::discovery::parse_category(&category),
                             uid,
                             audio_inputs,
                             audio_outputs,
                             has_gui: false, // Will be updated by caller
                             has_midi_input,
                             has_midi_output,
+                            format: crate::plugin::PluginFormat::Vst3,
+                            duplicates: Vec::new(),
                         });
                     }
                 }
@@ -540,13 +722,36 @@ impl PluginImpl {
         }
     }
 
+    /// Map the sample size to the VST3 enum value, first dropping a 64-bit request to 32 bits
+    /// (with a warning) if the plugin can't process 64-bit samples.
+    fn vst_sample_size(&mut self) -> i32 {
+        if self.sample_size == SampleSize::F64 {
+            // SAFETY: `processor` is a live IAudioProcessor owned by this plugin.
+            let supported = unsafe {
+                self.processor
+                    .canProcessSampleSize(SymbolicSampleSizes_::kSample64 as i32)
+            };
+            if supported != kResultOk {
+                log::warn!(
+                    "{} can't process 64-bit samples; falling back to 32-bit",
+                    self.info.name
+                );
+                self.sample_size = SampleSize::F32;
+            }
+        }
+        match self.sample_size {
+            SampleSize::F32 => SymbolicSampleSizes_::kSample32 as i32,
+            SampleSize::F64 => SymbolicSampleSizes_::kSample64 as i32,
+        }
+    }
+
     /// Set up processing with current configuration
     fn setup_processing(&mut self) -> Result<()> {
         unsafe {
             // Set up processing
             let setup = ProcessSetup {
                 processMode: self.vst_process_mode(),
-                symbolicSampleSize: SymbolicSampleSizes_::kSample32 as i32,
+                symbolicSampleSize: self.vst_sample_size(),
                 maxSamplesPerBlock: self.block_size as i32,
                 sampleRate: self.sample_rate,
             };
@@ -559,6 +764,16 @@ impl PluginImpl {
                 )));
             }
 
+            // Pick up the new layout if the plugin reported an I/O change (`restartComponent`
+            // with kIoChanged) since it was cached.
+            if self
+                .component_handler
+                .as_ref()
+                .is_some_and(|handler| handler.take_io_changed())
+            {
+                self.bus_layout = CachedBusLayout::query(&self.component);
+            }
+
             // Create process data
             self.create_process_data()?;
 
@@ -572,18 +787,7 @@ impl PluginImpl {
     #[allow(clippy::unnecessary_cast)]
     fn create_process_data(&mut self) -> Result<()> {
         unsafe {
-            let mut data = Box::new(HostProcessData {
-                process_data: std::mem::zeroed(),
-                input_buffers: Vec::new(),
-                output_buffers: Vec::new(),
-                input_bus_buffers: Vec::new(),
-                output_bus_buffers: Vec::new(),
-                process_context: std::mem::zeroed(),
-                input_param_changes: ComWrapper::new(ParameterChanges::default()),
-                output_param_changes: ComWrapper::new(ParameterChanges::default()),
-                input_channel_ptrs: SendChannelPtrs(Vec::new()),
-                output_channel_ptrs: SendChannelPtrs(Vec::new()),
-            });
+            let mut data = HostProcessData::new(self.bus_layout.clone());
 
             // Initialize process context
             data.process_context.sampleRate = self.sample_rate;
@@ -595,7 +799,10 @@ impl PluginImpl {
             // Set up process data
             data.process_data.processMode = self.vst_process_mode();
             data.process_data.numSamples = self.block_size as i32;
-            data.process_data.symbolicSampleSize = SymbolicSampleSizes_::kSample32 as i32;
+            data.process_data.symbolicSampleSize = match self.sample_size {
+                SampleSize::F32 => SymbolicSampleSizes_::kSample32 as i32,
+                SampleSize::F64 => SymbolicSampleSizes_::kSample64 as i32,
+            };
             data.process_data.processContext = &mut data.process_context;
 
             // Set up event lists
@@ -622,138 +829,38 @@ impl PluginImpl {
                 .map(|ptr| ptr.as_ptr())
                 .unwrap_or(ptr::null_mut());
 
-            // Prepare buffers
-            self.prepare_buffers(&mut data)?;
+            // Activate the buses, then lay out their buffers from the cached layout
+            self.activate_audio_buses();
+            prepare_buffers(
+                &mut data,
+                &self.inactive_audio_buses,
+                self.block_size,
+                self.sample_size,
+            );
 
             self.process_data = Some(data);
             Ok(())
         }
     }
 
-    /// Prepare audio buffers based on plugin bus configuration
-    unsafe fn prepare_buffers(&mut self, data: &mut HostProcessData) -> Result<()> {
-        // Get bus counts
-        let input_bus_count = self.component.getBusCount(kAudio as i32, kInput as i32);
-        let output_bus_count = self.component.getBusCount(kAudio as i32, kOutput as i32);
-
-        // Clear existing buffers
-        data.input_buffers.clear();
-        data.output_buffers.clear();
-        data.input_bus_buffers.clear();
-        data.output_bus_buffers.clear();
-
-        // Prepare input buffers
-        for bus_idx in 0..input_bus_count {
-            let mut bus_info: BusInfo = std::mem::zeroed();
-            if self
-                .component
-                .getBusInfo(kAudio as i32, kInput as i32, bus_idx, &mut bus_info)
-                == kResultOk
-            {
-                let channel_count = bus_info.channelCount;
-
-                // Activate the bus
-                self.component
-                    .activateBus(kAudio as i32, kInput as i32, bus_idx, 1);
-
-                // Create buffers for this bus
-                for _ in 0..channel_count {
-                    let buffer = vec![0.0f32; self.block_size];
-                    data.input_buffers.push(buffer);
-                }
-
-                // Create AudioBusBuffers struct
-                let mut audio_bus_buffer: AudioBusBuffers = std::mem::zeroed();
-                audio_bus_buffer.numChannels = channel_count;
-                data.input_bus_buffers.push(audio_bus_buffer);
-            }
+    /// Activate every audio bus the host hasn't deactivated, unless bus negotiation is skipped.
+    unsafe fn activate_audio_buses(&self) {
+        if self
+            .init_flags
+            .contains(PluginInitFlags::SKIP_BUS_NEGOTIATION)
+        {
+            return;
         }
-
-        // Prepare output buffers
-        for bus_idx in 0..output_bus_count {
-            let mut bus_info: BusInfo = std::mem::zeroed();
-            if self
-                .component
-                .getBusInfo(kAudio as i32, kOutput as i32, bus_idx, &mut bus_info)
-                == kResultOk
-            {
-                let channel_count = bus_info.channelCount;
-
-                // Activate the bus
-                self.component
-                    .activateBus(kAudio as i32, kOutput as i32, bus_idx, 1);
-
-                // Create buffers for this bus
-                for _ in 0..channel_count {
-                    let buffer = vec![0.0f32; self.block_size];
-                    data.output_buffers.push(buffer);
-                }
-
-                // Create AudioBusBuffers struct
-                let mut audio_bus_buffer: AudioBusBuffers = std::mem::zeroed();
-                audio_bus_buffer.numChannels = channel_count;
-                data.output_bus_buffers.push(audio_bus_buffer);
-            }
-        }
-
-        // Set up process data counts
-        data.process_data.numInputs = data.input_bus_buffers.len() as i32;
-        data.process_data.numOutputs = data.output_bus_buffers.len() as i32;
-
-        // Build the channel-pointer arrays ONCE and point each bus + the process data at the
-        // (now stable) buffers. process() reuses these without allocating per block.
-        let build_ptrs = |buffers: &mut [Vec<f32>], buses: &[AudioBusBuffers]| {
-            let mut per_bus: Vec<Vec<*mut f32>> = Vec::with_capacity(buses.len());
-            let mut chan = 0usize;
-            for bus in buses {
-                let mut ptrs = Vec::with_capacity(bus.numChannels as usize);
-                for _ in 0..bus.numChannels {
-                    if chan < buffers.len() {
-                        ptrs.push(buffers[chan].as_mut_ptr());
-                        chan += 1;
-                    }
+        for (dir, count) in [
+            (kInput as i32, self.bus_layout.audio_input_count),
+            (kOutput as i32, self.bus_layout.audio_output_count),
+        ] {
+            for bus_idx in 0..count {
+                if !self.inactive_audio_buses.contains(&(dir, bus_idx)) {
+                    self.component.activateBus(kAudio as i32, dir, bus_idx, 1);
                 }
-                per_bus.push(ptrs);
-            }
-            per_bus
-        };
-        data.input_channel_ptrs =
-            SendChannelPtrs(build_ptrs(&mut data.input_buffers, &data.input_bus_buffers));
-        data.output_channel_ptrs = SendChannelPtrs(build_ptrs(
-            &mut data.output_buffers,
-            &data.output_bus_buffers,
-        ));
-
-        for (i, bus) in data.input_bus_buffers.iter_mut().enumerate() {
-            if !data.input_channel_ptrs.0[i].is_empty() {
-                bus.__field0.channelBuffers32 = data.input_channel_ptrs.0[i].as_mut_ptr();
             }
         }
-        for (i, bus) in data.output_bus_buffers.iter_mut().enumerate() {
-            if !data.output_channel_ptrs.0[i].is_empty() {
-                bus.__field0.channelBuffers32 = data.output_channel_ptrs.0[i].as_mut_ptr();
-            }
-        }
-        data.process_data.inputs = if data.input_bus_buffers.is_empty() {
-            ptr::null_mut()
-        } else {
-            data.input_bus_buffers.as_mut_ptr()
-        };
-        data.process_data.outputs = if data.output_bus_buffers.is_empty() {
-            ptr::null_mut()
-        } else {
-            data.output_bus_buffers.as_mut_ptr()
-        };
-
-        log::debug!(
-            "Prepared buffers: {} input buses, {} output buses, {} input channels, {} output channels",
-            input_bus_count,
-            output_bus_count,
-            data.input_buffers.len(),
-            data.output_buffers.len()
-        );
-
-        Ok(())
     }
 }
 
@@ -774,10 +881,11 @@ impl PluginInternal for PluginImpl {
                 id,
                 value,
                 sample_offset,
+                timestamp: std::time::Instant::now(),
             });
             Ok(())
         } else {
-            Err(Error::InterfaceError("No controller available".to_string()))
+            Err(Error::NoController)
         }
     }
 
@@ -796,11 +904,20 @@ impl PluginInternal for PluginImpl {
         Ok(())
     }
 
+    fn set_transport_position(&mut self, samples: i64) -> Result<()> {
+        if let Some(ref mut data) = self.process_data {
+            let ctx = &mut data.process_context;
+            advance_process_context(ctx, samples.wrapping_sub(ctx.projectTimeSamples));
+            ctx.continousTimeSamples = samples;
+        }
+        Ok(())
+    }
+
     fn get_parameter(&self, id: u32) -> Result<f64> {
         if let Some(ref controller) = self.controller {
             unsafe { Ok(controller.getParamNormalized(id)) }
         } else {
-            Err(Error::InterfaceError("No controller available".to_string()))
+            Err(Error::NoController)
         }
     }
 
@@ -832,6 +949,7 @@ impl PluginInternal for PluginImpl {
                             is_bypass: (info.flags & ParameterInfo_::ParameterFlags_::kIsBypass)
                                 != 0,
                             flags: info.flags as u32,
+                            unit_id: info.unitId,
                         };
                         params.push(param);
                     }
@@ -854,10 +972,40 @@ impl PluginInternal for PluginImpl {
                 "Plugin could not format parameter {id}"
             )))
         } else {
-            Err(Error::InterfaceError("No controller available".to_string()))
+            Err(Error::NoController)
         }
     }
 
+    fn parse_parameter(&self, id: u32, text: &str) -> Result<f64> {
+        let controller = self.controller.as_ref().ok_or(Error::NoController)?;
+        let mut wide: Vec<TChar> = text.encode_utf16().collect();
+        wide.push(0);
+        let mut normalized: ParamValue = 0.0;
+        // SAFETY: `controller` is a live IEditController; `wide` is NUL-terminated and outlives
+        // the call, and `normalized` is a valid out-pointer.
+        let result =
+            unsafe { controller.getParamValueByString(id, wide.as_mut_ptr(), &mut normalized) };
+        if result == kResultOk {
+            Ok(normalized)
+        } else {
+            Err(Error::InvalidParameter(format!(
+                "Plugin could not parse '{text}' for parameter {id}"
+            )))
+        }
+    }
+
+    fn normalized_to_plain(&self, id: u32, normalized: f64) -> Result<f64> {
+        let controller = self.controller.as_ref().ok_or(Error::NoController)?;
+        // SAFETY: `controller` is a live IEditController owned by this plugin.
+        Ok(unsafe { controller.normalizedParamToPlain(id, normalized) })
+    }
+
+    fn plain_to_normalized(&self, id: u32, plain: f64) -> Result<f64> {
+        let controller = self.controller.as_ref().ok_or(Error::NoController)?;
+        // SAFETY: `controller` is a live IEditController owned by this plugin.
+        Ok(unsafe { controller.plainParamToNormalized(id, plain) })
+    }
+
     fn process(&mut self, buffers: &mut AudioBuffers) -> Result<()> {
         if !self.is_active || !self.is_processing {
             return Err(Error::Other("Plugin is not processing".to_string()));
@@ -910,28 +1058,35 @@ impl PluginInternal for PluginImpl {
                 // the component/controller connection; others rely on the host to do this. We do
                 // it unconditionally; a plugin that also self-relays just gets the same value
                 // twice in the same block, which is idempotent.) Drained here at offset 0 and
-                // stashed for the host's display poll (get_parameter_changes).
-                if let Some(ref handler) = self.component_handler {
-                    if let Ok(mut gui_changes) = handler.parameter_changes.lock() {
-                        if !gui_changes.is_empty() {
-                            for &(id, value) in gui_changes.iter() {
-                                data.input_param_changes.enqueue(id, 0, value);
-                            }
-                            if let Ok(mut stash) = self.gui_param_changes_for_host.lock() {
-                                stash.append(&mut gui_changes);
-                            } else {
-                                gui_changes.clear();
-                            }
+                // stashed for the host's display poll (get_parameter_changes) unless a display
+                // subscriber already received them.
+                let stash_for_host = !self
+                    .component_handler
+                    .as_ref()
+                    .is_some_and(|h| h.is_subscribed());
+                for (id, value) in self.editor_changes.try_iter() {
+                    data.input_param_changes.enqueue(id, 0, value);
+                    if stash_for_host {
+                        if let Ok(mut stash) = self.gui_param_changes_for_host.lock() {
+                            stash.push((id, value));
                         }
                     }
                 }
 
                 // Copy input audio to plugin buffers (length-clamped — never assume the
-                // caller's block equals the configured block size).
-                for (ch_idx, channel) in buffers.inputs.iter().enumerate() {
-                    if ch_idx < data.input_buffers.len() {
-                        let n = channel.len().min(data.input_buffers[ch_idx].len());
-                        data.input_buffers[ch_idx][..n].copy_from_slice(&channel[..n]);
+                // caller's block equals the configured block size): main-bus channels from
+                // `inputs`, aux-bus channels from `sidechain_inputs`, silence where the caller
+                // has fewer channels than the plugin.
+                let mut main = buffers.inputs.iter();
+                let mut sidechain = buffers.sidechain_inputs.iter();
+                for (dst, &aux) in data.input_buffers.iter_mut().zip(&data.input_channel_aux) {
+                    let src = if aux { sidechain.next() } else { main.next() };
+                    match src {
+                        Some(src) => {
+                            let n = src.len().min(dst.len());
+                            dst[..n].copy_from_slice(&src[..n]);
+                        }
+                        None => dst.fill(0.0),
                     }
                 }
 
@@ -939,6 +1094,15 @@ impl PluginInternal for PluginImpl {
                 for buffer in &mut data.output_buffers {
                     buffer.fill(0.0);
                 }
+                // A 64-bit plugin reads and writes the double-precision twins instead.
+                for (dst, src) in data.input_buffers64.iter_mut().zip(&data.input_buffers) {
+                    for (d, &s) in dst.iter_mut().zip(src) {
+                        *d = f64::from(s);
+                    }
+                }
+                for buffer in &mut data.output_buffers64 {
+                    buffer.fill(0.0);
+                }
 
                 // Channel pointers and process-data input/output pointers were wired once in
                 // prepare_buffers (buffer addresses are stable), so there's nothing to rebuild
@@ -975,6 +1139,12 @@ impl PluginInternal for PluginImpl {
                     });
                 }
 
+                for (dst, src) in data.output_buffers.iter_mut().zip(&data.output_buffers64) {
+                    for (d, &s) in dst.iter_mut().zip(src) {
+                        *d = s as f32;
+                    }
+                }
+
                 // Copy output to provided buffers (length-clamped to the actual frames).
                 for (ch_idx, channel) in buffers.outputs.iter_mut().enumerate() {
                     if ch_idx < data.output_buffers.len() {
@@ -1124,6 +1294,8 @@ impl PluginInternal for PluginImpl {
                 out_raw.len() as i32,
             );
 
+            // The arrangement decides each bus's channel count.
+            self.bus_layout = CachedBusLayout::query(&self.component);
             self.setup_processing()?;
 
             if was_active {
@@ -1140,6 +1312,40 @@ impl PluginInternal for PluginImpl {
         Ok(())
     }
 
+    fn refresh_bus_layout(&mut self) -> Result<()> {
+        if self.is_processing {
+            return Err(Error::Other(
+                "cannot refresh the bus layout while processing".to_string(),
+            ));
+        }
+        unsafe {
+            self.bus_layout = CachedBusLayout::query(&self.component);
+            // Buffers laid out from the old layout are rebuilt now rather than at the next
+            // start_processing, so process data never disagrees with the cache.
+            if self.process_data.is_some() {
+                self.create_process_data()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn bus_count(
+        &self,
+        media_type: crate::audio::MediaType,
+        direction: crate::audio::BusDirection,
+    ) -> Result<i32> {
+        use crate::audio::{BusDirection, MediaType};
+        let media = match media_type {
+            MediaType::Audio => kAudio as i32,
+            MediaType::Event => kEvent as i32,
+        };
+        let dir = match direction {
+            BusDirection::Input => kInput as i32,
+            BusDirection::Output => kOutput as i32,
+        };
+        Ok(unsafe { self.component.getBusCount(media, dir) })
+    }
+
     fn set_bus_active(
         &mut self,
         media_type: crate::audio::MediaType,
@@ -1198,6 +1404,13 @@ impl PluginInternal for PluginImpl {
                 )));
             }
         }
+        if media_type == MediaType::Audio {
+            if active {
+                self.inactive_audio_buses.remove(&(dir, bus_index));
+            } else {
+                self.inactive_audio_buses.insert((dir, bus_index));
+            }
+        }
         Ok(())
     }
 
@@ -1300,6 +1513,11 @@ impl PluginInternal for PluginImpl {
         Ok(())
     }
 
+    fn send_sysex(&mut self, data: &[u8]) -> Result<()> {
+        self.input_events.add_sysex(data.to_vec(), 0);
+        Ok(())
+    }
+
     fn start_processing(&mut self) -> Result<()> {
         unsafe {
             // Component should already be activated during initialization
@@ -1349,10 +1567,14 @@ impl PluginInternal for PluginImpl {
         }
     }
 
+    fn has_controller(&self) -> bool {
+        self.controller.is_some()
+    }
+
     fn has_editor(&self) -> bool {
         // First check our cached value
-        if self.info.has_gui {
-            return true;
+        if self.info.has_gui || self.init_flags.contains(PluginInitFlags::SKIP_GUI_CHECK) {
+            return self.info.has_gui;
         }
 
         // Otherwise do a runtime check
@@ -1376,7 +1598,11 @@ impl PluginInternal for PluginImpl {
         }
     }
 
-    fn open_editor(&mut self, parent: *mut std::ffi::c_void) -> Result<()> {
+    fn open_editor(
+        &mut self,
+        parent: *mut std::ffi::c_void,
+        platform_type: &'static std::ffi::CStr,
+    ) -> Result<()> {
         if self.plugin_view.is_some() {
             return Err(Error::Other("Editor already open".to_string()));
         }
@@ -1411,19 +1637,16 @@ impl PluginInternal for PluginImpl {
                     view.setFrame(frame.as_ptr());
                 }
 
-                // Platform-specific attachment
-                #[cfg(target_os = "macos")]
-                let platform_type = c"NSView".as_ptr();
-                #[cfg(target_os = "windows")]
-                let platform_type = c"HWND".as_ptr();
-                #[cfg(target_os = "linux")]
-                let platform_type = c"X11EmbedWindowID".as_ptr();
-                #[cfg(target_os = "android")]
-                let platform_type = c"kNativeWindowHandle".as_ptr();
+                // Platform-specific attachment (the handle says which: NSView, HWND,
+                // X11EmbedWindowID or WaylandSurfaceID).
+                let platform_name = platform_type.to_string_lossy();
+                let platform_type = platform_type.as_ptr();
 
                 // Check platform support
                 if view.isPlatformTypeSupported(platform_type) != kResultOk {
-                    return Err(Error::Other("Platform type not supported".to_string()));
+                    return Err(Error::Other(format!(
+                        "Platform type {platform_name} not supported"
+                    )));
                 }
 
                 // Attach to parent window
@@ -1439,7 +1662,7 @@ impl PluginInternal for PluginImpl {
                 Ok(())
             }
         } else {
-            Err(Error::Other("No controller available".to_string()))
+            Err(Error::NoController)
         }
     }
 
@@ -1489,14 +1712,83 @@ impl PluginInternal for PluginImpl {
                 }
             }
         } else {
-            Err(Error::Other("No controller available".to_string()))
+            Err(Error::NoController)
+        }
+    }
+
+    fn is_platform_type_supported(&self, platform_type: &std::ffi::CStr) -> Result<bool> {
+        if let Some(view) = &self.plugin_view {
+            return Ok(
+                unsafe { view.isPlatformTypeSupported(platform_type.as_ptr()) } == kResultOk,
+            );
+        }
+        let controller = self.controller.as_ref().ok_or(Error::NoController)?;
+        unsafe {
+            let view_ptr = controller.createView(c"editor".as_ptr());
+            // Released when `view` drops; never attached, so no `removed()`.
+            let view = ComPtr::<IPlugView>::from_raw(view_ptr)
+                .ok_or_else(|| Error::Other("Failed to create editor view".to_string()))?;
+            Ok(view.isPlatformTypeSupported(platform_type.as_ptr()) == kResultOk)
+        }
+    }
+
+    fn check_editor_size_constraint(&self, width: i32, height: i32) -> Result<(i32, i32)> {
+        self.with_editor_view(|view| unsafe {
+            let mut rect = ViewRect {
+                left: 0,
+                top: 0,
+                right: width,
+                bottom: height,
+            };
+            if view.canResize() != kResultTrue {
+                // A fixed-size editor's only acceptable size is its current one.
+                if view.getSize(&mut rect) != kResultOk {
+                    return (width, height);
+                }
+            } else {
+                // Some plugins adjust the rect and still return kResultFalse, so read it back
+                // either way.
+                view.checkSizeConstraint(&mut rect);
+            }
+            let (w, h) = (rect.right - rect.left, rect.bottom - rect.top);
+            if w > 0 && h > 0 {
+                (w, h)
+            } else {
+                (width, height)
+            }
+        })
+    }
+
+    fn resize_editor(&mut self, width: i32, height: i32) -> Result<()> {
+        let view = self
+            .plugin_view
+            .as_ref()
+            .ok_or_else(|| Error::Other("Editor is not open".to_string()))?;
+        let mut rect = ViewRect {
+            left: 0,
+            top: 0,
+            right: width,
+            bottom: height,
+        };
+        let result = unsafe { view.onSize(&mut rect) };
+        if result != kResultOk {
+            return Err(Error::Other(format!(
+                "Editor rejected size {width}x{height}: {result:#x}"
+            )));
         }
+        Ok(())
     }
 
     fn get_parameter_changes(&self) -> Vec<(u32, f64)> {
         self.get_parameter_changes()
     }
 
+    fn subscribe_parameter_changes(
+        &mut self,
+    ) -> Option<Receiver<crate::parameters::ParameterChange>> {
+        self.component_handler.as_ref().map(|h| h.subscribe())
+    }
+
     fn take_parameter_edits(&mut self) -> Vec<crate::plugin::ParameterEdit> {
         self.component_handler
             .as_ref()
@@ -1522,6 +1814,41 @@ impl PluginInternal for PluginImpl {
         self.editor_resize.lock().ok().and_then(|mut s| s.take())
     }
 
+    fn factory_reset(&mut self) -> Result<()> {
+        let was_processing = self.is_processing;
+        // The editor view belongs to the instance being replaced.
+        self.close_editor()?;
+
+        let mut fresh = PluginImpl::load(
+            &self.info.path,
+            self.init_flags,
+            &self.host_identity,
+            &mut |_| {},
+        )?;
+        fresh.set_audio_config(self.sample_rate, self.block_size);
+        fresh.set_sample_size(self.sample_size);
+        fresh.set_transport(
+            self.tempo,
+            self.time_sig_numerator,
+            self.time_sig_denominator,
+        );
+        fresh.playing = self.playing;
+        fresh.process_mode = self.process_mode;
+        // Keep already handed-out output MIDI consumers connected to the new instance.
+        fresh.output_midi = Arc::clone(&self.output_midi);
+        // Likewise the `subscribe_parameter_changes` receiver.
+        if let (Some(old), Some(new)) = (&self.component_handler, &fresh.component_handler) {
+            old.hand_over_subscriber(new);
+        }
+
+        // Dropping the old instance runs the full VST3 teardown.
+        *self = fresh;
+        if was_processing {
+            self.start_processing()?;
+        }
+        Ok(())
+    }
+
     fn latency_samples(&self) -> u32 {
         unsafe { self.processor.getLatencySamples() }
     }
@@ -1651,26 +1978,11 @@ impl PluginInternal for PluginImpl {
         };
         unsafe {
             // First resolve program lists (id -> program names), then attach to units.
-            let mut lists: std::collections::HashMap<i32, Vec<String>> =
-                std::collections::HashMap::new();
-            let list_count = unit_info.getProgramListCount();
-            for i in 0..list_count {
-                let mut pl: ProgramListInfo = std::mem::zeroed();
-                if unit_info.getProgramListInfo(i, &mut pl) != kResultOk {
-                    continue;
-                }
-                let mut programs = Vec::with_capacity(pl.programCount.max(0) as usize);
-                for p in 0..pl.programCount {
-                    let mut name: String128 = std::mem::zeroed();
-                    let s = if unit_info.getProgramName(pl.id, p, &mut name) == kResultOk {
-                        crate::internal::utils::vst_string_to_string(&name)
-                    } else {
-                        String::new()
-                    };
-                    programs.push(s);
-                }
-                lists.insert(pl.id, programs);
-            }
+            let lists: std::collections::HashMap<i32, Vec<String>> =
+                Self::program_lists(&unit_info)
+                    .into_iter()
+                    .map(|pl| (pl.id, Self::program_names(&unit_info, &pl)))
+                    .collect();
 
             let unit_count = unit_info.getUnitCount();
             let mut units = Vec::with_capacity(unit_count.max(0) as usize);
@@ -1684,6 +1996,7 @@ impl PluginInternal for PluginImpl {
                     id: ui.id,
                     parent_id: ui.parentUnitId,
                     name: crate::internal::utils::vst_string_to_string(&ui.name),
+                    program_list_id: ui.programListId,
                     programs,
                 });
             }
@@ -1691,6 +2004,29 @@ impl PluginInternal for PluginImpl {
         }
     }
 
+    fn get_programs(&self, program_list_id: i32) -> Result<Vec<crate::plugin::ProgramInfo>> {
+        let unit_info = self
+            .controller
+            .as_ref()
+            .and_then(|c| c.cast::<IUnitInfo>())
+            .ok_or_else(|| {
+                Error::Other("Plugin does not implement IUnitInfo (no program lists)".to_string())
+            })?;
+        unsafe {
+            let list = Self::program_lists(&unit_info)
+                .into_iter()
+                .find(|pl| pl.id == program_list_id)
+                .ok_or_else(|| {
+                    Error::Other(format!("unknown program list id {program_list_id}"))
+                })?;
+            Ok(Self::program_names(&unit_info, &list)
+                .into_iter()
+                .zip(0..)
+                .map(|(name, id)| crate::plugin::ProgramInfo { id, name })
+                .collect())
+        }
+    }
+
     fn select_program(&mut self, unit_id: i32, program_index: i32) -> Result<()> {
         let (param_id, program_count) = self.resolve_program_change(unit_id)?;
         if program_index < 0 || program_index >= program_count {
@@ -1712,21 +2048,17 @@ impl PluginInternal for PluginImpl {
     }
 
     fn output_channel_count(&self) -> usize {
-        unsafe {
-            let bus_count = self.component.getBusCount(kAudio as i32, kOutput as i32);
-            let mut total = 0usize;
-            for i in 0..bus_count {
-                let mut info: BusInfo = std::mem::zeroed();
-                if self
-                    .component
-                    .getBusInfo(kAudio as i32, kOutput as i32, i, &mut info)
-                    == kResultOk
-                {
-                    total += info.channelCount.max(0) as usize;
-                }
-            }
-            total
-        }
+        self.bus_layout
+            .outputs()
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| {
+                !self
+                    .inactive_audio_buses
+                    .contains(&(kOutput as i32, i as i32))
+            })
+            .map(|(_, &channels)| channels.max(0) as usize)
+            .sum()
     }
 
     fn save_state(&self) -> Result<Vec<u8>> {
@@ -1747,38 +2079,43 @@ impl PluginInternal for PluginImpl {
     }
 
     fn load_state(&mut self, data: &[u8]) -> Result<()> {
+        self.restore_state(|| create_memory_stream_from(data.to_vec()).to_com_ptr::<IBStream>())
+    }
+
+    fn save_state_attributes(&self) -> Result<HashMap<String, AttributeValue>> {
         unsafe {
-            // Restore the processor state.
-            let comp_stream = create_memory_stream_from(data.to_vec());
-            let comp_ptr = comp_stream
+            // The same as save_state, through a stream that also answers IStreamAttributes.
+            let stream = create_attribute_stream(Vec::new(), HashMap::new());
+            let stream_ptr = stream
                 .to_com_ptr::<IBStream>()
                 .ok_or_else(|| Error::InterfaceError("Failed to create state stream".into()))?;
-            let result = self.component.setState(comp_ptr.as_ptr());
-            // kNotImplemented is acceptable (some plugins keep all state on the controller).
-            if result != kResultOk && result != kNotImplemented {
+            let result = self.component.getState(stream_ptr.as_ptr());
+            if result != kResultOk {
                 return Err(Error::Other(format!(
-                    "Failed to restore plugin state (setState: {result:#x})"
+                    "Plugin does not provide state (getState: {result:#x})"
                 )));
             }
-
-            // For a *separate* controller, push the same bytes so its parameter cache /
-            // editor reflect the restored state. A fresh stream is used because setState
-            // consumed the first one's cursor. Skipped for single-component plugins, where
-            // setState already restored the one shared object (see `single_component`).
-            if !self.single_component {
-                if let Some(ref controller) = self.controller {
-                    let ctrl_stream = create_memory_stream_from(data.to_vec());
-                    if let Some(ctrl_ptr) = ctrl_stream.to_com_ptr::<IBStream>() {
-                        let r = controller.setComponentState(ctrl_ptr.as_ptr());
-                        if r != kResultOk && r != kNotImplemented {
-                            log::debug!("Controller setComponentState returned {r:#x} (ignored)");
-                        }
-                    }
-                }
+            let mut attrs = stream.attributes();
+            let bytes = stream.to_vec();
+            if !bytes.is_empty() {
+                attrs.insert(
+                    STATE_STREAM_ATTRIBUTE.to_string(),
+                    AttributeValue::Binary(bytes),
+                );
             }
-            Ok(())
+            Ok(attrs)
         }
     }
+
+    fn load_state_attributes(&mut self, mut attrs: HashMap<String, AttributeValue>) -> Result<()> {
+        let bytes = match attrs.remove(STATE_STREAM_ATTRIBUTE) {
+            Some(AttributeValue::Binary(bytes)) => bytes,
+            _ => Vec::new(),
+        };
+        self.restore_state(|| {
+            create_attribute_stream(bytes.clone(), attrs.clone()).to_com_ptr::<IBStream>()
+        })
+    }
 }
 
 /// Convert a raw VST3 `Event` (as a plugin emits into its output event list) into a safe
@@ -1846,6 +2183,81 @@ pub(crate) fn event_to_midi(e: &Event) -> Option<MidiEvent> {
 }
 
 impl PluginImpl {
+    /// `setState` on the processor, then `setComponentState` on a separate controller, each
+    /// with a fresh stream from `stream` (reading consumes a stream's cursor).
+    fn restore_state(&mut self, stream: impl Fn() -> Option<ComPtr<IBStream>>) -> Result<()> {
+        unsafe {
+            // Restore the processor state.
+            let comp_ptr = stream()
+                .ok_or_else(|| Error::InterfaceError("Failed to create state stream".into()))?;
+            let result = self.component.setState(comp_ptr.as_ptr());
+            // kNotImplemented is acceptable (some plugins keep all state on the controller).
+            if result != kResultOk && result != kNotImplemented {
+                return Err(Error::Other(format!(
+                    "Failed to restore plugin state (setState: {result:#x})"
+                )));
+            }
+
+            // For a *separate* controller, push the same state so its parameter cache /
+            // editor reflect the restored state. A fresh stream is used because setState
+            // consumed the first one's cursor. Skipped for single-component plugins, where
+            // setState already restored the one shared object (see `single_component`).
+            let transfer = !self
+                .init_flags
+                .contains(PluginInitFlags::SKIP_STATE_TRANSFER);
+            if transfer && !self.single_component {
+                if let Some(ref controller) = self.controller {
+                    if let Some(ctrl_ptr) = stream() {
+                        let r = controller.setComponentState(ctrl_ptr.as_ptr());
+                        if r != kResultOk && r != kNotImplemented {
+                            log::debug!("Controller setComponentState returned {r:#x} (ignored)");
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Run `f` on the open editor view, or on a throwaway view if the editor isn't open.
+    fn with_editor_view<T>(&self, f: impl FnOnce(&ComPtr<IPlugView>) -> T) -> Result<T> {
+        if let Some(view) = &self.plugin_view {
+            return Ok(f(view));
+        }
+        let controller = self.controller.as_ref().ok_or(Error::NoController)?;
+        unsafe {
+            let view_ptr = controller.createView(c"editor".as_ptr());
+            // Released when `view` drops; never attached, so no `removed()`.
+            let view = ComPtr::<IPlugView>::from_raw(view_ptr)
+                .ok_or_else(|| Error::Other("Failed to create editor view".to_string()))?;
+            Ok(f(&view))
+        }
+    }
+
+    /// Every program list the plugin's `IUnitInfo` advertises.
+    unsafe fn program_lists(unit_info: &ComPtr<IUnitInfo>) -> Vec<ProgramListInfo> {
+        (0..unit_info.getProgramListCount())
+            .filter_map(|i| {
+                let mut pl: ProgramListInfo = std::mem::zeroed();
+                (unit_info.getProgramListInfo(i, &mut pl) == kResultOk).then_some(pl)
+            })
+            .collect()
+    }
+
+    /// The names of the programs in `list`, in index order (empty for unnamed programs).
+    unsafe fn program_names(unit_info: &ComPtr<IUnitInfo>, list: &ProgramListInfo) -> Vec<String> {
+        (0..list.programCount)
+            .map(|p| {
+                let mut name: String128 = std::mem::zeroed();
+                if unit_info.getProgramName(list.id, p, &mut name) == kResultOk {
+                    crate::internal::utils::vst_string_to_string(&name)
+                } else {
+                    String::new()
+                }
+            })
+            .collect()
+    }
+
     /// Resolve a unit's program-change parameter for [`PluginInternal::select_program`].
     ///
     /// Returns `(param_id, program_count)`: the id of the controller parameter that switches
@@ -1854,10 +2266,7 @@ impl PluginImpl {
     /// if the plugin has no `IUnitInfo`, the unit is unknown, it has no (non-empty) program
     /// list, or no program-change parameter is found for it.
     fn resolve_program_change(&self, unit_id: i32) -> Result<(u32, i32)> {
-        let controller = self
-            .controller
-            .as_ref()
-            .ok_or_else(|| Error::InterfaceError("No controller available".to_string()))?;
+        let controller = self.controller.as_ref().ok_or(Error::NoController)?;
         let unit_info = controller.cast::<IUnitInfo>().ok_or_else(|| {
             Error::Other("Plugin does not implement IUnitInfo (no program lists)".to_string())
         })?;
@@ -2193,6 +2602,351 @@ fn advance_process_context(ctx: &mut ProcessContext, frames: i64) {
     }
 }
 
+/// Lay out `data`'s audio buffers from its cached bus layout: one `block_size` buffer per
+/// channel of each active bus, the channel-pointer arrays, and the bus counts. Makes no calls
+/// into the plugin, so the layout stays whatever was cached.
+fn prepare_buffers(
+    data: &mut HostProcessData,
+    inactive_audio_buses: &HashSet<(i32, i32)>,
+    block_size: usize,
+    sample_size: SampleSize,
+) {
+    data.input_buffers.clear();
+    data.output_buffers.clear();
+    data.input_bus_buffers.clear();
+    data.output_bus_buffers.clear();
+    data.input_channel_aux.clear();
+
+    // A bus the plugin couldn't describe (negative channel count) is left out entirely
+    let layout = &data.bus_layout;
+    for (bus_idx, &channels) in layout.inputs().iter().enumerate() {
+        if channels >= 0 {
+            let active = !inactive_audio_buses.contains(&(kInput as i32, bus_idx as i32));
+            let bus = allocate_bus(&mut data.input_buffers, channels, active, block_size);
+            let aux = layout.aux_inputs.get(bus_idx).copied().unwrap_or(false);
+            data.input_channel_aux
+                .extend(std::iter::repeat_n(aux, bus.numChannels as usize));
+            data.input_bus_buffers.push(bus);
+        }
+    }
+    for (bus_idx, &channels) in layout.outputs().iter().enumerate() {
+        if channels >= 0 {
+            let active = !inactive_audio_buses.contains(&(kOutput as i32, bus_idx as i32));
+            let bus = allocate_bus(&mut data.output_buffers, channels, active, block_size);
+            data.output_bus_buffers.push(bus);
+        }
+    }
+
+    // Set up process data counts
+    data.process_data.numInputs = data.input_bus_buffers.len() as i32;
+    data.process_data.numOutputs = data.output_bus_buffers.len() as i32;
+
+    // Build the channel-pointer arrays ONCE and point each bus + the process data at the
+    // (now stable) buffers. process() reuses these without allocating per block.
+    data.input_channel_ptrs =
+        SendChannelPtrs(build_ptrs(&mut data.input_buffers, &data.input_bus_buffers));
+    data.output_channel_ptrs = SendChannelPtrs(build_ptrs(
+        &mut data.output_buffers,
+        &data.output_bus_buffers,
+    ));
+    let twin = |buffers: &[Vec<f32>]| -> Vec<Vec<f64>> {
+        match sample_size {
+            SampleSize::F32 => Vec::new(),
+            SampleSize::F64 => buffers.iter().map(|b| vec![0.0; b.len()]).collect(),
+        }
+    };
+    data.input_buffers64 = twin(&data.input_buffers);
+    data.output_buffers64 = twin(&data.output_buffers);
+    data.input_channel_ptrs64 = SendChannelPtrs(build_ptrs(
+        &mut data.input_buffers64,
+        &data.input_bus_buffers,
+    ));
+    data.output_channel_ptrs64 = SendChannelPtrs(build_ptrs(
+        &mut data.output_buffers64,
+        &data.output_bus_buffers,
+    ));
+
+    // channelBuffers32/64 share one union slot: the plugin gets the set matching its sample size.
+    for (i, bus) in data.input_bus_buffers.iter_mut().enumerate() {
+        match sample_size {
+            SampleSize::F32 if !data.input_channel_ptrs.0[i].is_empty() => {
+                bus.__field0.channelBuffers32 = data.input_channel_ptrs.0[i].as_mut_ptr();
+            }
+            SampleSize::F64 if !data.input_channel_ptrs64.0[i].is_empty() => {
+                bus.__field0.channelBuffers64 = data.input_channel_ptrs64.0[i].as_mut_ptr();
+            }
+            _ => {}
+        }
+    }
+    for (i, bus) in data.output_bus_buffers.iter_mut().enumerate() {
+        match sample_size {
+            SampleSize::F32 if !data.output_channel_ptrs.0[i].is_empty() => {
+                bus.__field0.channelBuffers32 = data.output_channel_ptrs.0[i].as_mut_ptr();
+            }
+            SampleSize::F64 if !data.output_channel_ptrs64.0[i].is_empty() => {
+                bus.__field0.channelBuffers64 = data.output_channel_ptrs64.0[i].as_mut_ptr();
+            }
+            _ => {}
+        }
+    }
+    data.process_data.inputs = if data.input_bus_buffers.is_empty() {
+        ptr::null_mut()
+    } else {
+        data.input_bus_buffers.as_mut_ptr()
+    };
+    data.process_data.outputs = if data.output_bus_buffers.is_empty() {
+        ptr::null_mut()
+    } else {
+        data.output_bus_buffers.as_mut_ptr()
+    };
+
+    log::debug!(
+        "Prepared buffers: {} input buses, {} output buses, {} input channels, {} output channels",
+        data.bus_layout.audio_input_count,
+        data.bus_layout.audio_output_count,
+        data.input_buffers.len(),
+        data.output_buffers.len()
+    );
+}
+
+/// One channel-pointer array per bus, walking `buffers` in order (`buses` says how many
+/// channels each takes). Empty when `buffers` is.
+fn build_ptrs<T>(buffers: &mut [Vec<T>], buses: &[AudioBusBuffers]) -> Vec<Vec<*mut T>> {
+    let mut per_bus: Vec<Vec<*mut T>> = Vec::with_capacity(buses.len());
+    let mut chan = 0usize;
+    for bus in buses {
+        let mut ptrs = Vec::with_capacity(bus.numChannels as usize);
+        for _ in 0..bus.numChannels {
+            if chan < buffers.len() {
+                ptrs.push(buffers[chan].as_mut_ptr());
+                chan += 1;
+            }
+        }
+        per_bus.push(ptrs);
+    }
+    per_bus
+}
+
+/// Append one `block_size` buffer per channel of a `channel_count`-channel bus to `buffers`
+/// and return the bus's `AudioBusBuffers` (its channel pointers are filled in later). A
+/// deactivated bus gets no buffers and `numChannels == 0`, so the plugin has nothing to
+/// write.
+fn allocate_bus(
+    buffers: &mut Vec<Vec<f32>>,
+    channel_count: i32,
+    active: bool,
+    block_size: usize,
+) -> AudioBusBuffers {
+    let channels = if active { channel_count.max(0) } else { 0 };
+    for _ in 0..channels {
+        buffers.push(vec![0.0f32; block_size]);
+    }
+    // SAFETY: AudioBusBuffers is a plain C struct for which all-zero is valid.
+    let mut bus: AudioBusBuffers = unsafe { std::mem::zeroed() };
+    bus.numChannels = channels;
+    bus
+}
+
+#[cfg(test)]
+mod bus_buffer_tests {
+    use super::*;
+
+    #[test]
+    fn a_deactivated_bus_gets_no_buffers() {
+        let mut all = Vec::new();
+        allocate_bus(&mut all, 2, true, 64);
+        allocate_bus(&mut all, 2, true, 64);
+
+        let mut main_only = Vec::new();
+        let main = allocate_bus(&mut main_only, 2, true, 64);
+        let aux = allocate_bus(&mut main_only, 2, false, 64);
+        assert_eq!(all.len(), 4);
+        assert_eq!(main_only.len(), 2);
+        assert_eq!(main.numChannels, 2);
+        assert_eq!(aux.numChannels, 0);
+        assert!(main_only.iter().all(|buffer| buffer.len() == 64));
+    }
+
+    /// A component with one stereo input and two stereo outputs that counts every call made
+    /// into it.
+    #[derive(Default)]
+    struct CountingComponent {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl CountingComponent {
+        fn call(&self) {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    impl vst3::Class for CountingComponent {
+        type Interfaces = (IComponent,);
+    }
+
+    impl IPluginBaseTrait for CountingComponent {
+        unsafe fn initialize(&self, _context: *mut FUnknown) -> tresult {
+            self.call();
+            kResultOk
+        }
+        unsafe fn terminate(&self) -> tresult {
+            self.call();
+            kResultOk
+        }
+    }
+
+    impl IComponentTrait for CountingComponent {
+        unsafe fn getControllerClassId(&self, _class_id: *mut TUID) -> tresult {
+            self.call();
+            kNotImplemented
+        }
+        unsafe fn setIoMode(&self, _mode: IoMode) -> tresult {
+            self.call();
+            kNotImplemented
+        }
+        unsafe fn getBusCount(&self, media: MediaType, dir: BusDirection) -> i32 {
+            self.call();
+            if media != kAudio as i32 {
+                0
+            } else if dir == kInput as i32 {
+                1
+            } else {
+                2
+            }
+        }
+        unsafe fn getBusInfo(
+            &self,
+            _media: MediaType,
+            _dir: BusDirection,
+            _index: i32,
+            bus: *mut BusInfo,
+        ) -> tresult {
+            self.call();
+            (*bus).channelCount = 2;
+            kResultOk
+        }
+        unsafe fn getRoutingInfo(
+            &self,
+            _in_info: *mut RoutingInfo,
+            _out_info: *mut RoutingInfo,
+        ) -> tresult {
+            self.call();
+            kNotImplemented
+        }
+        unsafe fn activateBus(
+            &self,
+            _media: MediaType,
+            _dir: BusDirection,
+            _index: i32,
+            _state: TBool,
+        ) -> tresult {
+            self.call();
+            kResultOk
+        }
+        unsafe fn setActive(&self, _state: TBool) -> tresult {
+            self.call();
+            kResultOk
+        }
+        unsafe fn setState(&self, _state: *mut IBStream) -> tresult {
+            self.call();
+            kNotImplemented
+        }
+        unsafe fn getState(&self, _state: *mut IBStream) -> tresult {
+            self.call();
+            kNotImplemented
+        }
+    }
+
+    #[test]
+    fn prepare_buffers_makes_no_component_calls_once_the_layout_is_cached() {
+        use std::sync::atomic::Ordering;
+        let mock = ComWrapper::new(CountingComponent::default());
+        let component = mock.to_com_ptr::<IComponent>().unwrap();
+
+        let layout = unsafe { CachedBusLayout::query(&component) };
+        assert_eq!(
+            layout,
+            CachedBusLayout {
+                audio_input_count: 1,
+                audio_output_count: 2,
+                channel_counts: vec![2, 2, 2],
+                aux_inputs: vec![false],
+            }
+        );
+        let calls_to_cache = mock.calls.load(Ordering::SeqCst);
+        assert_eq!(calls_to_cache, 5); // two bus counts, three bus infos
+
+        let mut data = HostProcessData::new(layout);
+        let aux_off = HashSet::from([(kOutput as i32, 1)]);
+        prepare_buffers(&mut data, &aux_off, 64, SampleSize::F32);
+        prepare_buffers(&mut data, &HashSet::new(), 64, SampleSize::F32);
+        assert_eq!(mock.calls.load(Ordering::SeqCst), calls_to_cache);
+
+        assert_eq!(data.process_data.numInputs, 1);
+        assert_eq!(data.process_data.numOutputs, 2);
+        assert_eq!(data.input_buffers.len(), 2);
+        assert_eq!(data.output_buffers.len(), 4);
+    }
+
+    #[test]
+    fn sidechain_bus_channels_are_marked_aux() {
+        // Main stereo input, stereo sidechain, mono key input left undescribed, stereo output.
+        let layout = CachedBusLayout {
+            audio_input_count: 3,
+            audio_output_count: 1,
+            channel_counts: vec![2, 2, -1, 2],
+            aux_inputs: vec![false, true, false],
+        };
+        let mut data = HostProcessData::new(layout);
+        prepare_buffers(&mut data, &HashSet::new(), 64, SampleSize::F32);
+        assert_eq!(data.process_data.numInputs, 2);
+        assert_eq!(data.input_buffers.len(), 4);
+        assert_eq!(data.input_channel_aux, [false, false, true, true]);
+
+        // A deactivated sidechain bus contributes no channels.
+        prepare_buffers(
+            &mut data,
+            &HashSet::from([(kInput as i32, 1)]),
+            64,
+            SampleSize::F32,
+        );
+        assert_eq!(data.input_channel_aux, [false, false]);
+    }
+
+    #[test]
+    fn sixty_four_bit_processing_hands_the_plugin_double_buffers() {
+        let layout = CachedBusLayout {
+            audio_input_count: 1,
+            audio_output_count: 1,
+            channel_counts: vec![2, 2],
+            aux_inputs: vec![false],
+        };
+        let mut data = HostProcessData::new(layout);
+        prepare_buffers(&mut data, &HashSet::new(), 64, SampleSize::F64);
+        assert_eq!(data.input_buffers64.len(), 2);
+        assert_eq!(data.output_buffers64.len(), 2);
+        assert!(data.output_buffers64.iter().all(|b| b.len() == 64));
+        unsafe {
+            let bus = data.output_bus_buffers[0];
+            assert_eq!(
+                *bus.__field0.channelBuffers64.add(1),
+                data.output_buffers64[1].as_mut_ptr()
+            );
+        }
+
+        // Falling back to 32 bits drops the twins and points the buses at the f32 buffers.
+        prepare_buffers(&mut data, &HashSet::new(), 64, SampleSize::F32);
+        assert!(data.input_buffers64.is_empty() && data.output_buffers64.is_empty());
+        unsafe {
+            let bus = data.output_bus_buffers[0];
+            assert_eq!(
+                *bus.__field0.channelBuffers32,
+                data.output_buffers[0].as_mut_ptr()
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 mod transport_tests {
     use super::*;
@@ -54,15 +54,24 @@
 
 #![deny(missing_docs)]
 
+pub mod analysis;
 pub mod audio;
+pub mod compatibility;
 pub mod error;
+pub mod gm;
+pub mod graph;
 pub mod host;
 pub mod midi;
 pub mod parameters;
 pub mod playback;
 pub mod plugin;
+pub mod plugin_manager;
+pub mod preset;
 pub mod realtime;
+pub mod session;
 pub mod simple;
+pub mod stability;
+pub mod stream_monitor;
 pub mod transport;
 pub mod window;
 
@@ -71,7 +80,9 @@ pub mod discovery;
 #[cfg(feature = "egui-widgets")]
 pub mod embed;
 
-#[cfg(feature = "cpal-backend")]
+#[cfg(feature = "egui-widgets")]
+pub mod minimal_gui;
+
 pub mod backends;
 
 pub mod process_isolation;
@@ -79,41 +90,93 @@ pub mod process_isolation;
 #[cfg(feature = "midi-input")]
 pub mod midi_input;
 
+#[cfg(feature = "midi-output")]
+pub mod midi_output;
+
+#[cfg(feature = "clap-support")]
+pub mod clap_plugin;
+
 mod internal;
 
+pub use analysis::{
+    BlockStatistics, BlockStatsSnapshot, FrequencyResponsePoint, ParameterSweep, SpectrumAnalyzer,
+    DEFAULT_SPECTRUM_SIZE, HISTOGRAM_BINS,
+};
 pub use audio::{
-    read_wav, AudioBackend, AudioBuffers, AudioConfig, AudioLevels, AudioStream, BusArrangements,
-    BusDirection, ChannelLevel, InputSource, MediaType, PeakMeter, RmsWindow, SignalSource,
-    SpeakerArrangement,
+    read_wav, AnalysisReport, AtomicF32Pair, AudioBackend, AudioBuffers, AudioConfig,
+    AudioDeviceInfo, AudioLevels, AudioRecorder, AudioStream, AutoLimiter, BallisticsPreset,
+    BlockHealth, BusArrangements, BusConfiguration, BusDirection, CallbackJitterTracker,
+    CaptureSource, ChannelLevel, InputGainStage, InputSource, LinearResampler, MediaType,
+    MeterBallistics, MeterHoldMode, OutputAnalyzer, OversamplingAdapter, PeakMeter, RmsWindow,
+    SampleFormat, SampleSize, SignalSource, SpeakerArrangement, TailDetector, TimingStats,
+    TransportClock, WindowFunction, DEFAULT_LIMITER_RELEASE_MS, JITTER_BINS,
+    JITTER_OVERFLOW_BIN_US, JITTER_WARN_P99_US, NOMINAL_INPUT_LEVEL_DB, OVERSAMPLING_FACTORS,
+    SILENCE_THRESHOLD, TAIL_WINDOW_BLOCKS,
 };
+#[cfg(feature = "clap-support")]
+pub use clap_plugin::ClapPlugin;
+pub use compatibility::{CompatibilityDb, CompatibilityEntry, Workaround};
+#[cfg(feature = "clap-support")]
+pub use discovery::scan_for_clap_plugins;
 pub use discovery::{
-    discover_plugins_safe, get_detailed_plugin_info, probe_plugin_info_isolated, BusInfo,
-    BusLayout, ClassInfo, DetailedPluginInfo, FactoryInfo, PluginReport, SafeDiscoveryReport,
-    SafeDiscoverySkip, DEFAULT_PROBE_TIMEOUT,
+    check_vst3_abi, clap_standard_paths, deduplicate_plugins, discover_plugins_safe,
+    get_detailed_plugin_info, hash_bundle, load_plugin_with_timeout, parse_category,
+    plugins_to_html, probe_plugin_info_isolated, scan_clap_directories, AbiInfo, BusInfo,
+    BusLayout, CachedPluginEntry, CategoryPath, CategoryTree, ClassInfo, DetailedPluginInfo,
+    DiscoveryResult, FactoryInfo, PluginAnnotation, PluginReport, SafeDiscoveryReport,
+    SafeDiscoverySkip, ScanCache, DEFAULT_PROBE_TIMEOUT,
 };
 #[cfg(feature = "egui-widgets")]
 pub use embed::{EditorRect, EmbeddedEditor};
 pub use error::{Error, Result};
-pub use host::{DiscoveryProgress, ProbeResult, Vst3Host, Vst3HostBuilder};
-pub use midi::{cc, MidiChannel, MidiEvent, NoteExpressionInfo, NoteExpressionType, NoteId};
+pub use gm::{GmBus, MixBus, GM_CHANNELS};
+pub use graph::{
+    AudioGraph, GraphConnection, GraphNode, NodeId, NodeKind, NodePorts, PluginChain, PortKind,
+};
+pub use host::{
+    CopyReport, DiscoveryProgress, HostIdentity, ProbeResult, Vst3Host, Vst3HostBuilder,
+};
+pub use midi::{
+    bundled_controller_presets, cc, AftertouchBinding, ArpPattern, Arpeggiator, BitSet128,
+    CcBinding, ChannelPressureBinding, ChannelStrip, ControllerPreset, KeyboardPreset,
+    KeyboardSplit, MidiBinding, MidiChannel, MidiEvent, NoteBinding, NoteExpressionInfo,
+    NoteExpressionType, NoteId, NoteState, PitchBendBinding, PluginHandle, PolyphonyLimiter,
+    PolyphonyTracker, RoutingMatrix, SmfRecorder, SoftTakeover, SplitRegion, StealingStrategy,
+    SustainTracker, VelocityCurve, DEFAULT_KEYBOARD_VELOCITY,
+};
 #[cfg(feature = "midi-input")]
 pub use midi_input::{
     bind_to_handle, connect, list_midi_input_ports, MidiInputConnection, MidiInputPort,
 };
+#[cfg(feature = "midi-output")]
+pub use midi_output::VirtualMidiOutput;
+#[cfg(feature = "egui-widgets")]
+pub use minimal_gui::MinimalGuiHandle;
 pub use parameters::{
-    AutomationCurve, AutomationPoint, Parameter, ParameterAutomation, ParameterChange,
+    infer_widget_type, morph, params_near_value, randomize, AutomationCurve, AutomationEvent,
+    AutomationPoint, ChangeDebouncer, Distribution, NoteValue, Parameter, ParameterAutomation,
+    ParameterChange, ParameterMismatch, ParameterSnapshot, RandomizeConstraints, RandomizeResult,
+    SliderCurve, TempoSync, UndoStack, UndoStep, WidgetType, BIMODAL_SPREAD, DEFAULT_DEBOUNCE_MS,
+    DEFAULT_UNDO_DEPTH, FACTORY_DEFAULT_TOLERANCE, MAX_COMBO_BOX_STEPS, MORPH_EPSILON,
 };
 pub use playback::{
-    play_realtime_with_backend, play_with_backend, play_with_input_backend, AudioHandle, MidiSink,
+    play_gm_with_backend, play_monitored, play_realtime_with_backend, play_with_backend,
+    play_with_input_backend, play_with_input_monitored, AudioHandle, GmAudioHandle, MidiSink,
     RtAudioHandle,
 };
 pub use plugin::{
-    OutputMidiConsumer, ParameterEdit, ParameterEditKind, Plugin, PluginInfo, PluginPreset,
-    PluginUnit, ProcessMode, WindowHandle,
+    AttributeValue, KeyShortcut, LoadPhase, ModifierKeys, OutputMidiConsumer, ParameterEdit,
+    ParameterEditKind, Plugin, PluginFormat, PluginInfo, PluginInitFlags, PluginPreset, PluginUnit,
+    ProcessMode, ProgramInfo, WindowHandle, STATE_STREAM_ATTRIBUTE,
 };
+pub use plugin_manager::PluginManager;
+pub use preset::{MigrationStep, PresetInterpolator, PresetLoader, StateMigration};
 pub use realtime::{RealtimePluginRunner, RtControl};
+pub use session::{Session, TransportState, SESSION_VERSION};
+pub use stability::StabilityReport;
+pub use stream_monitor::{AudioStreamErrorHandler, AudioStreamStats, StreamError, StreamMonitor};
 pub use transport::{AutomationLane, BlockEvents, MidiClip, Timeline};
-pub use window::PluginWindow;
+pub use window::{LinuxPlatformType, PluginWindow, WindowBehavior, WindowGeometry};
 
 /// Prelude module for convenient imports
 pub mod prelude {
@@ -126,6 +189,7 @@ pub mod prelude {
         // NOTE: `Result` is intentionally NOT re-exported here. A single-type-param
         // `Result<T>` alias in a glob prelude shadows `std::result::Result` and breaks
         // any `Result<T, E>` written by consumers. Use `vst3_host::Result` explicitly.
+        discovery::CategoryTree,
         error::Error,
         host::{DiscoveryProgress, Vst3Host, Vst3HostBuilder},
         midi::{cc, MidiChannel, MidiEvent, NoteExpressionInfo, NoteExpressionType, NoteId},
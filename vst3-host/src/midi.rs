@@ -1,8 +1,13 @@
 //! MIDI types and utilities for VST3 host
 
+use crate::parameters::NoteValue;
+use crate::session::TransportState;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+#[cfg(feature = "midi-output")]
+pub use crate::midi_output::VirtualMidiOutput;
+
 /// MIDI channel enumeration (1-16)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MidiChannel {
@@ -259,6 +264,47 @@ pub struct NoteExpressionInfo {
 }
 
 impl MidiEvent {
+    /// The channel the event is addressed to.
+    pub fn channel(&self) -> MidiChannel {
+        match *self {
+            MidiEvent::NoteOn { channel, .. }
+            | MidiEvent::NoteOff { channel, .. }
+            | MidiEvent::ControlChange { channel, .. }
+            | MidiEvent::ProgramChange { channel, .. }
+            | MidiEvent::PitchBend { channel, .. }
+            | MidiEvent::ChannelAftertouch { channel, .. }
+            | MidiEvent::PolyAftertouch { channel, .. } => channel,
+        }
+    }
+
+    /// A pitch bend from `normalized` (clamped to -1.0..=1.0): -1.0 is full down (-8192 from
+    /// center, value 0), 0.0 is center (8192) and +1.0 is full up (+8191, value 16383).
+    pub fn pitch_bend(channel: MidiChannel, normalized: f64) -> Self {
+        let normalized = if normalized.is_nan() {
+            0.0
+        } else {
+            normalized.clamp(-1.0, 1.0)
+        };
+        let range = if normalized < 0.0 { 8192.0 } else { 8191.0 };
+        let value = (8192.0 + (normalized * range).round()) as u16;
+        MidiEvent::PitchBend { channel, value }
+    }
+
+    /// The same event addressed to `channel`.
+    pub fn with_channel(self, channel: MidiChannel) -> MidiEvent {
+        let mut event = self;
+        match &mut event {
+            MidiEvent::NoteOn { channel: c, .. }
+            | MidiEvent::NoteOff { channel: c, .. }
+            | MidiEvent::ControlChange { channel: c, .. }
+            | MidiEvent::ProgramChange { channel: c, .. }
+            | MidiEvent::PitchBend { channel: c, .. }
+            | MidiEvent::ChannelAftertouch { channel: c, .. }
+            | MidiEvent::PolyAftertouch { channel: c, .. } => *c = channel,
+        }
+        event
+    }
+
     /// Parse a single channel-voice MIDI message from raw bytes (status + data), as delivered
     /// by a MIDI input device.
     ///
@@ -324,6 +370,53 @@ impl MidiEvent {
             _ => None,
         }
     }
+
+    /// Encode the event as a raw channel-voice MIDI message, the inverse of
+    /// [`from_midi_bytes`](Self::from_midi_bytes). Returns the bytes and how many of them are
+    /// used (2 for Program Change and Channel Aftertouch, 3 otherwise); data bytes are masked to
+    /// 7 bits. Allocation-free, so it can run on the audio thread.
+    pub fn to_midi_bytes(&self) -> ([u8; 3], usize) {
+        let status = |kind: u8, channel: &MidiChannel| kind | channel.as_index();
+        match self {
+            MidiEvent::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => ([status(0x90, channel), note & 0x7F, velocity & 0x7F], 3),
+            MidiEvent::NoteOff {
+                channel,
+                note,
+                velocity,
+            } => ([status(0x80, channel), note & 0x7F, velocity & 0x7F], 3),
+            MidiEvent::ControlChange {
+                channel,
+                controller,
+                value,
+            } => ([status(0xB0, channel), controller & 0x7F, value & 0x7F], 3),
+            MidiEvent::ProgramChange { channel, program } => {
+                ([status(0xC0, channel), program & 0x7F, 0], 2)
+            }
+            MidiEvent::PitchBend { channel, value } => {
+                let value = (*value).min(0x3FFF);
+                (
+                    [
+                        status(0xE0, channel),
+                        (value & 0x7F) as u8,
+                        (value >> 7) as u8,
+                    ],
+                    3,
+                )
+            }
+            MidiEvent::ChannelAftertouch { channel, pressure } => {
+                ([status(0xD0, channel), pressure & 0x7F, 0], 2)
+            }
+            MidiEvent::PolyAftertouch {
+                channel,
+                note,
+                pressure,
+            } => ([status(0xA0, channel), note & 0x7F, pressure & 0x7F], 3),
+        }
+    }
 }
 
 /// Common MIDI control change numbers
@@ -430,231 +523,2732 @@ pub mod cc {
     pub const POLY_MODE_ON: u8 = 127;
 }
 
-/// Convert MIDI note number to note name
-/// Using the convention where C3 = MIDI 60
-pub fn note_to_name(note: u8) -> String {
-    let note_names = [
-        "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
-    ];
-    let octave = (note as i32 / 12) - 2;
-    let note_in_octave = note % 12;
-    format!("{}{}", note_names[note_in_octave as usize], octave)
+/// Maps polyphonic aftertouch onto a plugin parameter.
+///
+/// Pressure 0..=127 is scaled linearly into `range` (normalized; `range.0 > range.1` inverts
+/// the response). `None` for `channel` / `note` matches any.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AftertouchBinding {
+    /// Parameter to drive
+    pub param_id: u32,
+    /// Only react to this channel (`None` = any)
+    pub channel: Option<MidiChannel>,
+    /// Only react to this note (`None` = any)
+    pub note: Option<u8>,
+    /// Normalized value at pressure 0 and at pressure 127
+    pub range: (f64, f64),
+    /// Disabled bindings are kept but never fire
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
 }
 
-/// Convert note name to MIDI note number
-/// Accepts formats like "C3", "C#4", "Db3", etc.
-/// Using the convention where C3 = MIDI 60
-pub fn name_to_note(name: &str) -> Option<u8> {
-    let name = name.trim().to_uppercase();
-
-    // Extract the note letter and accidental
-    let (note_part, octave_str) = if name.contains('#') {
-        let parts: Vec<&str> = name.split('#').collect();
-        if parts.len() != 2 {
-            return None;
+impl AftertouchBinding {
+    /// Bind any channel / any note to `param_id` over the full `0.0..=1.0` range.
+    pub fn new(param_id: u32) -> Self {
+        Self {
+            param_id,
+            channel: None,
+            note: None,
+            range: (0.0, 1.0),
+            enabled: true,
         }
-        (format!("{}#", parts[0]), parts[1])
-    } else if name.contains('B') && name.len() > 2 && &name[1..2] == "B" {
-        // Handle Bb notation
-        (format!("{}B", &name[0..1]), &name[2..])
-    } else {
-        // Natural note
-        let mut chars = name.chars();
-        let note = chars.next()?.to_string();
-        let octave = chars.as_str();
-        (note, octave)
-    };
+    }
 
-    // Parse octave
-    let octave: i32 = octave_str.parse().ok()?;
+    /// Whether poly aftertouch on `channel` / `note` should drive this binding.
+    pub fn matches(&self, channel: MidiChannel, note: u8) -> bool {
+        self.enabled
+            && self.channel.is_none_or(|c| c == channel)
+            && self.note.is_none_or(|n| n == note)
+    }
 
-    // Convert note to semitone offset within octave
-    let semitone = match note_part.as_str() {
-        "C" => 0,
-        "C#" | "DB" => 1,
-        "D" => 2,
-        "D#" | "EB" => 3,
-        "E" => 4,
-        "F" => 5,
-        "F#" | "GB" => 6,
-        "G" => 7,
-        "G#" | "AB" => 8,
-        "A" => 9,
-        "A#" | "BB" => 10,
-        "B" => 11,
-        _ => return None,
-    };
+    /// The normalized parameter value for `pressure`.
+    pub fn value_for(&self, pressure: u8) -> f64 {
+        scale_pressure(pressure, self.range)
+    }
+}
 
-    // Calculate MIDI note number
-    // Using the convention where C3 = MIDI 60
-    let midi_note = (octave + 2) * 12 + semitone;
+/// Maps channel pressure (mono aftertouch) onto a plugin parameter; see [`AftertouchBinding`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChannelPressureBinding {
+    /// Parameter to drive
+    pub param_id: u32,
+    /// Only react to this channel (`None` = any)
+    pub channel: Option<MidiChannel>,
+    /// Normalized value at pressure 0 and at pressure 127
+    pub range: (f64, f64),
+    /// Disabled bindings are kept but never fire
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
 
-    if (0..=127).contains(&midi_note) {
-        Some(midi_note as u8)
-    } else {
-        None
+impl ChannelPressureBinding {
+    /// Bind any channel to `param_id` over the full `0.0..=1.0` range.
+    pub fn new(param_id: u32) -> Self {
+        Self {
+            param_id,
+            channel: None,
+            range: (0.0, 1.0),
+            enabled: true,
+        }
+    }
+
+    /// Whether channel pressure on `channel` should drive this binding.
+    pub fn matches(&self, channel: MidiChannel) -> bool {
+        self.enabled && self.channel.is_none_or(|c| c == channel)
+    }
+
+    /// The normalized parameter value for `pressure`.
+    pub fn value_for(&self, pressure: u8) -> f64 {
+        scale_pressure(pressure, self.range)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Maps a control change (CC) onto a plugin parameter; values 0..=127 scale into `range`
+/// like [`AftertouchBinding`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CcBinding {
+    /// Parameter to drive
+    pub param_id: u32,
+    /// Only react to this channel (`None` = any)
+    pub channel: Option<MidiChannel>,
+    /// Controller number (0-127, see [`cc`])
+    pub controller: u8,
+    /// Normalized value at CC value 0 and at 127
+    pub range: (f64, f64),
+    /// Disabled bindings are kept but never fire
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
 
-    #[test]
-    fn note_expression_type_ids_round_trip() {
-        for kind in [
-            NoteExpressionType::Volume,
-            NoteExpressionType::Pan,
-            NoteExpressionType::Tuning,
-            NoteExpressionType::Vibrato,
-            NoteExpressionType::Expression,
-            NoteExpressionType::Brightness,
-            NoteExpressionType::Custom(100_001),
-        ] {
-            assert_eq!(NoteExpressionType::from_type_id(kind.type_id()), kind);
+impl CcBinding {
+    /// Bind `controller` on any channel to `param_id` over the full `0.0..=1.0` range.
+    pub fn new(param_id: u32, controller: u8) -> Self {
+        Self {
+            param_id,
+            channel: None,
+            controller,
+            range: (0.0, 1.0),
+            enabled: true,
         }
-        // The well-known VST3 type ids.
-        assert_eq!(NoteExpressionType::Tuning.type_id(), 2);
-        assert_eq!(
-            NoteExpressionType::from_type_id(5),
-            NoteExpressionType::Brightness
-        );
     }
 
-    #[test]
-    fn from_midi_bytes_maps_channel_voice_messages() {
-        // Note on (ch 1, note 60, vel 100).
-        assert_eq!(
-            MidiEvent::from_midi_bytes(&[0x90, 60, 100]),
-            Some(MidiEvent::NoteOn {
-                channel: MidiChannel::Ch1,
-                note: 60,
-                velocity: 100
-            })
-        );
-        // Note on velocity 0 => note off.
-        assert_eq!(
-            MidiEvent::from_midi_bytes(&[0x90, 60, 0]),
-            Some(MidiEvent::NoteOff {
-                channel: MidiChannel::Ch1,
-                note: 60,
-                velocity: 0
-            })
-        );
-        // Note off on channel 10.
-        assert_eq!(
-            MidiEvent::from_midi_bytes(&[0x89, 64, 40]),
-            Some(MidiEvent::NoteOff {
-                channel: MidiChannel::Ch10,
-                note: 64,
-                velocity: 40
-            })
-        );
-        // CC.
-        assert_eq!(
-            MidiEvent::from_midi_bytes(&[0xB0, 1, 64]),
-            Some(MidiEvent::ControlChange {
-                channel: MidiChannel::Ch1,
-                controller: 1,
-                value: 64
-            })
-        );
-        // Channel + poly aftertouch.
-        assert_eq!(
-            MidiEvent::from_midi_bytes(&[0xD0, 90]),
-            Some(MidiEvent::ChannelAftertouch {
-                channel: MidiChannel::Ch1,
-                pressure: 90
-            })
-        );
-        assert_eq!(
-            MidiEvent::from_midi_bytes(&[0xA0, 60, 70]),
-            Some(MidiEvent::PolyAftertouch {
-                channel: MidiChannel::Ch1,
-                note: 60,
-                pressure: 70
-            })
-        );
+    /// Whether `controller` on `channel` should drive this binding.
+    pub fn matches(&self, channel: MidiChannel, controller: u8) -> bool {
+        self.enabled && self.channel.is_none_or(|c| c == channel) && self.controller == controller
     }
 
-    #[test]
-    fn from_midi_bytes_pitch_bend_is_14_bit() {
-        // Center: LSB 0, MSB 64 -> 8192.
-        assert_eq!(
-            MidiEvent::from_midi_bytes(&[0xE0, 0, 64]),
-            Some(MidiEvent::PitchBend {
-                channel: MidiChannel::Ch1,
-                value: 8192
-            })
-        );
-        // Max: LSB 127, MSB 127 -> 16383.
-        assert_eq!(
-            MidiEvent::from_midi_bytes(&[0xE0, 127, 127]),
-            Some(MidiEvent::PitchBend {
-                channel: MidiChannel::Ch1,
-                value: 16383
-            })
-        );
+    /// The normalized parameter value for CC `value`.
+    pub fn value_for(&self, value: u8) -> f64 {
+        scale_pressure(value, self.range)
     }
+}
 
-    #[test]
-    fn from_midi_bytes_rejects_unsupported_and_junk() {
-        assert_eq!(MidiEvent::from_midi_bytes(&[]), None); // empty
-        assert_eq!(MidiEvent::from_midi_bytes(&[0x60]), None); // data byte, not status
-        assert_eq!(MidiEvent::from_midi_bytes(&[0xF8]), None); // realtime clock
-        assert_eq!(MidiEvent::from_midi_bytes(&[0xF0, 1, 2]), None); // sysex
-        assert_eq!(MidiEvent::from_midi_bytes(&[0x90, 60]), None); // truncated note on
+/// How close (normalized) an unheard controller must be to the parameter to pick it up: one
+/// CC step.
+const PICKUP_TOLERANCE: f64 = 1.0 / 127.0;
+
+/// Soft takeover ("pickup" mode) for CC bindings: a controller only takes over a parameter
+/// once it has reached the parameter's current value, so a knob that is out of step with the
+/// plugin doesn't make the parameter jump.
+///
+/// Keeps the last value seen per controller and channel; a move picks the parameter up when
+/// the controller passes through (or lands on) the current value, in either direction.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SoftTakeover {
+    last: std::collections::HashMap<(u8, MidiChannel), f64>,
+}
+
+impl SoftTakeover {
+    /// Takeover with no controllers heard yet.
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    #[test]
-    fn from_midi_bytes_maps_program_change() {
-        assert_eq!(
-            MidiEvent::from_midi_bytes(&[0xC0, 5]),
-            Some(MidiEvent::ProgramChange {
-                channel: MidiChannel::Ch1,
-                program: 5
-            })
-        );
-        // Channel is taken from the low nibble; the program byte is masked to 7 bits.
-        assert_eq!(
-            MidiEvent::from_midi_bytes(&[0xC9, 0xFF]),
-            Some(MidiEvent::ProgramChange {
-                channel: MidiChannel::Ch10,
-                program: 127
-            })
-        );
-        // Truncated (no program byte) is rejected.
-        assert_eq!(MidiEvent::from_midi_bytes(&[0xC0]), None);
+    /// Feed CC `cc` on `ch` moving to `new_value` while the parameter sits at
+    /// `current_param_normalized`. Returns the new normalized value (`new_value / 127`) when
+    /// the controller has picked the parameter up, `None` while it hasn't.
+    pub fn process_cc(
+        &mut self,
+        cc: u8,
+        ch: MidiChannel,
+        new_value: u8,
+        current_param_normalized: f64,
+    ) -> Option<f64> {
+        let new = f64::from(new_value.min(127)) / 127.0;
+        let current = current_param_normalized;
+        let picked_up = match self.last.insert((cc, ch), new) {
+            // A controller that already drives the parameter sits on its value; otherwise the
+            // move must pass through it.
+            Some(last) => {
+                (last - current).abs() <= PICKUP_TOLERANCE
+                    || (last - current).signum() != (new - current).signum()
+            }
+            None => false,
+        } || (new - current).abs() <= PICKUP_TOLERANCE;
+        picked_up.then_some(new)
     }
 
-    #[test]
-    fn test_midi_conversions() {
-        // Test some known values using C3=60 convention
-        assert_eq!(name_to_note("C3"), Some(60));
-        assert_eq!(name_to_note("C2"), Some(48));
-        assert_eq!(name_to_note("A3"), Some(69)); // Concert A
-        assert_eq!(name_to_note("C-2"), Some(0));
-        assert_eq!(name_to_note("G8"), Some(127));
+    /// Forget every controller's last value, e.g. after the parameters were reloaded.
+    pub fn reset(&mut self) {
+        self.last.clear();
+    }
+}
 
-        // Test reverse conversion
-        assert_eq!(note_to_name(60), "C3");
-        assert_eq!(note_to_name(48), "C2");
-        assert_eq!(note_to_name(69), "A3");
-        assert_eq!(note_to_name(0), "C-2");
-        assert_eq!(note_to_name(127), "G8");
+/// Maps pitch bend onto a plugin parameter: the full 14-bit bend (0..=16383) scales into
+/// `range`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PitchBendBinding {
+    /// Parameter to drive
+    pub param_id: u32,
+    /// Only react to this channel (`None` = any)
+    pub channel: Option<MidiChannel>,
+    /// Normalized value at full bend down and full bend up
+    pub range: (f64, f64),
+    /// Disabled bindings are kept but never fire
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
 
-        // Test accidentals
-        assert_eq!(name_to_note("C#3"), Some(61));
-        assert_eq!(name_to_note("Db3"), Some(61));
-        assert_eq!(name_to_note("F#3"), Some(66));
+impl PitchBendBinding {
+    /// Bind any channel to `param_id` over the full `0.0..=1.0` range.
+    pub fn new(param_id: u32) -> Self {
+        Self {
+            param_id,
+            channel: None,
+            range: (0.0, 1.0),
+            enabled: true,
+        }
     }
 
-    #[test]
-    fn test_midi_channel() {
-        assert_eq!(MidiChannel::Ch1.as_index(), 0);
-        assert_eq!(MidiChannel::Ch16.as_index(), 15);
-        assert_eq!(MidiChannel::from_index(0), Some(MidiChannel::Ch1));
-        assert_eq!(MidiChannel::from_index(15), Some(MidiChannel::Ch16));
-        assert_eq!(MidiChannel::from_index(16), None);
+    /// Whether pitch bend on `channel` should drive this binding.
+    pub fn matches(&self, channel: MidiChannel) -> bool {
+        self.enabled && self.channel.is_none_or(|c| c == channel)
+    }
+
+    /// The normalized parameter value for bend `value` (0..=16383, center 8192).
+    pub fn value_for(&self, value: u16) -> f64 {
+        let (lo, hi) = self.range;
+        let t = value.min(16383) as f64 / 16383.0;
+        (lo + (hi - lo) * t).clamp(0.0, 1.0)
+    }
+}
+
+/// Maps notes onto a plugin parameter: a Note On sets the parameter from its velocity
+/// (scaled into `range`), a Note Off returns it to `range.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NoteBinding {
+    /// Parameter to drive
+    pub param_id: u32,
+    /// Only react to this channel (`None` = any)
+    pub channel: Option<MidiChannel>,
+    /// Only react to this note (`None` = any)
+    pub note: Option<u8>,
+    /// Normalized value at velocity 0 (and on release) and at velocity 127
+    pub range: (f64, f64),
+    /// Disabled bindings are kept but never fire
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+impl NoteBinding {
+    /// Bind any note on any channel to `param_id` over the full `0.0..=1.0` range.
+    pub fn new(param_id: u32) -> Self {
+        Self {
+            param_id,
+            channel: None,
+            note: None,
+            range: (0.0, 1.0),
+            enabled: true,
+        }
+    }
+
+    /// Whether `note` on `channel` should drive this binding.
+    pub fn matches(&self, channel: MidiChannel, note: u8) -> bool {
+        self.enabled
+            && self.channel.is_none_or(|c| c == channel)
+            && self.note.is_none_or(|n| n == note)
+    }
+
+    /// The normalized parameter value for a Note On with `velocity`.
+    pub fn value_for(&self, velocity: u8) -> f64 {
+        scale_pressure(velocity, self.range)
+    }
+}
+
+/// Any of the MIDI → parameter bindings, for storing them together (e.g. in preferences).
+///
+/// Serialized with a `"type"` tag (`"Cc"`, `"Aftertouch"`, …) next to the binding's fields.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum MidiBinding {
+    /// Control change
+    Cc(CcBinding),
+    /// Polyphonic aftertouch
+    Aftertouch(AftertouchBinding),
+    /// Channel pressure (mono aftertouch)
+    ChannelPressure(ChannelPressureBinding),
+    /// Pitch bend
+    PitchBend(PitchBendBinding),
+    /// Note velocity
+    Note(NoteBinding),
+}
+
+impl MidiBinding {
+    /// A binding of `param_id` to the source of `event` (its channel and controller / note),
+    /// for MIDI learn. `None` for events that can't drive a parameter (program changes).
+    pub fn learn(event: &MidiEvent, param_id: u32) -> Option<MidiBinding> {
+        let channel = Some(event.channel());
+        match *event {
+            MidiEvent::ControlChange { controller, .. } => Some(MidiBinding::Cc(CcBinding {
+                channel,
+                ..CcBinding::new(param_id, controller)
+            })),
+            MidiEvent::PolyAftertouch { note, .. } => {
+                Some(MidiBinding::Aftertouch(AftertouchBinding {
+                    channel,
+                    note: Some(note),
+                    ..AftertouchBinding::new(param_id)
+                }))
+            }
+            MidiEvent::ChannelAftertouch { .. } => {
+                Some(MidiBinding::ChannelPressure(ChannelPressureBinding {
+                    channel,
+                    ..ChannelPressureBinding::new(param_id)
+                }))
+            }
+            MidiEvent::PitchBend { .. } => Some(MidiBinding::PitchBend(PitchBendBinding {
+                channel,
+                ..PitchBendBinding::new(param_id)
+            })),
+            MidiEvent::NoteOn { note, .. } | MidiEvent::NoteOff { note, .. } => {
+                Some(MidiBinding::Note(NoteBinding {
+                    channel,
+                    note: Some(note),
+                    ..NoteBinding::new(param_id)
+                }))
+            }
+            MidiEvent::ProgramChange { .. } => None,
+        }
+    }
+
+    /// Short name of the binding's kind ("CC", "Poly AT", …).
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            MidiBinding::Cc(_) => "CC",
+            MidiBinding::Aftertouch(_) => "Poly AT",
+            MidiBinding::ChannelPressure(_) => "Ch. Pressure",
+            MidiBinding::PitchBend(_) => "Pitch Bend",
+            MidiBinding::Note(_) => "Note",
+        }
+    }
+
+    /// Parameter the binding drives
+    pub fn param_id(&self) -> u32 {
+        match self {
+            MidiBinding::Cc(b) => b.param_id,
+            MidiBinding::Aftertouch(b) => b.param_id,
+            MidiBinding::ChannelPressure(b) => b.param_id,
+            MidiBinding::PitchBend(b) => b.param_id,
+            MidiBinding::Note(b) => b.param_id,
+        }
+    }
+
+    /// Channel the binding listens on (`None` = any)
+    pub fn channel(&self) -> Option<MidiChannel> {
+        match self {
+            MidiBinding::Cc(b) => b.channel,
+            MidiBinding::Aftertouch(b) => b.channel,
+            MidiBinding::ChannelPressure(b) => b.channel,
+            MidiBinding::PitchBend(b) => b.channel,
+            MidiBinding::Note(b) => b.channel,
+        }
+    }
+
+    /// Normalized output range
+    pub fn range(&self) -> (f64, f64) {
+        *self.range_ref()
+    }
+
+    /// Set the normalized output range (each end clamped to `0.0..=1.0`)
+    pub fn set_range(&mut self, (lo, hi): (f64, f64)) {
+        *self.range_mut() = (lo.clamp(0.0, 1.0), hi.clamp(0.0, 1.0));
+    }
+
+    /// Whether the binding fires
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            MidiBinding::Cc(b) => b.enabled,
+            MidiBinding::Aftertouch(b) => b.enabled,
+            MidiBinding::ChannelPressure(b) => b.enabled,
+            MidiBinding::PitchBend(b) => b.enabled,
+            MidiBinding::Note(b) => b.enabled,
+        }
+    }
+
+    /// Enable or disable the binding
+    pub fn set_enabled(&mut self, enabled: bool) {
+        match self {
+            MidiBinding::Cc(b) => b.enabled = enabled,
+            MidiBinding::Aftertouch(b) => b.enabled = enabled,
+            MidiBinding::ChannelPressure(b) => b.enabled = enabled,
+            MidiBinding::PitchBend(b) => b.enabled = enabled,
+            MidiBinding::Note(b) => b.enabled = enabled,
+        }
+    }
+
+    /// The normalized value `event` sets the bound parameter to, or `None` if the binding is
+    /// disabled or doesn't listen to `event`.
+    pub fn value_for(&self, event: &MidiEvent) -> Option<f64> {
+        match (self, *event) {
+            (
+                MidiBinding::Cc(b),
+                MidiEvent::ControlChange {
+                    channel,
+                    controller,
+                    value,
+                },
+            ) => b.matches(channel, controller).then(|| b.value_for(value)),
+            (
+                MidiBinding::Aftertouch(b),
+                MidiEvent::PolyAftertouch {
+                    channel,
+                    note,
+                    pressure,
+                },
+            ) => b.matches(channel, note).then(|| b.value_for(pressure)),
+            (
+                MidiBinding::ChannelPressure(b),
+                MidiEvent::ChannelAftertouch { channel, pressure },
+            ) => b.matches(channel).then(|| b.value_for(pressure)),
+            (MidiBinding::PitchBend(b), MidiEvent::PitchBend { channel, value }) => {
+                b.matches(channel).then(|| b.value_for(value))
+            }
+            (
+                MidiBinding::Note(b),
+                MidiEvent::NoteOn {
+                    channel,
+                    note,
+                    velocity,
+                },
+            ) => b.matches(channel, note).then(|| b.value_for(velocity)),
+            (MidiBinding::Note(b), MidiEvent::NoteOff { channel, note, .. }) => {
+                b.matches(channel, note).then(|| b.value_for(0))
+            }
+            _ => None,
+        }
+    }
+
+    fn range_ref(&self) -> &(f64, f64) {
+        match self {
+            MidiBinding::Cc(b) => &b.range,
+            MidiBinding::Aftertouch(b) => &b.range,
+            MidiBinding::ChannelPressure(b) => &b.range,
+            MidiBinding::PitchBend(b) => &b.range,
+            MidiBinding::Note(b) => &b.range,
+        }
+    }
+
+    fn range_mut(&mut self) -> &mut (f64, f64) {
+        match self {
+            MidiBinding::Cc(b) => &mut b.range,
+            MidiBinding::Aftertouch(b) => &mut b.range,
+            MidiBinding::ChannelPressure(b) => &mut b.range,
+            MidiBinding::PitchBend(b) => &mut b.range,
+            MidiBinding::Note(b) => &mut b.range,
+        }
+    }
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+fn scale_pressure(pressure: u8, (lo, hi): (f64, f64)) -> f64 {
+    let t = pressure.min(127) as f64 / 127.0;
+    (lo + (hi - lo) * t).clamp(0.0, 1.0)
+}
+
+/// A named set of [`CcBinding`]s for a hardware controller, saved as JSON so a controller
+/// setup can be shared. See [`Vst3Host::load_controller_preset`](crate::Vst3Host::load_controller_preset)
+/// and [`bundled_controller_presets`] for the built-in ones.
+///
+/// The file is a JSON object; each binding has the [`CcBinding`] fields (`channel` is
+/// `"Ch1"`–`"Ch16"` or `null` for any channel, `range` the normalized values at CC 0 and
+/// 127, `enabled` optional and `true` when absent):
+///
+/// ```json
+/// {
+///   "name": "nanoKONTROL2 faders",
+///   "device_name": "nanoKONTROL2",
+///   "bindings": [
+///     { "param_id": 0, "channel": null, "controller": 0, "range": [0.0, 1.0] },
+///     { "param_id": 1, "channel": "Ch1", "controller": 1, "range": [1.0, 0.0], "enabled": false }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControllerPreset {
+    /// Display name
+    pub name: String,
+    /// The controller it was made for, as its MIDI port name (empty = any controller)
+    #[serde(default)]
+    pub device_name: String,
+    /// CC → parameter mappings
+    pub bindings: Vec<CcBinding>,
+}
+
+impl ControllerPreset {
+    /// Read a preset file.
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> crate::error::Result<Self> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| crate::error::Error::Other(format!("read controller preset: {e}")))?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| crate::error::Error::Other(format!("parse controller preset: {e}")))
+    }
+
+    /// Write the preset as pretty-printed JSON.
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> crate::error::Result<()> {
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| crate::error::Error::Other(format!("serialize controller preset: {e}")))?;
+        std::fs::write(path, json)
+            .map_err(|e| crate::error::Error::Other(format!("write controller preset: {e}")))
+    }
+}
+
+/// Built-in controller presets. They bind to parameter ids from 0 up, which suits plugins that
+/// number their parameters from 0; re-learn individual bindings for other plugins.
+pub fn bundled_controller_presets() -> &'static [ControllerPreset] {
+    static PRESETS: std::sync::OnceLock<Vec<ControllerPreset>> = std::sync::OnceLock::new();
+    PRESETS.get_or_init(|| {
+        let bind = |controllers: &mut dyn Iterator<Item = u8>| {
+            controllers
+                .enumerate()
+                .map(|(i, controller)| CcBinding::new(i as u32, controller))
+                .collect()
+        };
+        vec![
+            // CC 20-31 are undefined in the MIDI spec; most generic controllers default there.
+            ControllerPreset {
+                name: "Generic 8 faders".to_string(),
+                device_name: String::new(),
+                bindings: bind(&mut (20..28)),
+            },
+            // The General MIDI sound controllers most synths already respond to.
+            ControllerPreset {
+                name: "GM sound controllers".to_string(),
+                device_name: String::new(),
+                bindings: bind(
+                    &mut [
+                        cc::MODULATION,
+                        cc::VOLUME,
+                        cc::PAN,
+                        cc::EXPRESSION,
+                        cc::SOUND_CONTROLLER_2,
+                        cc::SOUND_CONTROLLER_3,
+                        cc::SOUND_CONTROLLER_4,
+                        cc::SOUND_CONTROLLER_5,
+                    ]
+                    .into_iter(),
+                ),
+            },
+            // Factory CC mode: faders send CC 0-7, knobs CC 16-23.
+            ControllerPreset {
+                name: "Korg nanoKONTROL2".to_string(),
+                device_name: "nanoKONTROL2".to_string(),
+                bindings: bind(&mut (0..8).chain(16..24)),
+            },
+        ]
+    })
+}
+
+/// Identifies the plugin a [`SplitRegion`] plays. The numbering is the application's own
+/// (e.g. an index into its list of loaded plugins); the host only passes it back from
+/// [`KeyboardSplit::route`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PluginHandle(pub u32);
+
+/// One zone of a [`KeyboardSplit`]: notes in `note_range` are played by `target_plugin` on
+/// `channel`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitRegion {
+    /// Notes this region covers (inclusive)
+    pub note_range: std::ops::RangeInclusive<u8>,
+    /// Plugin that plays the region
+    pub target_plugin: PluginHandle,
+    /// Channel the region's events are sent on
+    pub channel: MidiChannel,
+}
+
+/// A keyboard split: different key ranges play different plugins (e.g. bass below C3, piano
+/// above). Regions may overlap (layering); notes outside every region are dropped.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyboardSplit {
+    /// The regions, in no particular order
+    pub splits: Vec<SplitRegion>,
+}
+
+impl KeyboardSplit {
+    /// The note-on events for `note`: one per region containing it, on that region's channel.
+    pub fn route(&self, note: u8, velocity: u8) -> Vec<(PluginHandle, MidiEvent)> {
+        self.regions_for(note)
+            .map(|r| {
+                let event = MidiEvent::NoteOn {
+                    channel: r.channel,
+                    note,
+                    velocity,
+                };
+                (r.target_plugin, event)
+            })
+            .collect()
+    }
+
+    /// Route any event. Note on/off and poly aftertouch go to the regions containing their
+    /// note; channel-wide events (controllers, pitch bend, program and channel pressure) go to
+    /// every region. Each copy is moved to its region's channel.
+    pub fn route_event(&self, event: MidiEvent) -> Vec<(PluginHandle, MidiEvent)> {
+        let note = match event {
+            MidiEvent::NoteOn { note, .. }
+            | MidiEvent::NoteOff { note, .. }
+            | MidiEvent::PolyAftertouch { note, .. } => Some(note),
+            _ => None,
+        };
+        let mut routed: Vec<(PluginHandle, MidiEvent)> = Vec::new();
+        for region in &self.splits {
+            if note.is_some_and(|n| !region.note_range.contains(&n)) {
+                continue;
+            }
+            let copy = (region.target_plugin, event.with_channel(region.channel));
+            // Layered regions on the same plugin and channel need the event only once.
+            if !routed.contains(&copy) {
+                routed.push(copy);
+            }
+        }
+        routed
+    }
+
+    fn regions_for(&self, note: u8) -> impl Iterator<Item = &SplitRegion> {
+        self.splits
+            .iter()
+            .filter(move |r| r.note_range.contains(&note))
+    }
+}
+
+/// A MIDI routing matrix: each input channel fans out to any number of (plugin, output
+/// channel) destinations, e.g. to layer several plugins from one keyboard. Input channels
+/// with no destinations are dropped.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoutingMatrix {
+    /// Destinations per input channel
+    pub routes: std::collections::HashMap<MidiChannel, Vec<(PluginHandle, MidiChannel)>>,
+}
+
+impl RoutingMatrix {
+    /// A matrix with no routes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send `input` to `plugin` on `output`. Connecting twice has no further effect.
+    pub fn connect(&mut self, input: MidiChannel, plugin: PluginHandle, output: MidiChannel) {
+        let destinations = self.routes.entry(input).or_default();
+        if !destinations.contains(&(plugin, output)) {
+            destinations.push((plugin, output));
+        }
+    }
+
+    /// Stop sending `input` to `plugin` (on any output channel).
+    pub fn disconnect(&mut self, input: MidiChannel, plugin: PluginHandle) {
+        if let Some(destinations) = self.routes.get_mut(&input) {
+            destinations.retain(|&(p, _)| p != plugin);
+            if destinations.is_empty() {
+                self.routes.remove(&input);
+            }
+        }
+    }
+
+    /// The channel `input` reaches `plugin` on, if they're connected.
+    pub fn destination(&self, input: MidiChannel, plugin: PluginHandle) -> Option<MidiChannel> {
+        self.routes
+            .get(&input)?
+            .iter()
+            .find(|&&(p, _)| p == plugin)
+            .map(|&(_, output)| output)
+    }
+
+    /// One copy of `event` per destination of its channel, moved to that destination's
+    /// output channel.
+    pub fn route(&self, event: MidiEvent) -> Vec<(PluginHandle, MidiEvent)> {
+        self.routes
+            .get(&event.channel())
+            .map(|destinations| {
+                destinations
+                    .iter()
+                    .map(|&(plugin, output)| (plugin, event.with_channel(output)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// A set of 7-bit MIDI numbers (notes or controllers), one bit each.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BitSet128(pub u128);
+
+impl BitSet128 {
+    /// The empty set.
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Whether `n` is in the set. Numbers above 127 never are.
+    pub fn contains(self, n: u8) -> bool {
+        n < 128 && self.0 & (1 << n) != 0
+    }
+
+    /// Add (`on`) or remove `n`. Numbers above 127 are ignored.
+    pub fn set(&mut self, n: u8, on: bool) {
+        if n < 128 {
+            if on {
+                self.0 |= 1 << n;
+            } else {
+                self.0 &= !(1 << n);
+            }
+        }
+    }
+
+    /// Whether the set is empty.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// How many numbers are in the set.
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+}
+
+/// Per-channel MIDI processing applied before events reach a plugin: mute the channel,
+/// transpose its notes, reshape note-on velocities and drop chosen controllers. Install one
+/// per channel with [`Plugin::set_channel_strip`](crate::Plugin::set_channel_strip).
+///
+/// Change `transpose` while no notes are held on the channel, or their note-offs will miss.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChannelStrip {
+    /// The channel this strip processes
+    pub channel: MidiChannel,
+    /// `false` drops every event on the channel
+    pub enabled: bool,
+    /// Semitones added to note numbers; notes shifted out of 0–127 are dropped
+    pub transpose: i8,
+    /// Note-on velocity multiplier, `0.0..=2.0`
+    pub velocity_scale: f32,
+    /// Added to note-on velocities after scaling, `-127..=127`
+    pub velocity_offset: i8,
+    /// Controllers to drop
+    pub cc_filter: BitSet128,
+}
+
+impl ChannelStrip {
+    /// A strip for `channel` that passes everything through unchanged.
+    pub fn new(channel: MidiChannel) -> Self {
+        Self {
+            channel,
+            enabled: true,
+            transpose: 0,
+            velocity_scale: 1.0,
+            velocity_offset: 0,
+            cc_filter: BitSet128::new(),
+        }
+    }
+
+    /// Whether the strip leaves every event unchanged.
+    pub fn is_passthrough(&self) -> bool {
+        *self == Self::new(self.channel)
+    }
+
+    /// Apply the strip to `event`, or `None` if it should be dropped. Events on other channels
+    /// pass through untouched.
+    ///
+    /// Note-on velocities are clamped to `1..=127`, so scaling never turns a note-on into a
+    /// note-off.
+    pub fn process_event(&self, event: MidiEvent) -> Option<MidiEvent> {
+        if event.channel() != self.channel {
+            return Some(event);
+        }
+        if !self.enabled {
+            return None;
+        }
+        let transpose = |note: u8| u8::try_from(note as i16 + self.transpose as i16).ok();
+        Some(match event {
+            MidiEvent::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => MidiEvent::NoteOn {
+                channel,
+                note: transpose(note).filter(|&n| n <= 127)?,
+                velocity: self.shape_velocity(velocity),
+            },
+            MidiEvent::NoteOff {
+                channel,
+                note,
+                velocity,
+            } => MidiEvent::NoteOff {
+                channel,
+                note: transpose(note).filter(|&n| n <= 127)?,
+                velocity,
+            },
+            MidiEvent::PolyAftertouch {
+                channel,
+                note,
+                pressure,
+            } => MidiEvent::PolyAftertouch {
+                channel,
+                note: transpose(note).filter(|&n| n <= 127)?,
+                pressure,
+            },
+            MidiEvent::ControlChange { controller, .. } if self.cc_filter.contains(controller) => {
+                return None
+            }
+            other => other,
+        })
+    }
+
+    fn shape_velocity(&self, velocity: u8) -> u8 {
+        let scaled = (velocity as f32 * self.velocity_scale.clamp(0.0, 2.0)).round();
+        (scaled + self.velocity_offset as f32).clamp(1.0, 127.0) as u8
+    }
+}
+
+/// A note-on velocity mapping drawn as a line through breakpoints: each `(input, output)`
+/// pair pins one velocity, and velocities between two breakpoints are interpolated linearly.
+/// The curve always spans 0–127; unless a breakpoint says otherwise it starts at `(0, 0)` and
+/// ends at `(127, 127)`, so an empty curve leaves velocities unchanged. Install one with
+/// [`Plugin::set_velocity_curve`](crate::Plugin::set_velocity_curve).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VelocityCurve {
+    /// `(input, output)` velocities, sorted by input, at most one per input
+    pub breakpoints: Vec<(u8, u8)>,
+}
+
+impl VelocityCurve {
+    /// A curve through `breakpoints`, clamped to 0–127 and sorted by input. Of several
+    /// breakpoints at the same input the last wins.
+    pub fn new(breakpoints: impl IntoIterator<Item = (u8, u8)>) -> Self {
+        let mut curve = Self::identity();
+        for point in breakpoints {
+            curve.insert(point);
+        }
+        curve
+    }
+
+    /// Velocities pass through unchanged.
+    pub fn identity() -> Self {
+        Self::default()
+    }
+
+    /// Compresses every velocity into 30–100: quiet notes louder, loud notes softer.
+    pub fn soft() -> Self {
+        Self::new([(0, 30), (127, 100)])
+    }
+
+    /// Spreads the low velocities out so it takes a firmer touch to play loud: the lower
+    /// half of the range maps to 0–32.
+    pub fn hard() -> Self {
+        Self::new([(64, 32)])
+    }
+
+    /// The harder the key is struck, the quieter the note.
+    pub fn reversed() -> Self {
+        Self::new([(0, 127), (127, 1)])
+    }
+
+    /// The built-in curves and their names (handy for populating a picker).
+    pub fn presets() -> [(&'static str, VelocityCurve); 4] {
+        [
+            ("Identity", Self::identity()),
+            ("Soft", Self::soft()),
+            ("Hard", Self::hard()),
+            ("Reversed", Self::reversed()),
+        ]
+    }
+
+    /// Whether the curve leaves every velocity unchanged.
+    pub fn is_identity(&self) -> bool {
+        (0..=127).all(|v| self.apply(v) == v)
+    }
+
+    /// Add `(input, output)`, replacing any breakpoint already at `input`. Returns the new
+    /// breakpoint's index.
+    pub fn insert(&mut self, (input, output): (u8, u8)) -> usize {
+        let point = (input.min(127), output.min(127));
+        match self.breakpoints.binary_search_by_key(&point.0, |&(i, _)| i) {
+            Ok(index) => {
+                self.breakpoints[index] = point;
+                index
+            }
+            Err(index) => {
+                self.breakpoints.insert(index, point);
+                index
+            }
+        }
+    }
+
+    /// Remove the breakpoint at `index`, if there is one.
+    pub fn remove(&mut self, index: usize) -> Option<(u8, u8)> {
+        (index < self.breakpoints.len()).then(|| self.breakpoints.remove(index))
+    }
+
+    /// Move the breakpoint at `index` to `to`, keeping the breakpoints sorted. Returns its new
+    /// index, or `None` if there was no breakpoint at `index`.
+    pub fn move_point(&mut self, index: usize, to: (u8, u8)) -> Option<usize> {
+        self.remove(index)?;
+        Some(self.insert(to))
+    }
+
+    /// Map `velocity` (clamped to 0–127) through the curve.
+    pub fn apply(&self, velocity: u8) -> u8 {
+        let velocity = velocity.min(127);
+        let (mut below, mut above) = ((0, 0), (127, 127));
+        for &(input, output) in &self.breakpoints {
+            if input <= velocity {
+                below = (input, output);
+            } else {
+                above = (input, output);
+                break;
+            }
+        }
+        if below.0 == velocity || above.0 == below.0 {
+            return below.1;
+        }
+        let t = f32::from(velocity - below.0) / f32::from(above.0 - below.0);
+        let output = f32::from(below.1) + t * (f32::from(above.1) - f32::from(below.1));
+        output.round().clamp(0.0, 127.0) as u8
+    }
+
+    /// Apply the curve to `event` if it is a note-on. Its velocity stays within `1..=127`, so
+    /// the curve never turns a note-on into a note-off; a velocity-0 note-on (a note-off by
+    /// MIDI convention) passes through unchanged.
+    pub fn process_event(&self, event: MidiEvent) -> MidiEvent {
+        match event {
+            MidiEvent::NoteOn {
+                channel,
+                note,
+                velocity,
+            } if velocity > 0 => MidiEvent::NoteOn {
+                channel,
+                note,
+                velocity: self.apply(velocity).max(1),
+            },
+            other => other,
+        }
+    }
+}
+
+/// The order an [`Arpeggiator`] steps through the held notes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ArpPattern {
+    /// Lowest to highest, then wrap
+    #[default]
+    Up,
+    /// Highest to lowest, then wrap
+    Down,
+    /// Up then back down, without repeating the top and bottom notes
+    UpDown,
+    /// A random held note each step
+    Random,
+}
+
+impl ArpPattern {
+    /// Every pattern (handy for populating a picker).
+    pub const ALL: [ArpPattern; 4] = [
+        ArpPattern::Up,
+        ArpPattern::Down,
+        ArpPattern::UpDown,
+        ArpPattern::Random,
+    ];
+
+    /// Display name (e.g. `"Up/Down"`).
+    pub fn label(self) -> &'static str {
+        match self {
+            ArpPattern::Up => "Up",
+            ArpPattern::Down => "Down",
+            ArpPattern::UpDown => "Up/Down",
+            ArpPattern::Random => "Random",
+        }
+    }
+}
+
+/// A tempo-synced arpeggiator: plays the notes being held one at a time, a step every
+/// [`rate`](Self::rate), so a synth can be auditioned without playing it by hand.
+///
+/// Feed it incoming note events with [`handle_event`](Self::handle_event) (it keeps the ones it
+/// consumes from the plugin), then call [`tick`](Self::tick) once per block with the beat
+/// position at the block's end. Steps fall on the `rate` grid; each note lasts `gate` of a
+/// step. [`Vst3Host::enable_arpeggiator`](crate::Vst3Host::enable_arpeggiator) and
+/// [`AudioHandle::set_arpeggiator`](crate::AudioHandle::set_arpeggiator) run one in playback.
+///
+/// Only the settings are serialized; a deserialized arpeggiator holds no notes and starts
+/// from the first step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Arpeggiator {
+    /// Step order
+    pub pattern: ArpPattern,
+    /// Step length
+    pub rate: NoteValue,
+    /// Octaves the held notes are repeated over (1 = as played; 0 is treated as 1)
+    pub octave_range: u8,
+    /// Note length as a fraction of a step (0.0–1.0)
+    pub gate: f64,
+    /// Held notes as `(note, velocity, channel)`, in the order they were pressed.
+    #[serde(skip)]
+    pub(crate) active_notes: Vec<(u8, u8, MidiChannel)>,
+    /// The note playing now and the beat its note-off is due.
+    #[serde(skip)]
+    sounding: Option<(u8, MidiChannel, f64)>,
+    #[serde(skip)]
+    step: usize,
+    #[serde(skip)]
+    position: f64,
+    #[serde(skip, default = "default_samples_per_beat")]
+    samples_per_beat: f64,
+    #[serde(skip, default = "default_arp_seed")]
+    rng: u64,
+}
+
+/// 120 BPM at 48 kHz.
+fn default_samples_per_beat() -> f64 {
+    48000.0 * 60.0 / 120.0
+}
+
+fn default_arp_seed() -> u64 {
+    0x9E37_79B9_7F4A_7C15
+}
+
+impl Arpeggiator {
+    /// An arpeggiator over one octave with a 50% gate, timed for 120 BPM at 48 kHz until
+    /// [`set_timing`](Self::set_timing) says otherwise.
+    pub fn new(pattern: ArpPattern, rate: NoteValue) -> Self {
+        Self {
+            pattern,
+            rate,
+            octave_range: 1,
+            gate: 0.5,
+            active_notes: Vec::new(),
+            sounding: None,
+            step: 0,
+            position: 0.0,
+            samples_per_beat: default_samples_per_beat(),
+            rng: default_arp_seed(),
+        }
+    }
+
+    /// Set the sample rate and tempo used to turn beats into the sample offsets
+    /// [`tick`](Self::tick) reports. Invalid values are ignored.
+    pub fn set_timing(&mut self, sample_rate: f64, bpm: f64) {
+        let samples_per_beat = sample_rate * 60.0 / bpm;
+        if samples_per_beat.is_finite() && samples_per_beat > 0.0 {
+            self.samples_per_beat = samples_per_beat;
+        }
+    }
+
+    /// Samples per beat at the current timing.
+    pub fn samples_per_beat(&self) -> f64 {
+        self.samples_per_beat
+    }
+
+    /// The beat position reached by the last [`tick`](Self::tick).
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    /// The held notes as `(note, velocity, channel)`, in the order they were pressed.
+    pub fn active_notes(&self) -> &[(u8, u8, MidiChannel)] {
+        &self.active_notes
+    }
+
+    /// Take a note event for the arpeggiator. Note-ons (and the note-offs of notes it holds)
+    /// are consumed, returning `true`; anything else — including the note-off of a note pressed
+    /// before the arpeggiator was on — should go to the plugin as usual.
+    pub fn handle_event(&mut self, event: MidiEvent) -> bool {
+        match event {
+            MidiEvent::NoteOn {
+                channel,
+                note,
+                velocity,
+            } if velocity > 0 => {
+                self.active_notes
+                    .retain(|&(n, _, c)| (n, c) != (note, channel));
+                self.active_notes.push((note, velocity, channel));
+                true
+            }
+            MidiEvent::NoteOn { channel, note, .. } | MidiEvent::NoteOff { channel, note, .. } => {
+                let held = self.active_notes.len();
+                self.active_notes
+                    .retain(|&(n, _, c)| (n, c) != (note, channel));
+                if self.active_notes.is_empty() {
+                    self.step = 0;
+                }
+                self.active_notes.len() != held
+            }
+            _ => false,
+        }
+    }
+
+    /// Advance to `beat_pos` and return the note-ons and note-offs due since the previous
+    /// tick, each with its offset in samples from that previous position. Call it before
+    /// rendering a block with the beat position the block ends at.
+    pub fn tick(&mut self, beat_pos: f64) -> Vec<(MidiEvent, u32)> {
+        let mut events = Vec::new();
+        let from = self.position;
+        if beat_pos.is_nan() || beat_pos <= from {
+            return events;
+        }
+        let spb = self.samples_per_beat;
+        let offset = |beat: f64| ((beat - from) * spb).round().max(0.0) as u32;
+        let step_len = self.rate.beats();
+        let gate = self.gate.clamp(0.0, 1.0);
+        let mut next_step = (from / step_len).ceil() * step_len;
+        loop {
+            if let Some((note, channel, off)) = self.sounding {
+                if off < beat_pos && off <= next_step {
+                    let note_off = MidiEvent::NoteOff {
+                        channel,
+                        note,
+                        velocity: 0,
+                    };
+                    events.push((note_off, offset(off)));
+                    self.sounding = None;
+                    continue;
+                }
+            }
+            if next_step >= beat_pos {
+                break;
+            }
+            if let Some((note, velocity, channel)) = self.next_note() {
+                let note_on = MidiEvent::NoteOn {
+                    channel,
+                    note,
+                    velocity,
+                };
+                events.push((note_on, offset(next_step)));
+                self.sounding = Some((note, channel, next_step + step_len * gate));
+            }
+            next_step += step_len;
+        }
+        self.position = beat_pos;
+        events
+    }
+
+    /// Stop: forget the held notes and return the note-off for the note still sounding, if any.
+    pub fn release(&mut self) -> Option<MidiEvent> {
+        self.active_notes.clear();
+        self.step = 0;
+        self.sounding
+            .take()
+            .map(|(note, channel, _)| MidiEvent::NoteOff {
+                channel,
+                note,
+                velocity: 0,
+            })
+    }
+
+    /// The note for the next step, advancing the pattern.
+    fn next_note(&mut self) -> Option<(u8, u8, MidiChannel)> {
+        let mut sorted = self.active_notes.clone();
+        sorted.sort_by_key(|&(note, _, _)| note);
+        let octaves = self.octave_range.max(1);
+        let notes: Vec<_> = (0..octaves)
+            .flat_map(|octave| {
+                sorted.iter().filter_map(move |&(note, velocity, channel)| {
+                    let note = note.checked_add(octave.checked_mul(12)?)?;
+                    (note <= 127).then_some((note, velocity, channel))
+                })
+            })
+            .collect();
+        let len = notes.len();
+        if len == 0 {
+            return None;
+        }
+        let step = self.step;
+        self.step = self.step.wrapping_add(1);
+        let index = match self.pattern {
+            ArpPattern::Up => step % len,
+            ArpPattern::Down => len - 1 - step % len,
+            ArpPattern::UpDown if len == 1 => 0,
+            ArpPattern::UpDown => {
+                let i = step % (2 * len - 2);
+                if i < len {
+                    i
+                } else {
+                    2 * len - 2 - i
+                }
+            }
+            ArpPattern::Random => {
+                // xorshift64: cheap and allocation-free, good enough for auditioning.
+                self.rng ^= self.rng << 13;
+                self.rng ^= self.rng >> 7;
+                self.rng ^= self.rng << 17;
+                (self.rng % len as u64) as usize
+            }
+        };
+        Some(notes[index])
+    }
+}
+
+/// Velocity of virtual keyboard notes until another is chosen.
+pub const DEFAULT_KEYBOARD_VELOCITY: u8 = 100;
+
+/// A named snapshot of the virtual keyboard's setup: the channel it plays on, its octave
+/// shift and velocity, the arpeggiator and the CC bindings. Save and restore them with
+/// [`Vst3Host::save_keyboard_preset`](crate::Vst3Host::save_keyboard_preset) and
+/// [`Vst3Host::load_keyboard_preset`](crate::Vst3Host::load_keyboard_preset).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyboardPreset {
+    /// Display name
+    pub name: String,
+    /// Channel the keyboard plays on
+    pub channel: MidiChannel,
+    /// Octaves the keyboard is shifted by
+    pub octave_shift: i8,
+    /// Note-on velocity of played notes (1–127)
+    pub velocity: u8,
+    /// The arpeggiator, when one is on
+    #[serde(default)]
+    pub arpeggiator: Option<Arpeggiator>,
+    /// CC → parameter mappings
+    #[serde(default)]
+    pub cc_bindings: Vec<CcBinding>,
+}
+
+/// Which voice a [`PolyphonyLimiter`] cuts to make room for a new note.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StealingStrategy {
+    /// The voice held longest
+    #[default]
+    Oldest,
+    /// The most recently started voice
+    Newest,
+    /// The voice estimated to be quietest (see [`PolyphonyLimiter::voice_level`])
+    Quietest,
+}
+
+impl StealingStrategy {
+    /// Every strategy (handy for populating a picker).
+    pub const ALL: [StealingStrategy; 3] = [
+        StealingStrategy::Oldest,
+        StealingStrategy::Newest,
+        StealingStrategy::Quietest,
+    ];
+
+    /// Display name
+    pub fn label(self) -> &'static str {
+        match self {
+            StealingStrategy::Oldest => "Oldest",
+            StealingStrategy::Newest => "Newest",
+            StealingStrategy::Quietest => "Quietest",
+        }
+    }
+}
+
+/// Time constant (seconds) of the decay [`StealingStrategy::Quietest`] assumes for every voice.
+const VOICE_DECAY_SECONDS: f64 = 2.0;
+
+/// A sounding note tracked by [`PolyphonyLimiter`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Voice {
+    channel: MidiChannel,
+    note: u8,
+    velocity: u8,
+    /// Limiter clock (seconds) at the note-on
+    started: f64,
+}
+
+/// Caps how many notes sound at once: a note-on beyond `max_voices` first cuts an existing
+/// voice, chosen by `stealing`. Set one on a plugin with
+/// [`Plugin::set_polyphony_limit`](crate::Plugin::set_polyphony_limit).
+///
+/// Feed it every outgoing event with [`note_event`](Self::note_event) and advance its clock with
+/// [`advance`](Self::advance) as audio is rendered (only [`StealingStrategy::Quietest`] uses
+/// the clock).
+#[derive(Debug, Clone)]
+pub struct PolyphonyLimiter {
+    /// Most notes sounding at once (at least 1)
+    pub max_voices: u8,
+    /// Which voice to cut when the limit is reached
+    pub stealing: StealingStrategy,
+    /// Sounding voices, oldest first
+    voices: std::collections::VecDeque<Voice>,
+    clock: f64,
+    stolen: u64,
+}
+
+impl PolyphonyLimiter {
+    /// A limiter allowing `max_voices` notes (raised to 1 if 0).
+    pub fn new(max_voices: u8, stealing: StealingStrategy) -> Self {
+        Self {
+            max_voices: max_voices.max(1),
+            stealing,
+            voices: std::collections::VecDeque::with_capacity(max_voices.max(1) as usize + 1),
+            clock: 0.0,
+            stolen: 0,
+        }
+    }
+
+    /// Track `event` on its way to the plugin. Returns the note-off of the voice a note-on
+    /// steals, which must be sent **before** `event`; `event` itself is always passed on.
+    pub fn note_event(&mut self, event: MidiEvent) -> Option<MidiEvent> {
+        match event {
+            MidiEvent::NoteOn {
+                channel,
+                note,
+                velocity,
+            } if velocity > 0 => {
+                // A retrigger replaces the note's own voice rather than taking another.
+                self.release(channel, note);
+                let stolen = if self.voices.len() >= self.max_voices.max(1) as usize {
+                    self.steal()
+                } else {
+                    None
+                };
+                self.voices.push_back(Voice {
+                    channel,
+                    note,
+                    velocity,
+                    started: self.clock,
+                });
+                stolen
+            }
+            MidiEvent::NoteOn { channel, note, .. } | MidiEvent::NoteOff { channel, note, .. } => {
+                self.release(channel, note);
+                None
+            }
+            // All Sound Off / All Notes Off silence the channel.
+            MidiEvent::ControlChange {
+                channel,
+                controller: 120 | 123,
+                ..
+            } => {
+                self.voices.retain(|v| v.channel != channel);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Move the clock forward by `seconds` of rendered audio.
+    pub fn advance(&mut self, seconds: f64) {
+        if seconds.is_finite() && seconds > 0.0 {
+            self.clock += seconds;
+        }
+    }
+
+    /// Number of notes sounding now
+    pub fn active_voices(&self) -> usize {
+        self.voices.len()
+    }
+
+    /// The sounding notes as `(channel, note)`, oldest first
+    pub fn voices(&self) -> impl Iterator<Item = (MidiChannel, u8)> + '_ {
+        self.voices.iter().map(|v| (v.channel, v.note))
+    }
+
+    /// How many voices have been stolen so far
+    pub fn stolen_count(&self) -> u64 {
+        self.stolen
+    }
+
+    /// Forget every voice (e.g. after a MIDI panic).
+    pub fn reset(&mut self) {
+        self.voices.clear();
+    }
+
+    /// Estimated level of the voice playing `note` on `channel` (0.0–1.0), or `None` if it isn't
+    /// sounding. The limiter can't hear the plugin, so it assumes the note-on velocity decaying
+    /// exponentially with a 2 s time constant; [`StealingStrategy::Quietest`] cuts the lowest.
+    pub fn voice_level(&self, channel: MidiChannel, note: u8) -> Option<f64> {
+        self.voices
+            .iter()
+            .find(|v| (v.channel, v.note) == (channel, note))
+            .map(|v| self.level(v))
+    }
+
+    fn level(&self, voice: &Voice) -> f64 {
+        let age = self.clock - voice.started;
+        voice.velocity as f64 / 127.0 * (-age / VOICE_DECAY_SECONDS).exp()
+    }
+
+    fn release(&mut self, channel: MidiChannel, note: u8) {
+        self.voices
+            .retain(|v| (v.channel, v.note) != (channel, note));
+    }
+
+    /// Remove the voice the strategy picks and return its note-off.
+    fn steal(&mut self) -> Option<MidiEvent> {
+        let index = match self.stealing {
+            StealingStrategy::Oldest => 0,
+            StealingStrategy::Newest => self.voices.len().checked_sub(1)?,
+            StealingStrategy::Quietest => {
+                self.voices
+                    .iter()
+                    .enumerate()
+                    .min_by(|(_, a), (_, b)| self.level(a).total_cmp(&self.level(b)))?
+                    .0
+            }
+        };
+        let voice = self.voices.remove(index)?;
+        self.stolen += 1;
+        Some(MidiEvent::NoteOff {
+            channel: voice.channel,
+            note: voice.note,
+            velocity: 0,
+        })
+    }
+}
+
+/// Follows the sustain pedal (CC 64) on each channel and the notes let go while it's down,
+/// so they can still be stopped if the pedal never comes back up (a panic, a stopped
+/// plugin). Every [`Plugin`](crate::Plugin) keeps one, fed from
+/// [`Plugin::send_midi_event`](crate::Plugin::send_midi_event).
+#[derive(Debug, Clone, Default)]
+pub struct SustainTracker {
+    /// Whether the pedal is down, by channel index.
+    sustain_held: [bool; 16],
+    /// Sounding notes by `(channel index, note)`: `true` once the key was let go while the
+    /// pedal held the note.
+    sustained_notes: std::collections::HashMap<(u8, u8), bool>,
+}
+
+impl SustainTracker {
+    /// A tracker with every pedal up and no notes sounding.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track `event` on its way to the plugin. Returns the note-offs to send **after** it:
+    /// when the pedal comes up, one for each note let go while it was down.
+    pub fn note_event(&mut self, event: MidiEvent) -> Vec<MidiEvent> {
+        match event {
+            MidiEvent::NoteOn {
+                channel,
+                note,
+                velocity,
+            } if velocity > 0 => {
+                self.sustained_notes
+                    .insert((channel.as_index(), note), false);
+            }
+            MidiEvent::NoteOn { channel, note, .. } | MidiEvent::NoteOff { channel, note, .. } => {
+                let key = (channel.as_index(), note);
+                if self.sustain_held[key.0 as usize] {
+                    if let Some(released) = self.sustained_notes.get_mut(&key) {
+                        *released = true;
+                    }
+                } else {
+                    self.sustained_notes.remove(&key);
+                }
+            }
+            MidiEvent::ControlChange {
+                channel,
+                controller: 64,
+                value,
+            } => {
+                let index = channel.as_index();
+                let was_held =
+                    std::mem::replace(&mut self.sustain_held[index as usize], value >= 64);
+                if was_held && value < 64 {
+                    return self.release_channel(channel);
+                }
+            }
+            // Reset All Controllers lifts the pedal in the plugin too.
+            MidiEvent::ControlChange {
+                channel,
+                controller: 121,
+                ..
+            } => self.sustain_held[channel.as_index() as usize] = false,
+            // All Sound Off / All Notes Off silence the channel.
+            MidiEvent::ControlChange {
+                channel,
+                controller: 120 | 123,
+                ..
+            } => self
+                .sustained_notes
+                .retain(|&(index, _), _| index != channel.as_index()),
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Whether the pedal is down on `channel`.
+    pub fn is_sustained(&self, channel: MidiChannel) -> bool {
+        self.sustain_held[channel.as_index() as usize]
+    }
+
+    /// Whether the pedal is down, for each channel (index 0 = channel 1).
+    pub fn sustained_channels(&self) -> [bool; 16] {
+        self.sustain_held
+    }
+
+    /// The notes let go while the pedal was down that it still holds, as `(channel, note)`.
+    pub fn pending_releases(&self) -> impl Iterator<Item = (MidiChannel, u8)> + '_ {
+        self.sustained_notes
+            .iter()
+            .filter(|(_, &released)| released)
+            .filter_map(|(&(index, note), _)| Some((MidiChannel::from_index(index)?, note)))
+    }
+
+    fn release_channel(&mut self, channel: MidiChannel) -> Vec<MidiEvent> {
+        let index = channel.as_index();
+        let mut notes: Vec<u8> = self
+            .sustained_notes
+            .iter()
+            .filter(|(&(ch, _), &released)| ch == index && released)
+            .map(|(&(_, note), _)| note)
+            .collect();
+        notes.sort_unstable();
+        for note in &notes {
+            self.sustained_notes.remove(&(index, *note));
+        }
+        notes
+            .into_iter()
+            .map(|note| MidiEvent::NoteOff {
+                channel,
+                note,
+                velocity: 0,
+            })
+            .collect()
+    }
+}
+
+/// A note [`PolyphonyTracker`] has seen start and not yet stop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoteState {
+    /// Note-on velocity (1-127)
+    pub velocity: u8,
+    /// When the note-on was seen
+    pub note_on_time: std::time::Instant,
+    /// Which note-on this was, counting up from 0 across the tracker's lifetime
+    pub note_id: i32,
+}
+
+/// Which notes are sounding, on every channel: fed a stream of events (typically a plugin's
+/// MIDI output), it keeps each note from its note-on to its note-off. A note-on with velocity
+/// 0 counts as a note-off, and All Sound Off / All Notes Off (CC 120 / 123) end every note on
+/// the channel. Unlike [`PolyphonyLimiter`] it only observes.
+#[derive(Debug, Clone, Default)]
+pub struct PolyphonyTracker {
+    active_notes: std::collections::HashMap<(MidiChannel, u8), NoteState>,
+    next_note_id: i32,
+}
+
+impl PolyphonyTracker {
+    /// A tracker with no notes sounding.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track `event`. A note-on for a note already sounding restarts it.
+    pub fn update(&mut self, event: &MidiEvent) {
+        match *event {
+            MidiEvent::NoteOn {
+                channel,
+                note,
+                velocity,
+            } if velocity > 0 => {
+                let note_id = self.next_note_id;
+                self.next_note_id = self.next_note_id.wrapping_add(1);
+                self.active_notes.insert(
+                    (channel, note),
+                    NoteState {
+                        velocity,
+                        note_on_time: std::time::Instant::now(),
+                        note_id,
+                    },
+                );
+            }
+            MidiEvent::NoteOn { channel, note, .. } | MidiEvent::NoteOff { channel, note, .. } => {
+                self.active_notes.remove(&(channel, note));
+            }
+            MidiEvent::ControlChange {
+                channel,
+                controller: 120 | 123,
+                ..
+            } => self.active_notes.retain(|&(ch, _), _| ch != channel),
+            _ => {}
+        }
+    }
+
+    /// The sounding notes, by `(channel, note)`.
+    pub fn active_notes(&self) -> &std::collections::HashMap<(MidiChannel, u8), NoteState> {
+        &self.active_notes
+    }
+
+    /// How many notes are sounding, across all channels.
+    pub fn active_voice_count(&self) -> usize {
+        self.active_notes.len()
+    }
+
+    /// Forget every note, e.g. when the plugin is stopped or reloaded.
+    pub fn clear(&mut self) {
+        self.active_notes.clear();
+    }
+}
+
+/// Frame a System Exclusive message for delivery, adding the `0xF0` status prefix and the
+/// `0xF7` end-of-exclusive byte when `data` omits them.
+///
+/// # Errors
+///
+/// [`Error::MidiError`](crate::Error::MidiError) if the message has no payload, or if a byte
+/// between the framing bytes has its high bit set (SysEx data bytes are 7-bit).
+pub fn frame_sysex(data: &[u8]) -> crate::Result<Vec<u8>> {
+    let body = data.strip_prefix(&[0xF0]).unwrap_or(data);
+    let body = body.strip_suffix(&[0xF7]).unwrap_or(body);
+    if body.is_empty() {
+        return Err(crate::Error::MidiError("Empty SysEx message".to_string()));
+    }
+    if let Some(byte) = body.iter().find(|&&b| b > 0x7F) {
+        return Err(crate::Error::MidiError(format!(
+            "Invalid SysEx data byte: {:#04x}",
+            byte
+        )));
+    }
+    let mut framed = Vec::with_capacity(body.len() + 2);
+    framed.push(0xF0);
+    framed.extend_from_slice(body);
+    framed.push(0xF7);
+    Ok(framed)
+}
+
+/// Convert MIDI note number to note name
+/// Using the convention where C3 = MIDI 60
+pub fn note_to_name(note: u8) -> String {
+    let note_names = [
+        "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+    ];
+    let octave = (note as i32 / 12) - 2;
+    let note_in_octave = note % 12;
+    format!("{}{}", note_names[note_in_octave as usize], octave)
+}
+
+/// Convert note name to MIDI note number
+/// Accepts formats like "C3", "C#4", "Db3", etc.
+/// Using the convention where C3 = MIDI 60
+pub fn name_to_note(name: &str) -> Option<u8> {
+    let name = name.trim().to_uppercase();
+
+    // Extract the note letter and accidental
+    let (note_part, octave_str) = if name.contains('#') {
+        let parts: Vec<&str> = name.split('#').collect();
+        if parts.len() != 2 {
+            return None;
+        }
+        (format!("{}#", parts[0]), parts[1])
+    } else if name.contains('B') && name.len() > 2 && &name[1..2] == "B" {
+        // Handle Bb notation
+        (format!("{}B", &name[0..1]), &name[2..])
+    } else {
+        // Natural note
+        let mut chars = name.chars();
+        let note = chars.next()?.to_string();
+        let octave = chars.as_str();
+        (note, octave)
+    };
+
+    // Parse octave
+    let octave: i32 = octave_str.parse().ok()?;
+
+    // Convert note to semitone offset within octave
+    let semitone = match note_part.as_str() {
+        "C" => 0,
+        "C#" | "DB" => 1,
+        "D" => 2,
+        "D#" | "EB" => 3,
+        "E" => 4,
+        "F" => 5,
+        "F#" | "GB" => 6,
+        "G" => 7,
+        "G#" | "AB" => 8,
+        "A" => 9,
+        "A#" | "BB" => 10,
+        "B" => 11,
+        _ => return None,
+    };
+
+    // Calculate MIDI note number
+    // Using the convention where C3 = MIDI 60
+    let midi_note = (octave + 2) * 12 + semitone;
+
+    if (0..=127).contains(&midi_note) {
+        Some(midi_note as u8)
+    } else {
+        None
+    }
+}
+
+/// Records MIDI events as a Standard MIDI File (type 0: one track), for replay in a DAW.
+///
+/// Events arrive with sample positions and are placed on a tick grid at the tempo of the
+/// [`TransportState`] the recorder was given; the first event of a recording lands on tick 0.
+/// The saved file starts with that tempo and time signature, so a DAW lines it up with its own
+/// grid.
+#[derive(Debug, Clone)]
+pub struct SmfRecorder {
+    transport: TransportState,
+    ticks_per_quarter: u16,
+    recording: bool,
+    /// Sample position of the recording's first event, which becomes tick 0
+    origin: Option<i64>,
+    events: Vec<(u64, MidiEvent)>,
+}
+
+impl SmfRecorder {
+    /// Default resolution, in ticks per quarter note.
+    pub const DEFAULT_TICKS_PER_QUARTER: u16 = 480;
+
+    /// A stopped recorder timing events at `transport`'s tempo.
+    pub fn new(transport: TransportState) -> Self {
+        Self {
+            transport,
+            ticks_per_quarter: Self::DEFAULT_TICKS_PER_QUARTER,
+            recording: false,
+            origin: None,
+            events: Vec::new(),
+        }
+    }
+
+    /// Time events recorded from now on at `transport`'s tempo and save its time signature.
+    pub fn set_transport(&mut self, transport: TransportState) {
+        self.transport = transport;
+    }
+
+    /// Start a new recording at `ticks_per_quarter` (clamped to 1..=32767), discarding the
+    /// previous one.
+    pub fn start_recording(&mut self, ticks_per_quarter: u16) {
+        self.ticks_per_quarter = ticks_per_quarter.clamp(1, 0x7FFF);
+        self.recording = true;
+        self.origin = None;
+        self.events.clear();
+    }
+
+    /// Stop capturing, keeping what was recorded for [`Self::save`].
+    pub fn stop_recording(&mut self) {
+        self.recording = false;
+    }
+
+    /// Whether [`Self::record_event`] is capturing.
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// The recorded events with their tick positions, in the order they arrived.
+    pub fn events(&self) -> &[(u64, MidiEvent)] {
+        &self.events
+    }
+
+    /// Append `event`, heard at `sample_pos` at `sample_rate` Hz. Ignored while not recording
+    /// or when `sample_rate` isn't positive; events before the recording's first are placed
+    /// on tick 0.
+    pub fn record_event(&mut self, event: &MidiEvent, sample_pos: i64, sample_rate: f64) {
+        if !self.recording || sample_rate.is_nan() || sample_rate <= 0.0 {
+            return;
+        }
+        let origin = *self.origin.get_or_insert(sample_pos);
+        let seconds = (sample_pos - origin) as f64 / sample_rate;
+        let quarters = seconds * self.transport.tempo / 60.0;
+        let tick = (quarters * f64::from(self.ticks_per_quarter))
+            .round()
+            .max(0.0) as u64;
+        self.events.push((tick, *event));
+    }
+
+    /// The recording as the bytes of a type 0 Standard MIDI File.
+    pub fn to_bytes(&self) -> crate::error::Result<Vec<u8>> {
+        use midly::num::{u15, u24, u28, u4, u7};
+        use midly::{Format, Header, MetaMessage, Smf, Timing, TrackEvent, TrackEventKind};
+
+        let tempo = if self.transport.tempo > 0.0 {
+            self.transport.tempo
+        } else {
+            120.0
+        };
+        let micros_per_quarter = (60_000_000.0 / tempo).round().clamp(1.0, 16_777_215.0) as u32;
+        let numerator = self.transport.time_sig_numerator.clamp(1, 255) as u8;
+        let denominator = (self.transport.time_sig_denominator.max(1) as u32).ilog2() as u8;
+
+        let mut track = vec![
+            TrackEvent {
+                delta: u28::new(0),
+                kind: TrackEventKind::Meta(MetaMessage::Tempo(u24::new(micros_per_quarter))),
+            },
+            TrackEvent {
+                delta: u28::new(0),
+                kind: TrackEventKind::Meta(MetaMessage::TimeSignature(
+                    numerator,
+                    denominator,
+                    24,
+                    8,
+                )),
+            },
+        ];
+        let mut events: Vec<&(u64, MidiEvent)> = self.events.iter().collect();
+        events.sort_by_key(|(tick, _)| *tick);
+        let mut last_tick = 0;
+        for (tick, event) in events {
+            let ([status, data1, data2], _) = event.to_midi_bytes();
+            let message = match status & 0xF0 {
+                0x80 => midly::MidiMessage::NoteOff {
+                    key: u7::new(data1),
+                    vel: u7::new(data2),
+                },
+                0x90 => midly::MidiMessage::NoteOn {
+                    key: u7::new(data1),
+                    vel: u7::new(data2),
+                },
+                0xA0 => midly::MidiMessage::Aftertouch {
+                    key: u7::new(data1),
+                    vel: u7::new(data2),
+                },
+                0xB0 => midly::MidiMessage::Controller {
+                    controller: u7::new(data1),
+                    value: u7::new(data2),
+                },
+                0xC0 => midly::MidiMessage::ProgramChange {
+                    program: u7::new(data1),
+                },
+                0xD0 => midly::MidiMessage::ChannelAftertouch {
+                    vel: u7::new(data1),
+                },
+                _ => midly::MidiMessage::PitchBend {
+                    bend: midly::PitchBend(midly::num::u14::new(
+                        u16::from(data2) << 7 | u16::from(data1),
+                    )),
+                },
+            };
+            // A delta is 28 bits; longer gaps would need filler events, and no DAW session
+            // runs that long at any sane resolution.
+            let delta = (tick - last_tick).min(0x0FFF_FFFF) as u32;
+            last_tick = *tick;
+            track.push(TrackEvent {
+                delta: u28::new(delta),
+                kind: TrackEventKind::Midi {
+                    channel: u4::new(status & 0x0F),
+                    message,
+                },
+            });
+        }
+        track.push(TrackEvent {
+            delta: u28::new(0),
+            kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+        });
+
+        let mut smf = Smf::new(Header::new(
+            Format::SingleTrack,
+            Timing::Metrical(u15::new(self.ticks_per_quarter)),
+        ));
+        smf.tracks.push(track);
+        let mut bytes = Vec::new();
+        smf.write_std(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Write the recording to `path` as a type 0 Standard MIDI File.
+    pub fn save(&self, path: &std::path::Path) -> crate::error::Result<()> {
+        std::fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bass_and_piano() -> KeyboardSplit {
+        KeyboardSplit {
+            splits: vec![
+                SplitRegion {
+                    note_range: 36..=59,
+                    target_plugin: PluginHandle(0),
+                    channel: MidiChannel::Ch1,
+                },
+                SplitRegion {
+                    note_range: 60..=96,
+                    target_plugin: PluginHandle(1),
+                    channel: MidiChannel::Ch2,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn split_boundary_note_goes_to_the_upper_region() {
+        let split = bass_and_piano();
+        let on = |channel, note| MidiEvent::NoteOn {
+            channel,
+            note,
+            velocity: 100,
+        };
+        assert_eq!(
+            split.route(60, 100),
+            vec![(PluginHandle(1), on(MidiChannel::Ch2, 60))]
+        );
+        assert_eq!(
+            split.route(59, 100),
+            vec![(PluginHandle(0), on(MidiChannel::Ch1, 59))]
+        );
+        // Outside every region: dropped.
+        assert!(split.route(20, 100).is_empty());
+    }
+
+    #[test]
+    fn split_routes_note_offs_by_note_and_controllers_everywhere() {
+        let split = bass_and_piano();
+        let off = MidiEvent::NoteOff {
+            channel: MidiChannel::Ch1,
+            note: 40,
+            velocity: 0,
+        };
+        assert_eq!(split.route_event(off), vec![(PluginHandle(0), off)]);
+
+        let sustain = MidiEvent::ControlChange {
+            channel: MidiChannel::Ch1,
+            controller: cc::SUSTAIN,
+            value: 127,
+        };
+        let routed = split.route_event(sustain);
+        assert_eq!(routed.len(), 2);
+        assert_eq!(routed[1].1.channel(), MidiChannel::Ch2);
+    }
+
+    #[test]
+    fn soft_takeover_picks_up_when_crossing_upwards() {
+        let mut takeover = SoftTakeover::new();
+        let ch = MidiChannel::Ch1;
+        // The knob starts well below the parameter: nothing applies until it passes 0.5.
+        assert_eq!(takeover.process_cc(cc::EXPRESSION, ch, 10, 0.5), None);
+        assert_eq!(takeover.process_cc(cc::EXPRESSION, ch, 40, 0.5), None);
+        assert_eq!(
+            takeover.process_cc(cc::EXPRESSION, ch, 80, 0.5),
+            Some(80.0 / 127.0)
+        );
+        // Picked up: the parameter now follows, both ways.
+        let current = 80.0 / 127.0;
+        assert_eq!(
+            takeover.process_cc(cc::EXPRESSION, ch, 60, current),
+            Some(60.0 / 127.0)
+        );
+        // Another channel is tracked on its own.
+        assert_eq!(
+            takeover.process_cc(cc::EXPRESSION, MidiChannel::Ch2, 0, 0.5),
+            None
+        );
+    }
+
+    #[test]
+    fn soft_takeover_picks_up_when_crossing_downwards() {
+        let mut takeover = SoftTakeover::new();
+        let ch = MidiChannel::Ch5;
+        assert_eq!(takeover.process_cc(7, ch, 127, 0.25), None);
+        assert_eq!(takeover.process_cc(7, ch, 64, 0.25), None);
+        assert_eq!(takeover.process_cc(7, ch, 0, 0.25), Some(0.0));
+
+        // Landing on the value counts as crossing it, even as the first move.
+        takeover.reset();
+        assert_eq!(takeover.process_cc(7, ch, 127, 1.0), Some(1.0));
+
+        // Moving back away without reaching the parameter again doesn't apply.
+        takeover.reset();
+        assert_eq!(takeover.process_cc(7, ch, 100, 0.25), None);
+        assert_eq!(takeover.process_cc(7, ch, 110, 0.25), None);
+    }
+
+    #[test]
+    fn routing_matrix_fans_a_channel_out_to_each_plugin() {
+        let mut matrix = RoutingMatrix::new();
+        matrix.connect(MidiChannel::Ch1, PluginHandle(0), MidiChannel::Ch1);
+        matrix.connect(MidiChannel::Ch1, PluginHandle(1), MidiChannel::Ch3);
+        matrix.connect(MidiChannel::Ch1, PluginHandle(1), MidiChannel::Ch3);
+        let on = note_on(60, 100);
+        assert_eq!(
+            matrix.route(on),
+            vec![
+                (PluginHandle(0), on),
+                (PluginHandle(1), on.with_channel(MidiChannel::Ch3)),
+            ]
+        );
+        assert_eq!(
+            matrix.destination(MidiChannel::Ch1, PluginHandle(1)),
+            Some(MidiChannel::Ch3)
+        );
+
+        // Unrouted channels are dropped.
+        assert!(matrix.route(on.with_channel(MidiChannel::Ch2)).is_empty());
+        matrix.disconnect(MidiChannel::Ch1, PluginHandle(0));
+        matrix.disconnect(MidiChannel::Ch1, PluginHandle(1));
+        assert!(matrix.route(on).is_empty());
+        assert!(matrix.routes.is_empty());
+    }
+
+    #[test]
+    fn channel_strip_transposes_by_an_octave() {
+        let mut strip = ChannelStrip::new(MidiChannel::Ch1);
+        strip.transpose = 12;
+        assert_eq!(
+            strip.process_event(note_on(60, 100)),
+            Some(note_on(72, 100))
+        );
+        let off = MidiEvent::NoteOff {
+            channel: MidiChannel::Ch1,
+            note: 60,
+            velocity: 0,
+        };
+        assert_eq!(
+            strip.process_event(off),
+            Some(MidiEvent::NoteOff {
+                channel: MidiChannel::Ch1,
+                note: 72,
+                velocity: 0,
+            })
+        );
+        // Shifted out of range: dropped rather than wrapped.
+        assert_eq!(strip.process_event(note_on(120, 100)), None);
+        strip.transpose = -12;
+        assert_eq!(strip.process_event(note_on(5, 100)), None);
+    }
+
+    #[test]
+    fn channel_strip_scales_and_offsets_velocity() {
+        let mut strip = ChannelStrip::new(MidiChannel::Ch1);
+        strip.velocity_scale = 0.5;
+        assert_eq!(strip.process_event(note_on(60, 100)), Some(note_on(60, 50)));
+        strip.velocity_offset = 10;
+        assert_eq!(strip.process_event(note_on(60, 100)), Some(note_on(60, 60)));
+        // Never rounds down to a note-off or past 127.
+        strip.velocity_scale = 0.0;
+        strip.velocity_offset = 0;
+        assert_eq!(strip.process_event(note_on(60, 100)), Some(note_on(60, 1)));
+        strip.velocity_scale = 2.0;
+        assert_eq!(
+            strip.process_event(note_on(60, 100)),
+            Some(note_on(60, 127))
+        );
+    }
+
+    #[test]
+    fn channel_strip_filters_controllers_and_mutes() {
+        let mut strip = ChannelStrip::new(MidiChannel::Ch1);
+        strip.cc_filter.set(cc::SUSTAIN, true);
+        let cc = |controller| MidiEvent::ControlChange {
+            channel: MidiChannel::Ch1,
+            controller,
+            value: 127,
+        };
+        assert_eq!(strip.process_event(cc(cc::SUSTAIN)), None);
+        assert_eq!(
+            strip.process_event(cc(cc::EXPRESSION)),
+            Some(cc(cc::EXPRESSION))
+        );
+
+        strip.enabled = false;
+        assert_eq!(strip.process_event(note_on(60, 100)), None);
+        // Other channels are never touched.
+        let other = MidiEvent::NoteOn {
+            channel: MidiChannel::Ch2,
+            note: 60,
+            velocity: 100,
+        };
+        assert_eq!(strip.process_event(other), Some(other));
+    }
+
+    #[test]
+    fn a_single_breakpoint_at_64_127_maxes_every_velocity_above_it() {
+        let curve = VelocityCurve::new([(64, 127)]);
+        for velocity in 64..=127 {
+            assert_eq!(curve.apply(velocity), 127, "velocity {velocity}");
+        }
+        // Below the breakpoint the line runs up from (0, 0).
+        assert_eq!(curve.apply(0), 0);
+        assert_eq!(curve.apply(32), 64);
+    }
+
+    #[test]
+    fn velocity_curve_presets_and_editing() {
+        assert!(VelocityCurve::identity().is_identity());
+        let soft = VelocityCurve::soft();
+        assert_eq!((soft.apply(0), soft.apply(127)), (30, 100));
+        let hard = VelocityCurve::hard();
+        assert!(hard.apply(32) < 32 && hard.apply(127) == 127);
+        let reversed = VelocityCurve::reversed();
+        assert_eq!((reversed.apply(1), reversed.apply(127)), (126, 1));
+        // Never a note-off, and velocity-0 note-ons are left alone.
+        let silent = VelocityCurve::new([(0, 0), (127, 0)]);
+        assert_eq!(silent.process_event(note_on(60, 100)), note_on(60, 1));
+        assert_eq!(silent.process_event(note_on(60, 0)), note_on(60, 0));
+
+        let mut curve = VelocityCurve::new([(100, 10), (20, 90), (100, 20)]);
+        assert_eq!(curve.breakpoints, vec![(20, 90), (100, 20)]);
+        assert_eq!(curve.move_point(0, (110, 5)), Some(1));
+        assert_eq!(curve.breakpoints, vec![(100, 20), (110, 5)]);
+        assert_eq!(curve.remove(1), Some((110, 5)));
+        assert_eq!(curve.remove(5), None);
+    }
+
+    #[test]
+    fn bitset128_covers_all_128_numbers() {
+        let mut set = BitSet128::new();
+        set.set(0, true);
+        set.set(127, true);
+        set.set(200, true);
+        assert!(set.contains(0) && set.contains(127) && !set.contains(64));
+        assert_eq!(set.len(), 2);
+        set.set(0, false);
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn note_expression_type_ids_round_trip() {
+        for kind in [
+            NoteExpressionType::Volume,
+            NoteExpressionType::Pan,
+            NoteExpressionType::Tuning,
+            NoteExpressionType::Vibrato,
+            NoteExpressionType::Expression,
+            NoteExpressionType::Brightness,
+            NoteExpressionType::Custom(100_001),
+        ] {
+            assert_eq!(NoteExpressionType::from_type_id(kind.type_id()), kind);
+        }
+        // The well-known VST3 type ids.
+        assert_eq!(NoteExpressionType::Tuning.type_id(), 2);
+        assert_eq!(
+            NoteExpressionType::from_type_id(5),
+            NoteExpressionType::Brightness
+        );
+    }
+
+    #[test]
+    fn from_midi_bytes_maps_channel_voice_messages() {
+        // Note on (ch 1, note 60, vel 100).
+        assert_eq!(
+            MidiEvent::from_midi_bytes(&[0x90, 60, 100]),
+            Some(MidiEvent::NoteOn {
+                channel: MidiChannel::Ch1,
+                note: 60,
+                velocity: 100
+            })
+        );
+        // Note on velocity 0 => note off.
+        assert_eq!(
+            MidiEvent::from_midi_bytes(&[0x90, 60, 0]),
+            Some(MidiEvent::NoteOff {
+                channel: MidiChannel::Ch1,
+                note: 60,
+                velocity: 0
+            })
+        );
+        // Note off on channel 10.
+        assert_eq!(
+            MidiEvent::from_midi_bytes(&[0x89, 64, 40]),
+            Some(MidiEvent::NoteOff {
+                channel: MidiChannel::Ch10,
+                note: 64,
+                velocity: 40
+            })
+        );
+        // CC.
+        assert_eq!(
+            MidiEvent::from_midi_bytes(&[0xB0, 1, 64]),
+            Some(MidiEvent::ControlChange {
+                channel: MidiChannel::Ch1,
+                controller: 1,
+                value: 64
+            })
+        );
+        // Channel + poly aftertouch.
+        assert_eq!(
+            MidiEvent::from_midi_bytes(&[0xD0, 90]),
+            Some(MidiEvent::ChannelAftertouch {
+                channel: MidiChannel::Ch1,
+                pressure: 90
+            })
+        );
+        assert_eq!(
+            MidiEvent::from_midi_bytes(&[0xA0, 60, 70]),
+            Some(MidiEvent::PolyAftertouch {
+                channel: MidiChannel::Ch1,
+                note: 60,
+                pressure: 70
+            })
+        );
+    }
+
+    #[test]
+    fn from_midi_bytes_pitch_bend_is_14_bit() {
+        // Center: LSB 0, MSB 64 -> 8192.
+        assert_eq!(
+            MidiEvent::from_midi_bytes(&[0xE0, 0, 64]),
+            Some(MidiEvent::PitchBend {
+                channel: MidiChannel::Ch1,
+                value: 8192
+            })
+        );
+        // Max: LSB 127, MSB 127 -> 16383.
+        assert_eq!(
+            MidiEvent::from_midi_bytes(&[0xE0, 127, 127]),
+            Some(MidiEvent::PitchBend {
+                channel: MidiChannel::Ch1,
+                value: 16383
+            })
+        );
+    }
+
+    #[test]
+    fn pitch_bend_extremes_encode_as_14_bit_bytes() {
+        // +8191 from center: LSB 127, MSB 127.
+        assert_eq!(
+            MidiEvent::pitch_bend(MidiChannel::Ch1, 1.0).to_midi_bytes(),
+            ([0xE0, 0x7F, 0x7F], 3)
+        );
+        // -8192 from center: LSB 0, MSB 0.
+        assert_eq!(
+            MidiEvent::pitch_bend(MidiChannel::Ch2, -1.0).to_midi_bytes(),
+            ([0xE1, 0x00, 0x00], 3)
+        );
+        assert_eq!(
+            MidiEvent::pitch_bend(MidiChannel::Ch1, 0.0).to_midi_bytes(),
+            ([0xE0, 0x00, 0x40], 3)
+        );
+        assert_eq!(
+            MidiEvent::pitch_bend(MidiChannel::Ch1, 0.5),
+            MidiEvent::PitchBend {
+                channel: MidiChannel::Ch1,
+                value: 8192 + 4096
+            }
+        );
+    }
+
+    #[test]
+    fn from_midi_bytes_rejects_unsupported_and_junk() {
+        assert_eq!(MidiEvent::from_midi_bytes(&[]), None); // empty
+        assert_eq!(MidiEvent::from_midi_bytes(&[0x60]), None); // data byte, not status
+        assert_eq!(MidiEvent::from_midi_bytes(&[0xF8]), None); // realtime clock
+        assert_eq!(MidiEvent::from_midi_bytes(&[0xF0, 1, 2]), None); // sysex
+        assert_eq!(MidiEvent::from_midi_bytes(&[0x90, 60]), None); // truncated note on
+    }
+
+    #[test]
+    fn from_midi_bytes_maps_program_change() {
+        assert_eq!(
+            MidiEvent::from_midi_bytes(&[0xC0, 5]),
+            Some(MidiEvent::ProgramChange {
+                channel: MidiChannel::Ch1,
+                program: 5
+            })
+        );
+        // Channel is taken from the low nibble; the program byte is masked to 7 bits.
+        assert_eq!(
+            MidiEvent::from_midi_bytes(&[0xC9, 0xFF]),
+            Some(MidiEvent::ProgramChange {
+                channel: MidiChannel::Ch10,
+                program: 127
+            })
+        );
+        // Truncated (no program byte) is rejected.
+        assert_eq!(MidiEvent::from_midi_bytes(&[0xC0]), None);
+    }
+
+    #[test]
+    fn to_midi_bytes_round_trips_through_from_midi_bytes() {
+        let channel = MidiChannel::Ch3;
+        let events = [
+            MidiEvent::NoteOn {
+                channel,
+                note: 60,
+                velocity: 100,
+            },
+            MidiEvent::NoteOff {
+                channel,
+                note: 60,
+                velocity: 64,
+            },
+            MidiEvent::ControlChange {
+                channel,
+                controller: 74,
+                value: 12,
+            },
+            MidiEvent::ProgramChange {
+                channel,
+                program: 9,
+            },
+            MidiEvent::PitchBend {
+                channel,
+                value: 12345,
+            },
+            MidiEvent::ChannelAftertouch {
+                channel,
+                pressure: 33,
+            },
+            MidiEvent::PolyAftertouch {
+                channel,
+                note: 61,
+                pressure: 44,
+            },
+        ];
+        for event in events {
+            let (bytes, len) = event.to_midi_bytes();
+            assert_eq!(bytes[0] & 0x0F, 2, "{event:?} lost its channel");
+            assert_eq!(MidiEvent::from_midi_bytes(&bytes[..len]), Some(event));
+        }
+        assert_eq!(
+            MidiEvent::ProgramChange {
+                channel: MidiChannel::Ch1,
+                program: 5,
+            }
+            .to_midi_bytes(),
+            ([0xC0, 5, 0], 2)
+        );
+    }
+
+    #[test]
+    fn test_midi_conversions() {
+        // Test some known values using C3=60 convention
+        assert_eq!(name_to_note("C3"), Some(60));
+        assert_eq!(name_to_note("C2"), Some(48));
+        assert_eq!(name_to_note("A3"), Some(69)); // Concert A
+        assert_eq!(name_to_note("C-2"), Some(0));
+        assert_eq!(name_to_note("G8"), Some(127));
+
+        // Test reverse conversion
+        assert_eq!(note_to_name(60), "C3");
+        assert_eq!(note_to_name(48), "C2");
+        assert_eq!(note_to_name(69), "A3");
+        assert_eq!(note_to_name(0), "C-2");
+        assert_eq!(note_to_name(127), "G8");
+
+        // Test accidentals
+        assert_eq!(name_to_note("C#3"), Some(61));
+        assert_eq!(name_to_note("Db3"), Some(61));
+        assert_eq!(name_to_note("F#3"), Some(66));
+    }
+
+    #[test]
+    fn test_midi_channel() {
+        assert_eq!(MidiChannel::Ch1.as_index(), 0);
+        assert_eq!(MidiChannel::Ch16.as_index(), 15);
+        assert_eq!(MidiChannel::from_index(0), Some(MidiChannel::Ch1));
+        assert_eq!(MidiChannel::from_index(15), Some(MidiChannel::Ch16));
+        assert_eq!(MidiChannel::from_index(16), None);
+    }
+
+    #[test]
+    fn every_midi_binding_variant_round_trips_through_json() {
+        let bindings = [
+            MidiBinding::Cc(CcBinding {
+                channel: Some(MidiChannel::Ch2),
+                range: (0.2, 0.8),
+                ..CcBinding::new(1, cc::MODULATION)
+            }),
+            MidiBinding::Aftertouch(AftertouchBinding {
+                note: Some(60),
+                enabled: false,
+                ..AftertouchBinding::new(2)
+            }),
+            MidiBinding::ChannelPressure(ChannelPressureBinding::new(3)),
+            MidiBinding::PitchBend(PitchBendBinding {
+                range: (1.0, 0.0),
+                ..PitchBendBinding::new(4)
+            }),
+            MidiBinding::Note(NoteBinding {
+                channel: Some(MidiChannel::Ch10),
+                note: Some(36),
+                ..NoteBinding::new(5)
+            }),
+        ];
+        let tags = ["Cc", "Aftertouch", "ChannelPressure", "PitchBend", "Note"];
+        for (binding, tag) in bindings.into_iter().zip(tags) {
+            let json = serde_json::to_string(&binding).unwrap();
+            assert!(json.contains(&format!(r#""type":"{tag}""#)), "{json}");
+            let back: MidiBinding = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, binding);
+        }
+
+        // Bindings saved before `enabled` existed load enabled.
+        let old: MidiBinding = serde_json::from_str(
+            r#"{"type":"ChannelPressure","param_id":7,"channel":null,"range":[0.0,1.0]}"#,
+        )
+        .unwrap();
+        assert!(old.is_enabled());
+        assert_eq!(old.param_id(), 7);
+    }
+
+    #[test]
+    fn learned_bindings_follow_their_source_event() {
+        let cc = MidiEvent::ControlChange {
+            channel: MidiChannel::Ch3,
+            controller: 74,
+            value: 127,
+        };
+        let mut binding = MidiBinding::learn(&cc, 9).unwrap();
+        assert_eq!(binding.kind_name(), "CC");
+        assert_eq!(binding.channel(), Some(MidiChannel::Ch3));
+        assert_eq!(binding.value_for(&cc), Some(1.0));
+        // Another controller, or another channel, doesn't drive it.
+        let other = MidiEvent::ControlChange {
+            channel: MidiChannel::Ch3,
+            controller: 75,
+            value: 127,
+        };
+        assert_eq!(binding.value_for(&other), None);
+        assert_eq!(binding.value_for(&cc.with_channel(MidiChannel::Ch4)), None);
+
+        binding.set_range((0.25, 0.75));
+        assert_eq!(binding.value_for(&cc), Some(0.75));
+        binding.set_enabled(false);
+        assert_eq!(binding.value_for(&cc), None);
+
+        let bend = MidiEvent::PitchBend {
+            channel: MidiChannel::Ch1,
+            value: 0,
+        };
+        assert_eq!(
+            MidiBinding::learn(&bend, 1).unwrap().value_for(&bend),
+            Some(0.0)
+        );
+
+        let note_on = MidiEvent::NoteOn {
+            channel: MidiChannel::Ch1,
+            note: 40,
+            velocity: 127,
+        };
+        let note = MidiBinding::learn(&note_on, 1).unwrap();
+        assert_eq!(note.value_for(&note_on), Some(1.0));
+        let note_off = MidiEvent::NoteOff {
+            channel: MidiChannel::Ch1,
+            note: 40,
+            velocity: 64,
+        };
+        assert_eq!(note.value_for(&note_off), Some(0.0));
+
+        let program = MidiEvent::ProgramChange {
+            channel: MidiChannel::Ch1,
+            program: 3,
+        };
+        assert_eq!(MidiBinding::learn(&program, 1), None);
+    }
+
+    /// Note-ons the arpeggiator plays while `held` are held, over `beats` beats.
+    fn arp_notes(pattern: ArpPattern, held: &[u8], beats: f64) -> Vec<u8> {
+        let mut arp = Arpeggiator::new(pattern, NoteValue::Sixteenth);
+        for &note in held {
+            let on = MidiEvent::NoteOn {
+                channel: MidiChannel::Ch1,
+                note,
+                velocity: 100,
+            };
+            assert!(arp.handle_event(on));
+        }
+        arp.tick(beats)
+            .into_iter()
+            .filter_map(|(event, _)| match event {
+                MidiEvent::NoteOn { note, .. } => Some(note),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn single_held_note_plays_the_same_up_and_down() {
+        let up = arp_notes(ArpPattern::Up, &[60], 2.0);
+        assert_eq!(up, [60; 8]);
+        assert_eq!(arp_notes(ArpPattern::Down, &[60], 2.0), up);
+    }
+
+    #[test]
+    fn patterns_step_through_the_held_notes() {
+        let chord = [64, 60, 67];
+        assert_eq!(
+            arp_notes(ArpPattern::Up, &chord, 1.5),
+            [60, 64, 67, 60, 64, 67]
+        );
+        assert_eq!(
+            arp_notes(ArpPattern::Down, &chord, 1.5),
+            [67, 64, 60, 67, 64, 60]
+        );
+        assert_eq!(
+            arp_notes(ArpPattern::UpDown, &chord, 1.5),
+            [60, 64, 67, 64, 60, 64]
+        );
+        let random = arp_notes(ArpPattern::Random, &chord, 4.0);
+        assert!(random.iter().all(|n| chord.contains(n)));
+    }
+
+    #[test]
+    fn arpeggiator_gates_notes_at_sample_offsets() {
+        let mut arp = Arpeggiator::new(ArpPattern::Up, NoteValue::Quarter);
+        arp.octave_range = 2;
+        arp.gate = 0.25;
+        arp.set_timing(48000.0, 120.0); // 24000 samples per beat
+        arp.handle_event(MidiEvent::NoteOn {
+            channel: MidiChannel::Ch2,
+            note: 60,
+            velocity: 90,
+        });
+
+        let block = arp.tick(0.5);
+        assert_eq!(
+            block,
+            [
+                (
+                    MidiEvent::NoteOn {
+                        channel: MidiChannel::Ch2,
+                        note: 60,
+                        velocity: 90
+                    },
+                    0
+                ),
+                (
+                    MidiEvent::NoteOff {
+                        channel: MidiChannel::Ch2,
+                        note: 60,
+                        velocity: 0
+                    },
+                    6000
+                ),
+            ]
+        );
+        // The second step is an octave up, 12000 samples into the next block.
+        assert!(matches!(
+            arp.tick(1.1)[..],
+            [(MidiEvent::NoteOn { note: 72, .. }, 12000)]
+        ));
+
+        // Releasing the key stops new steps; the sounding note still ends on time.
+        assert!(arp.handle_event(MidiEvent::NoteOff {
+            channel: MidiChannel::Ch2,
+            note: 60,
+            velocity: 0,
+        }));
+        assert!(matches!(
+            arp.tick(2.5)[..],
+            [(MidiEvent::NoteOff { note: 72, .. }, 3600)]
+        ));
+        assert!(arp.tick(4.0).is_empty());
+        // A note-off for a key the arpeggiator never saw passes through.
+        assert!(!arp.handle_event(MidiEvent::NoteOff {
+            channel: MidiChannel::Ch1,
+            note: 50,
+            velocity: 0,
+        }));
+    }
+
+    fn note_on(note: u8, velocity: u8) -> MidiEvent {
+        MidiEvent::NoteOn {
+            channel: MidiChannel::Ch1,
+            note,
+            velocity,
+        }
+    }
+
+    #[test]
+    fn single_voice_limit_cuts_the_first_note() {
+        let mut limiter = PolyphonyLimiter::new(1, StealingStrategy::Oldest);
+        assert_eq!(limiter.note_event(note_on(60, 100)), None);
+        assert_eq!(
+            limiter.note_event(note_on(64, 100)),
+            Some(MidiEvent::NoteOff {
+                channel: MidiChannel::Ch1,
+                note: 60,
+                velocity: 0
+            })
+        );
+        assert_eq!(
+            limiter.voices().collect::<Vec<_>>(),
+            [(MidiChannel::Ch1, 64)]
+        );
+        assert_eq!(limiter.stolen_count(), 1);
+
+        // Retriggering the sounding note doesn't steal, and a velocity-0 note-on releases it.
+        assert_eq!(limiter.note_event(note_on(64, 80)), None);
+        assert_eq!(limiter.note_event(note_on(64, 0)), None);
+        assert_eq!(limiter.active_voices(), 0);
+    }
+
+    #[test]
+    fn stealing_strategies_pick_their_voice() {
+        let stolen_note = |stealing| {
+            let mut limiter = PolyphonyLimiter::new(3, stealing);
+            limiter.note_event(note_on(60, 40));
+            limiter.advance(0.5);
+            limiter.note_event(note_on(64, 127));
+            limiter.advance(0.5);
+            limiter.note_event(note_on(67, 100));
+            match limiter.note_event(note_on(72, 100)) {
+                Some(MidiEvent::NoteOff { note, .. }) => note,
+                other => panic!("expected a note-off, got {other:?}"),
+            }
+        };
+        assert_eq!(stolen_note(StealingStrategy::Oldest), 60);
+        assert_eq!(stolen_note(StealingStrategy::Newest), 67);
+        // 60 started softest and has decayed longest.
+        assert_eq!(stolen_note(StealingStrategy::Quietest), 60);
+
+        let mut limiter = PolyphonyLimiter::new(2, StealingStrategy::Quietest);
+        limiter.note_event(note_on(60, 127));
+        limiter.advance(4.0);
+        limiter.note_event(note_on(64, 30));
+        // A loud note held for 4 s has decayed below a fresh soft one.
+        let old = limiter.voice_level(MidiChannel::Ch1, 60).unwrap();
+        let fresh = limiter.voice_level(MidiChannel::Ch1, 64).unwrap();
+        assert!(old < fresh, "{old} vs {fresh}");
+        assert!(matches!(
+            limiter.note_event(note_on(67, 100)),
+            Some(MidiEvent::NoteOff { note: 60, .. })
+        ));
+
+        // All Notes Off clears the channel.
+        limiter.note_event(MidiEvent::ControlChange {
+            channel: MidiChannel::Ch1,
+            controller: 123,
+            value: 0,
+        });
+        assert_eq!(limiter.active_voices(), 0);
+    }
+
+    #[test]
+    fn controller_preset_with_eight_bindings_round_trips() {
+        let preset = ControllerPreset {
+            name: "Desk".to_string(),
+            device_name: "Keylab mkII 61".to_string(),
+            bindings: (0..8)
+                .map(|i| CcBinding {
+                    channel: MidiChannel::from_index(i as u8),
+                    range: (0.0, 1.0 - i as f64 / 10.0),
+                    enabled: i % 2 == 0,
+                    ..CcBinding::new(100 + i, 70 + i as u8)
+                })
+                .collect(),
+        };
+        let json = serde_json::to_string(&preset).unwrap();
+        let back: ControllerPreset = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, preset);
+        assert_eq!(back.bindings.len(), 8);
+
+        // The documented minimal form: no device, `enabled` omitted.
+        let minimal: ControllerPreset = serde_json::from_str(
+            r#"{"name": "x", "bindings": [{"param_id": 4, "channel": null, "controller": 7, "range": [0.0, 1.0]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(minimal.device_name, "");
+        assert_eq!(minimal.bindings, [CcBinding::new(4, 7)]);
+    }
+
+    #[test]
+    fn recorded_c_major_scale_saves_as_a_type_0_midi_file() {
+        let transport = crate::session::TransportState {
+            tempo: 120.0,
+            time_sig_numerator: 4,
+            time_sig_denominator: 4,
+            playing: true,
+        };
+        let mut recorder = SmfRecorder::new(transport);
+        recorder.start_recording(480);
+        let scale = [60, 62, 64, 65, 67, 69, 71, 72];
+        let sample_rate = 48_000.0;
+        // One quarter note (half a second at 120 bpm) each, starting mid-stream.
+        for (i, &note) in scale.iter().enumerate() {
+            let on = 1_000 + i as i64 * 24_000;
+            let channel = MidiChannel::Ch1;
+            let note_on = MidiEvent::NoteOn {
+                channel,
+                note,
+                velocity: 100,
+            };
+            let note_off = MidiEvent::NoteOff {
+                channel,
+                note,
+                velocity: 0,
+            };
+            recorder.record_event(&note_on, on, sample_rate);
+            recorder.record_event(&note_off, on + 12_000, sample_rate);
+        }
+        recorder.stop_recording();
+        assert_eq!(recorder.events()[0].0, 0);
+        assert_eq!(recorder.events()[2].0, 480);
+
+        let path = std::env::temp_dir().join(format!("smf-scale-{}.mid", std::process::id()));
+        recorder.save(&path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let smf = midly::Smf::parse(&bytes).unwrap();
+        assert_eq!(smf.header.format, midly::Format::SingleTrack);
+        assert_eq!(smf.tracks.len(), 1);
+
+        let mut ons = Vec::new();
+        let mut offs = Vec::new();
+        for event in &smf.tracks[0] {
+            match event.kind {
+                midly::TrackEventKind::Midi {
+                    message: midly::MidiMessage::NoteOn { key, .. },
+                    ..
+                } => ons.push(key.as_int()),
+                midly::TrackEventKind::Midi {
+                    message: midly::MidiMessage::NoteOff { key, .. },
+                    ..
+                } => offs.push(key.as_int()),
+                midly::TrackEventKind::Midi { .. } => panic!("unexpected event {event:?}"),
+                _ => {}
+            }
+        }
+        assert_eq!(ons.len() + offs.len(), 16);
+        assert_eq!(ons, scale);
+        assert_eq!(offs, scale);
+    }
+
+    #[test]
+    fn polyphony_tracker_ends_voices_on_note_off_and_zero_velocity_note_on() {
+        let on = |channel, note, velocity| MidiEvent::NoteOn {
+            channel,
+            note,
+            velocity,
+        };
+        let mut tracker = PolyphonyTracker::new();
+        tracker.update(&on(MidiChannel::Ch1, 60, 100));
+        tracker.update(&on(MidiChannel::Ch1, 64, 90));
+        tracker.update(&on(MidiChannel::Ch2, 60, 80));
+        assert_eq!(tracker.active_voice_count(), 3);
+        let state = tracker.active_notes()[&(MidiChannel::Ch2, 60)];
+        assert_eq!((state.velocity, state.note_id), (80, 2));
+
+        tracker.update(&MidiEvent::NoteOff {
+            channel: MidiChannel::Ch1,
+            note: 60,
+            velocity: 64,
+        });
+        assert!(!tracker.active_notes().contains_key(&(MidiChannel::Ch1, 60)));
+        tracker.update(&on(MidiChannel::Ch1, 64, 0));
+        assert_eq!(
+            tracker.active_notes().keys().collect::<Vec<_>>(),
+            [&(MidiChannel::Ch2, 60)]
+        );
+
+        tracker.update(&MidiEvent::ControlChange {
+            channel: MidiChannel::Ch2,
+            controller: 123,
+            value: 0,
+        });
+        assert_eq!(tracker.active_voice_count(), 0);
+    }
+
+    #[test]
+    fn sysex_is_framed_with_status_and_end_bytes() {
+        // A GM System On message, with and without its framing bytes.
+        let gm_on = [0xF0, 0x7E, 0x7F, 0x09, 0x01, 0xF7];
+        assert_eq!(frame_sysex(&gm_on).unwrap(), gm_on);
+        assert_eq!(frame_sysex(&gm_on[1..5]).unwrap(), gm_on);
+        assert_eq!(frame_sysex(&gm_on[..5]).unwrap(), gm_on);
+
+        assert!(frame_sysex(&[]).is_err());
+        assert!(frame_sysex(&[0xF0, 0xF7]).is_err());
+        assert!(frame_sysex(&[0xF0, 0x41, 0x90, 0xF7]).is_err());
     }
 }
@@ -0,0 +1,148 @@
+//! Publish a plugin's MIDI output as a virtual MIDI source other applications can record from.
+//!
+//! Enabled by the `midi-output` feature. Wraps the [`midir`] crate the same way
+//! [`midi_input`](crate::midi_input) does, so callers never touch its types:
+//!
+//! - **macOS** — a CoreMIDI virtual source (midir's CoreMIDI backend). It shows up next to the
+//!   IAC Driver buses in every DAW's MIDI input list.
+//! - **Linux** — an ALSA sequencer port (or JACK, if midir was built for it).
+//! - **Windows** — WinMM has no virtual ports, so [`VirtualMidiOutput::create`] opens an existing
+//!   output port whose name contains the requested name — create it first with loopMIDI (or a
+//!   similar loopback driver).
+//!
+//! The usual way in is [`Vst3Host::enable_virtual_midi_out`](crate::Vst3Host::enable_virtual_midi_out)
+//! and then [`AudioHandle::set_virtual_midi_out`](crate::AudioHandle::set_virtual_midi_out), which
+//! forwards every event the plugin emits from `process()`.
+
+use midir::MidiOutput;
+
+use crate::{
+    error::{Error, Result},
+    midi::MidiEvent,
+};
+
+/// The client name `midir` advertises to the OS.
+const CLIENT_NAME: &str = "vst3-host";
+
+/// Map a `midir` error into the library's [`Error::MidiError`].
+fn midi_err(context: &str, err: impl std::fmt::Display) -> Error {
+    Error::MidiError(format!("{context}: {err}"))
+}
+
+/// A virtual MIDI source fed with plugin output events.
+///
+/// **Dropping it removes the port** (or closes the loopMIDI connection on Windows).
+pub struct VirtualMidiOutput {
+    name: String,
+    connection: midir::MidiOutputConnection,
+}
+
+impl VirtualMidiOutput {
+    /// Create (macOS/Linux) or open (Windows) the virtual port `name`.
+    ///
+    /// Fails with [`Error::MidiError`] when the name is empty, the platform MIDI subsystem is
+    /// unavailable, or — on Windows — no output port matching `name` exists.
+    pub fn create(name: &str) -> Result<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::MidiError(
+                "virtual MIDI port name must not be empty".to_string(),
+            ));
+        }
+        let output = MidiOutput::new(CLIENT_NAME).map_err(|e| midi_err("init MIDI output", e))?;
+        let connection = open_port(output, name)?;
+        Ok(Self {
+            name: name.to_string(),
+            connection,
+        })
+    }
+
+    /// The port name other applications see.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Translate `event` to raw MIDI bytes and send it out of the port.
+    ///
+    /// Non-blocking, but a system call on every backend midir supports, so not for the audio
+    /// thread: [`AudioHandle::set_virtual_midi_out`](crate::AudioHandle::set_virtual_midi_out)
+    /// sends from a thread of its own.
+    pub fn send_event(&mut self, event: &MidiEvent) -> Result<()> {
+        let (bytes, len) = event.to_midi_bytes();
+        self.connection
+            .send(&bytes[..len])
+            .map_err(|e| midi_err("send MIDI", e))
+    }
+}
+
+impl std::fmt::Debug for VirtualMidiOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VirtualMidiOutput")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(not(windows))]
+fn open_port(output: MidiOutput, name: &str) -> Result<midir::MidiOutputConnection> {
+    use midir::os::unix::VirtualOutput;
+
+    output
+        .create_virtual(name)
+        .map_err(|e| midi_err("create virtual MIDI output", e))
+}
+
+/// WinMM cannot create ports, so connect to the loopback port the user set up under `name`.
+#[cfg(windows)]
+fn open_port(output: MidiOutput, name: &str) -> Result<midir::MidiOutputConnection> {
+    let port = output
+        .ports()
+        .into_iter()
+        .find(|port| {
+            output
+                .port_name(port)
+                .is_ok_and(|port_name| port_name.contains(name))
+        })
+        .ok_or_else(|| {
+            Error::MidiError(format!(
+                "no MIDI output port named '{name}' (create it in loopMIDI first)"
+            ))
+        })?;
+    output
+        .connect(&port, name)
+        .map_err(|e| midi_err("connect MIDI output", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::midi::MidiChannel;
+
+    #[test]
+    fn empty_names_are_rejected() {
+        assert!(matches!(
+            VirtualMidiOutput::create("  "),
+            Err(Error::MidiError(_))
+        ));
+    }
+
+    #[test]
+    fn create_and_send_when_a_midi_subsystem_exists() {
+        // Headless CI has no ALSA sequencer, so midir can't initialize a backend at all.
+        let mut out = match VirtualMidiOutput::create("vst3-host test out") {
+            Ok(out) => out,
+            Err(Error::MidiError(msg)) => {
+                eprintln!("skipping: no virtual MIDI output available ({msg})");
+                return;
+            }
+            Err(e) => panic!("unexpected error: {e:?}"),
+        };
+        assert_eq!(out.name(), "vst3-host test out");
+        out.send_event(&MidiEvent::NoteOn {
+            channel: MidiChannel::Ch1,
+            note: 60,
+            velocity: 100,
+        })
+        .unwrap();
+    }
+}
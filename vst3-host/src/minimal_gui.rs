@@ -0,0 +1,243 @@
+//! A small always-on-top parameter panel, for plugins whose own editor is too large or
+//! doesn't open on this OS.
+//!
+//! The panel is an egui viewport rather than a window of its own: a process can only run one
+//! native event loop, so it opens from the host application's egui context. Open it with
+//! [`Plugin::open_minimal_gui`] (or [`Vst3Host::open_minimal_gui`](crate::Vst3Host::open_minimal_gui)
+//! when several plugins may have one open), then call [`MinimalGuiHandle::show`] every frame.
+//! It shows a search box, the eight most recently changed parameters with sliders and a
+//! "Show All" button listing the rest. Requires the `egui-widgets` feature.
+#![cfg(feature = "egui-widgets")]
+
+use crate::error::Result;
+use crate::parameters::Parameter;
+use crate::plugin::Plugin;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// How many recently changed parameters the panel lists.
+pub const RECENT_PARAMETERS: usize = 8;
+
+/// How often the panel re-reads parameter values (30 Hz).
+const REFRESH_INTERVAL: Duration = Duration::from_millis(33);
+
+/// Parameter ids in the order they last changed, most recent first, at most
+/// [`RECENT_PARAMETERS`] of them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecentParameters(VecDeque<u32>);
+
+impl RecentParameters {
+    /// Move `id` to the front, dropping the oldest entry if the list is full.
+    pub fn touch(&mut self, id: u32) {
+        self.0.retain(|&other| other != id);
+        self.0.push_front(id);
+        self.0.truncate(RECENT_PARAMETERS);
+    }
+
+    /// The ids, most recently changed first.
+    pub fn ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+/// An open minimal parameter panel. Call [`Self::show`] every frame to keep it open; dropping
+/// the handle closes it, as egui closes any viewport not shown in a frame.
+pub struct MinimalGuiHandle {
+    viewport: egui::ViewportId,
+    title: String,
+    search: String,
+    show_all: bool,
+    recent: RecentParameters,
+    parameters: Vec<Parameter>,
+    /// Display strings from the plugin, refreshed with the values.
+    display: HashMap<u32, String>,
+    refreshed: Option<Instant>,
+    open: bool,
+}
+
+impl MinimalGuiHandle {
+    /// A panel titled `title` in viewport `viewport`, seeded with `parameters`.
+    pub(crate) fn new(
+        viewport: egui::ViewportId,
+        title: String,
+        parameters: Vec<Parameter>,
+    ) -> Self {
+        // Nothing has changed yet: start with the first writable parameters.
+        let first: Vec<u32> = parameters
+            .iter()
+            .filter(|p| !p.is_read_only)
+            .take(RECENT_PARAMETERS)
+            .map(|p| p.id)
+            .collect();
+        let mut recent = RecentParameters::default();
+        for id in first.into_iter().rev() {
+            recent.touch(id);
+        }
+        Self {
+            viewport,
+            title,
+            search: String::new(),
+            show_all: false,
+            recent,
+            parameters,
+            display: HashMap::new(),
+            refreshed: None,
+            open: true,
+        }
+    }
+
+    /// Whether the user hasn't closed the panel.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// The recently changed parameters the panel lists.
+    pub fn recent(&self) -> &RecentParameters {
+        &self.recent
+    }
+
+    /// Draw the panel for `plugin` and return whether it's still open. Parameter values are
+    /// re-read at 30 Hz, and a parameter moves to the top of the list whenever its value
+    /// changes, from the panel or elsewhere. Without multi-viewport support (or in tests) egui
+    /// draws the panel as a window inside `ctx` instead.
+    pub fn show(&mut self, ctx: &egui::Context, plugin: &mut Plugin) -> bool {
+        if !self.open {
+            return false;
+        }
+        if self
+            .refreshed
+            .is_none_or(|at| at.elapsed() >= REFRESH_INTERVAL)
+        {
+            self.refresh(plugin);
+        }
+        ctx.request_repaint_after(REFRESH_INTERVAL);
+
+        let builder = egui::ViewportBuilder::default()
+            .with_title(&self.title)
+            .with_inner_size([320.0, 380.0])
+            .with_always_on_top();
+        ctx.show_viewport_immediate(self.viewport, builder, |ui, class| {
+            self.contents(ui, plugin);
+            if class != egui::ViewportClass::EmbeddedWindow
+                && ui.input(|i| i.viewport().close_requested())
+            {
+                self.open = false;
+            }
+        });
+        self.open
+    }
+
+    /// Re-read every parameter, moving the ones whose value changed to the top.
+    fn refresh(&mut self, plugin: &Plugin) {
+        self.refreshed = Some(Instant::now());
+        let Ok(parameters) = plugin.get_parameters() else {
+            return;
+        };
+        let previous: HashMap<u32, f64> = self.parameters.iter().map(|p| (p.id, p.value)).collect();
+        for p in &parameters {
+            if previous.get(&p.id).is_some_and(|&v| v != p.value) {
+                self.recent.touch(p.id);
+            }
+        }
+        self.display = parameters
+            .iter()
+            .filter_map(|p| Some((p.id, plugin.format_parameter(p.id, p.value).ok()?)))
+            .collect();
+        self.parameters = parameters;
+    }
+
+    fn contents(&mut self, ui: &mut egui::Ui, plugin: &mut Plugin) {
+        ui.horizontal(|ui| {
+            ui.label("\u{1F50D}");
+            ui.add(egui::TextEdit::singleline(&mut self.search).hint_text("Search parameters"));
+        });
+        ui.separator();
+
+        let needle = self.search.to_lowercase();
+        let ids: Vec<u32> = if !needle.is_empty() {
+            self.parameters
+                .iter()
+                .filter(|p| p.name.to_lowercase().contains(&needle))
+                .map(|p| p.id)
+                .collect()
+        } else if self.show_all {
+            self.parameters.iter().map(|p| p.id).collect()
+        } else {
+            self.recent.ids().collect()
+        };
+
+        if ids.is_empty() {
+            ui.weak("No parameters");
+        }
+        egui::ScrollArea::vertical()
+            .max_height(280.0)
+            .show(ui, |ui| {
+                for id in ids {
+                    self.parameter_row(ui, plugin, id);
+                }
+            });
+
+        ui.separator();
+        let label = if self.show_all {
+            "Show Recent"
+        } else {
+            "Show All"
+        };
+        ui.horizontal(|ui| {
+            if ui.button(label).clicked() {
+                self.show_all = !self.show_all;
+            }
+            if ui.button("Close").clicked() {
+                self.open = false;
+            }
+        });
+    }
+
+    fn parameter_row(&mut self, ui: &mut egui::Ui, plugin: &mut Plugin, id: u32) {
+        let Some(param) = self.parameters.iter_mut().find(|p| p.id == id) else {
+            return;
+        };
+        ui.label(&param.name);
+        ui.horizontal(|ui| {
+            let slider = egui::Slider::new(&mut param.value, 0.0..=1.0).show_value(false);
+            let changed = ui.add_enabled(!param.is_read_only, slider).changed();
+            ui.label(self.display.get(&id).map_or("", String::as_str));
+            if changed && plugin.set_parameter(id, param.value).is_ok() {
+                self.recent.touch(id);
+            }
+        });
+    }
+}
+
+impl Plugin {
+    /// Open a [`MinimalGuiHandle`]: a small always-on-top panel with a parameter search, the
+    /// most recently changed parameters and a full list, drawn with egui instead of the
+    /// plugin's own editor. Works for plugins without an editor too. Errors if the parameters
+    /// can't be read.
+    pub fn open_minimal_gui(&mut self) -> Result<MinimalGuiHandle> {
+        let viewport = egui::ViewportId::from_hash_of(("minimal_gui", &self.info.path));
+        self.minimal_gui(viewport)
+    }
+
+    pub(crate) fn minimal_gui(&self, viewport: egui::ViewportId) -> Result<MinimalGuiHandle> {
+        let parameters = self.get_parameters()?;
+        let title = format!("{} \u{2014} Quick Edit", self.info.name);
+        Ok(MinimalGuiHandle::new(viewport, title, parameters))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_parameters_keep_the_latest_eight_without_duplicates() {
+        let mut recent = RecentParameters::default();
+        for id in 0..10 {
+            recent.touch(id);
+        }
+        assert_eq!(recent.ids().collect::<Vec<_>>(), [9, 8, 7, 6, 5, 4, 3, 2]);
+        recent.touch(5);
+        assert_eq!(recent.ids().collect::<Vec<_>>(), [5, 9, 8, 7, 6, 4, 3, 2]);
+    }
+}
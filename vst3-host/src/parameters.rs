@@ -32,10 +32,17 @@ pub struct Parameter {
     pub is_bypass: bool,
     /// Parameter flags
     pub flags: u32,
+    /// Id of the unit (`IUnitInfo`) the parameter belongs to; `0` is the root unit. Match it
+    /// against [`crate::PluginUnit::id`] to group parameters by unit.
+    #[serde(default)]
+    pub unit_id: i32,
 }
 
 impl Parameter {
-    /// Convert normalized value (0.0-1.0) to plain value
+    /// Convert normalized value (0.0-1.0) to plain value within `min..=max`.
+    ///
+    /// This is a linear approximation; [`crate::Plugin::normalized_to_plain`] asks the plugin
+    /// for its real mapping (see also [`plain_value`](Self::plain_value)).
     pub fn normalized_to_plain(&self, normalized: f64) -> f64 {
         if self.step_count > 1 {
             // Discrete parameter
@@ -57,6 +64,22 @@ impl Parameter {
         }
     }
 
+    /// The current value in the plugin's plain units (e.g. Hz), converted by `plugin` with
+    /// [`crate::Plugin::normalized_to_plain`].
+    pub fn plain_value(&self, plugin: &crate::Plugin) -> Result<f64> {
+        plugin.normalized_to_plain(self.id, self.value)
+    }
+
+    /// Set the parameter from a plain value: converted with
+    /// [`crate::Plugin::plain_to_normalized`], applied to `plugin`, and mirrored into
+    /// [`value`](Self::value).
+    pub fn set_plain_value(&mut self, plugin: &mut crate::Plugin, plain: f64) -> Result<()> {
+        let normalized = plugin.plain_to_normalized(self.id, plain)?.clamp(0.0, 1.0);
+        plugin.set_parameter(self.id, normalized)?;
+        self.value = normalized;
+        Ok(())
+    }
+
     /// Approximate a human-readable value string from normalized space.
     ///
     /// This cannot know the plugin's internal mapping (VST3 keeps that private), so
@@ -95,6 +118,55 @@ impl Parameter {
     pub fn is_boolean(&self) -> bool {
         self.step_count == 2
     }
+
+    /// Whether the normalized value is within `tolerance` of `target`.
+    pub fn is_near(&self, target: f64, tolerance: f64) -> bool {
+        (self.value - target).abs() <= tolerance
+    }
+}
+
+/// The parameters in `params` whose normalized value is within `tolerance` of `target`, e.g.
+/// `0.5, 0.5` for all of them or `1.0, 0.01` for those near their maximum. See
+/// [`crate::Plugin::find_params_near_value`].
+pub fn params_near_value(params: &[Parameter], target: f64, tolerance: f64) -> Vec<&Parameter> {
+    params
+        .iter()
+        .filter(|p| p.is_near(target, tolerance))
+        .collect()
+}
+
+/// Values closer than this to a parameter's factory default count as at the default.
+pub const FACTORY_DEFAULT_TOLERANCE: f64 = 1e-5;
+
+/// A parameter whose value differs from its factory default; see
+/// [`crate::Plugin::verify_factory_defaults`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterMismatch {
+    /// Parameter ID
+    pub param_id: u32,
+    /// Parameter name
+    pub title: String,
+    /// The factory default (normalized)
+    pub expected_normalized: f64,
+    /// The current value (normalized)
+    pub actual_normalized: f64,
+    /// `|actual - expected|`
+    pub delta: f64,
+}
+
+impl ParameterMismatch {
+    /// The mismatch for `param`, or `None` if it is within [`FACTORY_DEFAULT_TOLERANCE`] of its
+    /// default.
+    pub fn of(param: &Parameter) -> Option<Self> {
+        let delta = (param.value - param.default).abs();
+        (delta > FACTORY_DEFAULT_TOLERANCE).then(|| Self {
+            param_id: param.id,
+            title: param.name.clone(),
+            expected_normalized: param.default,
+            actual_normalized: param.value,
+            delta,
+        })
+    }
 }
 
 /// Parameter change event
@@ -106,6 +178,8 @@ pub struct ParameterChange {
     pub value: f64,
     /// Sample offset within the current block
     pub sample_offset: i32,
+    /// When the change was made (for a plugin editor's edits, when its `performEdit` arrived)
+    pub timestamp: std::time::Instant,
 }
 
 /// Batch parameter update
@@ -161,6 +235,17 @@ pub struct AutomationPoint {
     pub curve: AutomationCurve,
 }
 
+/// One recorded parameter move, from [`ParameterAutomation::record`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AutomationEvent {
+    /// Parameter ID
+    pub param_id: u32,
+    /// When the move was seen, in samples since the recording started
+    pub sample_offset: i64,
+    /// New normalized value (0.0 to 1.0)
+    pub value: f64,
+}
+
 /// Parameter automation data
 #[derive(Debug, Clone)]
 pub struct ParameterAutomation {
@@ -168,6 +253,8 @@ pub struct ParameterAutomation {
     pub points: Vec<AutomationPoint>,
     /// Whether to loop the automation
     pub looping: bool,
+    /// Parameter moves captured by [`Self::record`], in time order
+    pub recorded: Vec<AutomationEvent>,
 }
 
 impl ParameterAutomation {
@@ -176,9 +263,93 @@ impl ParameterAutomation {
         Self {
             points: Vec::new(),
             looping: false,
+            recorded: Vec::new(),
+        }
+    }
+
+    /// Record every parameter move on `plugin` for `duration`. The values are polled once
+    /// per block (`block_size / sample_rate` seconds) and each change is stamped with the
+    /// block's start in samples since recording began, so edits are captured whether they come
+    /// from the plugin's editor or the host, in or out of process. Blocks the calling thread
+    /// for `duration`. Values at the start are the baseline and are not recorded.
+    ///
+    /// # Errors
+    ///
+    /// If the plugin's sample rate or block size is zero, or its parameters can't be read.
+    pub fn record(plugin: &mut crate::Plugin, duration: std::time::Duration) -> Result<Self> {
+        let block_size = plugin.block_size();
+        let block =
+            std::time::Duration::try_from_secs_f64(block_size as f64 / plugin.sample_rate())
+                .ok()
+                .filter(|block| !block.is_zero())
+                .ok_or_else(|| {
+                    crate::Error::Other("recording needs a sample rate and block size".to_string())
+                })?;
+        let mut last: std::collections::HashMap<u32, f64> = plugin
+            .get_parameters()?
+            .into_iter()
+            .map(|p| (p.id, p.value))
+            .collect();
+
+        let mut automation = Self::new();
+        let started = std::time::Instant::now();
+        for blocks in 1u32.. {
+            let due = block * blocks;
+            if due > duration {
+                break;
+            }
+            // Sleep to the block's deadline rather than for a block, so polling doesn't drift.
+            if let Some(wait) = due.checked_sub(started.elapsed()) {
+                std::thread::sleep(wait);
+            }
+            let sample_offset = i64::from(blocks) * block_size as i64;
+            automation.record_changes(&mut last, &plugin.get_parameters()?, sample_offset);
+        }
+        Ok(automation)
+    }
+
+    /// Append an event for each of `params` whose value differs from `last`, then update
+    /// `last`.
+    fn record_changes(
+        &mut self,
+        last: &mut std::collections::HashMap<u32, f64>,
+        params: &[Parameter],
+        sample_offset: i64,
+    ) {
+        for param in params {
+            if last.insert(param.id, param.value) != Some(param.value) {
+                self.recorded.push(AutomationEvent {
+                    param_id: param.id,
+                    sample_offset,
+                    value: param.value,
+                });
+            }
         }
     }
 
+    /// The recorded moves falling in the `frames`-long block that starts `block_start` samples
+    /// into the recording, as `(param_id, sample_offset_in_block, value)`.
+    pub fn recorded_for_block(&self, block_start: i64, frames: usize) -> Vec<(u32, i32, f64)> {
+        let block = block_start..block_start + frames as i64;
+        self.recorded
+            .iter()
+            .filter(|e| block.contains(&e.sample_offset))
+            .map(|e| (e.param_id, (e.sample_offset - block_start) as i32, e.value))
+            .collect()
+    }
+
+    /// Queue the recorded moves for the plugin's next block, which plays the recording from
+    /// `block_start` samples in, at their sample offsets ([`crate::Plugin::set_parameter_at`]).
+    /// Call once per block with `block_start` advancing by [`crate::Plugin::block_size`].
+    /// Returns how many moves were queued.
+    pub fn play_back(&self, plugin: &mut crate::Plugin, block_start: i64) -> Result<usize> {
+        let events = self.recorded_for_block(block_start, plugin.block_size());
+        for &(id, sample_offset, value) in &events {
+            plugin.set_parameter_at(id, value, sample_offset)?;
+        }
+        Ok(events.len())
+    }
+
     /// Add an automation point
     pub fn add_point(mut self, time: f64, value: f64) -> Self {
         self.points.push(AutomationPoint {
@@ -296,10 +467,817 @@ impl Default for ParameterAutomation {
     }
 }
 
+/// A musical note length for tempo-synced rate parameters (LFO rates, synced delays).
+///
+/// Dotted values last 1.5x the plain value; triplets last 2/3 of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NoteValue {
+    /// 1/1
+    Whole,
+    /// 1/1 dotted
+    WholeDotted,
+    /// 1/1 triplet
+    WholeTriplet,
+    /// 1/2
+    Half,
+    /// 1/2 dotted
+    HalfDotted,
+    /// 1/2 triplet
+    HalfTriplet,
+    /// 1/4
+    Quarter,
+    /// 1/4 dotted
+    QuarterDotted,
+    /// 1/4 triplet
+    QuarterTriplet,
+    /// 1/8
+    Eighth,
+    /// 1/8 dotted
+    EighthDotted,
+    /// 1/8 triplet
+    EighthTriplet,
+    /// 1/16
+    Sixteenth,
+    /// 1/16 dotted
+    SixteenthDotted,
+    /// 1/16 triplet
+    SixteenthTriplet,
+}
+
+impl NoteValue {
+    /// Every note value, longest first (handy for populating a picker).
+    pub const ALL: [NoteValue; 15] = [
+        NoteValue::Whole,
+        NoteValue::WholeDotted,
+        NoteValue::WholeTriplet,
+        NoteValue::Half,
+        NoteValue::HalfDotted,
+        NoteValue::HalfTriplet,
+        NoteValue::Quarter,
+        NoteValue::QuarterDotted,
+        NoteValue::QuarterTriplet,
+        NoteValue::Eighth,
+        NoteValue::EighthDotted,
+        NoteValue::EighthTriplet,
+        NoteValue::Sixteenth,
+        NoteValue::SixteenthDotted,
+        NoteValue::SixteenthTriplet,
+    ];
+
+    /// Length of the note in quarter-note beats (e.g. `Quarter` = 1.0, `EighthTriplet` = 1/3).
+    pub fn beats(self) -> f64 {
+        use NoteValue::*;
+        let (plain, modifier) = match self {
+            Whole => (4.0, 1.0),
+            WholeDotted => (4.0, 1.5),
+            WholeTriplet => (4.0, 2.0 / 3.0),
+            Half => (2.0, 1.0),
+            HalfDotted => (2.0, 1.5),
+            HalfTriplet => (2.0, 2.0 / 3.0),
+            Quarter => (1.0, 1.0),
+            QuarterDotted => (1.0, 1.5),
+            QuarterTriplet => (1.0, 2.0 / 3.0),
+            Eighth => (0.5, 1.0),
+            EighthDotted => (0.5, 1.5),
+            EighthTriplet => (0.5, 2.0 / 3.0),
+            Sixteenth => (0.25, 1.0),
+            SixteenthDotted => (0.25, 1.5),
+            SixteenthTriplet => (0.25, 2.0 / 3.0),
+        };
+        plain * modifier
+    }
+
+    /// Short display label in the usual DAW notation (`"1/4"`, `"1/8."`, `"1/16T"`).
+    pub fn label(self) -> &'static str {
+        use NoteValue::*;
+        match self {
+            Whole => "1/1",
+            WholeDotted => "1/1.",
+            WholeTriplet => "1/1T",
+            Half => "1/2",
+            HalfDotted => "1/2.",
+            HalfTriplet => "1/2T",
+            Quarter => "1/4",
+            QuarterDotted => "1/4.",
+            QuarterTriplet => "1/4T",
+            Eighth => "1/8",
+            EighthDotted => "1/8.",
+            EighthTriplet => "1/8T",
+            Sixteenth => "1/16",
+            SixteenthDotted => "1/16.",
+            SixteenthTriplet => "1/16T",
+        }
+    }
+}
+
+/// Tempo-synced rate mapping: converts note values to Hz at a given tempo, and Hz to a
+/// plugin's normalized parameter value.
+///
+/// VST3 keeps a parameter's plain range private, so the caller supplies the Hz range the
+/// plugin maps `0.0..=1.0` onto (assumed linear, as in [`Parameter::plain_to_normalized`]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TempoSync {
+    /// Tempo in beats (quarter notes) per minute
+    pub bpm: f64,
+}
+
+impl TempoSync {
+    /// Create a mapping at `bpm`
+    pub fn new(bpm: f64) -> Self {
+        Self { bpm }
+    }
+
+    /// Frequency in Hz of one cycle per `nv` at `bpm` (e.g. a quarter note at 120 BPM = 2 Hz).
+    /// Returns 0.0 for a non-finite or non-positive tempo.
+    pub fn note_value_to_hz(nv: NoteValue, bpm: f64) -> f64 {
+        if !bpm.is_finite() || bpm <= 0.0 {
+            return 0.0;
+        }
+        bpm / 60.0 / nv.beats()
+    }
+
+    /// Normalized parameter value (clamped to `0.0..=1.0`) for `nv` at `bpm`, for a plugin
+    /// that maps its normalized range linearly onto `min_hz..=max_hz`.
+    pub fn note_value_to_normalized(nv: NoteValue, bpm: f64, min_hz: f64, max_hz: f64) -> f64 {
+        if (max_hz - min_hz).abs() < f64::EPSILON {
+            return 0.0;
+        }
+        let hz = Self::note_value_to_hz(nv, bpm);
+        ((hz - min_hz) / (max_hz - min_hz)).clamp(0.0, 1.0)
+    }
+
+    /// [`TempoSync::note_value_to_hz`] at this mapping's tempo
+    pub fn hz(&self, nv: NoteValue) -> f64 {
+        Self::note_value_to_hz(nv, self.bpm)
+    }
+
+    /// [`TempoSync::note_value_to_normalized`] at this mapping's tempo
+    pub fn normalized(&self, nv: NoteValue, min_hz: f64, max_hz: f64) -> f64 {
+        Self::note_value_to_normalized(nv, self.bpm, min_hz, max_hz)
+    }
+}
+
+/// Parameters whose A and B values differ by no more than this are left alone by [`morph`].
+pub const MORPH_EPSILON: f64 = 1e-4;
+
+/// Every parameter's value at one moment, with the metadata [`morph`] needs (read-only flag,
+/// step count). Take one with [`crate::Plugin::snapshot_parameters`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParameterSnapshot {
+    /// The captured parameters
+    pub parameters: Vec<Parameter>,
+}
+
+impl ParameterSnapshot {
+    /// Wrap a parameter list (e.g. from [`crate::Plugin::get_parameters`])
+    pub fn new(parameters: Vec<Parameter>) -> Self {
+        Self { parameters }
+    }
+
+    /// The captured normalized value of parameter `id`
+    pub fn value(&self, id: u32) -> Option<f64> {
+        self.parameters.iter().find(|p| p.id == id).map(|p| p.value)
+    }
+}
+
+/// Interpolate from snapshot `a` (`t = 0.0`) to snapshot `b` (`t = 1.0`).
+///
+/// Only parameters present in both snapshots, writable, and differing by more than
+/// [`MORPH_EPSILON`] produce a change. Stepped parameters snap to the nearest step so a
+/// morph never sends a value between two of a switch's positions.
+pub fn morph(a: &ParameterSnapshot, b: &ParameterSnapshot, t: f64) -> Vec<ParameterChange> {
+    let t = if t.is_finite() {
+        t.clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let now = std::time::Instant::now();
+    a.parameters
+        .iter()
+        .filter(|from| !from.is_read_only)
+        .filter_map(|from| {
+            let to = b.value(from.id)?;
+            if (to - from.value).abs() <= MORPH_EPSILON {
+                return None;
+            }
+            let mut value = from.value + (to - from.value) * t;
+            if from.step_count > 0 {
+                let steps = from.step_count as f64;
+                value = (value * steps).round() / steps;
+            }
+            Some(ParameterChange {
+                id: from.id,
+                value: value.clamp(0.0, 1.0),
+                sample_offset: 0,
+                timestamp: now,
+            })
+        })
+        .collect()
+}
+
+/// How [`randomize`] draws a parameter's new value (normalized, so every range is 0.0..=1.0).
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Distribution {
+    /// Anywhere in the range, every value equally likely
+    #[default]
+    Uniform,
+    /// Around the current value, with standard deviation `sigma` as a fraction of the range:
+    /// `sigma: 0.1` mostly moves a parameter by up to ±10%
+    Gaussian {
+        /// Standard deviation (0.0..=1.0 of the full range)
+        sigma: f64,
+    },
+    /// Near one end of the range or the other, like flipping a switch: the top end with
+    /// probability `weight`, else the bottom, each spread by [`BIMODAL_SPREAD`]
+    Bimodal {
+        /// Chance (0.0..=1.0) of landing near the top of the range
+        weight: f64,
+    },
+}
+
+/// Standard deviation, from the end of the range, of the two peaks of
+/// [`Distribution::Bimodal`].
+pub const BIMODAL_SPREAD: f64 = 0.15;
+
+impl Distribution {
+    /// Draw a new value for a parameter currently at `current`, in 0.0..=1.0.
+    pub fn sample(&self, rng: &mut impl rand::Rng, current: f64) -> f64 {
+        let value = match *self {
+            Distribution::Uniform => rng.gen::<f64>(),
+            Distribution::Gaussian { sigma } => current + sigma * standard_normal(rng),
+            Distribution::Bimodal { weight } => {
+                let offset = (BIMODAL_SPREAD * standard_normal(rng)).abs();
+                if rng.gen::<f64>() < weight {
+                    1.0 - offset
+                } else {
+                    offset
+                }
+            }
+        };
+        if value.is_finite() {
+            value.clamp(0.0, 1.0)
+        } else {
+            current.clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// A draw from the standard normal distribution (Box-Muller).
+fn standard_normal(rng: &mut impl rand::Rng) -> f64 {
+    // 1 - [0, 1) is never 0, so the logarithm is finite.
+    let u1 = 1.0 - rng.gen::<f64>();
+    let u2 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
+
+/// What [`randomize`] may change and how.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct RandomizeConstraints {
+    /// How new values are drawn
+    pub distribution: Distribution,
+    /// Parameters to leave alone (e.g. master volume)
+    pub exclude: Vec<u32>,
+}
+
+/// What [`randomize`] did: the distribution it drew from, so the result can be reproduced with
+/// the same seed, and the values it set.
+#[derive(Debug, Clone)]
+pub struct RandomizeResult {
+    /// The distribution the values were drawn from
+    pub distribution: Distribution,
+    /// The new value of each parameter changed
+    pub changes: Vec<ParameterChange>,
+}
+
+/// Draw new values for `params` from `constraints.distribution`. Read-only, bypass and
+/// excluded parameters are left alone, and stepped parameters land on a step.
+pub fn randomize(
+    params: &[Parameter],
+    constraints: &RandomizeConstraints,
+    rng: &mut impl rand::Rng,
+) -> RandomizeResult {
+    let now = std::time::Instant::now();
+    let changes = params
+        .iter()
+        .filter(|p| !p.is_read_only && !p.is_bypass && !constraints.exclude.contains(&p.id))
+        .map(|p| {
+            let mut value = constraints.distribution.sample(rng, p.value);
+            if p.step_count > 0 {
+                let steps = p.step_count as f64;
+                value = (value * steps).round() / steps;
+            }
+            ParameterChange {
+                id: p.id,
+                value,
+                sample_offset: 0,
+                timestamp: now,
+            }
+        })
+        .collect();
+    RandomizeResult {
+        distribution: constraints.distribution,
+        changes,
+    }
+}
+
+/// Default [`ChangeDebouncer::delay_ms`]: about one frame at 60 Hz.
+pub const DEFAULT_DEBOUNCE_MS: f64 = 16.0;
+
+/// Holds back rapid edits of a parameter, such as a slider dragged across its range, so the
+/// plugin gets the last value once the edits pause instead of every one of them. Push edits
+/// with [`Self::push`] and call [`Self::tick`] every UI frame for the ones to apply.
+#[derive(Debug, Clone)]
+pub struct ChangeDebouncer {
+    /// How long a parameter must go without a new value before it's applied, in milliseconds
+    pub delay_ms: f64,
+    /// Last value and when it came, per parameter id.
+    pending: std::collections::HashMap<u32, (f64, std::time::Instant)>,
+}
+
+impl Default for ChangeDebouncer {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEBOUNCE_MS)
+    }
+}
+
+impl ChangeDebouncer {
+    /// A debouncer applying edits after `delay_ms` without a new value.
+    pub fn new(delay_ms: f64) -> Self {
+        Self {
+            delay_ms,
+            pending: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Hold `value` for parameter `id`, replacing any value still held for it.
+    pub fn push(&mut self, id: u32, value: f64) {
+        self.push_at(id, value, std::time::Instant::now());
+    }
+
+    /// [`Self::push`] at a given time, for tests and callers with their own clock.
+    pub fn push_at(&mut self, id: u32, value: f64, now: std::time::Instant) {
+        self.pending.insert(id, (value, now));
+    }
+
+    /// The held values that have gone [`Self::delay_ms`] without a new one, by parameter id;
+    /// they're no longer held.
+    pub fn tick(&mut self) -> Vec<(u32, f64)> {
+        self.tick_at(std::time::Instant::now())
+    }
+
+    /// [`Self::tick`] at a given time.
+    pub fn tick_at(&mut self, now: std::time::Instant) -> Vec<(u32, f64)> {
+        let delay = std::time::Duration::from_secs_f64(self.delay_ms.max(0.0) / 1000.0);
+        let mut due: Vec<(u32, f64)> = self
+            .pending
+            .iter()
+            .filter(|(_, (_, at))| now.saturating_duration_since(*at) >= delay)
+            .map(|(&id, &(value, _))| (id, value))
+            .collect();
+        due.sort_by_key(|&(id, _)| id);
+        for (id, _) in &due {
+            self.pending.remove(id);
+        }
+        due
+    }
+
+    /// Every held value, due or not, by parameter id; nothing is held afterwards.
+    pub fn flush(&mut self) -> Vec<(u32, f64)> {
+        let mut all: Vec<(u32, f64)> = self
+            .pending
+            .drain()
+            .map(|(id, (value, _))| (id, value))
+            .collect();
+        all.sort_by_key(|&(id, _)| id);
+        all
+    }
+
+    /// Drop the value held for `id`, e.g. because something else just set the parameter.
+    pub fn cancel(&mut self, id: u32) {
+        self.pending.remove(&id);
+    }
+
+    /// Whether no values are held.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Default [`UndoStack`] depth: how many steps it keeps before dropping the oldest.
+pub const DEFAULT_UNDO_DEPTH: usize = 100;
+
+/// One step of an [`UndoStack`]: the parameters one edit changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndoStep {
+    /// Step number, counting every step recorded since the stack was created; numbers stay
+    /// with their steps when older ones are dropped
+    pub index: usize,
+    /// When the step was recorded
+    pub timestamp: std::time::Instant,
+    /// The parameters the step changed and their new normalized values, as `(id, value)`
+    pub changes: Vec<(u32, f64)>,
+    /// The same parameters' values before the step
+    previous: Vec<(u32, f64)>,
+}
+
+/// Parameter edit history with undo, redo and jumps to any recorded step.
+///
+/// The stack only keeps values: [`Self::undo`], [`Self::redo`] and [`Self::time_travel`]
+/// return the `(id, value)` pairs to apply and leave applying them to the caller. Recording
+/// a step after undoing discards the steps that were undone. The stack holds at most
+/// [`Self::depth`] steps, and [`Self::prune_older_than`] drops old ones to bound memory.
+#[derive(Debug, Clone)]
+pub struct UndoStack {
+    /// Most steps kept; recording more drops the oldest
+    pub depth: usize,
+    steps: Vec<UndoStep>,
+    /// How many of `steps` are applied; the rest can be redone
+    position: usize,
+    next_index: usize,
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self::new(DEFAULT_UNDO_DEPTH)
+    }
+}
+
+impl UndoStack {
+    /// An empty stack keeping up to `depth` steps.
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            steps: Vec::new(),
+            position: 0,
+            next_index: 0,
+        }
+    }
+
+    /// Record an edit as `(id, before, after)` values. Parameters whose value didn't change
+    /// are left out, and an edit that changed nothing records no step.
+    pub fn record(&mut self, edits: impl IntoIterator<Item = (u32, f64, f64)>) {
+        self.record_at(edits, std::time::Instant::now());
+    }
+
+    /// [`Self::record`] at a given time, for tests and callers with their own clock.
+    pub fn record_at(
+        &mut self,
+        edits: impl IntoIterator<Item = (u32, f64, f64)>,
+        now: std::time::Instant,
+    ) {
+        let (previous, changes): (Vec<_>, Vec<_>) = edits
+            .into_iter()
+            .filter(|(_, before, after)| before != after)
+            .map(|(id, before, after)| ((id, before), (id, after)))
+            .unzip();
+        if changes.is_empty() {
+            return;
+        }
+        self.steps.truncate(self.position);
+        self.steps.push(UndoStep {
+            index: self.next_index,
+            timestamp: now,
+            changes,
+            previous,
+        });
+        self.next_index += 1;
+        let excess = self.steps.len().saturating_sub(self.depth.max(1));
+        self.steps.drain(..excess);
+        self.position = self.steps.len();
+    }
+
+    /// Every kept step, oldest first.
+    pub fn history(&self) -> &[UndoStep] {
+        &self.steps
+    }
+
+    /// How many steps of [`Self::history`] are applied: the current position on the
+    /// timeline, from 0 (before the first step) to its length (after the last).
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Whether a step can be undone.
+    pub fn can_undo(&self) -> bool {
+        self.position > 0
+    }
+
+    /// Whether an undone step can be redone.
+    pub fn can_redo(&self) -> bool {
+        self.position < self.steps.len()
+    }
+
+    /// Undo the last applied step, returning the values to restore, or `None` if there's
+    /// nothing to undo.
+    pub fn undo(&mut self) -> Option<Vec<(u32, f64)>> {
+        self.can_undo().then(|| self.time_travel(self.position - 1))
+    }
+
+    /// Redo the next undone step, returning the values to apply, or `None` if there's
+    /// nothing to redo.
+    pub fn redo(&mut self) -> Option<Vec<(u32, f64)>> {
+        self.can_redo().then(|| self.time_travel(self.position + 1))
+    }
+
+    /// Move to `position` on the timeline (see [`Self::position`]; clamped to the history),
+    /// returning the values that bring the parameters there: each parameter touched on the
+    /// way, at its value at the destination. Moving back undoes steps, moving forward redoes
+    /// them.
+    pub fn time_travel(&mut self, position: usize) -> Vec<(u32, f64)> {
+        let position = position.min(self.steps.len());
+        let mut values: Vec<(u32, f64)> = Vec::new();
+        let mut set = |id: u32, value: f64| match values.iter_mut().find(|(i, _)| *i == id) {
+            Some(entry) => entry.1 = value,
+            None => values.push((id, value)),
+        };
+        if position < self.position {
+            for step in self.steps[position..self.position].iter().rev() {
+                step.previous.iter().for_each(|&(id, value)| set(id, value));
+            }
+        } else {
+            for step in &self.steps[self.position..position] {
+                step.changes.iter().for_each(|&(id, value)| set(id, value));
+            }
+        }
+        self.position = position;
+        values
+    }
+
+    /// Drop steps recorded more than `age` ago. Dropped steps can no longer be undone;
+    /// the parameters keep their values.
+    pub fn prune_older_than(&mut self, age: std::time::Duration) {
+        self.prune_older_than_at(age, std::time::Instant::now());
+    }
+
+    /// [`Self::prune_older_than`] as of `now`.
+    pub fn prune_older_than_at(&mut self, age: std::time::Duration, now: std::time::Instant) {
+        let old = self
+            .steps
+            .iter()
+            .take_while(|step| now.saturating_duration_since(step.timestamp) > age)
+            .count();
+        self.steps.drain(..old);
+        self.position = self.position.saturating_sub(old);
+    }
+
+    /// Forget every step.
+    pub fn clear(&mut self) {
+        self.steps.clear();
+        self.position = 0;
+    }
+}
+
+/// Most steps a parameter may have for [`infer_widget_type`] to offer it as a combo box.
+pub const MAX_COMBO_BOX_STEPS: i32 = 32;
+
+/// The control a parameter is best edited with; see [`infer_widget_type`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WidgetType {
+    /// A read-only value: show it, don't offer to edit it
+    Label,
+    /// A switch between two states (one step)
+    ToggleButton,
+    /// A list of the step labels ([`crate::Plugin::step_labels`])
+    ComboBox,
+    /// A slider whose value reads as a percentage
+    Percentage,
+    /// A slider on a logarithmic curve, for levels in dB
+    LogSlider,
+    /// A plain slider
+    #[default]
+    Slider,
+}
+
+/// Pick the widget for `param`, whose current value the plugin displays as `display`
+/// ([`crate::Plugin::format_parameter`]). In order: read-only parameters are a
+/// [`WidgetType::Label`], one step is a [`WidgetType::ToggleButton`], 2 to
+/// [`MAX_COMBO_BOX_STEPS`] steps a [`WidgetType::ComboBox`], a `"dB"` unit a
+/// [`WidgetType::LogSlider`] and a display with a `%` a [`WidgetType::Percentage`];
+/// anything else is a [`WidgetType::Slider`].
+pub fn infer_widget_type(param: &Parameter, display: &str) -> WidgetType {
+    if param.is_read_only {
+        WidgetType::Label
+    } else if param.step_count == 1 {
+        WidgetType::ToggleButton
+    } else if (2..=MAX_COMBO_BOX_STEPS).contains(&param.step_count) {
+        WidgetType::ComboBox
+    } else if param.unit.trim() == "dB" {
+        WidgetType::LogSlider
+    } else if display.contains('%') || param.unit.contains('%') {
+        WidgetType::Percentage
+    } else {
+        WidgetType::Slider
+    }
+}
+
+/// How far the display value at 0.5 may sit from the endpoints' geometric mean, relative to
+/// it, for [`SliderCurve::detect`] to call a parameter logarithmic.
+const LOG_DETECT_TOLERANCE: f64 = 0.1;
+
+/// How a slider's position maps onto a parameter's normalized value, so e.g. a frequency
+/// control can spend more of its travel on the low end.
+pub enum SliderCurve {
+    /// Position and value are the same
+    Linear,
+    /// `(base^t - 1) / (base - 1)`: the lower values get most of the travel (`base > 1`)
+    Logarithmic {
+        /// Steepness of the curve; values at or near 1 behave like [`Self::Linear`]
+        base: f64,
+    },
+    /// `t²`: a gentler version of [`Self::Logarithmic`]
+    Sqrt,
+    /// Any increasing function mapping `0.0..=1.0` onto `0.0..=1.0`
+    Custom(Box<dyn Fn(f64) -> f64 + Send + Sync>),
+}
+
+impl std::fmt::Debug for SliderCurve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Linear => f.write_str("Linear"),
+            Self::Logarithmic { base } => {
+                f.debug_struct("Logarithmic").field("base", base).finish()
+            }
+            Self::Sqrt => f.write_str("Sqrt"),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl SliderCurve {
+    /// The normalized value for slider position `t` (both `0.0..=1.0`).
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        let value = match self {
+            Self::Linear => t,
+            Self::Logarithmic { base } if (base - 1.0).abs() < 1e-9 || *base <= 0.0 => t,
+            Self::Logarithmic { base } => (base.powf(t) - 1.0) / (base - 1.0),
+            Self::Sqrt => t * t,
+            Self::Custom(f) => f(t),
+        };
+        value.clamp(0.0, 1.0)
+    }
+
+    /// The slider position showing normalized `value`: the inverse of [`Self::apply`]. A
+    /// [`Self::Custom`] curve is inverted numerically, so it must be increasing.
+    pub fn inverse(&self, value: f64) -> f64 {
+        let value = value.clamp(0.0, 1.0);
+        let t = match self {
+            Self::Linear => value,
+            Self::Logarithmic { base } if (base - 1.0).abs() < 1e-9 || *base <= 0.0 => value,
+            Self::Logarithmic { base } => (1.0 + value * (base - 1.0)).ln() / base.ln(),
+            Self::Sqrt => value.sqrt(),
+            Self::Custom(_) => {
+                let (mut low, mut high) = (0.0, 1.0);
+                for _ in 0..48 {
+                    let mid = 0.5 * (low + high);
+                    if self.apply(mid) < value {
+                        low = mid;
+                    } else {
+                        high = mid;
+                    }
+                }
+                0.5 * (low + high)
+            }
+        };
+        t.clamp(0.0, 1.0)
+    }
+
+    /// Pick a curve from how the plugin displays the parameter at normalized 0.0, 0.5 and
+    /// 1.0: [`Self::Logarithmic`] when all three read as positive numbers and the middle one
+    /// is (within 10%) the geometric mean of the ends, otherwise [`Self::Linear`].
+    pub fn detect(at_min: &str, at_mid: &str, at_max: &str) -> Self {
+        let (Some(min), Some(mid), Some(max)) = (
+            display_number(at_min),
+            display_number(at_mid),
+            display_number(at_max),
+        ) else {
+            return Self::Linear;
+        };
+        let (low, high) = (min.min(max), min.max(max));
+        // A narrow range can't tell a geometric mean from an arithmetic one.
+        if low <= 0.0 || high / low < 4.0 {
+            return Self::Linear;
+        }
+        let geometric = (low * high).sqrt();
+        if (mid - geometric).abs() <= geometric * LOG_DETECT_TOLERANCE {
+            Self::Logarithmic { base: high / low }
+        } else {
+            Self::Linear
+        }
+    }
+}
+
+/// The number a display string starts with, scaled by a metric prefix on its unit
+/// (`"1.2 kHz"` is 1200, `"500 ms"` is 0.5). `None` if it doesn't start with a number.
+fn display_number(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let end = text
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && (c == '-' || c == '+'))))
+        .map_or(text.len(), |(i, _)| i);
+    let number: f64 = text[..end].parse().ok()?;
+    let unit = text[end..].trim();
+    // Only a prefix if a unit follows it: "m" alone is metres, "ms" is milliseconds.
+    let scale = match unit.chars().next() {
+        Some(_) if unit.chars().count() < 2 => 1.0,
+        Some('k') => 1e3,
+        Some('M') => 1e6,
+        Some('m') => 1e-3,
+        Some('µ' | 'u') => 1e-6,
+        _ => 1.0,
+    };
+    Some(number * scale)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn slider_curves_invert_their_mapping() {
+        let curves = [
+            SliderCurve::Linear,
+            SliderCurve::Logarithmic { base: 1000.0 },
+            SliderCurve::Sqrt,
+            SliderCurve::Custom(Box::new(|t| t * t * t)),
+        ];
+        for curve in &curves {
+            assert_eq!(curve.apply(0.0), 0.0, "{curve:?}");
+            assert!((curve.apply(1.0) - 1.0).abs() < 1e-12, "{curve:?}");
+            for t in [0.1, 0.25, 0.5, 0.9] {
+                assert!(
+                    (curve.inverse(curve.apply(t)) - t).abs() < 1e-9,
+                    "{curve:?} at {t}"
+                );
+            }
+        }
+        // The low end gets most of the travel.
+        assert!(SliderCurve::Logarithmic { base: 1000.0 }.apply(0.5) < 0.05);
+        assert_eq!(SliderCurve::Sqrt.apply(0.5), 0.25);
+        // A base of 1 is a straight line rather than a division by zero.
+        assert_eq!(SliderCurve::Logarithmic { base: 1.0 }.apply(0.3), 0.3);
+    }
+
+    #[test]
+    fn logarithmic_displays_are_detected() {
+        assert!(matches!(
+            SliderCurve::detect("20.0 Hz", "632 Hz", "20.0 kHz"),
+            SliderCurve::Logarithmic { base } if (base - 1000.0).abs() < 1e-9
+        ));
+        // Halfway is the arithmetic mean: linear.
+        assert!(matches!(
+            SliderCurve::detect("20 Hz", "10010 Hz", "20000 Hz"),
+            SliderCurve::Linear
+        ));
+        assert!(matches!(
+            SliderCurve::detect("-60 dB", "-30 dB", "0 dB"),
+            SliderCurve::Linear
+        ));
+        assert!(matches!(
+            SliderCurve::detect("Sine", "Saw", "Square"),
+            SliderCurve::Linear
+        ));
+        assert_eq!(display_number("500 ms"), Some(0.5));
+        assert_eq!(display_number("1.2 kHz"), Some(1200.0));
+        assert_eq!(display_number("3 m"), Some(3.0));
+    }
+
+    #[test]
+    fn parameters_saved_without_a_unit_load_into_the_root_unit() {
+        let json = r#"{"id":1,"name":"Gain","value":0.5,"min":0.0,"max":1.0,"default":0.5,
+            "unit":"dB","step_count":0,"can_automate":true,"is_read_only":false,
+            "is_bypass":false,"flags":1}"#;
+        let param: Parameter = serde_json::from_str(json).unwrap();
+        assert_eq!(param.unit_id, 0);
+    }
+
+    #[test]
+    fn mismatch_needs_more_than_the_tolerance() {
+        let param = |value: f64| Parameter {
+            id: 3,
+            name: "Cutoff".to_string(),
+            value,
+            min: 0.0,
+            max: 1.0,
+            default: 0.5,
+            unit: String::new(),
+            step_count: 0,
+            can_automate: true,
+            is_read_only: false,
+            is_bypass: false,
+            flags: 0,
+            unit_id: 0,
+        };
+        assert_eq!(ParameterMismatch::of(&param(0.5)), None);
+        assert_eq!(ParameterMismatch::of(&param(0.500_005)), None);
+        let mismatch = ParameterMismatch::of(&param(0.75)).unwrap();
+        assert_eq!(mismatch.param_id, 3);
+        assert_eq!(mismatch.title, "Cutoff");
+        assert_eq!(mismatch.expected_normalized, 0.5);
+        assert_eq!(mismatch.actual_normalized, 0.75);
+        assert_eq!(mismatch.delta, 0.25);
+    }
+
     #[test]
     fn add_point_with_nan_time_does_not_panic() {
         // A NaN time is ordered deterministically by `total_cmp` in the sort, never panicking.
@@ -322,6 +1300,50 @@ mod tests {
         assert!(auto.points.last().unwrap().time.is_nan());
     }
 
+    #[test]
+    fn recorded_moves_are_stamped_and_split_into_blocks() {
+        let mut auto = ParameterAutomation::new();
+        let mut last = std::collections::HashMap::from([(1, 0.0), (2, 0.5)]);
+        auto.record_changes(
+            &mut last,
+            &[param(1, 0.0, 0, false), param(2, 0.5, 0, false)],
+            512,
+        );
+        assert!(auto.recorded.is_empty());
+        auto.record_changes(
+            &mut last,
+            &[param(1, 0.25, 0, false), param(2, 0.5, 0, false)],
+            1024,
+        );
+        auto.record_changes(
+            &mut last,
+            &[param(1, 0.25, 0, false), param(2, 1.0, 0, false)],
+            1536,
+        );
+        assert_eq!(
+            auto.recorded,
+            [
+                AutomationEvent {
+                    param_id: 1,
+                    sample_offset: 1024,
+                    value: 0.25
+                },
+                AutomationEvent {
+                    param_id: 2,
+                    sample_offset: 1536,
+                    value: 1.0
+                },
+            ]
+        );
+
+        assert_eq!(auto.recorded_for_block(0, 1024), []);
+        assert_eq!(
+            auto.recorded_for_block(1024, 1024),
+            [(1, 0, 0.25), (2, 512, 1.0)]
+        );
+        assert_eq!(auto.recorded_for_block(1536, 512), [(2, 0, 1.0)]);
+    }
+
     #[test]
     fn add_point_with_nan_value_is_not_used_in_ordering() {
         // The sort keys on time only, so a NaN *value* can never reach the comparator and
@@ -333,4 +1355,264 @@ mod tests {
         let times: Vec<f64> = auto.points.iter().map(|p| p.time).collect();
         assert_eq!(times, vec![0.0, 1.0, 2.0]);
     }
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn note_values_to_hz_at_120_bpm() {
+        // 120 BPM = 2 quarter notes per second.
+        let hz = |nv| TempoSync::note_value_to_hz(nv, 120.0);
+        assert!(approx(hz(NoteValue::Whole), 0.5));
+        assert!(approx(hz(NoteValue::Half), 1.0));
+        assert!(approx(hz(NoteValue::Quarter), 2.0));
+        assert!(approx(hz(NoteValue::Eighth), 4.0));
+        assert!(approx(hz(NoteValue::Sixteenth), 8.0));
+        // Dotted notes are 1.5x longer, triplets 2/3 as long.
+        assert!(approx(hz(NoteValue::QuarterDotted), 2.0 / 1.5));
+        assert!(approx(hz(NoteValue::EighthTriplet), 6.0));
+        assert!(approx(hz(NoteValue::SixteenthTriplet), 12.0));
+    }
+
+    #[test]
+    fn note_value_to_hz_rejects_bad_tempo() {
+        assert_eq!(TempoSync::note_value_to_hz(NoteValue::Quarter, 0.0), 0.0);
+        assert_eq!(
+            TempoSync::note_value_to_hz(NoteValue::Quarter, f64::NAN),
+            0.0
+        );
+    }
+
+    #[test]
+    fn note_value_to_normalized_maps_linearly_and_clamps() {
+        // 2 Hz in a 0..=10 Hz range is 0.2.
+        let n = TempoSync::note_value_to_normalized(NoteValue::Quarter, 120.0, 0.0, 10.0);
+        assert!(approx(n, 0.2));
+        assert!(approx(
+            TempoSync::new(120.0).normalized(NoteValue::Quarter, 0.0, 10.0),
+            n
+        ));
+        // Out-of-range rates clamp; a degenerate range yields 0.
+        let fast =
+            TempoSync::note_value_to_normalized(NoteValue::SixteenthTriplet, 120.0, 0.0, 10.0);
+        assert_eq!(fast, 1.0);
+        assert_eq!(
+            TempoSync::note_value_to_normalized(NoteValue::Whole, 120.0, 1.0, 10.0),
+            0.0
+        );
+        assert_eq!(
+            TempoSync::note_value_to_normalized(NoteValue::Quarter, 120.0, 5.0, 5.0),
+            0.0
+        );
+    }
+
+    fn param(id: u32, value: f64, step_count: i32, is_read_only: bool) -> Parameter {
+        Parameter {
+            id,
+            name: format!("P{id}"),
+            value,
+            min: 0.0,
+            max: 1.0,
+            default: 0.0,
+            unit: String::new(),
+            step_count,
+            can_automate: true,
+            is_read_only,
+            is_bypass: false,
+            flags: 0,
+            unit_id: 0,
+        }
+    }
+
+    #[test]
+    fn params_near_value_finds_near_zero_and_near_maximum() {
+        let params = [
+            param(1, 0.0, 0, false),
+            param(2, 0.005, 0, false),
+            param(3, 0.5, 0, false),
+            param(4, 0.995, 0, false),
+            param(5, 1.0, 0, false),
+        ];
+        let ids = |found: Vec<&Parameter>| found.iter().map(|p| p.id).collect::<Vec<_>>();
+        assert_eq!(ids(params_near_value(&params, 0.0, 0.01)), [1, 2]);
+        assert_eq!(ids(params_near_value(&params, 1.0, 0.01)), [4, 5]);
+        assert_eq!(ids(params_near_value(&params, 0.5, 0.0)), [3]);
+        assert_eq!(params_near_value(&params, 0.5, 0.5).len(), 5);
+    }
+
+    #[test]
+    fn morph_halfway_lands_between_snapshots() {
+        let a = ParameterSnapshot::new(vec![param(1, 0.2, 0, false), param(2, 1.0, 0, false)]);
+        let b = ParameterSnapshot::new(vec![param(1, 0.6, 0, false), param(2, 0.0, 0, false)]);
+        let changes = morph(&a, &b, 0.5);
+        assert_eq!(changes.len(), 2);
+        assert!(approx(changes[0].value, 0.4));
+        assert!(approx(changes[1].value, 0.5));
+        // The end points reproduce the snapshots.
+        assert!(approx(morph(&a, &b, 0.0)[0].value, 0.2));
+        assert!(approx(morph(&a, &b, 1.0)[0].value, 0.6));
+    }
+
+    #[test]
+    fn morph_skips_read_only_and_unchanged_and_quantizes_steps() {
+        let a = ParameterSnapshot::new(vec![
+            param(1, 0.0, 0, true),
+            param(2, 0.5, 0, false),
+            param(3, 0.0, 4, false),
+        ]);
+        let b = ParameterSnapshot::new(vec![
+            param(1, 1.0, 0, true),
+            param(2, 0.5 + MORPH_EPSILON / 2.0, 0, false),
+            param(3, 1.0, 4, false),
+        ]);
+        let changes = morph(&a, &b, 0.4);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].id, 3);
+        // 0.4 of the way over 4 steps is 1.6 steps -> step 2 of 4.
+        assert!(approx(changes[0].value, 0.5));
+    }
+
+    #[test]
+    fn gaussian_samples_stay_within_three_sigma_of_the_current_value() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(1681);
+        let gaussian = Distribution::Gaussian { sigma: 0.1 };
+        let draws = 10_000;
+        let within = (0..draws)
+            .filter(|_| (gaussian.sample(&mut rng, 0.5) - 0.5).abs() <= 0.3)
+            .count();
+        // 99.7% for a normal distribution.
+        assert!(within as f64 / draws as f64 >= 0.997, "{within} of {draws}");
+        let within_sigma = (0..draws)
+            .filter(|_| (gaussian.sample(&mut rng, 0.5) - 0.5).abs() <= 0.1)
+            .count();
+        assert!(
+            (6600..=7050).contains(&within_sigma),
+            "{within_sigma} of {draws}"
+        );
+    }
+
+    #[test]
+    fn randomize_skips_protected_parameters_and_reports_its_distribution() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(7);
+        let mut bypass = param(4, 0.0, 1, false);
+        bypass.is_bypass = true;
+        let params = [
+            param(1, 0.5, 0, false),
+            param(2, 0.5, 0, true),
+            param(3, 0.5, 4, false),
+            bypass,
+            param(5, 0.5, 0, false),
+        ];
+        let constraints = RandomizeConstraints {
+            distribution: Distribution::Bimodal { weight: 1.0 },
+            exclude: vec![5],
+        };
+        let result = randomize(&params, &constraints, &mut rng);
+        assert_eq!(result.distribution, constraints.distribution);
+        let ids: Vec<u32> = result.changes.iter().map(|c| c.id).collect();
+        assert_eq!(ids, [1, 3]);
+        // Every draw lands near the top, and the stepped one on a step.
+        assert!(result.changes.iter().all(|c| c.value > 0.4));
+        assert_eq!((result.changes[1].value * 4.0).fract(), 0.0);
+
+        let uniform = randomize(&params, &RandomizeConstraints::default(), &mut rng);
+        assert_eq!(uniform.distribution, Distribution::Uniform);
+        assert!(uniform
+            .changes
+            .iter()
+            .all(|c| (0.0..=1.0).contains(&c.value)));
+    }
+
+    #[test]
+    fn a_hundred_edits_within_the_delay_apply_once() {
+        let start = std::time::Instant::now();
+        let mut debouncer = ChangeDebouncer::default();
+        for i in 0..100 {
+            let at = start + std::time::Duration::from_micros(i * 150);
+            debouncer.push_at(4, i as f64 / 100.0, at);
+            assert!(debouncer.tick_at(at).is_empty());
+        }
+        let last = start + std::time::Duration::from_micros(99 * 150);
+        assert!(debouncer
+            .tick_at(last + std::time::Duration::from_millis(15))
+            .is_empty());
+        let applied = debouncer.tick_at(last + std::time::Duration::from_millis(16));
+        assert_eq!(applied, [(4, 0.99)]);
+        assert!(debouncer.is_empty());
+        assert!(debouncer
+            .tick_at(last + std::time::Duration::from_secs(1))
+            .is_empty());
+    }
+
+    #[test]
+    fn widget_types_follow_steps_flags_units_and_display() {
+        let infer = |step_count, is_read_only, unit: &str, display| {
+            let mut p = param(1, 0.5, step_count, is_read_only);
+            p.unit = unit.to_string();
+            infer_widget_type(&p, display)
+        };
+        assert_eq!(infer(0, true, "", "0.5"), WidgetType::Label);
+        assert_eq!(infer(4, true, "", "Saw"), WidgetType::Label);
+        assert_eq!(infer(1, false, "", "On"), WidgetType::ToggleButton);
+        assert_eq!(infer(2, false, "", "Saw"), WidgetType::ComboBox);
+        assert_eq!(infer(32, false, "", "Saw"), WidgetType::ComboBox);
+        assert_eq!(infer(33, false, "", "17"), WidgetType::Slider);
+        assert_eq!(infer(0, false, "dB", "-6.0"), WidgetType::LogSlider);
+        assert_eq!(infer(0, false, "", "50 %"), WidgetType::Percentage);
+        assert_eq!(infer(0, false, "Hz", "440 Hz"), WidgetType::Slider);
+    }
+
+    #[test]
+    fn undo_history_is_ordered_and_time_travel_restores_values() {
+        use std::time::{Duration, Instant};
+        let start = Instant::now();
+        let mut stack = UndoStack::new(10);
+        stack.record_at([(1, 0.0, 0.2)], start);
+        stack.record_at(
+            [(1, 0.2, 0.5), (2, 0.1, 0.9)],
+            start + Duration::from_secs(1),
+        );
+        stack.record_at([(2, 0.9, 0.9)], start + Duration::from_secs(2));
+        stack.record_at([(3, 0.3, 0.0)], start + Duration::from_secs(3));
+
+        let history = stack.history();
+        assert_eq!(history.len(), 3, "a no-op edit records no step");
+        assert_eq!(
+            history.iter().map(|s| s.index).collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+        assert!(history.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+        assert_eq!(history[1].changes, [(1, 0.5), (2, 0.9)]);
+        assert_eq!(stack.position(), 3);
+
+        // Back to just after the first step: 3 and 2 undone, 1 back at step 0's value.
+        assert_eq!(stack.time_travel(1), [(3, 0.3), (1, 0.2), (2, 0.1)]);
+        assert_eq!(stack.position(), 1);
+        assert_eq!(stack.redo(), Some(vec![(1, 0.5), (2, 0.9)]));
+        assert_eq!(stack.time_travel(0), [(1, 0.0), (2, 0.1)]);
+        assert_eq!(stack.undo(), None);
+        assert_eq!(stack.time_travel(3), [(1, 0.5), (2, 0.9), (3, 0.0)]);
+
+        // Recording after an undo drops the undone steps.
+        stack.undo();
+        stack.record_at([(4, 0.0, 1.0)], start + Duration::from_secs(4));
+        assert_eq!(
+            stack.history().iter().map(|s| s.index).collect::<Vec<_>>(),
+            [0, 1, 3]
+        );
+
+        stack.prune_older_than_at(Duration::from_millis(2500), start + Duration::from_secs(4));
+        assert_eq!(stack.history().len(), 1);
+        assert_eq!(stack.position(), 1);
+        assert_eq!(stack.undo(), Some(vec![(4, 0.0)]));
+
+        let mut shallow = UndoStack::new(2);
+        for i in 0..5 {
+            shallow.record_at([(i, 0.0, 1.0)], start);
+        }
+        assert_eq!(shallow.history()[0].index, 3);
+    }
 }
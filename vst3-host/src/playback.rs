@@ -13,12 +13,20 @@ use std::sync::{Arc, Mutex, MutexGuard};
 
 use rtrb::{Consumer, Producer, RingBuffer};
 
+#[cfg(feature = "midi-output")]
+use crate::midi_output::VirtualMidiOutput;
 use crate::{
-    audio::{AudioBackend, AudioBuffers, AudioConfig, AudioLevels, AudioStream, ChannelLevel},
+    audio::{
+        AtomicF32Pair, AudioBackend, AudioBuffers, AudioConfig, AudioLevels, AudioStream,
+        AutoLimiter, BallisticsPreset, ChannelLevel, InputGainStage, LinearResampler,
+        MeterBallistics,
+    },
     error::{Error, Result},
-    midi::MidiEvent,
+    gm::GmBus,
+    midi::{Arpeggiator, MidiEvent},
     plugin::Plugin,
     realtime::{RealtimePluginRunner, RtControl, TransportCommand},
+    stream_monitor::{StreamError, StreamMonitor},
 };
 
 /// Capacity of each lock-free side-channel ring between the UI/control thread and the audio
@@ -29,10 +37,21 @@ const SIDE_CHANNEL_CAPACITY: usize = 4096;
 /// A control command queued by a UI/control thread and applied on the audio thread (inside the
 /// callback, under the plugin lock it already holds) at the start of the next block.
 enum HybridCommand {
-    Midi { event: MidiEvent, offset: i32 },
-    Param { id: u32, value: f64 },
+    Midi {
+        event: MidiEvent,
+        offset: i32,
+    },
+    Param {
+        id: u32,
+        value: f64,
+    },
     Transport(TransportCommand),
     Panic,
+    Arpeggiator(Option<Box<Arpeggiator>>),
+    InputGain(Option<Box<InputGainStage>>),
+    Limiter(Option<Box<AutoLimiter>>),
+    #[cfg(feature = "midi-output")]
+    VirtualMidiOut(Option<Producer<MidiEvent>>),
 }
 
 /// Peak amplitude of one channel buffer, sanitizing non-finite samples to 0.
@@ -56,6 +75,27 @@ struct AudioSideChannels {
     /// UI last read it. Peaks are non-negative, so `fetch_max` on the bit pattern is a valid
     /// float max.
     levels: Arc<[AtomicU32]>,
+    /// Per-channel ballistic meters, run over every rendered sample.
+    meters: Vec<MeterBallistics>,
+    /// The meters' latest readings (f32 bits), overwritten each block.
+    meter_levels: Arc<[AtomicU32]>,
+    /// Turns held notes into arpeggios when set ([`AudioHandle::set_arpeggiator`]).
+    arpeggiator: Option<Box<Arpeggiator>>,
+    /// Gain applied to captured input before the plugin sees it
+    /// ([`AudioHandle::set_input_gain`]); only the duplex path has input to apply it to.
+    input_gain: Option<Box<InputGainStage>>,
+    /// The input stage's current gain in dB (f32 bits), for the UI to show auto-gain at work.
+    input_gain_db: Arc<AtomicU32>,
+    /// Safety limiter on the plugin's output ([`AudioHandle::set_auto_limiter`]).
+    limiter: Option<Box<AutoLimiter>>,
+    /// The limiter's gain reduction on the last block in dB (f32 bits), for the UI.
+    gain_reduction_db: Arc<AtomicU32>,
+    /// Feeds the thread mirroring the plugin's output MIDI to a virtual port, when one is set
+    /// ([`AudioHandle::set_virtual_midi_out`]).
+    #[cfg(feature = "midi-output")]
+    virtual_midi_out: Option<Producer<MidiEvent>>,
+    sample_rate: f64,
+    tempo: f64,
 }
 
 impl AudioSideChannels {
@@ -64,21 +104,102 @@ impl AudioSideChannels {
         while let Ok(cmd) = self.control_rx.pop() {
             match cmd {
                 HybridCommand::Midi { event, offset } => {
-                    let _ = plugin.send_midi_event_at(event, offset);
+                    let arpeggiated = self
+                        .arpeggiator
+                        .as_mut()
+                        .is_some_and(|arp| arp.handle_event(event));
+                    if !arpeggiated {
+                        let _ = plugin.send_midi_event_at(event, offset);
+                    }
                 }
                 HybridCommand::Param { id, value } => {
                     let _ = plugin.set_parameter(id, value);
                 }
                 HybridCommand::Transport(change) => {
+                    if let TransportCommand::Tempo(bpm) = change {
+                        self.tempo = bpm;
+                        if let Some(arp) = self.arpeggiator.as_mut() {
+                            arp.set_timing(self.sample_rate, bpm);
+                        }
+                    }
                     change.apply(plugin);
                 }
                 HybridCommand::Panic => {
+                    if let Some(arp) = self.arpeggiator.as_mut() {
+                        arp.release();
+                    }
                     let _ = plugin.midi_panic();
                 }
+                HybridCommand::Arpeggiator(arp) => self.replace_arpeggiator(arp, plugin),
+                HybridCommand::InputGain(stage) => {
+                    let gain_db = stage.as_ref().map_or(0.0, |stage| stage.gain_db);
+                    self.input_gain_db
+                        .store((gain_db as f32).to_bits(), Ordering::Relaxed);
+                    self.input_gain = stage;
+                }
+                HybridCommand::Limiter(limiter) => {
+                    self.gain_reduction_db
+                        .store(0.0f32.to_bits(), Ordering::Relaxed);
+                    self.limiter = limiter;
+                }
+                #[cfg(feature = "midi-output")]
+                HybridCommand::VirtualMidiOut(port) => self.virtual_midi_out = port,
             }
         }
     }
 
+    /// Swap the arpeggiator. The new one keeps the notes still held; the old one's sounding
+    /// note is stopped.
+    fn replace_arpeggiator(&mut self, arp: Option<Box<Arpeggiator>>, plugin: &mut Plugin) {
+        let mut held = Vec::new();
+        if let Some(mut old) = self.arpeggiator.take() {
+            held = std::mem::take(&mut old.active_notes);
+            if let Some(note_off) = old.release() {
+                let _ = plugin.send_midi_event_at(note_off, 0);
+            }
+        }
+        self.arpeggiator = arp.map(|mut arp| {
+            arp.set_timing(self.sample_rate, self.tempo);
+            arp.active_notes = held;
+            arp
+        });
+    }
+
+    /// Run the captured input through the input gain stage, if one is set. Every channel goes
+    /// through the one stage, so auto-gain measures the input as a whole.
+    fn apply_input_gain(&mut self, inputs: &mut [Vec<f32>]) {
+        let Some(stage) = self.input_gain.as_mut() else {
+            return;
+        };
+        for buf in inputs.iter_mut() {
+            stage.process_block(buf);
+        }
+        self.input_gain_db
+            .store((stage.gain_db as f32).to_bits(), Ordering::Relaxed);
+    }
+
+    /// Run the plugin's output through the limiter, if one is set, before it reaches the device
+    /// and the meters.
+    fn apply_limiter(&mut self, outputs: &mut [Vec<f32>]) {
+        let Some(limiter) = self.limiter.as_mut() else {
+            return;
+        };
+        limiter.process_block(outputs);
+        self.gain_reduction_db
+            .store(limiter.gain_reduction_db().to_bits(), Ordering::Relaxed);
+    }
+
+    /// Schedule the arpeggiator's notes for the next `frames`-sample block.
+    fn arpeggiate(&mut self, plugin: &mut Plugin, frames: usize) {
+        let Some(arp) = self.arpeggiator.as_mut() else {
+            return;
+        };
+        let block_end = arp.position() + frames as f64 / arp.samples_per_beat();
+        for (event, offset) in arp.tick(block_end) {
+            let _ = plugin.send_midi_event_at(event, offset as i32);
+        }
+    }
+
     /// Publish per-channel output peaks into the atomics. Only meaningful after a successful
     /// render, so the caller gates this on `process_audio` succeeding.
     fn publish_levels(&mut self, outputs: &[Vec<f32>]) {
@@ -86,6 +207,14 @@ impl AudioSideChannels {
             let peak = outputs.get(ch).map(|b| channel_peak(b)).unwrap_or(0.0);
             atomic.fetch_max(peak.to_bits(), Ordering::Relaxed);
         }
+        for ((meter, atomic), buf) in self
+            .meters
+            .iter_mut()
+            .zip(self.meter_levels.iter())
+            .zip(outputs)
+        {
+            atomic.store(meter.process_block(buf).to_bits(), Ordering::Relaxed);
+        }
     }
 
     /// Forward the plugin's drained output MIDI and editor parameter changes into their rings
@@ -94,6 +223,10 @@ impl AudioSideChannels {
     /// and the UI must stay in sync.
     fn publish_feedback(&mut self, plugin: &Plugin) {
         for event in plugin.take_output_midi() {
+            #[cfg(feature = "midi-output")]
+            if let Some(port) = self.virtual_midi_out.as_mut() {
+                let _ = port.push(event);
+            }
             let _ = self.out_midi_tx.push(event);
         }
         for change in plugin.get_parameter_changes() {
@@ -102,6 +235,29 @@ impl AudioSideChannels {
     }
 }
 
+/// Start a thread that passes every event pushed into the returned ring to `send`, keeping
+/// MIDI sends (system calls) off the audio thread. Once the producer is dropped it sends what
+/// is left and exits, dropping `send` (and the port it owns).
+#[cfg(feature = "midi-output")]
+fn spawn_midi_sender(
+    mut send: impl FnMut(&MidiEvent) + Send + 'static,
+) -> std::io::Result<Producer<MidiEvent>> {
+    let (tx, mut rx) = RingBuffer::<MidiEvent>::new(SIDE_CHANNEL_CAPACITY);
+    std::thread::Builder::new()
+        .name("virtual-midi-out".into())
+        .spawn(move || loop {
+            let abandoned = rx.is_abandoned();
+            while let Ok(event) = rx.pop() {
+                send(&event);
+            }
+            if abandoned {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        })?;
+    Ok(tx)
+}
+
 /// The UI-thread half of the side channels, stored in [`AudioHandle`]. The rtrb endpoints need
 /// `&mut` for push/pop, so they live behind `Mutex` to expose `&self` methods; this mutex is
 /// only ever touched by the UI/control thread, never the audio callback.
@@ -112,27 +268,56 @@ struct UiSideChannels {
     out_midi_rx: Mutex<Consumer<MidiEvent>>,
     param_rx: Mutex<Consumer<(u32, f64)>>,
     levels: Arc<[AtomicU32]>,
+    meter_levels: Arc<[AtomicU32]>,
+    meter_ballistics: BallisticsPreset,
+    input_gain_db: Arc<AtomicU32>,
+    gain_reduction_db: Arc<AtomicU32>,
 }
 
 /// Build a fresh set of side channels for `channels` output channels, returning the audio-side
-/// half (move into the callback) and the UI-side half (store in the handle).
-fn make_side_channels(channels: usize) -> (AudioSideChannels, UiSideChannels) {
+/// half (move into the callback) and the UI-side half (store in the handle). The output
+/// meters follow `config.meter_ballistics` at `config.sample_rate`.
+fn make_side_channels(
+    channels: usize,
+    config: &AudioConfig,
+) -> (AudioSideChannels, UiSideChannels) {
     let (control_tx, control_rx) = RingBuffer::<HybridCommand>::new(SIDE_CHANNEL_CAPACITY);
     let (out_midi_tx, out_midi_rx) = RingBuffer::<MidiEvent>::new(SIDE_CHANNEL_CAPACITY);
     let (param_tx, param_rx) = RingBuffer::<(u32, f64)>::new(SIDE_CHANNEL_CAPACITY);
     let levels: Arc<[AtomicU32]> = (0..channels).map(|_| AtomicU32::new(0)).collect();
+    let meter_levels: Arc<[AtomicU32]> = (0..channels).map(|_| AtomicU32::new(0)).collect();
+    let meters = (0..channels)
+        .map(|_| MeterBallistics::new(config.meter_ballistics, config.sample_rate))
+        .collect();
+    let input_gain_db = Arc::new(AtomicU32::new(0.0f32.to_bits()));
+    let gain_reduction_db = Arc::new(AtomicU32::new(0.0f32.to_bits()));
 
     let audio = AudioSideChannels {
         control_rx,
         out_midi_tx,
         param_tx,
         levels: Arc::clone(&levels),
+        meters,
+        meter_levels: Arc::clone(&meter_levels),
+        arpeggiator: None,
+        input_gain: None,
+        #[cfg(feature = "midi-output")]
+        virtual_midi_out: None,
+        input_gain_db: Arc::clone(&input_gain_db),
+        limiter: None,
+        gain_reduction_db: Arc::clone(&gain_reduction_db),
+        sample_rate: config.sample_rate,
+        tempo: config.tempo,
     };
     let ui = UiSideChannels {
         control_tx: Arc::new(Mutex::new(control_tx)),
         out_midi_rx: Mutex::new(out_midi_rx),
         param_rx: Mutex::new(param_rx),
         levels,
+        meter_levels,
+        meter_ballistics: config.meter_ballistics,
+        input_gain_db,
+        gain_reduction_db,
     };
     (audio, ui)
 }
@@ -318,6 +503,80 @@ impl AudioHandle {
             .unwrap_or(false)
     }
 
+    /// Run `arpeggiator` on the audio thread from the next block (`None` turns it off). While
+    /// it's on, note events sent through this handle (or its [`MidiSink`]s) feed the
+    /// arpeggiator instead of the plugin; notes still held carry over when it is replaced.
+    /// Returns `false` if the ring is full.
+    pub fn set_arpeggiator(&self, arpeggiator: Option<Arpeggiator>) -> bool {
+        let command = HybridCommand::Arpeggiator(arpeggiator.map(Box::new));
+        self.ui
+            .control_tx
+            .lock()
+            .map(|mut tx| tx.push(command).is_ok())
+            .unwrap_or(false)
+    }
+
+    /// Put `stage` between the captured input and the plugin's input buses from the next block
+    /// (`None` removes it). Only the duplex stream from [`play_with_input_backend`] has input
+    /// to apply it to. Returns `false` if the ring is full.
+    pub fn set_input_gain(&self, stage: Option<InputGainStage>) -> bool {
+        let command = HybridCommand::InputGain(stage.map(Box::new));
+        self.ui
+            .control_tx
+            .lock()
+            .map(|mut tx| tx.push(command).is_ok())
+            .unwrap_or(false)
+    }
+
+    /// Put `limiter` after the plugin's output from the next block (`None` removes it). The
+    /// output meters show the limited signal. Returns `false` if the ring is full.
+    pub fn set_auto_limiter(&self, limiter: Option<AutoLimiter>) -> bool {
+        let command = HybridCommand::Limiter(limiter.map(Box::new));
+        self.ui
+            .control_tx
+            .lock()
+            .map(|mut tx| tx.push(command).is_ok())
+            .unwrap_or(false)
+    }
+
+    /// How far the limiter turned the last block down, in dB ([`AutoLimiter::gain_reduction_db`]),
+    /// or `0.0` with no limiter set.
+    pub fn gain_reduction_db(&self) -> f32 {
+        f32::from_bits(self.ui.gain_reduction_db.load(Ordering::Relaxed))
+    }
+
+    /// Mirror every MIDI event the plugin emits from `process()` to `port` from the next block
+    /// (`None` closes the current one). The events still reach [`drain_output_midi`]
+    /// as before. The audio thread only queues them; a thread of the port's own sends them.
+    /// Returns `false` if that thread can't be started or the ring is full.
+    ///
+    /// [`drain_output_midi`]: Self::drain_output_midi
+    #[cfg(feature = "midi-output")]
+    pub fn set_virtual_midi_out(&self, port: Option<VirtualMidiOutput>) -> bool {
+        let feed = port
+            .map(|mut port| {
+                spawn_midi_sender(move |event| {
+                    let _ = port.send_event(event);
+                })
+            })
+            .transpose();
+        let Ok(feed) = feed else {
+            return false;
+        };
+        let command = HybridCommand::VirtualMidiOut(feed);
+        self.ui
+            .control_tx
+            .lock()
+            .map(|mut tx| tx.push(command).is_ok())
+            .unwrap_or(false)
+    }
+
+    /// The gain (dB) the input stage applied to the last block — moving while auto-gain
+    /// settles — or `0.0` with no stage set.
+    pub fn input_gain_db(&self) -> f64 {
+        f32::from_bits(self.ui.input_gain_db.load(Ordering::Relaxed)) as f64
+    }
+
     /// Queue an all-notes-off "panic" (CC 123/120/121 on every channel) without locking the
     /// audio thread. Returns `false` if the ring is full.
     pub fn midi_panic(&self) -> bool {
@@ -348,7 +607,29 @@ impl AudioHandle {
                 }
             })
             .collect();
-        AudioLevels { channels }
+        let mut levels = AudioLevels::new(0);
+        levels.channels = channels;
+        levels
+    }
+
+    /// Read the per-channel output meters (linear, `0.0..`) without locking the audio thread.
+    ///
+    /// Unlike [`Self::output_levels`], these already follow the configured
+    /// [`BallisticsPreset`] (see [`Vst3HostBuilder::meter_ballistics`]), integrated per sample
+    /// on the audio thread, so a UI can draw them as-is at any frame rate.
+    ///
+    /// [`Vst3HostBuilder::meter_ballistics`]: crate::Vst3HostBuilder::meter_ballistics
+    pub fn meter_levels(&self) -> Vec<f32> {
+        self.ui
+            .meter_levels
+            .iter()
+            .map(|atomic| f32::from_bits(atomic.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    /// The ballistics [`Self::meter_levels`] follows.
+    pub fn meter_ballistics(&self) -> BallisticsPreset {
+        self.ui.meter_ballistics
     }
 
     /// Drain MIDI the plugin emitted during processing (arpeggiators, MPE, …) without locking
@@ -420,6 +701,87 @@ fn prepare_scratch(scratch: &mut AudioBuffers, frames: usize) {
     scratch.block_size = frames;
 }
 
+/// Capture end of the live-input bridge: the input callback pushes interleaved frames.
+struct InputFeed {
+    producer: Producer<f32>,
+    channels: usize,
+    dropped_frames: u64,
+}
+
+impl InputFeed {
+    /// Queue a captured block. Whole frames that don't fit (the output side fell behind) are
+    /// dropped rather than blocking the capture callback.
+    fn push(&mut self, data: &[f32]) {
+        let fit = self.producer.slots() / self.channels * self.channels;
+        let (kept, excess) = data.split_at(fit.min(data.len()));
+        for &s in kept {
+            let _ = self.producer.push(s);
+        }
+        self.dropped_frames += (excess.len() / self.channels) as u64;
+    }
+}
+
+/// Playback end of the live-input bridge: fills the plugin's input buffers from the ring,
+/// resampling when the input device runs at another rate.
+struct InputBridge {
+    consumer: Consumer<f32>,
+    resampler: Option<LinearResampler>,
+}
+
+impl InputBridge {
+    /// A ring between a `channels`-channel input at `input_rate` and an output at
+    /// `output_rate` pulling blocks of about `block_size` frames.
+    fn new(
+        channels: usize,
+        input_rate: f64,
+        output_rate: f64,
+        block_size: usize,
+    ) -> (InputFeed, InputBridge) {
+        let resampler = (input_rate != output_rate)
+            .then(|| LinearResampler::new(channels, input_rate, output_rate));
+        let ratio = resampler.as_ref().map_or(1.0, LinearResampler::ratio);
+        // Hold a few blocks (in input frames) so the independent device clocks don't starve
+        // or overrun immediately.
+        let block_frames = (block_size as f64 * ratio.max(1.0)).ceil() as usize;
+        let (producer, consumer) = RingBuffer::<f32>::new((block_frames * channels * 8).max(2048));
+        (
+            InputFeed {
+                producer,
+                channels,
+                dropped_frames: 0,
+            },
+            InputBridge {
+                consumer,
+                resampler,
+            },
+        )
+    }
+
+    /// Fill `inputs[ch][..frames]`; missing input reads as silence.
+    fn read(&mut self, inputs: &mut [Vec<f32>], frames: usize) {
+        let consumer = &mut self.consumer;
+        match &mut self.resampler {
+            Some(resampler) => resampler.process(inputs, frames, |frame| {
+                // Interleaved frame-major order matches the input callback's push order.
+                if consumer.slots() < frame.len() {
+                    return false;
+                }
+                for s in frame.iter_mut() {
+                    *s = consumer.pop().unwrap_or(0.0);
+                }
+                true
+            }),
+            None => {
+                for f in 0..frames {
+                    for ch in inputs.iter_mut() {
+                        ch[f] = consumer.pop().unwrap_or(0.0);
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Start streaming `plugin` through `backend`'s default output device.
 ///
 /// The plugin is moved behind a shared lock so it can keep being controlled while
@@ -433,10 +795,134 @@ pub fn play_with_backend<B: AudioBackend>(
     plugin: Plugin,
     config: AudioConfig,
 ) -> Result<AudioHandle> {
-    let device = backend
-        .default_output_device()
-        .ok_or_else(|| Error::AudioBackendError("No default output device available".into()))?;
+    let (render, plugin, ui) = output_renderer(plugin, &config)?;
+    let stream = open_output(backend, config, render, Box::new(|_| {}))?;
+    Ok(AudioHandle {
+        _stream: stream,
+        _input_stream: None,
+        plugin,
+        ui,
+    })
+}
+
+/// How long [`play_monitored`] waits after the device disconnects before reopening it.
+pub const RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// [`play_with_backend`], reporting what goes wrong to `monitor`: stream errors (see
+/// [`StreamError::from_backend_error`]) and how long each callback takes.
+///
+/// When the device disconnects, the stream is reopened [`RECONNECT_DELAY`] later on the
+/// default output device of the backend `reconnect` creates, and again every
+/// [`RECONNECT_DELAY`] until that succeeds or the handle is dropped. The plugin keeps its
+/// state across the reconnection.
+pub fn play_monitored<B, F>(
+    backend: &B,
+    plugin: Plugin,
+    config: AudioConfig,
+    monitor: Arc<StreamMonitor>,
+    reconnect: F,
+) -> Result<AudioHandle>
+where
+    B: AudioBackend,
+    F: Fn() -> Result<B> + Send + 'static,
+{
+    let (render, plugin, ui) = output_renderer(plugin, &config)?;
+    monitor.start_timing(config.block_size, config.sample_rate);
+    let stream =
+        open_reconnecting_output(backend, config, render, monitor, reconnect, RECONNECT_DELAY)?;
+    Ok(AudioHandle {
+        _stream: stream,
+        _input_stream: None,
+        plugin,
+        ui,
+    })
+}
 
+/// Open an output stream that reports to `monitor` and reopens itself through `reconnect`
+/// `delay` after its device disconnects (see [`play_monitored`]).
+fn open_reconnecting_output<B, F>(
+    backend: &B,
+    config: AudioConfig,
+    render: RenderCallback,
+    monitor: Arc<StreamMonitor>,
+    reconnect: F,
+    delay: std::time::Duration,
+) -> Result<Box<dyn AudioStream>>
+where
+    B: AudioBackend,
+    F: Fn() -> Result<B> + Send + 'static,
+{
+    let render = Arc::new(Mutex::new(render));
+    let (disconnected, disconnections) = std::sync::mpsc::channel();
+    let stream = open_output(
+        backend,
+        config,
+        timed_render(&render, &monitor, config.output_channels),
+        stream_error_reporter(&monitor, disconnected.clone()),
+    )?;
+    let stream = Arc::new(Mutex::new(stream));
+    let current = Arc::downgrade(&stream);
+    std::thread::Builder::new()
+        .name("audio-reconnect".into())
+        .spawn(move || loop {
+            match disconnections.recv_timeout(delay.max(std::time::Duration::from_millis(50))) {
+                Ok(()) => {}
+                // Check now and then whether the handle is gone.
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) if current.strong_count() > 0 => {
+                    continue
+                }
+                Err(_) => return,
+            }
+            std::thread::sleep(delay);
+            while disconnections.try_recv().is_ok() {}
+            let Some(stream) = current.upgrade() else {
+                return;
+            };
+            let reopened = reconnect().and_then(|backend| {
+                open_output(
+                    &backend,
+                    config,
+                    timed_render(&render, &monitor, config.output_channels),
+                    stream_error_reporter(&monitor, disconnected.clone()),
+                )
+            });
+            match reopened {
+                Ok(reopened) => {
+                    log::info!("audio output reconnected");
+                    *stream.lock().unwrap_or_else(|p| p.into_inner()) = reopened;
+                }
+                Err(e) => {
+                    log::warn!("couldn't reconnect the audio output: {e}");
+                    let _ = disconnected.send(());
+                }
+            }
+        })
+        .map_err(|e| Error::AudioBackendError(format!("Failed to spawn reconnect thread: {e}")))?;
+    Ok(Box::new(ReconnectingStream(stream)))
+}
+
+/// The output stream of [`play_monitored`], swapped for a new one when the device reconnects.
+struct ReconnectingStream(Arc<Mutex<Box<dyn AudioStream>>>);
+
+impl AudioStream for ReconnectingStream {
+    fn play(&self) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        self.0.lock().unwrap_or_else(|p| p.into_inner()).play()
+    }
+
+    fn pause(&self) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        self.0.lock().unwrap_or_else(|p| p.into_inner()).pause()
+    }
+}
+
+type RenderCallback = Box<dyn FnMut(&mut [f32]) + Send>;
+type StreamErrorCallback = Box<dyn FnMut(StreamError) + Send>;
+
+/// Arm `plugin` and build the device callback that renders it into interleaved output,
+/// along with the shared plugin and the UI ends of its side channels.
+fn output_renderer(
+    plugin: Plugin,
+    config: &AudioConfig,
+) -> Result<(RenderCallback, Arc<Mutex<Plugin>>, UiSideChannels)> {
     let channels = config.output_channels;
     let sample_rate = config.sample_rate;
 
@@ -449,11 +935,11 @@ pub fn play_with_backend<B: AudioBackend>(
 
     let plugin_cb = Arc::clone(&plugin);
     // Lock-free side channels: UI control in, feedback (peaks / output MIDI / param changes) out.
-    let (mut side, ui) = make_side_channels(channels);
+    let (mut side, ui) = make_side_channels(channels, config);
     // Reusable scratch buffer so the steady-state callback does not allocate.
     let mut scratch = AudioBuffers::new(0, channels, config.block_size, sample_rate);
 
-    let data_cb = Box::new(move |data: &mut [f32]| {
+    let render = Box::new(move |data: &mut [f32]| {
         // Start from silence so unproduced channels/frames are quiet.
         data.fill(0.0);
         if channels == 0 {
@@ -473,30 +959,71 @@ pub fn play_with_backend<B: AudioBackend>(
         // successful render, but MIDI/param feedback is published even when stopped so the UI
         // stays in sync.
         side.apply_control(&mut p);
+        side.arpeggiate(&mut p, frames);
         if p.process_audio(&mut scratch).is_ok() {
+            side.apply_limiter(&mut scratch.outputs);
             interleave_outputs(&scratch.outputs, data, channels);
             side.publish_levels(&scratch.outputs);
         }
         side.publish_feedback(&p);
     });
+    Ok((render, plugin, ui))
+}
 
-    let err_cb = Box::new(|e: B::Error| {
+/// Open and start an output stream on `backend`'s default output device, passing the
+/// stream's errors to `on_error`.
+fn open_output<B: AudioBackend>(
+    backend: &B,
+    config: AudioConfig,
+    render: RenderCallback,
+    mut on_error: StreamErrorCallback,
+) -> Result<Box<dyn AudioStream>> {
+    let device = backend
+        .default_output_device()
+        .ok_or_else(|| Error::AudioBackendError("No default output device available".into()))?;
+
+    let err_cb = Box::new(move |e: B::Error| {
         log::error!("audio stream error: {}", e);
+        on_error(StreamError::from_backend_error(&e));
     });
 
     let stream = backend
-        .create_output_stream(&device, config, data_cb, err_cb)
+        .create_output_stream(&device, config, render, err_cb)
         .map_err(|e| Error::AudioBackendError(format!("Failed to create output stream: {}", e)))?;
 
     stream
         .play()
         .map_err(|e| Error::AudioBackendError(format!("Failed to start stream: {}", e)))?;
+    Ok(Box::new(stream))
+}
 
-    Ok(AudioHandle {
-        _stream: Box::new(stream),
-        _input_stream: None,
-        plugin,
-        ui,
+/// A device callback running the shared `render` and timing it for `monitor`.
+fn timed_render(
+    render: &Arc<Mutex<RenderCallback>>,
+    monitor: &Arc<StreamMonitor>,
+    channels: usize,
+) -> RenderCallback {
+    let (render, monitor) = (Arc::clone(render), Arc::clone(monitor));
+    Box::new(move |data: &mut [f32]| {
+        let start = std::time::Instant::now();
+        monitor.record_callback_start(start, data.len() / channels.max(1));
+        (render.lock().unwrap_or_else(|p| p.into_inner()))(data);
+        monitor.record_callback(start.elapsed());
+    })
+}
+
+/// A stream error callback reporting to `monitor`, and to `disconnected` when the device
+/// went away.
+fn stream_error_reporter(
+    monitor: &Arc<StreamMonitor>,
+    disconnected: std::sync::mpsc::Sender<()>,
+) -> StreamErrorCallback {
+    let monitor = Arc::clone(monitor);
+    Box::new(move |error| {
+        if error == StreamError::DeviceDisconnected {
+            let _ = disconnected.send(());
+        }
+        monitor.report(error);
     })
 }
 
@@ -509,12 +1036,29 @@ pub fn play_with_backend<B: AudioBackend>(
 /// `input_channels`/`output_channels`/`sample_rate` define the streams. Like
 /// [`play_with_backend`], control the plugin via the returned [`AudioHandle`].
 ///
+/// The input device is the backend's [`default_input_device`](AudioBackend::default_input_device)
+/// (see [`AudioBackend::set_input_source`]). If it can't capture at `config.sample_rate`, it
+/// runs at its own rate ([`AudioBackend::input_sample_rate`]) and the captured audio is
+/// linearly resampled.
+///
 /// Note: the two device clocks are independent; this uses a small bridge buffer and tolerates
 /// drift by dropping/zero-filling at the edges. Suitable for monitoring/auditioning effects.
 pub fn play_with_input_backend<B: AudioBackend>(
     backend: &B,
     plugin: Plugin,
     config: AudioConfig,
+) -> Result<AudioHandle> {
+    play_with_input_monitored(backend, plugin, config, Arc::default())
+}
+
+/// [`play_with_input_backend`], reporting both streams' errors and the output callback's
+/// duration to `monitor`. Unlike [`play_monitored`], neither stream is reopened if its
+/// device disconnects.
+pub fn play_with_input_monitored<B: AudioBackend>(
+    backend: &B,
+    plugin: Plugin,
+    config: AudioConfig,
+    monitor: Arc<StreamMonitor>,
 ) -> Result<AudioHandle> {
     let in_device = backend
         .default_input_device()
@@ -533,53 +1077,62 @@ pub fn play_with_input_backend<B: AudioBackend>(
         .unwrap_or_else(|p| p.into_inner())
         .start_processing()?;
 
-    // SPSC bridge: input callback (producer) -> output callback (consumer). Hold a few
-    // blocks of interleaved input so the independent device clocks don't starve immediately.
-    let ring_cap = (config.block_size * in_channels * 8).max(2048);
-    let (mut producer, mut consumer) = rtrb::RingBuffer::<f32>::new(ring_cap);
+    // SPSC bridge: input callback (feed) -> output callback (bridge).
+    let input_rate = backend.input_sample_rate(&in_device, sample_rate);
+    let (mut feed, mut bridge) =
+        InputBridge::new(in_channels, input_rate, sample_rate, config.block_size);
 
-    let in_data_cb = Box::new(move |data: &[f32]| {
-        // Drop on full (output side fell behind) rather than block the capture callback.
-        for &s in data {
-            let _ = producer.push(s);
-        }
+    let in_data_cb = Box::new(move |data: &[f32]| feed.push(data));
+    let in_monitor = Arc::clone(&monitor);
+    let in_err_cb = Box::new(move |e: B::Error| {
+        log::error!("input stream error: {}", e);
+        in_monitor.report(StreamError::from_backend_error(&e));
     });
-    let in_err_cb = Box::new(|e: B::Error| log::error!("input stream error: {}", e));
+    let in_config = AudioConfig {
+        sample_rate: input_rate,
+        ..config
+    };
     let input_stream = backend
-        .create_input_stream(&in_device, config, in_data_cb, in_err_cb)
+        .create_input_stream(&in_device, in_config, in_data_cb, in_err_cb)
         .map_err(|e| Error::AudioBackendError(format!("Failed to create input stream: {}", e)))?;
 
     let plugin_cb = Arc::clone(&plugin);
     // Lock-free side channels (same as the output-only path) so effect hosting is also
     // controllable without locking the audio thread.
-    let (mut side, ui) = make_side_channels(out_channels);
+    let (mut side, ui) = make_side_channels(out_channels, &config);
     let mut scratch = AudioBuffers::new(in_channels, out_channels, config.block_size, sample_rate);
+    let out_monitor = Arc::clone(&monitor);
+    out_monitor.start_timing(config.block_size, sample_rate);
     let out_data_cb = Box::new(move |data: &mut [f32]| {
+        let start = std::time::Instant::now();
+        out_monitor.record_callback_start(start, data.len() / out_channels.max(1));
         data.fill(0.0);
         if out_channels == 0 {
             return;
         }
         let frames = data.len() / out_channels;
         prepare_scratch(&mut scratch, frames);
-        // Deinterleave captured input from the ring into the plugin's input buffers
-        // (interleaved frame-major order matches the input callback's push order).
-        for f in 0..frames {
-            for ch in scratch.inputs.iter_mut() {
-                ch[f] = consumer.pop().unwrap_or(0.0);
-            }
-        }
+        // Deinterleave captured input from the ring into the plugin's input buffers.
+        bridge.read(&mut scratch.inputs, frames);
         let mut p = match plugin_cb.lock() {
             Ok(guard) => guard,
             Err(poisoned) => poisoned.into_inner(),
         };
         side.apply_control(&mut p);
+        side.apply_input_gain(&mut scratch.inputs);
+        side.arpeggiate(&mut p, frames);
         if p.process_audio(&mut scratch).is_ok() {
+            side.apply_limiter(&mut scratch.outputs);
             interleave_outputs(&scratch.outputs, data, out_channels);
             side.publish_levels(&scratch.outputs);
         }
         side.publish_feedback(&p);
+        out_monitor.record_callback(start.elapsed());
+    });
+    let out_err_cb = Box::new(move |e: B::Error| {
+        log::error!("output stream error: {}", e);
+        monitor.report(StreamError::from_backend_error(&e));
     });
-    let out_err_cb = Box::new(|e: B::Error| log::error!("output stream error: {}", e));
     let output_stream = backend
         .create_output_stream(&out_device, config, out_data_cb, out_err_cb)
         .map_err(|e| Error::AudioBackendError(format!("Failed to create output stream: {}", e)))?;
@@ -674,10 +1227,287 @@ pub fn play_realtime_with_backend<B: AudioBackend>(
     })
 }
 
+/// A running audio stream driving a [`GmBus`]. Dropping it stops playback.
+pub struct GmAudioHandle {
+    _stream: Box<dyn AudioStream>,
+    bus: Arc<Mutex<GmBus>>,
+    /// Left/right output peak since the last [`GmAudioHandle::output_peak`].
+    peak: Arc<AtomicF32Pair>,
+}
+
+impl GmAudioHandle {
+    /// Lock the running bus to route MIDI, change programs or adjust the mixer. Recovers from
+    /// a poisoned lock like [`AudioHandle::lock`].
+    pub fn lock(&self) -> MutexGuard<'_, GmBus> {
+        self.bus
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Lock without blocking, or `None` while the audio callback holds the bus. Use this for
+    /// per-frame UI reads such as [`GmBus::levels`].
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, GmBus>> {
+        match self.bus.try_lock() {
+            Ok(guard) => Some(guard),
+            Err(std::sync::TryLockError::Poisoned(p)) => Some(p.into_inner()),
+            Err(std::sync::TryLockError::WouldBlock) => None,
+        }
+    }
+
+    /// The mixed output's left and right peaks (linear) since the last call, without locking
+    /// the bus. A mono stream reports the same value for both.
+    pub fn output_peak(&self) -> (f32, f32) {
+        self.peak.swap(0.0, 0.0)
+    }
+
+    /// Stop playback now (equivalent to dropping the handle).
+    pub fn stop(self) {}
+}
+
+/// Stream a [`GmBus`] through `backend`'s default output device: every block, each channel's
+/// instance renders and the bus mixes them. Like [`play_with_backend`], the bus sits behind a
+/// lock shared with the returned [`GmAudioHandle`].
+pub fn play_gm_with_backend<B: AudioBackend>(
+    backend: &B,
+    bus: GmBus,
+    config: AudioConfig,
+) -> Result<GmAudioHandle> {
+    let device = backend
+        .default_output_device()
+        .ok_or_else(|| Error::AudioBackendError("No default output device available".into()))?;
+
+    let channels = config.output_channels;
+    let sample_rate = config.sample_rate;
+
+    let bus = Arc::new(Mutex::new(bus));
+    bus.lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .start_processing()?;
+
+    let bus_cb = Arc::clone(&bus);
+    let peak = Arc::new(AtomicF32Pair::default());
+    let peak_cb = Arc::clone(&peak);
+    let mut scratch = AudioBuffers::new(0, channels, config.block_size, sample_rate);
+
+    let data_cb = Box::new(move |data: &mut [f32]| {
+        data.fill(0.0);
+        if channels == 0 {
+            return;
+        }
+        let frames = data.len() / channels;
+        prepare_scratch(&mut scratch, frames);
+
+        let mut bus = match bus_cb.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if bus.process_audio(&mut scratch).is_ok() {
+            interleave_outputs(&scratch.outputs, data, channels);
+            let left = scratch.outputs.first().map_or(0.0, |b| channel_peak(b));
+            let right = scratch.outputs.get(1).map_or(left, |b| channel_peak(b));
+            peak_cb.fetch_max(left, right);
+        }
+    });
+
+    let err_cb = Box::new(|e: B::Error| {
+        log::error!("audio stream error: {}", e);
+    });
+
+    let stream = backend
+        .create_output_stream(&device, config, data_cb, err_cb)
+        .map_err(|e| Error::AudioBackendError(format!("Failed to create output stream: {}", e)))?;
+
+    stream
+        .play()
+        .map_err(|e| Error::AudioBackendError(format!("Failed to start stream: {}", e)))?;
+
+    Ok(GmAudioHandle {
+        _stream: Box::new(stream),
+        bus,
+        peak,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "midi-output")]
+    #[test]
+    fn midi_sender_forwards_events_off_the_queueing_thread() {
+        let (sent, received) = std::sync::mpsc::channel();
+        let mut feed = spawn_midi_sender(move |event| {
+            let thread = std::thread::current().name().map(str::to_string);
+            let _ = sent.send((*event, thread));
+        })
+        .unwrap();
+        let note = MidiEvent::NoteOn {
+            channel: crate::midi::MidiChannel::Ch1,
+            note: 60,
+            velocity: 100,
+        };
+        feed.push(note).unwrap();
+        drop(feed);
+
+        let (event, thread) = received
+            .recv_timeout(std::time::Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(event, note);
+        assert_eq!(thread.as_deref(), Some("virtual-midi-out"));
+        // The thread exits once the feed is dropped, dropping the sender with it.
+        assert!(received
+            .recv_timeout(std::time::Duration::from_secs(1))
+            .is_err());
+    }
+
+    #[test]
+    fn input_bridge_keeps_up_with_a_slower_input_device() {
+        // 44.1 kHz capture in 10 ms callbacks feeding 48 kHz output pulling 512-frame blocks:
+        // the callbacks fire at different rates, but the resampling reader drains the ring as
+        // fast as it fills, so nothing is dropped and the backlog stays bounded.
+        let (mut feed, mut bridge) = InputBridge::new(2, 44_100.0, 48_000.0, 512);
+        let capacity = feed.producer.slots();
+        let mut inputs = vec![vec![0.0; 512]; 2];
+        let (mut next_in, mut next_out) = (0.0, 0.0);
+        let mut worst_backlog = 0;
+        while next_in < 10.0 {
+            if next_in <= next_out {
+                feed.push(&[0.25; 441 * 2]);
+                next_in += 441.0 / 44_100.0;
+            } else {
+                bridge.read(&mut inputs, 512);
+                next_out += 512.0 / 48_000.0;
+            }
+            worst_backlog = worst_backlog.max(bridge.consumer.slots());
+        }
+        assert_eq!(feed.dropped_frames, 0);
+        assert!(worst_backlog < capacity / 2, "backlog {worst_backlog}");
+        assert!(inputs.iter().flatten().all(|&s| (s - 0.25).abs() < 1e-6));
+
+        // With no reader, the overflow is dropped in whole frames.
+        let (mut feed, _bridge) = InputBridge::new(2, 48_000.0, 48_000.0, 512);
+        let capacity = feed.producer.slots();
+        feed.push(&vec![0.0; capacity + 6]);
+        assert_eq!(feed.dropped_frames, 3);
+    }
+
+    type ErrorCallback = Box<dyn FnMut(Error) + Send>;
+
+    /// A backend whose streams never run. It keeps each stream's error callback so a test can
+    /// make the stream fail.
+    #[derive(Clone, Default)]
+    struct FailingBackend {
+        error_callbacks: Arc<Mutex<Vec<ErrorCallback>>>,
+    }
+
+    impl FailingBackend {
+        fn fail(&self, stream: usize, error: StreamError) {
+            (self.error_callbacks.lock().unwrap()[stream])(Error::AudioStream(error));
+        }
+
+        fn streams_opened(&self) -> usize {
+            self.error_callbacks.lock().unwrap().len()
+        }
+    }
+
+    struct IdleStream;
+
+    impl AudioStream for IdleStream {
+        fn play(&self) -> std::result::Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+
+        fn pause(&self) -> std::result::Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+    }
+
+    impl AudioBackend for FailingBackend {
+        type Stream = IdleStream;
+        type Device = ();
+        type Error = Error;
+
+        fn enumerate_output_devices(&self) -> Result<Vec<()>> {
+            Ok(vec![()])
+        }
+
+        fn enumerate_input_devices(&self) -> Result<Vec<()>> {
+            Ok(Vec::new())
+        }
+
+        fn default_output_device(&self) -> Option<()> {
+            Some(())
+        }
+
+        fn default_input_device(&self) -> Option<()> {
+            None
+        }
+
+        fn create_output_stream(
+            &self,
+            _device: &(),
+            _config: AudioConfig,
+            _data_callback: Box<dyn FnMut(&mut [f32]) + Send>,
+            error_callback: ErrorCallback,
+        ) -> Result<IdleStream> {
+            self.error_callbacks.lock().unwrap().push(error_callback);
+            Ok(IdleStream)
+        }
+
+        fn create_input_stream(
+            &self,
+            _device: &(),
+            _config: AudioConfig,
+            _data_callback: Box<dyn FnMut(&[f32]) + Send>,
+            _error_callback: ErrorCallback,
+        ) -> Result<IdleStream> {
+            Err(Error::AudioBackendError("no input".into()))
+        }
+    }
+
+    #[test]
+    fn stream_errors_reach_the_handler_and_a_lost_device_is_reopened() {
+        use crate::stream_monitor::AudioStreamErrorHandler;
+        use std::time::{Duration, Instant};
+
+        let backend = FailingBackend::default();
+        let monitor = Arc::new(StreamMonitor::new());
+        let (handler, errors) = AudioStreamErrorHandler::channel();
+        monitor.set_handler(Some(handler));
+        let reopen = backend.clone();
+        let _stream = open_reconnecting_output(
+            &backend,
+            AudioConfig::default(),
+            Box::new(|_| {}),
+            Arc::clone(&monitor),
+            move || Ok(reopen.clone()),
+            Duration::from_millis(10),
+        )
+        .unwrap();
+
+        let dropout = StreamError::Dropout {
+            missed_samples: 256,
+        };
+        backend.fail(0, dropout.clone());
+        backend.fail(0, StreamError::DeviceDisconnected);
+        let timeout = Duration::from_secs(5);
+        assert_eq!(errors.recv_timeout(timeout), Ok(dropout));
+        assert_eq!(
+            errors.recv_timeout(timeout),
+            Ok(StreamError::DeviceDisconnected)
+        );
+        assert_eq!(monitor.stats().dropout_count, 1);
+
+        let deadline = Instant::now() + timeout;
+        while backend.streams_opened() < 2 {
+            assert!(Instant::now() < deadline, "the output was never reopened");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        // The reopened stream reports to the same monitor.
+        backend.fail(1, StreamError::Overrun { frames: 32 });
+        assert_eq!(monitor.stats().xrun_count, 2);
+    }
+
     #[test]
     fn interleaves_two_channels() {
         // outputs[ch][frame]
@@ -3,8 +3,14 @@
 use crate::{
     audio::{AudioBuffers, AudioLevels},
     error::{Error, Result},
-    midi::{MidiChannel, MidiEvent},
-    parameters::{Parameter, ParameterUpdate},
+    midi::{
+        AftertouchBinding, CcBinding, ChannelPressureBinding, ChannelStrip, MidiChannel, MidiEvent,
+        PolyphonyLimiter, SoftTakeover, StealingStrategy, VelocityCurve,
+    },
+    parameters::{
+        Distribution, Parameter, ParameterChange, ParameterSnapshot, ParameterUpdate,
+        RandomizeConstraints, RandomizeResult, SliderCurve,
+    },
 };
 use crossbeam_queue::ArrayQueue;
 use std::sync::{Arc, Mutex};
@@ -48,7 +54,7 @@ impl OutputMidiConsumer {
 }
 
 /// Information about a VST3 plugin
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct PluginInfo {
     /// Full path to the VST3 bundle/file
     pub path: std::path::PathBuf,
@@ -58,8 +64,13 @@ pub struct PluginInfo {
     pub vendor: String,
     /// Plugin version
     pub version: String,
-    /// Plugin category (e.g., "Fx", "Instrument")
-    pub category: String,
+    /// Plugin category, one entry per level (e.g. `["Instrument", "Synth"]`); see
+    /// [`parse_category`](crate::discovery::parse_category)
+    #[serde(
+        alias = "category",
+        deserialize_with = "crate::discovery::deserialize_category_path"
+    )]
+    pub category_path: crate::discovery::CategoryPath,
     /// Unique plugin ID
     pub uid: String,
     /// Number of audio input buses
@@ -72,6 +83,68 @@ pub struct PluginInfo {
     pub has_midi_output: bool,
     /// Whether the plugin has a GUI
     pub has_gui: bool,
+    /// The plugin standard the bundle implements
+    #[serde(default)]
+    pub format: PluginFormat,
+    /// Paths of other copies of this plugin merged into this entry by
+    /// [`deduplicate_plugins`](crate::discovery::deduplicate_plugins)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<std::path::PathBuf>,
+}
+
+/// The plugin standard a [`PluginInfo`] describes.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum PluginFormat {
+    /// A Steinberg VST3 bundle (`.vst3`)
+    #[default]
+    Vst3,
+    /// A CLAP bundle (`.clap`), loaded with the `clap-support` feature
+    Clap,
+}
+
+impl std::fmt::Display for PluginFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PluginFormat::Vst3 => "VST3",
+            PluginFormat::Clap => "CLAP",
+        })
+    }
+}
+
+impl PluginInfo {
+    /// The category in VST3's `"Instrument|Synth"` form, for display
+    pub fn category(&self) -> String {
+        self.category_path.join("|")
+    }
+
+    /// A Markdown summary of this metadata: a heading with the plugin name and a field table.
+    ///
+    /// Pure data — nothing is loaded. For factory, class, bus and parameter sections use
+    /// [`Plugin::export_info_markdown`] or [`PluginReport::to_markdown`](crate::PluginReport::to_markdown).
+    pub fn to_markdown(&self) -> String {
+        use crate::discovery::markdown_cell as cell;
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        let mut md = format!("# {}\n\n", cell(&self.name));
+        md.push_str("| Field | Value |\n|---|---|\n");
+        let rows = [
+            ("Vendor", cell(&self.vendor)),
+            ("Version", cell(&self.version)),
+            ("Category", cell(&self.category())),
+            ("UID", cell(&self.uid)),
+            ("Path", cell(&self.path.to_string_lossy())),
+            ("Audio inputs", self.audio_inputs.to_string()),
+            ("Audio outputs", self.audio_outputs.to_string()),
+            ("MIDI input", yes_no(self.has_midi_input).to_string()),
+            ("MIDI output", yes_no(self.has_midi_output).to_string()),
+            ("Editor", yes_no(self.has_gui).to_string()),
+        ];
+        for (field, value) in rows {
+            md.push_str(&format!("| {field} | {value} |\n"));
+        }
+        md
+    }
 }
 
 /// A saved plugin preset: the plugin's identity plus its opaque state blob.
@@ -89,6 +162,25 @@ pub struct PluginPreset {
     pub state: Vec<u8>,
 }
 
+/// One value of a plugin's key/value state (VST3 `IAttributeList`), as returned by
+/// [`Plugin::save_state_as_attributes`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum AttributeValue {
+    /// Set with `setInt`.
+    Int(i64),
+    /// Set with `setFloat`.
+    Float(f64),
+    /// Set with `setString` (UTF-16 on the plugin side).
+    String(String),
+    /// Set with `setBinary`.
+    Binary(Vec<u8>),
+}
+
+/// The key under which [`Plugin::save_state_as_attributes`] keeps the bytes the plugin wrote
+/// to the state stream itself, as an [`AttributeValue::Binary`]. Most plugins write all
+/// their state there; this keeps attribute state lossless for them too.
+pub const STATE_STREAM_ATTRIBUTE: &str = "vst3-host.stream";
+
 /// A plugin unit (from `IUnitInfo`) and its program list, if any.
 ///
 /// Units form a hierarchy (via [`parent_id`](Self::parent_id)); a unit may carry a named
@@ -101,10 +193,28 @@ pub struct PluginUnit {
     pub parent_id: i32,
     /// Unit display name.
     pub name: String,
+    /// Id of this unit's program list, or `-1` (`kNoProgramListId`) if it has none. Pass it to
+    /// [`Plugin::get_programs`] for the programs' ids.
+    #[serde(default = "no_program_list")]
+    pub program_list_id: i32,
     /// Program names in this unit's program list (empty if the unit has none).
     pub programs: Vec<String>,
 }
 
+/// `kNoProgramListId`, for units serialized before [`PluginUnit::program_list_id`] existed.
+fn no_program_list() -> i32 {
+    -1
+}
+
+/// One program (preset) in a plugin program list, from [`Plugin::get_programs`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProgramInfo {
+    /// Program index within its list, as [`Plugin::select_program`] takes it.
+    pub id: i32,
+    /// Program display name.
+    pub name: String,
+}
+
 /// What kind of parameter-edit gesture event a plugin's editor reported.
 ///
 /// VST3 editors bracket a user gesture with `beginEdit`/`endEdit` (e.g. mouse-down /
@@ -151,6 +261,106 @@ pub enum ProcessMode {
     Offline,
 }
 
+/// Initialization steps to skip when loading a plugin, for plugins that crash or hang in
+/// one of them (WaveShell, some iZotope products), plus [`Self::REQUIRE_CONTROLLER`] to be
+/// strict about the edit controller instead. Combine flags with `|`; the default skips
+/// nothing. Set them per plugin with
+/// [`Vst3Host::set_plugin_init_flags`](crate::Vst3Host::set_plugin_init_flags).
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize,
+)]
+#[serde(transparent)]
+pub struct PluginInitFlags(u32);
+
+impl PluginInitFlags {
+    /// Don't create (or look for) the edit controller. The plugin then has no parameters
+    /// and no editor, but can still process audio and MIDI.
+    pub const SKIP_CONTROLLER_INIT: Self = Self(1);
+    /// Don't probe for an editor by creating a throwaway view at load.
+    pub const SKIP_GUI_CHECK: Self = Self(1 << 1);
+    /// Don't push restored component state to the controller (`setComponentState`) when
+    /// loading a state blob.
+    pub const SKIP_STATE_TRANSFER: Self = Self(1 << 2);
+    /// Don't activate event and audio buses; keep the plugin's default bus activation.
+    pub const SKIP_BUS_NEGOTIATION: Self = Self(1 << 3);
+    /// Refuse to load the plugin if it ends up without an edit controller, instead of loading
+    /// it for audio only.
+    pub const REQUIRE_CONTROLLER: Self = Self(1 << 4);
+
+    /// Every flag with its name, in bit order.
+    pub const ALL: [(Self, &'static str); 5] = [
+        (Self::SKIP_CONTROLLER_INIT, "SKIP_CONTROLLER_INIT"),
+        (Self::SKIP_GUI_CHECK, "SKIP_GUI_CHECK"),
+        (Self::SKIP_STATE_TRANSFER, "SKIP_STATE_TRANSFER"),
+        (Self::SKIP_BUS_NEGOTIATION, "SKIP_BUS_NEGOTIATION"),
+        (Self::REQUIRE_CONTROLLER, "REQUIRE_CONTROLLER"),
+    ];
+
+    /// No flags: initialize everything.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Whether no flag is set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether every flag in `other` is set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Set or clear the flags in `other`.
+    pub fn set(&mut self, other: Self, on: bool) {
+        if on {
+            self.0 |= other.0;
+        } else {
+            self.0 &= !other.0;
+        }
+    }
+}
+
+impl std::ops::BitOr for PluginInitFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for PluginInitFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// A step of loading a plugin, as recorded in a [load
+/// journal](crate::Vst3HostBuilder::load_journal) so a crash can be traced to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum LoadPhase {
+    /// Loading the module and creating the component
+    Component,
+    /// Activating event and audio buses
+    BusNegotiation,
+    /// Creating, initializing and connecting the edit controller
+    ControllerInit,
+    /// Probing for an editor view
+    GuiCheck,
+}
+
+impl LoadPhase {
+    /// The flag that skips this phase, if it can be skipped.
+    pub fn skip_flag(self) -> Option<PluginInitFlags> {
+        match self {
+            LoadPhase::Component => None,
+            LoadPhase::BusNegotiation => Some(PluginInitFlags::SKIP_BUS_NEGOTIATION),
+            LoadPhase::ControllerInit => Some(PluginInitFlags::SKIP_CONTROLLER_INIT),
+            LoadPhase::GuiCheck => Some(PluginInitFlags::SKIP_GUI_CHECK),
+        }
+    }
+}
+
 /// VST3 plugin instance
 #[allow(clippy::type_complexity)] // callback fields are Box<dyn Fn...>; intrinsic to the API
 pub struct Plugin {
@@ -164,6 +374,44 @@ pub struct Plugin {
     pub(crate) audio_levels: Arc<Mutex<AudioLevels>>,
     pub(crate) parameter_change_callback: Option<Box<dyn Fn(u32, f64) + Send + 'static>>,
     pub(crate) audio_callback: Option<Box<dyn Fn(&AudioLevels) + Send + 'static>>,
+    /// Pressure-to-parameter mappings applied by [`Plugin::process_aftertouch`].
+    pub(crate) aftertouch_bindings: Vec<AftertouchBinding>,
+    pub(crate) channel_pressure_bindings: Vec<ChannelPressureBinding>,
+    /// Only MIDI on this channel reaches the plugin (see [`Plugin::set_midi_channel_filter`]).
+    pub(crate) midi_channel_filter: Option<MidiChannel>,
+    /// Output health checks run on every processed block ([`Plugin::output_analysis`]).
+    pub(crate) output_analyzer: Option<crate::audio::OutputAnalyzer>,
+    /// Runs the plugin at a multiple of the host rate ([`Plugin::oversampling_factor`]).
+    pub(crate) oversampler: Option<crate::audio::Oversampler>,
+    /// Voice cap applied to outgoing notes ([`Plugin::set_polyphony_limit`]).
+    pub(crate) polyphony_limiter: Option<PolyphonyLimiter>,
+    /// Parameters that were off their factory defaults right after loading
+    /// ([`Plugin::factory_default_mismatches`]).
+    pub(crate) factory_default_mismatches: Vec<crate::parameters::ParameterMismatch>,
+    /// Per-channel MIDI transforms, at most one per channel ([`Plugin::set_channel_strip`]).
+    pub(crate) channel_strips: Vec<ChannelStrip>,
+    /// Pickup state for CC bindings, when enabled ([`Plugin::enable_soft_takeover`]).
+    pub(crate) soft_takeover: Option<SoftTakeover>,
+    /// Edits held back by [`Plugin::set_parameter_debounced`], when on
+    /// ([`Plugin::set_debounce_delay`]).
+    pub(crate) debouncer: Option<crate::parameters::ChangeDebouncer>,
+    /// Note-on velocity mapping, when one is set ([`Plugin::set_velocity_curve`]).
+    pub(crate) velocity_curve: Option<VelocityCurve>,
+    /// Output statistics published after every block ([`crate::Vst3Host::block_statistics`]).
+    pub(crate) block_statistics: Option<crate::analysis::BlockStatisticsTap>,
+    /// Output spectrum fed after every block ([`crate::Vst3Host::spectrum`]).
+    pub(crate) spectrum: Option<crate::analysis::SpectrumTap>,
+    /// Slider mappings per parameter id, detected on first use or set ([`Plugin::slider_curve`]).
+    pub(crate) slider_curves: std::collections::HashMap<u32, SliderCurve>,
+    /// Buses switched on or off through [`Plugin::set_bus_active`], by media type, direction
+    /// and index.
+    pub(crate) bus_activation:
+        std::collections::HashMap<(crate::audio::MediaType, crate::audio::BusDirection, i32), bool>,
+    /// Sustain pedal state and the notes it holds ([`Plugin::release_sustained_notes`]).
+    pub(crate) sustain: crate::midi::SustainTracker,
+    /// Compatibility workarounds still to apply when processing first starts
+    /// ([`crate::compatibility`]).
+    pub(crate) pending_workarounds: Vec<crate::compatibility::Workaround>,
 
     // These will be populated by the actual implementation
     pub(crate) internal: Option<Box<dyn PluginInternal>>,
@@ -181,6 +429,27 @@ pub(crate) trait PluginInternal: Send {
     fn get_parameter(&self, id: u32) -> Result<f64>;
     fn get_all_parameters(&self) -> Result<Vec<Parameter>>;
     fn format_parameter(&self, id: u32, normalized: f64) -> Result<String>;
+    /// Parse a display string into a normalized value (`IEditController::getParamValueByString`).
+    /// Defaults to unsupported.
+    fn parse_parameter(&self, _id: u32, _text: &str) -> Result<f64> {
+        Err(Error::Other(
+            "parameter string parsing is not supported for this plugin".to_string(),
+        ))
+    }
+    /// Map a normalized value to the parameter's plain (real-world) value
+    /// (`IEditController::normalizedParamToPlain`). Defaults to unsupported.
+    fn normalized_to_plain(&self, _id: u32, _normalized: f64) -> Result<f64> {
+        Err(Error::Other(
+            "plain value conversion is not supported for this plugin".to_string(),
+        ))
+    }
+    /// Map a plain value to normalized (`IEditController::plainParamToNormalized`). Defaults
+    /// to unsupported.
+    fn plain_to_normalized(&self, _id: u32, _plain: f64) -> Result<f64> {
+        Err(Error::Other(
+            "plain value conversion is not supported for this plugin".to_string(),
+        ))
+    }
     fn process(&mut self, buffers: &mut AudioBuffers) -> Result<()>;
     /// Re-run `setupProcessing` for a new sample rate / block size. Defaults to unsupported
     /// for implementations that don't support it.
@@ -226,6 +495,24 @@ pub(crate) trait PluginInternal: Send {
             "bus activation is not supported for this plugin".to_string(),
         ))
     }
+    /// Re-query the audio bus layout cached at load and lay out buffers from the new one.
+    /// Defaults to unsupported.
+    fn refresh_bus_layout(&mut self) -> Result<()> {
+        Err(Error::Other(
+            "bus layout refresh is not supported for this plugin".to_string(),
+        ))
+    }
+    /// Number of buses of `media_type` in `direction` (`IComponent::getBusCount`). Defaults to
+    /// unsupported.
+    fn bus_count(
+        &self,
+        _media_type: crate::audio::MediaType,
+        _direction: crate::audio::BusDirection,
+    ) -> Result<i32> {
+        Err(Error::Other(
+            "bus count query is not supported for this plugin".to_string(),
+        ))
+    }
     /// Update the transport tempo (BPM) advertised in the host `ProcessContext`, taking effect
     /// on the next processed block. The caller validates `bpm > 0`. Defaults to unsupported
     /// (overridden by the in-process and isolated implementations).
@@ -242,6 +529,13 @@ pub(crate) trait PluginInternal: Send {
             "runtime transport mutation is not supported for this plugin".to_string(),
         ))
     }
+    /// Move the transport playhead (`continousTimeSamples`/`projectTimeSamples`) in the host
+    /// `ProcessContext` to `samples` for the next processed block. Defaults to unsupported.
+    fn set_transport_position(&mut self, _samples: i64) -> Result<()> {
+        Err(Error::Other(
+            "runtime transport mutation is not supported for this plugin".to_string(),
+        ))
+    }
     /// Toggle the transport playing state (`kPlaying`) in the host `ProcessContext`, taking
     /// effect on the next processed block. Defaults to unsupported.
     fn set_playing(&mut self, _playing: bool) -> Result<()> {
@@ -256,6 +550,13 @@ pub(crate) trait PluginInternal: Send {
     fn send_midi_event_at(&mut self, event: MidiEvent, _sample_offset: i32) -> Result<()> {
         self.send_midi_event(event)
     }
+    /// Queue a framed SysEx message (`0xF0 ... 0xF7`) for the next process block. Defaults to
+    /// unsupported.
+    fn send_sysex(&mut self, _data: &[u8]) -> Result<()> {
+        Err(Error::Other(
+            "SysEx delivery is not supported for this plugin".to_string(),
+        ))
+    }
     /// Start a note and return a per-voice [`NoteId`] for targeting note-expression. Default:
     /// unsupported, for implementations that don't support per-note expression.
     fn note_on(
@@ -299,10 +600,49 @@ pub(crate) trait PluginInternal: Send {
     fn start_processing(&mut self) -> Result<()>;
     fn stop_processing(&mut self) -> Result<()>;
     fn has_editor(&self) -> bool;
-    fn open_editor(&mut self, parent: *mut std::ffi::c_void) -> Result<()>;
+    /// Whether the plugin has an edit controller. Defaults to true for implementations that
+    /// can't tell; the calls that need a controller still fail without one.
+    fn has_controller(&self) -> bool {
+        true
+    }
+    fn open_editor(
+        &mut self,
+        parent: *mut std::ffi::c_void,
+        platform_type: &'static std::ffi::CStr,
+    ) -> Result<()>;
+    /// Whether the plugin's editor view accepts `platform_type` (`IPlugView::isPlatformTypeSupported`).
+    fn is_platform_type_supported(&self, _platform_type: &std::ffi::CStr) -> Result<bool> {
+        Err(Error::Other(
+            "Platform type queries are not supported for this plugin".to_string(),
+        ))
+    }
     fn close_editor(&mut self) -> Result<()>;
     fn get_editor_size(&self) -> Result<(i32, i32)>;
+    /// The size nearest to `width`×`height` the editor accepts
+    /// (`IPlugView::checkSizeConstraint`). Defaults to accepting any size.
+    fn check_editor_size_constraint(&self, width: i32, height: i32) -> Result<(i32, i32)> {
+        Ok((width, height))
+    }
+    /// The keyboard shortcuts the editor handles. VST3 has no interface for listing them (the
+    /// view only receives `onKeyDown`/`onKeyUp`), so this defaults to none.
+    fn key_shortcuts(&self) -> Result<Vec<KeyShortcut>> {
+        Ok(Vec::new())
+    }
+    /// Tell the open editor its area is now `width`×`height` (`IPlugView::onSize`). Defaults
+    /// to unsupported.
+    fn resize_editor(&mut self, _width: i32, _height: i32) -> Result<()> {
+        Err(Error::Other(
+            "editor resizing is not supported for this plugin".to_string(),
+        ))
+    }
     fn get_parameter_changes(&self) -> Vec<(u32, f64)>;
+    /// Forward the editor's parameter changes to a receiver as they happen. Defaults to `None`
+    /// for implementations that only report them by polling.
+    fn subscribe_parameter_changes(
+        &mut self,
+    ) -> Option<std::sync::mpsc::Receiver<ParameterChange>> {
+        None
+    }
     /// Drain the ordered parameter-edit gesture log (begin/change/end) the plugin's editor
     /// reported since the last call. Defaults to empty for implementations that don't capture
     /// gestures.
@@ -319,11 +659,22 @@ pub(crate) trait PluginInternal: Send {
     fn output_midi_handle(&self) -> Option<OutputMidiConsumer> {
         None
     }
+    /// Subscribe to the plugin's captured console output. Defaults to `None` for
+    /// implementations that share the host's stdout/stderr (in-process).
+    fn log_lines(&self) -> Option<std::sync::mpsc::Receiver<String>> {
+        None
+    }
     /// Enumerate the plugin's units and their program lists (`IUnitInfo`). Defaults to empty
     /// for implementations that don't query it.
     fn get_units(&self) -> Result<Vec<PluginUnit>> {
         Ok(Vec::new())
     }
+    /// List the programs in program list `program_list_id`. Defaults to unsupported.
+    fn get_programs(&self, _program_list_id: i32) -> Result<Vec<ProgramInfo>> {
+        Err(Error::Other(
+            "program lists are not supported for this plugin".to_string(),
+        ))
+    }
     /// Select a program in a unit's program list. Defaults to unsupported (e.g. plugins
     /// without `IUnitInfo`); implementations resolve the unit's program-change parameter and
     /// set it to the index's normalized value.
@@ -357,6 +708,18 @@ pub(crate) trait PluginInternal: Send {
             "state save/restore is not supported".to_string(),
         ))
     }
+    /// Serialize the plugin's state through a stream that also offers an attribute list,
+    /// returning the attributes plus the stream bytes under [`STATE_STREAM_ATTRIBUTE`].
+    fn save_state_attributes(&self) -> Result<std::collections::HashMap<String, AttributeValue>> {
+        Err(Error::Other("attribute state is not supported".to_string()))
+    }
+    /// Restore the plugin's state from attributes returned by [`Self::save_state_attributes`].
+    fn load_state_attributes(
+        &mut self,
+        _attrs: std::collections::HashMap<String, AttributeValue>,
+    ) -> Result<()> {
+        Err(Error::Other("attribute state is not supported".to_string()))
+    }
     /// OS process id of the isolated helper, if this plugin runs out-of-process.
     fn helper_pid(&self) -> Option<u32> {
         None
@@ -373,6 +736,13 @@ pub(crate) trait PluginInternal: Send {
             "recovery is only supported for process-isolated plugins".to_string(),
         ))
     }
+    /// Replace the plugin instance with a freshly loaded one from the same path and audio
+    /// settings, without restoring any state. Processing resumes if it was running.
+    fn factory_reset(&mut self) -> Result<()> {
+        Err(Error::Other(
+            "factory reset is not supported for this plugin".to_string(),
+        ))
+    }
     /// The size the plugin's editor has requested (via `IPlugFrame`) since the last poll.
     fn take_editor_resize_request(&self) -> Option<(i32, i32)> {
         None
@@ -383,12 +753,46 @@ pub(crate) trait PluginInternal: Send {
     }
 }
 
+/// A bus index as the SDK's `int32`.
+fn bus_index_i32(index: usize) -> Result<i32> {
+    i32::try_from(index).map_err(|_| Error::InvalidParameter(format!("bus index {index}")))
+}
+
 impl Plugin {
     /// Get plugin information
     pub fn info(&self) -> &PluginInfo {
         &self.info
     }
 
+    /// A Markdown document describing this plugin, for documentation: name/vendor/version,
+    /// factory info, class ids, bus topology and a parameter table.
+    ///
+    /// Factory, class and bus details come from [`get_detailed_plugin_info`](crate::get_detailed_plugin_info)
+    /// on the plugin's bundle; if that fails those sections are left empty rather than
+    /// failing the export.
+    pub fn export_info_markdown(&self) -> String {
+        let detailed = match crate::discovery::get_detailed_plugin_info(&self.info.path) {
+            Ok(detailed) => crate::discovery::DetailedPluginInfo {
+                info: self.info.clone(),
+                ..detailed
+            },
+            Err(e) => {
+                log::debug!(
+                    "export_info_markdown: no introspection for {}: {e}",
+                    self.info.name
+                );
+                crate::discovery::DetailedPluginInfo {
+                    info: self.info.clone(),
+                    factory: Default::default(),
+                    classes: Vec::new(),
+                    buses: Default::default(),
+                }
+            }
+        };
+        let parameters = self.get_parameters().unwrap_or_default();
+        crate::discovery::PluginReport::new(detailed, parameters).to_markdown()
+    }
+
     /// The sample rate (Hz) this plugin was configured with at load.
     pub fn sample_rate(&self) -> f64 {
         self.sample_rate
@@ -399,6 +803,14 @@ impl Plugin {
         self.block_size
     }
 
+    /// How many times the host rate the plugin runs at
+    /// ([`Vst3HostBuilder::oversampling_factor`](crate::Vst3HostBuilder::oversampling_factor));
+    /// 1 when not oversampled. [`Self::sample_rate`] and [`Self::block_size`] are host-rate
+    /// values either way.
+    pub fn oversampling_factor(&self) -> u32 {
+        self.oversampler.as_ref().map_or(1, |o| o.factor())
+    }
+
     /// Reconfigure the plugin for a new sample rate and/or maximum block size, re-running the
     /// plugin's `setupProcessing` and rebuilding its audio buffers.
     ///
@@ -425,10 +837,11 @@ impl Plugin {
             )));
         }
 
+        let factor = self.oversampling_factor() as usize;
         self.internal
             .as_mut()
             .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
-            .reconfigure(sample_rate, block_size)?;
+            .reconfigure(sample_rate * factor as f64, block_size * factor)?;
 
         self.sample_rate = sample_rate;
         self.block_size = block_size;
@@ -489,6 +902,27 @@ impl Plugin {
             .set_bus_arrangements(inputs, outputs)
     }
 
+    /// Re-query the plugin's audio bus layout.
+    ///
+    /// The bus counts and channel counts are read once at load and cached, so starting
+    /// processing lays out buffers without asking the plugin again. Call this when the plugin
+    /// changes its buses on its own, e.g. after it signals `kIoChanged` through
+    /// `restartComponent` (in-process plugins also pick that signal up themselves at the next
+    /// [`Self::start_processing`]). Errors while processing, and under process isolation,
+    /// where the helper owns the layout.
+    pub fn refresh_bus_layout(&mut self) -> Result<()> {
+        if self.is_processing {
+            return Err(Error::Other(
+                "cannot refresh the bus layout while processing; call stop_processing() first"
+                    .to_string(),
+            ));
+        }
+        self.internal
+            .as_mut()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .refresh_bus_layout()
+    }
+
     /// Activate or deactivate a single bus on the plugin (`IComponent::activateBus`).
     ///
     /// Hosts must explicitly activate the buses they intend to use; a plugin's secondary
@@ -521,7 +955,172 @@ impl Plugin {
         self.internal
             .as_mut()
             .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
-            .set_bus_active(media_type, direction, bus_index, active)
+            .set_bus_active(media_type, direction, bus_index, active)?;
+        self.bus_activation
+            .insert((media_type, direction, bus_index), active);
+        Ok(())
+    }
+
+    /// Whether a bus is active. Buses not changed through [`Self::set_bus_active`] report
+    /// active, as the load sequence activates them all.
+    pub fn is_bus_active(
+        &self,
+        media_type: crate::audio::MediaType,
+        direction: crate::audio::BusDirection,
+        bus_index: i32,
+    ) -> bool {
+        self.bus_activation
+            .get(&(media_type, direction, bus_index))
+            .copied()
+            .unwrap_or(true)
+    }
+
+    /// Activate bus `index` of `media_type` in `direction`: [`Self::set_bus_active`] with
+    /// `true`.
+    pub fn activate_bus(
+        &mut self,
+        media_type: crate::audio::MediaType,
+        direction: crate::audio::BusDirection,
+        index: usize,
+    ) -> Result<()> {
+        self.set_bus_active(media_type, direction, bus_index_i32(index)?, true)
+    }
+
+    /// Deactivate bus `index` of `media_type` in `direction`, e.g. an unused multi-out, so the
+    /// plugin can skip rendering it. The host then passes the bus no channel buffers, which
+    /// also spares the memory writes for it. Like [`Self::set_bus_active`] this must happen
+    /// while the plugin isn't processing; the next [`Self::start_processing`] lays out the
+    /// buffers without it.
+    pub fn deactivate_bus(
+        &mut self,
+        media_type: crate::audio::MediaType,
+        direction: crate::audio::BusDirection,
+        index: usize,
+    ) -> Result<()> {
+        self.set_bus_active(media_type, direction, bus_index_i32(index)?, false)
+    }
+
+    /// How many buses of `media_type` in `direction` are active.
+    pub fn active_bus_count(
+        &self,
+        media_type: crate::audio::MediaType,
+        direction: crate::audio::BusDirection,
+    ) -> usize {
+        (0..self.bus_count(media_type, direction))
+            .filter(|&i| self.is_bus_active(media_type, direction, i as i32))
+            .count()
+    }
+
+    /// Number of buses of `media_type` in `direction`. Falls back to [`PluginInfo`]'s counts
+    /// (one event bus per supported MIDI direction) when the plugin can't be asked.
+    fn bus_count(
+        &self,
+        media_type: crate::audio::MediaType,
+        direction: crate::audio::BusDirection,
+    ) -> usize {
+        use crate::audio::{BusDirection, MediaType};
+        if let Some(Ok(count)) = self
+            .internal
+            .as_ref()
+            .map(|i| i.bus_count(media_type, direction))
+        {
+            return count.max(0) as usize;
+        }
+        match (media_type, direction) {
+            (MediaType::Audio, BusDirection::Input) => self.info.audio_inputs as usize,
+            (MediaType::Audio, BusDirection::Output) => self.info.audio_outputs as usize,
+            (MediaType::Event, BusDirection::Input) => self.info.has_midi_input as usize,
+            (MediaType::Event, BusDirection::Output) => self.info.has_midi_output as usize,
+        }
+    }
+
+    /// Snapshot which buses are active and the audio buses' speaker arrangements, to restore
+    /// later with [`Self::apply_bus_configuration`].
+    pub fn save_bus_configuration(&self) -> crate::audio::BusConfiguration {
+        use crate::audio::{BusDirection, MediaType};
+        let active = |media_type, direction| -> Vec<bool> {
+            (0..self.bus_count(media_type, direction))
+                .map(|i| self.is_bus_active(media_type, direction, i as i32))
+                .collect()
+        };
+        let speaker_arrangements = self
+            .bus_arrangements()
+            .map(|a| a.inputs.into_iter().chain(a.outputs).collect())
+            .unwrap_or_default();
+        crate::audio::BusConfiguration {
+            active_audio_inputs: active(MediaType::Audio, BusDirection::Input),
+            active_audio_outputs: active(MediaType::Audio, BusDirection::Output),
+            active_event_inputs: active(MediaType::Event, BusDirection::Input),
+            active_event_outputs: active(MediaType::Event, BusDirection::Output),
+            speaker_arrangements,
+        }
+    }
+
+    /// Restore a configuration from [`Self::save_bus_configuration`]: request its speaker
+    /// arrangements (if it has them and they differ), then activate or deactivate each bus
+    /// whose state differs.
+    ///
+    /// Like [`Self::set_bus_active`] this must happen while the plugin isn't processing.
+    /// Errors if the configuration's bus counts don't match this plugin's.
+    pub fn apply_bus_configuration(
+        &mut self,
+        config: &crate::audio::BusConfiguration,
+    ) -> Result<()> {
+        use crate::audio::{BusDirection, MediaType};
+        if self.is_processing {
+            return Err(Error::Other(
+                "cannot change the bus configuration while processing; call stop_processing() \
+                 first"
+                    .to_string(),
+            ));
+        }
+        let groups = [
+            (
+                MediaType::Audio,
+                BusDirection::Input,
+                &config.active_audio_inputs,
+            ),
+            (
+                MediaType::Audio,
+                BusDirection::Output,
+                &config.active_audio_outputs,
+            ),
+            (
+                MediaType::Event,
+                BusDirection::Input,
+                &config.active_event_inputs,
+            ),
+            (
+                MediaType::Event,
+                BusDirection::Output,
+                &config.active_event_outputs,
+            ),
+        ];
+        for (media_type, direction, active) in groups {
+            let count = self.bus_count(media_type, direction);
+            if active.len() != count {
+                return Err(Error::InvalidParameter(format!(
+                    "bus configuration has {} {media_type:?} {direction:?} buses, the plugin \
+                     has {count}",
+                    active.len()
+                )));
+            }
+        }
+
+        if let Some(wanted) = config.bus_arrangements() {
+            if self.bus_arrangements().ok().as_ref() != Some(&wanted) {
+                self.set_bus_arrangements(&wanted.inputs, &wanted.outputs)?;
+            }
+        }
+        for (media_type, direction, active) in groups {
+            for (index, &active) in active.iter().enumerate() {
+                let index = index as i32;
+                if self.is_bus_active(media_type, direction, index) != active {
+                    self.set_bus_active(media_type, direction, index, active)?;
+                }
+            }
+        }
+        Ok(())
     }
 
     /// Get all parameters
@@ -532,6 +1131,87 @@ impl Plugin {
             .get_all_parameters()
     }
 
+    /// Compare every parameter's current value with its factory default and list those more
+    /// than [`FACTORY_DEFAULT_TOLERANCE`] apart. Read-only parameters (meters and the like)
+    /// are skipped. Returns an empty list if the parameters can't be read.
+    ///
+    /// [`FACTORY_DEFAULT_TOLERANCE`]: crate::parameters::FACTORY_DEFAULT_TOLERANCE
+    pub fn verify_factory_defaults(&self) -> Vec<crate::parameters::ParameterMismatch> {
+        self.get_parameters()
+            .unwrap_or_default()
+            .iter()
+            .filter(|p| !p.is_read_only)
+            .filter_map(crate::parameters::ParameterMismatch::of)
+            .collect()
+    }
+
+    /// The parameters that were off their factory defaults right after loading, before any
+    /// state or preset was applied — a plugin that doesn't start at its own defaults (e.g.
+    /// it picked up a machine-specific setting) sounds different from one machine to the next.
+    /// The host logs each at warn level when it loads the plugin.
+    pub fn factory_default_mismatches(&self) -> Vec<crate::parameters::ParameterMismatch> {
+        self.factory_default_mismatches.clone()
+    }
+
+    /// What the plugin displays at each step of `param` (`getParamStringByValue` at
+    /// `k / step_count`), e.g. `["Sine", "Saw", "Square"]`; the step number where it can't
+    /// say. Empty for continuous parameters.
+    pub fn step_labels(&self, param: &crate::parameters::Parameter) -> Vec<String> {
+        let steps = param.step_count.max(0);
+        (0..=steps)
+            .filter(|_| steps > 0)
+            .map(|step| {
+                self.format_parameter(param.id, f64::from(step) / f64::from(steps))
+                    .unwrap_or_else(|_| step.to_string())
+            })
+            .collect()
+    }
+
+    /// Guess a [`SliderCurve`] for every continuous, writable parameter from how the plugin
+    /// displays it at 0.0, 0.5 and 1.0 (see [`SliderCurve::detect`]). Only the non-linear ones
+    /// are returned. This formats three values per parameter, so the host doesn't run it on
+    /// load; [`Self::slider_curve`] detects one parameter's curve when first asked for it.
+    pub fn detect_slider_curves(&self) -> std::collections::HashMap<u32, SliderCurve> {
+        self.get_parameters()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|p| Some((p.id, self.detect_slider_curve(p)?)))
+            .collect()
+    }
+
+    /// The non-linear curve `param` displays with, if it's continuous and writable.
+    fn detect_slider_curve(&self, param: &Parameter) -> Option<SliderCurve> {
+        if param.is_read_only || param.step_count != 0 {
+            return None;
+        }
+        let display = |v| self.format_parameter(param.id, v).ok();
+        let curve = SliderCurve::detect(&display(0.0)?, &display(0.5)?, &display(1.0)?);
+        (!matches!(curve, SliderCurve::Linear)).then_some(curve)
+    }
+
+    /// The curve a slider for parameter `id` should use: map the slider position through
+    /// [`SliderCurve::apply`] when writing and the value through [`SliderCurve::inverse`]
+    /// when drawing. Unless one was set, it's detected the first time it's asked for (as
+    /// [`Self::detect_slider_curves`] does) and remembered; [`SliderCurve::Linear`] if none is.
+    pub fn slider_curve(&mut self, id: u32) -> &SliderCurve {
+        if !self.slider_curves.contains_key(&id) {
+            let curve = self
+                .get_parameters()
+                .unwrap_or_default()
+                .iter()
+                .find(|p| p.id == id)
+                .and_then(|p| self.detect_slider_curve(p))
+                .unwrap_or(SliderCurve::Linear);
+            self.slider_curves.insert(id, curve);
+        }
+        &self.slider_curves[&id]
+    }
+
+    /// Override the slider curve for parameter `id`, detected or not.
+    pub fn set_slider_curve(&mut self, id: u32, curve: SliderCurve) {
+        self.slider_curves.insert(id, curve);
+    }
+
     /// Set a parameter value by ID
     pub fn set_parameter(&mut self, id: u32, value: f64) -> Result<()> {
         if !(0.0..=1.0).contains(&value) {
@@ -545,6 +1225,10 @@ impl Plugin {
             .as_mut()
             .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
             .set_parameter(id, value)?;
+        // A direct write (automation, a preset) wins over an edit still held back.
+        if let Some(debouncer) = &mut self.debouncer {
+            debouncer.cancel(id);
+        }
 
         // Trigger callback if set
         if let Some(ref callback) = self.parameter_change_callback {
@@ -554,6 +1238,56 @@ impl Plugin {
         Ok(())
     }
 
+    /// Set a parameter from a UI edit such as a slider drag. With a debounce delay
+    /// ([`Self::set_debounce_delay`]) the value is held until the parameter goes that long
+    /// without another edit, and [`Self::apply_debounced_parameters`] (called every UI frame)
+    /// then applies it; without one it's applied right away, like [`Self::set_parameter`].
+    pub fn set_parameter_debounced(&mut self, id: u32, value: f64) -> Result<()> {
+        match &mut self.debouncer {
+            Some(debouncer) if (0.0..=1.0).contains(&value) => {
+                debouncer.push(id, value);
+                Ok(())
+            }
+            _ => self.set_parameter(id, value),
+        }
+    }
+
+    /// Apply the edits [`Self::set_parameter_debounced`] held back that are now due, and
+    /// return how many there were. Call it every UI frame while debouncing is on.
+    pub fn apply_debounced_parameters(&mut self) -> Result<usize> {
+        let due = self
+            .debouncer
+            .as_mut()
+            .map(|debouncer| debouncer.tick())
+            .unwrap_or_default();
+        for &(id, value) in &due {
+            self.set_parameter(id, value)?;
+        }
+        Ok(due.len())
+    }
+
+    /// Hold back [`Self::set_parameter_debounced`] edits until a parameter goes `ms`
+    /// milliseconds without another ([`DEFAULT_DEBOUNCE_MS`](crate::parameters::DEFAULT_DEBOUNCE_MS)
+    /// suits a UI drawn at 60 Hz). Zero or less turns debouncing off, applying any edits
+    /// still held. Writes through [`Self::set_parameter`], such as automation, are never held.
+    pub fn set_debounce_delay(&mut self, ms: f64) -> Result<()> {
+        if ms > 0.0 {
+            self.debouncer
+                .get_or_insert_with(crate::parameters::ChangeDebouncer::default)
+                .delay_ms = ms;
+            return Ok(());
+        }
+        for (id, value) in self
+            .debouncer
+            .take()
+            .map(|mut debouncer| debouncer.flush())
+            .unwrap_or_default()
+        {
+            self.set_parameter(id, value)?;
+        }
+        Ok(())
+    }
+
     /// Set a parameter value at a specific sample offset within the next process block.
     ///
     /// This is the sample-accurate building block for automation: call it once per
@@ -572,6 +1306,7 @@ impl Plugin {
                 value
             )));
         }
+        let sample_offset = self.oversampled_offset(sample_offset);
         self.internal
             .as_mut()
             .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
@@ -635,10 +1370,28 @@ impl Plugin {
             .set_playing(playing)
     }
 
+    /// Move the transport playhead advertised to the plugin in the host `ProcessContext` to
+    /// `samples` (both `continousTimeSamples` and `projectTimeSamples`, with the musical
+    /// playhead following), taking effect on the **next** processed block. The playhead keeps
+    /// advancing from there on its own.
+    ///
+    /// Used to keep several plugins on one [`TransportClock`](crate::audio::TransportClock).
+    /// Has no effect until processing has started. Works both in-process and across process
+    /// isolation.
+    pub fn set_transport_position(&mut self, samples: i64) -> Result<()> {
+        self.internal
+            .as_mut()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .set_transport_position(samples)
+    }
+
     /// Enumerate the plugin's units and their program lists (`IUnitInfo`).
     ///
     /// Returns an empty list for plugins that don't implement `IUnitInfo`. The root unit (id
     /// `0`) is typically present. Works both in-process and across process isolation.
+    /// [`Parameter::unit_id`](crate::parameters::Parameter::unit_id) places each parameter in
+    /// this tree.
+    #[doc(alias = "get_unit_info")]
     pub fn get_units(&self) -> Result<Vec<PluginUnit>> {
         self.internal
             .as_ref()
@@ -646,6 +1399,19 @@ impl Plugin {
             .get_units()
     }
 
+    /// List the programs in the program list `program_list_id` (a
+    /// [`PluginUnit::program_list_id`]), in list order.
+    ///
+    /// # Errors
+    ///
+    /// If the plugin doesn't implement `IUnitInfo` or has no program list with that id.
+    pub fn get_programs(&self, program_list_id: i32) -> Result<Vec<ProgramInfo>> {
+        self.internal
+            .as_ref()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .get_programs(program_list_id)
+    }
+
     /// Select a program (preset) in a unit's program list (`IUnitInfo`).
     ///
     /// `unit_id` is a [`PluginUnit::id`] from [`get_units`](Self::get_units) (the root unit is
@@ -670,11 +1436,19 @@ impl Plugin {
     /// oversampling), via `IAudioProcessor::getLatencySamples`. Use it to delay-compensate
     /// when aligning the plugin's output with other signals. `0` if it reports none. Works
     /// both in-process and across process isolation.
+    ///
+    /// When oversampled, this is in host-rate samples and includes the resampling filters'
+    /// delay.
     pub fn latency_samples(&self) -> u32 {
-        self.internal
+        let reported = self
+            .internal
             .as_ref()
             .map(|i| i.latency_samples())
-            .unwrap_or(0)
+            .unwrap_or(0);
+        match &self.oversampler {
+            Some(o) => reported / o.factor() + o.latency() as u32,
+            None => reported,
+        }
     }
 
     /// The plugin's reported tail length in samples (how long it keeps producing output
@@ -688,6 +1462,91 @@ impl Plugin {
             .unwrap_or(0)
     }
 
+    /// Measure the plugin's actual tail, in samples, instead of trusting
+    /// [`Self::tail_samples`] (many reverbs report an infinite tail).
+    ///
+    /// Plays `note` on channel 1 for `note_length_blocks` blocks — with a 440 Hz test tone on
+    /// the audio inputs, so effects ring out too — then releases it and renders until the
+    /// output stays below `threshold_db` (dBFS) for [`TAIL_WINDOW_BLOCKS`] blocks. The result
+    /// counts whole blocks from the release to the start of that quiet window. Processing is
+    /// started if needed and left as it was; the output is discarded.
+    ///
+    /// Fails if the tail is still going after a minute of audio (self-oscillating plugins).
+    ///
+    /// [`TAIL_WINDOW_BLOCKS`]: crate::audio::TAIL_WINDOW_BLOCKS
+    pub fn measure_actual_tail(
+        &mut self,
+        note: u8,
+        note_length_blocks: usize,
+        threshold_db: f64,
+    ) -> Result<u64> {
+        let was_processing = self.is_processing;
+        if !was_processing {
+            self.start_processing()?;
+        }
+        let result = self.render_tail(note.min(127), note_length_blocks, threshold_db);
+        if !was_processing {
+            self.stop_processing()?;
+        }
+        result
+    }
+
+    /// The body of [`Self::measure_actual_tail`], run while processing.
+    fn render_tail(
+        &mut self,
+        note: u8,
+        note_length_blocks: usize,
+        threshold_db: f64,
+    ) -> Result<u64> {
+        use crate::audio::{TailDetector, TAIL_WINDOW_BLOCKS};
+
+        let block = self.block_size.max(1);
+        let inputs = self.info.audio_inputs as usize;
+        let outputs = self.output_channel_count().max(1);
+        let mut buffers = AudioBuffers::new(inputs, outputs, block, self.sample_rate);
+        let step = 2.0 * std::f64::consts::PI * 440.0 / self.sample_rate;
+        let mut phase = 0.0_f64;
+
+        self.send_midi_event(MidiEvent::NoteOn {
+            channel: MidiChannel::Ch1,
+            note,
+            velocity: 100,
+        })?;
+        for _ in 0..note_length_blocks {
+            for frame in 0..block {
+                let sample = (0.5 * phase.sin()) as f32;
+                phase += step;
+                for ch in &mut buffers.inputs {
+                    ch[frame] = sample;
+                }
+            }
+            self.process_audio(&mut buffers)?;
+        }
+        self.send_midi_event(MidiEvent::NoteOff {
+            channel: MidiChannel::Ch1,
+            note,
+            velocity: 0,
+        })?;
+
+        for ch in &mut buffers.inputs {
+            ch.fill(0.0);
+        }
+        let max_blocks = (60.0 * self.sample_rate / block as f64).ceil() as usize;
+        let mut detector = TailDetector::new();
+        detector.start();
+        while !detector.is_tail_ended(threshold_db, TAIL_WINDOW_BLOCKS) {
+            if detector.blocks_monitored() >= max_blocks {
+                return Err(Error::Other(format!(
+                    "tail did not fall below {threshold_db} dBFS within 60 seconds"
+                )));
+            }
+            self.process_audio(&mut buffers)?;
+            detector.push_block(&buffers.outputs);
+        }
+        let tail_blocks = detector.blocks_monitored() - TAIL_WINDOW_BLOCKS;
+        Ok((tail_blocks * block) as u64)
+    }
+
     /// Resolve a MIDI controller to the parameter it's mapped to, via the plugin's
     /// `IMidiMapping` (`getMidiControllerAssignment`).
     ///
@@ -723,6 +1582,11 @@ impl Plugin {
     /// show — e.g. `"440.00 Hz"`, `"-6.0 dB"`, `"Sine"`. Prefer this over
     /// [`Parameter::format_value`], which can only approximate without the plugin's
     /// internal mapping.
+    ///
+    /// Returns [`Error::NoController`] when the plugin has no edit controller and
+    /// [`Error::InvalidParameter`] when the controller doesn't return `kResultOk`.
+    #[doc(alias = "get_parameter_display_string")]
+    #[doc(alias = "getParamStringByValue")]
     pub fn format_parameter(&self, id: u32, normalized: f64) -> Result<String> {
         self.internal
             .as_ref()
@@ -730,15 +1594,49 @@ impl Plugin {
             .format_parameter(id, normalized)
     }
 
-    /// Set a parameter by name
-    pub fn set_parameter_by_name(&mut self, name: &str, value: f64) -> Result<()> {
-        let params = self.get_parameters()?;
-        let param = params
-            .iter()
-            .find(|p| p.name == name)
-            .ok_or_else(|| Error::InvalidParameter(format!("Parameter '{}' not found", name)))?;
-
-        self.set_parameter(param.id, value)
+    /// Parse `text` for parameter `id` the way the plugin's own UI would — the inverse of
+    /// [`Self::format_parameter`] — returning the normalized value (`getParamValueByString`).
+    ///
+    /// Fails like [`Self::format_parameter`]: [`Error::NoController`] without an edit
+    /// controller, [`Error::InvalidParameter`] when the controller rejects `text`.
+    #[doc(alias = "string_to_normalized")]
+    #[doc(alias = "getParamValueByString")]
+    pub fn parse_parameter(&self, id: u32, text: &str) -> Result<f64> {
+        self.internal
+            .as_ref()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .parse_parameter(id, text)
+    }
+
+    /// Convert a normalized value (0.0–1.0) of parameter `id` to its plain, real-world value
+    /// (e.g. Hz or dB), using the plugin's own mapping (`normalizedParamToPlain`).
+    ///
+    /// Unlike [`Parameter::normalized_to_plain`], this is exact for non-linear parameters.
+    pub fn normalized_to_plain(&self, param_id: u32, normalized: f64) -> Result<f64> {
+        self.internal
+            .as_ref()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .normalized_to_plain(param_id, normalized)
+    }
+
+    /// Convert a plain value of parameter `id` to normalized (0.0–1.0), using the plugin's own
+    /// mapping (`plainParamToNormalized`).
+    pub fn plain_to_normalized(&self, param_id: u32, plain: f64) -> Result<f64> {
+        self.internal
+            .as_ref()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .plain_to_normalized(param_id, plain)
+    }
+
+    /// Set a parameter by name
+    pub fn set_parameter_by_name(&mut self, name: &str, value: f64) -> Result<()> {
+        let params = self.get_parameters()?;
+        let param = params
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| Error::InvalidParameter(format!("Parameter '{}' not found", name)))?;
+
+        self.set_parameter(param.id, value)
     }
 
     /// Find a parameter by name
@@ -750,6 +1648,34 @@ impl Plugin {
             .ok_or_else(|| Error::InvalidParameter(format!("Parameter '{}' not found", name)))
     }
 
+    /// The parameters whose normalized value is within `tolerance` of `target`, e.g. to answer
+    /// "which parameters are near their maximum?" while debugging a patch.
+    pub fn find_params_near_value(&self, target: f64, tolerance: f64) -> Result<Vec<Parameter>> {
+        let params = self.get_parameters()?;
+        Ok(
+            crate::parameters::params_near_value(&params, target, tolerance)
+                .into_iter()
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// The parameters at their factory default, within
+    /// [`FACTORY_DEFAULT_TOLERANCE`](crate::parameters::FACTORY_DEFAULT_TOLERANCE).
+    pub fn find_params_at_default(&self) -> Result<Vec<Parameter>> {
+        let params = self.get_parameters()?;
+        Ok(params
+            .into_iter()
+            .filter(|p| p.is_near(p.default, crate::parameters::FACTORY_DEFAULT_TOLERANCE))
+            .collect())
+    }
+
+    /// The parameters at 0 (normalized), within
+    /// [`FACTORY_DEFAULT_TOLERANCE`](crate::parameters::FACTORY_DEFAULT_TOLERANCE).
+    pub fn find_params_at_zero(&self) -> Result<Vec<Parameter>> {
+        self.find_params_near_value(0.0, crate::parameters::FACTORY_DEFAULT_TOLERANCE)
+    }
+
     /// Send a MIDI note on event
     pub fn send_midi_note(&mut self, note: u8, velocity: u8, channel: MidiChannel) -> Result<()> {
         if note > 127 {
@@ -801,12 +1727,37 @@ impl Plugin {
         self.send_midi_event(event)
     }
 
+    /// Send a pitch bend of `normalized`, from -1.0 (full down) through 0.0 (center) to +1.0
+    /// (full up). See [`MidiEvent::pitch_bend`] for the 14-bit value it becomes.
+    pub fn send_pitch_bend(&mut self, channel: MidiChannel, normalized: f64) -> Result<()> {
+        if !(-1.0..=1.0).contains(&normalized) {
+            return Err(Error::MidiError(format!(
+                "Invalid pitch bend: {}",
+                normalized
+            )));
+        }
+        self.send_midi_event(MidiEvent::pitch_bend(channel, normalized))
+    }
+
     /// Send a generic MIDI event
     pub fn send_midi_event(&mut self, event: MidiEvent) -> Result<()> {
-        self.internal
+        let Some(event) = self.route_midi(event) else {
+            return Ok(());
+        };
+        let stolen = self.limit_voices(event);
+        let released = self.sustain.note_event(event);
+        let internal = self
+            .internal
             .as_mut()
-            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
-            .send_midi_event(event)
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?;
+        if let Some(note_off) = stolen {
+            internal.send_midi_event(note_off)?;
+        }
+        internal.send_midi_event(event)?;
+        for note_off in released {
+            internal.send_midi_event(note_off)?;
+        }
+        Ok(())
     }
 
     /// Schedule a MIDI event at a sample offset within the **next** [`process_audio`] block.
@@ -821,10 +1772,142 @@ impl Plugin {
     ///
     /// [`process_audio`]: Self::process_audio
     pub fn send_midi_event_at(&mut self, event: MidiEvent, sample_offset: i32) -> Result<()> {
+        let Some(event) = self.route_midi(event) else {
+            return Ok(());
+        };
+        let sample_offset = self.oversampled_offset(sample_offset);
+        let stolen = self.limit_voices(event);
+        let released = self.sustain.note_event(event);
+        let internal = self
+            .internal
+            .as_mut()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?;
+        if let Some(note_off) = stolen {
+            internal.send_midi_event_at(note_off, sample_offset)?;
+        }
+        internal.send_midi_event_at(event, sample_offset)?;
+        for note_off in released {
+            internal.send_midi_event_at(note_off, sample_offset)?;
+        }
+        Ok(())
+    }
+
+    /// Send a System Exclusive message to the plugin's event input bus, delivered at the start
+    /// of the next [`process_audio`] block as a VST3 `kDataEvent` of type `kMidiSysEx`.
+    ///
+    /// `data` may include the `0xF0` status prefix and the `0xF7` terminator or leave them out;
+    /// see [`crate::midi::frame_sysex`]. SysEx carries no channel, so the MIDI channel filter and
+    /// channel strips don't apply.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::MidiError`] for an empty message or a data byte above `0x7F`.
+    ///
+    /// [`process_audio`]: Self::process_audio
+    pub fn send_sysex(&mut self, data: &[u8]) -> Result<()> {
+        let framed = crate::midi::frame_sysex(data)?;
         self.internal
             .as_mut()
             .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
-            .send_midi_event_at(event, sample_offset)
+            .send_sysex(&framed)
+    }
+
+    /// Run `event` through the polyphony limiter, returning the note-off of any voice it steals.
+    fn limit_voices(&mut self, event: MidiEvent) -> Option<MidiEvent> {
+        self.polyphony_limiter.as_mut()?.note_event(event)
+    }
+
+    /// Cap the notes sounding at once to `max_voices` (at least 1). Past the cap, a note-on sent
+    /// through [`Self::send_midi_event`] (or anything built on it) is preceded by a note-off for
+    /// the voice `stealing` picks. Useful for plugins without their own voice limit, or to
+    /// audition a patch monophonically. Replaces any previous limit; voices already sounding
+    /// aren't tracked. [`Self::note_on`] bypasses the limiter.
+    pub fn set_polyphony_limit(&mut self, max_voices: u8, stealing: StealingStrategy) {
+        self.polyphony_limiter = Some(PolyphonyLimiter::new(max_voices, stealing));
+    }
+
+    /// Remove the limit set by [`Self::set_polyphony_limit`].
+    pub fn clear_polyphony_limit(&mut self) {
+        self.polyphony_limiter = None;
+    }
+
+    /// The active polyphony limiter (its voice count and settings), if any.
+    pub fn polyphony_limiter(&self) -> Option<&PolyphonyLimiter> {
+        self.polyphony_limiter.as_ref()
+    }
+
+    /// A host-rate sample offset at the rate the plugin runs at.
+    fn oversampled_offset(&self, sample_offset: i32) -> i32 {
+        sample_offset.saturating_mul(self.oversampling_factor() as i32)
+    }
+
+    /// Only pass MIDI on `channel` to the plugin; events on other channels sent through
+    /// [`Self::send_midi_event`] (and everything built on it, including
+    /// [`AudioHandle::send_midi`](crate::AudioHandle::send_midi)) are silently dropped. `None`
+    /// accepts every channel, the default. [`Self::note_on`] addresses a channel explicitly and
+    /// is not filtered.
+    pub fn set_midi_channel_filter(&mut self, channel: Option<MidiChannel>) {
+        self.midi_channel_filter = channel;
+    }
+
+    /// The channel set by [`Self::set_midi_channel_filter`], if any.
+    pub fn midi_channel_filter(&self) -> Option<MidiChannel> {
+        self.midi_channel_filter
+    }
+
+    fn accepts_midi(&self, event: &MidiEvent) -> bool {
+        self.midi_channel_filter
+            .is_none_or(|channel| event.channel() == channel)
+    }
+
+    /// Apply the channel filter and the event's channel strip, or `None` if it's dropped.
+    fn route_midi(&self, event: MidiEvent) -> Option<MidiEvent> {
+        if !self.accepts_midi(&event) {
+            return None;
+        }
+        let event = match self.channel_strip(event.channel()) {
+            Some(strip) => strip.process_event(event)?,
+            None => event,
+        };
+        Some(match &self.velocity_curve {
+            Some(curve) => curve.process_event(event),
+            None => event,
+        })
+    }
+
+    /// Map every note-on velocity sent through [`Self::send_midi_event`] through `curve`,
+    /// after the channel strips and before the polyphony limiter. An identity curve turns the
+    /// mapping off.
+    pub fn set_velocity_curve(&mut self, curve: VelocityCurve) {
+        self.velocity_curve = (!curve.is_identity()).then_some(curve);
+    }
+
+    /// The curve set by [`Self::set_velocity_curve`], if any.
+    pub fn velocity_curve(&self) -> Option<&VelocityCurve> {
+        self.velocity_curve.as_ref()
+    }
+
+    /// Transform MIDI on `channel` with `strip` before it reaches the plugin: mute the channel,
+    /// transpose it, reshape its velocities or drop controllers (see [`ChannelStrip`]). Applies
+    /// to everything sent through [`Self::send_midi_event`], after
+    /// [`Self::set_midi_channel_filter`] and before the polyphony limiter. Replaces the strip
+    /// previously set for `channel`; `strip.channel` is overwritten with `channel`.
+    pub fn set_channel_strip(&mut self, channel: MidiChannel, mut strip: ChannelStrip) {
+        strip.channel = channel;
+        self.channel_strips.retain(|s| s.channel != channel);
+        if !strip.is_passthrough() {
+            self.channel_strips.push(strip);
+        }
+    }
+
+    /// The strip set for `channel` by [`Self::set_channel_strip`], if any.
+    pub fn channel_strip(&self, channel: MidiChannel) -> Option<&ChannelStrip> {
+        self.channel_strips.iter().find(|s| s.channel == channel)
+    }
+
+    /// Remove every channel strip.
+    pub fn clear_channel_strips(&mut self) {
+        self.channel_strips.clear();
     }
 
     /// Start a note and get a per-voice [`NoteId`](crate::midi::NoteId) handle for sending
@@ -919,11 +2002,38 @@ impl Plugin {
             return Ok(());
         }
 
-        self.internal
+        // Workarounds from the host's compatibility database apply to the first start only.
+        use crate::compatibility::Workaround;
+        let workarounds = std::mem::take(&mut self.pending_workarounds);
+        for workaround in &workarounds {
+            if let Workaround::DelayStartProcessingMs(ms) = *workaround {
+                std::thread::sleep(std::time::Duration::from_millis(ms));
+            }
+        }
+
+        let internal = self
+            .internal
             .as_mut()
-            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
-            .start_processing()?;
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?;
+        internal.start_processing()?;
+        // Queued for the first block, past any MIDI routing: this is for the plugin, not a
+        // note the user played.
+        for workaround in &workarounds {
+            if let Workaround::SendCcBeforeProcessing { cc, value } = *workaround {
+                internal.send_midi_event(MidiEvent::ControlChange {
+                    channel: MidiChannel::Ch1,
+                    controller: cc,
+                    value,
+                })?;
+            }
+        }
 
+        if let Some(stats) = self.block_statistics.as_mut() {
+            stats.reset();
+        }
+        if let Some(spectrum) = self.spectrum.as_mut() {
+            spectrum.reset();
+        }
         self.is_processing = true;
         Ok(())
     }
@@ -943,16 +2053,46 @@ impl Plugin {
         Ok(())
     }
 
-    /// Process audio buffers
+    /// Process one block: `buffers.inputs` are fed to the plugin's input buses and its output
+    /// buses are written to `buffers.outputs`.
+    ///
+    /// For an effect (EQ, compressor, reverb) fill `inputs` with the signal to process, one
+    /// `[channel][sample]` buffer per channel of the plugin's main input buses in order, and
+    /// [`sidechain_inputs`](AudioBuffers::sidechain_inputs) with the key signal for its aux
+    /// (sidechain) buses; instruments are usually given none (`AudioBuffers::new(0, ...)`).
+    /// Plugin input channels without a buffer get silence, and buffers beyond the plugin's
+    /// channels are ignored. The plugin must be [processing](Self::start_processing).
+    #[doc(alias = "process_audio_with_input")]
     pub fn process_audio(&mut self, buffers: &mut AudioBuffers) -> Result<()> {
         if !self.is_processing {
             return Err(Error::Other("Plugin is not processing".to_string()));
         }
 
-        self.internal
+        let internal = self
+            .internal
             .as_mut()
-            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
-            .process(buffers)?;
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?;
+        match self.oversampler.as_mut() {
+            Some(oversampler) => {
+                oversampler.process(buffers, |oversampled| internal.process(oversampled))?
+            }
+            None => internal.process(buffers)?,
+        }
+
+        if let Some(limiter) = self.polyphony_limiter.as_mut() {
+            limiter.advance(buffers.block_size as f64 / self.sample_rate);
+        }
+
+        // Catch NaN/Inf before it reaches the levels (or the device).
+        if let Some(analyzer) = self.output_analyzer.as_mut() {
+            analyzer.analyze(&mut buffers.outputs);
+        }
+        if let Some(stats) = self.block_statistics.as_mut() {
+            stats.process(&buffers.outputs);
+        }
+        if let Some(spectrum) = self.spectrum.as_mut() {
+            spectrum.process(&buffers.outputs);
+        }
 
         // Update audio levels
         if let Ok(mut levels) = self.audio_levels.lock() {
@@ -967,6 +2107,45 @@ impl Plugin {
         Ok(())
     }
 
+    /// Output health counters (silent, clipping and NaN/Inf blocks) since loading or the last
+    /// [`Self::reset_output_analysis`]. `None` when analysis is disabled
+    /// ([`Vst3HostBuilder::enable_output_analysis`]).
+    ///
+    /// A block containing NaN or Inf is silenced before it leaves [`Self::process_audio`], and
+    /// [`AnalysisReport::needs_recovery`] is set: recover ([`Self::recover`]) or
+    /// [`Self::factory_reset`] the plugin, either of which clears it.
+    ///
+    /// [`Vst3HostBuilder::enable_output_analysis`]: crate::Vst3HostBuilder::enable_output_analysis
+    /// [`AnalysisReport::needs_recovery`]: crate::audio::AnalysisReport::needs_recovery
+    pub fn output_analysis(&self) -> Option<crate::audio::AnalysisReport> {
+        self.output_analyzer.as_ref().map(|a| a.report())
+    }
+
+    /// Zero the output health counters.
+    pub fn reset_output_analysis(&mut self) {
+        if let Some(analyzer) = self.output_analyzer.as_mut() {
+            analyzer.reset();
+        }
+    }
+
+    /// Drop the output peak holds to silence, the way a click on a meter resets it. In
+    /// [`MeterHoldMode::ManualReset`](crate::audio::MeterHoldMode::ManualReset) this is the
+    /// only thing that lowers them.
+    pub fn reset_peak_hold(&mut self) {
+        if let Ok(mut levels) = self.audio_levels.lock() {
+            levels.reset_peak_hold(std::time::Instant::now());
+        }
+    }
+
+    /// Set how long the output peak holds last ([`AudioConfig::meter_hold_mode`]).
+    ///
+    /// [`AudioConfig::meter_hold_mode`]: crate::audio::AudioConfig::meter_hold_mode
+    pub fn set_meter_hold_mode(&mut self, mode: crate::audio::MeterHoldMode) {
+        if let Ok(mut levels) = self.audio_levels.lock() {
+            levels.set_hold_mode(mode);
+        }
+    }
+
     /// Get current output levels.
     ///
     /// Recovers automatically if the audio thread panicked while holding the lock
@@ -992,6 +2171,107 @@ impl Plugin {
         self.parameter_change_callback = Some(Box::new(callback));
     }
 
+    /// Map polyphonic aftertouch onto a parameter (see [`Plugin::process_aftertouch`]).
+    pub fn add_aftertouch_binding(&mut self, binding: AftertouchBinding) {
+        self.aftertouch_bindings.push(binding);
+    }
+
+    /// Map channel pressure onto a parameter (see [`Plugin::process_aftertouch`]).
+    pub fn add_channel_pressure_binding(&mut self, binding: ChannelPressureBinding) {
+        self.channel_pressure_bindings.push(binding);
+    }
+
+    /// Remove all aftertouch and channel-pressure bindings.
+    pub fn clear_aftertouch_bindings(&mut self) {
+        self.aftertouch_bindings.clear();
+        self.channel_pressure_bindings.clear();
+    }
+
+    /// The polyphonic aftertouch bindings, in the order they were added.
+    pub fn aftertouch_bindings(&self) -> &[AftertouchBinding] {
+        &self.aftertouch_bindings
+    }
+
+    /// The channel-pressure bindings, in the order they were added.
+    pub fn channel_pressure_bindings(&self) -> &[ChannelPressureBinding] {
+        &self.channel_pressure_bindings
+    }
+
+    /// Apply pressure to the bound parameters. `ch` is the 0-based MIDI channel (0-15);
+    /// `note: Some(n)` is polyphonic aftertouch on note `n` (drives [`AftertouchBinding`]s),
+    /// `None` is channel pressure (drives [`ChannelPressureBinding`]s). Parameters without a
+    /// matching binding are left alone.
+    pub fn process_aftertouch(&mut self, ch: u8, note: Option<u8>, pressure: u8) -> Result<()> {
+        let channel = MidiChannel::from_index(ch).ok_or_else(|| {
+            Error::InvalidParameter(format!("MIDI channel index {ch} out of range"))
+        })?;
+        let updates: Vec<(u32, f64)> = match note {
+            Some(note) => self
+                .aftertouch_bindings
+                .iter()
+                .filter(|b| b.matches(channel, note))
+                .map(|b| (b.param_id, b.value_for(pressure)))
+                .collect(),
+            None => self
+                .channel_pressure_bindings
+                .iter()
+                .filter(|b| b.matches(channel))
+                .map(|b| (b.param_id, b.value_for(pressure)))
+                .collect(),
+        };
+        for (id, value) in updates {
+            self.set_parameter(id, value)?;
+        }
+        Ok(())
+    }
+
+    /// Turn soft takeover on or off for CC bindings (see [`SoftTakeover`]). While on, a
+    /// controller only moves a parameter once it has reached the parameter's current value.
+    /// Turning it off forgets every controller's position.
+    pub fn enable_soft_takeover(&mut self, enable: bool) {
+        if !enable {
+            self.soft_takeover = None;
+        } else if self.soft_takeover.is_none() {
+            self.soft_takeover = Some(SoftTakeover::new());
+        }
+    }
+
+    /// Whether soft takeover is on ([`Self::enable_soft_takeover`]).
+    pub fn soft_takeover_enabled(&self) -> bool {
+        self.soft_takeover.is_some()
+    }
+
+    /// The normalized value `binding` should set for CC `value` on `channel`, or `None` while
+    /// soft takeover is holding the controller back. Without soft takeover this is always
+    /// [`CcBinding::value_for`]. The caller applies the value, e.g. through the lock-free
+    /// [`AudioHandle::set_parameter`](crate::AudioHandle::set_parameter).
+    pub fn cc_binding_value(
+        &mut self,
+        binding: &CcBinding,
+        channel: MidiChannel,
+        value: u8,
+    ) -> Result<Option<f64>> {
+        let target = binding.value_for(value);
+        let (low, high) = binding.range;
+        let Some(takeover) = self.soft_takeover.as_mut() else {
+            return Ok(Some(target));
+        };
+        if (high - low).abs() < f64::EPSILON {
+            return Ok(Some(target));
+        }
+        let current = self
+            .internal
+            .as_ref()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .get_parameter(binding.param_id)?;
+        // Compare in controller space so inverted and partial ranges pick up where they meet
+        // the parameter.
+        let position = ((current - low) / (high - low)).clamp(0.0, 1.0);
+        Ok(takeover
+            .process_cc(binding.controller, channel, value, position)
+            .map(|_| target))
+    }
+
     /// Set a callback for audio processing (called after each process cycle)
     pub fn on_audio_process<F>(&mut self, callback: F)
     where
@@ -1008,12 +2288,38 @@ impl Plugin {
             .unwrap_or(false)
     }
 
+    /// Whether the plugin has an edit controller.
+    ///
+    /// A plugin whose component neither implements `IEditController` nor names a controller
+    /// class still loads (unless [`PluginInitFlags::REQUIRE_CONTROLLER`] is set) and processes
+    /// audio and MIDI, but has no parameters: setting or reading one returns
+    /// [`Error::NoController`]. Under process isolation this reports true, and the calls that
+    /// need a controller fail.
+    pub fn has_controller(&self) -> bool {
+        self.internal
+            .as_ref()
+            .map(|i| i.has_controller())
+            .unwrap_or(false)
+    }
+
     /// Open the plugin editor window
     pub fn open_editor(&mut self, parent: WindowHandle) -> Result<()> {
         self.internal
             .as_mut()
             .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
-            .open_editor(parent.0)
+            .open_editor(parent.ptr, parent.platform_type)
+    }
+
+    /// Whether the plugin's editor can embed into a Linux parent of the given type
+    /// (`IPlugView::isPlatformTypeSupported`). Creates a throwaway editor view to ask.
+    pub fn supports_linux_platform_type(
+        &self,
+        platform_type: crate::window::LinuxPlatformType,
+    ) -> Result<bool> {
+        self.internal
+            .as_ref()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .is_platform_type_supported(platform_type.as_cstr())
     }
 
     /// Close the plugin editor window
@@ -1032,6 +2338,37 @@ impl Plugin {
             .get_editor_size()
     }
 
+    /// The size nearest to `width`×`height` that the editor accepts, via
+    /// `IPlugView::checkSizeConstraint`. A fixed-size editor always answers with its current
+    /// size. Works whether or not the editor is open.
+    pub fn check_editor_size_constraint(&self, width: i32, height: i32) -> Result<(i32, i32)> {
+        self.internal
+            .as_ref()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .check_editor_size_constraint(width, height)
+    }
+
+    /// Resize the open editor to fit an area of `width`×`height`: the size is first adjusted
+    /// with [`Self::check_editor_size_constraint`], then passed to `IPlugView::onSize`.
+    /// Returns the size actually applied, which the host should give the editor's area.
+    pub fn resize_editor(&mut self, width: i32, height: i32) -> Result<(i32, i32)> {
+        let (width, height) = self.check_editor_size_constraint(width, height)?;
+        self.internal
+            .as_mut()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .resize_editor(width, height)?;
+        Ok((width, height))
+    }
+
+    /// The keyboard shortcuts the plugin's editor reports. VST3 offers no way to query them,
+    /// so this is empty for VST3 plugins: see the plugin's documentation instead.
+    pub fn get_key_shortcuts(&self) -> Result<Vec<KeyShortcut>> {
+        self.internal
+            .as_ref()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .key_shortcuts()
+    }
+
     /// Create a batch parameter update
     pub fn update_parameters<F>(&mut self, f: F) -> Result<()>
     where
@@ -1042,8 +2379,140 @@ impl Plugin {
         update.apply()
     }
 
-    /// Send MIDI panic (all notes off, all sounds off, reset controllers)
+    /// Reset every writable (non-read-only) parameter to its default value.
+    ///
+    /// Returns one [`ParameterChange`] per parameter that was reset, carrying the value it had
+    /// *before* the reset, so applying them with [`set_parameter`](Self::set_parameter) undoes it.
+    pub fn reset_all_parameters(&mut self) -> Result<Vec<ParameterChange>> {
+        self.reset_parameters_where(|_| true)
+    }
+
+    /// Like [`reset_all_parameters`](Self::reset_all_parameters), but only touches parameters
+    /// whose current value differs from the default (by more than `1e-6`).
+    pub fn reset_modified_parameters(&mut self) -> Result<Vec<ParameterChange>> {
+        self.reset_parameters_where(|p| (p.value - p.default).abs() > 1e-6)
+    }
+
+    fn reset_parameters_where(
+        &mut self,
+        filter: impl Fn(&Parameter) -> bool,
+    ) -> Result<Vec<ParameterChange>> {
+        let mut undo = Vec::new();
+        let now = std::time::Instant::now();
+        for param in self.get_parameters()? {
+            if param.is_read_only || !filter(&param) {
+                continue;
+            }
+            self.set_parameter(param.id, param.default.clamp(0.0, 1.0))?;
+            undo.push(ParameterChange {
+                id: param.id,
+                value: param.value,
+                sample_offset: 0,
+                timestamp: now,
+            });
+        }
+        Ok(undo)
+    }
+
+    /// Capture every parameter's current value for [`Self::morph_snapshots`].
+    pub fn snapshot_parameters(&self) -> Result<ParameterSnapshot> {
+        Ok(ParameterSnapshot::new(self.get_parameters()?))
+    }
+
+    /// Move the plugin to position `t` (0.0 = `a`, 1.0 = `b`) between two snapshots and
+    /// return the changes applied. See [`morph`](crate::parameters::morph) for which
+    /// parameters are touched. Cheap enough to call every UI frame while dragging.
+    pub fn morph_snapshots(
+        &mut self,
+        a: &ParameterSnapshot,
+        b: &ParameterSnapshot,
+        t: f64,
+    ) -> Result<Vec<ParameterChange>> {
+        let changes = crate::parameters::morph(a, b, t);
+        for change in &changes {
+            self.set_parameter(change.id, change.value)?;
+        }
+        Ok(changes)
+    }
+
+    /// Set every writable parameter to a value drawn from `constraints.distribution` and
+    /// return what was set. See [`randomize`](crate::parameters::randomize) for which
+    /// parameters are touched.
+    pub fn randomize_parameters(
+        &mut self,
+        rng: &mut impl rand::Rng,
+        constraints: &RandomizeConstraints,
+    ) -> Result<RandomizeResult> {
+        let result = crate::parameters::randomize(&self.get_parameters()?, constraints, rng);
+        for change in &result.changes {
+            self.set_parameter(change.id, change.value)?;
+        }
+        Ok(result)
+    }
+
+    /// Move every writable parameter a little from where it is: a
+    /// [`Distribution::Gaussian`] randomization with standard deviation `sigma` (a fraction of
+    /// the range, e.g. `0.05`).
+    pub fn nudge_parameters(
+        &mut self,
+        rng: &mut impl rand::Rng,
+        sigma: f64,
+    ) -> Result<Vec<ParameterChange>> {
+        let constraints = RandomizeConstraints {
+            distribution: Distribution::Gaussian { sigma },
+            ..RandomizeConstraints::default()
+        };
+        Ok(self.randomize_parameters(rng, &constraints)?.changes)
+    }
+
+    /// Whether parameter `param_id` morphs smoothly: it exists, is writable, and is
+    /// continuous. Stepped parameters still morph, but jump between steps.
+    pub fn can_morph_param(&self, param_id: u32) -> bool {
+        self.get_parameters().is_ok_and(|params| {
+            params
+                .iter()
+                .any(|p| p.id == param_id && !p.is_read_only && p.step_count == 0)
+        })
+    }
+
+    /// Lift the sustain pedal (CC 64 = 0) on every channel where it's down, followed by a
+    /// note-off for each note let go while it was held, so no note hangs on a pedal that never
+    /// came up. Channels without the pedal down are left alone. [`Self::midi_panic`] does
+    /// this first.
+    pub fn release_sustained_notes(&mut self) -> Result<()> {
+        for (index, held) in self.sustain.sustained_channels().into_iter().enumerate() {
+            let Some(channel) = MidiChannel::from_index(index as u8).filter(|_| held) else {
+                continue;
+            };
+            // Straight to the plugin: the pedal and notes were tracked after routing.
+            let pedal_up = MidiEvent::ControlChange {
+                channel,
+                controller: 64,
+                value: 0,
+            };
+            let released = self.sustain.note_event(pedal_up);
+            let internal = self
+                .internal
+                .as_mut()
+                .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?;
+            internal.send_midi_event(pedal_up)?;
+            for note_off in released {
+                internal.send_midi_event(note_off)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the sustain pedal is down on each channel (index 0 = channel 1), as sent
+    /// through [`Self::send_midi_event`].
+    pub fn sustained_channels(&self) -> [bool; 16] {
+        self.sustain.sustained_channels()
+    }
+
+    /// Send MIDI panic (all notes off, all sounds off, reset controllers), after releasing any
+    /// notes the sustain pedal holds ([`Self::release_sustained_notes`]).
     pub fn midi_panic(&mut self) -> Result<()> {
+        self.release_sustained_notes()?;
         for i in 0..16 {
             if let Some(channel) = MidiChannel::from_index(i) {
                 // All Notes Off
@@ -1067,6 +2536,22 @@ impl Plugin {
             .unwrap_or_default()
     }
 
+    /// Receive the parameter changes the plugin's own editor makes the moment it reports them
+    /// (`IComponentHandler::performEdit`), each stamped with when it arrived — instead of
+    /// polling [`Self::get_parameter_changes`], which only sees them once the next block has
+    /// been processed. Safe to drain from a UI thread while the plugin plays.
+    ///
+    /// While subscribed, [`Self::get_parameter_changes`] (and
+    /// [`AudioHandle::drain_parameter_changes`](crate::AudioHandle::drain_parameter_changes))
+    /// no longer report editor changes; dropping every receiver switches back. Each call adds
+    /// a receiver, and every receiver gets every edit. `None` under process isolation, where
+    /// editor changes are only available by polling.
+    pub fn subscribe_parameter_changes(
+        &mut self,
+    ) -> Option<std::sync::mpsc::Receiver<ParameterChange>> {
+        self.internal.as_mut()?.subscribe_parameter_changes()
+    }
+
     /// Drain the ordered log of parameter-edit gestures the plugin's editor has reported since
     /// the last call.
     ///
@@ -1111,6 +2596,17 @@ impl Plugin {
         self.internal.as_ref().and_then(|i| i.output_midi_handle())
     }
 
+    /// Subscribe to what the plugin prints, line by line. Only process-isolated plugins
+    /// have their output captured: the helper's stdout/stderr go to a per-plugin log file
+    /// under [`helper_log_dir`](crate::process_isolation::helper_log_dir), and every line
+    /// written after this call is also sent on the returned channel. Returns `None` for
+    /// in-process plugins, whose output goes to the host's own console. The channel
+    /// disconnects if the helper is restarted after a crash; subscribe again to follow the
+    /// new one.
+    pub fn log_lines(&self) -> Option<std::sync::mpsc::Receiver<String>> {
+        self.internal.as_ref().and_then(|i| i.log_lines())
+    }
+
     /// Save the plugin's current state (parameters, internal settings, loaded preset) to
     /// an opaque byte blob.
     ///
@@ -1142,6 +2638,36 @@ impl Plugin {
             .load_state(data)
     }
 
+    /// Save the plugin's state as key/value pairs. Some plugins keep (part of) their state in
+    /// the `IAttributeList` they get from the state stream's `IStreamAttributes` rather than
+    /// in the stream's bytes; this returns those attributes, plus whatever bytes the plugin
+    /// wrote as an [`AttributeValue::Binary`] under [`STATE_STREAM_ATTRIBUTE`].
+    ///
+    /// Restore with [`Self::load_state_from_attributes`]. In-process plugins only; isolated
+    /// plugins return an error. Call this on the main thread.
+    pub fn save_state_as_attributes(
+        &self,
+    ) -> Result<std::collections::HashMap<String, AttributeValue>> {
+        self.internal
+            .as_ref()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .save_state_attributes()
+    }
+
+    /// Restore plugin state from attributes produced by [`Self::save_state_as_attributes`]
+    /// on the *same* plugin. The plugin reads the bytes under [`STATE_STREAM_ATTRIBUTE`]
+    /// from the state stream and the other attributes from its attribute list; the
+    /// controller gets the same, as with [`Self::load_state`].
+    pub fn load_state_from_attributes(
+        &mut self,
+        attrs: std::collections::HashMap<String, AttributeValue>,
+    ) -> Result<()> {
+        self.internal
+            .as_mut()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .load_state_attributes(attrs)
+    }
+
     /// Save this plugin's state to a file as a [`PluginPreset`] (JSON: the plugin's `uid`
     /// and name plus the opaque state blob). The embedded `uid` lets [`Self::load_preset`]
     /// reject a preset saved from a different plugin.
@@ -1271,12 +2797,48 @@ impl Plugin {
         self.internal
             .as_mut()
             .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
-            .recover()
+            .recover()?;
+        self.reset_output_analysis();
+        // The fresh instance comes up with every bus active again.
+        self.bus_activation.clear();
+        Ok(())
+    }
+
+    /// Reload the plugin from scratch so it comes up in its built-in factory state.
+    ///
+    /// The current instance is torn down and a new one is loaded from the same path with the
+    /// same sample rate, block size, transport and process mode; no saved state is restored,
+    /// so every parameter returns to its default (see [`Self::factory_defaults`]). An open
+    /// editor is closed and must be reopened; processing resumes if it was running. A receiver
+    /// from [`Self::subscribe_parameter_changes`] stays connected to the new instance. Works
+    /// both in-process and across process isolation.
+    pub fn factory_reset(&mut self) -> Result<()> {
+        self.internal
+            .as_mut()
+            .ok_or_else(|| Error::Other("Plugin not initialized".to_string()))?
+            .factory_reset()?;
+        self.reset_output_analysis();
+        // The fresh instance comes up with every bus active again.
+        self.bus_activation.clear();
+        Ok(())
+    }
+
+    /// Every parameter's default normalized value, keyed by parameter id — the values a
+    /// [`Self::factory_reset`] brings the plugin back to. Empty if the parameters can't be
+    /// queried.
+    pub fn factory_defaults(&self) -> std::collections::HashMap<u32, f64> {
+        self.get_parameters()
+            .map(|params| params.into_iter().map(|p| (p.id, p.default)).collect())
+            .unwrap_or_default()
     }
 }
 
 /// Platform-specific window handle
-pub struct WindowHandle(pub(crate) *mut std::ffi::c_void);
+pub struct WindowHandle {
+    pub(crate) ptr: *mut std::ffi::c_void,
+    /// VST3 platform type string passed to `IPlugView::attached` alongside `ptr`.
+    pub(crate) platform_type: &'static std::ffi::CStr,
+}
 
 impl WindowHandle {
     /// Create from a raw window handle
@@ -1284,7 +2846,69 @@ impl WindowHandle {
     /// # Safety
     /// The pointer must be a valid window handle for the platform
     pub unsafe fn from_raw(handle: *mut std::ffi::c_void) -> Self {
-        Self(handle)
+        Self::native(handle)
+    }
+
+    /// A handle of the platform's default type (`NSView`, `HWND`, `X11EmbedWindowID`).
+    fn native(ptr: *mut std::ffi::c_void) -> Self {
+        #[cfg(target_os = "macos")]
+        let platform_type = c"NSView";
+        #[cfg(target_os = "windows")]
+        let platform_type = c"HWND";
+        #[cfg(target_os = "linux")]
+        let platform_type = c"X11EmbedWindowID";
+        #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+        let platform_type = c"kNativeWindowHandle";
+        Self { ptr, platform_type }
+    }
+}
+
+/// Modifier keys held for a [`KeyShortcut`], as in VST3's `KeyModifier` flags.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct ModifierKeys {
+    /// Shift
+    pub shift: bool,
+    /// Alt (Option on macOS)
+    pub alternate: bool,
+    /// Command on macOS, Ctrl elsewhere
+    pub command: bool,
+    /// Ctrl on macOS, Windows key elsewhere
+    pub control: bool,
+}
+
+/// The held modifiers, each followed by `+` (e.g. `"Cmd+Shift+"`).
+impl std::fmt::Display for ModifierKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = [
+            (self.command, "Cmd"),
+            (self.control, "Ctrl"),
+            (self.alternate, "Alt"),
+            (self.shift, "Shift"),
+        ];
+        for (_, name) in names.iter().filter(|(held, _)| *held) {
+            write!(f, "{name}+")?;
+        }
+        Ok(())
+    }
+}
+
+/// A keyboard shortcut a plugin editor handles ([`Plugin::get_key_shortcuts`]).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct KeyShortcut {
+    /// The key pressed
+    pub key: char,
+    /// Modifiers held with it
+    pub modifiers: ModifierKeys,
+    /// What it does
+    pub description: String,
+}
+
+impl KeyShortcut {
+    /// The key combination as shown to users, e.g. `"Cmd+Shift+Z"`.
+    pub fn label(&self) -> String {
+        format!("{}{}", self.modifiers, self.key.to_uppercase())
     }
 }
 
@@ -1295,7 +2919,7 @@ unsafe impl Send for WindowHandle {}
 impl WindowHandle {
     /// Create from an NSView pointer on macOS
     pub fn from_nsview(view: *mut std::ffi::c_void) -> Self {
-        Self(view)
+        Self::native(view)
     }
 }
 
@@ -1303,7 +2927,7 @@ impl WindowHandle {
 impl WindowHandle {
     /// Create from an HWND on Windows
     pub fn from_hwnd(hwnd: *mut std::ffi::c_void) -> Self {
-        Self(hwnd)
+        Self::native(hwnd)
     }
 }
 
@@ -1314,7 +2938,20 @@ impl WindowHandle {
     /// The VST3 X11 platform type expects the window id itself as the handle value,
     /// not a pointer to it.
     pub fn from_x11(window_id: u32) -> Self {
-        Self(window_id as usize as *mut std::ffi::c_void)
+        Self {
+            ptr: window_id as usize as *mut std::ffi::c_void,
+            platform_type: crate::window::LinuxPlatformType::X11EmbedWindowID.as_cstr(),
+        }
+    }
+
+    /// Create from a `wl_surface*` on Linux/Wayland (for VST3 `WaylandSurfaceID`).
+    ///
+    /// The plugin parents its own subsurface to this surface, so it must outlive the editor.
+    pub fn from_wayland_surface(surface: *mut std::ffi::c_void) -> Self {
+        Self {
+            ptr: surface,
+            platform_type: crate::window::LinuxPlatformType::WaylandSurfaceID.as_cstr(),
+        }
     }
 }
 
@@ -1507,6 +3144,817 @@ mod output_midi_consumer_tests {
     }
 }
 
+/// A `Plugin` wrapping a test double, for unit tests that drive the wrapper
+/// without loading a binary. Tests adjust the fields they care about afterwards.
+#[cfg(test)]
+pub(crate) fn plugin_with(internal: Box<dyn PluginInternal>) -> Plugin {
+    Plugin {
+        info: PluginInfo {
+            name: "mock".to_string(),
+            has_midi_input: true,
+            ..Default::default()
+        },
+        is_processing: false,
+        sample_rate: 48000.0,
+        block_size: 512,
+        audio_levels: Arc::new(Mutex::new(AudioLevels::new(2))),
+        parameter_change_callback: None,
+        audio_callback: None,
+        aftertouch_bindings: Vec::new(),
+        channel_pressure_bindings: Vec::new(),
+        midi_channel_filter: None,
+        output_analyzer: Some(crate::audio::OutputAnalyzer::new()),
+        oversampler: None,
+        polyphony_limiter: None,
+        factory_default_mismatches: Vec::new(),
+        channel_strips: Vec::new(),
+        soft_takeover: None,
+        debouncer: None,
+        velocity_curve: None,
+        block_statistics: None,
+        spectrum: None,
+        slider_curves: std::collections::HashMap::new(),
+        bus_activation: std::collections::HashMap::new(),
+        sustain: crate::midi::SustainTracker::new(),
+        pending_workarounds: Vec::new(),
+        internal: Some(internal),
+    }
+}
+
+/// No-op bodies for the required [`PluginInternal`] methods, so a test double spells out
+/// only what its test exercises. The required methods it implements itself are named after
+/// `except`, as in `stub_plugin_internal!(except process);` inside the `impl` block.
+#[cfg(test)]
+macro_rules! stub_plugin_internal {
+    ($(except $($own:ident),+ $(,)?)?) => {
+        $crate::plugin::stub_plugin_internal!(@stub set_parameter [$($($own)*)?]);
+        $crate::plugin::stub_plugin_internal!(@stub get_parameter [$($($own)*)?]);
+        $crate::plugin::stub_plugin_internal!(@stub get_all_parameters [$($($own)*)?]);
+        $crate::plugin::stub_plugin_internal!(@stub format_parameter [$($($own)*)?]);
+        $crate::plugin::stub_plugin_internal!(@stub process [$($($own)*)?]);
+        $crate::plugin::stub_plugin_internal!(@stub send_midi_event [$($($own)*)?]);
+        $crate::plugin::stub_plugin_internal!(@stub start_processing [$($($own)*)?]);
+        $crate::plugin::stub_plugin_internal!(@stub stop_processing [$($($own)*)?]);
+        $crate::plugin::stub_plugin_internal!(@stub has_editor [$($($own)*)?]);
+        $crate::plugin::stub_plugin_internal!(@stub open_editor [$($($own)*)?]);
+        $crate::plugin::stub_plugin_internal!(@stub close_editor [$($($own)*)?]);
+        $crate::plugin::stub_plugin_internal!(@stub get_editor_size [$($($own)*)?]);
+        $crate::plugin::stub_plugin_internal!(@stub get_parameter_changes [$($($own)*)?]);
+    };
+    // A method the double implements itself gets no stub...
+    (@stub set_parameter [set_parameter $($rest:ident)*]) => {};
+    (@stub get_parameter [get_parameter $($rest:ident)*]) => {};
+    (@stub get_all_parameters [get_all_parameters $($rest:ident)*]) => {};
+    (@stub format_parameter [format_parameter $($rest:ident)*]) => {};
+    (@stub process [process $($rest:ident)*]) => {};
+    (@stub send_midi_event [send_midi_event $($rest:ident)*]) => {};
+    (@stub start_processing [start_processing $($rest:ident)*]) => {};
+    (@stub stop_processing [stop_processing $($rest:ident)*]) => {};
+    (@stub has_editor [has_editor $($rest:ident)*]) => {};
+    (@stub open_editor [open_editor $($rest:ident)*]) => {};
+    (@stub close_editor [close_editor $($rest:ident)*]) => {};
+    (@stub get_editor_size [get_editor_size $($rest:ident)*]) => {};
+    (@stub get_parameter_changes [get_parameter_changes $($rest:ident)*]) => {};
+    (@stub $method:ident [$other:ident $($rest:ident)*]) => {
+        $crate::plugin::stub_plugin_internal!(@stub $method [$($rest)*]);
+    };
+    // ...and every other one a no-op.
+    (@stub set_parameter []) => {
+        fn set_parameter(&mut self, _id: u32, _value: f64) -> $crate::Result<()> {
+            Ok(())
+        }
+    };
+    (@stub get_parameter []) => {
+        fn get_parameter(&self, _id: u32) -> $crate::Result<f64> {
+            Ok(0.0)
+        }
+    };
+    (@stub get_all_parameters []) => {
+        fn get_all_parameters(&self) -> $crate::Result<Vec<$crate::parameters::Parameter>> {
+            Ok(Vec::new())
+        }
+    };
+    (@stub format_parameter []) => {
+        fn format_parameter(&self, _id: u32, normalized: f64) -> $crate::Result<String> {
+            Ok(normalized.to_string())
+        }
+    };
+    (@stub process []) => {
+        fn process(&mut self, _buffers: &mut $crate::audio::AudioBuffers) -> $crate::Result<()> {
+            Ok(())
+        }
+    };
+    (@stub send_midi_event []) => {
+        fn send_midi_event(&mut self, _event: $crate::midi::MidiEvent) -> $crate::Result<()> {
+            Ok(())
+        }
+    };
+    (@stub start_processing []) => {
+        fn start_processing(&mut self) -> $crate::Result<()> {
+            Ok(())
+        }
+    };
+    (@stub stop_processing []) => {
+        fn stop_processing(&mut self) -> $crate::Result<()> {
+            Ok(())
+        }
+    };
+    (@stub has_editor []) => {
+        fn has_editor(&self) -> bool {
+            false
+        }
+    };
+    (@stub open_editor []) => {
+        fn open_editor(
+            &mut self,
+            _parent: *mut std::ffi::c_void,
+            _platform_type: &'static std::ffi::CStr,
+        ) -> $crate::Result<()> {
+            Ok(())
+        }
+    };
+    (@stub close_editor []) => {
+        fn close_editor(&mut self) -> $crate::Result<()> {
+            Ok(())
+        }
+    };
+    (@stub get_editor_size []) => {
+        fn get_editor_size(&self) -> $crate::Result<(i32, i32)> {
+            Ok((0, 0))
+        }
+    };
+    (@stub get_parameter_changes []) => {
+        fn get_parameter_changes(&self) -> Vec<(u32, f64)> {
+            Vec::new()
+        }
+    };
+}
+#[cfg(test)]
+pub(crate) use stub_plugin_internal;
+
+#[cfg(test)]
+mod aftertouch_binding_tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Just enough of a plugin to record parameter writes.
+    #[derive(Default)]
+    struct ParamStore(HashMap<u32, f64>);
+
+    impl PluginInternal for ParamStore {
+        fn set_parameter(&mut self, id: u32, value: f64) -> Result<()> {
+            self.0.insert(id, value);
+            Ok(())
+        }
+        fn get_parameter(&self, id: u32) -> Result<f64> {
+            Ok(self.0.get(&id).copied().unwrap_or(0.0))
+        }
+        // Every parameter is a 20 Hz – 20 kHz exponential frequency.
+        fn normalized_to_plain(&self, _id: u32, normalized: f64) -> Result<f64> {
+            Ok(20.0 * 1000f64.powf(normalized))
+        }
+        fn plain_to_normalized(&self, _id: u32, plain: f64) -> Result<f64> {
+            Ok(((plain / 20.0).ln() / 1000f64.ln()).clamp(0.0, 1.0))
+        }
+        crate::plugin::stub_plugin_internal!(except set_parameter, get_parameter);
+    }
+
+    fn plugin() -> Plugin {
+        plugin_with(Box::new(ParamStore::default()))
+    }
+
+    #[test]
+    fn debounced_edits_reach_the_plugin_once() {
+        let mut plugin = plugin();
+        let writes = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&writes);
+        plugin.on_parameter_change(move |id, value| log.lock().unwrap().push((id, value)));
+        plugin.set_debounce_delay(16.0).unwrap();
+        for i in 0..100 {
+            plugin.set_parameter_debounced(2, i as f64 / 100.0).unwrap();
+        }
+        assert_eq!(plugin.apply_debounced_parameters().unwrap(), 0);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        assert_eq!(plugin.apply_debounced_parameters().unwrap(), 1);
+        assert_eq!(*writes.lock().unwrap(), [(2, 0.99)]);
+        assert_eq!(plugin.get_parameter(2).unwrap(), 0.99);
+    }
+
+    #[test]
+    fn markdown_export_without_a_bundle_still_describes_the_plugin() {
+        let md = plugin().export_info_markdown();
+        assert!(md.starts_with("# mock\n"));
+        assert!(md.contains("| MIDI input | yes |"));
+        assert!(md.contains("## Parameters"));
+    }
+
+    /// A plugin whose output turns to NaN at a given frame of every block.
+    struct NanAt(usize);
+
+    impl PluginInternal for NanAt {
+        fn process(&mut self, buffers: &mut AudioBuffers) -> Result<()> {
+            for ch in &mut buffers.outputs {
+                ch.fill(0.25);
+                ch[self.0] = f32::NAN;
+            }
+            Ok(())
+        }
+        crate::plugin::stub_plugin_internal!(except process);
+    }
+
+    /// A feedback delay with a one-block delay line: each block outputs its input plus the
+    /// previous block's output at half level.
+    #[derive(Default)]
+    struct Delay(Vec<Vec<f32>>);
+
+    impl PluginInternal for Delay {
+        fn process(&mut self, buffers: &mut AudioBuffers) -> Result<()> {
+            self.0.resize(buffers.outputs.len(), Vec::new());
+            for (ch, out) in buffers.outputs.iter_mut().enumerate() {
+                let line = &mut self.0[ch];
+                line.resize(out.len(), 0.0);
+                let input = buffers.inputs.get(ch).map(Vec::as_slice).unwrap_or(&[]);
+                for (i, o) in out.iter_mut().enumerate() {
+                    *o = input.get(i).copied().unwrap_or(0.0) + 0.5 * line[i];
+                }
+                line.copy_from_slice(out);
+            }
+            Ok(())
+        }
+        crate::plugin::stub_plugin_internal!(except process);
+    }
+
+    #[test]
+    fn delay_tail_is_measured_until_it_decays() {
+        let mut plugin = plugin_with(Box::new(Delay::default()));
+        plugin.info.audio_inputs = 2;
+        plugin.info.audio_outputs = 2;
+
+        // The echoes fall 6 dB per block, so from around -6 dBFS they need roughly nine
+        // blocks to get below -60 dBFS.
+        let tail = plugin.measure_actual_tail(60, 4, -60.0).expect("measure");
+        assert!(tail > 0);
+        assert_eq!(tail % 512, 0);
+        assert!((6 * 512..=10 * 512).contains(&tail), "tail {tail}");
+        // Processing is left as it was.
+        assert!(!plugin.is_processing());
+    }
+
+    #[test]
+    fn nan_output_is_detected_and_silenced() {
+        let mut plugin = plugin_with(Box::new(NanAt(3)));
+        plugin.start_processing().expect("start");
+        let mut buffers = AudioBuffers::new(0, 2, 16, 48000.0);
+        plugin.process_audio(&mut buffers).expect("process");
+
+        assert!(buffers.outputs.iter().flatten().all(|&x| x == 0.0));
+        let report = plugin.output_analysis().expect("analysis is on");
+        assert_eq!(report.nan_blocks, 1);
+        assert_eq!(report.last_nan_sample_index, Some(3));
+        assert!(report.needs_recovery);
+        // The silenced block must not poison the level meters either.
+        assert_eq!(plugin.get_output_levels().channels[0].peak, 0.0);
+    }
+
+    #[test]
+    fn slider_curves_default_to_linear_and_can_be_overridden() {
+        let mut plugin = plugin();
+        assert!(plugin.detect_slider_curves().is_empty());
+        assert!(matches!(plugin.slider_curve(3), SliderCurve::Linear));
+        plugin.set_slider_curve(3, SliderCurve::Sqrt);
+        assert_eq!(plugin.slider_curve(3).apply(0.5), 0.25);
+        plugin.set_slider_curve(3, SliderCurve::Linear);
+        assert!(matches!(plugin.slider_curve(3), SliderCurve::Linear));
+    }
+
+    /// One 20 Hz – 20 kHz frequency parameter (id 7), counting how often it's formatted.
+    #[derive(Default)]
+    struct Frequency(Arc<std::sync::atomic::AtomicUsize>);
+
+    impl PluginInternal for Frequency {
+        fn get_all_parameters(&self) -> Result<Vec<Parameter>> {
+            Ok(vec![Parameter {
+                id: 7,
+                name: "Frequency".to_string(),
+                value: 0.5,
+                min: 0.0,
+                max: 1.0,
+                default: 0.5,
+                unit: "Hz".to_string(),
+                step_count: 0,
+                can_automate: true,
+                is_read_only: false,
+                is_bypass: false,
+                flags: 0,
+                unit_id: 0,
+            }])
+        }
+        fn format_parameter(&self, _id: u32, normalized: f64) -> Result<String> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Ok(format!("{:.1} Hz", 20.0 * 1000f64.powf(normalized)))
+        }
+        crate::plugin::stub_plugin_internal!(except get_all_parameters, format_parameter);
+    }
+
+    #[test]
+    fn slider_curves_are_detected_on_first_use_only() {
+        let formats = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut plugin = plugin_with(Box::new(Frequency(Arc::clone(&formats))));
+        let count = || formats.load(std::sync::atomic::Ordering::Relaxed);
+        assert_eq!(count(), 0);
+
+        assert!(matches!(
+            plugin.slider_curve(7),
+            SliderCurve::Logarithmic { .. }
+        ));
+        assert_eq!(count(), 3);
+        assert!(matches!(
+            plugin.slider_curve(7),
+            SliderCurve::Logarithmic { .. }
+        ));
+        assert_eq!(count(), 3);
+        // An unknown id is linear, and asking again costs nothing either.
+        assert!(matches!(plugin.slider_curve(8), SliderCurve::Linear));
+        assert!(matches!(plugin.slider_curve(8), SliderCurve::Linear));
+        assert_eq!(count(), 3);
+    }
+
+    #[test]
+    fn key_shortcuts_are_empty_without_a_way_to_query_them() {
+        assert_eq!(plugin().get_key_shortcuts().unwrap(), Vec::new());
+        let save = KeyShortcut {
+            key: 's',
+            modifiers: ModifierKeys {
+                command: true,
+                shift: true,
+                ..ModifierKeys::default()
+            },
+            description: "Save preset".to_string(),
+        };
+        assert_eq!(save.label(), "Cmd+Shift+S");
+    }
+
+    #[cfg(feature = "egui-widgets")]
+    #[test]
+    fn minimal_gui_opens_and_closes() {
+        let mut plugin = plugin();
+        let mut gui = plugin.open_minimal_gui().unwrap();
+        let ctx = egui::Context::default();
+        for _ in 0..3 {
+            let _ = ctx.run_ui(egui::RawInput::default(), |ui| {
+                assert!(gui.show(ui.ctx(), &mut plugin));
+            });
+        }
+        assert!(gui.is_open());
+        drop(gui);
+        let _ = ctx.run_ui(egui::RawInput::default(), |_| {});
+
+        let host = crate::Vst3Host::builder().build().unwrap();
+        let gui = host
+            .open_minimal_gui(crate::midi::PluginHandle(3), &mut plugin)
+            .unwrap();
+        assert_eq!(gui.recent().ids().count(), 0);
+    }
+
+    /// A plugin with a stereo main out and a stereo aux out that records bus activations.
+    #[derive(Default)]
+    struct Buses {
+        activations: Arc<Mutex<Vec<(crate::audio::BusDirection, i32, bool)>>>,
+    }
+
+    impl PluginInternal for Buses {
+        fn bus_count(
+            &self,
+            media_type: crate::audio::MediaType,
+            direction: crate::audio::BusDirection,
+        ) -> Result<i32> {
+            use crate::audio::{BusDirection, MediaType};
+            Ok(match (media_type, direction) {
+                (MediaType::Audio, BusDirection::Output) => 2,
+                (MediaType::Event, BusDirection::Input) => 1,
+                _ => 0,
+            })
+        }
+        fn bus_arrangements(&self) -> Result<crate::audio::BusArrangements> {
+            Ok(crate::audio::BusArrangements {
+                inputs: Vec::new(),
+                outputs: vec![crate::audio::SpeakerArrangement::STEREO; 2],
+            })
+        }
+        fn set_bus_active(
+            &mut self,
+            _media_type: crate::audio::MediaType,
+            direction: crate::audio::BusDirection,
+            bus_index: i32,
+            active: bool,
+        ) -> Result<()> {
+            self.activations
+                .lock()
+                .unwrap()
+                .push((direction, bus_index, active));
+            Ok(())
+        }
+        crate::plugin::stub_plugin_internal!();
+    }
+
+    #[test]
+    fn a_saved_bus_configuration_restores_an_inactive_output() {
+        use crate::audio::{BusDirection, MediaType};
+        let buses = Buses::default();
+        let activations = Arc::clone(&buses.activations);
+        let mut plugin = plugin_with(Box::new(buses));
+
+        let all_active = plugin.save_bus_configuration();
+        assert_eq!(all_active.active_audio_outputs, [true, true]);
+        assert_eq!(all_active.active_event_inputs, [true]);
+        assert!(all_active.active_audio_inputs.is_empty());
+        assert_eq!(all_active.speaker_arrangements.len(), 2);
+
+        plugin
+            .set_bus_active(MediaType::Audio, BusDirection::Output, 1, false)
+            .unwrap();
+        let main_only = plugin.save_bus_configuration();
+        assert_eq!(main_only.active_audio_outputs, [true, false]);
+
+        plugin.apply_bus_configuration(&all_active).unwrap();
+        assert!(plugin.is_bus_active(MediaType::Audio, BusDirection::Output, 1));
+        plugin.apply_bus_configuration(&main_only).unwrap();
+        assert!(!plugin.is_bus_active(MediaType::Audio, BusDirection::Output, 1));
+        assert_eq!(plugin.save_bus_configuration(), main_only);
+        // Only the buses whose state changed were touched.
+        assert_eq!(
+            *activations.lock().unwrap(),
+            [
+                (BusDirection::Output, 1, false),
+                (BusDirection::Output, 1, true),
+                (BusDirection::Output, 1, false),
+            ]
+        );
+
+        // A configuration for another plugin's buses is refused.
+        let mismatched = crate::audio::BusConfiguration {
+            active_audio_outputs: vec![true],
+            ..main_only
+        };
+        assert!(plugin.apply_bus_configuration(&mismatched).is_err());
+        plugin.start_processing().unwrap();
+        assert!(plugin.apply_bus_configuration(&all_active).is_err());
+    }
+
+    #[test]
+    fn deactivating_a_multi_out_deactivates_that_bus_only() {
+        use crate::audio::{BusDirection, MediaType};
+        let buses = Buses::default();
+        let activations = Arc::clone(&buses.activations);
+        let mut plugin = plugin_with(Box::new(buses));
+        let outputs =
+            |plugin: &Plugin| plugin.active_bus_count(MediaType::Audio, BusDirection::Output);
+        assert_eq!(outputs(&plugin), 2);
+
+        plugin
+            .deactivate_bus(MediaType::Audio, BusDirection::Output, 1)
+            .unwrap();
+        assert_eq!(outputs(&plugin), 1);
+        assert_eq!(
+            plugin.active_bus_count(MediaType::Event, BusDirection::Input),
+            1
+        );
+        plugin
+            .activate_bus(MediaType::Audio, BusDirection::Output, 1)
+            .unwrap();
+        assert_eq!(outputs(&plugin), 2);
+        assert_eq!(
+            *activations.lock().unwrap(),
+            [
+                (BusDirection::Output, 1, false),
+                (BusDirection::Output, 1, true)
+            ]
+        );
+
+        plugin.start_processing().unwrap();
+        assert!(plugin
+            .deactivate_bus(MediaType::Audio, BusDirection::Output, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn block_statistics_are_published_and_reset_on_start() {
+        let shared = Arc::default();
+        let mut plugin = plugin_with(Box::new(NanAt(3)));
+        plugin.block_statistics = Some(crate::analysis::BlockStatisticsTap::new(Arc::clone(
+            &shared,
+        )));
+        plugin.start_processing().expect("start");
+        let mut buffers = AudioBuffers::new(0, 2, 16, 48000.0);
+        plugin.process_audio(&mut buffers).expect("process");
+        plugin.process_audio(&mut buffers).expect("process");
+        // Both channels of both blocks, after the NaN was silenced.
+        let snapshot = *shared.read().unwrap();
+        assert_eq!(snapshot.samples, 64);
+        assert!(snapshot.mean.is_finite());
+
+        plugin.stop_processing().expect("stop");
+        plugin.start_processing().expect("restart");
+        assert_eq!(shared.read().unwrap().samples, 0);
+    }
+
+    #[test]
+    fn midi_channel_filter_passes_only_its_channel() {
+        let mut plugin = plugin();
+        let on = |channel| MidiEvent::NoteOn {
+            channel,
+            note: 60,
+            velocity: 100,
+        };
+        assert!(plugin.accepts_midi(&on(MidiChannel::Ch5)));
+
+        plugin.set_midi_channel_filter(Some(MidiChannel::Ch2));
+        assert!(plugin.accepts_midi(&on(MidiChannel::Ch2)));
+        assert!(!plugin.accepts_midi(&on(MidiChannel::Ch5)));
+        // Dropped events are not an error.
+        plugin
+            .send_midi_event(on(MidiChannel::Ch5))
+            .expect("filtered send");
+    }
+
+    #[test]
+    fn channel_strips_transform_only_their_channel() {
+        let mut plugin = plugin();
+        let on = |channel| MidiEvent::NoteOn {
+            channel,
+            note: 60,
+            velocity: 100,
+        };
+        let mut strip = ChannelStrip::new(MidiChannel::Ch1);
+        strip.transpose = 12;
+        strip.velocity_scale = 0.5;
+        plugin.set_channel_strip(MidiChannel::Ch3, strip);
+        assert_eq!(
+            plugin.channel_strip(MidiChannel::Ch3).map(|s| s.channel),
+            Some(MidiChannel::Ch3)
+        );
+        assert_eq!(
+            plugin.route_midi(on(MidiChannel::Ch3)),
+            Some(MidiEvent::NoteOn {
+                channel: MidiChannel::Ch3,
+                note: 72,
+                velocity: 50,
+            })
+        );
+        assert_eq!(
+            plugin.route_midi(on(MidiChannel::Ch4)),
+            Some(on(MidiChannel::Ch4))
+        );
+
+        // A pass-through strip is the same as none.
+        plugin.set_channel_strip(MidiChannel::Ch3, ChannelStrip::new(MidiChannel::Ch3));
+        assert!(plugin.channel_strip(MidiChannel::Ch3).is_none());
+    }
+
+    #[test]
+    fn velocity_curves_reshape_note_ons_after_the_channel_strip() {
+        let mut plugin = plugin();
+        let on = |velocity| MidiEvent::NoteOn {
+            channel: MidiChannel::Ch1,
+            note: 60,
+            velocity,
+        };
+        let mut strip = ChannelStrip::new(MidiChannel::Ch1);
+        strip.velocity_offset = -40;
+        plugin.set_channel_strip(MidiChannel::Ch1, strip);
+        plugin.set_velocity_curve(VelocityCurve::new([(64, 127)]));
+        // 110 → 70 by the strip, then everything from 64 up maps to 127.
+        assert_eq!(plugin.route_midi(on(110)), Some(on(127)));
+        // 80 → 40, on the line from (0, 0) to (64, 127).
+        assert_eq!(plugin.route_midi(on(80)), Some(on(79)));
+        let off = MidiEvent::NoteOff {
+            channel: MidiChannel::Ch1,
+            note: 60,
+            velocity: 10,
+        };
+        assert_eq!(plugin.route_midi(off), Some(off));
+
+        plugin.set_velocity_curve(VelocityCurve::identity());
+        assert!(plugin.velocity_curve().is_none());
+    }
+
+    #[test]
+    fn soft_takeover_holds_cc_bindings_until_they_reach_the_parameter() {
+        let mut plugin = plugin();
+        plugin.set_parameter(4, 0.5).unwrap();
+        let binding = CcBinding::new(4, 7);
+        let ch = MidiChannel::Ch1;
+        assert_eq!(plugin.cc_binding_value(&binding, ch, 0).unwrap(), Some(0.0));
+
+        plugin.enable_soft_takeover(true);
+        assert!(plugin.soft_takeover_enabled());
+        assert_eq!(plugin.cc_binding_value(&binding, ch, 0).unwrap(), None);
+        assert_eq!(
+            plugin.cc_binding_value(&binding, ch, 127).unwrap(),
+            Some(1.0)
+        );
+
+        // An inverted range picks up where it meets the parameter too.
+        let inverted = CcBinding {
+            range: (1.0, 0.0),
+            ..CcBinding::new(4, 8)
+        };
+        plugin.set_parameter(4, 0.25).unwrap();
+        assert_eq!(plugin.cc_binding_value(&inverted, ch, 0).unwrap(), None);
+        assert_eq!(plugin.cc_binding_value(&inverted, ch, 40).unwrap(), None);
+        assert_eq!(
+            plugin.cc_binding_value(&inverted, ch, 127).unwrap(),
+            Some(0.0)
+        );
+
+        plugin.enable_soft_takeover(false);
+        assert!(!plugin.soft_takeover_enabled());
+    }
+
+    #[test]
+    fn plain_conversions_round_trip_and_drive_the_parameter() {
+        let mut plugin = plugin();
+        for i in 0..=20 {
+            let x = i as f64 / 20.0;
+            let plain = plugin.normalized_to_plain(4, x).unwrap();
+            assert!((plugin.plain_to_normalized(4, plain).unwrap() - x).abs() < 1e-9);
+        }
+
+        let mut param = Parameter {
+            id: 4,
+            name: "Cutoff".to_string(),
+            value: 0.0,
+            min: 0.0,
+            max: 1.0,
+            default: 0.0,
+            unit: "Hz".to_string(),
+            step_count: 0,
+            can_automate: true,
+            is_read_only: false,
+            is_bypass: false,
+            flags: 0,
+            unit_id: 0,
+        };
+        param.set_plain_value(&mut plugin, 632.455_532).unwrap();
+        assert!((param.value - 0.5).abs() < 1e-6);
+        assert!((plugin.get_parameter(4).unwrap() - 0.5).abs() < 1e-6);
+        assert!((param.plain_value(&plugin).unwrap() - 632.455_532).abs() < 1e-3);
+    }
+
+    #[test]
+    fn poly_aftertouch_only_drives_the_bound_note() {
+        let mut plugin = plugin();
+        plugin.add_aftertouch_binding(AftertouchBinding {
+            note: Some(60),
+            ..AftertouchBinding::new(1)
+        });
+        plugin.add_aftertouch_binding(AftertouchBinding {
+            note: Some(62),
+            ..AftertouchBinding::new(2)
+        });
+        plugin.add_channel_pressure_binding(ChannelPressureBinding::new(3));
+
+        plugin.process_aftertouch(0, Some(60), 127).unwrap();
+        assert_eq!(plugin.get_parameter(1).unwrap(), 1.0);
+        assert_eq!(plugin.get_parameter(2).unwrap(), 0.0);
+        assert_eq!(plugin.get_parameter(3).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn channel_pressure_respects_channel_and_range() {
+        let mut plugin = plugin();
+        plugin.add_channel_pressure_binding(ChannelPressureBinding {
+            channel: Some(MidiChannel::Ch2),
+            range: (1.0, 0.5),
+            ..ChannelPressureBinding::new(7)
+        });
+
+        // Wrong channel: untouched. Right channel: inverted range, full pressure -> 0.5.
+        plugin.process_aftertouch(0, None, 127).unwrap();
+        assert_eq!(plugin.get_parameter(7).unwrap(), 0.0);
+        plugin.process_aftertouch(1, None, 127).unwrap();
+        assert_eq!(plugin.get_parameter(7).unwrap(), 0.5);
+
+        plugin.clear_aftertouch_bindings();
+        assert!(plugin.channel_pressure_bindings().is_empty());
+        assert!(plugin.process_aftertouch(16, None, 0).is_err());
+    }
+
+    /// Records the MIDI it receives.
+    #[derive(Default)]
+    struct MidiLog(Arc<Mutex<Vec<MidiEvent>>>);
+
+    impl PluginInternal for MidiLog {
+        fn send_midi_event(&mut self, event: MidiEvent) -> Result<()> {
+            self.0.lock().unwrap().push(event);
+            Ok(())
+        }
+        crate::plugin::stub_plugin_internal!(except send_midi_event);
+    }
+
+    #[test]
+    fn pitch_bends_reach_the_plugin_as_14_bit_values() {
+        let log = MidiLog::default();
+        let received = log.0.clone();
+        let mut plugin = plugin_with(Box::new(log));
+        plugin.send_pitch_bend(MidiChannel::Ch1, 1.0).unwrap();
+        plugin.send_pitch_bend(MidiChannel::Ch1, -1.0).unwrap();
+        plugin.send_pitch_bend(MidiChannel::Ch1, 0.0).unwrap();
+        assert!(plugin.send_pitch_bend(MidiChannel::Ch1, 1.5).is_err());
+        assert!(plugin.send_pitch_bend(MidiChannel::Ch1, f64::NAN).is_err());
+        let values: Vec<_> = received
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| match e {
+                MidiEvent::PitchBend { value, .. } => *value,
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+        assert_eq!(values, [16383, 0, 8192]);
+    }
+
+    #[test]
+    fn polyphony_limit_sends_the_stolen_note_off_first() {
+        let log = MidiLog::default();
+        let received = log.0.clone();
+        let mut plugin = plugin_with(Box::new(log));
+        plugin.set_polyphony_limit(1, StealingStrategy::Oldest);
+
+        plugin.send_midi_note(60, 100, MidiChannel::Ch1).unwrap();
+        plugin.send_midi_note(64, 100, MidiChannel::Ch1).unwrap();
+        assert_eq!(
+            *received.lock().unwrap(),
+            [
+                MidiEvent::NoteOn {
+                    channel: MidiChannel::Ch1,
+                    note: 60,
+                    velocity: 100
+                },
+                MidiEvent::NoteOff {
+                    channel: MidiChannel::Ch1,
+                    note: 60,
+                    velocity: 0
+                },
+                MidiEvent::NoteOn {
+                    channel: MidiChannel::Ch1,
+                    note: 64,
+                    velocity: 100
+                },
+            ]
+        );
+        assert_eq!(plugin.polyphony_limiter().unwrap().active_voices(), 1);
+
+        plugin.clear_polyphony_limit();
+        received.lock().unwrap().clear();
+        plugin.send_midi_note(67, 100, MidiChannel::Ch1).unwrap();
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn lifting_sustain_sends_the_deferred_note_off() {
+        let log = MidiLog::default();
+        let received = log.0.clone();
+        let mut plugin = plugin_with(Box::new(log));
+        let note_off = |note| MidiEvent::NoteOff {
+            channel: MidiChannel::Ch2,
+            note,
+            velocity: 0,
+        };
+
+        plugin.send_midi_cc(64, 127, MidiChannel::Ch2).unwrap();
+        plugin.send_midi_note(60, 100, MidiChannel::Ch2).unwrap();
+        plugin.send_midi_note(64, 100, MidiChannel::Ch2).unwrap();
+        plugin.send_midi_note_off(60, MidiChannel::Ch2).unwrap();
+        assert!(plugin.sustained_channels()[1]);
+        received.lock().unwrap().clear();
+
+        // Only the note let go under the pedal is released; 64 is still held down.
+        plugin.send_midi_cc(64, 0, MidiChannel::Ch2).unwrap();
+        assert_eq!(
+            *received.lock().unwrap(),
+            [
+                MidiEvent::ControlChange {
+                    channel: MidiChannel::Ch2,
+                    controller: 64,
+                    value: 0
+                },
+                note_off(60),
+            ]
+        );
+        assert!(!plugin.sustained_channels()[1]);
+
+        // A panic lifts a pedal left down before its all-notes-off.
+        plugin.send_midi_cc(64, 100, MidiChannel::Ch2).unwrap();
+        plugin.send_midi_note_off(64, MidiChannel::Ch2).unwrap();
+        received.lock().unwrap().clear();
+        plugin.midi_panic().unwrap();
+        assert_eq!(received.lock().unwrap()[1], note_off(64));
+        assert_eq!(received.lock().unwrap().len(), 2 + 16 * 3);
+        assert_eq!(plugin.sustained_channels(), [false; 16]);
+    }
+}
+
 #[cfg(test)]
 mod vstpreset_tests {
     use super::vstpreset;
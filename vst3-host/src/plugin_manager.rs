@@ -0,0 +1,399 @@
+//! Organizing installed plugins on disk: moving bundles into the user's plugin folder,
+//! linking them into other folders, disabling and re-enabling them, and backing them up.
+//!
+//! Every operation that moves or adds a plugin keeps the [`ScanCache`] in step, so the next
+//! scan finds the plugin's metadata under its new path instead of loading it again.
+//!
+//! ```no_run
+//! # use vst3_host::Vst3Host;
+//! # fn main() -> vst3_host::Result<()> {
+//! let mut host = Vst3Host::new()?;
+//! let disabled = host
+//!     .plugin_manager()
+//!     .disable_plugin("/Library/Audio/Plug-Ins/VST3/Noisy.vst3".as_ref())?;
+//! host.plugin_manager().enable_plugin(&disabled)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    discovery::ScanCache,
+    error::{Error, Result},
+};
+
+/// The extension [`PluginManager::disable_plugin`] appends, which scans skip.
+pub const DISABLED_EXTENSION: &str = "disabled";
+
+/// The per-user VST3 folder: `~/Library/Audio/Plug-Ins/VST3` on macOS, `~/.vst3` on Linux and
+/// `%LOCALAPPDATA%\Programs\Common\VST3` on Windows. `None` if the home folder is unknown.
+pub fn user_plugin_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Audio/Plug-Ins/VST3"))
+    }
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var_os("LOCALAPPDATA")
+            .map(|local| PathBuf::from(local).join(r"Programs\Common\VST3"))
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".vst3"))
+    }
+}
+
+/// File-system operations on plugin bundles, keeping a [`ScanCache`] up to date. Get one from
+/// [`Vst3Host::plugin_manager`](crate::Vst3Host::plugin_manager) to work on the host's cache.
+pub struct PluginManager<'a> {
+    cache: &'a mut ScanCache,
+    user_dir: Option<PathBuf>,
+}
+
+impl<'a> PluginManager<'a> {
+    /// A manager updating `cache`, moving plugins into [`user_plugin_dir`].
+    pub fn new(cache: &'a mut ScanCache) -> Self {
+        Self {
+            cache,
+            user_dir: user_plugin_dir(),
+        }
+    }
+
+    /// Move plugins into `dir` instead of [`user_plugin_dir`].
+    pub fn with_user_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.user_dir = Some(dir.into());
+        self
+    }
+
+    /// The folder [`Self::move_to_user_dir`] moves plugins into.
+    pub fn user_dir(&self) -> Option<&Path> {
+        self.user_dir.as_deref()
+    }
+
+    /// Move the plugin at `path` into the user plugin folder, creating the folder if needed.
+    /// A plugin of the same name already there is left alone and an error returned.
+    pub fn move_to_user_dir(&mut self, path: &Path) -> Result<()> {
+        let dir = self
+            .user_dir
+            .clone()
+            .ok_or_else(|| Error::Other("no user plugin folder on this system".to_string()))?;
+        std::fs::create_dir_all(&dir)?;
+        let destination = dir.join(file_name(path)?);
+        move_path(path, &destination)?;
+        self.cache.rename(path, &destination);
+        Ok(())
+    }
+
+    /// Make the plugin at `path` also appear in `target_dir` through a symbolic link, so
+    /// hosts scanning either folder find it.
+    pub fn create_symlink(&mut self, path: &Path, target_dir: &Path) -> Result<()> {
+        let link = target_dir.join(file_name(path)?);
+        refuse_existing(&link)?;
+        let original = std::fs::canonicalize(path)?;
+        symlink(&original, &link)?;
+        if let Some(entry) = self.cache.get(path).cloned() {
+            let mut info = entry.info;
+            info.path = link;
+            self.cache.insert(info)?;
+        }
+        Ok(())
+    }
+
+    /// Hide the plugin at `path` from scans by renaming `Name.vst3` to `Name.vst3.disabled`.
+    /// Returns the new path.
+    pub fn disable_plugin(&mut self, path: &Path) -> Result<PathBuf> {
+        if is_disabled(path) {
+            return Err(Error::InvalidParameter(format!(
+                "{} is already disabled",
+                path.display()
+            )));
+        }
+        let mut disabled = path.as_os_str().to_owned();
+        disabled.push(".");
+        disabled.push(DISABLED_EXTENSION);
+        let disabled = PathBuf::from(disabled);
+        move_path(path, &disabled)?;
+        self.cache.rename(path, &disabled);
+        Ok(disabled)
+    }
+
+    /// Undo [`Self::disable_plugin`]: rename `Name.vst3.disabled` back to `Name.vst3`.
+    /// Returns the new path.
+    pub fn enable_plugin(&mut self, path: &Path) -> Result<PathBuf> {
+        if !is_disabled(path) {
+            return Err(Error::InvalidParameter(format!(
+                "{} is not a disabled plugin",
+                path.display()
+            )));
+        }
+        let enabled = path.with_extension("");
+        move_path(path, &enabled)?;
+        self.cache.rename(path, &enabled);
+        Ok(enabled)
+    }
+
+    /// Copy the plugin at `path` (the whole bundle) into `backup_dir`, creating the folder
+    /// if needed. An existing backup of the same name is never overwritten. The plugin
+    /// itself stays where it is, so the cache is unchanged.
+    pub fn backup(&self, path: &Path, backup_dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(backup_dir)?;
+        let destination = backup_dir.join(file_name(path)?);
+        refuse_existing(&destination)?;
+        copy_or_clean_up(path, &destination)
+    }
+}
+
+/// Whether `path` is a plugin renamed by [`PluginManager::disable_plugin`].
+pub fn is_disabled(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == DISABLED_EXTENSION)
+        && Path::new(path.file_stem().unwrap_or_default())
+            .extension()
+            .is_some_and(|ext| ext == "vst3")
+}
+
+fn file_name(path: &Path) -> Result<&std::ffi::OsStr> {
+    path.file_name()
+        .ok_or_else(|| Error::InvalidParameter(format!("{} has no file name", path.display())))
+}
+
+fn refuse_existing(path: &Path) -> Result<()> {
+    if path.symlink_metadata().is_ok() {
+        return Err(Error::Other(format!("{} already exists", path.display())));
+    }
+    Ok(())
+}
+
+/// Rename `from` to `to`, falling back to copy-and-delete when they're on different file
+/// systems. Any other rename error is returned as is.
+fn move_path(from: &Path, to: &Path) -> Result<()> {
+    refuse_existing(to)?;
+    if from.symlink_metadata().is_err() {
+        return Err(Error::PluginNotFound(from.display().to_string()));
+    }
+    match std::fs::rename(from, to) {
+        Ok(()) => return Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {}
+        Err(e) => return Err(e.into()),
+    }
+    copy_or_clean_up(from, to)?;
+    remove_path(from)
+}
+
+/// [`copy_recursively`], removing whatever it wrote of `to` if it fails part way.
+fn copy_or_clean_up(from: &Path, to: &Path) -> Result<()> {
+    copy_recursively(from, to).inspect_err(|_| {
+        let _ = remove_path(to);
+    })
+}
+
+/// Copy a file, symlink or directory tree. Symlinks are copied as links, not followed.
+fn copy_recursively(from: &Path, to: &Path) -> Result<()> {
+    let file_type = from.symlink_metadata()?.file_type();
+    if file_type.is_symlink() {
+        return symlink(&std::fs::read_link(from)?, to);
+    }
+    if !file_type.is_dir() {
+        std::fs::copy(from, to)?;
+        return Ok(());
+    }
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        copy_recursively(&entry.path(), &to.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+/// Remove a file, symlink (not its target) or directory tree.
+fn remove_path(path: &Path) -> Result<()> {
+    if path.symlink_metadata()?.is_dir() {
+        std::fs::remove_dir_all(path)?;
+    } else {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink(original: &Path, link: &Path) -> Result<()> {
+    Ok(std::os::unix::fs::symlink(original, link)?)
+}
+
+#[cfg(windows)]
+fn symlink(original: &Path, link: &Path) -> Result<()> {
+    if original.is_dir() {
+        Ok(std::os::windows::fs::symlink_dir(original, link)?)
+    } else {
+        Ok(std::os::windows::fs::symlink_file(original, link)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{discovery::parse_category, plugin::PluginInfo};
+
+    /// A fresh directory under the temp dir, removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir()
+                .join(format!("vh_plugin_manager_{name}_{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// A single-file plugin at `dir/Name.vst3`, cached in `cache`.
+    fn cached_plugin(dir: &Path, cache: &mut ScanCache) -> PathBuf {
+        let path = dir.join("Name.vst3");
+        std::fs::write(&path, b"plugin binary").unwrap();
+        cache
+            .insert(PluginInfo {
+                path: path.clone(),
+                name: "Name".into(),
+                category_path: parse_category("Fx"),
+                audio_inputs: 1,
+                audio_outputs: 1,
+                ..Default::default()
+            })
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn moving_to_the_user_dir_carries_the_cache_entry() {
+        let tmp = TempDir::new("move");
+        let mut cache = ScanCache::new();
+        let path = cached_plugin(&tmp.0, &mut cache);
+        let user_dir = tmp.0.join("user");
+
+        let mut manager = PluginManager::new(&mut cache).with_user_dir(&user_dir);
+        manager.move_to_user_dir(&path).unwrap();
+        let moved = user_dir.join("Name.vst3");
+        assert!(!path.exists());
+        assert_eq!(std::fs::read(&moved).unwrap(), b"plugin binary");
+        assert!(cache.get(&path).is_none());
+        assert_eq!(cache.fresh_info(&moved).unwrap().path, moved);
+    }
+
+    #[test]
+    fn moving_never_overwrites() {
+        let tmp = TempDir::new("overwrite");
+        let mut cache = ScanCache::new();
+        let path = cached_plugin(&tmp.0, &mut cache);
+        let user_dir = tmp.0.join("user");
+        std::fs::create_dir_all(&user_dir).unwrap();
+        std::fs::write(user_dir.join("Name.vst3"), b"other").unwrap();
+
+        let mut manager = PluginManager::new(&mut cache).with_user_dir(&user_dir);
+        assert!(manager.move_to_user_dir(&path).is_err());
+        assert!(path.exists());
+        assert_eq!(std::fs::read(user_dir.join("Name.vst3")).unwrap(), b"other");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_make_the_plugin_appear_in_both_folders() {
+        let tmp = TempDir::new("symlink");
+        let mut cache = ScanCache::new();
+        let path = cached_plugin(&tmp.0, &mut cache);
+        let other = tmp.0.join("other");
+        std::fs::create_dir_all(&other).unwrap();
+
+        PluginManager::new(&mut cache)
+            .create_symlink(&path, &other)
+            .unwrap();
+        let link = other.join("Name.vst3");
+        assert!(link.symlink_metadata().unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read(&link).unwrap(), b"plugin binary");
+        assert!(cache.fresh_info(&path).is_some());
+        assert_eq!(cache.fresh_info(&link).unwrap().path, link);
+    }
+
+    #[test]
+    fn disabling_and_enabling_round_trips() {
+        let tmp = TempDir::new("disable");
+        let mut cache = ScanCache::new();
+        let path = cached_plugin(&tmp.0, &mut cache);
+        let mut manager = PluginManager::new(&mut cache);
+
+        let disabled = manager.disable_plugin(&path).unwrap();
+        assert_eq!(disabled, tmp.0.join("Name.vst3.disabled"));
+        assert!(is_disabled(&disabled));
+        assert!(!path.exists());
+        assert!(manager.disable_plugin(&disabled).is_err());
+        let found = crate::discovery::scan_directories(std::slice::from_ref(&tmp.0)).unwrap();
+        assert!(found.is_empty());
+
+        let enabled = manager.enable_plugin(&disabled).unwrap();
+        assert_eq!(enabled, path);
+        assert!(manager.enable_plugin(&enabled).is_err());
+        assert!(cache.get(&disabled).is_none());
+        assert!(cache.fresh_info(&path).is_some());
+    }
+
+    #[test]
+    fn backups_copy_whole_bundles() {
+        let tmp = TempDir::new("backup");
+        let bundle = tmp.0.join("Bundle.vst3");
+        let binary_dir = bundle.join("Contents/x86_64-linux");
+        std::fs::create_dir_all(&binary_dir).unwrap();
+        std::fs::write(binary_dir.join("Bundle.so"), b"binary").unwrap();
+        let backups = tmp.0.join("backups");
+
+        let mut cache = ScanCache::new();
+        let manager = PluginManager::new(&mut cache);
+        manager.backup(&bundle, &backups).unwrap();
+        assert_eq!(
+            std::fs::read(backups.join("Bundle.vst3/Contents/x86_64-linux/Bundle.so")).unwrap(),
+            b"binary"
+        );
+        assert!(bundle.exists());
+        assert!(manager.backup(&bundle, &backups).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copies_keep_symlinks_as_links() {
+        let tmp = TempDir::new("copy_links");
+        let bundle = tmp.0.join("Bundle.vst3");
+        std::fs::create_dir_all(bundle.join("Versions/A")).unwrap();
+        std::fs::write(bundle.join("Versions/A/Bundle"), b"binary").unwrap();
+        std::os::unix::fs::symlink("Versions/A", bundle.join("Current")).unwrap();
+
+        let copy = tmp.0.join("Copy.vst3");
+        copy_recursively(&bundle, &copy).unwrap();
+        let link = copy.join("Current");
+        assert!(link.symlink_metadata().unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_link(&link).unwrap(), Path::new("Versions/A"));
+        assert_eq!(std::fs::read(link.join("Bundle")).unwrap(), b"binary");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failed_copies_leave_no_partial_destination() {
+        let tmp = TempDir::new("copy_fails");
+        let bundle = tmp.0.join("Bundle.vst3");
+        std::fs::create_dir_all(bundle.join("Contents")).unwrap();
+        std::fs::write(bundle.join("Contents/Bundle.so"), b"binary").unwrap();
+        // A socket can't be opened for reading, so copying it fails.
+        let _socket =
+            std::os::unix::net::UnixListener::bind(bundle.join("Contents/socket")).unwrap();
+
+        let copy = tmp.0.join("Copy.vst3");
+        assert!(copy_or_clean_up(&bundle, &copy).is_err());
+        assert!(copy.symlink_metadata().is_err());
+        assert!(bundle.join("Contents/Bundle.so").exists());
+    }
+}
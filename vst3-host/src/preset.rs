@@ -0,0 +1,425 @@
+//! Plugin presets: state migration across plugin versions, and rendering a sweep between
+//! two presets.
+//!
+//! A state blob from [`Plugin::save_state`] is private to the plugin, and a plugin update can
+//! change its layout so old blobs fail to load (or load wrong values). A [`MigrationStep`]
+//! rewrites a blob from one plugin version to the next; [`PresetLoader::load_with_migration`]
+//! chains the steps that lead to the loaded plugin's version ([`PluginInfo::version`]) and
+//! applies the result.
+//!
+//! A [`PresetInterpolator`] morphs a plugin between two [`ParameterSnapshot`]s and captures
+//! the audio at each step, showing how the sound evolves from one preset to the other.
+//!
+//! [`PluginInfo::version`]: crate::PluginInfo::version
+
+use crate::audio::AudioBuffers;
+use crate::error::{Error, Result};
+use crate::parameters::ParameterSnapshot;
+use crate::plugin::Plugin;
+
+/// Rewrites a state blob saved by `from_version` of a plugin into the layout `to_version`
+/// expects.
+pub struct MigrationStep {
+    /// Plugin version the input bytes were saved with
+    pub from_version: String,
+    /// Plugin version the output bytes are for
+    pub to_version: String,
+    /// The rewrite itself
+    pub transform: Box<dyn Fn(Vec<u8>) -> Result<Vec<u8>> + Send + Sync>,
+}
+
+impl MigrationStep {
+    /// Create a step from `from_version` to `to_version`.
+    pub fn new<F>(
+        from_version: impl Into<String>,
+        to_version: impl Into<String>,
+        transform: F,
+    ) -> Self
+    where
+        F: Fn(Vec<u8>) -> Result<Vec<u8>> + Send + Sync + 'static,
+    {
+        Self {
+            from_version: from_version.into(),
+            to_version: to_version.into(),
+            transform: Box::new(transform),
+        }
+    }
+}
+
+impl std::fmt::Debug for MigrationStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MigrationStep")
+            .field("from_version", &self.from_version)
+            .field("to_version", &self.to_version)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An ordered set of [`MigrationStep`]s for one plugin.
+#[derive(Debug, Default)]
+pub struct StateMigration {
+    /// The steps, in any order; chains are found by matching versions
+    pub steps: Vec<MigrationStep>,
+}
+
+impl StateMigration {
+    /// Create an empty migration set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a step
+    pub fn step<F>(mut self, from_version: &str, to_version: &str, transform: F) -> Self
+    where
+        F: Fn(Vec<u8>) -> Result<Vec<u8>> + Send + Sync + 'static,
+    {
+        self.steps
+            .push(MigrationStep::new(from_version, to_version, transform));
+        self
+    }
+
+    /// Migrate `bytes` saved by `from_version` to `to_version`. Equal versions return the
+    /// bytes untouched; a missing link in the chain is an error.
+    pub fn migrate(&self, bytes: Vec<u8>, from_version: &str, to_version: &str) -> Result<Vec<u8>> {
+        migrate(&self.steps, bytes, from_version, to_version)
+    }
+}
+
+/// Applies saved state to a plugin, migrating it from older plugin versions when needed.
+pub struct PresetLoader;
+
+impl PresetLoader {
+    /// Load `bytes` into `plugin`, migrating them if they come from an older plugin version.
+    ///
+    /// Raw state blobs carry no host-readable version, so the bytes are first tried as-is.
+    /// If the plugin rejects them, each version that `migrations` can bring up to the
+    /// plugin's current version ([`PluginInfo::version`]) is tried in turn, newest first, and
+    /// the first migrated blob the plugin accepts wins. Note a plugin may accept (and
+    /// misread) bytes of the wrong layout without error; when the saved version is known,
+    /// prefer [`StateMigration::migrate`] followed by [`Plugin::load_state`].
+    ///
+    /// [`PluginInfo::version`]: crate::PluginInfo::version
+    pub fn load_with_migration(
+        plugin: &mut Plugin,
+        bytes: &[u8],
+        migrations: &[MigrationStep],
+    ) -> Result<()> {
+        let direct = match plugin.load_state(bytes) {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+
+        let target = plugin.info().version.clone();
+        let chain = chain_to(migrations, &target);
+        if chain.is_empty() {
+            return Err(direct);
+        }
+
+        let mut last_error = direct;
+        for start in (0..chain.len()).rev() {
+            let migrated = chain[start..]
+                .iter()
+                .try_fold(bytes.to_vec(), |state, step| (step.transform)(state));
+            match migrated.and_then(|state| plugin.load_state(&state)) {
+                Ok(()) => {
+                    log::info!(
+                        "Migrated {} state from version {} to {target}",
+                        plugin.info().name,
+                        chain[start].from_version
+                    );
+                    return Ok(());
+                }
+                Err(e) => last_error = e,
+            }
+        }
+        Err(Error::Other(format!(
+            "state did not load as-is or after migrating to version {target}: {last_error}"
+        )))
+    }
+}
+
+/// The steps leading to `target`, oldest first (each step's `to_version` is the next step's
+/// `from_version`). Stops at the first version with no incoming step, or at a cycle.
+fn chain_to<'a>(steps: &'a [MigrationStep], target: &str) -> Vec<&'a MigrationStep> {
+    let mut chain: Vec<&MigrationStep> = Vec::new();
+    let mut current = target;
+    while let Some(step) = steps.iter().find(|s| s.to_version == current) {
+        if chain.iter().any(|s| std::ptr::eq(*s, step)) {
+            break;
+        }
+        chain.push(step);
+        current = &step.from_version;
+    }
+    chain.reverse();
+    chain
+}
+
+fn migrate(
+    steps: &[MigrationStep],
+    bytes: Vec<u8>,
+    from_version: &str,
+    to_version: &str,
+) -> Result<Vec<u8>> {
+    if from_version == to_version {
+        return Ok(bytes);
+    }
+    let chain = chain_to(steps, to_version);
+    let start = chain
+        .iter()
+        .position(|s| s.from_version == from_version)
+        .ok_or_else(|| {
+            Error::Other(format!(
+                "no state migration path from version {from_version} to {to_version}"
+            ))
+        })?;
+    chain[start..]
+        .iter()
+        .try_fold(bytes, |state, step| (step.transform)(state))
+}
+
+/// Renders a plugin's sound along a morph between two presets, for preset sweeps and audio
+/// demos. Each step moves the plugin to a position `t` between [`a`](Self::a) (`t = 0.0`) and
+/// [`b`](Self::b) (`t = 1.0`) with [`Plugin::morph_snapshots`] and captures the audio it
+/// renders from silent input. The positions need not be evenly spaced, so a sweep can
+/// linger where the sound changes most.
+#[derive(Debug, Clone)]
+pub struct PresetInterpolator {
+    /// The preset at `t = 0.0`
+    pub a: ParameterSnapshot,
+    /// The preset at `t = 1.0`
+    pub b: ParameterSnapshot,
+}
+
+impl PresetInterpolator {
+    /// Interpolate from `a` to `b`, e.g. two [`Plugin::snapshot_parameters`] taken with
+    /// different presets loaded.
+    pub fn new(a: ParameterSnapshot, b: ParameterSnapshot) -> Self {
+        Self { a, b }
+    }
+
+    /// Morph `plugin` to each of `t_values` in turn and render `samples_per_step` samples
+    /// there, returning one buffer per step. Processing is started for the sweep if needed
+    /// (and stopped again); the plugin is left at the last position.
+    pub fn render(
+        &self,
+        plugin: &mut Plugin,
+        t_values: &[f64],
+        samples_per_step: usize,
+    ) -> Result<Vec<AudioBuffers>> {
+        if samples_per_step == 0 {
+            return Err(Error::InvalidParameter(
+                "a preset sweep step needs at least one sample".to_string(),
+            ));
+        }
+        let was_processing = plugin.is_processing();
+        plugin.start_processing()?;
+        let steps = t_values
+            .iter()
+            .map(|&t| {
+                plugin.morph_snapshots(&self.a, &self.b, t)?;
+                render_silence(plugin, samples_per_step)
+            })
+            .collect::<Result<Vec<_>>>();
+        if !was_processing {
+            plugin.stop_processing()?;
+        }
+        steps
+    }
+
+    /// [`Self::render`] `block_size` samples at each of `t_values` and write the steps one
+    /// after another to a 32-bit float WAV file at `output`.
+    pub fn render_to_file(
+        &self,
+        plugin: &mut Plugin,
+        t_values: &[f64],
+        block_size: usize,
+        output: &std::path::Path,
+    ) -> Result<()> {
+        let steps = self.render(plugin, t_values, block_size)?;
+        let channels = steps.first().map_or(0, |step| step.outputs.len());
+        let joined: Vec<Vec<f32>> = (0..channels)
+            .map(|ch| {
+                steps
+                    .iter()
+                    .flat_map(|step| step.outputs[ch].iter().copied())
+                    .collect()
+            })
+            .collect();
+        crate::audio::write_wav(output, &joined, plugin.sample_rate() as u32)
+    }
+}
+
+/// Process `samples` samples of silence through `plugin`, a block at a time, into one buffer.
+fn render_silence(plugin: &mut Plugin, samples: usize) -> Result<AudioBuffers> {
+    let sample_rate = plugin.sample_rate();
+    let block = plugin.block_size().max(1);
+    let inputs = plugin.info().audio_inputs as usize;
+    let outputs = plugin.output_channel_count().max(1);
+    let mut rendered = AudioBuffers::new(inputs, outputs, samples, sample_rate);
+    let mut done = 0;
+    while done < samples {
+        let len = block.min(samples - done);
+        let mut buffers = AudioBuffers::new(inputs, outputs, len, sample_rate);
+        plugin.process_audio(&mut buffers)?;
+        for (to, from) in rendered.outputs.iter_mut().zip(&buffers.outputs) {
+            let n = len.min(from.len());
+            to[done..done + n].copy_from_slice(&from[..n]);
+        }
+        done += len;
+    }
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_op_migration_round_trips_bytes_unchanged() {
+        let migration = StateMigration::new().step("1.0.0", "1.1.0", Ok);
+        let bytes = vec![0u8, 1, 2, 254, 255];
+        assert_eq!(
+            migration.migrate(bytes.clone(), "1.0.0", "1.1.0").unwrap(),
+            bytes
+        );
+        assert_eq!(
+            migration.migrate(bytes.clone(), "1.1.0", "1.1.0").unwrap(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn steps_chain_in_version_order() {
+        // Registered out of order; the chain follows versions, not insertion order.
+        let migration = StateMigration::new()
+            .step("2", "3", |mut b| {
+                b.push(3);
+                Ok(b)
+            })
+            .step("1", "2", |mut b| {
+                b.push(2);
+                Ok(b)
+            });
+        assert_eq!(migration.migrate(vec![1], "1", "3").unwrap(), vec![1, 2, 3]);
+        assert_eq!(migration.migrate(vec![2], "2", "3").unwrap(), vec![2, 3]);
+    }
+
+    #[test]
+    fn missing_path_and_failing_step_are_errors() {
+        let migration = StateMigration::new()
+            .step("1", "2", Ok)
+            .step("2", "3", |_| Err(Error::Other("corrupt".to_string())));
+        assert!(migration.migrate(vec![], "0", "3").is_err());
+        assert!(migration.migrate(vec![], "1", "3").is_err());
+    }
+
+    #[test]
+    fn cyclic_steps_terminate() {
+        let steps = vec![
+            MigrationStep::new("a", "b", Ok),
+            MigrationStep::new("b", "a", Ok),
+        ];
+        assert_eq!(chain_to(&steps, "a").len(), 2);
+    }
+
+    /// A sine at 1 kHz, scaled by parameter 0 (level) and doubled when parameter 1 (a switch)
+    /// is on. Its phase carries across blocks.
+    struct Tone {
+        values: [f64; 2],
+        phase: f64,
+    }
+
+    impl crate::plugin::PluginInternal for Tone {
+        fn set_parameter(&mut self, id: u32, value: f64) -> Result<()> {
+            self.values[id as usize] = value;
+            Ok(())
+        }
+        fn get_parameter(&self, id: u32) -> Result<f64> {
+            Ok(self.values[id as usize])
+        }
+        fn get_all_parameters(&self) -> Result<Vec<crate::parameters::Parameter>> {
+            Ok(vec![
+                param(0, self.values[0], 0),
+                param(1, self.values[1], 1),
+            ])
+        }
+        fn process(&mut self, buffers: &mut AudioBuffers) -> Result<()> {
+            let gain = self.values[0] * (1.0 + self.values[1]);
+            for i in 0..buffers.block_size {
+                let sample = (gain * self.phase.sin()) as f32;
+                for channel in &mut buffers.outputs {
+                    channel[i] = sample;
+                }
+                self.phase += 2.0 * std::f64::consts::PI * 1000.0 / 48_000.0;
+            }
+            Ok(())
+        }
+        crate::plugin::stub_plugin_internal!(
+            except set_parameter, get_parameter, get_all_parameters, process
+        );
+    }
+
+    fn param(id: u32, value: f64, step_count: i32) -> crate::parameters::Parameter {
+        crate::parameters::Parameter {
+            id,
+            name: format!("P{id}"),
+            value,
+            min: 0.0,
+            max: 1.0,
+            default: 0.0,
+            unit: String::new(),
+            step_count,
+            can_automate: true,
+            is_read_only: false,
+            is_bypass: false,
+            flags: 0,
+            unit_id: 0,
+        }
+    }
+
+    fn tone() -> Plugin {
+        let mut plugin = crate::plugin::plugin_with(Box::new(Tone {
+            values: [0.0; 2],
+            phase: 0.0,
+        }));
+        plugin.info.name = "Tone".to_string();
+        plugin.info.audio_outputs = 2;
+        plugin.info.has_midi_input = false;
+        plugin.block_size = 64;
+        plugin
+    }
+
+    #[test]
+    fn rendering_the_endpoints_matches_applying_each_snapshot() {
+        let a = ParameterSnapshot::new(vec![param(0, 0.25, 0), param(1, 0.0, 1)]);
+        let b = ParameterSnapshot::new(vec![param(0, 0.75, 0), param(1, 1.0, 1)]);
+        let interpolator = PresetInterpolator::new(a.clone(), b.clone());
+        // 100 samples per step: a block of 64 and a partial one.
+        let mut swept = tone();
+        let steps = interpolator.render(&mut swept, &[0.0, 1.0], 100).unwrap();
+        assert!(!swept.is_processing());
+        assert_eq!(steps.len(), 2);
+
+        let mut direct = tone();
+        direct.start_processing().unwrap();
+        for (snapshot, step) in [a, b].iter().zip(&steps) {
+            for p in &snapshot.parameters {
+                direct.set_parameter(p.id, p.value).unwrap();
+            }
+            let expected = render_silence(&mut direct, 100).unwrap();
+            assert_eq!(step.outputs, expected.outputs);
+        }
+        assert!(steps[1].outputs[0].iter().any(|&s| s.abs() > 1.0));
+
+        let path = std::env::temp_dir().join(format!("vh_sweep_{}.wav", std::process::id()));
+        interpolator
+            .render_to_file(&mut tone(), &[0.0, 0.1, 1.0], 50, &path)
+            .unwrap();
+        let (channels, sample_rate) = crate::audio::read_wav(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(sample_rate, 48_000);
+        assert_eq!(channels.len(), 2);
+        assert_eq!(channels[0].len(), 150);
+        assert!(interpolator.render(&mut tone(), &[0.5], 0).is_err());
+    }
+}
@@ -5,14 +5,88 @@
 
 use serde::{Deserialize, Serialize};
 use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Child, ChildStdin, Command, Stdio};
-use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
 use std::time::Duration;
 
 /// Default time to wait for a helper response before treating the plugin as hung.
 pub(crate) const DEFAULT_RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// Environment variable naming the file the helper writes its [`CrashReport`] to when it
+/// dies on a fatal signal (or, on Windows, an unhandled exception).
+pub const CRASH_REPORT_ENV: &str = "VST3_HOST_CRASH_REPORT";
+
+/// Environment variable that, when set, replaces [`helper_log_dir`]'s default location.
+pub const HELPER_LOG_DIR_ENV: &str = "VST3_HOST_LOG_DIR";
+
+/// Helper logs untouched for longer than this are deleted whenever a new one is created.
+const HELPER_LOG_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// What the helper knew when it crashed, written from its fatal-signal handler and read
+/// back by the host once the helper is gone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrashReport {
+    /// Path of the plugin the helper had loaded (empty if none had been loaded yet).
+    pub plugin_path: String,
+    /// Name of the last command the helper received, e.g. `"ProcessAudio"`.
+    pub last_command: String,
+    /// When the crash happened, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The fatal signal number, or the exception code on Windows.
+    pub signal: i32,
+    /// The crashing thread's backtrace, as far as it could be symbolized.
+    pub backtrace: String,
+}
+
+impl CrashReport {
+    /// A readable name for [`Self::signal`], such as `"SIGSEGV"`, or `None` if unknown.
+    pub fn signal_name(&self) -> Option<&'static str> {
+        if cfg!(windows) {
+            return match self.signal as u32 {
+                0xC000_0005 => Some("EXCEPTION_ACCESS_VIOLATION"),
+                0xC000_001D => Some("EXCEPTION_ILLEGAL_INSTRUCTION"),
+                0xC000_0094 => Some("EXCEPTION_INT_DIVIDE_BY_ZERO"),
+                0xC000_00FD => Some("EXCEPTION_STACK_OVERFLOW"),
+                0xC000_0409 => Some("STATUS_STACK_BUFFER_OVERRUN"),
+                _ => None,
+            };
+        }
+        match self.signal {
+            6 => Some("SIGABRT"),
+            11 => Some("SIGSEGV"),
+            // SIGBUS is 7 on Linux and 10 on the BSDs and macOS.
+            7 if cfg!(target_os = "linux") => Some("SIGBUS"),
+            10 if !cfg!(target_os = "linux") => Some("SIGBUS"),
+            _ => None,
+        }
+    }
+
+    /// Read and remove the report at `path`; `None` if the helper didn't write one.
+    fn take_from(path: &Path) -> Option<Self> {
+        let text = std::fs::read_to_string(path).ok()?;
+        let _ = std::fs::remove_file(path);
+        serde_json::from_str(&text).ok()
+    }
+}
+
+impl std::fmt::Display for CrashReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.signal_name() {
+            Some(name) => write!(f, "{name}")?,
+            None => write!(f, "signal {}", self.signal)?,
+        }
+        write!(f, " during {}", self.last_command)?;
+        if !self.plugin_path.is_empty() {
+            write!(f, " in {}", self.plugin_path)?;
+        }
+        Ok(())
+    }
+}
+
 /// Commands that can be sent to the isolated plugin process.
 ///
 /// This enum is the single source of truth for the isolation IPC protocol — the
@@ -34,6 +108,9 @@ pub enum HostCommand {
         time_sig_numerator: i32,
         /// Time signature denominator to advertise in the host `ProcessContext`.
         time_sig_denominator: i32,
+        /// Sample precision to process in (32-bit when absent, as from an older host).
+        #[serde(default)]
+        sample_size: crate::audio::SampleSize,
     },
     /// Unload the current plugin
     UnloadPlugin,
@@ -41,6 +118,13 @@ pub enum HostCommand {
     CreateGui,
     /// Close plugin GUI
     CloseGui,
+    /// Attach the plugin editor into a window of the host process ([`GuiBridge::Embedded`])
+    /// instead of a helper-owned one. Closed with `CloseGui` like any other editor.
+    OpenGui {
+        /// The host's parent window as an integer: an `HWND` on Windows, an X11 window id on
+        /// Linux.
+        parent_handle: usize,
+    },
     /// Start the plugin's audio processing.
     StartProcessing,
     /// Stop the plugin's audio processing.
@@ -93,6 +177,12 @@ pub enum HostCommand {
         /// Whether the transport is playing.
         playing: bool,
     },
+    /// Move the transport playhead in the plugin's host `ProcessContext`, taking effect on the
+    /// next processed block.
+    SetTransportPosition {
+        /// Playhead position in samples.
+        samples: i64,
+    },
     /// Read a parameter's current normalized value.
     GetParameter {
         /// Parameter id.
@@ -107,6 +197,27 @@ pub enum HostCommand {
         /// Normalized value to format.
         normalized: f64,
     },
+    /// Parse a display string into a normalized value (`getParamValueByString`).
+    ParseParameter {
+        /// Parameter id.
+        id: u32,
+        /// Display string to parse.
+        text: String,
+    },
+    /// Map a normalized value to the parameter's plain value (`normalizedParamToPlain`).
+    NormalizedToPlain {
+        /// Parameter id.
+        id: u32,
+        /// Normalized value.
+        normalized: f64,
+    },
+    /// Map a plain value to normalized (`plainParamToNormalized`).
+    PlainToNormalized {
+        /// Parameter id.
+        id: u32,
+        /// Plain value.
+        plain: f64,
+    },
     /// Send a MIDI event to the plugin.
     SendMidi {
         /// The event to deliver.
@@ -119,10 +230,18 @@ pub enum HostCommand {
         /// Sample offset within the next processed block.
         sample_offset: i32,
     },
+    /// Queue a framed SysEx message for the next process block.
+    SendSysEx {
+        /// The message, `0xF0` through `0xF7`.
+        data: Vec<u8>,
+    },
     /// Process one block of audio. `inputs` is per-channel; `frames` is the block length.
     Process {
         /// Per-channel input samples (`[channel][frame]`).
         inputs: Vec<Vec<f32>>,
+        /// Per-channel sidechain samples (`[channel][frame]`), for the aux input buses.
+        #[serde(default)]
+        sidechain: Vec<Vec<f32>>,
         /// Number of frames in this block.
         frames: u32,
     },
@@ -200,6 +319,11 @@ pub enum HostCommand {
     },
     /// Enumerate the plugin's units and their program lists (`IUnitInfo`).
     GetUnits,
+    /// List the programs in one program list (`IUnitInfo`).
+    GetPrograms {
+        /// Program list id.
+        program_list_id: i32,
+    },
     /// Query the plugin's reported processing latency in samples
     /// (`IAudioProcessor::getLatencySamples`).
     LatencySamples,
@@ -275,6 +399,13 @@ pub enum HostResponse {
         /// Editor height in pixels.
         height: i32,
     },
+    /// The editor was attached into the host's window (reply to `OpenGui`) at this size.
+    GuiOpened {
+        /// Editor width in pixels.
+        width: i32,
+        /// Editor height in pixels.
+        height: i32,
+    },
     /// Plugin information
     PluginInfo {
         /// Vendor / manufacturer.
@@ -326,6 +457,11 @@ pub enum HostResponse {
         /// The advertised units.
         units: Vec<crate::plugin::PluginUnit>,
     },
+    /// A program list's programs (reply to `GetPrograms`).
+    Programs {
+        /// The list's programs, in order.
+        programs: Vec<crate::plugin::ProgramInfo>,
+    },
     /// The plugin's reported processing latency in samples (reply to `LatencySamples`).
     LatencySamples {
         /// Latency in samples.
@@ -343,6 +479,66 @@ pub enum HostResponse {
     },
 }
 
+/// How an isolated plugin's editor reaches the host's screen.
+///
+/// Windows and X11 window handles are global to the session, so the helper can attach the
+/// editor straight into a host window: the host sends [`HostCommand::OpenGui`] with its
+/// window's handle, the helper passes it to `IPlugView::attached`, and from then on the
+/// window system delivers mouse and keyboard input to the editor's child window directly —
+/// nothing is forwarded over the pipe. macOS has no public API for showing one process's
+/// views inside another's window (`NSRemoteView` and the sandbox's Mach-port handoff are
+/// private), so there the helper opens its own top-level window ([`HostCommand::CreateGui`]).
+/// See `SECURITY.md` for what embedding another process's window implies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuiBridge {
+    /// The helper attaches the editor into a host window (`OpenGui`).
+    Embedded,
+    /// The helper shows the editor in a window it owns (`CreateGui`).
+    HelperWindow,
+}
+
+impl GuiBridge {
+    /// The bridge this platform supports.
+    pub fn current() -> Self {
+        if cfg!(any(target_os = "windows", target_os = "linux")) {
+            GuiBridge::Embedded
+        } else {
+            GuiBridge::HelperWindow
+        }
+    }
+
+    /// The VST3 platform type a host window must have to be embedded into: `HWND` on
+    /// Windows, `X11EmbedWindowID` on Linux. `None` when the bridge can't embed (and for
+    /// Wayland, whose surfaces can't be shared across processes).
+    pub fn platform_type(self) -> Option<&'static std::ffi::CStr> {
+        match self {
+            GuiBridge::HelperWindow => None,
+            GuiBridge::Embedded if cfg!(target_os = "windows") => Some(c"HWND"),
+            GuiBridge::Embedded if cfg!(target_os = "linux") => Some(c"X11EmbedWindowID"),
+            GuiBridge::Embedded => None,
+        }
+    }
+
+    /// Whether a host window of `platform_type` can take an isolated editor.
+    pub fn can_embed(self, platform_type: &std::ffi::CStr) -> bool {
+        self.platform_type() == Some(platform_type)
+    }
+
+    /// Helper side: the parent to attach the editor to for the host's `parent_handle`, or
+    /// `None` when this bridge doesn't embed.
+    ///
+    /// # Safety
+    /// `parent_handle` must be a live window of [`Self::platform_type`]'s type.
+    pub unsafe fn parent_window(self, parent_handle: usize) -> Option<crate::plugin::WindowHandle> {
+        self.platform_type()?;
+        // SAFETY: the caller guarantees a live handle; on Windows and Linux the native
+        // handle type is exactly the embeddable one.
+        Some(unsafe {
+            crate::plugin::WindowHandle::from_raw(parent_handle as *mut std::ffi::c_void)
+        })
+    }
+}
+
 /// Manages a plugin running in an isolated process.
 ///
 /// Responses are read on a background thread and delivered over a channel, so
@@ -360,6 +556,157 @@ pub struct PluginHostProcess {
     timeout: Duration,
     /// Set once the child has been killed/exited so we stop trying to talk to it.
     dead: bool,
+    /// The helper's console output captured to a log file, if the file could be created.
+    log: Option<HelperLog>,
+    /// Where the helper writes its crash report (see [`CRASH_REPORT_ENV`]).
+    crash_report_path: PathBuf,
+    /// The report read back after the helper died, until taken.
+    crash_report: Option<CrashReport>,
+}
+
+/// A helper's stderr (its own diagnostics plus whatever the plugin prints) redirected to a
+/// file, with a thread tailing the file and fanning new lines out to subscribers.
+struct HelperLog {
+    path: PathBuf,
+    subscribers: Arc<Mutex<Vec<Sender<String>>>>,
+    /// Tells the tail thread to exit once it has drained the file.
+    stop: Arc<AtomicBool>,
+    tail: Option<JoinHandle<()>>,
+}
+
+/// Directory holding per-plugin helper logs: [`HELPER_LOG_DIR_ENV`] when set, otherwise
+/// `$XDG_CACHE_HOME/vst3-host/logs`, falling back to `~/.cache` (or `%LOCALAPPDATA%` on
+/// Windows, then the temp dir) when unset.
+pub fn helper_log_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(HELPER_LOG_DIR_ENV).filter(|v| !v.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    let cache = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .unwrap_or_else(std::env::temp_dir);
+    cache.join("vst3-host").join("logs")
+}
+
+/// A fresh path in the temp dir for one helper's crash report.
+fn crash_report_path() -> PathBuf {
+    static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("vst3-host-crash-{}-{n}.json", std::process::id()))
+}
+
+impl HelperLog {
+    /// Create `<plugin_name>_<timestamp>.log` in `dir`, after pruning old logs there, and
+    /// return it with the file the child's stderr should write to. `None` (console output
+    /// stays inherited) if the file can't be created.
+    fn create(dir: &Path, plugin_name: &str) -> Option<(Self, std::fs::File)> {
+        std::fs::create_dir_all(dir).ok()?;
+        prune_helper_logs(dir, std::time::SystemTime::now());
+        let name: String = plugin_name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = dir.join(format!("{name}_{stamp}_{}.log", std::process::id()));
+        let file = std::fs::File::create(&path).ok()?;
+
+        let subscribers: Arc<Mutex<Vec<Sender<String>>>> = Arc::default();
+        let stop = Arc::new(AtomicBool::new(false));
+        let tail = {
+            let (path, subscribers, stop) = (path.clone(), subscribers.clone(), stop.clone());
+            std::thread::spawn(move || tail_log(&path, &subscribers, &stop))
+        };
+        let log = Self {
+            path,
+            subscribers,
+            stop,
+            tail: Some(tail),
+        };
+        Some((log, file))
+    }
+
+    /// Stop tailing after forwarding everything written so far.
+    fn finish(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(tail) = self.tail.take() {
+            let _ = tail.join();
+        }
+    }
+}
+
+/// Delete helper logs in `dir` (see [`helper_log_host_pid`]) not modified for
+/// [`HELPER_LOG_MAX_AGE`]. Other files are left alone, and so are this process's own logs,
+/// whose helpers may still be writing to them.
+fn prune_helper_logs(dir: &Path, now: std::time::SystemTime) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(pid) = name.to_str().and_then(helper_log_host_pid) else {
+            continue;
+        };
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > HELPER_LOG_MAX_AGE);
+        if stale && pid != std::process::id() {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// The host process id in a helper log's `<plugin>_<timestamp>_<pid>.log` file name, or
+/// `None` if `file_name` isn't one.
+fn helper_log_host_pid(file_name: &str) -> Option<u32> {
+    let mut parts = file_name.strip_suffix(".log")?.rsplitn(3, '_');
+    let pid = parts.next()?;
+    let stamp = parts.next()?;
+    let plugin = parts.next()?;
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    // The stamp is milliseconds since the epoch: 13 digits for centuries to come.
+    if plugin.is_empty() || stamp.len() < 13 || !digits(stamp) || !digits(pid) {
+        return None;
+    }
+    pid.parse().ok()
+}
+
+/// Follow `path`, sending each complete line (lossily decoded) to every live subscriber.
+fn tail_log(path: &Path, subscribers: &Mutex<Vec<Sender<String>>>, stop: &AtomicBool) {
+    let Ok(file) = std::fs::File::open(path) else {
+        return;
+    };
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    loop {
+        // Checked before reading so that, once asked to stop, we still drain to EOF.
+        let stopping = stop.load(Ordering::SeqCst);
+        match reader.read_until(b'\n', &mut line) {
+            Ok(_) if line.ends_with(b"\n") => {
+                let text = String::from_utf8_lossy(&line).trim_end().to_string();
+                line.clear();
+                if let Ok(mut subs) = subscribers.lock() {
+                    subs.retain(|tx| tx.send(text.clone()).is_ok());
+                }
+            }
+            // EOF (possibly mid-line): wait for the helper to write more.
+            Ok(_) if !stopping => std::thread::sleep(Duration::from_millis(50)),
+            _ => break,
+        }
+    }
 }
 
 impl PluginHostProcess {
@@ -367,6 +714,16 @@ impl PluginHostProcess {
     pub fn new(
         helper_override: Option<std::path::PathBuf>,
         timeout: Duration,
+    ) -> Result<Self, String> {
+        Self::new_for_plugin(helper_override, timeout, "vst3-host-helper")
+    }
+
+    /// Like [`Self::new`], naming the helper's log file after `plugin_name` (see
+    /// [`Self::log_lines`]).
+    pub fn new_for_plugin(
+        helper_override: Option<std::path::PathBuf>,
+        timeout: Duration,
+        plugin_name: &str,
     ) -> Result<Self, String> {
         // An explicit helper path (builder option or the VST3_HOST_HELPER_PATH env var) wins
         // over the heuristic search below — and a missing one is reported clearly here.
@@ -379,7 +736,7 @@ impl PluginHostProcess {
                     p.display()
                 ));
             }
-            return Self::spawn(p, timeout);
+            return Self::spawn(p, timeout, plugin_name, &helper_log_dir());
         }
 
         // Get the path to our helper executable
@@ -444,15 +801,27 @@ impl PluginHostProcess {
         let helper_path = helper_path
             .ok_or_else(|| format!("Helper executable not found. Searched in {:?} and parent directories. Make sure to build with --bins flag.", exe_dir))?;
 
-        Self::spawn(helper_path, timeout)
+        Self::spawn(helper_path, timeout, plugin_name, &helper_log_dir())
     }
 
-    /// Spawn the helper at `helper_path` and wire up the response reader thread.
-    fn spawn(helper_path: std::path::PathBuf, timeout: Duration) -> Result<Self, String> {
+    /// Spawn the helper at `helper_path` and wire up the response reader thread. The
+    /// helper's stderr goes to a per-plugin log file in `log_dir` when one can be created.
+    fn spawn(
+        helper_path: std::path::PathBuf,
+        timeout: Duration,
+        plugin_name: &str,
+        log_dir: &Path,
+    ) -> Result<Self, String> {
+        let (log, stderr) = match HelperLog::create(log_dir, plugin_name) {
+            Some((log, file)) => (Some(log), Stdio::from(file)),
+            None => (None, Stdio::inherit()),
+        };
+        let crash_report_path = crash_report_path();
         let mut child = Command::new(&helper_path)
+            .env(CRASH_REPORT_ENV, &crash_report_path)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::inherit())
+            .stderr(stderr)
             .spawn()
             .map_err(|e| format!("Failed to spawn helper process: {}", e))?;
 
@@ -487,9 +856,32 @@ impl PluginHostProcess {
             reader: Some(reader),
             timeout,
             dead: false,
+            log,
+            crash_report_path,
+            crash_report: None,
         })
     }
 
+    /// Subscribe to the helper's console output (its stderr, which on Unix also carries
+    /// whatever the plugin prints to stdout). Each complete line written from now on is
+    /// delivered on the returned channel; earlier lines are only in the file at
+    /// [`Self::log_path`]. The channel disconnects when the helper shuts down, or at once if
+    /// no log file could be created.
+    pub fn log_lines(&self) -> Receiver<String> {
+        let (tx, rx) = mpsc::channel();
+        if let Some(log) = &self.log {
+            if let Ok(mut subs) = log.subscribers.lock() {
+                subs.push(tx);
+            }
+        }
+        rx
+    }
+
+    /// The helper's log file, if its output is being captured.
+    pub fn log_path(&self) -> Option<&Path> {
+        self.log.as_ref().map(|l| l.path.as_path())
+    }
+
     /// Set how long to wait for a helper response before declaring a timeout.
     pub fn set_timeout(&mut self, timeout: Duration) {
         self.timeout = timeout;
@@ -537,6 +929,12 @@ impl PluginHostProcess {
             Err(RecvTimeoutError::Disconnected) => {
                 // Reader thread ended => stdout closed => helper exited/crashed.
                 self.dead = true;
+                // The helper writes its report before it dies, so it's complete by the time
+                // stdout closes.
+                self.crash_report = CrashReport::take_from(&self.crash_report_path);
+                if let Some(report) = &self.crash_report {
+                    return Err(format!("Helper process crashed: {report}"));
+                }
                 match self.check_process_status() {
                     Err(status) => Err(format!("Helper process crashed: {}", status)),
                     Ok(()) => Err("Helper process exited unexpectedly".to_string()),
@@ -556,6 +954,12 @@ impl PluginHostProcess {
         self.process.as_ref().map(|c| c.id())
     }
 
+    /// The crash report the helper left behind when it died on a fatal signal, handed out
+    /// once. `None` while it's alive, or if it exited without writing one (e.g. `SIGKILL`).
+    pub fn take_crash_report(&mut self) -> Option<CrashReport> {
+        self.crash_report.take()
+    }
+
     /// Check if the helper process is still running
     pub fn check_process_status(&mut self) -> Result<(), String> {
         if let Some(ref mut process) = self.process {
@@ -619,6 +1023,12 @@ impl PluginHostProcess {
         if let Some(reader) = self.reader.take() {
             let _ = reader.join();
         }
+        // The helper has exited, so its log is complete: forward the tail, then stop.
+        if let Some(log) = self.log.as_mut() {
+            log.finish();
+        }
+        // A report nobody collected (the helper crashed while idle) is of no further use.
+        let _ = std::fs::remove_file(&self.crash_report_path);
         self.dead = true;
     }
 }
@@ -732,6 +1142,44 @@ mod wire_tests {
         }
     }
 
+    #[test]
+    fn embedded_gui_commands_round_trip_across_the_wire() {
+        let open = HostCommand::OpenGui {
+            parent_handle: 0x0040_1234,
+        };
+        let json = serde_json::to_string(&open).expect("serialize OpenGui");
+        match serde_json::from_str::<HostCommand>(&json).expect("deserialize OpenGui") {
+            HostCommand::OpenGui { parent_handle } => assert_eq!(parent_handle, 0x0040_1234),
+            other => panic!("OpenGui round-trip changed the variant: {other:?}"),
+        }
+
+        let opened = HostResponse::GuiOpened {
+            width: 640,
+            height: 480,
+        };
+        let json = serde_json::to_string(&opened).expect("serialize GuiOpened");
+        match serde_json::from_str::<HostResponse>(&json).expect("deserialize GuiOpened") {
+            HostResponse::GuiOpened { width, height } => assert_eq!((width, height), (640, 480)),
+            other => panic!("GuiOpened round-trip changed the variant: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn gui_bridge_embeds_only_the_native_window_type() {
+        let bridge = GuiBridge::current();
+        assert!(!GuiBridge::HelperWindow.can_embed(c"NSView"));
+        assert!(!bridge.can_embed(c"WaylandSurfaceID"));
+        if cfg!(target_os = "linux") {
+            assert_eq!(bridge, GuiBridge::Embedded);
+            assert!(bridge.can_embed(c"X11EmbedWindowID"));
+        }
+        if cfg!(target_os = "macos") {
+            assert_eq!(bridge, GuiBridge::HelperWindow);
+            // SAFETY: a bridge that can't embed never touches the handle.
+            assert!(unsafe { bridge.parent_window(1) }.is_none());
+        }
+    }
+
     #[test]
     fn set_parameter_at_round_trips_across_the_wire() {
         // The sample-accurate automation command must survive the JSON transport intact
@@ -835,6 +1283,14 @@ mod wire_tests {
             HostCommand::SetPlaying { playing } => assert!(!playing),
             other => panic!("round-trip changed the variant: {other:?}"),
         }
+
+        let position = HostCommand::SetTransportPosition { samples: 96_000 };
+        let json = serde_json::to_string(&position).expect("serialize SetTransportPosition");
+        match serde_json::from_str::<HostCommand>(&json).expect("deserialize SetTransportPosition")
+        {
+            HostCommand::SetTransportPosition { samples } => assert_eq!(samples, 96_000),
+            other => panic!("round-trip changed the variant: {other:?}"),
+        }
     }
 
     #[test]
@@ -917,6 +1373,7 @@ mod wire_tests {
             id: 0,
             parent_id: -1,
             name: "Root".to_string(),
+            program_list_id: 1,
             programs: vec!["Init".to_string(), "Lead".to_string()],
         }];
         let resp = HostResponse::Units {
@@ -927,6 +1384,37 @@ mod wire_tests {
             HostResponse::Units { units: back } => assert_eq!(back, units),
             other => panic!("Units round-trip changed the variant: {other:?}"),
         }
+
+        // A helper that predates program lists sends units without one.
+        let old: PluginUnit =
+            serde_json::from_str(r#"{"id":1,"parent_id":0,"name":"Osc","programs":[]}"#)
+                .expect("deserialize a unit without program_list_id");
+        assert_eq!(old.program_list_id, -1);
+    }
+
+    #[test]
+    fn get_programs_round_trips_across_the_wire() {
+        use crate::plugin::ProgramInfo;
+
+        let cmd = serde_json::to_string(&HostCommand::GetPrograms { program_list_id: 7 })
+            .expect("serialize GetPrograms");
+        assert!(matches!(
+            serde_json::from_str::<HostCommand>(&cmd).expect("deserialize GetPrograms"),
+            HostCommand::GetPrograms { program_list_id: 7 }
+        ));
+
+        let programs = vec![ProgramInfo {
+            id: 0,
+            name: "Init".to_string(),
+        }];
+        let resp = HostResponse::Programs {
+            programs: programs.clone(),
+        };
+        let resp_json = serde_json::to_string(&resp).expect("serialize Programs");
+        match serde_json::from_str::<HostResponse>(&resp_json).expect("deserialize Programs") {
+            HostResponse::Programs { programs: back } => assert_eq!(back, programs),
+            other => panic!("Programs round-trip changed the variant: {other:?}"),
+        }
     }
 
     #[test]
@@ -1147,8 +1635,13 @@ mod wire_tests {
         drop(f);
         std::fs::set_permissions(&fake, std::fs::Permissions::from_mode(0o755)).unwrap();
 
-        let mut proc =
-            PluginHostProcess::spawn(fake.clone(), Duration::from_millis(200)).expect("spawn");
+        let mut proc = PluginHostProcess::spawn(
+            fake.clone(),
+            Duration::from_millis(200),
+            "hung-helper",
+            &dir,
+        )
+        .expect("spawn");
         let started = Instant::now();
         let res = proc.send_command(HostCommand::Shutdown);
         let elapsed = started.elapsed();
@@ -1164,6 +1657,102 @@ mod wire_tests {
         // The child was killed; a follow-up command also errors rather than hanging.
         assert!(proc.send_command(HostCommand::Shutdown).is_err());
 
+        drop(proc);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Whatever the helper (or the plugin inside it) prints reaches the host through the log
+    /// channel. The fake helper mirrors the real one: it keeps the protocol on a copy of
+    /// stdout and points stdout itself at stderr, so plain `echo`s land in the log.
+    #[cfg(unix)]
+    #[test]
+    fn helper_output_is_forwarded_through_log_lines() {
+        use std::io::Write;
+        use std::os::unix::fs::PermissionsExt;
+        use std::time::Duration;
+
+        let dir = std::env::temp_dir().join(format!("vst3_log_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let fake = dir.join("chatty-helper");
+        let mut f = std::fs::File::create(&fake).unwrap();
+        // Wait for the first command so the test has subscribed before anything is printed.
+        writeln!(
+            f,
+            "#!/bin/sh\nexec 3>&1 1>&2\nread cmd\necho 'plugin: hello'\necho 'plugin: bye'\nexec sleep 30"
+        )
+        .unwrap();
+        drop(f);
+        std::fs::set_permissions(&fake, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut proc =
+            PluginHostProcess::spawn(fake, Duration::from_millis(500), "Chatty Synth", &dir)
+                .expect("spawn");
+        let log_path = proc.log_path().expect("log file").to_path_buf();
+        assert_eq!(log_path.parent(), Some(dir.as_path()));
+        assert!(log_path
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("Chatty_Synth_"));
+
+        let lines = proc.log_lines();
+        // No response ever comes; the command only releases the script's `read`.
+        let _ = proc.send_command(HostCommand::Shutdown);
+        let got: Vec<String> = (0..2)
+            .map(|_| {
+                lines
+                    .recv_timeout(Duration::from_secs(3))
+                    .expect("log line")
+            })
+            .collect();
+        assert_eq!(got, vec!["plugin: hello", "plugin: bye"]);
+
+        // Shutting down disconnects subscribers.
+        drop(proc);
+        assert!(lines.recv_timeout(Duration::from_secs(1)).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Pruning drops only stale helper logs from other host processes; anything else in the
+    /// directory is left alone.
+    #[test]
+    fn old_helper_logs_are_pruned() {
+        use std::time::{Duration, SystemTime};
+
+        let dir = std::env::temp_dir().join(format!("vst3_prune_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let now = SystemTime::now();
+        let stale = now - HELPER_LOG_MAX_AGE - Duration::from_secs(60);
+        let own = std::process::id();
+        let other = own.wrapping_add(1);
+        let files = [
+            (format!("Synth_1700000000000_{other}.log"), stale),
+            (format!("My_Synth_1700000000000_{other}.log"), stale),
+            (format!("Synth_1700000000001_{other}.log"), now),
+            (format!("Synth_1700000000000_{own}.log"), stale),
+            ("server.log".to_string(), stale),
+            ("server_2024_01.log.txt".to_string(), stale),
+            ("server_2024_01.log".to_string(), stale),
+            ("notes_v2.log".to_string(), stale),
+        ];
+        for (name, modified) in &files {
+            let file = std::fs::File::create(dir.join(name)).unwrap();
+            file.set_modified(*modified).unwrap();
+        }
+
+        prune_helper_logs(&dir, now);
+
+        let mut left: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        let mut expected: Vec<String> = files[2..].iter().map(|(n, _)| n.clone()).collect();
+        expected.sort();
+        assert_eq!(left, expected);
+
         let _ = std::fs::remove_dir_all(&dir);
     }
 }
@@ -0,0 +1,201 @@
+//! Sessions: everything needed to bring a plugin back as it was.
+//!
+//! A [`Session`] records the loaded plugin's path, its parameter values, the MIDI bindings
+//! driving it, the transport and the host's audio configuration, as JSON.
+//! [`Vst3Host::save_session`](crate::Vst3Host::save_session) captures one and
+//! [`Vst3Host::load_session`](crate::Vst3Host::load_session) restores it.
+//!
+//! Parameters are stored by value rather than as the plugin's state blob, so a session stays
+//! readable (and editable) and survives plugin updates that change the blob layout.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audio::AudioConfig,
+    error::{Error, Result},
+    midi::MidiBinding,
+    plugin::Plugin,
+};
+
+/// The session format version [`Session::save`] writes. Files from a newer version are
+/// rejected rather than half-read.
+pub const SESSION_VERSION: u32 = 1;
+
+/// The transport a session was saved with.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TransportState {
+    /// Tempo in beats per minute
+    pub tempo: f64,
+    /// Time signature numerator
+    pub time_sig_numerator: i32,
+    /// Time signature denominator
+    pub time_sig_denominator: i32,
+    /// Whether the transport was playing
+    pub playing: bool,
+}
+
+impl TransportState {
+    /// The tempo and time signature of `config`, stopped.
+    pub fn from_config(config: &AudioConfig) -> Self {
+        Self {
+            tempo: config.tempo,
+            time_sig_numerator: config.time_sig_numerator,
+            time_sig_denominator: config.time_sig_denominator,
+            playing: false,
+        }
+    }
+
+    /// Advertise this transport to `plugin`.
+    pub fn apply(&self, plugin: &mut Plugin) -> Result<()> {
+        plugin.set_tempo(self.tempo)?;
+        plugin.set_time_signature(self.time_sig_numerator, self.time_sig_denominator)?;
+        plugin.set_playing(self.playing)
+    }
+}
+
+/// A saved plugin session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// Format version ([`SESSION_VERSION`] when written by this library)
+    pub version: u32,
+    /// The plugin's bundle path
+    pub plugin_path: PathBuf,
+    /// Every parameter's normalized value, as `(id, value)`
+    pub parameters: Vec<(u32, f64)>,
+    /// MIDI bindings driving the plugin's parameters
+    pub midi_bindings: Vec<MidiBinding>,
+    /// Transport tempo, time signature and play state
+    pub transport: TransportState,
+    /// The host's audio configuration
+    pub audio_config: AudioConfig,
+}
+
+impl Session {
+    /// Capture `plugin`'s path and parameter values, with `audio_config` and the transport it
+    /// implies. Read-only parameters (meters and the like) are left out. MIDI bindings start
+    /// out as the plugin's own aftertouch and channel-pressure bindings.
+    pub fn capture(plugin: &Plugin, audio_config: &AudioConfig) -> Result<Self> {
+        let parameters = plugin
+            .get_parameters()?
+            .into_iter()
+            .filter(|p| !p.is_read_only)
+            .map(|p| (p.id, p.value))
+            .collect();
+        let midi_bindings = plugin
+            .aftertouch_bindings()
+            .iter()
+            .copied()
+            .map(MidiBinding::Aftertouch)
+            .chain(
+                plugin
+                    .channel_pressure_bindings()
+                    .iter()
+                    .copied()
+                    .map(MidiBinding::ChannelPressure),
+            )
+            .collect();
+        Ok(Self {
+            version: SESSION_VERSION,
+            plugin_path: plugin.info().path.clone(),
+            parameters,
+            midi_bindings,
+            transport: TransportState::from_config(audio_config),
+            audio_config: *audio_config,
+        })
+    }
+
+    /// Read a session written by [`Self::save`].
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path).map_err(|e| Error::Other(format!("read session: {e}")))?;
+        let session: Session = serde_json::from_slice(&bytes)
+            .map_err(|e| Error::Other(format!("parse session: {e}")))?;
+        if session.version > SESSION_VERSION {
+            return Err(Error::Other(format!(
+                "session version {} is newer than this host supports ({SESSION_VERSION})",
+                session.version
+            )));
+        }
+        Ok(session)
+    }
+
+    /// Write the session as pretty-printed JSON.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| Error::Other(format!("serialize session: {e}")))?;
+        std::fs::write(path, json).map_err(|e| Error::Other(format!("write session: {e}")))
+    }
+
+    /// Set every saved parameter on `plugin`, returning how many were applied. Parameters the
+    /// plugin rejects (e.g. removed in a newer version) are skipped.
+    pub fn apply_parameters(&self, plugin: &mut Plugin) -> usize {
+        self.parameters
+            .iter()
+            .filter(|&&(id, value)| plugin.set_parameter(id, value).is_ok())
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::midi::{CcBinding, MidiChannel, NoteBinding};
+
+    fn session() -> Session {
+        let audio_config = AudioConfig {
+            sample_rate: 48000.0,
+            block_size: 256,
+            tempo: 96.5,
+            time_sig_numerator: 7,
+            time_sig_denominator: 8,
+            ..AudioConfig::default()
+        };
+        Session {
+            version: SESSION_VERSION,
+            plugin_path: PathBuf::from("/plugins/Synth.vst3"),
+            parameters: vec![(0, 0.25), (7, 1.0), (4_000_000_001, 0.123_456_789)],
+            midi_bindings: vec![
+                MidiBinding::Cc(CcBinding {
+                    channel: Some(MidiChannel::Ch2),
+                    ..CcBinding::new(7, 74)
+                }),
+                MidiBinding::Note(NoteBinding::new(0)),
+            ],
+            transport: TransportState {
+                playing: true,
+                ..TransportState::from_config(&audio_config)
+            },
+            audio_config,
+        }
+    }
+
+    #[test]
+    fn sessions_round_trip_through_a_file() {
+        let path =
+            std::env::temp_dir().join(format!("vst3-host-session-{}.json", std::process::id()));
+        let saved = session();
+        saved.save(&path).unwrap();
+        let loaded = Session::load(&path);
+        let _ = std::fs::remove_file(&path);
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded, saved);
+        assert_eq!(loaded.audio_config.sample_rate, 48000.0);
+        assert_eq!(loaded.audio_config.block_size, 256);
+        assert_eq!(loaded.transport.time_sig_numerator, 7);
+    }
+
+    #[test]
+    fn sessions_from_a_newer_version_are_rejected() {
+        let path = std::env::temp_dir().join(format!(
+            "vst3-host-session-newer-{}.json",
+            std::process::id()
+        ));
+        let mut newer = session();
+        newer.version = SESSION_VERSION + 1;
+        newer.save(&path).unwrap();
+        let loaded = Session::load(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(loaded, Err(Error::Other(msg)) if msg.contains("newer")));
+    }
+}
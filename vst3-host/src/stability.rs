@@ -0,0 +1,352 @@
+//! Long-running soak tests: keep a plugin processing in real time and count what goes wrong.
+//!
+//! [`run_stability_test`] paces blocks at the plugin's sample rate as an audio device would,
+//! plays a note every [`NOTE_INTERVAL`], and records failed or panicking blocks, NaN/Inf
+//! output, blocks that took longer than their real-time budget and how much the process's
+//! memory grew. The `stability_test` binary wraps it for CI.
+
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use crate::{
+    audio::{AudioBuffers, BlockHealth, OutputAnalyzer},
+    midi::{MidiChannel, MidiEvent},
+    plugin::Plugin,
+};
+
+/// How often [`run_stability_test`] plays a note, in audio time.
+pub const NOTE_INTERVAL: Duration = Duration::from_secs(2);
+/// How long each note is held before its Note Off.
+const NOTE_LENGTH: Duration = Duration::from_secs(1);
+/// Middle C.
+const TEST_NOTE: u8 = 60;
+const TEST_VELOCITY: u8 = 100;
+
+/// What a [`run_stability_test`] run observed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StabilityReport {
+    /// Wall-clock time the test ran
+    pub duration: Duration,
+    /// Blocks the plugin processed successfully
+    pub blocks_processed: u64,
+    /// Failed or panicking blocks, and failures to start processing. The test stops at the
+    /// first one.
+    pub crash_events: Vec<String>,
+    /// Blocks containing a NaN or infinite sample
+    pub nan_events: u64,
+    /// Blocks that took longer to process than they last in real time — each would have been
+    /// an audible dropout on a device
+    pub dropout_events: u64,
+    /// The slowest block's processing time as a percentage of its real-time budget
+    pub peak_cpu_percent: f64,
+    /// Mean processing time as a percentage of the real-time budget
+    pub average_cpu_percent: f64,
+    /// Change in the process's resident memory from the end of the first note to the end of
+    /// the test, in bytes. `None` if the test ended before the first note did, or where
+    /// [`resident_memory_bytes`] is unsupported.
+    pub memory_growth_bytes: Option<i64>,
+}
+
+impl StabilityReport {
+    /// No crashes and no NaN/Inf output. Dropouts and memory growth are left to the caller to
+    /// judge, since they depend on the machine and the plugin.
+    pub fn passed(&self) -> bool {
+        self.crash_events.is_empty() && self.nan_events == 0
+    }
+}
+
+impl fmt::Display for StabilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "duration:          {:.1} s", self.duration.as_secs_f64())?;
+        writeln!(f, "blocks processed:  {}", self.blocks_processed)?;
+        writeln!(f, "crashes:           {}", self.crash_events.len())?;
+        for event in &self.crash_events {
+            writeln!(f, "  {event}")?;
+        }
+        writeln!(f, "NaN/Inf blocks:    {}", self.nan_events)?;
+        writeln!(f, "dropouts:          {}", self.dropout_events)?;
+        writeln!(f, "peak CPU:          {:.1}%", self.peak_cpu_percent)?;
+        writeln!(f, "average CPU:       {:.1}%", self.average_cpu_percent)?;
+        match self.memory_growth_bytes {
+            Some(bytes) => write!(f, "memory growth:     {:+.1} MiB", bytes as f64 / MIB),
+            None => write!(f, "memory growth:     unknown"),
+        }
+    }
+}
+
+const MIB: f64 = 1024.0 * 1024.0;
+
+/// The process's resident memory in bytes: current RSS on Linux, peak RSS on other Unixes.
+/// `None` on Windows or if it can't be read.
+pub fn resident_memory_bytes() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        // statm: total and resident size, in pages.
+        let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+        let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+        // SAFETY: sysconf has no preconditions.
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        u64::try_from(page_size).ok().map(|size| pages * size)
+    }
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        // SAFETY: getrusage only writes to the zeroed struct we pass it.
+        let usage = unsafe {
+            let mut usage: libc::rusage = std::mem::zeroed();
+            if libc::getrusage(libc::RUSAGE_SELF, &mut usage) != 0 {
+                return None;
+            }
+            usage
+        };
+        // ru_maxrss is in bytes on macOS and kilobytes on the BSDs.
+        let scale = if cfg!(target_os = "macos") { 1 } else { 1024 };
+        u64::try_from(usage.ru_maxrss).ok().map(|rss| rss * scale)
+    }
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+/// Process `plugin` block by block in real time for `duration`, playing a one-second note
+/// every [`NOTE_INTERVAL`], and report what went wrong.
+///
+/// Processing is started for the test and stopped afterwards. Every `report_every` (zero
+/// disables it) `progress` is called with the report so far. The test stops early at the
+/// first block that returns an error or panics; a plugin that crashes the process outright
+/// can't be caught here — run the test in a child process (as the `stability_test` binary
+/// does) to report that.
+///
+/// NaN blocks are counted from the plugin's own output analysis when it is enabled (which
+/// silences them), and by a local [`OutputAnalyzer`] otherwise.
+pub fn run_stability_test(
+    plugin: &mut Plugin,
+    duration: Duration,
+    report_every: Duration,
+    mut progress: impl FnMut(&StabilityReport),
+) -> StabilityReport {
+    let mut report = StabilityReport::default();
+    if let Err(e) = plugin.start_processing() {
+        report.crash_events.push(format!("start processing: {e}"));
+        return report;
+    }
+
+    let sample_rate = plugin.sample_rate();
+    let block = plugin.block_size().max(1);
+    let budget = Duration::from_secs_f64(block as f64 / sample_rate);
+    let mut buffers = AudioBuffers::new(
+        plugin.info().audio_inputs as usize,
+        plugin.output_channel_count().max(1),
+        block,
+        sample_rate,
+    );
+    let mut analyzer = OutputAnalyzer::new();
+    let mut plugin_nans = plugin.output_analysis().map(|r| r.nan_blocks);
+    let mut total_cpu = 0.0;
+    let mut next_note = Duration::ZERO;
+    let mut note_off_at = None;
+    let mut baseline_memory = None;
+    let mut next_report = report_every;
+
+    let started = Instant::now();
+    while started.elapsed() < duration {
+        let audio_time = budget * report.blocks_processed as u32;
+        let event = if audio_time >= next_note {
+            next_note += NOTE_INTERVAL;
+            note_off_at = Some(audio_time + NOTE_LENGTH);
+            Some(MidiEvent::NoteOn {
+                channel: MidiChannel::Ch1,
+                note: TEST_NOTE,
+                velocity: TEST_VELOCITY,
+            })
+        } else if note_off_at.is_some_and(|at| audio_time >= at) {
+            note_off_at = None;
+            if baseline_memory.is_none() {
+                // Measure growth from here, once the first note has allocated its voice.
+                baseline_memory = resident_memory_bytes();
+            }
+            Some(MidiEvent::NoteOff {
+                channel: MidiChannel::Ch1,
+                note: TEST_NOTE,
+                velocity: 0,
+            })
+        } else {
+            None
+        };
+        if let Some(event) = event {
+            if let Err(e) = plugin.send_midi_event(event) {
+                log::warn!("stability test: send {event:?}: {e}");
+            }
+        }
+
+        let block_start = Instant::now();
+        let result = catch_unwind(AssertUnwindSafe(|| plugin.process_audio(&mut buffers)));
+        let elapsed = block_start.elapsed();
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                report
+                    .crash_events
+                    .push(format!("block {}: {e}", report.blocks_processed));
+                break;
+            }
+            Err(panic) => {
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                report.crash_events.push(format!(
+                    "block {}: panicked: {message}",
+                    report.blocks_processed
+                ));
+                break;
+            }
+        }
+        report.blocks_processed += 1;
+
+        let non_finite = match (plugin_nans, plugin.output_analysis()) {
+            (Some(before), Some(now)) => {
+                plugin_nans = Some(now.nan_blocks);
+                now.nan_blocks > before
+            }
+            _ => analyzer.analyze(&mut buffers.outputs) == BlockHealth::NonFinite,
+        };
+        if non_finite {
+            report.nan_events += 1;
+        }
+
+        let cpu = elapsed.as_secs_f64() / budget.as_secs_f64() * 100.0;
+        total_cpu += cpu;
+        report.peak_cpu_percent = report.peak_cpu_percent.max(cpu);
+        if cpu > 100.0 {
+            report.dropout_events += 1;
+        }
+
+        if !report_every.is_zero() && started.elapsed() >= next_report {
+            next_report += report_every;
+            report.duration = started.elapsed();
+            report.average_cpu_percent = total_cpu / report.blocks_processed as f64;
+            progress(&report);
+        }
+
+        // Pace blocks like a device would; a late block runs immediately.
+        let deadline = started + budget * report.blocks_processed as u32;
+        if let Some(wait) = deadline.checked_duration_since(Instant::now()) {
+            std::thread::sleep(wait);
+        }
+    }
+
+    if report.crash_events.is_empty() {
+        if let Err(e) = plugin.stop_processing() {
+            report.crash_events.push(format!("stop processing: {e}"));
+        }
+    }
+    report.duration = started.elapsed();
+    if report.blocks_processed > 0 {
+        report.average_cpu_percent = total_cpu / report.blocks_processed as f64;
+    }
+    report.memory_growth_bytes = baseline_memory
+        .zip(resident_memory_bytes())
+        .map(|(before, after)| after as i64 - before as i64);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::{Error, Result},
+        plugin::PluginInternal,
+    };
+    use std::sync::{Arc, Mutex};
+
+    /// Outputs a constant, with a NaN every `nan_every` blocks, and fails block `fail_at`.
+    struct Flaky {
+        blocks: u64,
+        nan_every: u64,
+        fail_at: Option<u64>,
+        midi: Arc<Mutex<Vec<MidiEvent>>>,
+    }
+
+    impl PluginInternal for Flaky {
+        fn process(&mut self, buffers: &mut AudioBuffers) -> Result<()> {
+            if self.fail_at == Some(self.blocks) {
+                return Err(Error::PluginCrashed);
+            }
+            self.blocks += 1;
+            let value = if self.blocks % self.nan_every == 0 {
+                f32::NAN
+            } else {
+                0.25
+            };
+            for channel in &mut buffers.outputs {
+                channel.fill(value);
+            }
+            Ok(())
+        }
+        fn send_midi_event(&mut self, event: MidiEvent) -> Result<()> {
+            self.midi.lock().unwrap().push(event);
+            Ok(())
+        }
+        crate::plugin::stub_plugin_internal!(except process, send_midi_event);
+    }
+
+    /// A plugin processing 1 ms blocks.
+    fn flaky(nan_every: u64, fail_at: Option<u64>) -> (Plugin, Arc<Mutex<Vec<MidiEvent>>>) {
+        let midi = Arc::new(Mutex::new(Vec::new()));
+        let mut plugin = crate::plugin::plugin_with(Box::new(Flaky {
+            blocks: 0,
+            nan_every,
+            fail_at,
+            midi: midi.clone(),
+        }));
+        plugin.info.name = "Flaky".to_string();
+        plugin.info.audio_outputs = 1;
+        plugin.block_size = 48;
+        (plugin, midi)
+    }
+
+    #[test]
+    fn counts_nan_blocks_in_real_time() {
+        let (mut plugin, midi) = flaky(10, None);
+        let mut reports = 0;
+        let report = run_stability_test(
+            &mut plugin,
+            Duration::from_millis(200),
+            Duration::from_millis(50),
+            |_| reports += 1,
+        );
+        assert!(report.crash_events.is_empty(), "{report}");
+        // Paced at 1 ms per block: no more blocks than fit in the run.
+        assert!(
+            (20..=205).contains(&report.blocks_processed),
+            "{}",
+            report.blocks_processed
+        );
+        assert_eq!(report.nan_events, report.blocks_processed / 10);
+        assert!(!report.passed());
+        assert!((3..=4).contains(&reports), "{reports} progress reports");
+        assert!(matches!(
+            midi.lock().unwrap().as_slice(),
+            [MidiEvent::NoteOn {
+                note: TEST_NOTE,
+                ..
+            }]
+        ));
+        assert!(!plugin.is_processing());
+    }
+
+    #[test]
+    fn stops_at_the_first_failed_block() {
+        let (mut plugin, _) = flaky(u64::MAX, Some(5));
+        let report =
+            run_stability_test(&mut plugin, Duration::from_secs(5), Duration::ZERO, |_| {});
+        assert_eq!(report.blocks_processed, 5);
+        assert_eq!(report.crash_events.len(), 1);
+        assert!(report.crash_events[0].starts_with("block 5:"));
+        assert!(report.duration < Duration::from_secs(1));
+        assert!(!report.passed());
+    }
+}
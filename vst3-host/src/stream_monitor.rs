@@ -0,0 +1,301 @@
+//! Health of running audio streams: what the backend reported going wrong ([`StreamError`]),
+//! running counts of dropouts and xruns ([`AudioStreamStats`]), and a handler that hears about
+//! each problem as it happens ([`AudioStreamErrorHandler`]).
+//!
+//! A [`StreamMonitor`] collects all three, plus how regularly the output callbacks arrive
+//! ([`TimingStats`]). [`Vst3Host`](crate::Vst3Host) keeps one for the
+//! streams it starts ([`Vst3Host::audio_stream_stats`](crate::Vst3Host::audio_stream_stats));
+//! [`play_monitored`](crate::playback::play_monitored) reports into any other.
+
+use crate::audio::{CallbackJitterTracker, TimingStats, JITTER_WARN_P99_US};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A problem a running audio stream ran into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamError {
+    /// The output wasn't fed in time and played a gap (an underrun). Backends that don't say
+    /// how long the gap was report one callback buffer.
+    Dropout {
+        /// Frames of output lost
+        missed_samples: u64,
+    },
+    /// The device went away (unplugged, or claimed by another application)
+    DeviceDisconnected,
+    /// Captured input arrived faster than it was read and was lost
+    Overrun {
+        /// Frames of input lost
+        frames: u64,
+    },
+    /// Anything else the backend reported
+    Unknown(String),
+}
+
+impl StreamError {
+    /// Classify an error a backend passed to its stream error callback. Backends in this crate
+    /// report [`Error::AudioStream`](crate::Error::AudioStream); anything else is
+    /// [`StreamError::Unknown`].
+    pub fn from_backend_error(error: &(dyn std::error::Error + 'static)) -> Self {
+        match error.downcast_ref::<crate::Error>() {
+            Some(crate::Error::AudioStream(e)) => e.clone(),
+            _ => StreamError::Unknown(error.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Dropout { missed_samples } => {
+                write!(f, "dropout ({missed_samples} samples missed)")
+            }
+            StreamError::DeviceDisconnected => f.write_str("audio device disconnected"),
+            StreamError::Overrun { frames } => write!(f, "input overrun ({frames} frames lost)"),
+            StreamError::Unknown(message) => f.write_str(message),
+        }
+    }
+}
+
+/// Called with every [`StreamError`] a monitored stream reports. It runs on the backend's
+/// error thread, so keep it short; [`Self::channel`] hands the errors to another thread
+/// instead.
+pub struct AudioStreamErrorHandler {
+    /// The callback
+    pub on_error: Box<dyn Fn(StreamError) + Send + 'static>,
+}
+
+impl AudioStreamErrorHandler {
+    /// A handler calling `on_error`.
+    pub fn new(on_error: impl Fn(StreamError) + Send + 'static) -> Self {
+        Self {
+            on_error: Box::new(on_error),
+        }
+    }
+
+    /// A handler that forwards every error to the returned receiver, e.g. for a UI thread to
+    /// drain once per frame.
+    pub fn channel() -> (Self, mpsc::Receiver<StreamError>) {
+        let (tx, rx) = mpsc::channel();
+        let handler = Self::new(move |error| {
+            // The receiver is gone once nobody is listening; the counters still count.
+            let _ = tx.send(error);
+        });
+        (handler, rx)
+    }
+}
+
+impl fmt::Debug for AudioStreamErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AudioStreamErrorHandler")
+            .finish_non_exhaustive()
+    }
+}
+
+/// Counters of a [`StreamMonitor`] at one moment.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AudioStreamStats {
+    /// Output underruns
+    pub dropout_count: u64,
+    /// Underruns and overruns together
+    pub xrun_count: u64,
+    /// Longest time one audio callback took, in milliseconds
+    pub callback_latency_max_ms: f64,
+}
+
+/// [`StreamMonitor::last_callback_ns`] before the first callback of a stream.
+const NO_CALLBACK: u64 = u64::MAX;
+
+/// Counts what monitored streams report and passes it on to the installed
+/// [`AudioStreamErrorHandler`]. Shared (in an `Arc`) between the streams and whoever reads it.
+#[derive(Debug)]
+pub struct StreamMonitor {
+    dropout_count: Arc<AtomicU64>,
+    xrun_count: AtomicU64,
+    callback_latency_max_us: AtomicU64,
+    handler: Mutex<Option<AudioStreamErrorHandler>>,
+    jitter: Mutex<CallbackJitterTracker>,
+    // When the last output callback started, in ns since `epoch`, or `NO_CALLBACK`. Kept
+    // outside `jitter` so it advances even when a reader holds the tracker.
+    last_callback_ns: AtomicU64,
+    epoch: Instant,
+    // Whether the current stream's jitter has been warned about.
+    jitter_warned: AtomicBool,
+}
+
+impl Default for StreamMonitor {
+    fn default() -> Self {
+        Self {
+            dropout_count: Arc::default(),
+            xrun_count: AtomicU64::new(0),
+            callback_latency_max_us: AtomicU64::new(0),
+            handler: Mutex::new(None),
+            jitter: Mutex::default(),
+            last_callback_ns: AtomicU64::new(NO_CALLBACK),
+            epoch: Instant::now(),
+            jitter_warned: AtomicBool::new(false),
+        }
+    }
+}
+
+impl StreamMonitor {
+    /// A monitor with zeroed counters and no handler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `handler` with every error reported from now on, replacing the previous one;
+    /// `None` only counts.
+    pub fn set_handler(&self, handler: Option<AudioStreamErrorHandler>) {
+        *self.handler.lock().unwrap_or_else(|p| p.into_inner()) = handler;
+    }
+
+    /// The live dropout counter, for a display that reads it without going through
+    /// [`Self::stats`].
+    pub fn dropout_count(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.dropout_count)
+    }
+
+    /// The counters now.
+    pub fn stats(&self) -> AudioStreamStats {
+        AudioStreamStats {
+            dropout_count: self.dropout_count.load(Ordering::Relaxed),
+            xrun_count: self.xrun_count.load(Ordering::Relaxed),
+            callback_latency_max_ms: self.callback_latency_max_us.load(Ordering::Relaxed) as f64
+                / 1000.0,
+        }
+    }
+
+    /// Zero the counters and the callback timing.
+    pub fn reset(&self) {
+        self.dropout_count.store(0, Ordering::Relaxed);
+        self.xrun_count.store(0, Ordering::Relaxed);
+        self.callback_latency_max_us.store(0, Ordering::Relaxed);
+        self.lock_jitter().reset();
+        self.last_callback_ns.store(NO_CALLBACK, Ordering::Relaxed);
+        self.jitter_warned.store(false, Ordering::Relaxed);
+    }
+
+    /// Start timing a new stream's callbacks of `block_size` frames at `sample_rate` Hz,
+    /// forgetting the previous stream's. The expected period follows the frames each callback
+    /// actually gets (see [`Self::record_callback_start`]).
+    pub fn start_timing(&self, block_size: usize, sample_rate: f64) {
+        *self.lock_jitter() = CallbackJitterTracker::new(block_size, sample_rate);
+        self.last_callback_ns.store(NO_CALLBACK, Ordering::Relaxed);
+        self.jitter_warned.store(false, Ordering::Relaxed);
+    }
+
+    /// Record that an output callback for `frames` frames started at `at`. The start time is
+    /// always kept; only filing the interval is skipped, rather than waited for, while
+    /// [`Self::timing_stats`] copies the tracker, so the audio thread never blocks on it.
+    pub fn record_callback_start(&self, at: Instant, frames: usize) {
+        let now_ns = u64::try_from(at.saturating_duration_since(self.epoch).as_nanos())
+            .unwrap_or(NO_CALLBACK - 1);
+        let last_ns = self.last_callback_ns.swap(now_ns, Ordering::Relaxed);
+        if last_ns == NO_CALLBACK {
+            return;
+        }
+        if let Ok(mut jitter) = self.jitter.try_lock() {
+            jitter.record_interval(Duration::from_nanos(now_ns.saturating_sub(last_ns)), frames);
+        }
+    }
+
+    /// How regularly the callbacks have arrived since [`Self::start_timing`]. Logs a warning,
+    /// once per stream, when p99 jitter passes [`JITTER_WARN_P99_US`].
+    pub fn timing_stats(&self) -> TimingStats {
+        // Copy the tracker out so the audio thread's `try_lock` is only held off for the copy,
+        // not the percentile computation.
+        let tracker = self.lock_jitter().clone();
+        let stats = tracker.stats();
+        if stats.is_jittery() && !self.jitter_warned.swap(true, Ordering::Relaxed) {
+            log::warn!(
+                "audio callback p99 jitter is {:.0} \u{00B5}s (over {JITTER_WARN_P99_US:.0} \u{00B5}s)",
+                stats.p99_jitter_us
+            );
+        }
+        stats
+    }
+
+    fn lock_jitter(&self) -> std::sync::MutexGuard<'_, CallbackJitterTracker> {
+        self.jitter.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Count `error` and pass it to the handler.
+    pub fn report(&self, error: StreamError) {
+        match error {
+            StreamError::Dropout { .. } => {
+                self.dropout_count.fetch_add(1, Ordering::Relaxed);
+                self.xrun_count.fetch_add(1, Ordering::Relaxed);
+            }
+            StreamError::Overrun { .. } => {
+                self.xrun_count.fetch_add(1, Ordering::Relaxed);
+            }
+            StreamError::DeviceDisconnected | StreamError::Unknown(_) => {}
+        }
+        if let Some(handler) = &*self.handler.lock().unwrap_or_else(|p| p.into_inner()) {
+            (handler.on_error)(error);
+        }
+    }
+
+    /// Record that one audio callback took `elapsed`. Lock-free, so the audio thread can call
+    /// it every block.
+    pub fn record_callback(&self, elapsed: Duration) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        self.callback_latency_max_us
+            .fetch_max(micros, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_are_counted_and_forwarded() {
+        let monitor = StreamMonitor::new();
+        let (handler, errors) = AudioStreamErrorHandler::channel();
+        monitor.set_handler(Some(handler));
+        monitor.report(StreamError::Dropout {
+            missed_samples: 512,
+        });
+        monitor.report(StreamError::Overrun { frames: 64 });
+        monitor.report(StreamError::DeviceDisconnected);
+        monitor.record_callback(Duration::from_micros(2_500));
+        monitor.record_callback(Duration::from_micros(700));
+
+        let stats = monitor.stats();
+        assert_eq!((stats.dropout_count, stats.xrun_count), (1, 2));
+        assert_eq!(stats.callback_latency_max_ms, 2.5);
+        assert_eq!(errors.try_iter().count(), 3);
+
+        monitor.start_timing(480, 48_000.0);
+        let start = Instant::now();
+        monitor.record_callback_start(start, 480);
+        monitor.record_callback_start(start + Duration::from_micros(10_003), 480);
+        let timing = monitor.timing_stats();
+        assert_eq!((timing.callbacks, timing.jitter_histogram[3]), (1, 1));
+
+        // A callback whose interval couldn't be filed (the tracker was busy) still moves the
+        // clock on, so the next interval is one period, not two.
+        let busy = monitor.jitter.lock().unwrap();
+        monitor.record_callback_start(start + Duration::from_micros(20_003), 480);
+        drop(busy);
+        monitor.record_callback_start(start + Duration::from_micros(30_003), 480);
+        let timing = monitor.timing_stats();
+        assert_eq!((timing.callbacks, timing.jitter_histogram[0]), (2, 1));
+        monitor.reset();
+        assert_eq!(monitor.stats(), AudioStreamStats::default());
+        assert_eq!(monitor.timing_stats().callbacks, 0);
+
+        let wrapped = crate::Error::AudioStream(StreamError::DeviceDisconnected);
+        assert_eq!(
+            StreamError::from_backend_error(&wrapped),
+            StreamError::DeviceDisconnected
+        );
+        assert_eq!(
+            StreamError::from_backend_error(&std::fmt::Error),
+            StreamError::Unknown("an error occurred when formatting an argument".into())
+        );
+    }
+}
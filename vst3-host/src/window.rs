@@ -20,8 +20,11 @@ use winapi::{
     shared::windef::{HWND, RECT},
     um::libloaderapi::GetModuleHandleW,
     um::winuser::{
-        CreateWindowExW, DefWindowProcW, DestroyWindow, LoadCursorW, RegisterClassExW, ShowWindow,
-        UpdateWindow, CS_HREDRAW, CS_VREDRAW, CW_USEDEFAULT, IDC_ARROW, SW_SHOW, WNDCLASSEXW,
+        CreateWindowExW, DefWindowProcW, DestroyWindow, GetSystemMetrics, GetWindowLongW,
+        GetWindowRect, LoadCursorW, RegisterClassExW, SetLayeredWindowAttributes, SetWindowLongW,
+        SetWindowPos, ShowWindow, UpdateWindow, CS_HREDRAW, CS_VREDRAW, CW_USEDEFAULT, GWL_EXSTYLE,
+        HWND_NOTOPMOST, HWND_TOPMOST, IDC_ARROW, LWA_ALPHA, SM_CXSCREEN, SM_CYSCREEN,
+        SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, SW_SHOW, WNDCLASSEXW, WS_EX_LAYERED,
         WS_OVERLAPPEDWINDOW,
     },
 };
@@ -35,9 +38,107 @@ struct XcbWindowState {
     window: xcb::x::Window,
 }
 
+/// How a plugin editor embeds into a Linux parent, named after the VST3 `IPlugView` platform
+/// types. Query support with [`Plugin::supports_linux_platform_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LinuxPlatformType {
+    /// X11 (XEmbed): the editor is parented to an X11 window id (`XID`).
+    X11EmbedWindowID,
+    /// Wayland: the editor is parented to a `wl_surface*` as a subsurface.
+    WaylandSurfaceID,
+}
+
+impl LinuxPlatformType {
+    /// The VST3 platform type string (`"X11EmbedWindowID"` / `"WaylandSurfaceID"`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LinuxPlatformType::X11EmbedWindowID => "X11EmbedWindowID",
+            LinuxPlatformType::WaylandSurfaceID => "WaylandSurfaceID",
+        }
+    }
+
+    pub(crate) fn as_cstr(self) -> &'static std::ffi::CStr {
+        match self {
+            LinuxPlatformType::X11EmbedWindowID => c"X11EmbedWindowID",
+            LinuxPlatformType::WaylandSurfaceID => c"WaylandSurfaceID",
+        }
+    }
+}
+
+/// Check that `plugin`'s editor accepts `wanted` before creating native windows for it.
+///
+/// Plugins that can't answer (e.g. process-isolated ones, whose helper owns the editor) are
+/// given the benefit of the doubt; `attached()` reports the real failure.
+#[cfg(target_os = "linux")]
+pub(crate) fn ensure_linux_platform_type(plugin: &Plugin, wanted: LinuxPlatformType) -> Result<()> {
+    use LinuxPlatformType::*;
+    if !plugin.supports_linux_platform_type(wanted).unwrap_or(true) {
+        let supported: Vec<&str> = [X11EmbedWindowID, WaylandSurfaceID]
+            .into_iter()
+            .filter(|&t| plugin.supports_linux_platform_type(t).unwrap_or(false))
+            .map(LinuxPlatformType::as_str)
+            .collect();
+        return Err(Error::Other(format!(
+            "Plugin editor does not support {} (supports: {})",
+            wanted.as_str(),
+            if supported.is_empty() {
+                "none".to_string()
+            } else {
+                supported.join(", ")
+            }
+        )));
+    }
+    Ok(())
+}
+
+/// The position and size of a native plugin window, in the platform's screen coordinates.
+///
+/// Read from an open window with [`PluginWindow::geometry`] and handed back with
+/// [`PluginWindow::restore_geometry`] so an editor reopens where the user left it. The values
+/// are opaque outside the platform that produced them (macOS measures from the bottom-left of
+/// the screen, Windows and X11 from the top-left), which is fine for persisting per machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct WindowGeometry {
+    /// Horizontal position of the window's frame origin.
+    pub x: i32,
+    /// Vertical position of the window's frame origin.
+    pub y: i32,
+    /// Frame width in pixels (including decorations where the platform reports them).
+    pub width: i32,
+    /// Frame height in pixels (including decorations where the platform reports them).
+    pub height: i32,
+}
+
+/// How a native plugin window sits among other windows: pinned above them, and how opaque.
+///
+/// Set with [`PluginWindow::set_always_on_top`] and [`PluginWindow::set_opacity`]; like
+/// [`WindowGeometry`], it is kept across close/open and is worth persisting per plugin.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct WindowBehavior {
+    /// Keep the window above normal windows (a floating window on macOS, `HWND_TOPMOST` on
+    /// Windows, `_NET_WM_STATE_ABOVE` on X11).
+    pub always_on_top: bool,
+    /// Window opacity, from 0.0 (invisible) to 1.0 (opaque).
+    pub opacity: f32,
+}
+
+impl Default for WindowBehavior {
+    fn default() -> Self {
+        Self {
+            always_on_top: false,
+            opacity: 1.0,
+        }
+    }
+}
+
 /// A plugin window that manages the native window and plugin editor lifecycle
 pub struct PluginWindow {
     plugin: Arc<Mutex<Plugin>>,
+    /// Geometry to apply on the next open, and the last frame seen before closing.
+    geometry: Option<WindowGeometry>,
+    /// Stacking and opacity, applied on every open.
+    behavior: WindowBehavior,
     #[cfg(target_os = "macos")]
     native_window: Option<Retained<NSWindow>>,
     #[cfg(target_os = "windows")]
@@ -53,6 +154,8 @@ impl PluginWindow {
     pub fn new(plugin: Arc<Mutex<Plugin>>) -> Self {
         Self {
             plugin,
+            geometry: None,
+            behavior: WindowBehavior::default(),
             #[cfg(any(
                 target_os = "macos",
                 target_os = "windows",
@@ -154,10 +257,14 @@ impl PluginWindow {
                 .unwrap_or_else(|p| p.into_inner())
                 .open_editor(window_handle)?;
 
-            // Match the window to the editor size, then show and center it.
+            // Match the window to the editor size, then show it where it was last left (or
+            // centered, the first time).
             window.setContentSize(container_frame.size);
+            match self.geometry {
+                Some(g) => window.setFrame_display(ns_rect_from_geometry(g), true),
+                None => window.center(),
+            }
             window.makeKeyAndOrderFront(None);
-            window.center();
 
             self.native_window = Some(window);
         }
@@ -232,6 +339,17 @@ impl PluginWindow {
                     .open_editor(window_handle)
                 {
                     Ok(()) => {
+                        if let Some(g) = self.geometry {
+                            SetWindowPos(
+                                hwnd,
+                                ptr::null_mut(),
+                                g.x,
+                                g.y,
+                                g.width,
+                                g.height,
+                                SWP_NOZORDER | SWP_NOACTIVATE,
+                            );
+                        }
                         ShowWindow(hwnd, SW_SHOW);
                         UpdateWindow(hwnd);
                         self.native_window = Some(hwnd);
@@ -249,7 +367,13 @@ impl PluginWindow {
             use xcb::Xid;
 
             // Create an X11 window via XCB and embed the plugin editor into it using the
-            // VST3 X11EmbedWindowID platform type (handled in plugin_impl::open_editor).
+            // VST3 X11EmbedWindowID platform type (handled in plugin_impl::open_editor). This
+            // also covers Wayland sessions through XWayland; a Wayland-only editor must be
+            // embedded into a Wayland parent surface with `EmbeddedEditor` instead.
+            ensure_linux_platform_type(
+                &self.plugin.lock().unwrap_or_else(|p| p.into_inner()),
+                LinuxPlatformType::X11EmbedWindowID,
+            )?;
             let (connection, screen_number) = xcb::Connection::connect(None)
                 .map_err(|e| Error::Other(format!("Failed to connect to X server: {e}")))?;
             let setup = connection.get_setup();
@@ -258,14 +382,18 @@ impl PluginWindow {
                 .nth(screen_number as usize)
                 .ok_or_else(|| Error::Other("No X11 screen found".to_string()))?;
             let window = connection.generate_id();
+            let (x, y, width, height) = match self.geometry {
+                Some(g) => (g.x, g.y, g.width, g.height),
+                None => (0, 0, width, height),
+            };
 
             connection
                 .send_and_check_request(&xcb::x::CreateWindow {
                     depth: xcb::x::COPY_FROM_PARENT as u8,
                     wid: window,
                     parent: screen.root(),
-                    x: 0,
-                    y: 0,
+                    x: x as i16,
+                    y: y as i16,
                     width: width as u16,
                     height: height as u16,
                     border_width: 0,
@@ -290,8 +418,15 @@ impl PluginWindow {
                 data: title.as_bytes(),
             });
 
-            // Show the window, then attach the plugin editor to its X11 id.
+            // Show the window, then attach the plugin editor to its X11 id. Window managers
+            // may ignore the create-time position, so re-assert a restored one after mapping.
             connection.send_request(&xcb::x::MapWindow { window });
+            if self.geometry.is_some() {
+                connection.send_request(&xcb::x::ConfigureWindow {
+                    window,
+                    value_list: &[xcb::x::ConfigWindow::X(x), xcb::x::ConfigWindow::Y(y)],
+                });
+            }
             let _ = connection.flush();
 
             let handle = crate::plugin::WindowHandle::from_x11(window.resource_id());
@@ -310,11 +445,19 @@ impl PluginWindow {
             ));
         }
 
+        self.apply_native_behavior();
         Ok(())
     }
 
     /// Close the plugin window
+    ///
+    /// The window's final frame is remembered, so [`geometry`](Self::geometry) still reports
+    /// it after closing and a later [`open`](Self::open) reopens in the same place.
     pub fn close(&mut self) {
+        if let Some(g) = self.native_geometry() {
+            self.geometry = Some(g);
+        }
+
         // Close the plugin editor first
         if let Ok(mut plugin) = self.plugin.lock() {
             let _ = plugin.close_editor();
@@ -360,6 +503,411 @@ impl PluginWindow {
     pub fn is_open(&self) -> bool {
         self.native_window.is_some()
     }
+
+    /// The window's position and size: its live frame while open, otherwise the geometry it
+    /// last closed with (or was given via [`restore_geometry`](Self::restore_geometry)).
+    /// `None` if the window has never been opened or positioned.
+    ///
+    /// Persist this (e.g. keyed by plugin path) before dropping the window to reopen the
+    /// editor where the user left it next session.
+    pub fn geometry(&self) -> Option<WindowGeometry> {
+        self.native_geometry().or(self.geometry)
+    }
+
+    /// Position and size the window with a previously saved [`WindowGeometry`].
+    ///
+    /// Applied immediately if the window is open, otherwise on the next [`open`](Self::open)
+    /// (in place of the default centered placement).
+    pub fn restore_geometry(&mut self, geometry: WindowGeometry) {
+        self.geometry = Some(geometry);
+        self.apply_native_geometry(geometry);
+    }
+
+    /// Forget any saved geometry and re-center the window (immediately if open, otherwise on
+    /// the next [`open`](Self::open)). Callers that persist geometry should drop their saved
+    /// entry too.
+    pub fn reset_position(&mut self) {
+        self.geometry = None;
+        self.center_native_window();
+    }
+
+    /// The window's stacking and opacity settings.
+    pub fn behavior(&self) -> WindowBehavior {
+        self.behavior
+    }
+
+    /// Keep the window above other (non-floating) windows, or return it to normal stacking.
+    ///
+    /// Applied immediately if the window is open and remembered for the next
+    /// [`open`](Self::open).
+    pub fn set_always_on_top(&mut self, enable: bool) {
+        self.behavior.always_on_top = enable;
+        self.apply_native_behavior();
+    }
+
+    /// Set the window's opacity, from 0.0 (invisible) to 1.0 (opaque); values outside that
+    /// range are clamped.
+    ///
+    /// Applied immediately if the window is open and remembered for the next
+    /// [`open`](Self::open).
+    pub fn set_opacity(&mut self, alpha: f32) {
+        self.behavior.opacity = clamp_opacity(alpha);
+        self.apply_native_behavior();
+    }
+
+    /// Tell the open editor that its area is now `width`×`height` pixels.
+    ///
+    /// The plugin first gets to adjust the size to one it supports
+    /// (`IPlugView::checkSizeConstraint`; a fixed-size editor keeps its own), then is resized
+    /// to it with `IPlugView::onSize`, and the window's content area is resized to match.
+    /// Call this whenever the area you give the editor changes size.
+    pub fn notify_resize(&mut self, width: i32, height: i32) -> Result<()> {
+        let (width, height) = self
+            .plugin
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .resize_editor(width, height)?;
+        self.apply_native_content_size(width, height);
+        Ok(())
+    }
+
+    /// The size the editor would like: its current size (`IPlugView::getSize`) passed through
+    /// `IPlugView::checkSizeConstraint`. Falls back to 800×600 if the plugin can't say.
+    pub fn preferred_size(&self) -> (i32, i32) {
+        let plugin = self.plugin.lock().unwrap_or_else(|p| p.into_inner());
+        plugin
+            .get_editor_size()
+            .and_then(|(w, h)| plugin.check_editor_size_constraint(w, h))
+            .unwrap_or((800, 600))
+    }
+
+    #[cfg(target_os = "macos")]
+    fn native_geometry(&self) -> Option<WindowGeometry> {
+        let frame = self.native_window.as_ref()?.frame();
+        Some(WindowGeometry {
+            x: frame.origin.x.round() as i32,
+            y: frame.origin.y.round() as i32,
+            width: frame.size.width.round() as i32,
+            height: frame.size.height.round() as i32,
+        })
+    }
+
+    #[cfg(target_os = "macos")]
+    fn apply_native_geometry(&self, geometry: WindowGeometry) {
+        if let Some(window) = &self.native_window {
+            window.setFrame_display(ns_rect_from_geometry(geometry), true);
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn apply_native_content_size(&self, width: i32, height: i32) {
+        if let Some(window) = &self.native_window {
+            window.setContentSize(NSSize::new(width as f64, height as f64));
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn center_native_window(&self) {
+        if let Some(window) = &self.native_window {
+            window.center();
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn apply_native_behavior(&self) {
+        // `NSNormalWindowLevel` / `NSFloatingWindowLevel` (kCGNormalWindowLevel and
+        // kCGFloatingWindowLevel).
+        const NORMAL_LEVEL: isize = 0;
+        const FLOATING_LEVEL: isize = 3;
+        if let Some(window) = &self.native_window {
+            window.setLevel(if self.behavior.always_on_top {
+                FLOATING_LEVEL
+            } else {
+                NORMAL_LEVEL
+            });
+            window.setAlphaValue(self.behavior.opacity as f64);
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn native_geometry(&self) -> Option<WindowGeometry> {
+        let hwnd = self.native_window?;
+        let mut rect = RECT {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+        };
+        // SAFETY: `hwnd` is a live window we created and own until `close`.
+        if unsafe { GetWindowRect(hwnd, &mut rect) } == 0 {
+            return None;
+        }
+        Some(WindowGeometry {
+            x: rect.left,
+            y: rect.top,
+            width: rect.right - rect.left,
+            height: rect.bottom - rect.top,
+        })
+    }
+
+    #[cfg(target_os = "windows")]
+    fn apply_native_geometry(&self, geometry: WindowGeometry) {
+        if let Some(hwnd) = self.native_window {
+            // SAFETY: `hwnd` is a live window we created and own until `close`.
+            unsafe {
+                SetWindowPos(
+                    hwnd,
+                    std::ptr::null_mut(),
+                    geometry.x,
+                    geometry.y,
+                    geometry.width,
+                    geometry.height,
+                    SWP_NOZORDER | SWP_NOACTIVATE,
+                );
+            }
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn apply_native_content_size(&self, width: i32, height: i32) {
+        let Some(hwnd) = self.native_window else {
+            return;
+        };
+        let mut rect = RECT {
+            left: 0,
+            top: 0,
+            right: width,
+            bottom: height,
+        };
+        // SAFETY: `hwnd` is a live window we created and own until `close`.
+        unsafe {
+            winapi::um::winuser::AdjustWindowRectEx(&mut rect, WS_OVERLAPPEDWINDOW, 0, 0);
+            SetWindowPos(
+                hwnd,
+                std::ptr::null_mut(),
+                0,
+                0,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+                SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE,
+            );
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn center_native_window(&self) {
+        let Some(current) = self.native_geometry() else {
+            return;
+        };
+        // SAFETY: plain metric queries with no pointer arguments.
+        let (screen_w, screen_h) =
+            unsafe { (GetSystemMetrics(SM_CXSCREEN), GetSystemMetrics(SM_CYSCREEN)) };
+        self.apply_native_geometry(WindowGeometry {
+            x: (screen_w - current.width) / 2,
+            y: (screen_h - current.height) / 2,
+            ..current
+        });
+    }
+
+    #[cfg(target_os = "windows")]
+    fn apply_native_behavior(&self) {
+        let Some(hwnd) = self.native_window else {
+            return;
+        };
+        // SAFETY: `hwnd` is a live window we created and own until `close`.
+        unsafe {
+            let insert_after = if self.behavior.always_on_top {
+                HWND_TOPMOST
+            } else {
+                HWND_NOTOPMOST
+            };
+            SetWindowPos(
+                hwnd,
+                insert_after,
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+            );
+
+            // Per-window alpha needs a layered window.
+            let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32;
+            if ex_style & WS_EX_LAYERED == 0 {
+                SetWindowLongW(hwnd, GWL_EXSTYLE, (ex_style | WS_EX_LAYERED) as i32);
+            }
+            let alpha = (self.behavior.opacity * 255.0).round() as u8;
+            SetLayeredWindowAttributes(hwnd, 0, alpha, LWA_ALPHA);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn native_geometry(&self) -> Option<WindowGeometry> {
+        let state = self.native_window.as_ref()?;
+        let conn = &state.connection;
+        let geometry = conn
+            .wait_for_reply(conn.send_request(&xcb::x::GetGeometry {
+                drawable: xcb::x::Drawable::Window(state.window),
+            }))
+            .ok()?;
+        // GetGeometry is relative to the parent (a WM frame when reparented); translate the
+        // origin to root coordinates so the saved position is absolute.
+        let origin = conn
+            .wait_for_reply(conn.send_request(&xcb::x::TranslateCoordinates {
+                src_window: state.window,
+                dst_window: geometry.root(),
+                src_x: 0,
+                src_y: 0,
+            }))
+            .ok()?;
+        Some(WindowGeometry {
+            x: origin.dst_x() as i32,
+            y: origin.dst_y() as i32,
+            width: geometry.width() as i32,
+            height: geometry.height() as i32,
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    fn apply_native_geometry(&self, geometry: WindowGeometry) {
+        if let Some(state) = &self.native_window {
+            state.connection.send_request(&xcb::x::ConfigureWindow {
+                window: state.window,
+                value_list: &[
+                    xcb::x::ConfigWindow::X(geometry.x),
+                    xcb::x::ConfigWindow::Y(geometry.y),
+                    xcb::x::ConfigWindow::Width(geometry.width.max(1) as u32),
+                    xcb::x::ConfigWindow::Height(geometry.height.max(1) as u32),
+                ],
+            });
+            let _ = state.connection.flush();
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn apply_native_content_size(&self, width: i32, height: i32) {
+        if let Some(state) = &self.native_window {
+            state.connection.send_request(&xcb::x::ConfigureWindow {
+                window: state.window,
+                value_list: &[
+                    xcb::x::ConfigWindow::Width(width.max(1) as u32),
+                    xcb::x::ConfigWindow::Height(height.max(1) as u32),
+                ],
+            });
+            let _ = state.connection.flush();
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn center_native_window(&self) {
+        let (Some(state), Some(current)) = (self.native_window.as_ref(), self.native_geometry())
+        else {
+            return;
+        };
+        let conn = &state.connection;
+        let Some(screen) = conn.get_setup().roots().next() else {
+            return;
+        };
+        let (screen_w, screen_h) = (
+            screen.width_in_pixels() as i32,
+            screen.height_in_pixels() as i32,
+        );
+        self.apply_native_geometry(WindowGeometry {
+            x: (screen_w - current.width) / 2,
+            y: (screen_h - current.height) / 2,
+            ..current
+        });
+    }
+
+    /// Ask the window manager (EWMH) to keep the window above others, and set the
+    /// compositor's `_NET_WM_WINDOW_OPACITY` hint. Both are ignored by window managers or
+    /// compositors that don't support them.
+    #[cfg(target_os = "linux")]
+    fn apply_native_behavior(&self) {
+        use xcb::Xid;
+
+        let Some(state) = self.native_window.as_ref() else {
+            return;
+        };
+        let conn = &state.connection;
+        let atom = |name: &[u8]| {
+            conn.wait_for_reply(conn.send_request(&xcb::x::InternAtom {
+                only_if_exists: false,
+                name,
+            }))
+            .map(|reply| reply.atom())
+            .ok()
+        };
+        let (Some(wm_state), Some(above), Some(opacity)) = (
+            atom(b"_NET_WM_STATE"),
+            atom(b"_NET_WM_STATE_ABOVE"),
+            atom(b"_NET_WM_WINDOW_OPACITY"),
+        ) else {
+            return;
+        };
+        let Some(screen) = conn.get_setup().roots().next() else {
+            return;
+        };
+
+        // _NET_WM_STATE_REMOVE = 0, _NET_WM_STATE_ADD = 1; source indication 1 = application.
+        let action = u32::from(self.behavior.always_on_top);
+        let event = xcb::x::ClientMessageEvent::new(
+            state.window,
+            wm_state,
+            xcb::x::ClientMessageData::Data32([action, above.resource_id(), 0, 1, 0]),
+        );
+        conn.send_request(&xcb::x::SendEvent {
+            propagate: false,
+            destination: xcb::x::SendEventDest::Window(screen.root()),
+            event_mask: xcb::x::EventMask::SUBSTRUCTURE_NOTIFY
+                | xcb::x::EventMask::SUBSTRUCTURE_REDIRECT,
+            event: &event,
+        });
+
+        let alpha = (self.behavior.opacity as f64 * u32::MAX as f64).round() as u32;
+        conn.send_request(&xcb::x::ChangeProperty {
+            mode: xcb::x::PropMode::Replace,
+            window: state.window,
+            property: opacity,
+            r#type: xcb::x::ATOM_CARDINAL,
+            data: &[alpha],
+        });
+        let _ = conn.flush();
+    }
+
+    #[cfg(target_os = "android")]
+    fn native_geometry(&self) -> Option<WindowGeometry> {
+        None
+    }
+
+    #[cfg(target_os = "android")]
+    fn apply_native_geometry(&self, _geometry: WindowGeometry) {}
+
+    #[cfg(target_os = "android")]
+    fn apply_native_content_size(&self, _width: i32, _height: i32) {}
+
+    #[cfg(target_os = "android")]
+    fn center_native_window(&self) {}
+
+    #[cfg(target_os = "android")]
+    fn apply_native_behavior(&self) {}
+}
+
+#[cfg(target_os = "macos")]
+fn ns_rect_from_geometry(g: WindowGeometry) -> NSRect {
+    NSRect::new(
+        NSPoint::new(g.x as f64, g.y as f64),
+        NSSize::new(g.width as f64, g.height as f64),
+    )
+}
+
+/// Clamp an opacity to 0.0..=1.0, treating NaN as opaque.
+fn clamp_opacity(alpha: f32) -> f32 {
+    if alpha.is_nan() {
+        1.0
+    } else {
+        alpha.clamp(0.0, 1.0)
+    }
 }
 
 impl Drop for PluginWindow {
@@ -388,3 +936,101 @@ impl PluginWindowBuilder {
         Ok(window)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginInternal;
+
+    /// The sizes passed to `onSize`, in order.
+    type ResizeLog = Arc<Mutex<Vec<(i32, i32)>>>;
+
+    /// An editor that snaps to a 10-pixel grid, at least 200×100, and records `onSize` calls.
+    struct GridEditor {
+        resized: ResizeLog,
+    }
+
+    impl PluginInternal for GridEditor {
+        fn has_editor(&self) -> bool {
+            true
+        }
+        fn get_editor_size(&self) -> Result<(i32, i32)> {
+            Ok((333, 77))
+        }
+        fn check_editor_size_constraint(&self, width: i32, height: i32) -> Result<(i32, i32)> {
+            Ok(((width / 10 * 10).max(200), (height / 10 * 10).max(100)))
+        }
+        fn resize_editor(&mut self, width: i32, height: i32) -> Result<()> {
+            self.resized.lock().unwrap().push((width, height));
+            Ok(())
+        }
+        crate::plugin::stub_plugin_internal!(except has_editor, get_editor_size);
+    }
+
+    fn grid_window() -> (PluginWindow, ResizeLog) {
+        let resized = Arc::new(Mutex::new(Vec::new()));
+        let mut plugin = crate::plugin::plugin_with(Box::new(GridEditor {
+            resized: resized.clone(),
+        }));
+        plugin.info.name = "Grid".to_string();
+        plugin.info.has_midi_input = false;
+        plugin.info.has_gui = true;
+        (PluginWindow::new(Arc::new(Mutex::new(plugin))), resized)
+    }
+
+    #[test]
+    fn notify_resize_applies_the_constrained_size() {
+        let (mut window, resized) = grid_window();
+        window.notify_resize(645, 482).unwrap();
+        window.notify_resize(50, 20).unwrap();
+        assert_eq!(*resized.lock().unwrap(), vec![(640, 480), (200, 100)]);
+    }
+
+    #[test]
+    fn preferred_size_is_the_constrained_editor_size() {
+        let (window, resized) = grid_window();
+        assert_eq!(window.preferred_size(), (330, 100));
+        assert!(resized.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn window_behavior_defaults_and_opacity_clamping() {
+        // Older preference files may predate a field.
+        let behavior: WindowBehavior = serde_json::from_str(r#"{"always_on_top":true}"#).unwrap();
+        assert_eq!(
+            behavior,
+            WindowBehavior {
+                always_on_top: true,
+                opacity: 1.0,
+            }
+        );
+
+        assert_eq!(clamp_opacity(1.5), 1.0);
+        assert_eq!(clamp_opacity(-0.5), 0.0);
+        assert_eq!(clamp_opacity(f32::NAN), 1.0);
+        assert_eq!(clamp_opacity(0.4), 0.4);
+    }
+
+    #[test]
+    fn linux_platform_types_match_the_vst3_sdk_strings() {
+        // Compiled on every platform so the enum stays buildable off Linux.
+        for (t, name) in [
+            (LinuxPlatformType::X11EmbedWindowID, "X11EmbedWindowID"),
+            (LinuxPlatformType::WaylandSurfaceID, "WaylandSurfaceID"),
+        ] {
+            assert_eq!(t.as_str(), name);
+            assert_eq!(t.as_cstr().to_str().unwrap(), name);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn linux_window_handles_carry_their_platform_type() {
+        let x11 = crate::plugin::WindowHandle::from_x11(0x2a);
+        assert_eq!(x11.ptr as usize, 0x2a);
+        assert_eq!(x11.platform_type, c"X11EmbedWindowID");
+
+        let wayland = crate::plugin::WindowHandle::from_wayland_surface(std::ptr::null_mut());
+        assert_eq!(wayland.platform_type, c"WaylandSurfaceID");
+    }
+}
@@ -109,3 +109,55 @@ fn test_audio_config_defaults() {
     assert_eq!(config.input_channels, 0);
     assert_eq!(config.output_channels, 2);
 }
+
+/// Feed `secs` of a 1 kHz sine with the given RMS level through `meter` at 48 kHz.
+fn feed_sine(meter: &mut MeterBallistics, rms: f32, secs: f32) {
+    let sample_rate = 48000.0;
+    let amplitude = rms * std::f32::consts::SQRT_2;
+    for i in 0..(secs * sample_rate) as usize {
+        let phase = 2.0 * std::f32::consts::PI * 1000.0 * i as f32 / sample_rate;
+        meter.process_sample(amplitude * phase.sin());
+    }
+}
+
+#[test]
+fn test_vu_meter_reads_zero_vu_for_plus_4_dbu_sine() {
+    let mut meter = MeterBallistics::new(BallisticsPreset::VU, 48000.0);
+    feed_sine(&mut meter, MeterBallistics::VU_REFERENCE_RMS, 0.3);
+    assert!(
+        meter.vu().abs() <= 0.5,
+        "VU reading after 300 ms: {} VU",
+        meter.vu()
+    );
+}
+
+#[test]
+fn test_vu_meter_integrates_rather_than_jumping() {
+    let mut meter = MeterBallistics::new(BallisticsPreset::VU, 48000.0);
+    feed_sine(&mut meter, MeterBallistics::VU_REFERENCE_RMS, 0.03);
+    // 30 ms into a 300 ms integration the needle is still well below the final reading.
+    assert!(meter.vu() < -3.0, "VU after 30 ms: {}", meter.vu());
+}
+
+#[test]
+fn test_ppm_ballistics_attack_and_decay() {
+    let sample_rate = 48000.0;
+    // Peak attacks instantly; PPMs integrate over their attack time.
+    let mut peak = MeterBallistics::new(BallisticsPreset::Peak, sample_rate);
+    let mut ppm = MeterBallistics::new(BallisticsPreset::PpmType1, sample_rate);
+    assert_eq!(peak.process_sample(1.0), 1.0);
+    assert!(ppm.process_sample(1.0) < 0.01);
+
+    // DIN PPM falls 20 dB in 1.5 s; IEC Type II falls 20 dB in 300 ms.
+    for (preset, fall_secs) in [
+        (BallisticsPreset::PpmType1, 1.5),
+        (BallisticsPreset::IecType2, 0.3),
+    ] {
+        let mut meter = MeterBallistics::new(preset, sample_rate);
+        meter.process_block(&vec![1.0; sample_rate as usize]);
+        let start = meter.level_db();
+        meter.process_block(&vec![0.0; (fall_secs * sample_rate) as usize]);
+        let fallen = start - meter.level_db();
+        assert!((fallen - 20.0).abs() < 0.1, "{preset:?} fell {fallen} dB");
+    }
+}
@@ -0,0 +1,134 @@
+//! Run the `vst3-inspect` CLI against the bundled test synth and parse its output.
+
+use std::process::{Command, Output};
+
+const CLI: &str = env!("CARGO_BIN_EXE_vst3-inspect");
+
+/// Path to our own bundled test synth (built by `just test-plugin`), or `None` with a note.
+fn test_synth_path() -> Option<&'static str> {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../test_plugins/TestSynth.vst3"
+    );
+    if std::path::Path::new(path).exists() {
+        Some(path)
+    } else {
+        println!("TestSynth.vst3 not found at {path} — run `just test-plugin`, skipping");
+        None
+    }
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(CLI)
+        .args(args)
+        .output()
+        .expect("run vst3-inspect")
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "vst3-inspect failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout.clone()).expect("utf-8 output")
+}
+
+/// The value column of the `key\tvalue` line starting with `key`.
+fn field<'a>(out: &'a str, key: &str) -> Option<&'a str> {
+    out.lines()
+        .filter_map(|line| line.split_once('\t'))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v)
+}
+
+#[test]
+fn usage_errors_exit_with_1() {
+    assert_eq!(run(&[]).status.code(), Some(1));
+    assert_eq!(run(&["frobnicate", "x"]).status.code(), Some(1));
+    assert_eq!(
+        run(&["info", "/no/such/Plugin.vst3"]).status.code(),
+        Some(1)
+    );
+}
+
+#[test]
+fn info_prints_name_vendor_and_classes() {
+    let Some(path) = test_synth_path() else {
+        return;
+    };
+    let out = stdout(&run(&["info", path]));
+    assert!(!field(&out, "name").unwrap_or_default().is_empty());
+    assert!(field(&out, "vendor").is_some());
+    assert!(out
+        .lines()
+        .any(|l| l.starts_with("class\t") && l.contains("Audio Module Class")));
+}
+
+#[test]
+fn params_is_a_tsv_table() {
+    let Some(path) = test_synth_path() else {
+        return;
+    };
+    let out = stdout(&run(&["params", path]));
+    let mut lines = out.lines();
+    assert_eq!(lines.next(), Some("id\tname\tdefault\tunits"));
+    let rows: Vec<Vec<&str>> = lines.map(|l| l.split('\t').collect()).collect();
+    assert!(!rows.is_empty());
+    for row in &rows {
+        assert_eq!(row.len(), 4, "{row:?}");
+        row[0].parse::<u32>().expect("numeric id");
+        let default: f64 = row[2].parse().expect("numeric default");
+        assert!((0.0..=1.0).contains(&default));
+    }
+}
+
+#[test]
+fn preset_list_has_a_header() {
+    let Some(path) = test_synth_path() else {
+        return;
+    };
+    let out = stdout(&run(&["preset-list", path]));
+    assert_eq!(out.lines().next(), Some("unit\tindex\tname"));
+}
+
+#[test]
+fn set_param_renders_a_wav() {
+    let Some(path) = test_synth_path() else {
+        return;
+    };
+    let params = stdout(&run(&["params", path]));
+    let id = params
+        .lines()
+        .nth(1)
+        .and_then(|row| row.split('\t').next())
+        .expect("a parameter")
+        .to_string();
+    let wav = std::env::temp_dir().join(format!("vst3-inspect-{}.wav", std::process::id()));
+    let wav_arg = wav.to_string_lossy().into_owned();
+    let output = run(&[
+        "set-param",
+        path,
+        &id,
+        "0.5",
+        "--duration-s",
+        "0.25",
+        "--output-wav",
+        &wav_arg,
+    ]);
+    let rendered = vst3_host::read_wav(&wav);
+    let _ = std::fs::remove_file(&wav);
+    let out = stdout(&output);
+    assert!(field(&out, "peak").unwrap().parse::<f32>().is_ok());
+    let (channels, _) = rendered.expect("read rendered wav");
+    assert!(channels[0].len() >= 11_000, "{} frames", channels[0].len());
+}
+
+#[test]
+fn validate_accepts_the_test_synth() {
+    let Some(path) = test_synth_path() else {
+        return;
+    };
+    let out = stdout(&run(&["validate", path]));
+    assert_eq!(out.lines().last(), Some("ok"));
+}
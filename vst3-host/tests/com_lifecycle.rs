@@ -0,0 +1,174 @@
+//! COM reference-count lifecycle: a regression guard against leaked (or double-released) host
+//! objects.
+//!
+//! The host hands plugins objects like event lists wrapped in `ComWrapper`, and holds plugin
+//! interfaces in `ComPtr`. Getting the ownership wrong — e.g. `mem::forget`-ing a pointer to
+//! dodge a `release`, or re-wrapping a raw pointer without the matching `into_raw` — leaks the
+//! object or frees it under the plugin's feet. These tests pin down the counting semantics the
+//! rest of the crate relies on, using a mock `IEventList` that records when it is destroyed.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use vst3::{Class, ComPtr, ComWrapper, Steinberg::Vst::*, Steinberg::*};
+
+/// A minimal `IEventList` whose `Drop` bumps a shared counter, so a test can observe exactly
+/// when the last reference goes away.
+struct MockIEventList {
+    events: Mutex<Vec<Event>>,
+    drops: Arc<AtomicUsize>,
+}
+
+impl MockIEventList {
+    fn new(drops: Arc<AtomicUsize>) -> Self {
+        Self {
+            events: Mutex::new(Vec::new()),
+            drops,
+        }
+    }
+}
+
+impl Drop for MockIEventList {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl Class for MockIEventList {
+    type Interfaces = (IEventList,);
+}
+
+impl IEventListTrait for MockIEventList {
+    unsafe fn getEventCount(&self) -> i32 {
+        self.events.lock().unwrap().len() as i32
+    }
+
+    unsafe fn getEvent(&self, index: i32, event: *mut Event) -> tresult {
+        match self.events.lock().unwrap().get(index as usize) {
+            Some(e) if !event.is_null() && index >= 0 => {
+                *event = *e;
+                kResultOk
+            }
+            _ => kResultFalse,
+        }
+    }
+
+    unsafe fn addEvent(&self, event: *mut Event) -> tresult {
+        if event.is_null() {
+            return kResultFalse;
+        }
+        self.events.lock().unwrap().push(*event);
+        kResultOk
+    }
+}
+
+/// The object's current reference count, read through its own `addRef`/`release` (each returns
+/// the resulting count) so the probe leaves the count unchanged.
+fn ref_count(ptr: &ComPtr<IEventList>) -> u32 {
+    let unknown = ptr.as_ptr() as *mut FUnknown;
+    unsafe {
+        let vtbl = &*(*unknown).vtbl;
+        let after_add = (vtbl.addRef)(unknown);
+        (vtbl.release)(unknown);
+        after_add - 1
+    }
+}
+
+fn mock() -> (ComWrapper<MockIEventList>, Arc<AtomicUsize>) {
+    let drops = Arc::new(AtomicUsize::new(0));
+    (ComWrapper::new(MockIEventList::new(drops.clone())), drops)
+}
+
+#[test]
+fn to_com_ptr_takes_a_new_reference() {
+    let (wrapper, _drops) = mock();
+    let ptr = wrapper.to_com_ptr::<IEventList>().expect("IEventList");
+    // One reference held by the wrapper, one by the ComPtr.
+    assert_eq!(ref_count(&ptr), 2);
+}
+
+#[test]
+fn from_raw_adopts_the_reference_without_incrementing() {
+    let (wrapper, _drops) = mock();
+    let ptr = wrapper.to_com_ptr::<IEventList>().expect("IEventList");
+    assert_eq!(ref_count(&ptr), 2);
+
+    // into_raw hands the reference out; from_raw takes it back. The round trip must neither
+    // add nor lose a reference — this is the correct replacement for `mem::forget`.
+    let raw = ptr.into_raw();
+    let ptr = unsafe { ComPtr::from_raw(raw) }.expect("non-null");
+    assert_eq!(ref_count(&ptr), 2);
+}
+
+#[test]
+fn com_ref_to_com_ptr_increments() {
+    let (wrapper, _drops) = mock();
+    let ptr = wrapper.to_com_ptr::<IEventList>().expect("IEventList");
+    // Borrowing is free; upgrading the borrow to an owning pointer adds a reference.
+    let borrowed = ptr.as_com_ref();
+    let owned = borrowed.to_com_ptr();
+    assert_eq!(ref_count(&owned), 3);
+}
+
+#[test]
+fn clone_increments_and_drop_decrements() {
+    let (wrapper, _drops) = mock();
+    let ptr = wrapper.to_com_ptr::<IEventList>().expect("IEventList");
+    assert_eq!(ref_count(&ptr), 2);
+
+    let clone = ptr.clone();
+    assert_eq!(ref_count(&ptr), 3);
+
+    drop(clone);
+    assert_eq!(ref_count(&ptr), 2);
+
+    let wrapper_clone = wrapper.clone();
+    assert_eq!(ref_count(&ptr), 3);
+    drop(wrapper_clone);
+    assert_eq!(ref_count(&ptr), 2);
+}
+
+#[test]
+fn object_is_destroyed_exactly_once_when_all_handles_drop() {
+    let (wrapper, drops) = mock();
+    let ptr = wrapper.to_com_ptr::<IEventList>().expect("IEventList");
+    let clone = ptr.clone();
+    let raw = clone.into_raw();
+
+    drop(wrapper);
+    drop(ptr);
+    // The raw reference is still outstanding, so the object must be alive.
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+    let last = unsafe { ComPtr::from_raw(raw) }.expect("non-null");
+    assert_eq!(ref_count(&last), 1);
+    drop(last);
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn events_survive_while_a_plugin_side_reference_is_held() {
+    let (wrapper, drops) = mock();
+    let plugin_side = wrapper.to_com_ptr::<IEventList>().expect("IEventList");
+    drop(wrapper);
+
+    // The host dropped its handle but the plugin's reference keeps the list usable.
+    let mut event: Event = unsafe { std::mem::zeroed() };
+    event.sampleOffset = 7;
+    unsafe {
+        assert_eq!(plugin_side.addEvent(&mut event), kResultOk);
+        assert_eq!(plugin_side.getEventCount(), 1);
+    }
+    assert_eq!(ref_count(&plugin_side), 1);
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+    drop(plugin_side);
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn iid_is_the_event_list_interface() {
+    // Guard against the mock accidentally advertising a different interface.
+    let (wrapper, _drops) = mock();
+    assert!(wrapper.to_com_ptr::<IEventList>().is_some());
+    assert!(wrapper.to_com_ptr::<IParameterChanges>().is_none());
+}
@@ -1,5 +1,5 @@
 use vst3_host::host::DiscoveryProgress;
-use vst3_host::plugin::PluginInfo;
+use vst3_host::plugin::{PluginFormat, PluginInfo};
 
 #[test]
 fn test_plugin_info() {
@@ -8,13 +8,15 @@ fn test_plugin_info() {
         name: "Test Plugin".to_string(),
         vendor: "Test Vendor".to_string(),
         version: "1.0.0".to_string(),
-        category: "Fx".to_string(),
+        category_path: vec!["Fx".to_string()],
         uid: "123456789ABCDEF0".to_string(),
         audio_inputs: 2,
         audio_outputs: 2,
         has_midi_input: false,
         has_midi_output: false,
         has_gui: true,
+        format: PluginFormat::Vst3,
+        duplicates: Vec::new(),
     };
 
     assert_eq!(info.name, "Test Plugin");
@@ -44,13 +46,15 @@ fn test_discovery_progress() {
         name: "Found Plugin".to_string(),
         vendor: "Vendor".to_string(),
         version: "1.0".to_string(),
-        category: "Instrument".to_string(),
+        category_path: vec!["Instrument".to_string()],
         uid: "0000000000000000".to_string(),
         audio_inputs: 0,
         audio_outputs: 2,
         has_midi_input: true,
         has_midi_output: false,
         has_gui: false,
+        format: PluginFormat::Vst3,
+        duplicates: Vec::new(),
     };
 
     let progress = DiscoveryProgress::Found {
@@ -105,13 +109,15 @@ fn test_plugin_info_uid() {
         name: "UID Test".to_string(),
         vendor: "Test".to_string(),
         version: "1.0".to_string(),
-        category: "Fx".to_string(),
+        category_path: vec!["Fx".to_string()],
         uid: uid.clone(),
         audio_inputs: 0,
         audio_outputs: 0,
         has_midi_input: false,
         has_midi_output: false,
         has_gui: false,
+        format: PluginFormat::Vst3,
+        duplicates: Vec::new(),
     };
 
     // Verify UID is stored correctly
@@ -126,16 +132,18 @@ fn test_instrument_vs_effect() {
         name: "Synth".to_string(),
         vendor: "Vendor".to_string(),
         version: "1.0".to_string(),
-        category: "Instrument".to_string(),
+        category_path: vec!["Instrument".to_string()],
         uid: "0000000000000000".to_string(),
         audio_inputs: 0,
         audio_outputs: 2,
         has_midi_input: true,
         has_midi_output: false,
         has_gui: true,
+        format: PluginFormat::Vst3,
+        duplicates: Vec::new(),
     };
 
-    assert_eq!(instrument.category, "Instrument");
+    assert_eq!(instrument.category(), "Instrument");
     assert_eq!(instrument.audio_inputs, 0); // Instruments often have no audio input
     assert!(instrument.has_midi_input); // But they do have MIDI input
 
@@ -145,16 +153,18 @@ fn test_instrument_vs_effect() {
         name: "Reverb".to_string(),
         vendor: "Vendor".to_string(),
         version: "1.0".to_string(),
-        category: "Fx".to_string(),
+        category_path: vec!["Fx".to_string()],
         uid: "1111111111111111".to_string(),
         audio_inputs: 2,
         audio_outputs: 2,
         has_midi_input: false,
         has_midi_output: false,
         has_gui: true,
+        format: PluginFormat::Vst3,
+        duplicates: Vec::new(),
     };
 
-    assert_eq!(effect.category, "Fx");
+    assert_eq!(effect.category(), "Fx");
     assert_eq!(effect.audio_inputs, 2); // Effects typically process input
     assert_eq!(effect.audio_outputs, 2);
 }
@@ -396,3 +406,110 @@ fn safe_discovery_finds_dexed_out_of_process() {
 
     std::env::remove_var("VST3_HOST_PROBE_PATH");
 }
+
+/// A scan folder with CLAP plugins next to VST3 bundles and a nested vendor folder.
+fn mixed_format_folder(name: &str) -> std::path::PathBuf {
+    let tmp = std::env::temp_dir().join(format!("vst3-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&tmp);
+    std::fs::create_dir_all(tmp.join("Vendor")).expect("mk tmp");
+    std::fs::write(tmp.join("Synth.clap"), b"not a real plugin binary").expect("write clap");
+    std::fs::create_dir_all(tmp.join("Vendor").join("Bundle.clap").join("Contents"))
+        .expect("mk clap bundle");
+    // A VST3 bundle with a .clap inside must not be searched.
+    std::fs::create_dir_all(tmp.join("Synth.vst3").join("Contents")).expect("mk vst3");
+    std::fs::write(
+        tmp.join("Synth.vst3").join("Contents").join("Hidden.clap"),
+        b"",
+    )
+    .expect("write hidden");
+    tmp
+}
+
+#[test]
+fn clap_scan_lists_clap_bundles_and_skips_vst3() {
+    let tmp = mixed_format_folder("clap-scan");
+    let found =
+        vst3_host::discovery::scan_clap_directories(&[tmp.clone(), tmp.join("missing-folder")]);
+    assert_eq!(
+        found,
+        vec![
+            tmp.join("Synth.clap"),
+            tmp.join("Vendor").join("Bundle.clap")
+        ]
+    );
+    // Listed plugins are taken as they are.
+    assert_eq!(vst3_host::discovery::scan_clap_directories(&found), found);
+    // And the VST3 scan doesn't pick up the CLAP plugins.
+    let vst3 = vst3_host::discovery::scan_directories(std::slice::from_ref(&tmp)).unwrap();
+    assert_eq!(vst3, vec![tmp.join("Synth.vst3")]);
+    let _ = std::fs::remove_dir_all(&tmp);
+}
+
+#[cfg(feature = "clap-support")]
+#[test]
+fn clap_metadata_scan_skips_bundles_that_fail_to_load() {
+    let tmp = mixed_format_folder("clap-info");
+    assert!(vst3_host::discovery::scan_for_clap_plugins(std::slice::from_ref(&tmp)).is_empty());
+    let _ = std::fs::remove_dir_all(&tmp);
+}
+
+/// Compile `source` into a shared library in a fresh temp dir, or `None` without a C compiler.
+#[cfg(unix)]
+fn stub_library(name: &str, source: &str) -> Option<std::path::PathBuf> {
+    let dir = std::env::temp_dir().join(format!("vst3-abi-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("mk dir");
+    let c_file = dir.join("stub.c");
+    std::fs::write(&c_file, source).expect("write stub source");
+    let ext = if cfg!(target_os = "macos") {
+        "dylib"
+    } else {
+        "so"
+    };
+    let library = dir.join(format!("{name}.{ext}"));
+    let built = std::process::Command::new("cc")
+        .args(["-shared", "-fPIC", "-o"])
+        .arg(&library)
+        .arg(&c_file)
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false);
+    built.then_some(library)
+}
+
+#[cfg(unix)]
+#[test]
+fn abi_check_rejects_a_library_without_get_plugin_factory() {
+    let Some(stub) = stub_library("no-factory", "int not_a_plugin(void) { return 0; }\n") else {
+        eprintln!("no C compiler; skipping");
+        return;
+    };
+    let result = vst3_host::check_vst3_abi(stub.to_str().unwrap());
+    match result {
+        Err(vst3_host::Error::InvalidPlugin(detail)) => {
+            assert_eq!(detail, "No GetPluginFactory export")
+        }
+        other => panic!("expected InvalidPlugin, got {other:?}"),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn abi_check_reports_module_entry_points_and_sdk_version() {
+    let source = r#"
+        #include <stdbool.h>
+        const char* sdk = "VST 3.7.9";
+        void* GetPluginFactory(void) { return 0; }
+        bool ModuleEntry(void* handle) { return true; }
+        bool ModuleExit(void) { return true; }
+    "#;
+    let Some(stub) = stub_library("entry-points", source) else {
+        eprintln!("no C compiler; skipping");
+        return;
+    };
+    let abi = vst3_host::check_vst3_abi(stub.to_str().unwrap()).expect("exports GetPluginFactory");
+    assert!(abi.has_module_entry);
+    assert!(abi.has_module_exit);
+    assert!(!abi.has_compat_interface);
+    assert_eq!(abi.sdk_version.as_deref(), Some("VST 3.7.9"));
+}
@@ -97,6 +97,116 @@ fn measure_freq(plugin: &mut Plugin) -> f64 {
     (crossings as f64 / 2.0) / (frames as f64 / sr)
 }
 
+/// `SKIP_CONTROLLER_INIT` leaves the controller uncreated: TestSynth then has no parameters
+/// and no editor, though it has both when loaded normally.
+#[test]
+#[ignore = "Requires the bundled TestSynth (just test-plugin)"]
+fn test_skip_controller_init_creates_no_controller() {
+    use vst3_host::PluginInitFlags;
+
+    let _guard = plugin_guard();
+    let Some(path) = test_synth_path() else {
+        return;
+    };
+    let mut host = Vst3Host::builder().build().expect("build host");
+    let plugin = host.load_plugin(path).expect("load TestSynth");
+    assert!(!plugin.get_parameters().unwrap().is_empty());
+    drop(plugin);
+
+    let mut host = Vst3Host::builder()
+        .plugin_init_flags("TestSynth", PluginInitFlags::SKIP_CONTROLLER_INIT)
+        .build()
+        .expect("build host");
+    let mut plugin = host.load_plugin(path).expect("load TestSynth");
+    assert!(!plugin.has_controller());
+    assert!(plugin.get_parameters().unwrap().is_empty());
+    assert!(!plugin.has_editor());
+    assert!(matches!(
+        plugin.get_parameter(0),
+        Err(vst3_host::Error::NoController)
+    ));
+    assert!(matches!(
+        plugin.set_parameter(0, 0.5),
+        Err(vst3_host::Error::NoController)
+    ));
+    // The processor still works without a controller.
+    plugin.start_processing().expect("start processing");
+    let mut buffers = AudioBuffers::new(0, 2, 512, 44100.0);
+    plugin.process_audio(&mut buffers).expect("process_audio");
+}
+
+/// `REQUIRE_CONTROLLER` turns a plugin left without a controller into a load error.
+#[test]
+#[ignore = "Requires the bundled TestSynth (just test-plugin)"]
+fn test_require_controller_refuses_a_controllerless_load() {
+    use vst3_host::PluginInitFlags;
+
+    let _guard = plugin_guard();
+    let Some(path) = test_synth_path() else {
+        return;
+    };
+    let mut host = Vst3Host::builder()
+        .plugin_init_flags("TestSynth", PluginInitFlags::REQUIRE_CONTROLLER)
+        .build()
+        .expect("build host");
+    assert!(host
+        .load_plugin(path)
+        .expect("load TestSynth")
+        .has_controller());
+
+    let mut host = Vst3Host::builder()
+        .plugin_init_flags(
+            "TestSynth",
+            PluginInitFlags::SKIP_CONTROLLER_INIT | PluginInitFlags::REQUIRE_CONTROLLER,
+        )
+        .build()
+        .expect("build host");
+    assert!(matches!(
+        host.load_plugin(path),
+        Err(vst3_host::Error::NoController)
+    ));
+}
+
+/// A fresh TestSynth starts at its factory defaults; one restored from a state saved after
+/// moving a parameter reports exactly that parameter.
+#[test]
+#[ignore = "Requires the bundled TestSynth (just test-plugin)"]
+fn test_factory_default_mismatches() {
+    let _guard = plugin_guard();
+    let Some(path) = test_synth_path() else {
+        return;
+    };
+    let mut host = Vst3Host::builder().build().expect("build host");
+    let mut plugin = host.load_plugin(path).expect("load TestSynth");
+    assert!(plugin.factory_default_mismatches().is_empty());
+    assert!(plugin.verify_factory_defaults().is_empty());
+
+    let param = plugin
+        .get_parameters()
+        .unwrap()
+        .into_iter()
+        .find(|p| !p.is_read_only)
+        .expect("a writable parameter");
+    let moved = if param.default < 0.5 { 1.0 } else { 0.0 };
+    plugin.set_parameter(param.id, moved).unwrap();
+    // The processor picks the change up (and saves it in its state) on the next block.
+    plugin.start_processing().expect("start processing");
+    let mut buffers = AudioBuffers::new(0, 2, 512, 44100.0);
+    plugin.process_audio(&mut buffers).expect("process_audio");
+    plugin.stop_processing().expect("stop processing");
+    let state = plugin.save_state().expect("save state");
+
+    let mut restored = host.load_plugin(path).expect("load TestSynth");
+    assert!(restored.factory_default_mismatches().is_empty());
+    restored.load_state(&state).expect("load state");
+    let mismatches = restored.verify_factory_defaults();
+    assert_eq!(mismatches.len(), 1, "{mismatches:?}");
+    assert_eq!(mismatches[0].param_id, param.id);
+    assert_eq!(mismatches[0].expected_normalized, param.default);
+    assert!((mismatches[0].actual_normalized - moved).abs() < 1e-6);
+    assert!((mismatches[0].delta - (moved - param.default).abs()).abs() < 1e-6);
+}
+
 /// Note expression (MPE) end-to-end against our own TestSynth: a per-note Tuning expression
 /// audibly bends one voice's pitch. Dexed can't demonstrate this (no INoteExpressionController).
 #[test]
@@ -144,6 +254,35 @@ fn test_note_expression_bends_pitch() {
     );
 }
 
+/// With 2× oversampling the plugin is set up at 96 kHz and fed twice the frames, so a note
+/// still sounds at its true pitch at the host rate (set up at 48 kHz it would play an octave
+/// up), and each block comes back at the host's frame count.
+#[test]
+#[ignore = "Requires the bundled TestSynth (just test-plugin)"]
+fn test_oversampled_plugin_keeps_pitch_and_block_size() {
+    let _guard = plugin_guard();
+    let Some(path) = test_synth_path() else {
+        return;
+    };
+    let mut host = Vst3Host::builder()
+        .sample_rate(48000.0)
+        .block_size(4096)
+        .oversampling_factor(2)
+        .build()
+        .expect("build host");
+    let mut plugin = host.load_plugin(path).expect("load TestSynth");
+    assert_eq!(plugin.oversampling_factor(), 2);
+    assert_eq!(plugin.sample_rate(), 48000.0);
+
+    plugin.start_processing().expect("start_processing");
+    plugin.send_midi_note(60, 100, MidiChannel::Ch1).unwrap();
+    let freq = measure_freq(&mut plugin);
+    plugin.stop_processing().ok();
+
+    println!("oversampled note 60: {freq:.1} Hz");
+    assert!(freq > 200.0 && freq < 320.0, "expected ~261 Hz, got {freq}");
+}
+
 /// Same as [`load_test_synth`], but runs the plugin out-of-process (process isolation).
 #[cfg(feature = "process-isolation")]
 fn load_test_synth_isolated() -> Option<(Vst3Host, Plugin)> {
@@ -456,6 +595,163 @@ fn test_parameter_set_get_format_roundtrip() {
     }
 }
 
+/// Bulk reset: after moving a few parameters, `reset_modified_parameters` resets exactly
+/// those (returning their pre-reset values for undo), and afterwards every writable parameter
+/// sits at its default; `reset_all_parameters` then touches every writable parameter.
+#[test]
+#[ignore = "Requires the bundled test plugin"]
+fn test_reset_parameters_restores_defaults() {
+    let _guard = plugin_guard();
+    let Some((_host, mut plugin)) = load_dexed() else {
+        return;
+    };
+    let writable: Vec<Parameter> = plugin
+        .get_parameters()
+        .expect("get_parameters")
+        .into_iter()
+        .filter(|p| !p.is_read_only)
+        .collect();
+    assert!(!writable.is_empty(), "Dexed has writable parameters");
+
+    // Start from defaults, then move a few continuous parameters well away from them.
+    plugin.reset_all_parameters().expect("reset_all_parameters");
+    let moved: Vec<(u32, f64)> = writable
+        .iter()
+        .filter(|p| p.step_count == 0)
+        .take(3)
+        .map(|p| (p.id, if p.default > 0.5 { 0.1 } else { 0.9 }))
+        .collect();
+    for &(id, v) in &moved {
+        plugin.set_parameter(id, v).expect("set_parameter");
+    }
+
+    let undo = plugin
+        .reset_modified_parameters()
+        .expect("reset_modified_parameters");
+    assert_eq!(undo.len(), moved.len(), "only the moved parameters reset");
+    for &(id, v) in &moved {
+        let change = undo
+            .iter()
+            .find(|c| c.id == id)
+            .expect("moved param in undo list");
+        assert!(
+            (change.value - v).abs() < 0.05,
+            "undo holds the pre-reset value"
+        );
+    }
+
+    let assert_all_default = |plugin: &Plugin| {
+        for p in plugin.get_parameters().expect("get_parameters") {
+            if !p.is_read_only {
+                assert!(
+                    (p.value - p.default).abs() < 1e-3,
+                    "param '{}' (id {}) = {} after reset, default {}",
+                    p.name,
+                    p.id,
+                    p.value,
+                    p.default
+                );
+            }
+        }
+    };
+    assert_all_default(&plugin);
+
+    let all = plugin.reset_all_parameters().expect("reset_all_parameters");
+    assert_eq!(all.len(), writable.len());
+    assert_all_default(&plugin);
+}
+
+/// Morphing halfway between two snapshots puts a continuous parameter halfway between its
+/// A and B values, and `can_morph_param` accepts it.
+#[test]
+#[ignore = "Requires the bundled test plugin"]
+fn test_morph_snapshots_halfway() {
+    let _guard = plugin_guard();
+    let Some((_host, mut plugin)) = load_dexed() else {
+        return;
+    };
+    let param = plugin
+        .get_parameters()
+        .expect("get_parameters")
+        .into_iter()
+        .find(|p| !p.is_read_only && p.step_count == 0)
+        .expect("Dexed has a writable continuous parameter");
+    assert!(plugin.can_morph_param(param.id));
+
+    plugin.set_parameter(param.id, 0.2).expect("set_parameter");
+    let a = plugin.snapshot_parameters().expect("snapshot A");
+    plugin.set_parameter(param.id, 0.8).expect("set_parameter");
+    let b = plugin.snapshot_parameters().expect("snapshot B");
+
+    let applied = plugin
+        .morph_snapshots(&a, &b, 0.5)
+        .expect("morph_snapshots");
+    assert!(applied.iter().any(|c| c.id == param.id));
+    let value = plugin.get_parameter(param.id).expect("get_parameter");
+    assert!((value - 0.5).abs() < 0.02, "halfway value was {value}");
+}
+
+/// Factory reset reloads the plugin from scratch: every parameter comes back at its
+/// default normalized value, even ones changed beforehand.
+#[test]
+#[ignore = "Requires the bundled test plugin"]
+fn test_factory_reset_restores_defaults() {
+    let _guard = plugin_guard();
+    let Some((_host, mut plugin)) = load_dexed() else {
+        return;
+    };
+    for param in plugin.get_parameters().expect("get_parameters") {
+        if !param.is_read_only {
+            let _ = plugin.set_parameter(param.id, 1.0 - param.default.clamp(0.0, 1.0));
+        }
+    }
+
+    plugin.factory_reset().expect("factory_reset");
+
+    let defaults = plugin.factory_defaults();
+    assert!(!defaults.is_empty());
+    for (id, default) in defaults {
+        let value = plugin.get_parameter(id).expect("get_parameter");
+        assert!(
+            (value - default).abs() < 1e-3,
+            "parameter {id} is {value} after factory reset, default {default}"
+        );
+    }
+}
+
+/// `plain_to_normalized(normalized_to_plain(x)) ≈ x` for every parameter.
+#[test]
+#[ignore = "Requires the bundled test plugin"]
+fn test_plain_normalized_round_trip() {
+    let _guard = plugin_guard();
+    let Some((_host, plugin)) = load_dexed() else {
+        return;
+    };
+    for param in plugin.get_parameters().expect("get_parameters") {
+        // Stepped parameters round to their steps, so only test values on a step.
+        let steps = param.step_count.max(0) as usize;
+        let samples: Vec<f64> = if steps > 0 {
+            (0..=steps).map(|s| s as f64 / steps as f64).collect()
+        } else {
+            (0..=10).map(|i| i as f64 / 10.0).collect()
+        };
+        for x in samples {
+            let plain = plugin
+                .normalized_to_plain(param.id, x)
+                .expect("normalized_to_plain");
+            let back = plugin
+                .plain_to_normalized(param.id, plain)
+                .expect("plain_to_normalized");
+            assert!(
+                (back - x).abs() < 1e-3,
+                "parameter {} ({}): {x} -> {plain} -> {back}",
+                param.id,
+                param.name
+            );
+        }
+    }
+}
+
 /// JSON preset save/load round-trip: save -> change param -> load -> value restored.
 #[test]
 #[ignore = "Requires the bundled test plugin"]
@@ -1457,6 +1753,82 @@ fn test_testsynth_state_roundtrip() {
     }
 }
 
+/// Keeps every warning logged in this test binary, so a test can check the host warned.
+struct WarningLog(Mutex<Vec<String>>);
+
+impl log::Log for WarningLog {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Warn
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            let mut warnings = self.0.lock().unwrap_or_else(|e| e.into_inner());
+            warnings.push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static WARNINGS: WarningLog = WarningLog(Mutex::new(Vec::new()));
+
+/// Install [`WARNINGS`] as the logger (once) and return what it has captured.
+fn captured_warnings() -> &'static Mutex<Vec<String>> {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        if log::set_logger(&WARNINGS).is_ok() {
+            log::set_max_level(log::LevelFilter::Warn);
+        }
+    });
+    &WARNINGS.0
+}
+
+/// 64-bit processing: TestSynth rejects `canProcessSampleSize(kSample64)`, so asking for
+/// `SampleSize::F64` falls back to 32-bit with a warning instead of failing, and the synth
+/// still renders a held note.
+#[test]
+#[ignore = "Requires the bundled TestSynth (just test-plugin)"]
+fn test_testsynth_f64_request_falls_back_to_f32() {
+    let _guard = plugin_guard();
+    let Some(path) = test_synth_path() else {
+        return;
+    };
+    let warnings = captured_warnings();
+    let mut host = Vst3Host::builder()
+        .sample_rate(48000.0)
+        .block_size(512)
+        .with_sample_size(vst3_host::SampleSize::F64)
+        .build()
+        .expect("build host");
+    let mut plugin = host.load_plugin(path).expect("load TestSynth at F64");
+    plugin.start_processing().expect("start_processing");
+    plugin
+        .send_midi_note(60, 110, MidiChannel::Ch1)
+        .expect("send note on");
+
+    let mut peak = 0.0f32;
+    for _ in 0..10 {
+        let mut buffers = AudioBuffers::new(0, 2, 512, 48000.0);
+        plugin.process_audio(&mut buffers).expect("process_audio");
+        peak = buffers
+            .outputs
+            .iter()
+            .flatten()
+            .fold(peak, |p, s| p.max(s.abs()));
+    }
+    plugin.stop_processing().ok();
+
+    assert!(peak > 0.0, "the 32-bit fallback produced no audio");
+    let warnings = warnings.lock().unwrap_or_else(|e| e.into_inner());
+    assert!(
+        warnings
+            .iter()
+            .any(|w| w.contains("can't process 64-bit samples; falling back to 32-bit")),
+        "no fallback warning in {warnings:?}"
+    );
+}
+
 /// Factory programs: IUnitInfo exposes the list, and select_program loads the preset.
 #[test]
 #[ignore = "Requires the bundled TestSynth (just test-plugin)"]
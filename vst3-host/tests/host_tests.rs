@@ -115,6 +115,28 @@ fn test_cpal_backend_creation() {
     assert!(input_devices.is_ok());
 }
 
+#[cfg(feature = "cpal-backend")]
+#[test]
+fn test_list_audio_output_devices_finds_a_device() {
+    let mut host = Vst3Host::builder().build().unwrap();
+    let devices = host.list_audio_output_devices().unwrap();
+    assert!(!devices.is_empty(), "no audio output devices listed");
+    for device in &devices {
+        assert!(!device.name.is_empty());
+        assert!(device.sample_rates.windows(2).all(|w| w[0] < w[1]));
+    }
+    host.set_audio_device(&devices[0].name).unwrap();
+    assert_eq!(host.audio_device(), Some(devices[0].name.as_str()));
+}
+
+#[cfg(feature = "cpal-backend")]
+#[test]
+fn test_set_audio_device_rejects_an_unknown_device() {
+    let mut host = Vst3Host::builder().build().unwrap();
+    assert!(host.set_audio_device("No Such Device 1640").is_err());
+    assert_eq!(host.audio_device(), None);
+}
+
 #[test]
 fn test_host_with_custom_backend() {
     // Create a mock backend for testing
@@ -257,6 +257,60 @@ fn test_audio_processing() {
     );
 }
 
+/// Effect hosting: `process_audio` feeds `buffers.inputs` to the plugin's input buses, so a
+/// gain-style effect's output level follows its input level. Needs an effect plugin with a
+/// linear response at its default settings (a gain or utility plugin), named by the
+/// `VST3_TEST_EFFECT` environment variable.
+#[test]
+#[ignore = "Requires an effect plugin named by VST3_TEST_EFFECT"]
+fn test_effect_output_tracks_input() {
+    let Ok(path) = std::env::var("VST3_TEST_EFFECT") else {
+        println!("VST3_TEST_EFFECT not set, skipping");
+        return;
+    };
+    let mut host = Vst3Host::builder()
+        .sample_rate(48000.0)
+        .block_size(512)
+        .build()
+        .unwrap();
+    let mut plugin = host.load_plugin(&path).unwrap();
+    plugin.start_processing().unwrap();
+
+    // Feed a stereo 440 Hz sine at `amplitude` for a while and measure the settled output RMS.
+    fn render_rms(plugin: &mut Plugin, amplitude: f32) -> f64 {
+        let (mut sumsq, mut n) = (0.0f64, 0u64);
+        for block in 0..40 {
+            let mut b = AudioBuffers::new(2, 2, 512, 48000.0);
+            for channel in &mut b.inputs {
+                for (i, s) in channel.iter_mut().enumerate() {
+                    let t = (block * 512 + i) as f32 / 48000.0;
+                    *s = amplitude * (2.0 * std::f32::consts::PI * 440.0 * t).sin();
+                }
+            }
+            plugin.process_audio(&mut b).unwrap();
+            if block >= 20 {
+                for &s in b.outputs.iter().flatten() {
+                    sumsq += (s as f64) * (s as f64);
+                    n += 1;
+                }
+            }
+        }
+        (sumsq / n.max(1) as f64).sqrt()
+    }
+
+    let quiet = render_rms(&mut plugin, 0.1);
+    let loud = render_rms(&mut plugin, 0.4);
+    plugin.stop_processing().ok();
+
+    println!("effect input tracking: quiet RMS={quiet:.6}, loud RMS={loud:.6}");
+    assert!(quiet > 0.0, "the effect passed no audio through");
+    let ratio = loud / quiet;
+    assert!(
+        (3.0..=5.0).contains(&ratio),
+        "output didn't follow a 4x input level change: ratio {ratio:.3}"
+    );
+}
+
 /// Runtime transport mutation: change tempo / time signature / playing-state while the plugin
 /// is actively processing and confirm the setters take effect on the next block without error.
 ///
@@ -466,10 +520,10 @@ fn test_process_isolation() {
     // Accurate metadata crosses the boundary (not the old "unknown"/default placeholders).
     let info = plugin.info();
     assert!(
-        info.category.to_lowercase().contains("inst")
-            || info.category.to_lowercase().contains("synth"),
+        info.category().to_lowercase().contains("inst")
+            || info.category().to_lowercase().contains("synth"),
         "isolated metadata lost the real category: {:?}",
-        info.category
+        info.category()
     );
     assert_ne!(
         info.uid, "unknown",
@@ -631,11 +685,11 @@ fn test_plugin_metadata_is_detected() {
     let plugin = host.load_plugin(path).unwrap();
     let i = plugin.info();
     assert!(!i.version.is_empty(), "version should be detected");
-    let cat = i.category.to_lowercase();
+    let cat = i.category().to_lowercase();
     assert!(
         cat.contains("inst") || cat.contains("synth"),
         "Dexed is an instrument; got category {:?}",
-        i.category
+        i.category()
     );
     assert!(i.has_midi_input, "Dexed accepts MIDI input");
     assert_eq!(plugin.output_channel_count(), 2, "Dexed is stereo");
@@ -951,6 +1005,42 @@ fn test_isolation_dead_helper_errors_fast() {
     );
 }
 
+/// A helper killed by a fatal signal leaves a crash report naming the signal and the last
+/// command it was given.
+#[cfg(all(unix, feature = "process-isolation"))]
+#[test]
+fn test_isolation_helper_crash_report() {
+    use std::time::Duration;
+    use vst3_host::process_isolation::{HostCommand, HostResponse, PluginHostProcess};
+
+    let helper = std::path::PathBuf::from(env!("CARGO_BIN_EXE_vst3-host-helper"));
+    let mut proc =
+        PluginHostProcess::new(Some(helper), Duration::from_secs(5)).expect("helper should spawn");
+    let res = proc.send_command(HostCommand::GetAllParameters);
+    assert!(matches!(res, Ok(HostResponse::Error { .. })), "{res:?}");
+
+    let pid = proc.helper_pid().expect("helper pid") as libc::pid_t;
+    assert_eq!(unsafe { libc::kill(pid, libc::SIGABRT) }, 0);
+
+    // Other threads keep answering while the handler writes the report, so poll until the
+    // helper is gone.
+    let err = (0..100)
+        .find_map(|_| {
+            std::thread::sleep(Duration::from_millis(50));
+            proc.send_command(HostCommand::GetAllParameters).err()
+        })
+        .expect("the helper should die");
+    let report = proc.take_crash_report().expect("crash report");
+    assert!(err.contains("SIGABRT"), "{err}");
+    assert_eq!(report.signal, libc::SIGABRT);
+    assert_eq!(report.signal_name(), Some("SIGABRT"));
+    assert_eq!(report.last_command, "GetAllParameters");
+    assert!(report.plugin_path.is_empty());
+    assert!(report.timestamp > 0);
+    assert!(!report.backtrace.is_empty());
+    assert!(proc.take_crash_report().is_none(), "handed out once");
+}
+
 #[test]
 #[ignore = "Requires free VST3 synths to be installed"]
 fn test_specific_free_plugins() {
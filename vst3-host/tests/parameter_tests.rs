@@ -15,6 +15,7 @@ fn test_parameter_creation() {
         is_read_only: false,
         is_bypass: false,
         flags: 0,
+        unit_id: 0,
     };
 
     assert_eq!(param.id, 100);
@@ -45,6 +46,7 @@ fn test_parameter_value_validation() {
         is_read_only: false,
         is_bypass: false,
         flags: 0,
+        unit_id: 0,
     };
 
     // Values should be clamped to min/max range
@@ -69,6 +71,7 @@ fn test_parameter_normalization() {
         is_read_only: false,
         is_bypass: false,
         flags: 0,
+        unit_id: 0,
     };
 
     // Test normalized to plain
@@ -98,6 +101,7 @@ fn test_parameter_formatting() {
         is_read_only: false,
         is_bypass: false,
         flags: 0,
+        unit_id: 0,
     };
 
     assert_eq!(gain.format_value(0.5), "0.000 dB");
@@ -116,6 +120,7 @@ fn test_parameter_formatting() {
         is_read_only: false,
         is_bypass: true,
         flags: 0,
+        unit_id: 0,
     };
 
     assert_eq!(bypass.format_value(0.0), "Off");
@@ -138,6 +143,7 @@ fn test_parameter_types() {
         is_read_only: false,
         is_bypass: false,
         flags: 0,
+        unit_id: 0,
     };
 
     assert!(!continuous.is_discrete());
@@ -157,6 +163,7 @@ fn test_parameter_types() {
         is_read_only: false,
         is_bypass: false,
         flags: 0,
+        unit_id: 0,
     };
 
     assert!(discrete.is_discrete());
@@ -176,6 +183,7 @@ fn test_parameter_types() {
         is_read_only: false,
         is_bypass: false,
         flags: 0,
+        unit_id: 0,
     };
 
     assert!(boolean.is_discrete());
@@ -188,6 +196,7 @@ fn test_parameter_change() {
         id: 42,
         value: 0.75,
         sample_offset: 128,
+        timestamp: std::time::Instant::now(),
     };
 
     assert_eq!(change.id, 42);
@@ -278,6 +287,7 @@ fn test_bypass_parameter() {
         is_read_only: false,
         is_bypass: true,
         flags: 0,
+        unit_id: 0,
     };
 
     assert!(bypass.is_bypass);
@@ -0,0 +1,95 @@
+//! The Processing tab's output analysis: a histogram of the output samples from -1 to +1 with
+//! their mean, variance and kurtosis, flagging output that is far from Gaussian.
+
+use eframe::egui;
+use vst3_host::BlockStatsSnapshot;
+
+const CHART_HEIGHT: f32 = 120.0;
+
+/// Height of a bar holding `count` samples as a fraction of the chart, on a square-root scale
+/// so the tails stay visible next to a tall bin around silence.
+fn bar_fraction(count: u32, max: u32) -> f32 {
+    if max == 0 {
+        return 0.0;
+    }
+    (count as f32 / max as f32).sqrt()
+}
+
+/// Draw `stats`: the figures on one line, the histogram below.
+pub fn show(ui: &mut egui::Ui, stats: &BlockStatsSnapshot) {
+    if stats.samples == 0 {
+        ui.label("No output analysed yet \u{2014} start processing to collect statistics.");
+        return;
+    }
+    ui.horizontal(|ui| {
+        ui.label(format!("Samples: {}", stats.samples));
+        ui.separator();
+        ui.label(format!("Mean: {:+.5}", stats.mean));
+        ui.separator();
+        ui.label(format!("Variance: {:.5}", stats.variance));
+        ui.separator();
+        ui.label(format!("Kurtosis: {:.2}", stats.kurtosis));
+    });
+    if stats.kurtosis_alert() {
+        ui.colored_label(
+            egui::Color32::YELLOW,
+            format!(
+                "\u{26A0} Kurtosis alert: {:.2} is far from 3.0, the output isn't Gaussian",
+                stats.kurtosis
+            ),
+        );
+    }
+
+    let width = ui.available_width().max(100.0);
+    let (rect, response) =
+        ui.allocate_exact_size(egui::vec2(width, CHART_HEIGHT), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+    let max = stats.histogram.iter().copied().max().unwrap_or(0);
+    let bar_width = rect.width() / stats.histogram.len() as f32;
+    for (i, &count) in stats.histogram.iter().enumerate() {
+        let height = bar_fraction(count, max) * rect.height();
+        if height <= 0.0 {
+            continue;
+        }
+        let left = rect.left() + i as f32 * bar_width;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left, rect.bottom() - height),
+            egui::pos2(left + bar_width.max(1.0), rect.bottom()),
+        );
+        painter.rect_filled(bar, 0.0, egui::Color32::from_rgb(90, 160, 230));
+    }
+    painter.vline(
+        rect.center().x,
+        rect.y_range(),
+        egui::Stroke::new(1.0, egui::Color32::GRAY),
+    );
+
+    if let Some(pos) = response.hover_pos() {
+        let bin = (((pos.x - rect.left()) / bar_width) as usize).min(stats.histogram.len() - 1);
+        let low = bin as f32 / stats.histogram.len() as f32 * 2.0 - 1.0;
+        let high = low + 2.0 / stats.histogram.len() as f32;
+        response.on_hover_text(format!(
+            "{low:+.3} to {high:+.3}: {} samples",
+            stats.histogram[bin]
+        ));
+    }
+    ui.horizontal(|ui| {
+        ui.small("-1.0");
+        ui.add_space((width - 60.0).max(0.0));
+        ui.small("+1.0");
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bars_use_a_square_root_scale() {
+        assert_eq!(bar_fraction(0, 0), 0.0);
+        assert_eq!(bar_fraction(100, 100), 1.0);
+        assert_eq!(bar_fraction(25, 100), 0.5);
+        assert_eq!(bar_fraction(0, 100), 0.0);
+    }
+}
@@ -0,0 +1,149 @@
+//! Per-channel MIDI strips for the MIDI testing tab: one row per channel with its on switch,
+//! transpose, velocity scale and offset, and the controllers it drops.
+
+use eframe::egui;
+use vst3_host::{BitSet128, ChannelStrip, MidiChannel};
+
+/// Channels shown, one row each.
+const CHANNELS: usize = 16;
+
+/// Parse a comma-separated list of controllers and ranges (`"1, 64-67"`), or `None` if any
+/// entry isn't a controller number (0–127) or a range of them.
+pub fn parse_cc_list(text: &str) -> Option<BitSet128> {
+    let mut set = BitSet128::new();
+    for part in text.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (start.trim().parse().ok()?, end.trim().parse().ok()?),
+            None => {
+                let cc = part.parse().ok()?;
+                (cc, cc)
+            }
+        };
+        if start > end || end > 127u8 {
+            return None;
+        }
+        for cc in start..=end {
+            set.set(cc, true);
+        }
+    }
+    Some(set)
+}
+
+/// UI state for the panel: every channel's strip and the CC filter text being edited.
+pub struct ChannelStripPanel {
+    strips: [ChannelStrip; CHANNELS],
+    cc_text: [String; CHANNELS],
+}
+
+impl Default for ChannelStripPanel {
+    fn default() -> Self {
+        Self {
+            strips: std::array::from_fn(|i| {
+                ChannelStrip::new(MidiChannel::from_index(i as u8).unwrap_or(MidiChannel::Ch1))
+            }),
+            cc_text: Default::default(),
+        }
+    }
+}
+
+impl ChannelStripPanel {
+    /// Every channel's strip, including pass-through ones.
+    pub fn strips(&self) -> &[ChannelStrip] {
+        &self.strips
+    }
+
+    /// Draw the panel. Returns the strips edited this frame.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Vec<ChannelStrip> {
+        let mut changed = Vec::new();
+        if ui
+            .button("Reset all")
+            .on_hover_text("Pass every channel through unchanged")
+            .clicked()
+        {
+            *self = Self::default();
+            changed.extend(self.strips);
+        }
+        egui::ScrollArea::vertical()
+            .id_salt("channel_strip_scroll")
+            .max_height(260.0)
+            .show(ui, |ui| {
+                egui::Grid::new("channel_strip_grid")
+                    .num_columns(6)
+                    .spacing([10.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Channel");
+                        ui.strong("On");
+                        ui.strong("Transpose");
+                        ui.strong("Vel ×");
+                        ui.strong("Vel +");
+                        ui.strong("Drop CCs");
+                        ui.end_row();
+
+                        for (strip, cc_text) in self.strips.iter_mut().zip(&mut self.cc_text) {
+                            if show_row(ui, strip, cc_text) {
+                                changed.push(*strip);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        changed
+    }
+}
+
+/// One channel's controls. Returns whether the strip changed.
+fn show_row(ui: &mut egui::Ui, strip: &mut ChannelStrip, cc_text: &mut String) -> bool {
+    let mut changed = false;
+    ui.label(strip.channel.to_string());
+    changed |= ui.checkbox(&mut strip.enabled, "").changed();
+    changed |= ui
+        .add(
+            egui::DragValue::new(&mut strip.transpose)
+                .range(-48..=48)
+                .suffix(" st"),
+        )
+        .on_hover_text("Change only while no notes are held on this channel")
+        .changed();
+    changed |= ui
+        .add(egui::Slider::new(&mut strip.velocity_scale, 0.0..=2.0).fixed_decimals(2))
+        .changed();
+    changed |= ui
+        .add(egui::DragValue::new(&mut strip.velocity_offset).range(-127..=127))
+        .changed();
+
+    let valid = parse_cc_list(cc_text);
+    let mut edit = egui::TextEdit::singleline(cc_text)
+        .hint_text("e.g. 1, 64-67")
+        .desired_width(110.0);
+    if valid.is_none() {
+        edit = edit.text_color(ui.visuals().error_fg_color);
+    }
+    if ui.add(edit).changed() {
+        if let Some(set) = parse_cc_list(cc_text) {
+            changed |= set != strip.cc_filter;
+            strip.cc_filter = set;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cc_lists_parse() {
+        let set = parse_cc_list("1, 64-67,7").expect("valid list");
+        assert!(set.contains(1) && set.contains(7) && set.contains(66));
+        assert_eq!(set.len(), 6);
+        assert_eq!(parse_cc_list(""), Some(BitSet128::new()));
+    }
+
+    #[test]
+    fn bad_cc_lists_are_rejected() {
+        assert_eq!(parse_cc_list("128"), None);
+        assert_eq!(parse_cc_list("10-5"), None);
+        assert_eq!(parse_cc_list("sustain"), None);
+    }
+}
@@ -0,0 +1,183 @@
+//! A color-map view of a parameter sweep's frequency response: parameter value across, log
+//! frequency up, magnitude as color.
+
+use eframe::egui;
+use vst3_host::FrequencyResponsePoint;
+
+/// Magnitude shown at the bottom of the color scale.
+const FLOOR_DB: f32 = -60.0;
+/// Magnitude shown at the top of the color scale.
+const CEILING_DB: f32 = 12.0;
+/// Lowest frequency drawn.
+const MIN_HZ: f32 = 20.0;
+/// Frequency rows in the map.
+const ROWS: usize = 96;
+const MAP_HEIGHT: f32 = 200.0;
+
+/// The sweep settings and the last measured response.
+pub struct FreqResponseView {
+    /// The parameter to sweep
+    pub param_id: Option<u32>,
+    /// How many parameter values to measure
+    pub steps: usize,
+    points: Vec<FrequencyResponsePoint>,
+    bin_hz: Vec<f32>,
+}
+
+impl Default for FreqResponseView {
+    fn default() -> Self {
+        Self {
+            param_id: None,
+            steps: 16,
+            points: Vec::new(),
+            bin_hz: Vec::new(),
+        }
+    }
+}
+
+impl FreqResponseView {
+    /// Replace the displayed response. `bin_hz` is the center frequency of each magnitude bin.
+    pub fn set_response(&mut self, points: Vec<FrequencyResponsePoint>, bin_hz: Vec<f32>) {
+        self.points = points;
+        self.bin_hz = bin_hz;
+    }
+
+    /// Draw the sweep controls for `params` (as `(id, title)`) and the color map. Returns
+    /// `true` when a new sweep was requested.
+    pub fn show(&mut self, ui: &mut egui::Ui, params: &[(u32, String)]) -> bool {
+        let mut run = false;
+        ui.horizontal(|ui| {
+            let selected = self
+                .param_id
+                .and_then(|id| params.iter().find(|(p, _)| *p == id))
+                .map_or("Select parameter", |(_, title)| title.as_str());
+            egui::ComboBox::from_id_salt("freq_sweep_param")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (id, title) in params {
+                        ui.selectable_value(&mut self.param_id, Some(*id), title);
+                    }
+                });
+            ui.add(
+                egui::DragValue::new(&mut self.steps)
+                    .range(2..=128)
+                    .suffix(" steps"),
+            );
+            run = ui
+                .add_enabled(self.param_id.is_some(), egui::Button::new("Run Sweep"))
+                .on_hover_text("Audio output pauses while the sweep runs")
+                .clicked();
+        });
+        if !self.points.is_empty() {
+            self.show_map(ui);
+        }
+        run
+    }
+
+    fn show_map(&self, ui: &mut egui::Ui) {
+        let nyquist = self.bin_hz.last().copied().unwrap_or(0.0);
+        if nyquist <= MIN_HZ {
+            return;
+        }
+        let width = ui.available_width().max(100.0);
+        let (rect, response) =
+            ui.allocate_exact_size(egui::vec2(width, MAP_HEIGHT), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let column_width = rect.width() / self.points.len() as f32;
+        let row_height = rect.height() / ROWS as f32;
+        for (column, point) in self.points.iter().enumerate() {
+            for row in 0..ROWS {
+                let hz = row_frequency(row, nyquist);
+                let db = self.magnitude_at(point, hz);
+                let min = egui::pos2(
+                    rect.left() + column as f32 * column_width,
+                    rect.bottom() - (row + 1) as f32 * row_height,
+                );
+                let cell = egui::Rect::from_min_size(min, egui::vec2(column_width, row_height))
+                    .expand(0.5);
+                painter.rect_filled(cell, 0.0, db_color(db));
+            }
+        }
+
+        if let Some(pos) = response.hover_pos() {
+            let column =
+                (((pos.x - rect.left()) / column_width) as usize).min(self.points.len() - 1);
+            let row = (((rect.bottom() - pos.y) / row_height) as usize).min(ROWS - 1);
+            let point = &self.points[column];
+            let hz = row_frequency(row, nyquist);
+            response.on_hover_text(format!(
+                "{}: {:.1} dB at {hz:.0} Hz",
+                point.display_value,
+                self.magnitude_at(point, hz)
+            ));
+        }
+
+        ui.horizontal(|ui| {
+            ui.small(format!("{MIN_HZ:.0} Hz \u{2013} {nyquist:.0} Hz (log), "));
+            ui.small(format!("{FLOOR_DB:.0} dB"));
+            let (bar, _) = ui.allocate_exact_size(egui::vec2(120.0, 10.0), egui::Sense::hover());
+            let steps = 24;
+            for i in 0..steps {
+                let t = i as f32 / steps as f32;
+                let x = bar.left() + t * bar.width();
+                let slice = egui::Rect::from_min_max(
+                    egui::pos2(x, bar.top()),
+                    egui::pos2(x + bar.width() / steps as f32 + 0.5, bar.bottom()),
+                );
+                ui.painter().rect_filled(
+                    slice,
+                    0.0,
+                    db_color(FLOOR_DB + t * (CEILING_DB - FLOOR_DB)),
+                );
+            }
+            ui.small(format!("+{CEILING_DB:.0} dB"));
+        });
+    }
+
+    /// The magnitude of the bin nearest `hz`.
+    fn magnitude_at(&self, point: &FrequencyResponsePoint, hz: f32) -> f32 {
+        let spacing = self.bin_hz.get(1).copied().unwrap_or(1.0).max(f32::EPSILON);
+        let bin = ((hz / spacing).round() as usize).min(point.magnitude_db.len().saturating_sub(1));
+        point.magnitude_db.get(bin).copied().unwrap_or(FLOOR_DB)
+    }
+}
+
+/// The frequency at the center of `row`, log-spaced from [`MIN_HZ`] to `nyquist`.
+fn row_frequency(row: usize, nyquist: f32) -> f32 {
+    let t = (row as f32 + 0.5) / ROWS as f32;
+    MIN_HZ * (nyquist / MIN_HZ).powf(t)
+}
+
+/// Map a magnitude onto a blue → green → yellow → red scale between [`FLOOR_DB`] and
+/// [`CEILING_DB`].
+fn db_color(db: f32) -> egui::Color32 {
+    const STOPS: [(u8, u8, u8); 4] = [(20, 30, 120), (40, 180, 90), (240, 220, 60), (230, 40, 30)];
+    let t = ((db - FLOOR_DB) / (CEILING_DB - FLOOR_DB)).clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+    let i = (t as usize).min(STOPS.len() - 2);
+    let f = t - i as f32;
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * f).round() as u8;
+    let (a, b) = (STOPS[i], STOPS[i + 1]);
+    egui::Color32::from_rgb(lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_scale_spans_blue_to_red_and_clamps() {
+        assert_eq!(db_color(FLOOR_DB), egui::Color32::from_rgb(20, 30, 120));
+        assert_eq!(db_color(-200.0), db_color(FLOOR_DB));
+        assert_eq!(db_color(CEILING_DB), egui::Color32::from_rgb(230, 40, 30));
+        assert_eq!(db_color(100.0), db_color(CEILING_DB));
+    }
+
+    #[test]
+    fn rows_cover_the_audible_range_in_order() {
+        let first = row_frequency(0, 24000.0);
+        let last = row_frequency(ROWS - 1, 24000.0);
+        assert!(first > MIN_HZ && first < 25.0);
+        assert!(last < 24000.0 && last > 20000.0);
+        assert!((1..ROWS).all(|r| row_frequency(r, 24000.0) > row_frequency(r - 1, 24000.0)));
+    }
+}
@@ -0,0 +1,148 @@
+//! The General MIDI channel strip: one row per channel of a `GmBus` with its name, program,
+//! volume, mute/solo switches and a level meter.
+
+use eframe::egui;
+use vst3_host::{GmBus, GM_CHANNELS};
+
+/// Bottom of the meter scale, in dBFS.
+const METER_FLOOR_DB: f32 = -60.0;
+/// Per-frame decay of the displayed meter level.
+const METER_FALLOFF: f32 = 0.85;
+
+/// Position (0..=1) of a linear peak `level` on the meter's dB scale.
+pub fn meter_fraction(level: f32) -> f32 {
+    if level <= 0.0 {
+        return 0.0;
+    }
+    let db = 20.0 * level.log10();
+    ((db - METER_FLOOR_DB) / -METER_FLOOR_DB).clamp(0.0, 1.0)
+}
+
+/// Draw a horizontal meter bar `size` wide and tall, filled to `level` (linear) in `color`.
+fn meter_bar(ui: &mut egui::Ui, size: egui::Vec2, level: f32, color: egui::Color32) {
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter();
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+    let mut filled = rect;
+    filled.set_width(rect.width() * meter_fraction(level));
+    painter.rect_filled(filled, 2.0, color);
+}
+
+/// UI state for the strip: the program last sent to each channel and the meters' falloff.
+pub struct GeneralMidiChannelStrip {
+    programs: [u8; GM_CHANNELS],
+    meters: [f32; GM_CHANNELS],
+    /// The mixed output's left/right meters.
+    output: [f32; 2],
+}
+
+impl Default for GeneralMidiChannelStrip {
+    fn default() -> Self {
+        Self {
+            programs: [0; GM_CHANNELS],
+            meters: [0.0; GM_CHANNELS],
+            output: [0.0; 2],
+        }
+    }
+}
+
+impl GeneralMidiChannelStrip {
+    /// Draw the mixed output's left/right meters from `peak`, as read lock-free with
+    /// `GmAudioHandle::output_peak`.
+    pub fn show_output(&mut self, ui: &mut egui::Ui, peak: (f32, f32)) {
+        ui.horizontal(|ui| {
+            ui.label("Output");
+            ui.vertical(|ui| {
+                ui.spacing_mut().item_spacing.y = 2.0;
+                for (meter, level) in self.output.iter_mut().zip([peak.0, peak.1]) {
+                    *meter = level.max(*meter * METER_FALLOFF);
+                    let color = if *meter >= 1.0 {
+                        egui::Color32::RED
+                    } else {
+                        egui::Color32::from_rgb(90, 200, 120)
+                    };
+                    meter_bar(ui, egui::vec2(200.0, 6.0), *meter, color);
+                }
+            });
+        });
+    }
+
+    /// Draw the strip for `bus` and apply this frame's edits. Returns an error message if a
+    /// program change could not be sent.
+    pub fn show(&mut self, ui: &mut egui::Ui, bus: &mut GmBus) -> Option<String> {
+        let mut error = None;
+        let levels = bus.levels();
+        egui::Grid::new("gm_channel_strip")
+            .num_columns(6)
+            .spacing([10.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Channel");
+                ui.strong("Program");
+                ui.strong("Volume");
+                ui.strong("Mute");
+                ui.strong("Solo");
+                ui.strong("Level");
+                ui.end_row();
+
+                for ch in 0..bus.channel_count().min(GM_CHANNELS) {
+                    let index = ch as u8;
+                    ui.label(bus.name(index).unwrap_or_default());
+
+                    let program = &mut self.programs[ch];
+                    if ui
+                        .add(egui::DragValue::new(program).range(0..=127))
+                        .on_hover_text("Program change sent to this channel's instance")
+                        .changed()
+                    {
+                        if let Err(e) = bus.set_program(index, *program) {
+                            error = Some(format!("Program change failed: {e}"));
+                        }
+                    }
+
+                    let mix = bus.mix_bus_mut();
+                    let mut gain = mix.gain(ch);
+                    if ui
+                        .add(egui::Slider::new(&mut gain, 0.0..=1.5).show_value(false))
+                        .changed()
+                    {
+                        mix.set_gain(ch, gain);
+                    }
+                    let mut muted = mix.is_muted(ch);
+                    if ui.toggle_value(&mut muted, "M").changed() {
+                        mix.set_muted(ch, muted);
+                    }
+                    let mut soloed = mix.is_soloed(ch);
+                    if ui.toggle_value(&mut soloed, "S").changed() {
+                        mix.set_soloed(ch, soloed);
+                    }
+
+                    let level = levels.get(ch).copied().unwrap_or(0.0);
+                    self.meters[ch] = level.max(self.meters[ch] * METER_FALLOFF);
+                    let color = if self.meters[ch] >= 1.0 {
+                        egui::Color32::RED
+                    } else if mix.is_audible(ch) {
+                        egui::Color32::from_rgb(90, 200, 120)
+                    } else {
+                        egui::Color32::GRAY
+                    };
+                    meter_bar(ui, egui::vec2(100.0, 10.0), self.meters[ch], color);
+                    ui.end_row();
+                }
+            });
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meter_maps_full_scale_and_floor() {
+        assert_eq!(meter_fraction(1.0), 1.0);
+        assert_eq!(meter_fraction(0.0), 0.0);
+        assert_eq!(meter_fraction(0.0001), 0.0); // -80 dB, below the floor
+        assert!((meter_fraction(0.1) - 2.0 / 3.0).abs() < 1e-5); // -20 dB
+    }
+}
@@ -0,0 +1,125 @@
+//! Play the plugin from the computer keyboard, DAW-style: the home row is the white keys and
+//! the row above it the black keys (`A` = C, `W` = C#, `S` = D, … `K` = the next C).
+//!
+//! Unshifted, `A` is MIDI 60 — middle C, labelled "C3" by this app's note naming (C3 = 60) and
+//! "C4" in scientific pitch notation. Each octave shift moves the whole layout 12 semitones.
+
+use eframe::egui::Key;
+use std::collections::HashMap;
+
+/// Lowest / highest octave shift offered (keeps the layout mostly inside the MIDI range).
+pub const MIN_OCTAVE_SHIFT: i8 = -4;
+pub const MAX_OCTAVE_SHIFT: i8 = 4;
+
+/// Key that shifts the layout down / up an octave.
+pub const OCTAVE_DOWN_KEY: Key = Key::Z;
+pub const OCTAVE_UP_KEY: Key = Key::X;
+
+/// Note the `A` key plays with no octave shift.
+const BASE_NOTE: i32 = 60;
+
+/// The piano layout as (key, semitones above the `A` key's C).
+const QWERTY_PIANO: [(Key, i32); 15] = [
+    (Key::A, 0),
+    (Key::W, 1),
+    (Key::S, 2),
+    (Key::E, 3),
+    (Key::D, 4),
+    (Key::F, 5),
+    (Key::T, 6),
+    (Key::G, 7),
+    (Key::Y, 8),
+    (Key::H, 9),
+    (Key::U, 10),
+    (Key::J, 11),
+    (Key::K, 12),
+    (Key::O, 13),
+    (Key::L, 14),
+];
+
+/// Which computer key plays which MIDI note.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyboardMapping {
+    /// egui key → MIDI note
+    pub keys: HashMap<Key, u8>,
+    octave_shift: i8,
+}
+
+impl KeyboardMapping {
+    /// The standard piano layout shifted by `octave_shift` octaves (clamped to
+    /// [`MIN_OCTAVE_SHIFT`]..=[`MAX_OCTAVE_SHIFT`]). Keys that would fall outside the MIDI
+    /// note range are left unmapped.
+    pub fn qwerty_piano(octave_shift: i8) -> Self {
+        let octave_shift = octave_shift.clamp(MIN_OCTAVE_SHIFT, MAX_OCTAVE_SHIFT);
+        let base = BASE_NOTE + 12 * octave_shift as i32;
+        let keys = QWERTY_PIANO
+            .iter()
+            .filter_map(|&(key, offset)| u8::try_from(base + offset).ok().map(|n| (key, n)))
+            .filter(|&(_, note)| note <= 127)
+            .collect();
+        Self { keys, octave_shift }
+    }
+
+    /// The octave shift this layout was built with.
+    pub fn octave_shift(&self) -> i8 {
+        self.octave_shift
+    }
+
+    /// The note `key` plays, if it is mapped.
+    pub fn note_for(&self, key: Key) -> Option<u8> {
+        self.keys.get(&key).copied()
+    }
+
+    /// The key that plays `note`, if any (for labelling the on-screen piano).
+    pub fn key_for(&self, note: u8) -> Option<Key> {
+        self.keys
+            .iter()
+            .find(|(_, &n)| n == note)
+            .map(|(&key, _)| key)
+    }
+}
+
+impl Default for KeyboardMapping {
+    fn default() -> Self {
+        Self::qwerty_piano(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_key_plays_c_in_every_octave_shift() {
+        for shift in MIN_OCTAVE_SHIFT..=MAX_OCTAVE_SHIFT {
+            let mapping = KeyboardMapping::qwerty_piano(shift);
+            let expected = (60 + 12 * shift as i32) as u8;
+            assert_eq!(mapping.note_for(Key::A), Some(expected), "shift {shift}");
+            assert_eq!(mapping.key_for(expected), Some(Key::A));
+        }
+    }
+
+    #[test]
+    fn layout_follows_the_piano() {
+        let mapping = KeyboardMapping::qwerty_piano(0);
+        assert_eq!(mapping.note_for(Key::W), Some(61)); // C#
+        assert_eq!(mapping.note_for(Key::S), Some(62)); // D
+        assert_eq!(mapping.note_for(Key::K), Some(72)); // next C
+
+        // The octave keys never play notes.
+        assert_eq!(mapping.note_for(OCTAVE_DOWN_KEY), None);
+        assert_eq!(mapping.note_for(OCTAVE_UP_KEY), None);
+    }
+
+    #[test]
+    fn octave_shift_is_clamped() {
+        assert_eq!(
+            KeyboardMapping::qwerty_piano(9).octave_shift(),
+            MAX_OCTAVE_SHIFT
+        );
+        assert_eq!(
+            KeyboardMapping::qwerty_piano(-9),
+            KeyboardMapping::qwerty_piano(-4)
+        );
+    }
+}
@@ -3,7 +3,7 @@
 
 use eframe::egui;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
@@ -13,17 +13,93 @@ use vst3_host::{AudioHandle, PeakMeter, Vst3Host};
 
 // Import modules
 mod automation;
+mod block_stats_view;
+mod channel_strip_panel;
 mod data_structures;
+mod freq_response_view;
+mod gm_strip;
+mod keyboard_input;
+mod midi_bindings;
 mod midi_input;
 mod midi_player;
+mod mix_bus_view;
+mod param_sort;
+mod param_tooltip;
+mod param_widget;
+mod plugin_browser;
+mod plugin_graph;
+mod routing_matrix_editor;
+mod spectrum_view;
+mod split_editor;
+mod timing_view;
+mod undo_history_view;
+mod velocity_curve_editor;
+mod voice_monitor;
+mod wheel_strips;
 
 use automation::{AutomationState, Shape};
+use channel_strip_panel::ChannelStripPanel;
+use freq_response_view::FreqResponseView;
+use gm_strip::GeneralMidiChannelStrip;
+use keyboard_input::KeyboardMapping;
+use midi_bindings::MidiBindingEditor;
 use midi_input::MidiInputState;
 use midi_player::MidiFilePlayer;
+use mix_bus_view::MixBusView;
+use param_sort::{ParameterSort, SortColumn};
+use plugin_graph::GraphEditor;
+
+/// Input selector entry for the OS default input device.
+const SYSTEM_DEFAULT_INPUT: &str = "System default";
+
+/// Play `plugin` with live audio input from the input device named `input` (or the OS
+/// default for [`SYSTEM_DEFAULT_INPUT`]), for effects.
+fn play_with_live_input(
+    host: &Vst3Host,
+    plugin: vst3_host::Plugin,
+    input: &str,
+) -> vst3_host::Result<AudioHandle> {
+    let mut backend = vst3_host::backends::CpalBackend::new()?;
+    if let Some(output) = host.audio_device() {
+        let device = backend.find_output_device(output).ok_or_else(|| {
+            vst3_host::Error::AudioBackendError(format!("Output device not found: {output}"))
+        })?;
+        backend = backend.with_output_device(device);
+    }
+    if input != SYSTEM_DEFAULT_INPUT {
+        let device = backend.find_input_device(input).ok_or_else(|| {
+            vst3_host::Error::AudioBackendError(format!("Input device not found: {input}"))
+        })?;
+        backend = backend.with_input_device(device);
+    }
+    let config = vst3_host::AudioConfig {
+        input_channels: 2,
+        output_channels: 2,
+        ..*host.config()
+    };
+    vst3_host::play_with_input_monitored(&backend, plugin, config, host.stream_monitor())
+}
+
+/// One-line description of an output device's capabilities for its selector tooltip.
+fn device_summary(device: &vst3_host::AudioDeviceInfo) -> String {
+    let rates: Vec<String> = device
+        .sample_rates
+        .iter()
+        .map(|rate| format!("{}", *rate as u32))
+        .collect();
+    let mut summary = format!("{} channels, {} Hz", device.max_channels, rates.join("/"));
+    if device.max_buffer_size > 0 {
+        summary.push_str(&format!(
+            ", buffers {}\u{2013}{}",
+            device.min_buffer_size, device.max_buffer_size
+        ));
+    }
+    summary
+}
 
-/// Scan for installed VST3 plugin paths via the `vst3-host` library (lightweight —
-/// lists `.vst3` bundles without loading them).
-fn discover_vst3_paths(custom_paths: &[String]) -> Vec<String> {
+/// Scan for installed VST3 and CLAP plugin paths via the `vst3-host` library (lightweight —
+/// lists `.vst3` and `.clap` bundles without loading them).
+fn discover_plugin_paths(custom_paths: &[String]) -> Vec<String> {
     let mut builder = vst3_host::Vst3Host::builder().scan_default_paths();
     for p in custom_paths {
         builder = builder.add_scan_path(p);
@@ -35,6 +111,7 @@ fn discover_vst3_paths(custom_paths: &[String]) -> Vec<String> {
     let mut paths: Vec<String> = host
         .scan_plugin_paths()
         .into_iter()
+        .chain(host.scan_clap_plugin_paths())
         .map(|p| p.to_string_lossy().to_string())
         .collect();
     paths.sort();
@@ -53,10 +130,574 @@ fn note_name_to_midi(name: &str) -> Option<u8> {
     vst3_host::midi::name_to_note(name)
 }
 
+/// The plugin to load from files dropped onto the window: the first dropped path, if it is a
+/// `.vst3` bundle (file or folder) or a folder with a VST3 bundle's binary layout. `None` when
+/// nothing with a path was dropped; `Err` explains why the drop was rejected.
+fn dropped_plugin_path(files: &[egui::DroppedFile]) -> Option<Result<String, String>> {
+    let path = files.iter().find_map(|f| f.path.as_deref())?;
+    let is_vst3 = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("vst3"));
+    if !is_vst3 && !path.is_dir() {
+        return Some(Err(format!("Not a VST3 plugin: {}", path.display())));
+    }
+    Some(
+        vst3_host::discovery::get_vst3_binary_path(path)
+            .map(|_| path.to_string_lossy().into_owned())
+            .map_err(|_| format!("Not a valid VST3 bundle: {}", path.display())),
+    )
+}
+
+/// A distribution's name with its setting, e.g. "Gaussian \u{00B1}10%".
+fn distribution_label(distribution: &vst3_host::Distribution) -> String {
+    match distribution {
+        vst3_host::Distribution::Uniform => "Uniform".to_string(),
+        vst3_host::Distribution::Gaussian { sigma } => {
+            format!("Gaussian \u{00B1}{:.0}%", sigma * 100.0)
+        }
+        vst3_host::Distribution::Bimodal { weight } => {
+            format!("Bimodal ({:.0}% high)", weight * 100.0)
+        }
+    }
+}
+
+/// The library event behind a MIDI monitor entry (see `log_midi_event` for the type codes).
+fn monitor_event_to_midi(
+    event_type: u16,
+    channel: u8,
+    data1: u8,
+    data2: u8,
+) -> Option<vst3_host::MidiEvent> {
+    let status = match event_type {
+        0 => 0x90,
+        1 => 0x80,
+        2 => 0xA0,
+        3 => 0xB0,
+        4 => 0xC0,
+        5 => 0xD0,
+        6 => 0xE0,
+        _ => return None,
+    };
+    vst3_host::MidiEvent::from_midi_bytes(&[status | (channel & 0x0F), data1, data2])
+}
+
+/// The status line after a "Copy from…": how many values were copied, skipped and failed.
+fn copy_report_summary(report: &vst3_host::CopyReport) -> String {
+    format!(
+        "Copied {} parameter(s) ({} skipped, {} failed)",
+        report.copied, report.skipped, report.failed
+    )
+}
+
+/// Whether a parameter looks like a rate/tempo control that a tempo-synced note value can drive.
+/// Where the next node added to `graph` goes: a loose cascade, so new nodes don't stack exactly.
+fn next_node_position(graph: &vst3_host::AudioGraph) -> (f32, f32) {
+    let n = graph.nodes.len() as f32;
+    (20.0 + (n * 170.0) % 680.0, 20.0 + (n / 4.0).floor() * 90.0)
+}
+
+/// Why the category scan skipped a plugin, for the Scan Results list.
+fn scan_failure_reason(skip: &vst3_host::SafeDiscoverySkip) -> String {
+    match skip {
+        vst3_host::SafeDiscoverySkip::Crashed { detail, .. } => format!("crashed ({detail})"),
+        vst3_host::SafeDiscoverySkip::TimedOut { .. } => "timed out".to_string(),
+        vst3_host::SafeDiscoverySkip::Failed { detail, .. } => detail.clone(),
+    }
+}
+
+fn is_tempo_syncable(title: &str) -> bool {
+    let title = title.to_lowercase();
+    title.contains("rate") || title.contains("tempo")
+}
+
+/// The curve the slider for `param_id` follows: the detected one, else linear.
+fn slider_curve(
+    curves: &HashMap<u32, vst3_host::SliderCurve>,
+    param_id: u32,
+) -> &vst3_host::SliderCurve {
+    static LINEAR: vst3_host::SliderCurve = vst3_host::SliderCurve::Linear;
+    curves.get(&param_id).unwrap_or(&LINEAR)
+}
+
+/// A table of the editor's keyboard shortcuts, or a pointer to the documentation when the
+/// plugin reports none.
+fn shortcuts_table(ui: &mut egui::Ui, shortcuts: &[vst3_host::KeyShortcut]) {
+    if shortcuts.is_empty() {
+        ui.weak("See plugin documentation for keyboard shortcuts");
+        return;
+    }
+    egui::Grid::new(ui.next_auto_id())
+        .num_columns(2)
+        .spacing([16.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for shortcut in shortcuts {
+                ui.label(egui::RichText::new(shortcut.label()).monospace());
+                ui.label(&shortcut.description);
+                ui.end_row();
+            }
+        });
+}
+
+/// What the Bus Presets controls asked for, by preset name.
+enum BusPresetAction {
+    Apply(String),
+    Save(String),
+    Delete(String),
+}
+
+/// The Component Information panel's Bus Presets: a dropdown applying a saved preset, and a
+/// name field saving the current buses as one.
+fn bus_presets_ui(
+    ui: &mut egui::Ui,
+    presets: &[(String, vst3_host::BusConfiguration)],
+    selected: Option<&str>,
+    new_name: &mut String,
+) -> Option<BusPresetAction> {
+    let mut action = None;
+    ui.strong("Bus Presets");
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt("bus_presets_combo")
+            .selected_text(selected.unwrap_or("(none)"))
+            .show_ui(ui, |ui| {
+                if presets.is_empty() {
+                    ui.weak("No presets saved for this plugin");
+                }
+                for (name, _) in presets {
+                    if ui
+                        .selectable_label(selected == Some(name.as_str()), name)
+                        .clicked()
+                    {
+                        action = Some(BusPresetAction::Apply(name.clone()));
+                    }
+                }
+            });
+        if let Some(name) = selected {
+            if ui.small_button("Delete").clicked() {
+                action = Some(BusPresetAction::Delete(name.to_string()));
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(new_name)
+                .hint_text("Preset name")
+                .desired_width(140.0),
+        );
+        let name = new_name.trim();
+        if ui
+            .add_enabled(!name.is_empty(), egui::Button::new("Save Current"))
+            .on_hover_text("Save which buses are active and their speaker arrangements")
+            .clicked()
+        {
+            action = Some(BusPresetAction::Save(name.to_string()));
+        }
+    });
+    action
+}
+
+/// The header's line for a crashed isolation helper, with its report (and backtrace) folded
+/// underneath. Returns whether it was dismissed.
+fn crash_report_header(
+    ui: &mut egui::Ui,
+    report: &vst3_host::process_isolation::CrashReport,
+) -> bool {
+    let mut dismissed = false;
+    ui.horizontal(|ui| {
+        ui.colored_label(
+            egui::Color32::RED,
+            format!("\u{1F4A5} Plugin helper crashed: {report}"),
+        );
+        dismissed = ui.small_button("Dismiss").clicked();
+    });
+    egui::CollapsingHeader::new("Crash report")
+        .id_salt("helper_crash_report")
+        .show(ui, |ui| {
+            egui::Grid::new("helper_crash_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Plugin:");
+                    ui.label(&report.plugin_path);
+                    ui.end_row();
+                    ui.label("Last command:");
+                    ui.label(&report.last_command);
+                    ui.end_row();
+                    ui.label("Signal:");
+                    ui.label(match report.signal_name() {
+                        Some(name) => format!("{name} ({})", report.signal),
+                        None => report.signal.to_string(),
+                    });
+                    ui.end_row();
+                    ui.label("Time:");
+                    ui.label(format!("{} (Unix seconds)", report.timestamp));
+                    ui.end_row();
+                });
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    ui.label(egui::RichText::new(&report.backtrace).monospace().small());
+                });
+            if ui.button("Copy").clicked() {
+                ui.ctx()
+                    .copy_text(format!("{report}\n\n{}", report.backtrace));
+            }
+        });
+    dismissed
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn monitor_entries_map_back_to_midi_events() {
+        assert_eq!(
+            monitor_event_to_midi(0, 2, 60, 100),
+            Some(vst3_host::MidiEvent::NoteOn {
+                channel: MidiChannel::Ch3,
+                note: 60,
+                velocity: 100,
+            })
+        );
+        assert_eq!(
+            monitor_event_to_midi(6, 0, 0x7F, 0x3F),
+            Some(vst3_host::MidiEvent::PitchBend {
+                channel: MidiChannel::Ch1,
+                value: 0x1FFF,
+            })
+        );
+        assert_eq!(monitor_event_to_midi(9, 0, 0, 0), None);
+    }
+
+    #[test]
+    fn copy_report_summary_counts_every_outcome() {
+        let report = vst3_host::CopyReport {
+            copied: 12,
+            skipped: 3,
+            failed: 1,
+        };
+        assert_eq!(
+            copy_report_summary(&report),
+            "Copied 12 parameter(s) (3 skipped, 1 failed)"
+        );
+    }
+
+    #[test]
+    fn distribution_labels_name_their_setting() {
+        use vst3_host::Distribution;
+        assert_eq!(distribution_label(&Distribution::Uniform), "Uniform");
+        assert_eq!(
+            distribution_label(&Distribution::Gaussian { sigma: 0.1 }),
+            "Gaussian \u{00B1}10%"
+        );
+        assert_eq!(
+            distribution_label(&Distribution::Bimodal { weight: 0.25 }),
+            "Bimodal (25% high)"
+        );
+    }
+
+    #[test]
+    fn only_auto_start_loads_the_last_plugin_at_startup() {
+        let existing = env!("CARGO_MANIFEST_DIR").to_string();
+        let mut prefs = Preferences {
+            last_loaded_plugin: Some(existing.clone()),
+            auto_start_processing: false,
+            ..Preferences::default()
+        };
+        assert_eq!(prefs.startup_plugin(), Ok(None));
+        assert_eq!(prefs.last_loaded_plugin.as_deref(), Some(existing.as_str()));
+
+        prefs.auto_start_processing = true;
+        assert_eq!(prefs.startup_plugin(), Ok(Some(existing)));
+
+        prefs.last_loaded_plugin = Some("/nonexistent/Gone.vst3".into());
+        assert!(prefs.startup_plugin().is_err());
+        assert_eq!(prefs.last_loaded_plugin, None);
+        assert_eq!(prefs.startup_plugin(), Ok(None));
+    }
+
+    #[test]
+    fn plugin_window_positions_round_trip_through_preferences() {
+        let path =
+            std::env::temp_dir().join(format!("vst3-inspector-prefs-{}.json", std::process::id()));
+        let geometry = vst3_host::WindowGeometry {
+            x: 120,
+            y: -40,
+            width: 800,
+            height: 600,
+        };
+        let mut prefs = Preferences::default();
+        prefs
+            .plugin_window_positions
+            .insert("/plugins/Dexed.vst3".to_string(), geometry);
+        let behavior = vst3_host::WindowBehavior {
+            always_on_top: true,
+            opacity: 0.6,
+        };
+        prefs
+            .window_behaviors
+            .insert("/plugins/Dexed.vst3".to_string(), behavior);
+        prefs.save_to(&path).unwrap();
+
+        let loaded = Preferences::load_from(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(
+            loaded.plugin_window_positions.get("/plugins/Dexed.vst3"),
+            Some(&geometry)
+        );
+        assert_eq!(
+            loaded.window_behaviors.get("/plugins/Dexed.vst3"),
+            Some(&behavior)
+        );
+    }
+
+    #[test]
+    fn plugin_graph_round_trips_through_preferences() {
+        let path =
+            std::env::temp_dir().join(format!("vst3-inspector-graph-{}.json", std::process::id()));
+        let mut prefs = Preferences::default();
+        let graph = &mut prefs.plugin_graph;
+        let synth = graph.add_node(
+            "/p/synth.vst3",
+            "synth",
+            vst3_host::NodePorts::ALL,
+            (0.0, 0.0),
+        );
+        let fx = graph.add_node("/p/fx.vst3", "fx", vst3_host::NodePorts::ALL, (170.0, 0.0));
+        graph
+            .connect(synth, fx, vst3_host::PortKind::Audio)
+            .unwrap();
+        prefs.save_to(&path).unwrap();
+
+        let loaded = Preferences::load_from(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(loaded.plugin_graph, prefs.plugin_graph);
+        assert_eq!(
+            vst3_host::PluginChain::from_graph(&loaded.plugin_graph)
+                .unwrap()
+                .order(),
+            vec![synth, fx]
+        );
+    }
+
+    #[test]
+    fn plugin_notes_round_trip_through_preferences() {
+        let path =
+            std::env::temp_dir().join(format!("vst3-inspector-notes-{}.json", std::process::id()));
+        let mut prefs = Preferences::default();
+        prefs.plugin_notes.insert(
+            "/vst3/Kick.vst3".to_string(),
+            vst3_host::PluginAnnotation {
+                notes: "license expires 2025-12\nuse for drums only".to_string(),
+                custom_tags: vec!["drums".to_string(), "licensed".to_string()],
+                rating: 4,
+                favorite: true,
+            },
+        );
+        prefs.save_to(&path).unwrap();
+
+        let loaded = Preferences::load_from(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(loaded.plugin_notes, prefs.plugin_notes);
+    }
+
+    #[test]
+    fn preferred_audio_device_round_trips_through_preferences() {
+        let path =
+            std::env::temp_dir().join(format!("vst3-inspector-device-{}.json", std::process::id()));
+        let prefs = Preferences {
+            preferred_audio_device: Some("USB Audio Interface".to_string()),
+            ..Preferences::default()
+        };
+        prefs.save_to(&path).unwrap();
+
+        let loaded = Preferences::load_from(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(loaded.preferred_audio_device, prefs.preferred_audio_device);
+    }
+
+    #[test]
+    fn device_summary_lists_rates_and_buffers() {
+        let device = vst3_host::AudioDeviceInfo {
+            name: "Interface".to_string(),
+            max_channels: 2,
+            sample_rates: vec![44_100.0, 48_000.0],
+            min_buffer_size: 64,
+            max_buffer_size: 1024,
+        };
+        assert_eq!(
+            device_summary(&device),
+            "2 channels, 44100/48000 Hz, buffers 64\u{2013}1024"
+        );
+    }
+
+    #[test]
+    fn preferences_without_window_positions_still_load() {
+        // Config files written before the field existed must keep loading.
+        let prefs: Preferences = serde_json::from_str(r#"{"custom_plugin_paths":["/x"]}"#).unwrap();
+        assert_eq!(prefs.custom_plugin_paths, vec!["/x".to_string()]);
+        assert!(prefs.plugin_window_positions.is_empty());
+    }
+
+    #[test]
+    fn scan_failure_reasons_name_the_cause() {
+        let path = std::path::PathBuf::from("/x/Bad.vst3");
+        let crashed = vst3_host::SafeDiscoverySkip::Crashed {
+            path: path.clone(),
+            detail: "signal 6".to_string(),
+        };
+        assert_eq!(scan_failure_reason(&crashed), "crashed (signal 6)");
+        let timed_out = vst3_host::SafeDiscoverySkip::TimedOut { path };
+        assert_eq!(scan_failure_reason(&timed_out), "timed out");
+    }
+
+    #[test]
+    fn last_session_path_round_trips_through_preferences() {
+        let prefs = Preferences {
+            last_session_path: Some("/sessions/pad.json".to_string()),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&prefs).unwrap();
+        let loaded: Preferences = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            loaded.last_session_path.as_deref(),
+            Some("/sessions/pad.json")
+        );
+        // Older config files have no session to offer.
+        let old: Preferences = serde_json::from_str("{}").unwrap();
+        assert_eq!(old.last_session_path, None);
+    }
+
+    #[test]
+    fn plugin_init_flags_round_trip_through_preferences() {
+        use vst3_host::PluginInitFlags;
+        let flags = PluginInitFlags::SKIP_GUI_CHECK | PluginInitFlags::SKIP_STATE_TRANSFER;
+        let prefs = Preferences {
+            plugin_init_flags: HashMap::from([("WaveShell".to_string(), flags)]),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&prefs).unwrap();
+        let loaded: Preferences = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.plugin_init_flags["WaveShell"], flags);
+        assert!(
+            !loaded.plugin_init_flags["WaveShell"].contains(PluginInitFlags::SKIP_CONTROLLER_INIT)
+        );
+    }
+
+    fn dropped(path: &std::path::Path) -> egui::DroppedFile {
+        egui::DroppedFile {
+            path: Some(path.to_path_buf()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn dropping_a_vst3_file_loads_it() {
+        let dir = std::env::temp_dir().join(format!("vst3-inspector-drop-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let plugin = dir.join("Synth.vst3");
+        std::fs::write(&plugin, b"").unwrap();
+        let other = dir.join("notes.txt");
+        std::fs::write(&other, b"").unwrap();
+
+        let loaded = dropped_plugin_path(&[dropped(&plugin)]);
+        let rejected = dropped_plugin_path(&[dropped(&other)]);
+        let empty_folder = dropped_plugin_path(&[dropped(&dir.join("missing.vst3"))]);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded, Some(Ok(plugin.to_string_lossy().into_owned())));
+        assert!(matches!(rejected, Some(Err(_))));
+        assert!(matches!(empty_folder, Some(Err(_))));
+        // Web-style drops carry no path; there is nothing to load.
+        assert_eq!(dropped_plugin_path(&[egui::DroppedFile::default()]), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn dropping_a_bundle_folder_loads_it() {
+        let dir =
+            std::env::temp_dir().join(format!("vst3-inspector-bundle-{}", std::process::id()));
+        let binary_dir = dir.join("Plain").join("Contents").join("x86_64-linux");
+        std::fs::create_dir_all(&binary_dir).unwrap();
+        std::fs::write(binary_dir.join("Plain.so"), b"").unwrap();
+
+        // No `.vst3` extension, but the bundle layout is there.
+        let bundle = dir.join("Plain");
+        let loaded = dropped_plugin_path(&[dropped(&bundle)]);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(loaded, Some(Ok(bundle.to_string_lossy().into_owned())));
+    }
+
+    #[test]
+    fn tempo_sync_offered_for_rate_and_tempo_params() {
+        assert!(is_tempo_syncable("LFO Rate"));
+        assert!(is_tempo_syncable("Delay TEMPO"));
+        assert!(!is_tempo_syncable("Cutoff"));
+    }
+
+    #[test]
+    fn value_range_filter_finds_near_zero_and_near_maximum_parameters() {
+        let param = |current_value| ParameterInfo {
+            id: 0,
+            title: String::new(),
+            short_title: String::new(),
+            units: String::new(),
+            step_count: 0,
+            default_normalized_value: 0.0,
+            unit_id: 0,
+            flags: 0,
+            current_value,
+            widget: vst3_host::WidgetType::Slider,
+            step_labels: Vec::new(),
+        };
+        let values = [0.0, 0.004, 0.01, 0.5, 0.99, 1.0];
+        let found = |min, max| {
+            let filter = ParameterFilter::InRange { min, max };
+            values
+                .into_iter()
+                .filter(|&v| filter.matches(&param(v)))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found(0.0, 0.01), [0.0, 0.004, 0.01]);
+        assert_eq!(found(0.99, 1.0), [0.99, 1.0]);
+    }
+
+    #[test]
+    fn editor_edits_show_within_two_frames() {
+        let (tx, rx) = std::sync::mpsc::channel::<vst3_host::ParameterChange>();
+        let mut params = vec![ParameterInfo {
+            id: 7,
+            title: "Cutoff".to_string(),
+            short_title: String::new(),
+            units: String::new(),
+            step_count: 0,
+            default_normalized_value: 0.5,
+            unit_id: 0,
+            flags: 0,
+            current_value: 0.5,
+            widget: vst3_host::WidgetType::Slider,
+            step_labels: Vec::new(),
+        }];
+        let frame = |params: &mut Vec<ParameterInfo>| {
+            let changes = rx.try_iter().map(|c| (c.id, c.value)).collect();
+            apply_parameter_changes(params, changes);
+        };
+
+        frame(&mut params);
+        // What the component handler sends on performEdit, between two frames.
+        for value in [0.6, 0.75] {
+            tx.send(vst3_host::ParameterChange {
+                id: 7,
+                value,
+                sample_offset: 0,
+                timestamp: Instant::now(),
+            })
+            .unwrap();
+        }
+        frame(&mut params);
+        assert_eq!(params[0].current_value, 0.75);
+    }
+
     #[test]
     fn ab_slot_label_maps() {
         assert_eq!(ab_slot_label(AbSlot::A), "A");
@@ -110,6 +751,8 @@ struct PluginInfo {
     controller_info: Option<ControllerInfo>,
     has_gui: bool,
     gui_size: Option<(i32, i32)>,
+    // From the host's compatibility database, for the "Known issues" section.
+    known_issues: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -169,6 +812,18 @@ struct ParameterInfo {
     unit_id: i32,
     flags: i32,
     current_value: f64,
+    // Widget the value column draws, and the plugin's labels for each step.
+    widget: vst3_host::WidgetType,
+    step_labels: Vec<String>,
+}
+
+/// Show each changed parameter's new value in `params` (later changes to a parameter win).
+fn apply_parameter_changes(params: &mut [ParameterInfo], changes: Vec<(u32, f64)>) {
+    for (id, value) in changes {
+        if let Some(p) = params.iter_mut().find(|p| p.id == id) {
+            p.current_value = value;
+        }
+    }
 }
 
 /// Headless self-test: drive the `vst3-host` library end to end (discover → introspect
@@ -247,7 +902,7 @@ fn run_selftest(path: &str) -> i32 {
                         json.len(),
                         report.parameters.len(),
                         report.detailed.info.version,
-                        report.detailed.info.category,
+                        report.detailed.info.category(),
                         report.detailed.info.has_midi_input,
                         report.detailed.info.has_midi_output,
                     );
@@ -356,27 +1011,41 @@ fn main() {
 
             // Scan for available plugins
             inspector.discovered_plugins =
-                discover_vst3_paths(&inspector.preferences.custom_plugin_paths);
+                discover_plugin_paths(&inspector.preferences.custom_plugin_paths);
 
-            // Load the default plugin, or fall back to the last-loaded plugin from the
-            // previous session if the default isn't present.
-            let default_path = inspector.plugin_path.clone();
-            let to_load = if std::path::Path::new(&default_path).exists() {
-                Some(default_path)
-            } else {
+            // With auto-start on, pick up the last session's plugin; loading it starts
+            // processing once it's ready (see `poll_pending_load`).
+            let to_load = match inspector.preferences.startup_plugin() {
+                Ok(path) => path,
+                Err(e) => {
+                    if let Err(e) = inspector.preferences.save() {
+                        eprintln!("Failed to save preferences: {e}");
+                    }
+                    inspector.set_error(e);
+                    None
+                }
+            };
+            let crashed_last_time = |path: &String| {
                 inspector
-                    .preferences
-                    .last_loaded_plugin
-                    .clone()
-                    .filter(|p| std::path::Path::new(p).exists())
+                    .interrupted_load
+                    .as_ref()
+                    .is_some_and(|(crashed, _)| *crashed == std::path::Path::new(path))
             };
             match to_load {
+                Some(path) if crashed_last_time(&path) => {
+                    println!("Not auto-loading {path}: it crashed while loading last time");
+                }
                 Some(path) => {
-                    inspector.plugin_path = path.clone();
                     inspector.load_plugin(path);
+                    inspector.startup_loading = true;
                 }
-                None => println!("No default or last-loaded plugin found, none loaded at startup"),
+                None => println!("No plugin loaded at startup"),
             }
+            inspector.offer_session_reload = inspector
+                .preferences
+                .last_session_path
+                .as_ref()
+                .is_some_and(|p| std::path::Path::new(p).exists());
 
             Ok(Box::new(inspector))
         }),
@@ -472,39 +1141,145 @@ struct Preferences {
     // Session state restored on next launch.
     last_tab: Option<Tab>,
     last_midi_channel: Option<i16>,
+    // Where each plugin's editor window was last left, keyed by plugin path.
+    plugin_window_positions: HashMap<String, vst3_host::WindowGeometry>,
+    // Each plugin editor's always-on-top and opacity settings, keyed by plugin path.
+    window_behaviors: HashMap<String, vst3_host::WindowBehavior>,
+    // The Processing tab's plugin routing graph.
+    plugin_graph: vst3_host::AudioGraph,
+    // Octave shift of the computer-keyboard piano (-4..=4).
+    keyboard_octave_shift: i8,
+    // Keyboard split applied to played notes; `None` when the split is off.
+    keyboard_split: Option<vst3_host::KeyboardSplit>,
+    // MIDI → parameter bindings (CC, aftertouch, pitch bend, note), applied to MIDI input.
+    midi_bindings: Vec<vst3_host::MidiBinding>,
+    // Metadata of every plugin loaded or category-scanned so far, keyed by plugin path; files
+    // the Plugins tab's tree by category.
+    plugin_catalog: HashMap<String, vst3_host::PluginInfo>,
+    // Live audio input fed to the plugin (device name or `SYSTEM_DEFAULT_INPUT`); `None` plays
+    // without input.
+    input_device: Option<String>,
+    // The user's notes, tags, rating and favorite mark per plugin path.
+    plugin_notes: HashMap<String, vst3_host::PluginAnnotation>,
+    // Output device to play through; `None` uses the system default.
+    preferred_audio_device: Option<String>,
+    // The session file last saved or loaded, offered for reload on the next launch.
+    last_session_path: Option<String>,
+    // Plugins disabled from the Plugins tab, by their `.vst3.disabled` path.
+    disabled_plugins: Vec<String>,
+    // Initialization steps to skip for crash-prone plugins, by path or name substring.
+    plugin_init_flags: HashMap<String, vst3_host::PluginInitFlags>,
+    // The parameter table's sort order.
+    parameter_sort: ParameterSort,
+    // Named bus configurations per plugin path, in the order they were saved.
+    bus_configs: HashMap<String, Vec<(String, vst3_host::BusConfiguration)>>,
+    // Note-on velocity curve applied to everything played into the plugin.
+    velocity_curve: vst3_host::VelocityCurve,
+    // Named virtual keyboard setups (channel, octave, velocity, arpeggiator, CC bindings).
+    keyboard_presets: Vec<vst3_host::KeyboardPreset>,
 }
 
 impl Preferences {
+    fn config_path() -> Option<std::path::PathBuf> {
+        directories::ProjectDirs::from("com", "vst-host", "vst-host")
+            .map(|dirs| dirs.config_dir().join("preferences.json"))
+    }
+
     fn load() -> Self {
-        if let Some(config_dir) = directories::ProjectDirs::from("com", "vst-host", "vst-host") {
-            let config_path = config_dir.config_dir().join("preferences.json");
-            if let Ok(data) = std::fs::read_to_string(config_path) {
-                if let Ok(prefs) = serde_json::from_str(&data) {
-                    return prefs;
-                }
-            }
-        }
-        Self::default()
+        Self::config_path()
+            .map(|path| Self::load_from(&path))
+            .unwrap_or_default()
+    }
+
+    fn load_from(path: &std::path::Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
     }
 
     fn save(&self) -> Result<(), std::io::Error> {
-        if let Some(config_dir) = directories::ProjectDirs::from("com", "vst-host", "vst-host") {
-            let config_dir = config_dir.config_dir();
-            std::fs::create_dir_all(config_dir)?;
-            let config_path = config_dir.join("preferences.json");
-            let data = serde_json::to_string_pretty(self)?;
-            std::fs::write(config_path, data)?;
+        match Self::config_path() {
+            Some(path) => self.save_to(&path),
+            None => Ok(()),
         }
-        Ok(())
+    }
+
+    fn save_to(&self, path: &std::path::Path) -> Result<(), std::io::Error> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let data = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, data)
+    }
+
+    /// The plugin to load at startup: the last-loaded one, but only with auto-start on. One
+    /// that's no longer at its saved path is forgotten and reported as the error.
+    fn startup_plugin(&mut self) -> Result<Option<String>, String> {
+        if !self.auto_start_processing {
+            return Ok(None);
+        }
+        let Some(path) = self.last_loaded_plugin.clone() else {
+            return Ok(None);
+        };
+        if !std::path::Path::new(&path).exists() {
+            self.last_loaded_plugin = None;
+            return Err(format!(
+                "The last plugin ({path}) is no longer there; starting without a plugin"
+            ));
+        }
+        Ok(Some(path))
     }
 }
 
+/// Where the inspector keeps saved controller presets (next to the preferences).
+fn controller_presets_dir() -> Option<std::path::PathBuf> {
+    directories::ProjectDirs::from("com", "vst-host", "vst-host")
+        .map(|dirs| dirs.config_dir().join("controller_presets"))
+}
+
+/// Where plugin loads record their current phase, so a crash mid-load can be diagnosed on
+/// the next launch (next to the preferences).
+fn load_journal_path() -> Option<std::path::PathBuf> {
+    directories::ProjectDirs::from("com", "vst-host", "vst-host")
+        .map(|dirs| dirs.config_dir().join("load_journal.json"))
+}
+
+/// What a plugin was doing in `phase`, for the crash report.
+fn load_phase_description(phase: vst3_host::LoadPhase) -> &'static str {
+    match phase {
+        vst3_host::LoadPhase::Component => "module loading",
+        vst3_host::LoadPhase::BusNegotiation => "bus activation",
+        vst3_host::LoadPhase::ControllerInit => "controller setup",
+        vst3_host::LoadPhase::GuiCheck => "the editor check",
+    }
+}
+
+/// The "Edit Notes" modal's working copy of one plugin's annotation.
+struct NotesEditor {
+    path: String,
+    annotation: vst3_host::PluginAnnotation,
+    // Text of the tag being typed
+    new_tag: String,
+}
+
 /// Result of a background plugin load, sent back to the UI thread.
 struct LoadedPlugin {
     detail: vst3_host::DetailedPluginInfo,
     params: Vec<vst3_host::parameters::Parameter>,
+    factory_mismatches: Vec<vst3_host::ParameterMismatch>,
     audio: AudioHandle,
+    block_statistics: Arc<std::sync::RwLock<vst3_host::BlockStatsSnapshot>>,
+    spectrum: Arc<std::sync::Mutex<vst3_host::SpectrumAnalyzer>>,
+    stream_monitor: Arc<vst3_host::StreamMonitor>,
+    slider_curves: HashMap<u32, vst3_host::SliderCurve>,
+    // Each parameter's widget and step labels, asked of the plugin while loading.
+    widgets: HashMap<u32, (vst3_host::WidgetType, Vec<String>)>,
+    has_controller: bool,
+    known_issues: Vec<String>,
     is_processing: bool,
+    // The rate playback settled on with the output device.
+    actual_sample_rate: f64,
 }
 
 /// A plugin load running on a background thread (so a hanging/slow plugin can't freeze the UI).
@@ -519,11 +1294,30 @@ struct VST3Inspector {
     // Prebuilt JSON export of the current plugin (PluginReport), for the "Copy JSON" button.
     // Built at load time so the button never re-introspects a loaded plugin.
     report_json: Option<String>,
+    // Parameters the loaded plugin started away from their factory defaults (badged in the
+    // parameter table).
+    factory_mismatches: Vec<vst3_host::ParameterMismatch>,
     // The plugin's native editor window while open (standalone; dropped to close).
     plugin_window: Option<vst3_host::PluginWindow>,
+    // Keyboard shortcuts the loaded plugin's editor reports, and whether the "?" list is open.
+    key_shortcuts: Vec<vst3_host::KeyShortcut>,
+    shortcuts_modal_open: bool,
+    // Non-linear slider curves detected for the loaded plugin's parameters (shared with the
+    // parameter table while it draws).
+    slider_curves: Arc<HashMap<u32, vst3_host::SliderCurve>>,
     // Plugin discovery
     discovered_plugins: Vec<String>,
     // The `vst3-host` library host (built once, used to load plugins).
+    // Plugins tab: only list plugins with a tag matching this (empty = all).
+    plugin_tag_filter: String,
+    // Plugins tab: list favorites first in each folder.
+    favorites_first: bool,
+    // The plugin whose notes are being edited in the notes modal.
+    notes_editor: Option<NotesEditor>,
+    // The plugin (path) whose init flags are being edited in the compatibility modal.
+    compat_editor: Option<(String, vst3_host::PluginInitFlags)>,
+    // A plugin load the last run crashed in, and the step it was in, until acknowledged.
+    interrupted_load: Option<(std::path::PathBuf, vst3_host::LoadPhase)>,
     host: Vst3Host,
     // The currently loaded + playing plugin. `Some` when a plugin is loaded; the
     // `Plugin` lives entirely inside this `AudioHandle` for its whole lifetime.
@@ -531,10 +1325,23 @@ struct VST3Inspector {
     // An in-flight load running on a background thread, so a slow or hanging plugin can't
     // freeze the UI. Polled each frame in `update`; resolves to the loaded plugin or an error.
     pending_load: Option<PendingLoad>,
+    // Showing the startup screen until the plugin auto-loaded at launch is ready.
+    startup_loading: bool,
+    // A category scan probing the listed plugins on a background thread.
+    category_scan: Option<
+        std::sync::mpsc::Receiver<(vst3_host::SafeDiscoveryReport, Vec<vst3_host::PluginInfo>)>,
+    >,
+    // Plugins the last category scan could not inspect; `None` until a scan has finished.
+    scan_failures: Option<Vec<vst3_host::SafeDiscoverySkip>>,
     // Last user-facing error/status message, shown in the header and auto-cleared.
     last_error: Option<String>,
     // When `last_error` was set, for the auto-clear timer.
     last_error_time: Option<Instant>,
+    // The crash report an isolated plugin's helper left behind, shown in the header until
+    // dismissed.
+    helper_crash: Option<vst3_host::process_isolation::CrashReport>,
+    // The always-on-top Quick Edit parameter panel, while open.
+    quick_edit: Option<vst3_host::MinimalGuiHandle>,
     // GUI management
     gui_attached: bool,
     // Parameter editing
@@ -542,7 +1349,35 @@ struct VST3Inspector {
     // Parameter table UI
     parameter_search: String,
     parameter_filter: ParameterFilter,
+    // Column the parameter table is sorted by (clicked in its header).
+    current_sort: ParameterSort,
+    // The bus preset last applied to the loaded plugin, re-applied when it reloads.
+    bus_preset: Option<String>,
+    // Name typed for the next saved bus preset.
+    bus_preset_name: String,
+    // Parameter edit history. Edits are recorded as the difference from `undo_baseline`, the
+    // values at the end of the last step, whenever no control is being dragged.
+    undo_stack: vst3_host::UndoStack,
+    undo_baseline: Vec<(u32, f64)>,
+    // Parameter values when a preset was last saved or loaded, for "Modified since last save".
+    saved_parameter_values: Option<Vec<(u32, f64)>>,
+    // Whether each audio input and output bus is active, kept from the last frame the plugin
+    // wasn't locked by the audio thread.
+    active_audio_buses: (Vec<bool>, Vec<bool>),
     show_only_modified: bool,
+    // Bulk reset awaiting confirmation in a modal.
+    pending_parameter_reset: Option<ParameterReset>,
+    // "Copy from…" dialog: open, and the General MIDI channel instance picked as the source.
+    copy_from_open: bool,
+    copy_from_channel: Option<u8>,
+    // "Randomize" dialog: open, its settings, and the generator it draws from.
+    randomize_open: bool,
+    randomize_constraints: vst3_host::RandomizeConstraints,
+    rng: rand::rngs::SmallRng,
+    // A session being restored: its parameters are applied once the plugin finishes loading.
+    pending_session: Option<vst3_host::Session>,
+    // Whether the "Reload last session?" prompt is still showing.
+    offer_session_reload: bool,
     table_scroll_to_selected: bool,
     // Pagination
     current_page: usize,
@@ -551,28 +1386,74 @@ struct VST3Inspector {
     current_tab: Tab,
     // Inline editing state
     parameter_being_edited: Option<u32>,
+    // The parameter value tooltip pending or open, and the range each parameter has reported
+    // since processing started (shown for read-only parameters).
+    param_tooltip: param_tooltip::HoverTooltip,
+    observed_ranges: param_tooltip::ObservedRanges,
     // Whether the loaded plugin is currently processing (cached from the library).
     is_processing: bool,
     // Host configuration
     block_size: i32,
     sample_rate: f64,
+    // The rate the output device settled on for the loaded plugin, when it differs from
+    // `sample_rate` (the device didn't support the requested one).
+    actual_sample_rate: Option<f64>,
+    // Transport tempo advertised to the plugin; also drives the Tempo Sync picker.
+    tempo_bpm: f64,
+    // Output meter ballistics (applied on load) and the latest per-channel readings.
+    meter_ballistics: vst3_host::BallisticsPreset,
+    meter_readings: [f32; 2],
+    // When the meters' peak hold markers drop: after 3 s, or on "Reset Hold".
+    meter_hold_mode: vst3_host::MeterHoldMode,
+    // Spectrum window (applied on load and when changed) and frames averaged.
+    spectrum_window: vst3_host::WindowFunction,
+    spectrum_averaging: usize,
+    // Input devices offered by the Input selector; listed when it is first shown.
+    input_devices: Option<Vec<String>>,
+    // Output devices offered by the Audio Device selector; listed when it is first shown.
+    output_devices: Option<Vec<vst3_host::AudioDeviceInfo>>,
+    // Assumed Hz range a tempo-synced rate parameter maps 0.0..=1.0 onto (VST3 keeps the
+    // real range private to the plugin).
+    tempo_sync_range_hz: (f64, f64),
     // Virtual keyboard state
     pressed_keys: HashSet<i16>,
+    // Positions of the strips under the keyboard: pitch bend (-1..=1) and mod wheel (0..=1).
+    pitch_bend: f64,
+    mod_wheel: f64,
+    // The CC 1 value last sent, so a drag within one step doesn't resend it.
+    last_mod_wheel_cc: Option<u8>,
+    // Computer-keyboard piano: the layout, and the note each held key is sounding (so an
+    // octave shift mid-hold still releases the note that was started).
+    keyboard_input: KeyboardMapping,
+    keyboard_notes: HashMap<egui::Key, i16>,
     selected_midi_channel: i16, // 0-15 for MIDI channels 1-16
     // MIDI monitoring
     midi_events: Arc<Mutex<Vec<MidiEvent>>>,
     midi_event_filter: MidiEventFilter,
     midi_monitor_paused: Arc<Mutex<bool>>,
     max_midi_events: usize,
+    // Records the monitored events for "⏺ Record"; positions count from `smf_record_started`.
+    smf_recorder: Arc<Mutex<vst3_host::SmfRecorder>>,
+    smf_record_started: Instant,
+    // The notes the plugin's MIDI output is holding, for the monitor's voice grid.
+    output_voices: vst3_host::PolyphonyTracker,
     // Preferences
     preferences: Preferences,
-    // VU meters: the library's PeakMeter handles falling ballistics + timed peak-hold.
+    // Peak-hold markers: the library's PeakMeter latches the loudest block peak for a while.
     meter_left: Arc<Mutex<PeakMeter>>,
     meter_right: Arc<Mutex<PeakMeter>>,
     // A/B preset compare: two captured state snapshots + which is currently applied.
     slot_a: Option<Vec<u8>>,
     slot_b: Option<Vec<u8>>,
     active_slot: Option<AbSlot>,
+    // Parameter values captured with each A/B slot, for the "Morph A→B" control.
+    morph_a: Option<vst3_host::ParameterSnapshot>,
+    morph_b: Option<vst3_host::ParameterSnapshot>,
+    // True while the mouse is held on the morph bar; `morph_t` is its position (0 = A, 1 = B).
+    morphing: bool,
+    morph_t: f64,
+    // Slider and knob edits held back until they pause (automation bypasses it).
+    param_debouncer: vst3_host::ChangeDebouncer,
     // Parameter-automation demo state.
     automation: AutomationState,
     // MIDI file (.mid) player.
@@ -581,6 +1462,111 @@ struct VST3Inspector {
     midi_input: MidiInputState,
     // Cached list of available MIDI input port names (refreshed on demand).
     midi_input_ports: Vec<String>,
+    // "Pressure → Param" binding editor.
+    pressure_binding: PressureBindingEditor,
+    midi_binding_editor: MidiBindingEditor,
+    // Controller presets saved in `controller_presets_dir()` (rescanned after a save).
+    saved_controller_presets: Vec<vst3_host::ControllerPreset>,
+    // Name typed for the next saved controller preset.
+    controller_preset_name: String,
+    // Velocity of notes played on the virtual and computer keyboards.
+    keyboard_velocity: u8,
+    // Keyboard preset picked in the "Keyboard Presets" dropdown, and the name typed for the
+    // next saved one.
+    selected_keyboard_preset: Option<String>,
+    keyboard_preset_name: String,
+    // Arpeggiator panel settings; `host.arpeggiator()` holds a copy while it's switched on.
+    arp_settings: vst3_host::Arpeggiator,
+    // Voice limit panel settings; applied to the playing plugin while `voice_limit_on`.
+    voice_limit_on: bool,
+    voice_limit: vst3_host::PolyphonyLimiter,
+    // Channel Strips panel settings; applied to the playing plugin (re-applied after a load).
+    channel_strips: ChannelStripPanel,
+    // CC bindings only move a parameter once the controller reaches it (re-applied after a load).
+    soft_takeover: bool,
+    // Input group settings: the gain stage applied to live input (with an input device set).
+    input_gain_db: f64,
+    auto_gain: bool,
+    auto_gain_target_db: f64,
+    // Safety limiter after the plugin's output (re-applied after a load).
+    auto_limiter: bool,
+    // Virtual MIDI Out: mirror the plugin's output MIDI to a port other apps can record from.
+    virtual_midi_out: bool,
+    virtual_midi_out_name: String,
+    // Binding range drags not yet saved (saved when the drag ends).
+    bindings_dirty: bool,
+    // Latest lines of the plugin's captured console output (process-isolated plugins only).
+    plugin_log: VecDeque<String>,
+    // Parameter changes from the loaded plugin's own editor, delivered as they happen (`None`
+    // for process-isolated plugins, whose changes arrive through the audio thread's ring).
+    editor_param_rx: Option<std::sync::mpsc::Receiver<vst3_host::ParameterChange>>,
+    plugin_log_rx: Option<std::sync::mpsc::Receiver<String>>,
+    // Plugin graph editor state (the graph itself lives in `preferences`).
+    graph_editor: GraphEditor,
+    graph_add_path: Option<String>,
+    // Node moves not yet saved (saved when the drag ends).
+    graph_dirty: bool,
+    // General MIDI mode: sixteen instances of a plugin, one per MIDI channel, playing on
+    // their own stream. Hardware MIDI goes here instead of `audio` while it is on.
+    gm_audio: Option<vst3_host::GmAudioHandle>,
+    gm_plugin_name: String,
+    gm_strip: GeneralMidiChannelStrip,
+    // Show the GM channels as a console of vertical strips rather than the channel table.
+    gm_mixer_view: bool,
+    gm_mixer: MixBusView,
+    // Frequency response of the loaded plugin across a parameter sweep.
+    freq_response: FreqResponseView,
+    // Keyboard split boundary moves not yet saved (saved when the drag ends).
+    split_dirty: bool,
+    // Latest output analysis of the playing plugin, for the "Health" section.
+    output_health: Option<vst3_host::AnalysisReport>,
+    // Output statistics the playing plugin publishes, for the "Analysis" section.
+    block_statistics: Option<Arc<std::sync::RwLock<vst3_host::BlockStatsSnapshot>>>,
+    // Spectrum of the playing plugin's output, for the "Analysis" section.
+    spectrum: Option<Arc<std::sync::Mutex<vst3_host::SpectrumAnalyzer>>>,
+    // Dropout and xrun counts of the playing stream, shown next to "Audio Output".
+    stream_monitor: Option<Arc<vst3_host::StreamMonitor>>,
+    // Stream errors reported from the audio backend's thread, drained every frame.
+    stream_errors: (
+        std::sync::mpsc::Sender<vst3_host::StreamError>,
+        std::sync::mpsc::Receiver<vst3_host::StreamError>,
+    ),
+}
+
+/// How many lines of plugin output the "Plugin Log" section keeps.
+const PLUGIN_LOG_LINES: usize = 100;
+
+/// Undo steps older than this are dropped from the parameter history.
+const UNDO_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Impulse length for the frequency response sweep (~12 Hz resolution at 48 kHz).
+const FREQ_SWEEP_IMPULSE_LEN: usize = 4096;
+
+/// Draft binding in the "Pressure → Param" editor, plus a test pressure to send.
+struct PressureBindingEditor {
+    param_id: Option<u32>,
+    // Polyphonic aftertouch (per note) vs channel pressure.
+    poly: bool,
+    any_note: bool,
+    note: u8,
+    // Otherwise the keyboard's selected channel.
+    any_channel: bool,
+    range: (f64, f64),
+    test_pressure: u8,
+}
+
+impl Default for PressureBindingEditor {
+    fn default() -> Self {
+        Self {
+            param_id: None,
+            poly: true,
+            any_note: false,
+            note: 60,
+            any_channel: true,
+            range: (0.0, 1.0),
+            test_pressure: 0,
+        }
+    }
 }
 
 /// One of the two A/B compare slots.
@@ -606,6 +1592,15 @@ enum Tab {
     MidiMonitor,
 }
 
+/// Which parameters a bulk reset touches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParameterReset {
+    All,
+    Modified,
+    /// Reload the plugin from scratch, discarding all of its state.
+    Factory,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 enum ParameterFilter {
     All,
@@ -613,6 +1608,34 @@ enum ParameterFilter {
     ReadOnly,
     HasSteps,
     HasUnits,
+    /// Normalized value within `min..=max`
+    InRange {
+        min: f64,
+        max: f64,
+    },
+}
+
+impl ParameterFilter {
+    fn label(&self) -> String {
+        match self {
+            ParameterFilter::InRange { min, max } => format!("Value {min:.2}–{max:.2}"),
+            other => format!("{other:?}"),
+        }
+    }
+
+    fn matches(&self, param: &ParameterInfo) -> bool {
+        match *self {
+            ParameterFilter::All => true,
+            ParameterFilter::Writable => (param.flags & 0x1) == 0, // Not read-only
+            ParameterFilter::ReadOnly => (param.flags & 0x1) != 0, // Read-only
+            ParameterFilter::HasSteps => param.step_count > 0,
+            ParameterFilter::HasUnits => !param.units.is_empty(),
+            // The same test as `Plugin::find_params_near_value`, as a center and tolerance.
+            ParameterFilter::InRange { min, max } => {
+                (param.current_value - (min + max) / 2.0).abs() <= (max - min) / 2.0 + 1e-9
+            }
+        }
+    }
 }
 
 impl eframe::App for VST3Inspector {
@@ -623,6 +1646,8 @@ impl eframe::App for VST3Inspector {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Finalize any background plugin load that has completed.
         self.poll_pending_load();
+        self.poll_category_scan();
+        self.drain_plugin_log();
 
         // Auto-clear the status/error line a few seconds after it was set.
         if self
@@ -637,6 +1662,7 @@ impl eframe::App for VST3Inspector {
         self.update_vu_meters();
         self.poll_plugin_output_midi();
         self.poll_plugin_parameter_changes();
+        self.show_quick_edit(ctx);
 
         // Drive the parameter-automation demo at UI cadence while it's enabled.
         if let Some(value) = self.automation.value_now(Instant::now()) {
@@ -645,6 +1671,24 @@ impl eframe::App for VST3Inspector {
                 self.automation.last_value = value;
             }
         }
+        while let Ok(error) = self.stream_errors.1.try_recv() {
+            match error {
+                vst3_host::StreamError::DeviceDisconnected => self.set_error(
+                    "Audio device disconnected; switching to the default output\u{2026}"
+                        .to_string(),
+                ),
+                other => eprintln!("Audio stream: {other}"),
+            }
+        }
+        for (id, value) in self.param_debouncer.tick() {
+            if let Err(e) = self.set_parameter_value(id, value) {
+                self.set_error(format!("Failed to set parameter: {e}"));
+            }
+        }
+        if !self.param_debouncer.is_empty() {
+            ctx.request_repaint_after(Duration::from_millis(5));
+        }
+        self.track_undo_steps(ctx);
 
         // Replay any MIDI file events that have come due, onto the live plugin.
         if self.midi_player.is_playing() {
@@ -658,13 +1702,24 @@ impl eframe::App for VST3Inspector {
             }
         }
 
+        self.handle_computer_keyboard(ctx);
+
         // Forward any live hardware-MIDI events (parsed on the device callback thread) to the
         // plugin from here on the UI thread, and log them to the monitor as Input.
         let device_events = self.midi_input.drain();
         if !device_events.is_empty() {
-            if let Some(audio) = &self.audio {
-                for &ev in &device_events {
-                    audio.send_midi(ev);
+            for &ev in &device_events {
+                if let Some(binding) = self.midi_binding_editor.learn_from(&ev) {
+                    self.set_error(format!(
+                        "Learned {} ({})",
+                        binding.kind_name(),
+                        midi_bindings::source_label(&binding)
+                    ));
+                    self.add_midi_binding(binding);
+                }
+                let _ = self.dispatch_midi(ev);
+                if self.gm_audio.is_none() {
+                    self.apply_midi_bindings(&ev);
                 }
             }
             for ev in device_events {
@@ -672,6 +1727,36 @@ impl eframe::App for VST3Inspector {
             }
         }
 
+        // Drag-and-drop: load a `.vst3` bundle dropped onto the window, highlighting the window
+        // as a drop zone while a drag hovers over it.
+        let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+        match dropped_plugin_path(&dropped) {
+            Some(Ok(path)) => self.load_plugin(path),
+            Some(Err(e)) => self.set_error(e),
+            None => {}
+        }
+        if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+            let rect = ctx.content_rect();
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                "drop_zone".into(),
+            ));
+            painter.rect_filled(rect, 0.0, egui::Color32::from_black_alpha(160));
+            painter.rect_stroke(
+                rect.shrink(8.0),
+                8.0,
+                egui::Stroke::new(2.0, egui::Color32::LIGHT_BLUE),
+                egui::StrokeKind::Inside,
+            );
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop a .vst3 bundle to load it",
+                egui::FontId::proportional(22.0),
+                egui::Color32::WHITE,
+            );
+        }
+
         // Drive a continuous render loop. egui is reactive by default (it repaints only when an
         // event wakes the loop), which makes a host UI feel dead between events and — worse —
         // lets a click that doesn't move the mouse sit unprocessed until the next event arrives
@@ -697,8 +1782,39 @@ impl eframe::App for VST3Inspector {
         );
         root_ui.set_clip_rect(ctx.content_rect());
 
+        if self.startup_loading {
+            if let Some(pending) = &self.pending_load {
+                let name = pending.name.clone();
+                egui::CentralPanel::default().show_inside(&mut root_ui, |ui| {
+                    ui.vertical_centered(|ui| {
+                        ui.add_space(ui.available_height() / 2.0 - 24.0);
+                        ui.spinner();
+                        ui.label(format!("Loading {name}\u{2026}"));
+                    });
+                });
+                return;
+            }
+            self.startup_loading = false;
+        }
+
         // Top header panel
         egui::Panel::top("header").show_inside(&mut root_ui, |ui| {
+            egui::MenuBar::new().ui(ui, |ui| {
+                ui.menu_button("Session", |ui| {
+                    if ui
+                        .add_enabled(
+                            self.audio.is_some(),
+                            egui::Button::new("Save Session\u{2026}"),
+                        )
+                        .clicked()
+                    {
+                        self.save_session_dialog();
+                    }
+                    if ui.button("Load Session\u{2026}").clicked() {
+                        self.load_session_dialog();
+                    }
+                });
+            });
             ui.add_space(8.0);
 
             // Plugin info - always shown at top
@@ -732,11 +1848,25 @@ impl eframe::App for VST3Inspector {
                     if let Some(err) = &self.last_error {
                         ui.colored_label(egui::Color32::ORANGE, err.clone());
                     }
+                    if let Some(report) = &self.helper_crash {
+                        if crash_report_header(ui, report) {
+                            self.helper_crash = None;
+                        }
+                    }
                 });
 
                 // Push GUI button to the right - only show on Plugin tab
                 if self.current_tab != Tab::Plugins {
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        // Quick Edit works without a native editor, so it's always offered.
+                        if self.audio.is_some()
+                            && ui
+                                .add_sized([120.0, 40.0], egui::Button::new("\u{26A1} Quick Edit"))
+                                .on_hover_text("A small always-on-top panel with the recently changed parameters")
+                                .clicked()
+                        {
+                            self.open_quick_edit();
+                        }
                         // Large GUI button
                         if self.plugin_info.as_ref().is_some_and(|p| p.has_gui) {
                             if self.gui_attached {
@@ -746,6 +1876,21 @@ impl eframe::App for VST3Inspector {
                                 {
                                     self.close_plugin_gui();
                                 }
+                                if ui
+                                    .small_button("Reset Position")
+                                    .on_hover_text("Forget the saved editor position and re-center")
+                                    .clicked()
+                                {
+                                    self.reset_plugin_window_position();
+                                }
+                                if ui
+                                    .small_button("?")
+                                    .on_hover_text("The plugin's keyboard shortcuts")
+                                    .clicked()
+                                {
+                                    self.shortcuts_modal_open = true;
+                                }
+                                self.show_plugin_window_toolbar(ui);
                             } else if ui
                                 .add_sized([120.0, 40.0], egui::Button::new("Open GUI"))
                                 .clicked()
@@ -784,6 +1929,9 @@ impl eframe::App for VST3Inspector {
             Tab::Processing => self.show_processing_tab(&mut root_ui),
             Tab::MidiMonitor => self.show_midi_monitor_tab(&mut root_ui),
         }
+        self.show_session_reload_modal(ctx);
+        self.show_interrupted_load_modal(ctx);
+        self.show_shortcuts_modal(ctx);
 
         // Persist session state (tab, channel, window size) whenever it changes, so the next
         // launch restores it. Captured here after the UI ran, debounced to only write on change.
@@ -804,6 +1952,7 @@ impl VST3Inspector {
             None => return,
         };
         for ev in events {
+            self.output_voices.update(&ev);
             let (ty, ch, d1, d2): (u16, u8, u8, u8) = match ev {
                 MidiEvent::NoteOn {
                     channel,
@@ -823,10 +1972,10 @@ impl VST3Inspector {
                 MidiEvent::ProgramChange { channel, program } => {
                     (4, channel.as_index(), program, 0)
                 }
-                MidiEvent::ChannelAftertouch { channel, pressure } => {
+                vst3_host::MidiEvent::ChannelAftertouch { channel, pressure } => {
                     (2, channel.as_index(), pressure, 0)
                 }
-                MidiEvent::PolyAftertouch {
+                vst3_host::MidiEvent::PolyAftertouch {
                     channel,
                     note,
                     pressure,
@@ -842,22 +1991,25 @@ impl VST3Inspector {
     /// the plugin GUI calls back via the component handler) into the inspector's parameter
     /// list, so the displayed values stay in sync with the plugin's editor.
     fn poll_plugin_parameter_changes(&mut self) {
-        // Lock-free: the audio callback drains the plugin's editor parameter changes into a
-        // ring (see poll_plugin_output_midi); we pop them here without locking.
-        let changes = match &self.audio {
+        // Lock-free: in-process plugins send editor edits straight to `editor_param_rx`; the
+        // rest reach us through the ring the audio callback fills after each block (see
+        // poll_plugin_output_midi). Neither path locks the audio thread.
+        let mut changes = match &self.audio {
             Some(a) => a.drain_parameter_changes(),
             None => return,
         };
+        if let Some(rx) = &self.editor_param_rx {
+            changes.extend(rx.try_iter().map(|change| (change.id, change.value)));
+        }
         if changes.is_empty() {
             return;
         }
+        for &(id, value) in &changes {
+            self.observed_ranges.observe(id, value);
+        }
         if let Some(plugin_info) = &mut self.plugin_info {
             if let Some(controller_info) = &mut plugin_info.controller_info {
-                for (id, value) in changes {
-                    if let Some(p) = controller_info.parameters.iter_mut().find(|p| p.id == id) {
-                        p.current_value = value;
-                    }
-                }
+                apply_parameter_changes(&mut controller_info.parameters, changes);
             }
         }
     }
@@ -870,6 +2022,107 @@ impl VST3Inspector {
         self.last_error_time = Some(Instant::now());
     }
 
+    /// Save, apply or delete a bus preset of the loaded plugin. Applying stops processing
+    /// while the buses change, as VST3 requires.
+    /// Activate or deactivate an audio bus, pausing processing around it as VST3 requires.
+    fn set_audio_bus_active(
+        &mut self,
+        direction: vst3_host::BusDirection,
+        index: usize,
+        active: bool,
+    ) {
+        let was_processing = self.is_processing;
+        if was_processing {
+            self.stop_processing();
+        }
+        let result = self.audio.as_ref().map(|a| {
+            let mut plugin = a.lock();
+            if active {
+                plugin.activate_bus(vst3_host::MediaType::Audio, direction, index)
+            } else {
+                plugin.deactivate_bus(vst3_host::MediaType::Audio, direction, index)
+            }
+        });
+        if let Some(Err(e)) = result {
+            self.set_error(format!("Failed to change bus {index}: {e}"));
+        }
+        if was_processing {
+            if let Err(e) = self.start_processing() {
+                self.set_error(e);
+            }
+        }
+    }
+
+    fn run_bus_preset_action(&mut self, action: BusPresetAction) {
+        let Some(audio) = &self.audio else {
+            return;
+        };
+        match action {
+            BusPresetAction::Save(name) => {
+                let config = audio.lock().save_bus_configuration();
+                let presets = self
+                    .preferences
+                    .bus_configs
+                    .entry(self.plugin_path.clone())
+                    .or_default();
+                match presets.iter_mut().find(|(n, _)| *n == name) {
+                    Some((_, existing)) => *existing = config,
+                    None => presets.push((name.clone(), config)),
+                }
+                self.bus_preset_name.clear();
+                self.set_error(format!("Saved bus preset \"{name}\""));
+                self.bus_preset = Some(name);
+            }
+            BusPresetAction::Apply(name) => {
+                let Some((_, config)) = self
+                    .preferences
+                    .bus_configs
+                    .get(&self.plugin_path)
+                    .and_then(|presets| presets.iter().find(|(n, _)| *n == name))
+                    .cloned()
+                else {
+                    return;
+                };
+                let was_processing = self.is_processing;
+                if was_processing {
+                    self.stop_processing();
+                }
+                let result = self
+                    .audio
+                    .as_ref()
+                    .map(|a| a.lock().apply_bus_configuration(&config));
+                match result {
+                    Some(Err(e)) => self.set_error(format!("Failed to apply bus preset: {e}")),
+                    _ => self.bus_preset = Some(name),
+                }
+                if was_processing {
+                    if let Err(e) = self.start_processing() {
+                        self.set_error(e);
+                    }
+                }
+                return;
+            }
+            BusPresetAction::Delete(name) => {
+                if let Some(presets) = self.preferences.bus_configs.get_mut(&self.plugin_path) {
+                    presets.retain(|(n, _)| *n != name);
+                }
+                if self.bus_preset.as_ref() == Some(&name) {
+                    self.bus_preset = None;
+                }
+            }
+        }
+        if let Err(e) = self.preferences.save() {
+            self.set_error(format!("Failed to save bus presets: {e}"));
+        }
+    }
+
+    /// Keep the crash report from a plugin call that failed because its helper died.
+    fn note_helper_crash(&mut self, e: &vst3_host::Error) {
+        if let vst3_host::Error::HelperCrash { report } = e {
+            self.helper_crash = Some(report.clone());
+        }
+    }
+
     /// Snapshot session state into `preferences` and save it to disk, but only when something
     /// changed — so a steady 60 fps UI doesn't rewrite the config file every frame.
     fn persist_session_if_changed(&mut self, ctx: &egui::Context) {
@@ -879,6 +2132,7 @@ impl VST3Inspector {
         let window_size = Some((size.x.round(), size.y.round()));
         let last_tab = Some(self.current_tab.clone());
         let last_midi_channel = Some(self.selected_midi_channel);
+        let parameter_sort = self.current_sort;
         // The loaded plugin path, so it can be auto-reloaded next launch.
         let last_loaded_plugin = self
             .audio
@@ -889,6 +2143,7 @@ impl VST3Inspector {
         let changed = self.preferences.window_size != window_size
             || self.preferences.last_tab != last_tab
             || self.preferences.last_midi_channel != last_midi_channel
+            || self.preferences.parameter_sort != parameter_sort
             || self.preferences.last_loaded_plugin != last_loaded_plugin;
         if !changed {
             return;
@@ -897,6 +2152,7 @@ impl VST3Inspector {
         self.preferences.window_size = window_size;
         self.preferences.last_tab = last_tab;
         self.preferences.last_midi_channel = last_midi_channel;
+        self.preferences.parameter_sort = parameter_sort;
         self.preferences.last_loaded_plugin = last_loaded_plugin;
         if let Err(e) = self.preferences.save() {
             // Don't spam the status line every frame; a console note is enough.
@@ -914,6 +2170,16 @@ impl VST3Inspector {
 
         let peak_left = levels.channels.first().map(|c| c.peak).unwrap_or(0.0);
         let peak_right = levels.channels.get(1).map(|c| c.peak).unwrap_or(peak_left);
+        let readings = self
+            .audio
+            .as_ref()
+            .map(|a| a.meter_levels())
+            .unwrap_or_default();
+        let reading_left = readings.first().copied().unwrap_or(0.0);
+        self.meter_readings = [
+            reading_left,
+            readings.get(1).copied().unwrap_or(reading_left),
+        ];
 
         let now = Instant::now();
         if let Ok(mut m) = self.meter_left.lock() {
@@ -924,7 +2190,30 @@ impl VST3Inspector {
         }
     }
 
+    /// Give the meters' hold markers, and the playing plugin's peak holds, the chosen mode.
+    fn apply_meter_hold_mode(&mut self) {
+        for meter in [&self.meter_left, &self.meter_right] {
+            meter.lock().unwrap().set_hold_mode(self.meter_hold_mode);
+        }
+        if let Some(audio) = &self.audio {
+            audio.lock().set_meter_hold_mode(self.meter_hold_mode);
+        }
+    }
+
+    /// Drop the peak hold markers to silence (the meters' "Reset Hold").
+    fn reset_peak_hold(&mut self) {
+        let now = Instant::now();
+        for meter in [&self.meter_left, &self.meter_right] {
+            meter.lock().unwrap().reset_peak_hold(now);
+        }
+        if let Some(audio) = &self.audio {
+            audio.lock().reset_peak_hold();
+        }
+    }
+
     fn show_plugins_tab(&mut self, root_ui: &mut egui::Ui) {
+        self.show_notes_modal(root_ui.ctx());
+        self.show_compat_modal(root_ui.ctx());
         egui::CentralPanel::default().show_inside(root_ui, |ui| {
             ui.add_space(8.0);
             ui.heading("Available VST3 Plugins");
@@ -934,7 +2223,26 @@ impl VST3Inspector {
                 ui.label(format!("Found {} plugins", self.discovered_plugins.len()));
                 if ui.button("Refresh").clicked() {
                     self.discovered_plugins =
-                        discover_vst3_paths(&self.preferences.custom_plugin_paths);
+                        discover_plugin_paths(&self.preferences.custom_plugin_paths);
+                }
+                if self.category_scan.is_some() {
+                    ui.spinner();
+                    ui.label("Scanning categories\u{2026}");
+                } else if ui
+                    .button("Scan Categories")
+                    .on_hover_text(
+                        "Probe every plugin (each in its own process) to file it by category",
+                    )
+                    .clicked()
+                {
+                    self.start_category_scan();
+                }
+                if ui
+                    .button("Export Report")
+                    .on_hover_text("Save an HTML report of every plugin with known metadata")
+                    .clicked()
+                {
+                    self.export_plugins_report();
                 }
 
                 // Add custom path button
@@ -951,7 +2259,7 @@ impl VST3Inspector {
                             }
                             // Refresh plugin list
                             self.discovered_plugins =
-                                discover_vst3_paths(&self.preferences.custom_plugin_paths);
+                                discover_plugin_paths(&self.preferences.custom_plugin_paths);
                         }
                     }
                 }
@@ -981,96 +2289,390 @@ impl VST3Inspector {
                         }
                         // Refresh plugin list
                         self.discovered_plugins =
-                            discover_vst3_paths(&self.preferences.custom_plugin_paths);
+                            discover_plugin_paths(&self.preferences.custom_plugin_paths);
                     }
                 });
 
                 ui.add_space(8.0);
             }
 
-            // Plugin table
-            self.show_plugins_table(ui);
+            if let Some(failures) = &self.scan_failures {
+                egui::CollapsingHeader::new(format!("Scan Results ({} failed)", failures.len()))
+                    .id_salt("scan_results")
+                    .show(ui, |ui| {
+                        if failures.is_empty() {
+                            ui.label("Every plugin was inspected successfully.");
+                        }
+                        for skip in failures {
+                            ui.horizontal(|ui| {
+                                ui.label(skip.path().display().to_string());
+                                ui.weak(scan_failure_reason(skip));
+                            });
+                        }
+                    });
+                ui.add_space(8.0);
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Tags:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.plugin_tag_filter)
+                        .hint_text("Filter by tag")
+                        .desired_width(160.0),
+                );
+                if !self.plugin_tag_filter.is_empty() && ui.small_button("Clear").clicked() {
+                    self.plugin_tag_filter.clear();
+                }
+                ui.checkbox(&mut self.favorites_first, "\u{2605} first")
+                    .on_hover_text("List favorites ahead of the other plugins in each folder");
+            });
+            ui.add_space(4.0);
+
+            // Plugin tree, by category
+            self.show_plugins_tree(ui);
         });
     }
 
-    fn show_plugins_table(&mut self, ui: &mut egui::Ui) {
-        use egui_extras::{Column, TableBuilder};
-
-        TableBuilder::new(ui)
-            .striped(true)
-            .resizable(false)
-            .cell_layout(egui::Layout::left_to_right(egui::Align::Center)) // Plugin Name
-            .column(Column::remainder().at_least(200.0))
-            .column(Column::remainder().at_least(300.0)) // Directory
-            .column(Column::auto().at_least(80.0)) // Actions
-            .header(20.0, |mut header| {
-                header.col(|ui| {
-                    ui.strong("Plugin Name");
-                });
-                header.col(|ui| {
-                    ui.strong("Directory");
-                });
-                header.col(|ui| {
-                    ui.strong("Actions");
+    fn show_plugins_tree(&mut self, ui: &mut egui::Ui) {
+        let paths = plugin_browser::filter_by_tag(
+            &self.discovered_plugins,
+            &self.preferences.plugin_notes,
+            &self.plugin_tag_filter,
+        );
+        let (tree, unscanned) =
+            plugin_browser::catalog_tree(&paths, &self.preferences.plugin_catalog);
+        let browser = plugin_browser::PluginBrowser {
+            current: &self.plugin_path,
+            custom_paths: &self.preferences.custom_plugin_paths,
+            annotations: &self.preferences.plugin_notes,
+            favorites_first: self.favorites_first,
+            disabled: &self.preferences.disabled_plugins,
+        };
+        let action = egui::ScrollArea::vertical()
+            .id_salt("plugins_tree_scroll")
+            .auto_shrink([false; 2])
+            .show(ui, |ui| browser.show(ui, &tree, &unscanned))
+            .inner;
+        match action {
+            Some(plugin_browser::BrowserAction::Load(path)) => {
+                self.load_plugin(path);
+                self.current_tab = Tab::Plugin; // Switch to plugin tab after loading
+            }
+            Some(plugin_browser::BrowserAction::ToggleFavorite(path)) => {
+                let mut annotation = self.plugin_annotation(&path);
+                annotation.favorite = !annotation.favorite;
+                self.annotate_plugin(path, annotation);
+            }
+            Some(plugin_browser::BrowserAction::EditNotes(path)) => {
+                self.notes_editor = Some(NotesEditor {
+                    annotation: self.plugin_annotation(&path),
+                    path,
+                    new_tag: String::new(),
                 });
-            })
-            .body(|mut body| {
-                for plugin_path in &self.discovered_plugins.clone() {
-                    let plugin_name = get_plugin_name_from_path(plugin_path);
-                    let directory = std::path::Path::new(plugin_path)
-                        .parent()
-                        .and_then(|p| p.to_str())
-                        .unwrap_or("Unknown");
-                    let is_current = self.plugin_path == *plugin_path;
-
-                    // Check if this plugin is from a custom path
-                    let is_custom = self
-                        .preferences
-                        .custom_plugin_paths
-                        .iter()
-                        .any(|custom_path| directory.starts_with(custom_path));
-
-                    body.row(25.0, |mut row| {
-                        // Plugin Name
-                        row.col(|ui| {
-                            let mut label = plugin_name.clone();
-                            if is_current {
-                                label = format!("[ACTIVE] {}", label);
-                            }
-                            if is_custom {
-                                label = format!("{} [Custom]", label);
-                            }
+            }
+            Some(plugin_browser::BrowserAction::CompatibilitySettings(path)) => {
+                let flags = self.host.plugin_init_flags(std::path::Path::new(&path));
+                self.compat_editor = Some((path, flags));
+            }
+            Some(action) => self.manage_plugin(action),
+            None => {}
+        }
+    }
 
-                            if is_current {
-                                ui.colored_label(egui::Color32::GREEN, label);
-                            } else if is_custom {
-                                ui.colored_label(egui::Color32::from_rgb(100, 149, 237), label);
-                            // Cornflower blue
-                            } else {
-                                ui.label(label);
-                            }
-                        });
+    /// Carry out a file operation from the plugin browser's context menu, then refresh the
+    /// plugin list and re-key what the preferences remember about a moved plugin.
+    fn manage_plugin(&mut self, action: plugin_browser::BrowserAction) {
+        use plugin_browser::BrowserAction;
+        use std::path::Path;
+
+        let (path, done) = match &action {
+            BrowserAction::MoveToUserDir(path) => (path.clone(), "Moved"),
+            BrowserAction::LinkInto(path) => (path.clone(), "Linked"),
+            BrowserAction::Backup(path) => (path.clone(), "Backed up"),
+            BrowserAction::Disable(path) => (path.clone(), "Disabled"),
+            BrowserAction::Enable(path) => (path.clone(), "Enabled"),
+            _ => return,
+        };
+        let disables = matches!(action, BrowserAction::Disable(_));
+        let moves = disables || matches!(action, BrowserAction::MoveToUserDir(_));
+        if moves && path == self.plugin_path {
+            self.set_error("Unload the plugin before moving or disabling it");
+            return;
+        }
+        let pick_folder = |title: &str| rfd::FileDialog::new().set_title(title).pick_folder();
+        let source = Path::new(&path);
+        let mut manager = self.host.plugin_manager();
+        // The plugin's new path, for the operations that move it.
+        let result = match action {
+            BrowserAction::MoveToUserDir(_) => manager.move_to_user_dir(source).map(|()| {
+                manager
+                    .user_dir()
+                    .zip(source.file_name())
+                    .map(|(dir, name)| dir.join(name))
+            }),
+            BrowserAction::LinkInto(_) => match pick_folder("Link Plugin Into Folder") {
+                Some(dir) => manager.create_symlink(source, &dir).map(|()| None),
+                None => return,
+            },
+            BrowserAction::Backup(_) => match pick_folder("Back Up Plugin To Folder") {
+                Some(dir) => manager.backup(source, &dir).map(|()| None),
+                None => return,
+            },
+            BrowserAction::Disable(_) => manager.disable_plugin(source).map(Some),
+            _ => manager.enable_plugin(source).map(Some),
+        };
+        let moved_to = match result {
+            Ok(moved_to) => moved_to,
+            Err(e) => {
+                self.set_error(format!("Plugin operation failed: {e}"));
+                return;
+            }
+        };
+        if let Some(new_path) = moved_to {
+            let new_path = new_path.to_string_lossy().to_string();
+            self.rekey_plugin(&path, &new_path);
+            self.preferences.disabled_plugins.retain(|p| *p != path);
+            if disables {
+                self.preferences.disabled_plugins.push(new_path);
+            }
+            if let Err(e) = self.preferences.save() {
+                self.set_error(format!("Failed to save preferences: {e}"));
+            }
+        }
+        self.discovered_plugins = discover_plugin_paths(&self.preferences.custom_plugin_paths);
+        self.set_error(format!(
+            "{done} {}",
+            get_plugin_name_from_path(path.trim_end_matches(".disabled"))
+        ));
+    }
 
-                        // Directory
-                        row.col(|ui| {
-                            ui.label(plugin_path);
-                        });
+    /// Move the catalog entry and notes kept for `from` over to `to`.
+    fn rekey_plugin(&mut self, from: &str, to: &str) {
+        if let Some(mut info) = self.preferences.plugin_catalog.remove(from) {
+            info.path = to.into();
+            self.preferences.plugin_catalog.insert(to.to_string(), info);
+        }
+        if let Some(notes) = self.preferences.plugin_notes.remove(from) {
+            self.preferences.plugin_notes.insert(to.to_string(), notes);
+        }
+    }
 
-                        // Actions
-                        row.col(|ui| {
-                            if is_current {
-                                ui.label("Current");
-                            } else if ui.button("Load").clicked() {
-                                self.load_plugin(plugin_path.clone());
-                                self.current_tab = Tab::Plugin; // Switch to plugin tab after loading
-                            }
-                        });
-                    });
+    /// The saved annotation for `path` (empty if there's none).
+    fn plugin_annotation(&self, path: &str) -> vst3_host::PluginAnnotation {
+        self.preferences
+            .plugin_notes
+            .get(path)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Store `annotation` for `path` in the host and the preferences, and save them.
+    fn annotate_plugin(&mut self, path: String, annotation: vst3_host::PluginAnnotation) {
+        if let Err(e) = self
+            .host
+            .annotate_plugin(std::path::Path::new(&path), annotation.clone())
+        {
+            self.set_error(format!("Failed to annotate plugin: {e}"));
+        }
+        if annotation.is_empty() {
+            self.preferences.plugin_notes.remove(&path);
+        } else {
+            self.preferences.plugin_notes.insert(path, annotation);
+        }
+        if let Err(e) = self.preferences.save() {
+            self.set_error(format!("Failed to save plugin notes: {e}"));
+        }
+    }
+
+    /// "Edit Notes": notes, tag chips (click \u{2715} to remove, Enter to add), rating and
+    /// favorite for one plugin. Saved on Save; Cancel or Escape discards.
+    fn show_notes_modal(&mut self, ctx: &egui::Context) {
+        let Some(editor) = self.notes_editor.as_mut() else {
+            return;
+        };
+        let mut saved = false;
+        let mut cancelled = false;
+        let modal = egui::Modal::new(egui::Id::new("plugin_notes_modal")).show(ctx, |ui| {
+            ui.heading(get_plugin_name_from_path(&editor.path));
+            ui.weak(&editor.path);
+            ui.add_space(6.0);
+            ui.add(
+                egui::TextEdit::multiline(&mut editor.annotation.notes)
+                    .hint_text("Notes (e.g. license expiry, what it's good for)")
+                    .desired_rows(6)
+                    .desired_width(360.0),
+            );
+            ui.add_space(6.0);
+            ui.horizontal_wrapped(|ui| {
+                let mut remove = None;
+                for (i, tag) in editor.annotation.custom_tags.iter().enumerate() {
+                    if ui
+                        .small_button(format!("{tag} \u{2715}"))
+                        .on_hover_text("Remove tag")
+                        .clicked()
+                    {
+                        remove = Some(i);
+                    }
+                }
+                if let Some(i) = remove {
+                    editor.annotation.custom_tags.remove(i);
+                }
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut editor.new_tag)
+                        .hint_text("Add tag")
+                        .desired_width(100.0),
+                );
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    editor.annotation.add_tag(&editor.new_tag);
+                    editor.new_tag.clear();
+                    response.request_focus();
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Rating:");
+                ui.add(egui::Slider::new(
+                    &mut editor.annotation.rating,
+                    0..=vst3_host::PluginAnnotation::MAX_RATING,
+                ));
+                ui.checkbox(&mut editor.annotation.favorite, "\u{2605} Favorite");
+            });
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                saved = ui.button("Save").clicked();
+                cancelled = ui.button("Cancel").clicked();
+            });
+        });
+        if saved {
+            if let Some(mut editor) = self.notes_editor.take() {
+                // A tag typed but not yet entered is kept too.
+                editor.annotation.add_tag(&editor.new_tag);
+                self.annotate_plugin(editor.path, editor.annotation);
+            }
+        } else if cancelled || modal.should_close() {
+            self.notes_editor = None;
+        }
+    }
+
+    /// "Compatibility Settings": which initialization steps to skip for one plugin, and
+    /// whether it must have a controller. Applies from the plugin's next load.
+    fn show_compat_modal(&mut self, ctx: &egui::Context) {
+        use vst3_host::PluginInitFlags;
+        const OPTIONS: [(PluginInitFlags, &str, &str); 5] = [
+            (
+                PluginInitFlags::SKIP_CONTROLLER_INIT,
+                "Skip controller",
+                "No parameters or editor, but audio and MIDI still work",
+            ),
+            (
+                PluginInitFlags::SKIP_GUI_CHECK,
+                "Skip editor check",
+                "Don't probe for an editor view while loading",
+            ),
+            (
+                PluginInitFlags::SKIP_STATE_TRANSFER,
+                "Skip state transfer",
+                "Don't sync restored state to the controller",
+            ),
+            (
+                PluginInitFlags::SKIP_BUS_NEGOTIATION,
+                "Skip bus activation",
+                "Keep the plugin's default audio and MIDI bus activation",
+            ),
+            (
+                PluginInitFlags::REQUIRE_CONTROLLER,
+                "Require controller",
+                "Fail the load instead of loading without parameters",
+            ),
+        ];
+        let Some((path, flags)) = self.compat_editor.as_mut() else {
+            return;
+        };
+        let mut saved = false;
+        let mut cancelled = false;
+        let modal = egui::Modal::new(egui::Id::new("plugin_compat_modal")).show(ctx, |ui| {
+            ui.heading(format!(
+                "Compatibility: {}",
+                get_plugin_name_from_path(path)
+            ));
+            ui.weak("For plugins that crash or hang while loading. Applies on the next load.");
+            ui.add_space(6.0);
+            for (flag, label, hover) in OPTIONS {
+                let mut on = flags.contains(flag);
+                if ui.checkbox(&mut on, label).on_hover_text(hover).changed() {
+                    flags.set(flag, on);
+                }
+            }
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                saved = ui.button("Save").clicked();
+                cancelled = ui.button("Cancel").clicked();
+            });
+        });
+        if saved {
+            if let Some((path, flags)) = self.compat_editor.take() {
+                self.set_init_flags(path, flags);
+            }
+        } else if cancelled || modal.should_close() {
+            self.compat_editor = None;
+        }
+    }
+
+    /// Store `flags` for `pattern` in the host and the preferences, and save them.
+    fn set_init_flags(&mut self, pattern: String, flags: vst3_host::PluginInitFlags) {
+        let _ = self
+            .host
+            .set_plugin_init_flags(std::path::Path::new(&pattern), flags);
+        if flags.is_empty() {
+            self.preferences.plugin_init_flags.remove(&pattern);
+        } else {
+            self.preferences.plugin_init_flags.insert(pattern, flags);
+        }
+        if let Err(e) = self.preferences.save() {
+            self.set_error(format!("Failed to save preferences: {e}"));
+        }
+    }
+
+    /// Offer to skip the step a plugin crashed in during the last run's load.
+    fn show_interrupted_load_modal(&mut self, ctx: &egui::Context) {
+        let Some((path, phase)) = self.interrupted_load.clone() else {
+            return;
+        };
+        let path = path.to_string_lossy().to_string();
+        let mut apply = false;
+        let mut dismissed = false;
+        let modal = egui::Modal::new(egui::Id::new("interrupted_load_modal")).show(ctx, |ui| {
+            ui.heading("A plugin crashed while loading");
+            ui.label(format!(
+                "{} took the inspector down during {}.",
+                get_plugin_name_from_path(&path),
+                load_phase_description(phase)
+            ));
+            ui.weak(&path);
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if phase.skip_flag().is_some() {
+                    apply = ui
+                        .button("Skip This Step Next Time")
+                        .on_hover_text("Change later under Compatibility Settings")
+                        .clicked();
                 }
+                dismissed = ui.button("Dismiss").clicked();
             });
+        });
+        if let (true, Some(flag)) = (apply, phase.skip_flag()) {
+            let flags = self.host.plugin_init_flags(std::path::Path::new(&path)) | flag;
+            self.set_init_flags(path, flags);
+        }
+        if apply || dismissed || modal.should_close() {
+            self.interrupted_load = None;
+        }
     }
 
     fn show_plugin_tab(&mut self, root_ui: &mut egui::Ui) {
+        self.show_parameter_reset_modal(root_ui.ctx());
+        self.show_copy_from_modal(root_ui.ctx());
+        self.show_randomize_modal(root_ui.ctx());
+
         // Left sidebar for plugin information
         egui::Panel::left("plugin_info_panel")
             .resizable(true)
@@ -1171,6 +2773,8 @@ impl VST3Inspector {
                 ui.add_space(8.0);
 
                 // Make the plugin information section scrollable
+                let mut bus_preset_action = None;
+                let mut bus_toggle = None;
                 egui::ScrollArea::vertical()
                     .id_salt("plugin_info_scroll")
                     .auto_shrink([false; 2])
@@ -1201,7 +2805,7 @@ impl VST3Inspector {
                                     ui.label(dash(&s.version));
                                     ui.end_row();
                                     ui.label("Category:");
-                                    ui.label(dash(&s.category));
+                                    ui.label(dash(&s.category_path.join(" › ")));
                                     ui.end_row();
                                     ui.label("Audio I/O:");
                                     ui.label(format!(
@@ -1226,6 +2830,19 @@ impl VST3Inspector {
                                 });
                             ui.add_space(8.0);
 
+                            if !plugin_info.known_issues.is_empty() {
+                                ui.label(
+                                    egui::RichText::new("Known issues")
+                                        .strong()
+                                        .color(ui.visuals().warn_fg_color),
+                                );
+                                ui.add_space(2.0);
+                                for issue in &plugin_info.known_issues {
+                                    ui.label(format!("• {issue}"));
+                                }
+                                ui.add_space(8.0);
+                            }
+
                             // Factory Information - collapsible
                             egui::CollapsingHeader::new("Factory Information")
                                 .id_salt("factory_info_header")
@@ -1317,25 +2934,82 @@ impl VST3Inspector {
                                             });
 
                                         ui.add_space(8.0);
+                                        let presets = self
+                                            .preferences
+                                            .bus_configs
+                                            .get(&self.plugin_path)
+                                            .map_or(&[][..], Vec::as_slice);
+                                        bus_preset_action = bus_presets_ui(
+                                            ui,
+                                            presets,
+                                            self.bus_preset.as_deref(),
+                                            &mut self.bus_preset_name,
+                                        );
+                                        ui.add_space(8.0);
 
-                                        if !info.audio_inputs.is_empty() {
-                                            ui.strong("Audio Inputs");
-                                            for bus in info.audio_inputs.iter() {
-                                                ui.label(format!(
-                                                    "  {} - {} channels",
-                                                    bus.name, bus.channel_count
-                                                ));
-                                            }
-                                            ui.add_space(4.0);
+                                        if let Some(plugin) =
+                                            self.audio.as_ref().and_then(|a| a.try_lock())
+                                        {
+                                            let active = |direction, count| -> Vec<bool> {
+                                                (0..count)
+                                                    .map(|i| {
+                                                        plugin.is_bus_active(
+                                                            vst3_host::MediaType::Audio,
+                                                            direction,
+                                                            i as i32,
+                                                        )
+                                                    })
+                                                    .collect()
+                                            };
+                                            self.active_audio_buses = (
+                                                active(
+                                                    vst3_host::BusDirection::Input,
+                                                    info.audio_inputs.len(),
+                                                ),
+                                                active(
+                                                    vst3_host::BusDirection::Output,
+                                                    info.audio_outputs.len(),
+                                                ),
+                                            );
                                         }
-
-                                        if !info.audio_outputs.is_empty() {
-                                            ui.strong("Audio Outputs");
-                                            for bus in info.audio_outputs.iter() {
-                                                ui.label(format!(
-                                                    "  {} - {} channels",
-                                                    bus.name, bus.channel_count
-                                                ));
+                                        let groups = [
+                                            (
+                                                "Audio Inputs",
+                                                vst3_host::BusDirection::Input,
+                                                &info.audio_inputs,
+                                                &self.active_audio_buses.0,
+                                            ),
+                                            (
+                                                "Audio Outputs",
+                                                vst3_host::BusDirection::Output,
+                                                &info.audio_outputs,
+                                                &self.active_audio_buses.1,
+                                            ),
+                                        ];
+                                        for (title, direction, buses, active) in groups {
+                                            if buses.is_empty() {
+                                                continue;
+                                            }
+                                            ui.strong(title);
+                                            for (i, bus) in buses.iter().enumerate() {
+                                                let mut is_active =
+                                                    active.get(i).copied().unwrap_or(true);
+                                                ui.horizontal(|ui| {
+                                                    if ui
+                                                        .checkbox(&mut is_active, "Active")
+                                                        .on_hover_text(
+                                                            "Inactive buses get no audio buffers",
+                                                        )
+                                                        .changed()
+                                                    {
+                                                        bus_toggle =
+                                                            Some((direction, i, is_active));
+                                                    }
+                                                    ui.label(format!(
+                                                        "{} - {} channels",
+                                                        bus.name, bus.channel_count
+                                                    ));
+                                                });
                                             }
                                             ui.add_space(4.0);
                                         }
@@ -1344,6 +3018,10 @@ impl VST3Inspector {
                                     });
                             }
 
+                            egui::CollapsingHeader::new("\u{2328} Keyboard Shortcuts")
+                                .id_salt("key_shortcuts_header")
+                                .show(ui, |ui| shortcuts_table(ui, &self.key_shortcuts));
+
                             // GUI Information - collapsible
                             egui::CollapsingHeader::new("GUI Information")
                                 .id_salt("gui_info_header")
@@ -1378,14 +3056,46 @@ impl VST3Inspector {
                             });
                         }
                     });
+                if let Some(action) = bus_preset_action {
+                    self.run_bus_preset_action(action);
+                }
+                if let Some((direction, index, active)) = bus_toggle {
+                    self.set_audio_bus_active(direction, index, active);
+                }
             });
 
         // Central panel for parameters
         egui::CentralPanel::default().show_inside(root_ui, |ui| {
             ui.add_space(8.0);
-            ui.heading("Parameter Control");
+            ui.horizontal(|ui| {
+                ui.heading("Parameter Control");
+                if self.modified_since_save() {
+                    ui.colored_label(egui::Color32::RED, "\u{1F534} Modified since last save");
+                }
+            });
             ui.add_space(8.0);
 
+            let mut travel_to = None;
+            egui::CollapsingHeader::new("Undo History")
+                .id_salt("undo_history_section")
+                .show(ui, |ui| {
+                    let params = self
+                        .plugin_info
+                        .as_ref()
+                        .and_then(|p| p.controller_info.as_ref())
+                        .map_or(&[][..], |c| c.parameters.as_slice());
+                    let name_of = |id: u32| {
+                        params
+                            .iter()
+                            .find(|p| p.id == id)
+                            .map_or_else(|| format!("#{id}"), |p| p.title.clone())
+                    };
+                    travel_to = undo_history_view::show(ui, &self.undo_stack, name_of);
+                });
+            if let Some(position) = travel_to {
+                self.time_travel(position);
+            }
+
             // Clone the plugin info to avoid borrowing issues
             let plugin_info_clone = self.plugin_info.clone();
 
@@ -1473,14 +3183,15 @@ impl VST3Inspector {
 
                             ui.label("Filter:");
                             let filter_changed = egui::ComboBox::from_label("")
-                                .selected_text(format!("{:?}", self.parameter_filter))
+                                .selected_text(self.parameter_filter.label())
                                 .show_ui(ui, |ui| {
-                                    ui.selectable_value(
-                                        &mut self.parameter_filter,
-                                        ParameterFilter::All,
-                                        "All Parameters",
-                                    )
-                                    .clicked()
+                                    let picked = ui
+                                        .selectable_value(
+                                            &mut self.parameter_filter,
+                                            ParameterFilter::All,
+                                            "All Parameters",
+                                        )
+                                        .clicked()
                                         || ui
                                             .selectable_value(
                                                 &mut self.parameter_filter,
@@ -1508,12 +3219,40 @@ impl VST3Inspector {
                                                 ParameterFilter::HasUnits,
                                                 "Has Units",
                                             )
-                                            .clicked()
+                                            .clicked();
+                                    let in_range = matches!(
+                                        self.parameter_filter,
+                                        ParameterFilter::InRange { .. }
+                                    );
+                                    let picked_range =
+                                        ui.selectable_label(in_range, "Value Range").clicked();
+                                    if picked_range && !in_range {
+                                        self.parameter_filter =
+                                            ParameterFilter::InRange { min: 0.0, max: 1.0 };
+                                    }
+                                    picked || picked_range
                                 })
                                 .inner
                                 .unwrap_or(false);
 
-                            if filter_changed {
+                            let mut range_changed = false;
+                            if let ParameterFilter::InRange { min, max } =
+                                &mut self.parameter_filter
+                            {
+                                ui.label("min");
+                                range_changed |= ui
+                                    .add(egui::DragValue::new(min).range(0.0..=1.0).speed(0.01))
+                                    .changed();
+                                ui.label("max");
+                                range_changed |= ui
+                                    .add(egui::DragValue::new(max).range(0.0..=1.0).speed(0.01))
+                                    .changed();
+                                if *min > *max {
+                                    std::mem::swap(min, max);
+                                }
+                            }
+
+                            if filter_changed || range_changed {
                                 self.current_page = 0;
                             }
 
@@ -1522,13 +3261,60 @@ impl VST3Inspector {
                             if modified_changed.changed() {
                                 self.current_page = 0;
                             }
-                        });
-                        ui.add_space(4.0);
-                    });
 
-                    ui.add_space(8.0);
+                            ui.separator();
 
-                    // Pagination and table
+                            if ui
+                                .button("Reset All")
+                                .on_hover_text("Reset every writable parameter to its default")
+                                .clicked()
+                            {
+                                self.pending_parameter_reset = Some(ParameterReset::All);
+                            }
+                            if ui
+                                .button("Reset Modified")
+                                .on_hover_text(
+                                    "Reset only parameters that differ from their default",
+                                )
+                                .clicked()
+                            {
+                                self.pending_parameter_reset = Some(ParameterReset::Modified);
+                            }
+                            if ui
+                                .button("Factory Reset")
+                                .on_hover_text(
+                                    "Reload the plugin from scratch with its built-in defaults",
+                                )
+                                .clicked()
+                            {
+                                self.pending_parameter_reset = Some(ParameterReset::Factory);
+                            }
+                            if ui
+                                .button("Copy from\u{2026}")
+                                .on_hover_text(
+                                    "Copy parameter values from another plugin instance, \
+                                     matched by name",
+                                )
+                                .clicked()
+                            {
+                                self.copy_from_open = true;
+                            }
+                            if ui
+                                .button("\u{1F3B2} Randomize\u{2026}")
+                                .on_hover_text("Set parameters to random values")
+                                .clicked()
+                            {
+                                self.randomize_open = true;
+                            }
+                        });
+                        ui.add_space(4.0);
+                        self.show_morph_control(ui);
+                        ui.add_space(4.0);
+                    });
+
+                    ui.add_space(8.0);
+
+                    // Pagination and table
                     if !filtered_params.is_empty() {
                         let total_pages = filtered_params.len().div_ceil(self.items_per_page);
                         let start_idx = self.current_page * self.items_per_page;
@@ -1616,7 +3402,7 @@ impl VST3Inspector {
                 } else {
                     ui.vertical_centered(|ui| {
                         ui.add_space(50.0);
-                        ui.label("No controller information available");
+                        ui.label("This plugin has no controller interface");
                     });
                 }
             } else {
@@ -1651,27 +3437,27 @@ impl VST3Inspector {
             .column(Column::auto().at_least(50.0)) // Steps
             .column(Column::auto().at_least(80.0)) // Actions
             .header(20.0, |mut header| {
-                header.col(|ui| {
-                    ui.strong("Index");
-                });
-                header.col(|ui| {
-                    ui.strong("ID");
-                });
-                header.col(|ui| {
-                    ui.strong("Parameter Name");
-                });
-                header.col(|ui| {
-                    ui.strong("Value");
-                });
-                header.col(|ui| {
-                    ui.strong("Default");
-                });
-                header.col(|ui| {
-                    ui.strong("Units");
-                });
-                header.col(|ui| {
-                    ui.strong("Steps");
-                });
+                for (column, title) in [
+                    (SortColumn::Index, "Index"),
+                    (SortColumn::Id, "ID"),
+                    (SortColumn::Name, "Parameter Name"),
+                    (SortColumn::Value, "Value"),
+                    (SortColumn::Default, "Default"),
+                    (SortColumn::Units, "Units"),
+                    (SortColumn::Steps, "Steps"),
+                ] {
+                    header.col(|ui| {
+                        let text = egui::RichText::new(self.current_sort.header(column, title));
+                        if ui
+                            .add(egui::Button::new(text.strong()).frame(false))
+                            .on_hover_text("Sort by this column; click again to reverse")
+                            .clicked()
+                        {
+                            self.current_sort.click(column);
+                            ui.ctx().request_repaint();
+                        }
+                    });
+                }
                 header.col(|ui| {
                     ui.strong("Actions");
                 });
@@ -1681,7 +3467,6 @@ impl VST3Inspector {
                     let is_selected = self.selected_parameter == Some(*original_index);
                     let is_modified =
                         (param.current_value - param.default_normalized_value).abs() > 0.001;
-                    let is_read_only = (param.flags & 0x1) != 0;
 
                     body.row(30.0, |mut row| {
                         // Index
@@ -1708,99 +3493,40 @@ impl VST3Inspector {
                             } else {
                                 ui.label(&param.title);
                             }
+                            if let Some(m) = self
+                                .factory_mismatches
+                                .iter()
+                                .find(|m| m.param_id == param.id)
+                            {
+                                ui.colored_label(egui::Color32::YELLOW, "\u{26a0}")
+                                    .on_hover_text(format!(
+                                        "Loaded at {:.4}, not its factory default {:.4}",
+                                        m.actual_normalized, m.expected_normalized
+                                    ));
+                            }
                         });
 
                         // Current Value - Inline Editor
                         row.col(|ui| {
-                            if is_read_only {
-                                // Read-only parameters - just show the value
-                                ui.add_enabled(false, |ui: &mut egui::Ui| {
-                                    ui.label(format!("{:.3}", param.current_value))
-                                });
-                            } else {
-                                // Editable parameters - show slider or drag value
-                                let mut new_value = param.current_value as f32;
-                                let step_size = if param.step_count > 0 {
-                                    1.0 / param.step_count as f32
-                                } else {
-                                    0.001
-                                };
-
-                                let is_being_edited = self.parameter_being_edited == Some(param.id);
-
-                                ui.horizontal(|ui| {
-                                    let _response =
-                                        if param.step_count > 0 && param.step_count <= 10 {
-                                            // For parameters with few steps, use a combo box
-                                            let current_step = (param.current_value
-                                                * param.step_count as f64)
-                                                .round()
-                                                as i32;
-                                            let mut selected_step = current_step;
-
-                                            let combo_response = egui::ComboBox::from_id_salt(
-                                                format!("param_{}", param.id),
-                                            )
-                                            .selected_text(format!("{}", current_step))
-                                            .width(60.0)
-                                            .show_ui(ui, |ui| {
-                                                let mut changed = false;
-                                                for step in 0..=param.step_count {
-                                                    if ui
-                                                        .selectable_value(
-                                                            &mut selected_step,
-                                                            step,
-                                                            format!("{}", step),
-                                                        )
-                                                        .clicked()
-                                                    {
-                                                        changed = true;
-                                                    }
-                                                }
-                                                changed
-                                            });
-
-                                            if combo_response.inner.unwrap_or(false) {
-                                                new_value =
-                                                    selected_step as f32 / param.step_count as f32;
-                                                self.parameter_being_edited = Some(param.id);
-                                                if let Err(e) = self
-                                                    .set_parameter_value(param.id, new_value as f64)
-                                                {
-                                                    self.set_error(format!(
-                                                        "Failed to set parameter: {e}"
-                                                    ));
-                                                }
-                                            }
-                                            combo_response.response
-                                        } else {
-                                            // For continuous parameters, use a compact slider
-                                            let slider_response = ui.add_sized(
-                                                [100.0, 20.0],
-                                                egui::Slider::new(&mut new_value, 0.0..=1.0)
-                                                    .step_by(step_size as f64)
-                                                    .show_value(false),
-                                            );
-
-                                            if slider_response.changed() {
-                                                self.parameter_being_edited = Some(param.id);
-                                                if let Err(e) = self
-                                                    .set_parameter_value(param.id, new_value as f64)
-                                                {
-                                                    self.set_error(format!(
-                                                        "Failed to set parameter: {e}"
-                                                    ));
-                                                }
-                                            }
-
-                                            if slider_response.drag_stopped() {
-                                                self.parameter_being_edited = None;
-                                            }
-
-                                            slider_response
-                                        };
+                            let is_being_edited = self.parameter_being_edited == Some(param.id);
+                            ui.horizontal(|ui| {
+                                // Sliders move along the parameter's curve; the value shown is
+                                // still the normalized one.
+                                let curves = Arc::clone(&self.slider_curves);
+                                let curve = slider_curve(&curves, param.id);
+                                let (response, new_value) = param_widget::show(ui, param, curve);
+                                if let Some(value) = new_value {
+                                    self.parameter_being_edited = Some(param.id);
+                                    if let Err(e) = self.edit_parameter_value(param.id, value) {
+                                        self.set_error(format!("Failed to set parameter: {e}"));
+                                    }
+                                }
+                                if response.drag_stopped() {
+                                    self.parameter_being_edited = None;
+                                }
 
-                                    // Show numeric value with enhanced visual feedback
+                                // Read-only labels already show the value.
+                                if param.widget != vst3_host::WidgetType::Label {
                                     let color = if is_being_edited {
                                         egui::Color32::YELLOW
                                     } else if is_modified {
@@ -1808,9 +3534,10 @@ impl VST3Inspector {
                                     } else {
                                         ui.style().visuals.text_color()
                                     };
-                                    ui.colored_label(color, format!("{:.3}", param.current_value));
-                                });
-                            }
+                                    ui.colored_label(color, param_widget::value_text(param));
+                                }
+                                self.show_parameter_tooltip(&response, param);
+                            });
                         });
 
                         // Default Value
@@ -1864,6 +3591,38 @@ impl VST3Inspector {
             });
     }
 
+    /// The value column's rich tooltip for `param`, opened after a short hover and kept up
+    /// while its slider is dragged so the values follow the drag.
+    fn show_parameter_tooltip(&mut self, response: &egui::Response, param: &ParameterInfo) {
+        let now = Instant::now();
+        let active = response.contains_pointer() || response.dragged();
+        let pointer = response.ctx.pointer_latest_pos();
+        if !self.param_tooltip.update(param.id, active, pointer, now) {
+            if let Some(left) = self.param_tooltip.remaining(now).filter(|_| active) {
+                response.ctx.request_repaint_after(left);
+            }
+            return;
+        }
+        // The audio thread holds the plugin while processing; skip the display value rather
+        // than wait for it.
+        let display = self
+            .audio
+            .as_ref()
+            .and_then(|audio| audio.try_lock())
+            .and_then(|plugin| plugin.format_parameter(param.id, param.current_value).ok());
+        let rows = param_tooltip::tooltip_rows(
+            param,
+            display.as_deref(),
+            self.observed_ranges.get(param.id),
+        );
+        let shown = egui::Popup::from_response(response)
+            .kind(egui::PopupKind::Tooltip)
+            .show(|ui| param_tooltip::show_rows(ui, &rows, param.current_value));
+        if let Some(shown) = shown {
+            self.param_tooltip.set_rect(shown.response.rect);
+        }
+    }
+
     fn show_parameter_editor(&mut self, ui: &mut egui::Ui, param: &ParameterInfo) {
         ui.group(|ui| {
             ui.horizontal(|ui| {
@@ -1888,17 +3647,32 @@ impl VST3Inspector {
                             0.001
                         };
 
+                        // Continuous parameters move along their curve; the value shown is
+                        // still the normalized one.
+                        let curves = Arc::clone(&self.slider_curves);
+                        let curve = match param.step_count {
+                            0 => slider_curve(&curves, param.id),
+                            _ => &vst3_host::SliderCurve::Linear,
+                        };
+                        let mut position = curve.inverse(f64::from(new_value));
                         let slider_response = ui.add(
-                            egui::Slider::new(&mut new_value, 0.0..=1.0)
+                            egui::Slider::new(&mut position, 0.0..=1.0)
                                 .step_by(step_size as f64)
-                                .show_value(true),
+                                .custom_formatter(|position, _| {
+                                    format!("{:.3}", curve.apply(position))
+                                }),
                         );
 
                         if slider_response.changed() {
-                            if let Err(e) = self.set_parameter_value(param.id, new_value as f64) {
+                            new_value = curve.apply(position) as f32;
+                            if let Err(e) = self.edit_parameter_value(param.id, new_value as f64) {
                                 self.set_error(format!("Failed to set parameter: {e}"));
                             }
                         }
+
+                        if self.tempo_bpm > 0.0 && is_tempo_syncable(&param.title) {
+                            self.show_tempo_sync_picker(ui, param);
+                        }
                     });
 
                     ui.horizontal(|ui| {
@@ -1931,6 +3705,46 @@ impl VST3Inspector {
         });
     }
 
+    /// "Tempo Sync" dropdown for rate-like parameters: picking a note value sets the
+    /// parameter to that rate at the current tempo, within the assumed Hz range.
+    fn show_tempo_sync_picker(&mut self, ui: &mut egui::Ui, param: &ParameterInfo) {
+        ui.separator();
+        let sync = vst3_host::TempoSync::new(self.tempo_bpm);
+        let (min_hz, max_hz) = self.tempo_sync_range_hz;
+        let mut picked = None;
+        egui::ComboBox::from_id_salt(("tempo_sync", param.id))
+            .selected_text("Tempo Sync")
+            .show_ui(ui, |ui| {
+                for nv in vst3_host::NoteValue::ALL {
+                    let text = format!("{} ({:.2} Hz)", nv.label(), sync.hz(nv));
+                    if ui.selectable_label(false, text).clicked() {
+                        picked = Some(nv);
+                    }
+                }
+            })
+            .response
+            .on_hover_text(format!("Note values at {:.1} BPM", self.tempo_bpm));
+        ui.label("Hz range:");
+        ui.add(
+            egui::DragValue::new(&mut self.tempo_sync_range_hz.0)
+                .range(0.0..=max_hz)
+                .speed(0.01),
+        );
+        ui.add(
+            egui::DragValue::new(&mut self.tempo_sync_range_hz.1)
+                .range(min_hz..=1000.0)
+                .speed(0.1),
+        )
+        .on_hover_text("Rate range the plugin maps 0.0-1.0 onto (assumed linear)");
+
+        if let Some(nv) = picked {
+            let value = sync.normalized(nv, min_hz, max_hz);
+            if let Err(e) = self.set_parameter_value(param.id, value) {
+                self.set_error(format!("Failed to set parameter: {e}"));
+            }
+        }
+    }
+
     fn show_processing_tab(&mut self, root_ui: &mut egui::Ui) {
         egui::CentralPanel::default().show_inside(root_ui, |ui| {
             ui.add_space(8.0);
@@ -1974,6 +3788,15 @@ impl VST3Inspector {
                         ui.label("Audio Output:");
                         if self.audio.is_some() {
                             ui.colored_label(egui::Color32::GREEN, "Running");
+                            if let Some(monitor) = &self.stream_monitor {
+                                let stats = monitor.stats();
+                                ui.separator();
+                                ui.label(format!("Dropouts: {}", stats.dropout_count))
+                                    .on_hover_text(format!(
+                                        "{} xruns; slowest audio callback {:.2} ms",
+                                        stats.xrun_count, stats.callback_latency_max_ms
+                                    ));
+                            }
                         } else {
                             ui.colored_label(egui::Color32::RED, "Not running (no plugin loaded)");
                         }
@@ -1994,6 +3817,17 @@ impl VST3Inspector {
                                     ui.selectable_value(&mut self.sample_rate, rate, &rate_text);
                                 }
                             });
+                        if let Some(actual) = self.actual_sample_rate {
+                            ui.colored_label(
+                                ui.visuals().warn_fg_color,
+                                format!("(device running at {} Hz)", actual as u32),
+                            )
+                            .on_hover_text(format!(
+                                "The output device doesn't support {} Hz, so the plugin runs at \
+                                 the closest rate it offers",
+                                self.sample_rate as u32
+                            ));
+                        }
 
                         ui.separator();
                         ui.label("Block Size:");
@@ -2007,6 +3841,78 @@ impl VST3Inspector {
                                     ui.selectable_value(&mut self.block_size, size, &size_text);
                                 }
                             });
+
+                        ui.separator();
+                        ui.label("Tempo:");
+                        ui.add(
+                            egui::DragValue::new(&mut self.tempo_bpm)
+                                .range(20.0..=300.0)
+                                .speed(0.5)
+                                .suffix(" BPM"),
+                        )
+                        .on_hover_text(
+                            "Transport tempo advertised to the plugin (applied on load)",
+                        );
+
+                        ui.separator();
+                        ui.label("Meter:");
+                        egui::ComboBox::from_id_salt("meter_ballistics_selector")
+                            .selected_text(self.meter_ballistics.label())
+                            .show_ui(ui, |ui| {
+                                for preset in vst3_host::BallisticsPreset::ALL {
+                                    ui.selectable_value(
+                                        &mut self.meter_ballistics,
+                                        preset,
+                                        preset.label(),
+                                    );
+                                }
+                            })
+                            .response
+                            .on_hover_text("Output meter ballistics (applied on load)");
+                        let manual = vst3_host::MeterHoldMode::ManualReset;
+                        let auto = vst3_host::MeterHoldMode::default();
+                        let hold_label = |mode| {
+                            if mode == manual {
+                                "Hold: manual"
+                            } else {
+                                "Hold: 3 s"
+                            }
+                        };
+                        let before = self.meter_hold_mode;
+                        egui::ComboBox::from_id_salt("meter_hold_selector")
+                            .selected_text(hold_label(self.meter_hold_mode))
+                            .show_ui(ui, |ui| {
+                                for mode in [auto, manual] {
+                                    ui.selectable_value(
+                                        &mut self.meter_hold_mode,
+                                        mode,
+                                        hold_label(mode),
+                                    );
+                                }
+                            })
+                            .response
+                            .on_hover_text(
+                                "Drop the peak hold markers after 3 seconds, or only when reset",
+                            );
+                        if self.meter_hold_mode != before {
+                            self.apply_meter_hold_mode();
+                        }
+                        ui.separator();
+                        ui.label("Param Debounce:");
+                        ui.add(
+                            egui::DragValue::new(&mut self.param_debouncer.delay_ms)
+                                .range(0.0..=250.0)
+                                .speed(1.0)
+                                .suffix(" ms"),
+                        )
+                        .on_hover_text(
+                            "Send a dragged parameter to the plugin once it has been still this \
+                             long (0 sends every move)",
+                        );
+                        ui.separator();
+                        self.show_output_device_selector(ui);
+                        ui.separator();
+                        self.show_input_device_selector(ui);
                     });
 
                     ui.separator();
@@ -2019,16 +3925,18 @@ impl VST3Inspector {
                     ui.horizontal(|ui| {
                         // VU Meter
                         ui.group(|ui| {
-                            ui.label("Output Levels (VU Meter):");
-
-                            let (peak_left, peak_hold_left) = {
-                                let m = self.meter_left.lock().unwrap();
-                                (m.level(), m.peak_hold())
-                            };
-                            let (peak_right, peak_hold_right) = {
-                                let m = self.meter_right.lock().unwrap();
-                                (m.level(), m.peak_hold())
-                            };
+                            let ballistics = self
+                                .audio
+                                .as_ref()
+                                .map_or(self.meter_ballistics, |a| a.meter_ballistics());
+                            ui.label(format!("Output Levels ({}):", ballistics.label()));
+
+                            // Bars follow the configured ballistics (integrated on the audio
+                            // thread); the hold markers come from the PeakMeters.
+                            let [peak_left, peak_right] = self.meter_readings;
+                            let peak_hold_left = self.meter_left.lock().unwrap().peak_hold();
+                            let peak_hold_right = self.meter_right.lock().unwrap().peak_hold();
+                            let mut reset_hold = false;
 
                             // Convert to dB
                             const MIN_DB: f32 = -60.0;
@@ -2086,13 +3994,16 @@ impl VST3Inspector {
                                     // for the trailing dB readout).
                                     let bar_width =
                                         (ui.available_width() - 80.0).clamp(120.0, 360.0);
-                                    let bar_rect = ui
+                                    let bar = ui
                                         .add(
                                             egui::ProgressBar::new(bar_value)
                                                 .desired_width(bar_width)
                                                 .fill(color),
                                         )
-                                        .rect;
+                                        .interact(egui::Sense::click())
+                                        .on_hover_text("Click to reset the peak hold");
+                                    reset_hold |= bar.clicked();
+                                    let bar_rect = bar.rect;
 
                                     // Draw peak hold indicator as a vertical line
                                     if hold_value > 0.0 {
@@ -2142,13 +4053,16 @@ impl VST3Inspector {
                                     // for the trailing dB readout).
                                     let bar_width =
                                         (ui.available_width() - 80.0).clamp(120.0, 360.0);
-                                    let bar_rect = ui
+                                    let bar = ui
                                         .add(
                                             egui::ProgressBar::new(bar_value)
                                                 .desired_width(bar_width)
                                                 .fill(color),
                                         )
-                                        .rect;
+                                        .interact(egui::Sense::click())
+                                        .on_hover_text("Click to reset the peak hold");
+                                    reset_hold |= bar.clicked();
+                                    let bar_rect = bar.rect;
 
                                     // Draw peak hold indicator as a vertical line
                                     if hold_value > 0.0 {
@@ -2169,8 +4083,41 @@ impl VST3Inspector {
                                     ui.colored_label(color, db_text);
                                 });
                             });
+
+                            if self.meter_hold_mode == vst3_host::MeterHoldMode::ManualReset {
+                                reset_hold |= ui.button("Reset Hold").clicked();
+                            }
+                            if reset_hold {
+                                self.reset_peak_hold();
+                            }
+
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .checkbox(&mut self.auto_limiter, "Limiter")
+                                    .on_hover_text(
+                                        "Turn the output down instantly whenever it would clip, \
+                                         recovering over 200 ms",
+                                    )
+                                    .changed()
+                                {
+                                    self.apply_auto_limiter();
+                                }
+                                let reduction = self
+                                    .audio
+                                    .as_ref()
+                                    .filter(|_| self.auto_limiter)
+                                    .map_or(0.0, |a| a.gain_reduction_db());
+                                let color = if reduction > 0.05 {
+                                    egui::Color32::YELLOW
+                                } else {
+                                    ui.visuals().weak_text_color()
+                                };
+                                ui.colored_label(color, format!("GR: \u{2212}{reduction:.1} dB"));
+                            });
                         });
 
+                        ui.add_space(20.0);
+                        self.show_input_gain(ui);
                         ui.add_space(20.0);
 
                         // Panic buttons
@@ -2187,6 +4134,14 @@ impl VST3Inspector {
                         });
                     });
 
+                    egui::CollapsingHeader::new("Analysis")
+                        .default_open(false)
+                        .show(ui, |ui| {
+                            self.show_block_statistics(ui);
+                            ui.separator();
+                            self.show_spectrum(ui);
+                        });
+
                     ui.separator();
                     ui.add_space(8.0);
 
@@ -2226,6 +4181,55 @@ impl VST3Inspector {
                             );
                         });
 
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "Computer keyboard: A\u{2013}L play notes (W E T Y U O for \
+                                 sharps), Z/X shift octave (now {:+})",
+                                self.keyboard_input.octave_shift()
+                            ))
+                            .weak(),
+                        );
+                        self.show_keyboard_presets(ui);
+
+                        // Channels whose sustain pedal is down: their released notes keep
+                        // sounding until it comes up (or a MIDI Panic lifts it).
+                        if let Some(audio) = &self.audio {
+                            let sustained = audio.lock().sustained_channels();
+                            ui.horizontal(|ui| {
+                                ui.label("Sustain:");
+                                for (index, held) in sustained.into_iter().enumerate() {
+                                    let text = egui::RichText::new(format!("{}", index + 1));
+                                    if held {
+                                        ui.label(text.strong().color(egui::Color32::LIGHT_BLUE))
+                                            .on_hover_text(format!(
+                                                "Sustain pedal down on channel {}",
+                                                index + 1
+                                            ));
+                                    } else {
+                                        ui.label(text.weak());
+                                    }
+                                }
+                            });
+                        }
+
+                        let mut split_on = self.host.keyboard_split().is_some();
+                        if ui
+                            .checkbox(&mut split_on, "Split keyboard")
+                            .on_hover_text(
+                                "Play different key ranges on different MIDI channels \
+                                 (in General MIDI mode, different instances)",
+                            )
+                            .changed()
+                        {
+                            if split_on {
+                                self.host.set_keyboard_split(split_editor::default_split());
+                            } else {
+                                self.host.clear_keyboard_split();
+                            }
+                            self.save_keyboard_split();
+                        }
+                        self.show_voice_limit(ui);
+
                         ui.add_space(4.0);
                         // The keyboard is wider than most windows; scroll it horizontally
                         // rather than forcing the whole window wide.
@@ -2234,8 +4238,47 @@ impl VST3Inspector {
                             .show(ui, |ui| {
                                 self.draw_piano_keyboard(ui);
                             });
+
+                        if let Some(split) = self.host.keyboard_split() {
+                            let mut split = split.clone();
+                            if split_editor::show_region_list(ui, &mut split) {
+                                self.host.set_keyboard_split(split);
+                                self.save_keyboard_split();
+                            }
+                        }
                     });
 
+                    ui.add_space(4.0);
+                    egui::CollapsingHeader::new("Pressure \u{2192} Param")
+                        .id_salt("pressure_bindings_section")
+                        .show(ui, |ui| self.show_pressure_bindings(ui));
+                    egui::CollapsingHeader::new("MIDI Bindings")
+                        .id_salt("midi_bindings_section")
+                        .show(ui, |ui| self.show_midi_bindings(ui));
+                    egui::CollapsingHeader::new("Arpeggiator")
+                        .id_salt("arpeggiator_section")
+                        .show(ui, |ui| self.show_arpeggiator(ui));
+                    egui::CollapsingHeader::new("Channel Strips")
+                        .id_salt("channel_strips_section")
+                        .show(ui, |ui| {
+                            let changed = self.channel_strips.show(ui);
+                            self.apply_channel_strips(&changed);
+                        });
+                    egui::CollapsingHeader::new("Velocity Curve")
+                        .id_salt("velocity_curve_section")
+                        .show(ui, |ui| {
+                            if velocity_curve_editor::show(ui, &mut self.preferences.velocity_curve)
+                            {
+                                self.apply_velocity_curve();
+                                if let Err(e) = self.preferences.save() {
+                                    eprintln!("Failed to save velocity curve: {e}");
+                                }
+                            }
+                        });
+                    egui::CollapsingHeader::new("MIDI Routing")
+                        .id_salt("midi_routing_section")
+                        .show(ui, |ui| self.show_midi_routing(ui));
+
                     ui.separator();
                     ui.add_space(8.0);
 
@@ -2336,73 +4379,1193 @@ impl VST3Inspector {
                         .id_salt("midi_input_section")
                         .default_open(true)
                         .show(ui, |ui| self.show_midi_input_device(ui));
+
+                    egui::CollapsingHeader::new("Virtual MIDI Out")
+                        .id_salt("virtual_midi_out_section")
+                        .show(ui, |ui| self.show_virtual_midi_out(ui));
+
+                    egui::CollapsingHeader::new("Plugin Log")
+                        .id_salt("plugin_log_section")
+                        .show(ui, |ui| self.show_plugin_log(ui));
+
+                    egui::CollapsingHeader::new("Plugin Graph")
+                        .id_salt("plugin_graph_section")
+                        .show(ui, |ui| self.show_plugin_graph(ui));
+
+                    egui::CollapsingHeader::new("General MIDI Channels")
+                        .id_salt("gm_section")
+                        .show(ui, |ui| self.show_gm_mode(ui));
+
+                    egui::CollapsingHeader::new("Frequency Response")
+                        .id_salt("freq_response_section")
+                        .show(ui, |ui| self.show_frequency_response(ui));
+
+                    egui::CollapsingHeader::new("Health")
+                        .id_salt("health_section")
+                        .default_open(true)
+                        .show(ui, |ui| self.show_output_health(ui));
+
+                    egui::CollapsingHeader::new("Timing")
+                        .id_salt("timing_section")
+                        .show(ui, |ui| self.show_callback_timing(ui));
                 }); // processing_scroll
         });
     }
 
-    /// Live hardware MIDI input: pick a connected controller and forward its MIDI to the plugin.
-    fn show_midi_input_device(&mut self, ui: &mut egui::Ui) {
-        ui.group(|ui| {
-            ui.label(egui::RichText::new("MIDI Input Device").strong());
-            ui.horizontal(|ui| {
-                let current = self
-                    .midi_input
-                    .connected_port()
-                    .unwrap_or("None")
-                    .to_string();
-                egui::ComboBox::from_id_salt("midi_input_port")
-                    .selected_text(current)
-                    .show_ui(ui, |ui| {
-                        if ui
-                            .selectable_label(!self.midi_input.is_connected(), "None")
-                            .clicked()
-                        {
-                            self.midi_input.disconnect();
-                        }
-                        // Clone the cached list so we don't borrow self across connect().
-                        for (i, name) in self.midi_input_ports.clone().iter().enumerate() {
-                            let selected = self.midi_input.connected_port() == Some(name.as_str());
-                            if ui.selectable_label(selected, name).clicked() {
-                                match self.midi_input.connect(i) {
-                                    Ok(()) => self.set_error(format!("Listening to MIDI: {name}")),
-                                    Err(e) => self.set_error(format!("MIDI input: {e}")),
-                                }
-                            }
-                        }
-                    });
-                if ui.button("Refresh").clicked() {
-                    self.midi_input_ports = MidiInputState::list_ports();
-                }
-                if self.midi_input_ports.is_empty() {
-                    ui.label("(no MIDI inputs found)");
-                }
-            });
-            if self.midi_input.is_connected() && self.audio.is_none() {
-                ui.colored_label(
-                    egui::Color32::YELLOW,
-                    "Load a plugin to hear incoming MIDI.",
-                );
-            }
-        });
+    /// How regularly the audio callbacks have arrived since the stream started.
+    fn show_callback_timing(&self, ui: &mut egui::Ui) {
+        let Some(monitor) = &self.stream_monitor else {
+            ui.label("Load a plugin to time its audio callbacks.");
+            return;
+        };
+        timing_view::show(ui, &monitor.timing_stats());
     }
 
-    /// Log a forwarded hardware-MIDI event into the monitor as Input.
-    fn log_incoming_midi(&self, ev: vst3_host::midi::MidiEvent) {
-        use vst3_host::midi::MidiEvent as Ev;
-        let (ty, ch, d1, d2): (u16, u8, u8, u8) = match ev {
-            Ev::NoteOn {
-                channel,
-                note,
-                velocity,
-            } => (0, channel.as_index(), note, velocity),
-            Ev::NoteOff {
-                channel,
-                note,
-                velocity,
-            } => (1, channel.as_index(), note, velocity),
-            Ev::PolyAftertouch {
-                channel,
-                note,
+    /// The output distribution of the playing plugin since processing last started.
+    fn show_block_statistics(&self, ui: &mut egui::Ui) {
+        let Some(shared) = &self.block_statistics else {
+            ui.label("Load a plugin to analyse its output.");
+            return;
+        };
+        // The audio thread publishes every block; the snapshot is copied out so it isn't held.
+        let stats = *shared.read().unwrap_or_else(|e| e.into_inner());
+        block_stats_view::show(ui, &stats);
+    }
+
+    /// The live spectrum of the playing plugin's output.
+    fn show_spectrum(&mut self, ui: &mut egui::Ui) {
+        let Some(shared) = &self.spectrum else {
+            ui.label("Load a plugin to see its spectrum.");
+            return;
+        };
+        let shared = Arc::clone(shared);
+        let changed = {
+            let analyzer = shared.lock().unwrap_or_else(|e| e.into_inner());
+            spectrum_view::show(
+                ui,
+                &analyzer,
+                self.sample_rate,
+                &mut self.spectrum_window,
+                &mut self.spectrum_averaging,
+            )
+        };
+        if changed {
+            self.apply_spectrum_settings();
+        }
+        ui.ctx()
+            .request_repaint_after(std::time::Duration::from_millis(50));
+    }
+
+    /// Set the spectrum's window and averaging to the selected ones.
+    fn apply_spectrum_settings(&self) {
+        if let Some(shared) = &self.spectrum {
+            let mut analyzer = shared.lock().unwrap_or_else(|e| e.into_inner());
+            analyzer.set_window(self.spectrum_window);
+            analyzer.set_averaging(self.spectrum_averaging);
+        }
+    }
+
+    /// Sweep a parameter and show the plugin's frequency response at each value.
+    fn show_frequency_response(&mut self, ui: &mut egui::Ui) {
+        let params: Vec<(u32, String)> = self
+            .plugin_info
+            .as_ref()
+            .and_then(|info| info.controller_info.as_ref())
+            .map(|controller| {
+                controller
+                    .parameters
+                    .iter()
+                    .map(|p| (p.id, p.title.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !self.freq_response.show(ui, &params) {
+            return;
+        }
+        let (Some(audio), Some(param_id)) = (self.audio.as_ref(), self.freq_response.param_id)
+        else {
+            return;
+        };
+        let result = {
+            let mut plugin = audio.lock();
+            let mut impulse = vec![0.0; FREQ_SWEEP_IMPULSE_LEN];
+            impulse[0] = 1.0;
+            vst3_host::ParameterSweep::frequency_response(
+                &mut plugin,
+                param_id,
+                &impulse,
+                self.freq_response.steps,
+            )
+            .map(|points| {
+                let bin_hz = vst3_host::ParameterSweep::bin_frequencies(
+                    FREQ_SWEEP_IMPULSE_LEN,
+                    plugin.sample_rate(),
+                );
+                (points, bin_hz)
+            })
+        };
+        match result {
+            Ok((points, bin_hz)) => {
+                self.freq_response.set_response(points, bin_hz);
+                let _ = self.refresh_parameter_values();
+            }
+            Err(e) => self.set_error(format!("Frequency sweep failed: {e}")),
+        }
+    }
+
+    /// General MIDI mode: play sixteen instances of the loaded plugin, one per MIDI channel,
+    /// from the MIDI input device, with a channel strip to mix them.
+    fn show_gm_mode(&mut self, ui: &mut egui::Ui) {
+        let Some(gm) = self.gm_audio.as_ref() else {
+            ui.label(
+                "Loads 16 instances of the loaded plugin, one per MIDI channel, and routes the \
+                 MIDI input device to them by channel.",
+            );
+            if ui
+                .add_enabled(
+                    !self.plugin_path.is_empty(),
+                    egui::Button::new("Enable GM Mode"),
+                )
+                .clicked()
+            {
+                self.enable_gm_mode();
+            }
+            return;
+        };
+
+        let mut disable = false;
+        ui.horizontal(|ui| {
+            ui.label(format!("{} \u{00d7} 16", self.gm_plugin_name));
+            if ui.button("Panic").clicked() {
+                let _ = gm.lock().midi_panic();
+            }
+            disable = ui.button("Disable GM Mode").clicked();
+            ui.toggle_value(&mut self.gm_mixer_view, "Mixer")
+                .on_hover_text("Show the channels as fader strips");
+        });
+        self.gm_strip.show_output(ui, gm.output_peak());
+        if self.gm_mixer_view {
+            let mut bus = gm.lock();
+            let names: Vec<String> = (0..bus.channel_count() as u8)
+                .map(|ch| bus.name(ch).unwrap_or_default().to_string())
+                .collect();
+            let levels = bus.levels();
+            self.gm_mixer.show(ui, bus.mix_bus_mut(), &names, &levels);
+        } else {
+            let error = self.gm_strip.show(ui, &mut gm.lock());
+            if let Some(e) = error {
+                self.set_error(e);
+            }
+        }
+        if disable {
+            self.gm_audio = None;
+            self.set_error("General MIDI mode off");
+        }
+    }
+
+    /// Output analysis of the playing plugin: silent, clipping and NaN/Inf blocks so far.
+    fn show_output_health(&mut self, ui: &mut egui::Ui) {
+        let Some(audio) = self.audio.as_ref() else {
+            ui.label("Start audio to monitor the plugin's output.");
+            return;
+        };
+        // Keep the last report when the audio thread holds the lock.
+        if let Some(plugin) = audio.try_lock() {
+            self.output_health = plugin.output_analysis();
+        }
+        let Some(report) = self.output_health else {
+            ui.label("Output analysis is disabled.");
+            return;
+        };
+
+        egui::Grid::new("output_health")
+            .num_columns(3)
+            .spacing([12.0, 4.0])
+            .show(ui, |ui| {
+                ui.label("Blocks");
+                ui.label(report.blocks.to_string());
+                ui.label("");
+                ui.end_row();
+
+                ui.label("Silent");
+                ui.label(report.silence_blocks.to_string());
+                ui.label(
+                    if report.silence_blocks == report.blocks && report.blocks > 0 {
+                        "\u{26a0}\u{fe0f}"
+                    } else {
+                        "\u{2705}"
+                    },
+                )
+                .on_hover_text("Blocks entirely below -100 dBFS");
+                ui.end_row();
+
+                ui.label("Clipping");
+                ui.label(report.clipping_blocks.to_string());
+                ui.label(if report.clipping_blocks > 0 {
+                    "\u{26a0}\u{fe0f}"
+                } else {
+                    "\u{2705}"
+                })
+                .on_hover_text("Blocks with a sample beyond \u{00b1}1.0");
+                ui.end_row();
+
+                ui.label("NaN / Inf");
+                ui.label(report.nan_blocks.to_string());
+                ui.label(if report.nan_blocks > 0 {
+                    "\u{1f534}"
+                } else {
+                    "\u{2705}"
+                })
+                .on_hover_text("Blocks with non-finite samples; they are silenced");
+                ui.end_row();
+            });
+
+        if let Some(index) = report.last_nan_sample_index {
+            ui.label(format!("Last non-finite sample at frame {index}"));
+        }
+        if report.needs_recovery {
+            ui.colored_label(
+                egui::Color32::from_rgb(230, 80, 80),
+                "The plugin produced NaN/Inf output and may need a Factory Reset.",
+            );
+        }
+        if ui.button("Reset Counters").clicked() {
+            audio.lock().reset_output_analysis();
+            self.output_health = None;
+        }
+    }
+
+    fn enable_gm_mode(&mut self) {
+        let path = std::path::PathBuf::from(&self.plugin_path);
+        let result = self
+            .host
+            .enable_gm_mode(&path)
+            .and_then(|bus| self.host.play_gm(bus));
+        match result {
+            Ok(handle) => {
+                self.gm_audio = Some(handle);
+                self.gm_plugin_name = get_plugin_name_from_path(&self.plugin_path);
+                self.gm_strip = GeneralMidiChannelStrip::default();
+                self.gm_mixer = MixBusView::default();
+                self.set_error("General MIDI mode on: MIDI input is routed by channel");
+            }
+            Err(e) => self.set_error(format!("Failed to enable GM mode: {e}")),
+        }
+    }
+
+    /// Routing graph editor: add plugin nodes, wire them, and see the processing order.
+    fn show_plugin_graph(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.plugin_path.is_empty(),
+                    egui::Button::new("Add Loaded"),
+                )
+                .on_hover_text("Add the loaded plugin as a node")
+                .clicked()
+            {
+                let ports = self
+                    .plugin_info
+                    .as_ref()
+                    .and_then(|i| i.component_info.as_ref())
+                    .map(|c| vst3_host::NodePorts {
+                        audio_in: !c.audio_inputs.is_empty(),
+                        audio_out: !c.audio_outputs.is_empty(),
+                        midi_in: !c.event_inputs.is_empty(),
+                        midi_out: !c.event_outputs.is_empty(),
+                    })
+                    .unwrap_or_default();
+                let path = self.plugin_path.clone();
+                self.add_graph_node(path, ports);
+                changed = true;
+            }
+
+            let selected = self
+                .graph_add_path
+                .as_deref()
+                .map(get_plugin_name_from_path)
+                .unwrap_or_else(|| "Choose plugin...".to_string());
+            egui::ComboBox::from_id_salt("graph_add_plugin")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for path in &self.discovered_plugins {
+                        ui.selectable_value(
+                            &mut self.graph_add_path,
+                            Some(path.clone()),
+                            get_plugin_name_from_path(path),
+                        );
+                    }
+                });
+            if ui
+                .add_enabled(self.graph_add_path.is_some(), egui::Button::new("Add"))
+                .on_hover_text("Add the chosen plugin as a node (all connectors shown)")
+                .clicked()
+            {
+                if let Some(path) = self.graph_add_path.clone() {
+                    self.add_graph_node(path, vst3_host::NodePorts::ALL);
+                    changed = true;
+                }
+            }
+            if ui
+                .button("Add Mix Bus")
+                .on_hover_text("Add a node that sums every audio wire into it")
+                .clicked()
+            {
+                let graph = &mut self.preferences.plugin_graph;
+                graph.add_mix_bus("Mix Bus", next_node_position(graph));
+                changed = true;
+            }
+            if ui.button("Clear").clicked() {
+                self.preferences.plugin_graph = vst3_host::AudioGraph::new();
+                changed = true;
+            }
+        });
+        ui.label(
+            egui::RichText::new(
+                "Drag an output connector onto an input of the same kind to wire it; \
+                 right-click a node to remove it.",
+            )
+            .weak(),
+        );
+
+        let edit = self
+            .graph_editor
+            .show(ui, &mut self.preferences.plugin_graph, 280.0);
+        if let Some(e) = edit.error {
+            self.set_error(e);
+        }
+
+        let graph = &self.preferences.plugin_graph;
+        match vst3_host::PluginChain::from_graph(graph) {
+            Ok(chain) if chain.steps().is_empty() => {}
+            Ok(chain) => {
+                let names: Vec<&str> = chain
+                    .order()
+                    .into_iter()
+                    .filter_map(|id| graph.node(id))
+                    .map(|n| n.name.as_str())
+                    .collect();
+                ui.label(format!("Processing order: {}", names.join(" \u{2192} ")));
+            }
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, e.to_string());
+            }
+        }
+
+        // Persist layout changes once a drag ends rather than on every moved pixel.
+        let dragging = ui.ctx().input(|i| i.pointer.any_down());
+        if changed || (edit.changed && !dragging) || (self.graph_dirty && !dragging) {
+            self.graph_dirty = false;
+            if let Err(e) = self.preferences.save() {
+                self.set_error(format!("Failed to save plugin graph: {e}"));
+            }
+        } else if edit.changed {
+            self.graph_dirty = true;
+        }
+    }
+
+    /// Add a node for `path`, placed in a loose cascade so new nodes don't stack exactly.
+    fn add_graph_node(&mut self, path: String, ports: vst3_host::NodePorts) {
+        let graph = &mut self.preferences.plugin_graph;
+        let position = next_node_position(graph);
+        let name = get_plugin_name_from_path(&path);
+        graph.add_node(path, name, ports, position);
+    }
+
+    /// Move newly captured plugin output into the bounded log buffer.
+    fn drain_plugin_log(&mut self) {
+        let Some(rx) = &self.plugin_log_rx else {
+            return;
+        };
+        while let Ok(line) = rx.try_recv() {
+            if self.plugin_log.len() == PLUGIN_LOG_LINES {
+                self.plugin_log.pop_front();
+            }
+            self.plugin_log.push_back(line);
+        }
+    }
+
+    /// The last lines the plugin printed, when its output is captured.
+    fn show_plugin_log(&mut self, ui: &mut egui::Ui) {
+        if self.plugin_log_rx.is_none() {
+            ui.label(
+                "Plugin output is captured only for process-isolated plugins; \
+                 this one prints to the inspector's console.",
+            );
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label(format!("Last {} lines", self.plugin_log.len()));
+            if ui.button("Clear").clicked() {
+                self.plugin_log.clear();
+            }
+        });
+        egui::ScrollArea::vertical()
+            .id_salt("plugin_log_scroll")
+            .max_height(200.0)
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for line in &self.plugin_log {
+                    ui.monospace(line);
+                }
+            });
+    }
+
+    /// Bind aftertouch / channel pressure to a parameter (added to the MIDI bindings), and
+    /// send a test pressure through the bindings.
+    fn show_pressure_bindings(&mut self, ui: &mut egui::Ui) {
+        if self.audio.is_none() {
+            ui.label("Load a plugin to bind pressure to its parameters.");
+            return;
+        }
+        let params: Vec<(u32, String)> = self
+            .plugin_info
+            .as_ref()
+            .and_then(|i| i.controller_info.as_ref())
+            .map(|c| {
+                c.parameters
+                    .iter()
+                    .map(|p| (p.id, p.title.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let title_of = |id: u32| {
+            params
+                .iter()
+                .find(|(pid, _)| *pid == id)
+                .map_or_else(|| format!("#{id}"), |(_, t)| t.clone())
+        };
+        let editor = &mut self.pressure_binding;
+        let selected_channel = self.selected_midi_channel as u8;
+
+        ui.horizontal(|ui| {
+            ui.label("Parameter:");
+            egui::ComboBox::from_id_salt("pressure_binding_param")
+                .selected_text(editor.param_id.map_or("Select...".to_string(), title_of))
+                .show_ui(ui, |ui| {
+                    for (id, title) in &params {
+                        ui.selectable_value(&mut editor.param_id, Some(*id), title);
+                    }
+                });
+            ui.radio_value(&mut editor.poly, true, "Poly Aftertouch");
+            ui.radio_value(&mut editor.poly, false, "Channel Pressure");
+        });
+        ui.horizontal(|ui| {
+            if editor.poly {
+                ui.checkbox(&mut editor.any_note, "Any note");
+                ui.add_enabled(
+                    !editor.any_note,
+                    egui::DragValue::new(&mut editor.note)
+                        .range(0..=127)
+                        .custom_formatter(|n, _| midi_note_to_name(n as u8)),
+                );
+            }
+            ui.checkbox(&mut editor.any_channel, "Any channel")
+                .on_hover_text("Otherwise the keyboard's MIDI channel");
+            ui.label("Range:");
+            ui.add(
+                egui::DragValue::new(&mut editor.range.0)
+                    .range(0.0..=1.0)
+                    .speed(0.01),
+            );
+            ui.label("to");
+            ui.add(
+                egui::DragValue::new(&mut editor.range.1)
+                    .range(0.0..=1.0)
+                    .speed(0.01),
+            );
+        });
+
+        let channel = (!editor.any_channel)
+            .then(|| vst3_host::MidiChannel::from_index(selected_channel))
+            .flatten();
+        let mut added = None;
+        ui.add_enabled_ui(editor.param_id.is_some(), |ui| {
+            if ui.button("Add Binding").clicked() {
+                if let Some(param_id) = editor.param_id {
+                    added = Some(if editor.poly {
+                        vst3_host::MidiBinding::Aftertouch(vst3_host::AftertouchBinding {
+                            channel,
+                            note: (!editor.any_note).then_some(editor.note),
+                            range: editor.range,
+                            ..vst3_host::AftertouchBinding::new(param_id)
+                        })
+                    } else {
+                        vst3_host::MidiBinding::ChannelPressure(vst3_host::ChannelPressureBinding {
+                            channel,
+                            range: editor.range,
+                            ..vst3_host::ChannelPressureBinding::new(param_id)
+                        })
+                    });
+                }
+            }
+        });
+
+        let mut test = None;
+        ui.horizontal(|ui| {
+            ui.label("Test pressure:");
+            let changed = ui
+                .add(egui::Slider::new(&mut editor.test_pressure, 0..=127))
+                .changed();
+            if changed {
+                let channel = vst3_host::MidiChannel::from_index(selected_channel)
+                    .unwrap_or(MidiChannel::Ch1);
+                test = Some(if editor.poly {
+                    vst3_host::MidiEvent::PolyAftertouch {
+                        channel,
+                        note: editor.note,
+                        pressure: editor.test_pressure,
+                    }
+                } else {
+                    vst3_host::MidiEvent::ChannelAftertouch {
+                        channel,
+                        pressure: editor.test_pressure,
+                    }
+                });
+            }
+        });
+        ui.weak("Bindings are listed under \"MIDI Bindings\".");
+
+        if let Some(binding) = added {
+            self.add_midi_binding(binding);
+        }
+        if let Some(event) = test {
+            self.apply_midi_bindings(&event);
+        }
+    }
+
+    /// The MIDI bindings table and MIDI learn.
+    fn show_midi_bindings(&mut self, ui: &mut egui::Ui) {
+        let params: Vec<(u32, String)> = self
+            .plugin_info
+            .as_ref()
+            .and_then(|i| i.controller_info.as_ref())
+            .map(|c| {
+                c.parameters
+                    .iter()
+                    .map(|p| (p.id, p.title.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.show_controller_presets(ui);
+        if ui
+            .checkbox(&mut self.soft_takeover, "Soft Takeover")
+            .on_hover_text(
+                "A controller only moves its parameter once it reaches the parameter's value",
+            )
+            .changed()
+        {
+            self.apply_soft_takeover();
+        }
+        if self
+            .midi_binding_editor
+            .show(ui, &mut self.preferences.midi_bindings, &params)
+        {
+            self.bindings_dirty = true;
+        }
+        // Saved once a range drag ends rather than every frame of it.
+        if self.bindings_dirty && !ui.input(|i| i.pointer.any_down()) {
+            self.bindings_dirty = false;
+            self.save_midi_bindings();
+        }
+    }
+
+    /// Velocity of keyboard notes, as `send_midi_note_on` takes it.
+    fn played_velocity(&self) -> f32 {
+        f32::from(self.keyboard_velocity) / 127.0
+    }
+
+    /// The keyboard velocity, and "Keyboard Presets": save the keyboard's channel, octave,
+    /// velocity, arpeggiator and CC bindings under a name, or load a saved setup.
+    fn show_keyboard_presets(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Velocity:");
+            ui.add(egui::DragValue::new(&mut self.keyboard_velocity).range(1..=127));
+            ui.separator();
+            let names = self.host.list_keyboard_presets();
+            egui::ComboBox::from_label("Keyboard Presets")
+                .selected_text(
+                    self.selected_keyboard_preset
+                        .as_deref()
+                        .unwrap_or("Choose\u{2026}"),
+                )
+                .show_ui(ui, |ui| {
+                    for name in names {
+                        let selected = self.selected_keyboard_preset.as_ref() == Some(&name);
+                        if ui.selectable_label(selected, &name).clicked() {
+                            self.selected_keyboard_preset = Some(name);
+                        }
+                    }
+                });
+            if ui
+                .add_enabled(
+                    self.selected_keyboard_preset.is_some(),
+                    egui::Button::new("Load"),
+                )
+                .clicked()
+            {
+                if let Some(name) = self.selected_keyboard_preset.clone() {
+                    self.load_keyboard_preset(&name);
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.keyboard_preset_name)
+                    .hint_text("Preset name")
+                    .desired_width(140.0),
+            );
+            let name = self.keyboard_preset_name.trim().to_string();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("Save current"))
+                .on_hover_text("Save the channel, octave, velocity, arpeggiator and CC bindings")
+                .clicked()
+            {
+                self.save_keyboard_preset(&name);
+            }
+        });
+    }
+
+    fn save_keyboard_preset(&mut self, name: &str) {
+        let device = self.midi_input.connected_port().unwrap_or("").to_string();
+        self.host.set_cc_bindings(
+            midi_bindings::cc_bindings(&self.preferences.midi_bindings),
+            &device,
+        );
+        self.host.set_keyboard_channel(self.current_midi_channel());
+        self.host
+            .set_keyboard_octave_shift(self.keyboard_input.octave_shift());
+        self.host.set_keyboard_velocity(self.keyboard_velocity);
+        if let Err(e) = self.host.save_keyboard_preset(name) {
+            self.set_error(format!("Failed to save keyboard preset: {e}"));
+            return;
+        }
+        self.preferences.keyboard_presets = self.host.keyboard_presets().to_vec();
+        self.selected_keyboard_preset = Some(name.to_string());
+        self.keyboard_preset_name.clear();
+        if let Err(e) = self.preferences.save() {
+            self.set_error(format!("Failed to save keyboard presets: {e}"));
+        }
+    }
+
+    fn load_keyboard_preset(&mut self, name: &str) {
+        if let Err(e) = self.host.load_keyboard_preset(name) {
+            self.set_error(format!("Failed to load keyboard preset: {e}"));
+            return;
+        }
+        self.selected_midi_channel = self.host.keyboard_channel().as_index() as i16;
+        self.keyboard_input = KeyboardMapping::qwerty_piano(self.host.keyboard_octave_shift());
+        self.preferences.keyboard_octave_shift = self.keyboard_input.octave_shift();
+        self.keyboard_velocity = self.host.keyboard_velocity();
+        if let Some(arp) = self.host.arpeggiator() {
+            self.arp_settings = arp.clone();
+        }
+        if let Some(audio) = &self.audio {
+            audio.set_arpeggiator(self.host.arpeggiator().cloned());
+        }
+        let bindings = &mut self.preferences.midi_bindings;
+        bindings.retain(|b| !matches!(b, vst3_host::MidiBinding::Cc(_)));
+        bindings.extend(
+            self.host
+                .cc_bindings()
+                .iter()
+                .copied()
+                .map(vst3_host::MidiBinding::Cc),
+        );
+        self.save_midi_bindings();
+    }
+
+    /// "Controller Presets": pick a built-in or saved preset to replace the CC bindings, or
+    /// save the current CC bindings as a named preset.
+    fn show_controller_presets(&mut self, ui: &mut egui::Ui) {
+        let mut selected = None;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Controller Presets")
+                .selected_text("Apply preset\u{2026}")
+                .show_ui(ui, |ui| {
+                    for preset in vst3_host::Vst3Host::list_bundled_presets() {
+                        if ui.selectable_label(false, &preset.name).clicked() {
+                            selected = Some(preset.clone());
+                        }
+                    }
+                    if !self.saved_controller_presets.is_empty() {
+                        ui.separator();
+                    }
+                    for preset in &self.saved_controller_presets {
+                        if ui.selectable_label(false, &preset.name).clicked() {
+                            selected = Some(preset.clone());
+                        }
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.controller_preset_name)
+                    .hint_text("Preset name")
+                    .desired_width(140.0),
+            );
+            let name = self.controller_preset_name.trim().to_string();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("Save Preset"))
+                .on_hover_text("Save the CC bindings as a controller preset")
+                .clicked()
+            {
+                self.save_controller_preset(&name);
+            }
+        });
+        if let Some(preset) = selected {
+            midi_bindings::apply_controller_preset(&mut self.preferences.midi_bindings, &preset);
+            self.host.apply_controller_preset(&preset);
+            self.save_midi_bindings();
+            self.set_error(format!(
+                "Applied controller preset \"{}\" ({} bindings)",
+                preset.name,
+                preset.bindings.len()
+            ));
+        }
+    }
+
+    fn save_controller_preset(&mut self, name: &str) {
+        let Some(dir) = controller_presets_dir() else {
+            self.set_error("No config directory to save controller presets in".to_string());
+            return;
+        };
+        let device = self.midi_input.connected_port().unwrap_or("").to_string();
+        self.host.set_cc_bindings(
+            midi_bindings::cc_bindings(&self.preferences.midi_bindings),
+            &device,
+        );
+        let path = dir.join(midi_bindings::preset_file_name(name));
+        let result = std::fs::create_dir_all(&dir)
+            .map_err(|e| e.to_string())
+            .and_then(|()| {
+                self.host
+                    .save_controller_preset(&path, name)
+                    .map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(()) => {
+                self.saved_controller_presets = midi_bindings::saved_presets(&dir);
+                self.controller_preset_name.clear();
+                self.set_error(format!("Saved controller preset to {}", path.display()));
+            }
+            Err(e) => self.set_error(format!("Failed to save controller preset: {e}")),
+        }
+    }
+
+    fn add_midi_binding(&mut self, binding: vst3_host::MidiBinding) {
+        self.preferences.midi_bindings.push(binding);
+        self.save_midi_bindings();
+    }
+
+    fn save_midi_bindings(&mut self) {
+        if let Err(e) = self.preferences.save() {
+            self.set_error(format!("Failed to save MIDI bindings: {e}"));
+        }
+    }
+
+    /// Set every parameter an enabled binding maps `event` onto (lock-free, like any other
+    /// parameter edit).
+    fn apply_midi_bindings(&mut self, event: &vst3_host::MidiEvent) {
+        let updates: Vec<(u32, f64)> = match (self.soft_takeover, &self.audio, *event) {
+            // Soft takeover needs the plugin's current values, so CC bindings ask the plugin.
+            (
+                true,
+                Some(audio),
+                vst3_host::MidiEvent::ControlChange {
+                    channel,
+                    controller,
+                    value,
+                },
+            ) => {
+                let mut plugin = audio.lock();
+                midi_bindings::cc_bindings(&self.preferences.midi_bindings)
+                    .iter()
+                    .filter(|b| b.matches(channel, controller))
+                    .filter_map(|b| {
+                        let value = plugin.cc_binding_value(b, channel, value).ok()??;
+                        Some((b.param_id, value))
+                    })
+                    .collect()
+            }
+            _ => self
+                .preferences
+                .midi_bindings
+                .iter()
+                .filter_map(|b| b.value_for(event).map(|v| (b.param_id(), v)))
+                .collect(),
+        };
+        for (id, value) in updates {
+            if let Err(e) = self.set_parameter_value(id, value) {
+                self.set_error(format!("MIDI binding failed: {e}"));
+            }
+        }
+    }
+
+    /// Arpeggiator on/off, pattern, rate, octave range and gate. Changes apply to the playing
+    /// plugin straight away (held notes carry over) and to plugins loaded later.
+    fn show_arpeggiator(&mut self, ui: &mut egui::Ui) {
+        let mut enabled = self.host.arpeggiator().is_some();
+        let arp = &mut self.arp_settings;
+        let mut changed = ui.checkbox(&mut enabled, "Arpeggiate held notes").changed();
+        ui.horizontal(|ui| {
+            ui.label("Pattern:");
+            egui::ComboBox::from_id_salt("arp_pattern")
+                .selected_text(arp.pattern.label())
+                .show_ui(ui, |ui| {
+                    for pattern in vst3_host::ArpPattern::ALL {
+                        changed |= ui
+                            .selectable_value(&mut arp.pattern, pattern, pattern.label())
+                            .changed();
+                    }
+                });
+            ui.label("Rate:");
+            egui::ComboBox::from_id_salt("arp_rate")
+                .selected_text(arp.rate.label())
+                .show_ui(ui, |ui| {
+                    for rate in vst3_host::NoteValue::ALL {
+                        changed |= ui
+                            .selectable_value(&mut arp.rate, rate, rate.label())
+                            .changed();
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            changed |= ui
+                .add(egui::Slider::new(&mut arp.octave_range, 1..=4).text("Octaves"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(&mut arp.gate, 0.05..=1.0).text("Gate"))
+                .changed();
+        });
+        if !changed {
+            return;
+        }
+        if enabled {
+            self.host.enable_arpeggiator(self.arp_settings.clone());
+        } else {
+            self.host.disable_arpeggiator();
+        }
+        if let Some(audio) = &self.audio {
+            audio.set_arpeggiator(self.host.arpeggiator().cloned());
+        }
+    }
+
+    /// Voice limit on/off, maximum and stealing strategy, plus how many voices are sounding.
+    fn show_voice_limit(&mut self, ui: &mut egui::Ui) {
+        let limit = &mut self.voice_limit;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut self.voice_limit_on, "Limit voices")
+                .on_hover_text("Cut a sounding note when a new one would exceed the limit")
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(&mut limit.max_voices, 1..=32))
+                .changed();
+            egui::ComboBox::from_id_salt("voice_stealing")
+                .selected_text(limit.stealing.label())
+                .show_ui(ui, |ui| {
+                    for stealing in vst3_host::StealingStrategy::ALL {
+                        changed |= ui
+                            .selectable_value(&mut limit.stealing, stealing, stealing.label())
+                            .changed();
+                    }
+                });
+            // The audio thread holds the plugin while processing; skip a frame rather than wait.
+            let active = self
+                .audio
+                .as_ref()
+                .and_then(|audio| audio.try_lock())
+                .and_then(|plugin| plugin.polyphony_limiter().map(|l| l.active_voices()));
+            if let Some(active) = active {
+                ui.label(format!("Voices: {active}/{}", limit.max_voices));
+            }
+        });
+        if changed {
+            self.apply_voice_limit();
+        }
+    }
+
+    /// Switch soft takeover on the playing plugin to match the MIDI Bindings checkbox.
+    fn apply_soft_takeover(&self) {
+        if let Some(audio) = &self.audio {
+            audio.lock().enable_soft_takeover(self.soft_takeover);
+        }
+    }
+
+    /// Set (or clear) the panel's voice limit on the playing plugin.
+    fn apply_voice_limit(&self) {
+        let Some(audio) = &self.audio else {
+            return;
+        };
+        let mut plugin = audio.lock();
+        if self.voice_limit_on {
+            plugin.set_polyphony_limit(self.voice_limit.max_voices, self.voice_limit.stealing);
+        } else {
+            plugin.clear_polyphony_limit();
+        }
+    }
+
+    /// The routing matrix editor: which input channels play which plugins (the GM instances in
+    /// General MIDI mode). Edits apply to the next played event.
+    fn show_midi_routing(&mut self, ui: &mut egui::Ui) {
+        let columns: Vec<routing_matrix_editor::Column> = match &self.gm_audio {
+            Some(gm) => {
+                let bus = gm.lock();
+                (0..bus.channel_count().min(16) as u8)
+                    .filter_map(|ch| {
+                        let channel = vst3_host::MidiChannel::from_index(ch)?;
+                        Some(routing_matrix_editor::Column {
+                            name: bus.name(ch).unwrap_or_default().to_string(),
+                            channel: Some(channel),
+                        })
+                    })
+                    .collect()
+            }
+            None => vec![routing_matrix_editor::Column {
+                name: "Plugin".to_string(),
+                channel: None,
+            }],
+        };
+
+        let mut routing_on = self.host.midi_routing().is_some();
+        if ui
+            .checkbox(&mut routing_on, "Route channels")
+            .on_hover_text(
+                "Send each input channel to the plugins ticked below; takes precedence over \
+                 the keyboard split",
+            )
+            .changed()
+        {
+            if routing_on {
+                self.host
+                    .set_midi_routing(routing_matrix_editor::default_matrix(&columns));
+            } else {
+                self.host.clear_midi_routing();
+            }
+        }
+        if let Some(matrix) = self.host.midi_routing() {
+            let mut matrix = matrix.clone();
+            if routing_matrix_editor::show(ui, &mut matrix, &columns) {
+                self.host.set_midi_routing(matrix);
+            }
+        }
+    }
+
+    /// Install `strips` on the playing plugin.
+    fn apply_channel_strips(&self, strips: &[vst3_host::ChannelStrip]) {
+        let Some(audio) = &self.audio else {
+            return;
+        };
+        let mut plugin = audio.lock();
+        for strip in strips {
+            plugin.set_channel_strip(strip.channel, *strip);
+        }
+    }
+
+    fn apply_velocity_curve(&self) {
+        if let Some(audio) = &self.audio {
+            audio
+                .lock()
+                .set_velocity_curve(self.preferences.velocity_curve.clone());
+        }
+    }
+
+    /// The Input group: gain on live input before the plugin, fixed or automatic.
+    fn show_input_gain(&mut self, ui: &mut egui::Ui) {
+        let applied = self.audio.as_ref().map(|audio| audio.input_gain_db());
+        let mut changed = false;
+        ui.group(|ui| {
+            ui.add_enabled_ui(self.preferences.input_device.is_some(), |ui| {
+                ui.label("Input:");
+                ui.horizontal(|ui| {
+                    ui.label("Gain:");
+                    changed |= ui
+                        .add_enabled(
+                            !self.auto_gain,
+                            egui::Slider::new(&mut self.input_gain_db, -24.0..=24.0).suffix(" dB"),
+                        )
+                        .changed();
+                });
+                ui.horizontal(|ui| {
+                    let auto = ui
+                        .checkbox(&mut self.auto_gain, "Auto gain")
+                        .on_hover_text("Bring the input's level (RMS over 1 s) to the target");
+                    if auto.changed() {
+                        // Keep the level auto-gain arrived at instead of jumping back.
+                        if let Some(applied) = applied.filter(|_| !self.auto_gain) {
+                            self.input_gain_db = applied;
+                        }
+                        changed = true;
+                    }
+                    changed |= ui
+                        .add_enabled(
+                            self.auto_gain,
+                            egui::DragValue::new(&mut self.auto_gain_target_db)
+                                .range(-40.0..=0.0)
+                                .speed(0.5)
+                                .suffix(" dBFS"),
+                        )
+                        .changed();
+                });
+                if let Some(applied) = applied.filter(|_| self.auto_gain) {
+                    ui.label(format!("Applied: {applied:+.1} dB"));
+                }
+            })
+            .response
+            .on_disabled_hover_text("Choose an input device to process live input");
+        });
+        if changed {
+            self.apply_input_gain();
+        }
+    }
+
+    /// Hand the Input group's gain stage to the playing plugin.
+    fn apply_input_gain(&self) {
+        let Some(audio) = &self.audio else {
+            return;
+        };
+        let mut stage = if self.auto_gain {
+            vst3_host::InputGainStage::with_auto_gain(self.sample_rate, self.auto_gain_target_db)
+        } else {
+            vst3_host::InputGainStage::new(self.sample_rate)
+        };
+        stage.gain_db = self.input_gain_db;
+        audio.set_input_gain(Some(stage));
+    }
+
+    /// Put the safety limiter after the playing plugin, or take it away.
+    fn apply_auto_limiter(&self) {
+        let Some(audio) = &self.audio else {
+            return;
+        };
+        audio.set_auto_limiter(
+            self.auto_limiter
+                .then(|| vst3_host::AutoLimiter::new(self.sample_rate)),
+        );
+    }
+
+    /// Live hardware MIDI input: pick a connected controller and forward its MIDI to the plugin.
+    fn show_midi_input_device(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.label(egui::RichText::new("MIDI Input Device").strong());
+            ui.horizontal(|ui| {
+                let current = self
+                    .midi_input
+                    .connected_port()
+                    .unwrap_or("None")
+                    .to_string();
+                egui::ComboBox::from_id_salt("midi_input_port")
+                    .selected_text(current)
+                    .show_ui(ui, |ui| {
+                        if ui
+                            .selectable_label(!self.midi_input.is_connected(), "None")
+                            .clicked()
+                        {
+                            self.midi_input.disconnect();
+                        }
+                        // Clone the cached list so we don't borrow self across connect().
+                        for (i, name) in self.midi_input_ports.clone().iter().enumerate() {
+                            let selected = self.midi_input.connected_port() == Some(name.as_str());
+                            if ui.selectable_label(selected, name).clicked() {
+                                match self.midi_input.connect(i) {
+                                    Ok(()) => self.set_error(format!("Listening to MIDI: {name}")),
+                                    Err(e) => self.set_error(format!("MIDI input: {e}")),
+                                }
+                            }
+                        }
+                    });
+                if ui.button("Refresh").clicked() {
+                    self.midi_input_ports = MidiInputState::list_ports();
+                }
+                if self.midi_input_ports.is_empty() {
+                    ui.label("(no MIDI inputs found)");
+                }
+            });
+            if self.midi_input.is_connected() && self.audio.is_none() {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    "Load a plugin to hear incoming MIDI.",
+                );
+            }
+        });
+    }
+
+    /// Virtual MIDI Out: publish the plugin's output MIDI as a virtual source (an IAC-style
+    /// CoreMIDI source on macOS, the loopMIDI port of that name on Windows).
+    fn show_virtual_midi_out(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.label(egui::RichText::new("Virtual MIDI Out").strong());
+            ui.horizontal(|ui| {
+                ui.label("Port name:");
+                ui.add_enabled(
+                    !self.virtual_midi_out,
+                    egui::TextEdit::singleline(&mut self.virtual_midi_out_name)
+                        .desired_width(160.0),
+                );
+                if ui.checkbox(&mut self.virtual_midi_out, "Enable").changed() {
+                    self.apply_virtual_midi_out();
+                }
+            });
+            if cfg!(windows) {
+                ui.label(
+                    egui::RichText::new("Create a port with this name in loopMIDI first.").weak(),
+                );
+            }
+            if self.virtual_midi_out && self.audio.is_none() {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    "The port opens once a plugin is loaded.",
+                );
+            }
+        });
+    }
+
+    /// Open (or close) the virtual MIDI port on the playing plugin to match the toggle. A
+    /// failed open turns the toggle back off.
+    fn apply_virtual_midi_out(&mut self) {
+        let Some(audio) = &self.audio else {
+            return;
+        };
+        if !self.virtual_midi_out {
+            audio.set_virtual_midi_out(None);
+            return;
+        }
+        match self
+            .host
+            .enable_virtual_midi_out(&self.virtual_midi_out_name)
+        {
+            Ok(port) => {
+                let status = format!("Sending plugin MIDI to '{}'", port.name());
+                audio.set_virtual_midi_out(Some(port));
+                self.set_error(status);
+            }
+            Err(e) => {
+                self.virtual_midi_out = false;
+                self.set_error(format!("Virtual MIDI out: {e}"));
+            }
+        }
+    }
+
+    /// Log a forwarded hardware-MIDI event into the monitor as Input.
+    fn log_incoming_midi(&self, ev: vst3_host::midi::MidiEvent) {
+        use vst3_host::midi::MidiEvent as Ev;
+        let (ty, ch, d1, d2): (u16, u8, u8, u8) = match ev {
+            Ev::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => (0, channel.as_index(), note, velocity),
+            Ev::NoteOff {
+                channel,
+                note,
+                velocity,
+            } => (1, channel.as_index(), note, velocity),
+            Ev::PolyAftertouch {
+                channel,
+                note,
                 pressure,
             } => (2, channel.as_index(), note, pressure),
             Ev::ControlChange {
@@ -2562,9 +5725,32 @@ impl VST3Inspector {
                 } else if ui.button("[Pause]").clicked() {
                     *self.midi_monitor_paused.lock().unwrap() = true;
                 }
-
-                if ui.button("Clear").clicked() {
-                    self.midi_events.lock().unwrap().clear();
+
+                if ui.button("Clear").clicked() {
+                    self.midi_events.lock().unwrap().clear();
+                }
+
+                ui.separator();
+                let (recording, recorded) = {
+                    let recorder = self.smf_recorder.lock().unwrap();
+                    (recorder.is_recording(), recorder.events().len())
+                };
+                if ui
+                    .add_enabled(!recording, egui::Button::new("\u{23FA} Record"))
+                    .on_hover_text("Capture the monitored events as a MIDI file")
+                    .clicked()
+                {
+                    self.start_smf_recording();
+                }
+                if ui
+                    .add_enabled(recorded > 0, egui::Button::new("\u{23F9} Save"))
+                    .on_hover_text("Stop recording and save a Standard MIDI File")
+                    .clicked()
+                {
+                    self.save_smf_recording();
+                }
+                if recording {
+                    ui.colored_label(egui::Color32::RED, format!("Recording ({recorded})"));
                 }
 
                 ui.separator();
@@ -2628,6 +5814,10 @@ impl VST3Inspector {
                 });
             });
 
+            ui.collapsing("Voice Monitor", |ui| {
+                voice_monitor::show(ui, &self.output_voices);
+            });
+
             ui.separator();
 
             // Event list using proper table
@@ -2742,6 +5932,17 @@ impl VST3Inspector {
             return Err("No plugin loaded".into());
         };
         let mut window = vst3_host::PluginWindow::new(audio.plugin());
+        if let Some(geometry) = self
+            .preferences
+            .plugin_window_positions
+            .get(&self.plugin_path)
+        {
+            window.restore_geometry(*geometry);
+        }
+        if let Some(behavior) = self.preferences.window_behaviors.get(&self.plugin_path) {
+            window.set_always_on_top(behavior.always_on_top);
+            window.set_opacity(behavior.opacity);
+        }
         if let Err(e) = window.open() {
             let msg = format!("Failed to open editor: {e}");
             self.set_error(msg.clone());
@@ -2752,12 +5953,102 @@ impl VST3Inspector {
         Ok(())
     }
 
+    fn open_quick_edit(&mut self) {
+        let Some(audio) = &self.audio else {
+            return;
+        };
+        let opened = audio.lock().open_minimal_gui();
+        match opened {
+            Ok(handle) => self.quick_edit = Some(handle),
+            Err(e) => self.set_error(format!("Failed to open Quick Edit: {e}")),
+        }
+    }
+
+    /// Draw the Quick Edit panel, dropping it once the user closes it or the plugin goes away.
+    fn show_quick_edit(&mut self, ctx: &egui::Context) {
+        let (Some(handle), Some(audio)) = (&mut self.quick_edit, &self.audio) else {
+            self.quick_edit = None;
+            return;
+        };
+        if !handle.show(ctx, &mut audio.lock()) {
+            self.quick_edit = None;
+        }
+    }
+
     fn close_plugin_gui(&mut self) {
-        // Dropping the window closes the editor and the native window.
+        // Remember where the editor was left so it reopens there, then drop the window, which
+        // closes the editor and the native window.
+        if let Some(geometry) = self.plugin_window.as_ref().and_then(|w| w.geometry()) {
+            self.preferences
+                .plugin_window_positions
+                .insert(self.plugin_path.clone(), geometry);
+            if let Err(e) = self.preferences.save() {
+                eprintln!("Failed to save plugin window position: {e}");
+            }
+        }
         self.plugin_window = None;
         self.gui_attached = false;
     }
 
+    /// Pin and opacity controls for the open editor window (laid out right to left, next to
+    /// the GUI buttons). Changes apply immediately and are saved per plugin path.
+    fn show_plugin_window_toolbar(&mut self, ui: &mut egui::Ui) {
+        let Some(window) = self.plugin_window.as_mut() else {
+            return;
+        };
+        let mut behavior = window.behavior();
+        let mut save = false;
+
+        let opacity = ui
+            .add(
+                egui::Slider::new(&mut behavior.opacity, 0.1..=1.0)
+                    .show_value(false)
+                    .fixed_decimals(2),
+            )
+            .on_hover_text("Editor window opacity");
+        if opacity.changed() {
+            window.set_opacity(behavior.opacity);
+        }
+        // Save once the drag ends rather than on every step.
+        save |= opacity.drag_stopped() || (opacity.changed() && !opacity.dragged());
+        ui.label("\u{03b1}");
+
+        if ui
+            .toggle_value(&mut behavior.always_on_top, "\u{1f4cc} Pin")
+            .on_hover_text("Keep the editor window above other windows")
+            .changed()
+        {
+            window.set_always_on_top(behavior.always_on_top);
+            save = true;
+        }
+
+        if save {
+            self.preferences
+                .window_behaviors
+                .insert(self.plugin_path.clone(), window.behavior());
+            if let Err(e) = self.preferences.save() {
+                self.set_error(format!("Failed to save preferences: {e}"));
+            }
+        }
+    }
+
+    /// Forget the loaded plugin's saved editor position and re-center the window if it's open.
+    fn reset_plugin_window_position(&mut self) {
+        if let Some(window) = self.plugin_window.as_mut() {
+            window.reset_position();
+        }
+        if self
+            .preferences
+            .plugin_window_positions
+            .remove(&self.plugin_path)
+            .is_some()
+        {
+            if let Err(e) = self.preferences.save() {
+                self.set_error(format!("Failed to save preferences: {e}"));
+            }
+        }
+    }
+
     /// Prompt for a path and save the loaded plugin's state. Supports the library's JSON
     /// `PluginPreset` (default) and the standard `.vstpreset` interchange format, chosen by
     /// the picked file's extension. Surfaces the result through `last_error`.
@@ -2795,6 +6086,7 @@ impl VST3Inspector {
 
         match result {
             Ok(()) => {
+                self.saved_parameter_values = Some(self.current_parameter_values());
                 self.set_error(format!("Saved preset to {}", path.display()));
             }
             Err(e) => {
@@ -2840,6 +6132,7 @@ impl VST3Inspector {
             Ok(()) => {
                 // Re-sync the cached parameter values with the restored plugin state.
                 let _ = self.refresh_parameter_values();
+                self.saved_parameter_values = Some(self.current_parameter_values());
                 self.set_error(format!("Loaded preset from {}", path.display()));
             }
             Err(e) => {
@@ -2848,6 +6141,122 @@ impl VST3Inspector {
         }
     }
 
+    /// Save the loaded plugin, its parameter values, the MIDI bindings and the transport as a
+    /// session file.
+    fn save_session_dialog(&mut self) {
+        let Some(audio) = &self.audio else {
+            self.set_error("No plugin loaded");
+            return;
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Save Session")
+            .add_filter("Session (JSON)", &["json"])
+            .set_file_name("session.json")
+            .save_file()
+        else {
+            return; // user cancelled
+        };
+
+        let config = vst3_host::AudioConfig {
+            sample_rate: self.sample_rate,
+            block_size: self.block_size as usize,
+            tempo: self.tempo_bpm,
+            ..Default::default()
+        };
+        let result = vst3_host::Session::capture(&audio.lock(), &config).and_then(|mut session| {
+            // The inspector applies MIDI bindings itself rather than through the plugin.
+            session.midi_bindings = self.preferences.midi_bindings.clone();
+            session.save(&path)
+        });
+        match result {
+            Ok(()) => {
+                self.remember_session(&path);
+                self.set_error(format!("Saved session to {}", path.display()));
+            }
+            Err(e) => self.set_error(format!("Failed to save session: {e}")),
+        }
+    }
+
+    fn load_session_dialog(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .set_title("Load Session")
+            .add_filter("Session (JSON)", &["json"])
+            .pick_file()
+        {
+            self.load_session(&path);
+        }
+    }
+
+    /// Restore the session at `path`: audio settings, tempo and MIDI bindings now, then the
+    /// plugin in the background with its parameters applied once it has loaded.
+    fn load_session(&mut self, path: &std::path::Path) {
+        let session = match vst3_host::Session::load(path) {
+            Ok(session) => session,
+            Err(e) => {
+                self.set_error(format!("Failed to load session: {e}"));
+                return;
+            }
+        };
+        self.sample_rate = session.audio_config.sample_rate;
+        self.block_size = session.audio_config.block_size as i32;
+        self.tempo_bpm = session.transport.tempo;
+        self.preferences.midi_bindings = session.midi_bindings.clone();
+        self.remember_session(path);
+        let plugin_path = session.plugin_path.to_string_lossy().into_owned();
+        self.pending_session = Some(session);
+        self.load_plugin(plugin_path);
+    }
+
+    /// Apply the parameters of the session being restored to the plugin that just loaded.
+    fn apply_pending_session(&mut self) {
+        let (Some(session), Some(audio)) = (self.pending_session.take(), self.audio.as_ref())
+        else {
+            return;
+        };
+        let applied = session.apply_parameters(&mut audio.lock());
+        let _ = self.refresh_parameter_values();
+        self.set_error(format!(
+            "Restored session: {applied} of {} parameters applied",
+            session.parameters.len()
+        ));
+    }
+
+    /// Record `path` as the session to offer on the next launch.
+    fn remember_session(&mut self, path: &std::path::Path) {
+        self.preferences.last_session_path = Some(path.to_string_lossy().into_owned());
+        if let Err(e) = self.preferences.save() {
+            self.set_error(format!("Failed to save preferences: {e}"));
+        }
+    }
+
+    /// The startup prompt offering to reload the last session.
+    fn show_session_reload_modal(&mut self, ctx: &egui::Context) {
+        if !self.offer_session_reload {
+            return;
+        }
+        let Some(path) = self.preferences.last_session_path.clone() else {
+            self.offer_session_reload = false;
+            return;
+        };
+        let mut reload = false;
+        let mut dismissed = false;
+        let modal = egui::Modal::new(egui::Id::new("session_reload_modal")).show(ctx, |ui| {
+            ui.heading("Reload last session?");
+            ui.label(&path);
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                reload = ui.button("Reload").clicked();
+                dismissed = ui.button("Dismiss").clicked();
+            });
+        });
+        if reload {
+            self.load_session(std::path::Path::new(&path));
+        }
+        if reload || dismissed || modal.should_close() {
+            self.offer_session_reload = false;
+        }
+    }
+
     /// Render the loaded plugin offline to a WAV file (4 s, a held C3), preserving the current
     /// state. The live plugin is owned by the `AudioHandle` and some plugins (e.g. Dexed) can't
     /// have two instances at once, so we snapshot state, drop the live instance, render a fresh
@@ -2922,79 +6331,419 @@ impl VST3Inspector {
             self.set_error("No plugin loaded");
             return;
         };
-        // Bind the result so the lock guard drops before we touch `self`.
-        let result = audio.lock().save_state();
+        // Bind the result so the lock guard drops before we touch `self`. The parameter
+        // snapshot rides along for the morph control (state blobs can't be interpolated).
+        let result = {
+            let plugin = audio.lock();
+            plugin
+                .save_state()
+                .map(|data| (data, plugin.snapshot_parameters().ok()))
+        };
+        match result {
+            Ok((data, snapshot)) => {
+                match slot {
+                    AbSlot::A => (self.slot_a, self.morph_a) = (Some(data), snapshot),
+                    AbSlot::B => (self.slot_b, self.morph_b) = (Some(data), snapshot),
+                }
+                self.set_error(format!("Captured state into slot {}", ab_slot_label(slot)));
+            }
+            Err(e) => self.set_error(format!(
+                "Failed to capture slot {}: {e}",
+                ab_slot_label(slot)
+            )),
+        }
+    }
+
+    /// Apply a previously-captured A/B slot to the live plugin and re-sync the parameter table.
+    fn apply_slot(&mut self, slot: AbSlot) {
+        let data = match slot {
+            AbSlot::A => self.slot_a.clone(),
+            AbSlot::B => self.slot_b.clone(),
+        };
+        let Some(data) = data else {
+            self.set_error(format!("Slot {} is empty", ab_slot_label(slot)));
+            return;
+        };
+        let Some(audio) = self.audio.as_ref() else {
+            self.set_error("No plugin loaded");
+            return;
+        };
+        let result = audio.lock().load_state(&data);
+        match result {
+            Ok(()) => {
+                self.active_slot = Some(slot);
+                let _ = self.refresh_parameter_values();
+                self.set_error(format!("Applied slot {}", ab_slot_label(slot)));
+            }
+            Err(e) => self.set_error(format!("Failed to apply slot {}: {e}", ab_slot_label(slot))),
+        }
+    }
+
+    fn set_parameter_value(&mut self, param_id: u32, normalized_value: f64) -> Result<(), String> {
+        let audio = match &self.audio {
+            Some(a) => a,
+            None => return Err("No plugin loaded".to_string()),
+        };
+
+        // Lock-free: queue the change onto the control ring; the audio callback applies it on
+        // the next block. Validate the range here since the ring push can't report it back.
+        if !(0.0..=1.0).contains(&normalized_value) {
+            return Err(format!(
+                "Parameter value {normalized_value} out of range 0.0..=1.0"
+            ));
+        }
+        audio.set_parameter(param_id, normalized_value);
+        // This value wins over a drag still being held back.
+        self.param_debouncer.cancel(param_id);
+
+        // The live state now diverges from any applied A/B snapshot — drop the stale indicator.
+        self.active_slot = None;
+        self.show_parameter_value(param_id, normalized_value);
+        Ok(())
+    }
+
+    /// Set a parameter from a slider or knob drag: held back by the debouncer until the drag
+    /// pauses (see "Param Debounce"), but shown at once.
+    fn edit_parameter_value(&mut self, param_id: u32, normalized_value: f64) -> Result<(), String> {
+        if self.param_debouncer.delay_ms <= 0.0 || !(0.0..=1.0).contains(&normalized_value) {
+            return self.set_parameter_value(param_id, normalized_value);
+        }
+        if self.audio.is_none() {
+            return Err("No plugin loaded".to_string());
+        }
+        self.param_debouncer.push(param_id, normalized_value);
+        self.active_slot = None;
+        self.show_parameter_value(param_id, normalized_value);
+        Ok(())
+    }
+
+    /// Update our cached parameter info for display.
+    fn show_parameter_value(&mut self, param_id: u32, normalized_value: f64) {
+        if let Some(ref mut plugin_info) = self.plugin_info {
+            if let Some(ref mut controller_info) = plugin_info.controller_info {
+                if let Some(param) = controller_info
+                    .parameters
+                    .iter_mut()
+                    .find(|p| p.id == param_id)
+                {
+                    param.current_value = normalized_value;
+                }
+            }
+        }
+    }
+
+    /// "Morph A→B": hold the mouse on the bar and drag to move between the A and B
+    /// snapshots (left edge = A, right edge = B).
+    fn show_morph_control(&mut self, ui: &mut egui::Ui) {
+        let ready = self.morph_a.is_some() && self.morph_b.is_some();
+        ui.horizontal(|ui| {
+            ui.label("Morph A\u{2192}B:");
+            let bar = egui::ProgressBar::new(self.morph_t as f32)
+                .desired_width(240.0)
+                .text(format!("{:.0}%", self.morph_t * 100.0))
+                .animate(self.morphing);
+            let response = ui
+                .add_enabled(ready, bar)
+                .interact(egui::Sense::click_and_drag())
+                .on_hover_text("Hold and drag: left edge = slot A, right edge = slot B")
+                .on_disabled_hover_text("Capture A and B to morph between them");
+            self.morphing = ready && response.is_pointer_button_down_on();
+            if !self.morphing {
+                return;
+            }
+            if let Some(pos) = response.interact_pointer_pos() {
+                let rect = response.rect;
+                self.morph_t = ((pos.x - rect.left()) / rect.width()).clamp(0.0, 1.0) as f64;
+            }
+            self.apply_morph();
+            ui.ctx().request_repaint();
+        });
+    }
+
+    /// Push the parameters at `morph_t` between the A and B snapshots. Goes through the
+    /// lock-free parameter path, since this runs every frame while dragging.
+    fn apply_morph(&mut self) {
+        let (Some(a), Some(b)) = (&self.morph_a, &self.morph_b) else {
+            return;
+        };
+        for change in vst3_host::morph(a, b, self.morph_t) {
+            if let Err(e) = self.set_parameter_value(change.id, change.value) {
+                self.set_error(format!("Morph failed: {e}"));
+                self.morphing = false;
+                return;
+            }
+        }
+    }
+
+    /// Confirmation dialog for a pending "Reset All" / "Reset Modified".
+    /// The "?" list of the editor's keyboard shortcuts, opened from the GUI toolbar.
+    fn show_shortcuts_modal(&mut self, ctx: &egui::Context) {
+        if !self.shortcuts_modal_open {
+            return;
+        }
+        let mut closed = false;
+        let modal = egui::Modal::new(egui::Id::new("key_shortcuts_modal")).show(ctx, |ui| {
+            ui.heading("\u{2328} Keyboard Shortcuts");
+            ui.add_space(6.0);
+            shortcuts_table(ui, &self.key_shortcuts);
+            ui.add_space(8.0);
+            closed = ui.button("Close").clicked();
+        });
+        if closed || modal.should_close() {
+            self.shortcuts_modal_open = false;
+        }
+    }
+
+    fn show_parameter_reset_modal(&mut self, ctx: &egui::Context) {
+        let Some(scope) = self.pending_parameter_reset else {
+            return;
+        };
+        let mut confirmed = false;
+        let mut cancelled = false;
+        let modal = egui::Modal::new(egui::Id::new("parameter_reset_modal")).show(ctx, |ui| {
+            ui.heading(match scope {
+                ParameterReset::All => "Reset all parameters?",
+                ParameterReset::Modified => "Reset modified parameters?",
+                ParameterReset::Factory => "Factory reset the plugin?",
+            });
+            ui.label(match scope {
+                ParameterReset::Factory => {
+                    "The plugin is reloaded without any saved state, so all of its settings \
+                     return to factory defaults. Its editor window will close."
+                }
+                _ => "Parameters will be set back to their default values.",
+            });
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                confirmed = ui.button("Reset").clicked();
+                cancelled = ui.button("Cancel").clicked();
+            });
+        });
+        if confirmed {
+            self.reset_parameters(scope);
+        }
+        if confirmed || cancelled || modal.should_close() {
+            self.pending_parameter_reset = None;
+        }
+    }
+
+    /// The "Copy from…" dialog: pick another live instance (the General MIDI channel
+    /// instances) and copy its parameter values into the loaded plugin by name.
+    fn show_copy_from_modal(&mut self, ctx: &egui::Context) {
+        if !self.copy_from_open {
+            return;
+        }
+        let instances: Vec<(u8, String)> = self
+            .gm_audio
+            .as_ref()
+            .map(|gm| {
+                let bus = gm.lock();
+                (0..bus.channel_count() as u8)
+                    .map(|ch| (ch, bus.name(ch).unwrap_or_default().to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut copy = false;
+        let mut cancelled = false;
+        let modal = egui::Modal::new(egui::Id::new("copy_from_modal")).show(ctx, |ui| {
+            ui.heading("Copy parameters from\u{2026}");
+            if instances.is_empty() {
+                ui.label(
+                    "No other plugin instances are loaded. Start General MIDI mode to get one \
+                     instance per channel.",
+                );
+            } else {
+                ui.label("Values are copied to parameters with the same name.");
+                egui::ScrollArea::vertical()
+                    .max_height(240.0)
+                    .show(ui, |ui| {
+                        for (ch, name) in &instances {
+                            let label = format!("{} \u{2014} {}", name, self.gm_plugin_name);
+                            ui.radio_value(&mut self.copy_from_channel, Some(*ch), label);
+                        }
+                    });
+            }
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                copy = ui
+                    .add_enabled(self.copy_from_channel.is_some(), egui::Button::new("Copy"))
+                    .clicked();
+                cancelled = ui.button("Cancel").clicked();
+            });
+        });
+        if copy {
+            if let Some(channel) = self.copy_from_channel {
+                self.copy_parameters_from_gm_channel(channel);
+            }
+        }
+        if copy || cancelled || modal.should_close() {
+            self.copy_from_open = false;
+        }
+    }
+
+    /// The "Randomize" dialog: pick a distribution (and its sigma or weight), then draw new
+    /// values for every writable parameter.
+    fn show_randomize_modal(&mut self, ctx: &egui::Context) {
+        use vst3_host::Distribution;
+        if !self.randomize_open {
+            return;
+        }
+        let mut randomize = false;
+        let mut close = false;
+        let distribution = &mut self.randomize_constraints.distribution;
+        let modal = egui::Modal::new(egui::Id::new("randomize_modal")).show(ctx, |ui| {
+            ui.heading("\u{1F3B2} Randomize");
+            ui.horizontal(|ui| {
+                ui.label("Distribution:");
+                egui::ComboBox::from_id_salt("randomize_distribution")
+                    .selected_text(distribution_label(distribution))
+                    .show_ui(ui, |ui| {
+                        for choice in [
+                            Distribution::Uniform,
+                            Distribution::Gaussian { sigma: 0.1 },
+                            Distribution::Bimodal { weight: 0.5 },
+                        ] {
+                            let selected = std::mem::discriminant(distribution)
+                                == std::mem::discriminant(&choice);
+                            if ui
+                                .selectable_label(selected, distribution_label(&choice))
+                                .clicked()
+                                && !selected
+                            {
+                                *distribution = choice;
+                            }
+                        }
+                    });
+            });
+            match distribution {
+                Distribution::Uniform => {
+                    ui.label("Any value in each parameter's range.");
+                }
+                Distribution::Gaussian { sigma } => {
+                    ui.add(
+                        egui::Slider::new(sigma, 0.01..=0.5)
+                            .text("Sigma")
+                            .custom_formatter(|v, _| format!("\u{00B1}{:.0}%", v * 100.0)),
+                    )
+                    .on_hover_text("How far values usually move from where they are");
+                }
+                Distribution::Bimodal { weight } => {
+                    ui.add(egui::Slider::new(weight, 0.0..=1.0).text("Top weight"))
+                        .on_hover_text("Chance of landing near the top rather than the bottom");
+                }
+            }
+            ui.label("Read-only and bypass parameters are left alone.");
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                randomize = ui.button("Randomize").clicked();
+                close = ui.button("Close").clicked();
+            });
+        });
+        if randomize {
+            self.randomize_parameters();
+        }
+        if close || modal.should_close() {
+            self.randomize_open = false;
+        }
+    }
+
+    /// Randomize the loaded plugin's parameters with the dialog's settings.
+    fn randomize_parameters(&mut self) {
+        let Some(audio) = self.audio.as_ref() else {
+            self.set_error("No plugin loaded");
+            return;
+        };
+        let result = audio
+            .lock()
+            .randomize_parameters(&mut self.rng, &self.randomize_constraints);
+        match result {
+            Ok(result) => {
+                self.active_slot = None;
+                let _ = self.refresh_parameter_values();
+                self.set_error(format!(
+                    "Randomized {} parameter(s) ({})",
+                    result.changes.len(),
+                    distribution_label(&result.distribution)
+                ));
+            }
+            Err(e) => self.set_error(format!("Failed to randomize parameters: {e}")),
+        }
+    }
+
+    /// Copy General MIDI channel `channel`'s parameter values into the loaded plugin.
+    fn copy_parameters_from_gm_channel(&mut self, channel: u8) {
+        let (Some(audio), Some(gm)) = (self.audio.as_ref(), self.gm_audio.as_ref()) else {
+            self.set_error("No plugin loaded");
+            return;
+        };
+        let result = {
+            let bus = gm.lock();
+            match bus.plugin(channel) {
+                Some(src) => self
+                    .host
+                    .copy_all_parameters_by_name(src, &mut audio.lock()),
+                None => Err(vst3_host::Error::InvalidParameter(format!(
+                    "no instance on channel {}",
+                    channel + 1
+                ))),
+            }
+        };
         match result {
-            Ok(data) => {
-                match slot {
-                    AbSlot::A => self.slot_a = Some(data),
-                    AbSlot::B => self.slot_b = Some(data),
-                }
-                self.set_error(format!("Captured state into slot {}", ab_slot_label(slot)));
+            Ok(report) => {
+                let _ = self.refresh_parameter_values();
+                self.set_error(copy_report_summary(&report));
             }
-            Err(e) => self.set_error(format!(
-                "Failed to capture slot {}: {e}",
-                ab_slot_label(slot)
-            )),
+            Err(e) => self.set_error(format!("Failed to copy parameters: {e}")),
         }
     }
 
-    /// Apply a previously-captured A/B slot to the live plugin and re-sync the parameter table.
-    fn apply_slot(&mut self, slot: AbSlot) {
-        let data = match slot {
-            AbSlot::A => self.slot_a.clone(),
-            AbSlot::B => self.slot_b.clone(),
-        };
-        let Some(data) = data else {
-            self.set_error(format!("Slot {} is empty", ab_slot_label(slot)));
+    /// Reset all (or only modified) writable parameters to their defaults.
+    fn reset_parameters(&mut self, scope: ParameterReset) {
+        if scope == ParameterReset::Factory {
+            self.factory_reset();
             return;
-        };
+        }
         let Some(audio) = self.audio.as_ref() else {
             self.set_error("No plugin loaded");
             return;
         };
-        let result = audio.lock().load_state(&data);
+        let result = {
+            let mut plugin = audio.lock();
+            match scope {
+                ParameterReset::Modified => plugin.reset_modified_parameters(),
+                _ => plugin.reset_all_parameters(),
+            }
+        };
         match result {
-            Ok(()) => {
-                self.active_slot = Some(slot);
+            Ok(changes) => {
+                self.active_slot = None;
                 let _ = self.refresh_parameter_values();
-                self.set_error(format!("Applied slot {}", ab_slot_label(slot)));
+                self.set_error(format!("Reset {} parameter(s) to default", changes.len()));
             }
-            Err(e) => self.set_error(format!("Failed to apply slot {}: {e}", ab_slot_label(slot))),
+            Err(e) => self.set_error(format!("Failed to reset parameters: {e}")),
         }
     }
 
-    fn set_parameter_value(&mut self, param_id: u32, normalized_value: f64) -> Result<(), String> {
-        let audio = match &self.audio {
-            Some(a) => a,
-            None => return Err("No plugin loaded".to_string()),
-        };
-
-        // Lock-free: queue the change onto the control ring; the audio callback applies it on
-        // the next block. Validate the range here since the ring push can't report it back.
-        if !(0.0..=1.0).contains(&normalized_value) {
-            return Err(format!(
-                "Parameter value {normalized_value} out of range 0.0..=1.0"
-            ));
+    /// Reload the plugin with its factory state. The editor belongs to the old instance, so
+    /// it is closed first.
+    fn factory_reset(&mut self) {
+        if self.audio.is_none() {
+            self.set_error("No plugin loaded");
+            return;
         }
-        audio.set_parameter(param_id, normalized_value);
-
-        // The live state now diverges from any applied A/B snapshot — drop the stale indicator.
-        self.active_slot = None;
-
-        // Update our cached parameter info for display.
-        if let Some(ref mut plugin_info) = self.plugin_info {
-            if let Some(ref mut controller_info) = plugin_info.controller_info {
-                if let Some(param) = controller_info
-                    .parameters
-                    .iter_mut()
-                    .find(|p| p.id == param_id)
-                {
-                    param.current_value = normalized_value;
-                }
+        self.close_plugin_gui();
+        let Some(audio) = self.audio.as_ref() else {
+            return;
+        };
+        let result = audio.lock().factory_reset();
+        match result {
+            Ok(()) => {
+                self.active_slot = None;
+                let _ = self.refresh_parameter_values();
+                self.set_error("Plugin reloaded with factory defaults");
             }
+            Err(e) => self.set_error(format!("Factory reset failed: {e}")),
         }
-        Ok(())
     }
 
     fn refresh_parameter_values(&mut self) -> Result<(), String> {
@@ -3016,11 +6765,80 @@ impl VST3Inspector {
         Ok(())
     }
 
+    /// Every parameter's displayed value, as `(id, value)`.
+    fn current_parameter_values(&self) -> Vec<(u32, f64)> {
+        self.plugin_info
+            .as_ref()
+            .and_then(|p| p.controller_info.as_ref())
+            .map(|c| {
+                c.parameters
+                    .iter()
+                    .map(|p| (p.id, p.current_value))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether any parameter differs from when a preset was last saved or loaded.
+    fn modified_since_save(&self) -> bool {
+        self.saved_parameter_values.as_ref().is_some_and(|saved| {
+            let current = self.current_parameter_values();
+            saved.len() != current.len()
+                || saved
+                    .iter()
+                    .zip(&current)
+                    .any(|(a, b)| a.0 != b.0 || (a.1 - b.1).abs() > vst3_host::MORPH_EPSILON)
+        })
+    }
+
+    /// Record what changed since the last undo step as a new one, once nothing is being
+    /// dragged, held back or automated. A different parameter list (another plugin) starts
+    /// the history over.
+    fn track_undo_steps(&mut self, ctx: &egui::Context) {
+        let busy = ctx.input(|i| i.pointer.any_down())
+            || !self.param_debouncer.is_empty()
+            || self.morphing
+            || self.automation.value_now(Instant::now()).is_some();
+        if busy {
+            return;
+        }
+        let current = self.current_parameter_values();
+        let same_parameters = current.len() == self.undo_baseline.len()
+            && current
+                .iter()
+                .zip(&self.undo_baseline)
+                .all(|(a, b)| a.0 == b.0);
+        if !same_parameters {
+            self.undo_stack.clear();
+            self.saved_parameter_values = None;
+        } else {
+            self.undo_stack.record(
+                self.undo_baseline
+                    .iter()
+                    .zip(&current)
+                    .map(|(&(id, before), &(_, after))| (id, before, after)),
+            );
+            self.undo_stack.prune_older_than(UNDO_MAX_AGE);
+        }
+        self.undo_baseline = current;
+    }
+
+    /// Restore the parameters to `position` on the undo timeline.
+    fn time_travel(&mut self, position: usize) {
+        for (id, value) in self.undo_stack.time_travel(position) {
+            if let Err(e) = self.set_parameter_value(id, value) {
+                self.set_error(format!("Failed to restore parameter: {e}"));
+            }
+        }
+        // The restored values are where the timeline now is, not a new edit.
+        self.undo_baseline = self.current_parameter_values();
+    }
+
     fn get_filtered_parameters<'a>(
         &self,
         parameters: &'a [ParameterInfo],
     ) -> Vec<(usize, &'a ParameterInfo)> {
-        parameters
+        let mut filtered: Vec<_> = parameters
             .iter()
             .enumerate()
             .filter(|(_, param)| {
@@ -3037,13 +6855,7 @@ impl VST3Inspector {
                 }
 
                 // Type filter
-                let type_matches = match self.parameter_filter {
-                    ParameterFilter::All => true,
-                    ParameterFilter::Writable => (param.flags & 0x1) == 0, // Not read-only
-                    ParameterFilter::ReadOnly => (param.flags & 0x1) != 0, // Read-only
-                    ParameterFilter::HasSteps => param.step_count > 0,
-                    ParameterFilter::HasUnits => !param.units.is_empty(),
-                };
+                let type_matches = self.parameter_filter.matches(param);
 
                 // Modified filter
                 let modified_matches = !self.show_only_modified
@@ -3051,7 +6863,9 @@ impl VST3Inspector {
 
                 type_matches && modified_matches
             })
-            .collect()
+            .collect();
+        self.current_sort.apply(&mut filtered);
+        filtered
     }
 
     /// Load a plugin through the `vst3-host` library and start playing it.
@@ -3063,17 +6877,30 @@ impl VST3Inspector {
     /// thread; `update` polls [`Self::poll_pending_load`] and finalizes when ready. Loading a
     /// plugin in-process can't be interrupted, but at least the window stays responsive.
     fn load_plugin(&mut self, plugin_path: String) {
+        if plugin_path.ends_with(".clap") {
+            self.set_error("CLAP plugins are listed but can't be opened in the inspector yet");
+            return;
+        }
         println!("Loading plugin: {}", plugin_path);
 
         // Drop any previously playing plugin first (stops audio, releases the device).
         self.audio = None;
+        self.quick_edit = None;
+        self.block_statistics = None;
+        self.spectrum = None;
+        self.stream_monitor = None;
+        // Edits held for the previous plugin's parameters mean nothing to the next one.
+        self.param_debouncer.flush();
+        self.key_shortcuts.clear();
+        self.plugin_log_rx = None;
+        self.editor_param_rx = None;
         self.plugin_info = None;
         self.selected_parameter = None;
         self.current_page = 0;
-        self.plugin_window = None; // close any open editor from the previous plugin
-        self.gui_attached = false;
+        self.close_plugin_gui(); // close any open editor from the previous plugin
         self.is_processing = false;
         self.report_json = None;
+        self.factory_mismatches.clear();
         self.last_error = None;
         self.last_error_time = None;
         // A/B snapshots belong to the previous plugin; applying them to a different plugin would
@@ -3081,36 +6908,117 @@ impl VST3Inspector {
         self.slot_a = None;
         self.slot_b = None;
         self.active_slot = None;
+        self.morph_a = None;
+        self.morph_b = None;
+        self.morphing = false;
+        self.morph_t = 0.0;
         self.plugin_path = plugin_path.clone();
 
         let name = get_plugin_name_from_path(&plugin_path);
         let sample_rate = self.sample_rate;
         let block_size = self.block_size as usize;
+        let tempo_bpm = self.tempo_bpm;
+        let meter_ballistics = self.meter_ballistics;
+        let meter_hold_mode = self.meter_hold_mode;
+        let spectrum_window = self.spectrum_window;
+        let input_device = self.preferences.input_device.clone();
+        let audio_device = self.preferences.preferred_audio_device.clone();
+        let init_flags = self.preferences.plugin_init_flags.clone();
+        let bus_presets = self
+            .preferences
+            .bus_configs
+            .get(&plugin_path)
+            .cloned()
+            .unwrap_or_default();
+        // Only a reload of the same plugin keeps its bus preset.
+        let bus_preset = self
+            .bus_preset
+            .clone()
+            .filter(|_| self.preferences.last_loaded_plugin.as_ref() == Some(&plugin_path));
         let path = plugin_path; // moved into the worker thread
+        let stream_errors = self.stream_errors.0.clone();
         let (tx, rx) = std::sync::mpsc::channel();
 
         std::thread::spawn(move || {
             let result = (|| {
                 let detail = vst3_host::get_detailed_plugin_info(std::path::Path::new(&path))
                     .map_err(|e| format!("Failed to introspect plugin: {e}"))?;
-                let mut host = Vst3Host::builder()
+                let mut builder = Vst3Host::builder()
                     .sample_rate(sample_rate)
                     .block_size(block_size)
+                    .tempo(tempo_bpm)
+                    .meter_ballistics(meter_ballistics)
+                    .spectrum_window(spectrum_window);
+                if let Some(journal) = load_journal_path() {
+                    builder = builder.load_journal(journal);
+                }
+                for (pattern, flags) in init_flags {
+                    builder = builder.plugin_init_flags(pattern, flags);
+                }
+                let mut host = builder
                     .build()
                     .map_err(|e| format!("Failed to build host: {e}"))?;
+                host.set_meter_hold_mode(meter_hold_mode);
+                if let Some(device) = &audio_device {
+                    host.set_audio_device(device)
+                        .map_err(|e| format!("Failed to select audio device: {e}"))?;
+                }
+                for (name, config) in bus_presets {
+                    host.add_bus_config_preset(&path, name, config);
+                }
+                if let Some(name) = &bus_preset {
+                    let _ = host.set_bus_config_preset(name);
+                }
+                host.set_stream_error_handler(Some(vst3_host::AudioStreamErrorHandler::new(
+                    move |error| {
+                        let _ = stream_errors.send(error);
+                    },
+                )));
                 let plugin = host
                     .load_plugin(&path)
                     .map_err(|e| format!("Failed to load plugin: {e}"))?;
                 let params = plugin.get_parameters().unwrap_or_default();
-                let audio = host
-                    .play(plugin)
-                    .map_err(|e| format!("Failed to start audio playback: {e}"))?;
+                let has_controller = plugin.has_controller();
+                let known_issues = host
+                    .compatibility_db()
+                    .lookup(plugin.info())
+                    .map(|entry| entry.known_issues.clone())
+                    .unwrap_or_default();
+                let factory_mismatches = plugin.factory_default_mismatches();
+                let slider_curves = plugin.detect_slider_curves();
+                let widgets = params
+                    .iter()
+                    .map(|p| {
+                        let display = plugin.format_parameter(p.id, p.value).unwrap_or_default();
+                        let widget = vst3_host::infer_widget_type(p, &display);
+                        let labels = match widget {
+                            vst3_host::WidgetType::ComboBox
+                            | vst3_host::WidgetType::ToggleButton => plugin.step_labels(p),
+                            _ => Vec::new(),
+                        };
+                        (p.id, (widget, labels))
+                    })
+                    .collect();
+                let audio = match &input_device {
+                    Some(input) => play_with_live_input(&host, plugin, input),
+                    None => host.play(plugin),
+                }
+                .map_err(|e| format!("Failed to start audio playback: {e}"))?;
                 let is_processing = audio.lock().is_processing();
                 Ok(LoadedPlugin {
                     detail,
                     params,
+                    factory_mismatches,
                     audio,
+                    block_statistics: host.block_statistics(),
+                    spectrum: host.spectrum(),
+                    stream_monitor: host.stream_monitor(),
+                    slider_curves,
+                    widgets,
+                    has_controller,
+                    known_issues,
                     is_processing,
+                    actual_sample_rate: host.actual_sample_rate(),
                 })
             })();
             let _ = tx.send(result);
@@ -3131,9 +7039,53 @@ impl VST3Inspector {
                     vst3_host::PluginReport::new(loaded.detail.clone(), loaded.params.clone())
                         .to_json()
                         .ok();
-                self.plugin_info = Some(Self::build_plugin_info(&loaded.detail, &loaded.params));
+                self.plugin_info = Some(PluginInfo {
+                    known_issues: loaded.known_issues,
+                    ..Self::build_plugin_info(
+                        &loaded.detail,
+                        &loaded.params,
+                        &loaded.widgets,
+                        loaded.has_controller,
+                    )
+                });
+                self.factory_mismatches = loaded.factory_mismatches;
+                // A different plugin starts in index order with its default buses; reloading
+                // the same one (or restoring it at startup) keeps the sort and bus preset.
+                if self.preferences.last_loaded_plugin.as_deref() != Some(self.plugin_path.as_str())
+                {
+                    self.current_sort = ParameterSort::default();
+                    self.bus_preset = None;
+                }
+                self.slider_curves = Arc::new(loaded.slider_curves);
+                self.key_shortcuts = loaded.audio.lock().get_key_shortcuts().unwrap_or_default();
+                self.catalog_plugins([loaded.detail.info.clone()]);
                 self.is_processing = loaded.is_processing;
+                self.actual_sample_rate = (loaded.actual_sample_rate != self.sample_rate)
+                    .then_some(loaded.actual_sample_rate);
+                self.plugin_log.clear();
+                self.output_voices.clear();
+                self.plugin_log_rx = loaded.audio.lock().log_lines();
+                // The load thread plays through its own host; hand the arpeggiator over.
+                if let Some(arp) = self.host.arpeggiator() {
+                    loaded.audio.set_arpeggiator(Some(arp.clone()));
+                }
+                // Editor knob turns then show on the next frame, not after the next block.
+                self.editor_param_rx = loaded.audio.lock().subscribe_parameter_changes();
                 self.audio = Some(loaded.audio);
+                self.block_statistics = Some(loaded.block_statistics);
+                self.spectrum = Some(loaded.spectrum);
+                self.stream_monitor = Some(loaded.stream_monitor);
+                self.apply_spectrum_settings();
+                if self.voice_limit_on {
+                    self.apply_voice_limit();
+                }
+                self.apply_channel_strips(self.channel_strips.strips());
+                self.apply_velocity_curve();
+                self.apply_soft_takeover();
+                self.apply_input_gain();
+                self.apply_auto_limiter();
+                self.apply_virtual_midi_out();
+                self.apply_pending_session();
                 self.pending_load = None;
                 println!("Plugin loaded successfully!");
                 if self.preferences.auto_start_processing {
@@ -3153,11 +7105,186 @@ impl VST3Inspector {
         }
     }
 
+    /// The "Input Device" selector: live audio input for effects, applied on the next load.
+    /// The Audio Device selector: which output device plugins play through (applied on load).
+    fn show_output_device_selector(&mut self, ui: &mut egui::Ui) {
+        ui.label("Audio Device:");
+        let devices = self
+            .output_devices
+            .get_or_insert_with(|| self.host.list_audio_output_devices().unwrap_or_default());
+        let config = vst3_host::AudioConfig {
+            sample_rate: self.sample_rate,
+            block_size: self.block_size as usize,
+            output_channels: 2,
+            ..Default::default()
+        };
+        let mut choice = self.preferences.preferred_audio_device.clone();
+        egui::ComboBox::from_id_salt("output_device_selector")
+            .selected_text(choice.as_deref().unwrap_or("System default"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut choice, None, "System default");
+                for device in devices.iter() {
+                    let mut label = egui::RichText::new(&device.name);
+                    if !device.supports_config(&config) {
+                        label = label.weak();
+                    }
+                    ui.selectable_value(&mut choice, Some(device.name.clone()), label)
+                        .on_hover_text(device_summary(device));
+                }
+            })
+            .response
+            .on_hover_text(
+                "Output device plugins play through (applied on load); greyed devices \
+                 don't support the current sample rate or block size",
+            );
+        if ui
+            .small_button("\u{27f3}")
+            .on_hover_text("Refresh the audio device list")
+            .clicked()
+        {
+            self.output_devices = None;
+        }
+        if choice != self.preferences.preferred_audio_device {
+            self.preferences.preferred_audio_device = choice;
+            if let Err(e) = self.preferences.save() {
+                self.set_error(format!("Failed to save preferences: {e}"));
+            }
+        }
+    }
+
+    fn show_input_device_selector(&mut self, ui: &mut egui::Ui) {
+        ui.label("Input Device:");
+        let devices = self.input_devices.get_or_insert_with(|| {
+            vst3_host::backends::CpalBackend::new()
+                .and_then(|b| b.list_input_devices())
+                .unwrap_or_default()
+        });
+        let mut choice = self.preferences.input_device.clone();
+        egui::ComboBox::from_id_salt("input_device_selector")
+            .selected_text(choice.as_deref().unwrap_or("None"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut choice, None, "None");
+                ui.selectable_value(
+                    &mut choice,
+                    Some(SYSTEM_DEFAULT_INPUT.to_string()),
+                    SYSTEM_DEFAULT_INPUT,
+                );
+                for name in devices.iter() {
+                    ui.selectable_value(&mut choice, Some(name.clone()), name);
+                }
+            })
+            .response
+            .on_hover_text("Live audio fed to the plugin's inputs, for effects (applied on load)");
+        if ui
+            .small_button("\u{27f3}")
+            .on_hover_text("Refresh the input device list")
+            .clicked()
+        {
+            self.input_devices = None;
+        }
+        if choice != self.preferences.input_device {
+            self.preferences.input_device = choice;
+            if let Err(e) = self.preferences.save() {
+                self.set_error(format!("Failed to save preferences: {e}"));
+            }
+        }
+    }
+
+    /// Record plugins' metadata in the catalog that files the Plugins tab by category.
+    fn catalog_plugins(&mut self, plugins: impl IntoIterator<Item = vst3_host::PluginInfo>) {
+        for info in plugins {
+            self.preferences
+                .plugin_catalog
+                .insert(info.path.to_string_lossy().to_string(), info);
+        }
+        if let Err(e) = self.preferences.save() {
+            self.set_error(format!("Failed to save preferences: {e}"));
+        }
+    }
+
+    /// Probe every listed plugin in throwaway processes (so a crashing plugin can't take the
+    /// inspector down) to learn their categories. CLAP plugins are read in-process: the probe
+    /// only speaks VST3.
+    fn start_category_scan(&mut self) {
+        // Same locations as the plugin list (see `discover_plugin_paths`).
+        let mut builder = Vst3Host::builder().scan_default_paths();
+        for p in &self.preferences.custom_plugin_paths {
+            builder = builder.add_scan_path(p);
+        }
+        let host = match builder.build() {
+            Ok(host) => host,
+            Err(e) => {
+                self.set_error(format!("Failed to build host: {e}"));
+                return;
+            }
+        };
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let clap = vst3_host::scan_for_clap_plugins(&host.scan_clap_plugin_paths());
+            let _ = tx.send((host.discover_plugins_safe(), clap));
+        });
+        self.category_scan = Some(rx);
+    }
+
+    /// Prompt for a file and write an HTML report of the listed plugins the catalog has
+    /// metadata for (loaded or category-scanned ones).
+    fn export_plugins_report(&mut self) {
+        let plugins: Vec<vst3_host::PluginInfo> = self
+            .discovered_plugins
+            .iter()
+            .filter_map(|path| self.preferences.plugin_catalog.get(path).cloned())
+            .collect();
+        if plugins.is_empty() {
+            self.set_error("No plugin metadata yet \u{2014} load plugins or run Scan Categories");
+            return;
+        }
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Export Plugin Report")
+            .add_filter("HTML", &["html"])
+            .set_file_name("plugins.html")
+            .save_file()
+        else {
+            return; // user cancelled
+        };
+        match std::fs::write(&path, vst3_host::plugins_to_html(&plugins)) {
+            Ok(()) => self.set_error(format!(
+                "Exported {} plugin(s) to {}",
+                plugins.len(),
+                path.display()
+            )),
+            Err(e) => self.set_error(format!("Failed to export report: {e}")),
+        }
+    }
+
+    fn poll_category_scan(&mut self) {
+        let Some(rx) = &self.category_scan else {
+            return;
+        };
+        match rx.try_recv() {
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            Ok((report, clap)) => {
+                self.category_scan = None;
+                let skipped = report.skipped.len();
+                self.catalog_plugins(report.plugins.into_iter().map(|d| d.info).chain(clap));
+                self.scan_failures = Some(report.skipped);
+                if skipped > 0 {
+                    self.set_error(format!("Category scan skipped {skipped} plugin(s)"));
+                }
+            }
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.category_scan = None;
+                self.set_error("Category scan stopped unexpectedly");
+            }
+        }
+    }
+
     /// Map the library's `DetailedPluginInfo` + parameter list into the inspector's own
     /// `PluginInfo` (which drives the existing UI rendering).
     fn build_plugin_info(
         detail: &vst3_host::DetailedPluginInfo,
         params: &[vst3_host::parameters::Parameter],
+        widgets: &HashMap<u32, (vst3_host::WidgetType, Vec<String>)>,
+        has_controller: bool,
     ) -> PluginInfo {
         let map_buses = |buses: &[vst3_host::BusInfo]| -> Vec<BusInfo> {
             buses
@@ -3193,6 +7320,8 @@ impl VST3Inspector {
                 unit_id: 0,
                 flags: p.flags as i32,
                 current_value: p.value,
+                widget: widgets.get(&p.id).map_or_else(Default::default, |w| w.0),
+                step_labels: widgets.get(&p.id).map(|w| w.1.clone()).unwrap_or_default(),
             })
             .collect();
 
@@ -3216,20 +7345,24 @@ impl VST3Inspector {
                 })
                 .collect(),
             component_info: Some(component_info),
-            controller_info: Some(ControllerInfo {
+            controller_info: has_controller.then_some(ControllerInfo {
                 parameter_count: parameters.len() as i32,
                 parameters,
             }),
             has_gui: detail.info.has_gui,
             gui_size: None,
+            known_issues: Vec::new(),
         }
     }
 
     fn stop_processing(&mut self) {
-        if let Some(audio) = &self.audio {
-            if let Err(e) = audio.lock().stop_processing() {
-                println!("stop_processing failed: {e}");
-            }
+        let result = self
+            .audio
+            .as_ref()
+            .map(|audio| audio.lock().stop_processing());
+        if let Some(Err(e)) = result {
+            println!("stop_processing failed: {e}");
+            self.note_helper_crash(&e);
         }
         self.is_processing = false;
     }
@@ -3239,11 +7372,13 @@ impl VST3Inspector {
             Some(a) => a,
             None => return Err("No plugin loaded".to_string()),
         };
-        audio
-            .lock()
-            .start_processing()
-            .map_err(|e| format!("Failed to start processing: {e}"))?;
+        let result = audio.lock().start_processing();
+        if let Err(e) = result {
+            self.note_helper_crash(&e);
+            return Err(format!("Failed to start processing: {e}"));
+        }
         self.is_processing = true;
+        self.observed_ranges.clear();
         Ok(())
     }
 
@@ -3262,18 +7397,11 @@ impl VST3Inspector {
             (velocity * 127.0) as u8,
         );
 
-        let ch = self.current_midi_channel();
-        let audio = match &self.audio {
-            Some(a) => a,
-            None => return Err("No plugin loaded".to_string()),
-        };
-        // Lock-free: queue onto the control ring (applied on the next audio block).
-        audio.send_midi(vst3_host::midi::MidiEvent::NoteOn {
-            channel: ch,
+        self.dispatch_midi(vst3_host::midi::MidiEvent::NoteOn {
+            channel: self.current_midi_channel(),
             note: pitch as u8,
             velocity: (velocity * 127.0) as u8,
-        });
-        Ok(())
+        })
     }
 
     /// Send a MIDI Note Off event.
@@ -3291,16 +7419,35 @@ impl VST3Inspector {
             (velocity * 127.0) as u8,
         );
 
-        let ch = self.current_midi_channel();
+        self.dispatch_midi(vst3_host::midi::MidiEvent::NoteOff {
+            channel: self.current_midi_channel(),
+            note: pitch as u8,
+            velocity: (velocity * 127.0) as u8,
+        })
+    }
+
+    /// Deliver a played event: through the routing matrix or keyboard split (if set), then to
+    /// the General MIDI bus when GM mode is on, else to the loaded plugin. The audio plugin
+    /// path is lock-free (queued onto the control ring, applied on the next audio block).
+    fn dispatch_midi(&self, event: vst3_host::MidiEvent) -> Result<(), String> {
+        let events = match self.host.route_midi(event) {
+            Some(routed) => routed.into_iter().map(|(_, e)| e).collect(),
+            None => vec![event],
+        };
+        if let Some(gm) = &self.gm_audio {
+            let mut bus = gm.lock();
+            for e in events {
+                bus.send_midi_event(e).map_err(|e| e.to_string())?;
+            }
+            return Ok(());
+        }
         let audio = match &self.audio {
             Some(a) => a,
             None => return Err("No plugin loaded".to_string()),
         };
-        audio.send_midi(vst3_host::midi::MidiEvent::NoteOff {
-            channel: ch,
-            note: pitch as u8,
-            velocity: (velocity * 127.0) as u8,
-        });
+        for e in events {
+            audio.send_midi(e);
+        }
         Ok(())
     }
 
@@ -3331,6 +7478,7 @@ impl VST3Inspector {
         if let Ok(mut m) = self.meter_right.lock() {
             m.reset();
         }
+        self.meter_readings = [0.0; 2];
         println!("Audio panic complete");
     }
 
@@ -3470,6 +7618,16 @@ impl VST3Inspector {
             },
         };
 
+        if let Ok(mut recorder) = self.smf_recorder.lock() {
+            if recorder.is_recording() {
+                if let Some(recorded) = monitor_event_to_midi(event_type, channel, data1, data2) {
+                    let elapsed = self.smf_record_started.elapsed().as_secs_f64();
+                    let sample_pos = (elapsed * self.sample_rate) as i64;
+                    recorder.record_event(&recorded, sample_pos, self.sample_rate);
+                }
+            }
+        }
+
         let event = MidiEvent {
             timestamp: Instant::now(),
             direction,
@@ -3488,6 +7646,101 @@ impl VST3Inspector {
         }
     }
 
+    /// Start capturing the monitored events at the current tempo, dropping any unsaved take.
+    fn start_smf_recording(&mut self) {
+        let transport = vst3_host::TransportState {
+            tempo: self.tempo_bpm,
+            ..vst3_host::TransportState::from_config(&vst3_host::AudioConfig::default())
+        };
+        let mut recorder = self.smf_recorder.lock().unwrap();
+        recorder.set_transport(transport);
+        recorder.start_recording(vst3_host::SmfRecorder::DEFAULT_TICKS_PER_QUARTER);
+        self.smf_record_started = Instant::now();
+    }
+
+    /// Stop recording and ask where to save the take.
+    fn save_smf_recording(&mut self) {
+        self.smf_recorder.lock().unwrap().stop_recording();
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Save MIDI Recording")
+            .add_filter("MIDI", &["mid", "midi"])
+            .set_file_name("recording.mid")
+            .save_file()
+        else {
+            return;
+        };
+        let result = self.smf_recorder.lock().unwrap().save(&path);
+        match result {
+            Ok(()) => self.set_error(format!("Saved MIDI recording to {}", path.display())),
+            Err(e) => self.set_error(format!("Failed to save MIDI recording: {e}")),
+        }
+    }
+
+    /// Play notes from the computer keyboard (see [`KeyboardMapping`]) and shift its octave
+    /// with Z/X. Ignored while a text field has focus, except that held notes still release.
+    fn handle_computer_keyboard(&mut self, ctx: &egui::Context) {
+        let typing = ctx.egui_wants_keyboard_input();
+        let (pressed, released, shift) = ctx.input(|i| {
+            let pressed: Vec<(egui::Key, u8)> = self
+                .keyboard_input
+                .keys
+                .keys()
+                .filter(|key| i.key_pressed(**key))
+                .filter_map(|&key| self.keyboard_input.note_for(key).map(|note| (key, note)))
+                .collect();
+            let released: Vec<egui::Key> = self
+                .keyboard_notes
+                .keys()
+                .filter(|key| i.key_released(**key))
+                .copied()
+                .collect();
+            let shift = i.key_pressed(keyboard_input::OCTAVE_UP_KEY) as i8
+                - i.key_pressed(keyboard_input::OCTAVE_DOWN_KEY) as i8;
+            (pressed, released, shift)
+        });
+
+        for key in released {
+            if let Some(note) = self.keyboard_notes.remove(&key) {
+                if let Err(e) = self.send_midi_note_off(self.selected_midi_channel, note, 0.0) {
+                    self.set_error(format!("Failed to send note off: {e}"));
+                }
+            }
+        }
+        if typing {
+            return;
+        }
+        for (key, note) in pressed {
+            // `key_pressed` repeats while a key is held; only the first press starts a note.
+            if self.keyboard_notes.contains_key(&key) {
+                continue;
+            }
+            self.keyboard_notes.insert(key, note as i16);
+            if let Err(e) = self.send_midi_note_on(
+                self.selected_midi_channel,
+                note as i16,
+                self.played_velocity(),
+            ) {
+                self.set_error(format!("Failed to send note on: {e}"));
+            }
+        }
+        if shift != 0 {
+            let octave = self.keyboard_input.octave_shift().saturating_add(shift);
+            self.keyboard_input = KeyboardMapping::qwerty_piano(octave);
+            self.preferences.keyboard_octave_shift = self.keyboard_input.octave_shift();
+            if let Err(e) = self.preferences.save() {
+                eprintln!("Failed to save keyboard octave: {e}");
+            }
+        }
+    }
+
+    /// Persist the host's keyboard split (or its absence) in the preferences.
+    fn save_keyboard_split(&mut self) {
+        self.preferences.keyboard_split = self.host.keyboard_split().cloned();
+        if let Err(e) = self.preferences.save() {
+            eprintln!("Failed to save keyboard split: {e}");
+        }
+    }
+
     fn draw_piano_keyboard(&mut self, ui: &mut egui::Ui) {
         let white_key_width = 24.0;
         let white_key_height = 120.0;
@@ -3503,6 +7756,53 @@ impl VST3Inspector {
         let total_white_keys = keys_per_octave * octave_count + 1; // +1 for final C
         let total_width = total_white_keys as f32 * white_key_width;
 
+        // The split bar sits right above the keys, sharing their horizontal layout: C0 (MIDI
+        // 24) is the first white key, and notes outside the keyboard pin to its edges.
+        let first_note = 24u8;
+        let note_x = |note: u8| -> f32 {
+            let Some(offset) = note.checked_sub(first_note) else {
+                return 0.0;
+            };
+            let octave = (offset / 12) as f32;
+            let (white, black) = match offset % 12 {
+                0 => (0, false),
+                1 => (0, true),
+                2 => (1, false),
+                3 => (1, true),
+                4 => (2, false),
+                5 => (3, false),
+                6 => (3, true),
+                7 => (4, false),
+                8 => (4, true),
+                9 => (5, false),
+                10 => (5, true),
+                _ => (6, false),
+            };
+            let x = (octave * keys_per_octave as f32 + white as f32) * white_key_width
+                + if black { white_key_width } else { 0.0 };
+            x.min(total_width)
+        };
+        let note_at_x = |x: f32| -> u8 {
+            let white = (x / white_key_width)
+                .floor()
+                .clamp(0.0, total_white_keys as f32);
+            let (octave, key) = (white as u8 / 7, white as u8 % 7);
+            let semitone = [0, 2, 4, 5, 7, 9, 11][key as usize];
+            first_note + octave * 12 + semitone
+        };
+        if let Some(split) = self.host.keyboard_split() {
+            let mut split = split.clone();
+            if split_editor::show_split_bar(ui, total_width, &mut split, note_x, note_at_x) {
+                self.host.set_keyboard_split(split);
+                self.split_dirty = true;
+            }
+        }
+        // Saved once the boundary drag ends rather than every frame of it.
+        if self.split_dirty && !ui.input(|i| i.pointer.any_down()) {
+            self.split_dirty = false;
+            self.save_keyboard_split();
+        }
+
         // Allocate space for the keyboard
         let (response, painter) = ui.allocate_painter(
             egui::vec2(total_width, white_key_height),
@@ -3543,7 +7843,9 @@ impl VST3Inspector {
                 );
 
                 let note = note_for_white_key(octave_start + octave, key);
-                let is_pressed = self.pressed_keys.contains(&note);
+                let is_pressed = self.pressed_keys.contains(&note)
+                    || self.keyboard_notes.values().any(|&n| n == note);
+                let computer_key = self.keyboard_input.key_for(note as u8);
 
                 // Check if mouse is over this key
                 let mut is_hover = false;
@@ -3559,6 +7861,15 @@ impl VST3Inspector {
                     egui::Color32::GRAY
                 } else if is_hover {
                     egui::Color32::from_gray(240)
+                } else if computer_key.is_some() {
+                    egui::Color32::from_rgb(225, 238, 255)
+                } else if let Some(region) = self
+                    .host
+                    .keyboard_split()
+                    .and_then(|split| split_editor::region_of(split, note as u8))
+                {
+                    // Tint each split region's keys with its color.
+                    egui::Color32::WHITE.lerp_to_gamma(split_editor::region_color(region), 0.25)
                 } else {
                     egui::Color32::WHITE
                 };
@@ -3571,6 +7882,19 @@ impl VST3Inspector {
                     egui::epaint::StrokeKind::Middle,
                 );
 
+                if let Some(computer_key) = computer_key {
+                    painter.text(
+                        egui::pos2(
+                            x + white_key_width / 2.0,
+                            rect.top() + black_key_height + 10.0,
+                        ),
+                        egui::Align2::CENTER_CENTER,
+                        computer_key.name(),
+                        egui::FontId::new(11.0, egui::FontFamily::Monospace),
+                        egui::Color32::from_rgb(40, 90, 170),
+                    );
+                }
+
                 // Draw note label
                 let note_names = ["C", "D", "E", "F", "G", "A", "B"];
                 let label = format!("{}{}", note_names[key as usize], octave_start + octave);
@@ -3614,7 +7938,9 @@ impl VST3Inspector {
                 let black_note_names = ["C#", "D#", "F#", "G#", "A#"];
                 let note_name = format!("{}{}", black_note_names[i], octave_start + octave);
                 let note = note_name_to_midi(&note_name).unwrap_or(0) as i16;
-                let is_pressed = self.pressed_keys.contains(&note);
+                let is_pressed = self.pressed_keys.contains(&note)
+                    || self.keyboard_notes.values().any(|&n| n == note);
+                let computer_key = self.keyboard_input.key_for(note as u8);
 
                 // Check if mouse is over this key (black keys take priority)
                 let mut is_hover = false;
@@ -3630,6 +7956,8 @@ impl VST3Inspector {
                     egui::Color32::from_gray(60)
                 } else if is_hover {
                     egui::Color32::from_gray(40)
+                } else if computer_key.is_some() {
+                    egui::Color32::from_rgb(20, 35, 70)
                 } else {
                     egui::Color32::BLACK
                 };
@@ -3642,6 +7970,16 @@ impl VST3Inspector {
                     egui::epaint::StrokeKind::Middle,
                 );
 
+                if let Some(computer_key) = computer_key {
+                    painter.text(
+                        egui::pos2(x + black_key_width / 2.0, key_rect.top() + 10.0),
+                        egui::Align2::CENTER_CENTER,
+                        computer_key.name(),
+                        egui::FontId::new(10.0, egui::FontFamily::Monospace),
+                        egui::Color32::from_rgb(150, 190, 255),
+                    );
+                }
+
                 // Draw MIDI number on black key
                 let text_color = if is_pressed {
                     egui::Color32::from_gray(200)
@@ -3667,7 +8005,11 @@ impl VST3Inspector {
                 // Mouse down - send note on
                 if !self.pressed_keys.contains(&note) {
                     self.pressed_keys.insert(note);
-                    if let Err(e) = self.send_midi_note_on(self.selected_midi_channel, note, 0.8) {
+                    if let Err(e) = self.send_midi_note_on(
+                        self.selected_midi_channel,
+                        note,
+                        self.played_velocity(),
+                    ) {
                         self.set_error(format!("Failed to send note on: {e}"));
                     }
                 }
@@ -3684,6 +8026,71 @@ impl VST3Inspector {
             }
             self.pressed_keys.clear();
         }
+
+        self.draw_wheel_strips(ui, total_width, white_key_width);
+    }
+
+    /// The pitch bend and mod wheel strips, each two white keys wide, under the keyboard's
+    /// left end and within its `keyboard_width`.
+    fn draw_wheel_strips(&mut self, ui: &mut egui::Ui, keyboard_width: f32, key_width: f32) {
+        use wheel_strips::StripKind;
+        let strip_width = (2.0 * key_width).min(keyboard_width / 2.0);
+        ui.add_space(4.0);
+        let (bend_changed, mod_changed) = ui
+            .allocate_ui_with_layout(
+                egui::vec2(keyboard_width, wheel_strips::STRIP_HEIGHT),
+                egui::Layout::left_to_right(egui::Align::Min),
+                |ui| {
+                    let bend = wheel_strips::show(
+                        ui,
+                        StripKind::PitchBend,
+                        strip_width,
+                        &mut self.pitch_bend,
+                    );
+                    let modulation = wheel_strips::show(
+                        ui,
+                        StripKind::ModWheel,
+                        strip_width,
+                        &mut self.mod_wheel,
+                    );
+                    (bend, modulation)
+                },
+            )
+            .inner;
+        if bend_changed {
+            let event =
+                vst3_host::MidiEvent::pitch_bend(self.current_midi_channel(), self.pitch_bend);
+            let (bytes, _) = event.to_midi_bytes();
+            self.log_midi_event(
+                MidiDirection::Input,
+                6, // Pitch Bend
+                self.selected_midi_channel as u8,
+                bytes[1],
+                bytes[2],
+            );
+            if let Err(e) = self.dispatch_midi(event) {
+                self.set_error(format!("Failed to send pitch bend: {e}"));
+            }
+        }
+        let cc = wheel_strips::mod_wheel_cc(self.mod_wheel);
+        if mod_changed && self.last_mod_wheel_cc != Some(cc) {
+            self.last_mod_wheel_cc = Some(cc);
+            self.log_midi_event(
+                MidiDirection::Input,
+                3, // Control Change
+                self.selected_midi_channel as u8,
+                1,
+                cc,
+            );
+            let event = vst3_host::MidiEvent::ControlChange {
+                channel: self.current_midi_channel(),
+                controller: 1,
+                value: cc,
+            };
+            if let Err(e) = self.dispatch_midi(event) {
+                self.set_error(format!("Failed to send mod wheel: {e}"));
+            }
+        }
     }
 }
 
@@ -3699,47 +8106,120 @@ impl VST3Inspector {
 
         // Build the library host once. If this fails we still construct a usable (but
         // plugin-less) inspector so the GUI can launch and surface the error.
-        let host = Vst3Host::builder()
+        let mut builder = Vst3Host::builder()
             .sample_rate(sample_rate)
-            .block_size(block_size as usize)
-            .build()
-            .unwrap_or_else(|e| {
-                eprintln!("Failed to build Vst3Host: {e}");
-                // Fall back to a default host; if that also fails, panic is acceptable
-                // since the app cannot function without it.
-                Vst3Host::new().expect("failed to build a default Vst3Host")
-            });
+            .block_size(block_size as usize);
+        if let Some(journal) = load_journal_path() {
+            builder = builder.load_journal(journal);
+        }
+        let mut host = builder.build().unwrap_or_else(|e| {
+            eprintln!("Failed to build Vst3Host: {e}");
+            // Fall back to a default host; if that also fails, panic is acceptable
+            // since the app cannot function without it.
+            Vst3Host::new().expect("failed to build a default Vst3Host")
+        });
+        if let Some(split) = preferences.keyboard_split.clone() {
+            host.set_keyboard_split(split);
+        }
+        host.set_keyboard_presets(preferences.keyboard_presets.clone());
+        for (plugin, annotation) in &preferences.plugin_notes {
+            // The host keeps annotations in memory only here (the preferences persist them),
+            // so this can't fail.
+            let _ = host.annotate_plugin(std::path::Path::new(plugin), annotation.clone());
+        }
+        for (pattern, flags) in &preferences.plugin_init_flags {
+            // In memory only, like the annotations.
+            let _ = host.set_plugin_init_flags(std::path::Path::new(pattern), *flags);
+        }
+        // Read before anything is loaded: the next load rewrites the journal.
+        let interrupted_load = host.interrupted_load();
+        host.clear_interrupted_load();
 
         Self {
             plugin_path: path.to_string(),
             plugin_info: None,
             report_json: None,
+            factory_mismatches: Vec::new(),
             plugin_window: None,
+            key_shortcuts: Vec::new(),
+            shortcuts_modal_open: false,
+            slider_curves: Arc::default(),
             discovered_plugins: Vec::new(),
+            category_scan: None,
+            scan_failures: None,
+            plugin_tag_filter: String::new(),
+            favorites_first: false,
+            notes_editor: None,
+            compat_editor: None,
+            interrupted_load,
             host,
             audio: None,
             pending_load: None,
+            startup_loading: false,
             last_error: None,
             last_error_time: None,
+            helper_crash: None,
+            quick_edit: None,
             gui_attached: false,
             selected_parameter: None,
             parameter_search: String::new(),
             parameter_filter: ParameterFilter::All,
+            current_sort: preferences.parameter_sort,
+            bus_preset: None,
+            bus_preset_name: String::new(),
+            active_audio_buses: (Vec::new(), Vec::new()),
+            undo_stack: vst3_host::UndoStack::default(),
+            undo_baseline: Vec::new(),
+            saved_parameter_values: None,
             show_only_modified: false,
+            pending_parameter_reset: None,
+            copy_from_open: false,
+            copy_from_channel: None,
+            randomize_open: false,
+            randomize_constraints: vst3_host::RandomizeConstraints::default(),
+            rng: rand::SeedableRng::seed_from_u64(
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_nanos() as u64),
+            ),
+            pending_session: None,
+            offer_session_reload: false,
             table_scroll_to_selected: false,
             current_page: 0,
             items_per_page: 50,
             current_tab,
             parameter_being_edited: None,
+            param_tooltip: param_tooltip::HoverTooltip::default(),
+            observed_ranges: param_tooltip::ObservedRanges::default(),
             is_processing: false,
             block_size,
             sample_rate,
+            actual_sample_rate: None,
+            tempo_bpm: 120.0,
+            meter_ballistics: vst3_host::BallisticsPreset::Peak,
+            meter_hold_mode: vst3_host::MeterHoldMode::default(),
+            spectrum_window: vst3_host::WindowFunction::Hann,
+            spectrum_averaging: 4,
+            input_devices: None,
+            output_devices: None,
+            meter_readings: [0.0; 2],
+            tempo_sync_range_hz: (0.01, 20.0),
             pressed_keys: HashSet::new(),
+            pitch_bend: 0.0,
+            mod_wheel: 0.0,
+            last_mod_wheel_cc: None,
+            keyboard_input: KeyboardMapping::qwerty_piano(preferences.keyboard_octave_shift),
+            keyboard_notes: HashMap::new(),
             selected_midi_channel,
             midi_events: Arc::new(Mutex::new(Vec::new())),
             midi_event_filter: MidiEventFilter::default(),
             midi_monitor_paused: Arc::new(Mutex::new(false)),
             max_midi_events: 1000,
+            smf_recorder: Arc::new(Mutex::new(vst3_host::SmfRecorder::new(
+                vst3_host::TransportState::from_config(&vst3_host::AudioConfig::default()),
+            ))),
+            smf_record_started: Instant::now(),
+            output_voices: vst3_host::PolyphonyTracker::new(),
             preferences,
             // 20 dB/s fall, 3 s peak-hold — the classic VU-meter ballistic.
             meter_left: Arc::new(Mutex::new(PeakMeter::new(20.0, Duration::from_secs(3)))),
@@ -3747,10 +8227,57 @@ impl VST3Inspector {
             slot_a: None,
             slot_b: None,
             active_slot: None,
+            morph_a: None,
+            morph_b: None,
+            morphing: false,
+            morph_t: 0.0,
+            param_debouncer: vst3_host::ChangeDebouncer::default(),
             automation: AutomationState::new(),
             midi_player: MidiFilePlayer::default(),
             midi_input: MidiInputState::default(),
             midi_input_ports: MidiInputState::list_ports(),
+            pressure_binding: PressureBindingEditor::default(),
+            midi_binding_editor: MidiBindingEditor::default(),
+            saved_controller_presets: controller_presets_dir()
+                .map(|dir| midi_bindings::saved_presets(&dir))
+                .unwrap_or_default(),
+            controller_preset_name: String::new(),
+            keyboard_velocity: vst3_host::DEFAULT_KEYBOARD_VELOCITY,
+            selected_keyboard_preset: None,
+            keyboard_preset_name: String::new(),
+            arp_settings: vst3_host::Arpeggiator::new(
+                vst3_host::ArpPattern::Up,
+                vst3_host::NoteValue::Sixteenth,
+            ),
+            voice_limit_on: false,
+            voice_limit: vst3_host::PolyphonyLimiter::new(8, vst3_host::StealingStrategy::Oldest),
+            channel_strips: ChannelStripPanel::default(),
+            soft_takeover: false,
+            input_gain_db: 0.0,
+            auto_gain: false,
+            auto_gain_target_db: vst3_host::NOMINAL_INPUT_LEVEL_DB,
+            auto_limiter: true,
+            virtual_midi_out: false,
+            virtual_midi_out_name: "VST3 Inspector".to_string(),
+            bindings_dirty: false,
+            plugin_log: VecDeque::new(),
+            editor_param_rx: None,
+            plugin_log_rx: None,
+            graph_editor: GraphEditor::default(),
+            graph_add_path: None,
+            graph_dirty: false,
+            gm_audio: None,
+            gm_plugin_name: String::new(),
+            gm_strip: GeneralMidiChannelStrip::default(),
+            gm_mixer_view: false,
+            gm_mixer: MixBusView::default(),
+            freq_response: FreqResponseView::default(),
+            split_dirty: false,
+            output_health: None,
+            block_statistics: None,
+            spectrum: None,
+            stream_monitor: None,
+            stream_errors: std::sync::mpsc::channel(),
         }
     }
 }
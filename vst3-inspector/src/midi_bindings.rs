@@ -0,0 +1,298 @@
+//! The MIDI bindings table: every MIDI → parameter binding (CC, aftertouch, pitch bend,
+//! note) with its source, target, an editable range and an enable switch, plus MIDI learn.
+//!
+//! The bindings themselves live in the preferences so they survive restarts; the editor only
+//! holds the learn state. The CC bindings can be swapped wholesale for a controller preset
+//! (built-in or saved as JSON in the presets folder).
+
+use eframe::egui;
+use std::path::Path;
+use vst3_host::{CcBinding, ControllerPreset, MidiBinding, MidiEvent};
+
+const RANGE_WIDTH: f32 = 140.0;
+const RANGE_HEIGHT: f32 = 14.0;
+const HANDLE_RADIUS: f32 = 5.0;
+
+/// Learn state: the parameter the next learned binding targets, and whether a learn is
+/// waiting for MIDI.
+#[derive(Default)]
+pub struct MidiBindingEditor {
+    learn_param: Option<u32>,
+    learning: bool,
+}
+
+/// Replace the CC bindings in `bindings` with `preset`'s, keeping the other kinds.
+pub fn apply_controller_preset(bindings: &mut Vec<MidiBinding>, preset: &ControllerPreset) {
+    bindings.retain(|b| !matches!(b, MidiBinding::Cc(_)));
+    bindings.extend(preset.bindings.iter().copied().map(MidiBinding::Cc));
+}
+
+/// The CC bindings among `bindings`.
+pub fn cc_bindings(bindings: &[MidiBinding]) -> Vec<CcBinding> {
+    bindings
+        .iter()
+        .filter_map(|b| match b {
+            MidiBinding::Cc(cc) => Some(*cc),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The controller presets saved in `dir`, by name. Unreadable files are skipped.
+pub fn saved_presets(dir: &Path) -> Vec<ControllerPreset> {
+    let mut presets: Vec<ControllerPreset> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| ControllerPreset::load(&path).ok())
+        .collect();
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+    presets
+}
+
+/// A file name for a preset called `name`: anything but letters, digits, `-` and `_` becomes
+/// `_`.
+pub fn preset_file_name(name: &str) -> String {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{stem}.json")
+}
+
+/// "ch 3" / "any ch" for a binding's channel filter.
+fn channel_label(channel: Option<vst3_host::MidiChannel>) -> String {
+    channel.map_or("any ch".to_string(), |c| format!("ch {}", c.as_index() + 1))
+}
+
+/// Where a binding listens: its channel plus controller or note.
+pub fn source_label(binding: &MidiBinding) -> String {
+    let channel = channel_label(binding.channel());
+    let note = |n: Option<u8>| n.map_or("any note".to_string(), crate::midi_note_to_name);
+    match binding {
+        MidiBinding::Cc(b) => format!("{channel}, CC {}", b.controller),
+        MidiBinding::Aftertouch(b) => format!("{channel}, {}", note(b.note)),
+        MidiBinding::Note(b) => format!("{channel}, {}", note(b.note)),
+        MidiBinding::ChannelPressure(_) | MidiBinding::PitchBend(_) => channel,
+    }
+}
+
+/// A `[min, max]` bar with a draggable handle at each end. The ends may cross (an inverted
+/// response). Returns whether either end moved.
+fn range_handles(ui: &mut egui::Ui, id: egui::Id, range: &mut (f64, f64)) -> bool {
+    let (rect, _) =
+        ui.allocate_exact_size(egui::vec2(RANGE_WIDTH, RANGE_HEIGHT), egui::Sense::hover());
+    let track = rect.shrink2(egui::vec2(HANDLE_RADIUS, 0.0));
+    let x_of = |v: f64| track.left() + track.width() * v as f32;
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(
+        egui::Rect::from_x_y_ranges(
+            track.x_range(),
+            rect.center().y - 2.0..=rect.center().y + 2.0,
+        ),
+        2.0,
+        ui.visuals().extreme_bg_color,
+    );
+    let (lo, hi) = (x_of(range.0), x_of(range.1));
+    painter.line_segment(
+        [
+            egui::pos2(lo.min(hi), rect.center().y),
+            egui::pos2(lo.max(hi), rect.center().y),
+        ],
+        egui::Stroke::new(4.0, ui.visuals().selection.bg_fill),
+    );
+
+    let mut changed = false;
+    for (end, value) in [&mut range.0, &mut range.1].into_iter().enumerate() {
+        let center = egui::pos2(x_of(*value), rect.center().y);
+        let response = ui
+            .interact(
+                egui::Rect::from_center_size(center, egui::Vec2::splat(HANDLE_RADIUS * 2.5)),
+                id.with(end),
+                egui::Sense::drag(),
+            )
+            .on_hover_text(format!(
+                "{}: {:.2}",
+                if end == 0 { "Min" } else { "Max" },
+                *value
+            ));
+        if response.dragged() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let v = ((pos.x - track.left()) / track.width()).clamp(0.0, 1.0) as f64;
+                changed |= v != *value;
+                *value = v;
+            }
+        }
+        let color = if response.hovered() || response.dragged() {
+            ui.visuals().strong_text_color()
+        } else {
+            ui.visuals().text_color()
+        };
+        painter.circle_filled(center, HANDLE_RADIUS, color);
+    }
+    changed
+}
+
+impl MidiBindingEditor {
+    /// Offer an incoming event to a pending learn. Returns the binding it creates (and ends
+    /// the learn), or `None` if no learn is pending or the event can't be bound.
+    pub fn learn_from(&mut self, event: &MidiEvent) -> Option<MidiBinding> {
+        let param_id = self.learn_param.filter(|_| self.learning)?;
+        let binding = MidiBinding::learn(event, param_id)?;
+        self.learning = false;
+        Some(binding)
+    }
+
+    /// Draw the learn controls and the bindings table, applying edits to `bindings`.
+    /// `params` are the loaded plugin's (id, title) pairs. Returns whether `bindings` changed.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        bindings: &mut Vec<MidiBinding>,
+        params: &[(u32, String)],
+    ) -> bool {
+        let title_of = |id: u32| {
+            params
+                .iter()
+                .find(|(pid, _)| *pid == id)
+                .map_or_else(|| format!("#{id}"), |(_, t)| t.clone())
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("Parameter:");
+            egui::ComboBox::from_id_salt("midi_learn_param")
+                .selected_text(self.learn_param.map_or("Select...".to_string(), title_of))
+                .show_ui(ui, |ui| {
+                    for (id, title) in params {
+                        ui.selectable_value(&mut self.learn_param, Some(*id), title);
+                    }
+                });
+            if self.learning {
+                ui.spinner();
+                ui.label("Move a control on the MIDI input device\u{2026}");
+                if ui.button("Cancel Learn").clicked() {
+                    self.learning = false;
+                }
+            } else if ui
+                .add_enabled(self.learn_param.is_some(), egui::Button::new("Learn"))
+                .on_hover_text("Bind the next incoming MIDI message to this parameter")
+                .clicked()
+            {
+                self.learning = true;
+            }
+        });
+
+        if bindings.is_empty() {
+            ui.weak("No bindings yet.");
+            return false;
+        }
+
+        let mut changed = false;
+        let mut remove = None;
+        egui::Grid::new("midi_bindings_table")
+            .num_columns(6)
+            .spacing([12.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Type");
+                ui.strong("Source");
+                ui.strong("Target");
+                ui.strong("Range");
+                ui.strong("Enabled");
+                ui.label("");
+                ui.end_row();
+
+                for (i, binding) in bindings.iter_mut().enumerate() {
+                    ui.label(binding.kind_name());
+                    ui.label(source_label(binding));
+                    ui.label(title_of(binding.param_id()));
+
+                    ui.horizontal(|ui| {
+                        let mut range = binding.range();
+                        if range_handles(ui, ui.id().with(("binding_range", i)), &mut range) {
+                            binding.set_range(range);
+                            changed = true;
+                        }
+                        ui.monospace(format!("{:.2}\u{2013}{:.2}", range.0, range.1));
+                    });
+
+                    let mut enabled = binding.is_enabled();
+                    if ui.checkbox(&mut enabled, "").changed() {
+                        binding.set_enabled(enabled);
+                        changed = true;
+                    }
+                    if ui
+                        .small_button("\u{2715}")
+                        .on_hover_text("Remove binding")
+                        .clicked()
+                    {
+                        remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = remove {
+            bindings.remove(i);
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vst3_host::MidiChannel;
+
+    #[test]
+    fn learn_binds_the_next_event_once() {
+        let cc = MidiEvent::ControlChange {
+            channel: MidiChannel::Ch2,
+            controller: 74,
+            value: 10,
+        };
+        let mut editor = MidiBindingEditor {
+            learn_param: Some(5),
+            learning: false,
+        };
+        assert_eq!(editor.learn_from(&cc), None); // not learning
+
+        editor.learning = true;
+        let program = MidiEvent::ProgramChange {
+            channel: MidiChannel::Ch2,
+            program: 1,
+        };
+        assert_eq!(editor.learn_from(&program), None); // unbindable, keep waiting
+        assert!(editor.learning);
+
+        let binding = editor.learn_from(&cc).expect("learned");
+        assert_eq!(binding.param_id(), 5);
+        assert_eq!(source_label(&binding), "ch 2, CC 74");
+        assert!(!editor.learning);
+        assert_eq!(editor.learn_from(&cc), None);
+    }
+
+    #[test]
+    fn controller_preset_replaces_only_cc_bindings() {
+        let mut bindings = vec![
+            MidiBinding::Cc(CcBinding::new(1, 74)),
+            MidiBinding::PitchBend(vst3_host::PitchBendBinding::new(2)),
+        ];
+        let preset = &vst3_host::bundled_controller_presets()[0];
+        apply_controller_preset(&mut bindings, preset);
+
+        assert_eq!(bindings.len(), 1 + preset.bindings.len());
+        assert!(matches!(bindings[0], MidiBinding::PitchBend(_)));
+        assert_eq!(cc_bindings(&bindings), preset.bindings);
+        assert_eq!(preset_file_name(" My Desk/1 "), "My_Desk_1.json");
+    }
+}
@@ -0,0 +1,83 @@
+//! A console-style view of a `MixBus`: one vertical channel strip per input, side by side,
+//! each with a fader, mute/solo switches and a peak meter.
+
+use eframe::egui;
+use vst3_host::MixBus;
+
+use crate::gm_strip::meter_fraction;
+
+/// Per-frame decay of the displayed meter level.
+const METER_FALLOFF: f32 = 0.85;
+/// Fader range, linear (1.0 = unity, the top leaves a little headroom).
+const FADER_MAX: f32 = 1.5;
+
+/// UI state for the view: each strip's meter falloff.
+#[derive(Default)]
+pub struct MixBusView {
+    meters: Vec<f32>,
+}
+
+impl MixBusView {
+    /// Draw a strip per input of `mix`, labelled with `names` and metering the linear peaks
+    /// in `levels`, and apply this frame's fader and switch edits.
+    pub fn show(&mut self, ui: &mut egui::Ui, mix: &mut MixBus, names: &[String], levels: &[f32]) {
+        self.meters.resize(mix.inputs(), 0.0);
+        egui::ScrollArea::horizontal()
+            .id_salt("mix_bus_view")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    for input in 0..mix.inputs() {
+                        let level = levels.get(input).copied().unwrap_or(0.0);
+                        self.meters[input] = level.max(self.meters[input] * METER_FALLOFF);
+                        let name = names.get(input).map_or("", String::as_str);
+                        ui.group(|ui| show_strip(ui, mix, input, name, self.meters[input]));
+                    }
+                });
+            });
+    }
+}
+
+fn show_strip(ui: &mut egui::Ui, mix: &mut MixBus, input: usize, name: &str, meter: f32) {
+    ui.vertical_centered(|ui| {
+        ui.set_width(48.0);
+        ui.small(name);
+        ui.horizontal(|ui| {
+            let mut gain = mix.gain(input);
+            if ui
+                .add(
+                    egui::Slider::new(&mut gain, 0.0..=FADER_MAX)
+                        .vertical()
+                        .show_value(false),
+                )
+                .on_hover_text(format!("{gain:.2}\u{00d7}"))
+                .changed()
+            {
+                mix.set_gain(input, gain);
+            }
+
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(8.0, 100.0), egui::Sense::hover());
+            let painter = ui.painter();
+            painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+            let mut filled = rect;
+            filled.set_top(rect.bottom() - rect.height() * meter_fraction(meter));
+            let color = if meter >= 1.0 {
+                egui::Color32::RED
+            } else if mix.is_audible(input) {
+                egui::Color32::from_rgb(90, 200, 120)
+            } else {
+                egui::Color32::GRAY
+            };
+            painter.rect_filled(filled, 2.0, color);
+        });
+        ui.horizontal(|ui| {
+            let mut muted = mix.is_muted(input);
+            if ui.toggle_value(&mut muted, "M").changed() {
+                mix.toggle_mute(input);
+            }
+            let mut soloed = mix.is_soloed(input);
+            if ui.toggle_value(&mut soloed, "S").changed() {
+                mix.set_soloed(input, soloed);
+            }
+        });
+    });
+}
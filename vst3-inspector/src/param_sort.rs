@@ -0,0 +1,152 @@
+//! Click-to-sort for the parameter table: clicking a column header sorts by that column,
+//! clicking it again flips the direction. Ties keep index order.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+use crate::ParameterInfo;
+
+/// A sortable column of the parameter table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortColumn {
+    #[default]
+    Index,
+    Id,
+    Name,
+    Value,
+    Default,
+    Units,
+    Steps,
+}
+
+/// Which way a [`ParameterSort`] runs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// The parameter table's order. The default is index order, as the plugin lists them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParameterSort {
+    pub column: SortColumn,
+    pub direction: SortDirection,
+}
+
+impl ParameterSort {
+    /// React to a click on `column`'s header: sort by it ascending, or flip the direction if
+    /// it's already the sort column.
+    pub fn click(&mut self, column: SortColumn) {
+        if self.column == column {
+            self.direction = match self.direction {
+                SortDirection::Ascending => SortDirection::Descending,
+                SortDirection::Descending => SortDirection::Ascending,
+            };
+        } else {
+            *self = Self {
+                column,
+                direction: SortDirection::Ascending,
+            };
+        }
+    }
+
+    /// `title` with a ▲/▼ when `column` is the sort column.
+    pub fn header(&self, column: SortColumn, title: &str) -> String {
+        if self.column != column {
+            return title.to_string();
+        }
+        match self.direction {
+            SortDirection::Ascending => format!("{title} \u{25B2}"),
+            SortDirection::Descending => format!("{title} \u{25BC}"),
+        }
+    }
+
+    /// Order `params` (pairs of table index and parameter). Names and units compare
+    /// case-insensitively, values by their normalized value.
+    pub fn apply(&self, params: &mut [(usize, &ParameterInfo)]) {
+        let key = |a: &(usize, &ParameterInfo), b: &(usize, &ParameterInfo)| -> Ordering {
+            let (a, b) = (a.1, b.1);
+            match self.column {
+                SortColumn::Index => Ordering::Equal,
+                SortColumn::Id => a.id.cmp(&b.id),
+                SortColumn::Name => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+                SortColumn::Value => a.current_value.total_cmp(&b.current_value),
+                SortColumn::Default => a
+                    .default_normalized_value
+                    .total_cmp(&b.default_normalized_value),
+                SortColumn::Units => a.units.to_lowercase().cmp(&b.units.to_lowercase()),
+                SortColumn::Steps => a.step_count.cmp(&b.step_count),
+            }
+        };
+        params.sort_by(|a, b| {
+            let order = key(a, b).then(a.0.cmp(&b.0));
+            match self.direction {
+                SortDirection::Ascending => order,
+                SortDirection::Descending => order.reverse(),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param(id: u32, title: &str, value: f64) -> ParameterInfo {
+        ParameterInfo {
+            id,
+            title: title.to_string(),
+            short_title: String::new(),
+            units: String::new(),
+            step_count: 0,
+            default_normalized_value: 0.0,
+            unit_id: 0,
+            flags: 0,
+            current_value: value,
+            widget: vst3_host::WidgetType::Slider,
+            step_labels: Vec::new(),
+        }
+    }
+
+    fn ids(params: &[(usize, &ParameterInfo)]) -> Vec<u32> {
+        params.iter().map(|(_, p)| p.id).collect()
+    }
+
+    #[test]
+    fn sorting_by_name_is_alphabetical_and_case_insensitive() {
+        let all = [
+            param(1, "cutoff", 0.2),
+            param(2, "Attack", 0.9),
+            param(3, "Resonance", 0.1),
+            param(4, "bandwidth", 0.5),
+        ];
+        let mut params: Vec<_> = all.iter().enumerate().collect();
+        let mut sort = ParameterSort::default();
+
+        sort.click(SortColumn::Name);
+        sort.apply(&mut params);
+        assert_eq!(ids(&params), [2, 4, 1, 3]);
+
+        sort.click(SortColumn::Name);
+        assert_eq!(sort.direction, SortDirection::Descending);
+        sort.apply(&mut params);
+        assert_eq!(ids(&params), [3, 1, 4, 2]);
+    }
+
+    #[test]
+    fn sorting_by_value_uses_the_normalized_value() {
+        let all = [param(1, "a", 0.2), param(2, "b", 0.9), param(3, "c", 0.1)];
+        let mut params: Vec<_> = all.iter().enumerate().collect();
+        let mut sort = ParameterSort::default();
+        sort.click(SortColumn::Value);
+        sort.apply(&mut params);
+        assert_eq!(ids(&params), [3, 1, 2]);
+        assert_eq!(sort.header(SortColumn::Value, "Value"), "Value \u{25B2}");
+        assert_eq!(sort.header(SortColumn::Name, "Name"), "Name");
+
+        // Back to index order.
+        ParameterSort::default().apply(&mut params);
+        assert_eq!(ids(&params), [1, 2, 3]);
+    }
+}
@@ -0,0 +1,235 @@
+//! The rich tooltip on the parameter table's value column: title, ID, normalized and display
+//! values, units, default and step count, with a button copying the normalized value. Read-only
+//! parameters (output meters and the like) also show the range observed since processing
+//! started.
+//!
+//! egui's own tooltips hide while the widget is dragged, so this one is driven by hand: it
+//! opens after [`HOVER_DELAY`] and then stays up while the slider is hovered or dragged, or the
+//! pointer is over the tooltip itself (to reach the copy button).
+
+use eframe::egui;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::ParameterInfo;
+
+/// How long the pointer must rest on a value before its tooltip opens.
+pub const HOVER_DELAY: Duration = Duration::from_millis(300);
+
+/// Lowest and highest value each parameter has reported since they were last cleared.
+#[derive(Debug, Default)]
+pub struct ObservedRanges(HashMap<u32, (f64, f64)>);
+
+impl ObservedRanges {
+    /// Widen parameter `id`'s range to include `value`.
+    pub fn observe(&mut self, id: u32, value: f64) {
+        let range = self.0.entry(id).or_insert((value, value));
+        range.0 = range.0.min(value);
+        range.1 = range.1.max(value);
+    }
+
+    /// The `(min, max)` seen for `id`, if it has reported anything.
+    pub fn get(&self, id: u32) -> Option<(f64, f64)> {
+        self.0.get(&id).copied()
+    }
+
+    /// Forget everything (processing restarted).
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Which parameter's tooltip is pending or open, and where it was last drawn.
+#[derive(Debug, Default)]
+pub struct HoverTooltip {
+    target: Option<(u32, Instant)>,
+    rect: Option<egui::Rect>,
+}
+
+impl HoverTooltip {
+    /// Track the pointer over parameter `id`'s value widget and say whether its tooltip
+    /// should show this frame. `active` is whether the widget is hovered or dragged.
+    pub fn update(
+        &mut self,
+        id: u32,
+        active: bool,
+        pointer: Option<egui::Pos2>,
+        now: Instant,
+    ) -> bool {
+        let ours = self.target.is_some_and(|(target, _)| target == id);
+        let over_tooltip = ours && pointer.zip(self.rect).is_some_and(|(p, r)| r.contains(p));
+        if !active && !over_tooltip {
+            if ours {
+                self.target = None;
+                self.rect = None;
+            }
+            return false;
+        }
+        if !ours {
+            self.target = Some((id, now));
+            self.rect = None;
+        }
+        self.target
+            .is_some_and(|(_, since)| now.duration_since(since) >= HOVER_DELAY)
+    }
+
+    /// Time left before the pending tooltip opens, to schedule a repaint.
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.target
+            .map(|(_, since)| HOVER_DELAY.saturating_sub(now.duration_since(since)))
+            .filter(|left| !left.is_zero())
+    }
+
+    /// Remember where the tooltip was drawn so moving onto it keeps it open.
+    pub fn set_rect(&mut self, rect: egui::Rect) {
+        self.rect = Some(rect);
+    }
+}
+
+/// The tooltip's rows as `(label, value)`. `display` is the plugin's own rendering of the value
+/// (`getParamStringByValue`), when it could be asked.
+pub fn tooltip_rows(
+    param: &ParameterInfo,
+    display: Option<&str>,
+    observed: Option<(f64, f64)>,
+) -> Vec<(&'static str, String)> {
+    let or_dash = |text: &str| {
+        if text.trim().is_empty() {
+            "\u{2014}".to_string()
+        } else {
+            text.to_string()
+        }
+    };
+    let mut rows = vec![
+        ("Parameter", or_dash(&param.title)),
+        ("ID", param.id.to_string()),
+        ("Normalized", format!("{:.4}", param.current_value)),
+        ("Display", or_dash(display.unwrap_or(""))),
+        ("Units", or_dash(&param.units)),
+        ("Default", format!("{:.4}", param.default_normalized_value)),
+        (
+            "Steps",
+            if param.step_count > 0 {
+                param.step_count.to_string()
+            } else {
+                "continuous".to_string()
+            },
+        ),
+    ];
+    if param.flags & 0x1 != 0 {
+        let (min, max) = observed.map_or_else(
+            || ("\u{2014}".to_string(), "\u{2014}".to_string()),
+            |(min, max)| (format!("{min:.4}"), format!("{max:.4}")),
+        );
+        rows.push(("Min observed", min));
+        rows.push(("Max observed", max));
+    }
+    rows
+}
+
+/// Draw the rows in a two-column grid, with a copy button for the normalized value.
+pub fn show_rows(ui: &mut egui::Ui, rows: &[(&'static str, String)], normalized: f64) {
+    egui::Grid::new("param_tooltip_grid")
+        .num_columns(2)
+        .spacing([12.0, 2.0])
+        .show(ui, |ui| {
+            for (label, value) in rows {
+                ui.label(egui::RichText::new(*label).weak());
+                if *label == "Normalized" {
+                    ui.horizontal(|ui| {
+                        ui.monospace(value);
+                        if ui
+                            .small_button("\u{1f4cb}")
+                            .on_hover_text("Copy the normalized value")
+                            .clicked()
+                        {
+                            ui.ctx().copy_text(format!("{normalized:.4}"));
+                        }
+                    });
+                } else {
+                    ui.label(value);
+                }
+                ui.end_row();
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param(units: &str, flags: i32) -> ParameterInfo {
+        ParameterInfo {
+            id: 7,
+            title: "Cutoff".to_string(),
+            short_title: String::new(),
+            units: units.to_string(),
+            step_count: 0,
+            default_normalized_value: 0.5,
+            unit_id: 0,
+            flags,
+            current_value: 0.123456,
+            widget: vst3_host::WidgetType::Slider,
+            step_labels: Vec::new(),
+        }
+    }
+
+    fn row<'a>(rows: &'a [(&'static str, String)], label: &str) -> Option<&'a str> {
+        rows.iter()
+            .find(|(l, _)| *l == label)
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn rows_cover_the_parameter_with_empty_units() {
+        let rows = tooltip_rows(&param("", 0), Some("1.2 kHz"), None);
+        assert_eq!(row(&rows, "Parameter"), Some("Cutoff"));
+        assert_eq!(row(&rows, "ID"), Some("7"));
+        assert_eq!(row(&rows, "Normalized"), Some("0.1235"));
+        assert_eq!(row(&rows, "Display"), Some("1.2 kHz"));
+        assert_eq!(row(&rows, "Units"), Some("\u{2014}"));
+        assert_eq!(row(&rows, "Default"), Some("0.5000"));
+        assert_eq!(row(&rows, "Steps"), Some("continuous"));
+        assert_eq!(row(&rows, "Min observed"), None);
+    }
+
+    #[test]
+    fn read_only_rows_add_the_observed_range() {
+        let mut observed = ObservedRanges::default();
+        let meter = param("dB", 0x1);
+        let rows = tooltip_rows(&meter, None, observed.get(meter.id));
+        assert_eq!(row(&rows, "Display"), Some("\u{2014}"));
+        assert_eq!(row(&rows, "Min observed"), Some("\u{2014}"));
+
+        for value in [0.4, 0.1, 0.9, 0.3] {
+            observed.observe(meter.id, value);
+        }
+        let rows = tooltip_rows(&meter, None, observed.get(meter.id));
+        assert_eq!(row(&rows, "Min observed"), Some("0.1000"));
+        assert_eq!(row(&rows, "Max observed"), Some("0.9000"));
+        observed.clear();
+        assert_eq!(observed.get(meter.id), None);
+    }
+
+    #[test]
+    fn tooltip_opens_after_the_hover_delay_and_stays_while_dragged() {
+        let mut tooltip = HoverTooltip::default();
+        let start = Instant::now();
+        assert!(!tooltip.update(7, true, None, start));
+        assert!(tooltip.remaining(start).is_some());
+        assert!(!tooltip.update(7, true, None, start + Duration::from_millis(200)));
+        assert!(tooltip.update(7, true, None, start + HOVER_DELAY));
+
+        // Moving onto the tooltip keeps it open; leaving both closes it.
+        tooltip.set_rect(egui::Rect::from_min_size(
+            egui::pos2(0.0, 0.0),
+            egui::vec2(100.0, 50.0),
+        ));
+        let later = start + Duration::from_secs(1);
+        assert!(tooltip.update(7, false, Some(egui::pos2(10.0, 10.0)), later));
+        assert!(!tooltip.update(7, false, Some(egui::pos2(500.0, 10.0)), later));
+
+        // Another parameter starts its own delay.
+        assert!(!tooltip.update(8, true, None, later));
+    }
+}
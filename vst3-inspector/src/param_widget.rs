@@ -0,0 +1,151 @@
+//! The parameter table's value editor: each row draws the widget
+//! [`vst3_host::infer_widget_type`] picked for its parameter when the plugin loaded — a label
+//! for read-only values, a toggle for switches, a combo box of the plugin's own step labels,
+//! or a slider (on a logarithmic curve for dB levels).
+
+use eframe::egui;
+use vst3_host::{SliderCurve, WidgetType};
+
+use crate::ParameterInfo;
+
+/// Curve for dB parameters the plugin didn't reveal a curve for.
+const DB_CURVE: SliderCurve = SliderCurve::Logarithmic { base: 10.0 };
+
+/// The number shown next to the widget: a percentage for [`WidgetType::Percentage`], the
+/// normalized value otherwise.
+pub fn value_text(param: &ParameterInfo) -> String {
+    match param.widget {
+        WidgetType::Percentage => format!("{:.1}%", param.current_value * 100.0),
+        _ => format!("{:.3}", param.current_value),
+    }
+}
+
+/// The label for step `step` of `param`: the plugin's if it gave one, the number otherwise.
+fn step_label(param: &ParameterInfo, step: i32) -> String {
+    usize::try_from(step)
+        .ok()
+        .and_then(|i| param.step_labels.get(i))
+        .cloned()
+        .unwrap_or_else(|| step.to_string())
+}
+
+/// Draw `param`'s widget, moving continuous ones along `curve` (the detected one, or
+/// [`SliderCurve::Linear`]). Returns the widget's response and the normalized value the user
+/// picked, if they changed it.
+pub fn show(
+    ui: &mut egui::Ui,
+    param: &ParameterInfo,
+    curve: &SliderCurve,
+) -> (egui::Response, Option<f64>) {
+    let value = param.current_value;
+    match param.widget {
+        WidgetType::Label => {
+            let response = ui.add_enabled(false, egui::Label::new(value_text(param)));
+            (response, None)
+        }
+        WidgetType::ToggleButton => {
+            let mut on = value >= 0.5;
+            let label = step_label(param, i32::from(on));
+            let response = ui.toggle_value(&mut on, label);
+            let changed = response.changed();
+            (response, changed.then_some(if on { 1.0 } else { 0.0 }))
+        }
+        WidgetType::ComboBox => {
+            let steps = param.step_count.max(1);
+            let current = (value * f64::from(steps)).round() as i32;
+            let mut selected = current;
+            let combo = egui::ComboBox::from_id_salt(("param", param.id))
+                .selected_text(step_label(param, current))
+                .width(90.0)
+                .show_ui(ui, |ui| {
+                    for step in 0..=steps {
+                        ui.selectable_value(&mut selected, step, step_label(param, step));
+                    }
+                });
+            let changed = selected != current;
+            (
+                combo.response,
+                changed.then(|| f64::from(selected) / f64::from(steps)),
+            )
+        }
+        WidgetType::Percentage | WidgetType::LogSlider | WidgetType::Slider => {
+            let curve = match (param.widget, curve) {
+                (WidgetType::LogSlider, SliderCurve::Linear) => &DB_CURVE,
+                _ => curve,
+            };
+            let step = if param.step_count > 0 {
+                1.0 / f64::from(param.step_count)
+            } else {
+                0.001
+            };
+            let mut position = curve.inverse(value);
+            let response = ui.add_sized(
+                [100.0, 20.0],
+                egui::Slider::new(&mut position, 0.0..=1.0)
+                    .step_by(step)
+                    .show_value(false),
+            );
+            let changed = response.changed();
+            (response, changed.then(|| curve.apply(position)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::accesskit::Role;
+
+    fn param(widget: WidgetType, step_count: i32) -> ParameterInfo {
+        ParameterInfo {
+            id: 3,
+            title: "Wave".to_string(),
+            short_title: String::new(),
+            units: String::new(),
+            step_count,
+            default_normalized_value: 0.0,
+            unit_id: 0,
+            flags: 0,
+            current_value: 0.5,
+            widget,
+            step_labels: ["Sine", "Saw", "Square"].map(String::from).to_vec(),
+        }
+    }
+
+    /// The accessibility roles of everything `param`'s widget draws.
+    fn roles(param: &ParameterInfo) -> Vec<Role> {
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        let output = ctx.run_ui(egui::RawInput::default(), |ui| {
+            show(ui, param, &SliderCurve::Linear);
+        });
+        let update = output.platform_output.accesskit_update.unwrap();
+        update.nodes.iter().map(|(_, node)| node.role()).collect()
+    }
+
+    #[test]
+    fn each_widget_type_draws_its_egui_widget() {
+        let cases = [
+            (WidgetType::Label, Role::Label),
+            (WidgetType::ToggleButton, Role::Button),
+            (WidgetType::ComboBox, Role::ComboBox),
+            (WidgetType::Percentage, Role::Slider),
+            (WidgetType::LogSlider, Role::Slider),
+            (WidgetType::Slider, Role::Slider),
+        ];
+        for (widget, role) in cases {
+            let roles = roles(&param(widget, 2));
+            assert!(roles.contains(&role), "{widget:?} drew {roles:?}");
+        }
+        assert!(!roles(&param(WidgetType::Label, 2)).contains(&Role::Slider));
+    }
+
+    #[test]
+    fn combo_boxes_and_toggles_show_the_step_labels() {
+        let combo = param(WidgetType::ComboBox, 2);
+        assert_eq!(step_label(&combo, 1), "Saw");
+        assert_eq!(step_label(&combo, 7), "7");
+        assert_eq!(value_text(&combo), "0.500");
+        assert_eq!(value_text(&param(WidgetType::Percentage, 0)), "50.0%");
+    }
+}
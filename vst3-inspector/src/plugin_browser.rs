@@ -0,0 +1,412 @@
+//! The Plugins tab's browser: installed plugins in a folder tree by category (Instrument ›
+//! Synth › …), built from the metadata the inspector has seen so far.
+//!
+//! Listing bundles doesn't load them, so a plugin's category is only known once it has been
+//! loaded or a category scan has probed it; the rest are grouped under "Not scanned".
+//!
+//! Each row carries the user's annotation: a favorite star, and notes and tags edited from its
+//! context menu. The context menu also moves, links, disables and backs up the bundle; disabled
+//! plugins are listed at the bottom so they can be enabled again.
+
+use eframe::egui;
+use std::collections::HashMap;
+use vst3_host::{CategoryTree, PluginAnnotation, PluginFormat, PluginInfo};
+
+const CURRENT_COLOR: egui::Color32 = egui::Color32::GREEN;
+const CUSTOM_COLOR: egui::Color32 = egui::Color32::from_rgb(100, 149, 237); // Cornflower blue
+const FAVORITE_COLOR: egui::Color32 = egui::Color32::GOLD;
+const VST3_COLOR: egui::Color32 = egui::Color32::from_rgb(150, 150, 160);
+const CLAP_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 140, 60);
+
+/// What the user asked for in the browser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrowserAction {
+    /// Load the plugin at this path
+    Load(String),
+    /// Flip the favorite mark of the plugin at this path
+    ToggleFavorite(String),
+    /// Open the notes editor for the plugin at this path
+    EditNotes(String),
+    /// Move the plugin at this path into the user plugin folder
+    MoveToUserDir(String),
+    /// Link the plugin at this path into another folder (picked next)
+    LinkInto(String),
+    /// Back up the plugin at this path into a folder (picked next)
+    Backup(String),
+    /// Hide the plugin at this path from scans
+    Disable(String),
+    /// Bring back the disabled plugin at this path
+    Enable(String),
+    /// Open the init flags editor for the plugin at this path
+    CompatibilitySettings(String),
+}
+
+/// `paths` whose annotation has a tag matching `query` (all of them for an empty query).
+pub fn filter_by_tag(
+    paths: &[String],
+    annotations: &HashMap<String, PluginAnnotation>,
+    query: &str,
+) -> Vec<String> {
+    if query.trim().is_empty() {
+        return paths.to_vec();
+    }
+    paths
+        .iter()
+        .filter(|path| annotations.get(*path).is_some_and(|a| a.has_tag(query)))
+        .cloned()
+        .collect()
+}
+
+/// Split `paths` into a category tree of those `catalog` has metadata for, and the paths it
+/// doesn't (sorted as given). Copies of the same plugin in several folders are filed once,
+/// listing the other paths in [`PluginInfo::duplicates`].
+pub fn catalog_tree(
+    paths: &[String],
+    catalog: &HashMap<String, PluginInfo>,
+) -> (CategoryTree, Vec<String>) {
+    let mut scanned = Vec::new();
+    let mut unscanned = Vec::new();
+    for path in paths {
+        match catalog.get(path) {
+            Some(info) => scanned.push(info.clone()),
+            None => unscanned.push(path.clone()),
+        }
+    }
+    let mut tree = CategoryTree::default();
+    for info in vst3_host::deduplicate_plugins(scanned) {
+        tree.insert(info);
+    }
+    (tree, unscanned)
+}
+
+/// The plugin standard of the plugin at `path`, from its extension.
+pub fn format_of(path: &str) -> PluginFormat {
+    if path.ends_with(".clap") {
+        PluginFormat::Clap
+    } else {
+        PluginFormat::Vst3
+    }
+}
+
+/// A small "VST3" / "CLAP" tag.
+fn format_badge(ui: &mut egui::Ui, format: PluginFormat) {
+    let color = match format {
+        PluginFormat::Vst3 => VST3_COLOR,
+        PluginFormat::Clap => CLAP_COLOR,
+    };
+    ui.label(
+        egui::RichText::new(format.to_string())
+            .small()
+            .strong()
+            .color(color),
+    );
+}
+
+/// Draws the tree; knows which plugin is loaded and which folders the user added.
+pub struct PluginBrowser<'a> {
+    /// Path of the loaded plugin
+    pub current: &'a str,
+    /// User-added scan folders (their plugins are marked "[Custom]")
+    pub custom_paths: &'a [String],
+    /// The user's notes per plugin path
+    pub annotations: &'a HashMap<String, PluginAnnotation>,
+    /// List favorites ahead of the other plugins in each folder
+    pub favorites_first: bool,
+    /// Plugins disabled from the context menu (their `.vst3.disabled` paths)
+    pub disabled: &'a [String],
+}
+
+impl PluginBrowser<'_> {
+    /// Draw `tree` and the `unscanned` paths. Returns what the user clicked, if anything.
+    pub fn show(
+        &self,
+        ui: &mut egui::Ui,
+        tree: &CategoryTree,
+        unscanned: &[String],
+    ) -> Option<BrowserAction> {
+        let mut action = None;
+        for (name, sub) in &tree.children {
+            self.show_folder(ui, name, sub, name, &mut action);
+        }
+        if !tree.plugins.is_empty() {
+            egui::CollapsingHeader::new(format!("Uncategorized ({})", tree.plugins.len()))
+                .id_salt("plugins_uncategorized")
+                .show(ui, |ui| self.plugin_rows(ui, &tree.plugins, &mut action));
+        }
+        if !unscanned.is_empty() {
+            egui::CollapsingHeader::new(format!("Not scanned ({})", unscanned.len()))
+                .id_salt("plugins_unscanned")
+                .show(ui, |ui| {
+                    ui.weak("Load these or run Scan Categories to file them by category.");
+                    let mut unscanned: Vec<&String> = unscanned.iter().collect();
+                    if self.favorites_first {
+                        unscanned.sort_by_key(|path| !self.is_favorite(path));
+                    }
+                    for path in unscanned {
+                        let name = crate::get_plugin_name_from_path(path);
+                        self.plugin_row(ui, &name, "", path, &[], &mut action);
+                    }
+                });
+        }
+        if !self.disabled.is_empty() {
+            egui::CollapsingHeader::new(format!("Disabled ({})", self.disabled.len()))
+                .id_salt("plugins_disabled")
+                .show(ui, |ui| {
+                    for path in self.disabled {
+                        ui.horizontal(|ui| {
+                            ui.weak(crate::get_plugin_name_from_path(
+                                path.trim_end_matches(".disabled"),
+                            ))
+                            .on_hover_text(path);
+                            if ui.small_button("Enable").clicked() {
+                                action = Some(BrowserAction::Enable(path.clone()));
+                            }
+                        });
+                    }
+                });
+        }
+        action
+    }
+
+    /// One category folder: its sub-categories, then the plugins filed directly in it.
+    fn show_folder(
+        &self,
+        ui: &mut egui::Ui,
+        name: &str,
+        tree: &CategoryTree,
+        id: &str,
+        action: &mut Option<BrowserAction>,
+    ) {
+        egui::CollapsingHeader::new(format!("{name} ({})", tree.plugin_count()))
+            .id_salt(("plugin_category", id))
+            .show(ui, |ui| {
+                for (child, sub) in &tree.children {
+                    self.show_folder(ui, child, sub, &format!("{id}|{child}"), action);
+                }
+                self.plugin_rows(ui, &tree.plugins, action);
+            });
+    }
+
+    /// The plugins filed in one folder, favorites first if asked (the sort is stable).
+    fn plugin_rows(
+        &self,
+        ui: &mut egui::Ui,
+        plugins: &[PluginInfo],
+        action: &mut Option<BrowserAction>,
+    ) {
+        let mut plugins: Vec<&PluginInfo> = plugins.iter().collect();
+        if self.favorites_first {
+            plugins.sort_by_key(|p| !self.is_favorite(&p.path.to_string_lossy()));
+        }
+        for plugin in plugins {
+            let path = plugin.path.to_string_lossy();
+            self.plugin_row(
+                ui,
+                &plugin.name,
+                &plugin.vendor,
+                &path,
+                &plugin.duplicates,
+                action,
+            );
+        }
+    }
+
+    fn is_favorite(&self, path: &str) -> bool {
+        self.annotations.get(path).is_some_and(|a| a.favorite)
+    }
+
+    fn plugin_row(
+        &self,
+        ui: &mut egui::Ui,
+        name: &str,
+        vendor: &str,
+        path: &str,
+        duplicates: &[std::path::PathBuf],
+        action: &mut Option<BrowserAction>,
+    ) {
+        let is_current = self.current == path;
+        let annotation = self.annotations.get(path);
+        let is_custom = self
+            .custom_paths
+            .iter()
+            .any(|custom| path.starts_with(custom.as_str()));
+        ui.horizontal(|ui| {
+            let favorite = annotation.is_some_and(|a| a.favorite);
+            let star = if favorite {
+                egui::RichText::new("\u{2605}").color(FAVORITE_COLOR)
+            } else {
+                egui::RichText::new("\u{2606}").weak()
+            };
+            if ui
+                .add(egui::Button::new(star).frame(false))
+                .on_hover_text(if favorite {
+                    "Remove from favorites"
+                } else {
+                    "Add to favorites"
+                })
+                .clicked()
+            {
+                *action = Some(BrowserAction::ToggleFavorite(path.to_string()));
+            }
+            format_badge(ui, format_of(path));
+            let mut label = name.to_string();
+            if is_current {
+                label = format!("[ACTIVE] {label}");
+            }
+            if is_custom {
+                label = format!("{label} [Custom]");
+            }
+            let response = if is_current {
+                ui.colored_label(CURRENT_COLOR, label)
+            } else if is_custom {
+                ui.colored_label(CUSTOM_COLOR, label)
+            } else {
+                ui.label(label)
+            };
+            let hover = match annotation.filter(|a| !a.notes.is_empty()) {
+                Some(a) => format!("{path}\n\n{}", a.notes),
+                None => path.to_string(),
+            };
+            response
+                .interact(egui::Sense::click())
+                .on_hover_text(hover)
+                .context_menu(|ui| {
+                    let path = path.to_string();
+                    let chosen = if ui.button("Edit Notes\u{2026}").clicked() {
+                        Some(BrowserAction::EditNotes(path))
+                    } else if ui.button("Move to User Folder").clicked() {
+                        Some(BrowserAction::MoveToUserDir(path))
+                    } else if ui.button("Link Into Folder\u{2026}").clicked() {
+                        Some(BrowserAction::LinkInto(path))
+                    } else if ui.button("Back Up To\u{2026}").clicked() {
+                        Some(BrowserAction::Backup(path))
+                    } else if ui.button("Compatibility Settings\u{2026}").clicked() {
+                        Some(BrowserAction::CompatibilitySettings(path))
+                    } else if ui.button("Disable").clicked() {
+                        Some(BrowserAction::Disable(path))
+                    } else {
+                        None
+                    };
+                    if chosen.is_some() {
+                        *action = chosen;
+                        ui.close();
+                    }
+                });
+            if !vendor.is_empty() {
+                ui.weak(vendor);
+            }
+            if !duplicates.is_empty() {
+                let copies = match duplicates.len() {
+                    1 => "(+1 copy)".to_string(),
+                    n => format!("(+{n} copies)"),
+                };
+                ui.menu_button(egui::RichText::new(copies).small(), |ui| {
+                    ui.label(egui::RichText::new(path).strong());
+                    for duplicate in duplicates {
+                        ui.label(duplicate.display().to_string());
+                    }
+                })
+                .response
+                .on_hover_text("Also installed in other folders; this copy is the one loaded");
+            }
+            for tag in annotation.map_or(&[][..], |a| &a.custom_tags) {
+                ui.small(format!("#{tag}"));
+            }
+            if is_current {
+                ui.label("Current");
+            } else if ui.small_button("Load").clicked() {
+                *action = Some(BrowserAction::Load(path.to_string()));
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_comes_from_the_extension() {
+        assert_eq!(format_of("/clap/Surge XT.clap"), PluginFormat::Clap);
+        assert_eq!(format_of("/vst3/Diva.vst3"), PluginFormat::Vst3);
+    }
+
+    #[test]
+    fn only_catalogued_paths_enter_the_tree() {
+        let info = PluginInfo {
+            path: "/vst3/Diva.vst3".into(),
+            name: "Diva".into(),
+            vendor: "u-he".into(),
+            version: String::new(),
+            category_path: vst3_host::parse_category("Instrument|Synth|Virtual Analog"),
+            uid: String::new(),
+            audio_inputs: 0,
+            audio_outputs: 1,
+            has_midi_input: true,
+            has_midi_output: false,
+            has_gui: true,
+            format: vst3_host::PluginFormat::Vst3,
+            duplicates: Vec::new(),
+        };
+        let catalog = HashMap::from([("/vst3/Diva.vst3".to_string(), info)]);
+        let paths = [
+            "/vst3/Diva.vst3".to_string(),
+            "/vst3/Other.vst3".to_string(),
+        ];
+
+        let (tree, unscanned) = catalog_tree(&paths, &catalog);
+        assert_eq!(unscanned, ["/vst3/Other.vst3"]);
+        let analog = tree
+            .get(&["Instrument", "Synth", "Virtual Analog"])
+            .unwrap();
+        assert_eq!(analog.plugins[0].name, "Diva");
+        assert_eq!(tree.plugin_count(), 1);
+    }
+
+    #[test]
+    fn copies_of_a_plugin_are_filed_once() {
+        let diva = |path: &str| PluginInfo {
+            path: path.into(),
+            name: "Diva".into(),
+            vendor: "u-he".into(),
+            version: "1.4".into(),
+            category_path: vst3_host::parse_category("Instrument|Synth"),
+            uid: String::new(),
+            audio_inputs: 0,
+            audio_outputs: 1,
+            has_midi_input: true,
+            has_midi_output: false,
+            has_gui: true,
+            format: vst3_host::PluginFormat::Vst3,
+            duplicates: Vec::new(),
+        };
+        let paths = [
+            "/usr/lib/vst3/Diva.vst3".to_string(),
+            "/home/ada/.vst3/Diva.vst3".to_string(),
+        ];
+        let catalog = paths
+            .iter()
+            .map(|path| (path.clone(), diva(path)))
+            .collect::<HashMap<_, _>>();
+
+        let (tree, _) = catalog_tree(&paths, &catalog);
+        assert_eq!(tree.plugin_count(), 1);
+        let synth = tree.get(&["Instrument", "Synth"]).unwrap();
+        assert_eq!(synth.plugins[0].duplicates.len(), 1);
+    }
+
+    #[test]
+    fn tag_filter_keeps_only_matching_plugins() {
+        let mut drums = PluginAnnotation::default();
+        drums.add_tag("Drums");
+        let annotations = HashMap::from([("/vst3/Kick.vst3".to_string(), drums)]);
+        let paths = ["/vst3/Kick.vst3".to_string(), "/vst3/Pad.vst3".to_string()];
+
+        assert_eq!(
+            filter_by_tag(&paths, &annotations, "drum"),
+            ["/vst3/Kick.vst3"]
+        );
+        assert_eq!(filter_by_tag(&paths, &annotations, "  ").len(), 2);
+        assert!(filter_by_tag(&paths, &annotations, "bass").is_empty());
+    }
+}
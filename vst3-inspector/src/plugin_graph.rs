@@ -0,0 +1,250 @@
+//! A small node-graph editor for the library's `AudioGraph`: plugin nodes with audio/MIDI
+//! connectors, wires drawn as bezier curves, nodes moved by dragging their body and wired by
+//! dragging from an output connector onto an input of the same kind.
+//!
+//! The editor only edits the graph description; which plugins actually run (and in what
+//! order) comes from `PluginChain::from_graph`, shown alongside it.
+
+use eframe::egui;
+use vst3_host::{AudioGraph, GraphConnection, NodeId, PortKind};
+
+const NODE_SIZE: egui::Vec2 = egui::vec2(150.0, 64.0);
+const PORT_RADIUS: f32 = 6.0;
+const AUDIO_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 200, 120);
+const MIDI_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 160, 60);
+
+/// A connector on a node: its kind and whether it is the node's input or output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Port {
+    node: NodeId,
+    kind: PortKind,
+    input: bool,
+}
+
+fn port_color(kind: PortKind) -> egui::Color32 {
+    match kind {
+        PortKind::Audio => AUDIO_COLOR,
+        PortKind::Midi => MIDI_COLOR,
+    }
+}
+
+/// Screen position of a connector on a node whose body is `rect`: inputs on the left edge,
+/// outputs on the right, audio above MIDI.
+fn port_pos(rect: egui::Rect, kind: PortKind, input: bool) -> egui::Pos2 {
+    let x = if input { rect.left() } else { rect.right() };
+    let y = match kind {
+        PortKind::Audio => rect.top() + rect.height() * 0.45,
+        PortKind::Midi => rect.top() + rect.height() * 0.78,
+    };
+    egui::pos2(x, y)
+}
+
+/// A horizontal-tangent bezier from an output at `from` to an input at `to`.
+fn wire(from: egui::Pos2, to: egui::Pos2, color: egui::Color32) -> egui::Shape {
+    let bend = ((to.x - from.x).abs() * 0.5).max(40.0);
+    egui::epaint::CubicBezierShape::from_points_stroke(
+        [
+            from,
+            from + egui::vec2(bend, 0.0),
+            to - egui::vec2(bend, 0.0),
+            to,
+        ],
+        false,
+        egui::Color32::TRANSPARENT,
+        egui::Stroke::new(2.5, color),
+    )
+    .into()
+}
+
+/// Editor state that lives across frames: the wire currently being dragged out.
+#[derive(Default)]
+pub struct GraphEditor {
+    dragging_from: Option<Port>,
+}
+
+/// What changed in a frame of [`GraphEditor::show`].
+#[derive(Default)]
+pub struct GraphEdit {
+    /// Nodes moved or wires added/removed; worth persisting.
+    pub changed: bool,
+    /// A rejected connection (incompatible connectors, or a feedback loop).
+    pub error: Option<String>,
+}
+
+impl GraphEditor {
+    /// Draw `graph` on a canvas of `height` and apply this frame's interactions to it.
+    pub fn show(&mut self, ui: &mut egui::Ui, graph: &mut AudioGraph, height: f32) -> GraphEdit {
+        let mut edit = GraphEdit::default();
+        let (canvas, painter) = ui.allocate_painter(
+            egui::vec2(ui.available_width(), height),
+            egui::Sense::hover(),
+        );
+        painter.rect_filled(canvas.rect, 4.0, ui.visuals().extreme_bg_color);
+        let origin = canvas.rect.min.to_vec2();
+        let node_rect = |pos: (f32, f32)| {
+            egui::Rect::from_min_size(egui::pos2(pos.0, pos.1) + origin, NODE_SIZE)
+        };
+
+        // Wires under the nodes.
+        for c in &graph.connections {
+            let (Some(from), Some(to)) = (graph.node(c.from), graph.node(c.to)) else {
+                continue;
+            };
+            painter.add(wire(
+                port_pos(node_rect(from.position), c.kind, false),
+                port_pos(node_rect(to.position), c.kind, true),
+                port_color(c.kind),
+            ));
+        }
+
+        let mut remove = None;
+        let mut disconnect = None;
+        let mut hovered_input = None;
+        let mut released_from = None;
+        for node in &mut graph.nodes {
+            let rect = node_rect(node.position);
+            let body = ui.interact(
+                rect,
+                ui.id().with(("graph_node", node.id)),
+                egui::Sense::click_and_drag(),
+            );
+            if body.dragged() {
+                let moved = rect.translate(body.drag_delta());
+                // Keep the node on the canvas.
+                let min = (moved.min - origin).to_vec2().max(egui::Vec2::ZERO);
+                let max = (canvas.rect.size() - NODE_SIZE).max(egui::Vec2::ZERO);
+                node.position = (min.x.min(max.x), min.y.min(max.y));
+                edit.changed = true;
+            }
+            body.context_menu(|ui| {
+                if ui.button("Remove node").clicked() {
+                    remove = Some(node.id);
+                    ui.close();
+                }
+                if ui.button("Disconnect all").clicked() {
+                    disconnect = Some(node.id);
+                    ui.close();
+                }
+            });
+
+            let rect = node_rect(node.position);
+            painter.rect(
+                rect,
+                6.0,
+                ui.visuals().widgets.noninteractive.bg_fill,
+                egui::Stroke::new(1.0, ui.visuals().widgets.noninteractive.fg_stroke.color),
+                egui::StrokeKind::Inside,
+            );
+            painter.text(
+                rect.left_top() + egui::vec2(10.0, 8.0),
+                egui::Align2::LEFT_TOP,
+                &node.name,
+                egui::FontId::proportional(13.0),
+                ui.visuals().strong_text_color(),
+            );
+
+            for kind in [PortKind::Audio, PortKind::Midi] {
+                for input in [true, false] {
+                    if !node.ports.has(kind, input) {
+                        continue;
+                    }
+                    let port = Port {
+                        node: node.id,
+                        kind,
+                        input,
+                    };
+                    let pos = port_pos(rect, kind, input);
+                    let hit =
+                        egui::Rect::from_center_size(pos, egui::Vec2::splat(PORT_RADIUS * 3.0));
+                    let response = ui.interact(
+                        hit,
+                        ui.id().with(("graph_port", node.id, kind, input)),
+                        egui::Sense::drag(),
+                    );
+                    if !input && response.drag_started() {
+                        self.dragging_from = Some(port);
+                    }
+                    if !input && response.drag_stopped() {
+                        released_from = Some(port);
+                    }
+                    if input && ui.rect_contains_pointer(hit) {
+                        hovered_input = Some(port);
+                    }
+                    let radius = if response.hovered() {
+                        PORT_RADIUS + 1.5
+                    } else {
+                        PORT_RADIUS
+                    };
+                    painter.circle_filled(pos, radius, port_color(kind));
+                    let label = match kind {
+                        PortKind::Audio => "audio",
+                        PortKind::Midi => "midi",
+                    };
+                    let (offset, align) = if input {
+                        (
+                            egui::vec2(PORT_RADIUS + 4.0, 0.0),
+                            egui::Align2::LEFT_CENTER,
+                        )
+                    } else {
+                        (
+                            egui::vec2(-PORT_RADIUS - 4.0, 0.0),
+                            egui::Align2::RIGHT_CENTER,
+                        )
+                    };
+                    painter.text(
+                        pos + offset,
+                        align,
+                        label,
+                        egui::FontId::proportional(10.0),
+                        ui.visuals().weak_text_color(),
+                    );
+                }
+            }
+        }
+
+        // The wire being dragged follows the pointer.
+        if let (Some(from), Some(pointer)) = (self.dragging_from, ui.ctx().pointer_latest_pos()) {
+            if let Some(node) = graph.node(from.node) {
+                painter.add(wire(
+                    port_pos(node_rect(node.position), from.kind, false),
+                    pointer,
+                    port_color(from.kind),
+                ));
+            }
+        }
+
+        if let Some(from) = released_from {
+            self.dragging_from = None;
+            if let Some(to) = hovered_input {
+                if to.kind != from.kind {
+                    edit.error = Some(format!(
+                        "Can't connect a {:?} output to a {:?} input",
+                        from.kind, to.kind
+                    ));
+                } else {
+                    match graph.connect(from.node, to.node, from.kind) {
+                        Ok(()) => edit.changed = true,
+                        Err(e) => edit.error = Some(format!("Can't connect: {e}")),
+                    }
+                }
+            }
+        }
+        if let Some(node) = disconnect {
+            let wires: Vec<GraphConnection> = graph
+                .connections
+                .iter()
+                .filter(|c| c.from == node || c.to == node)
+                .copied()
+                .collect();
+            for c in &wires {
+                graph.disconnect(c);
+            }
+            edit.changed |= !wires.is_empty();
+        }
+        if let Some(id) = remove {
+            graph.remove_node(id);
+            edit.changed = true;
+        }
+        edit
+    }
+}
@@ -0,0 +1,107 @@
+//! Routing matrix editor for the MIDI testing tab: a grid of checkboxes with one row per input
+//! channel and one column per plugin, each connecting that channel to that plugin.
+//!
+//! A column either keeps the input channel or, in General MIDI mode where the channel picks the
+//! instance, always plays on its instance's channel.
+
+use eframe::egui;
+use vst3_host::{MidiChannel, PluginHandle, RoutingMatrix};
+
+/// One plugin column: its header and the channel it is played on (`None` = the input channel).
+pub struct Column {
+    pub name: String,
+    pub channel: Option<MidiChannel>,
+}
+
+fn channels() -> impl Iterator<Item = MidiChannel> {
+    (0..16).filter_map(MidiChannel::from_index)
+}
+
+/// The matrix offered when routing is first switched on: every input channel plays each
+/// column that would receive it unchanged, so nothing goes quiet.
+pub fn default_matrix(columns: &[Column]) -> RoutingMatrix {
+    let mut matrix = RoutingMatrix::new();
+    for input in channels() {
+        for (i, column) in columns.iter().enumerate() {
+            if column.channel.is_none_or(|c| c == input) {
+                matrix.connect(input, PluginHandle(i as u32), input);
+            }
+        }
+    }
+    matrix
+}
+
+/// Draw the grid for `matrix`. Returns whether it was edited.
+pub fn show(ui: &mut egui::Ui, matrix: &mut RoutingMatrix, columns: &[Column]) -> bool {
+    let mut changed = false;
+    egui::ScrollArea::both()
+        .id_salt("routing_matrix_scroll")
+        .max_height(300.0)
+        .show(ui, |ui| {
+            egui::Grid::new("routing_matrix_grid")
+                .num_columns(columns.len() + 1)
+                .spacing([6.0, 2.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("In \\ Plugin");
+                    for column in columns {
+                        ui.strong(&column.name);
+                    }
+                    ui.end_row();
+
+                    for input in channels() {
+                        ui.label(input.to_string());
+                        for (i, column) in columns.iter().enumerate() {
+                            let plugin = PluginHandle(i as u32);
+                            let mut on = matrix.destination(input, plugin).is_some();
+                            let output = column.channel.unwrap_or(input);
+                            if ui
+                                .checkbox(&mut on, "")
+                                .on_hover_text(format!("{input} → {} on {output}", column.name))
+                                .changed()
+                            {
+                                if on {
+                                    matrix.connect(input, plugin, output);
+                                } else {
+                                    matrix.disconnect(input, plugin);
+                                }
+                                changed = true;
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_matrix_keeps_every_channel_playing() {
+        let single = [Column {
+            name: "Plugin".into(),
+            channel: None,
+        }];
+        let matrix = default_matrix(&single);
+        for input in channels() {
+            assert_eq!(matrix.destination(input, PluginHandle(0)), Some(input));
+        }
+
+        // General MIDI: each channel reaches only its own instance.
+        let gm: Vec<Column> = channels()
+            .map(|c| Column {
+                name: c.to_string(),
+                channel: Some(c),
+            })
+            .collect();
+        let matrix = default_matrix(&gm);
+        assert_eq!(
+            matrix.destination(MidiChannel::Ch3, PluginHandle(2)),
+            Some(MidiChannel::Ch3)
+        );
+        assert_eq!(matrix.destination(MidiChannel::Ch3, PluginHandle(0)), None);
+    }
+}
@@ -0,0 +1,124 @@
+//! The Processing tab's live spectrum: the playing plugin's output on a log-frequency axis,
+//! with the FFT window and the number of averaged frames selectable above the plot.
+
+use eframe::egui;
+use vst3_host::{SpectrumAnalyzer, WindowFunction};
+
+const CHART_HEIGHT: f32 = 140.0;
+/// The plot's level range, in dB relative to a full-scale sine.
+const FLOOR_DB: f32 = -120.0;
+const CEILING_DB: f32 = 6.0;
+const LOW_HZ: f32 = 20.0;
+
+/// Horizontal position of `hz` as a fraction of the plot, on a log scale from 20 Hz to
+/// `high_hz`.
+fn frequency_fraction(hz: f32, high_hz: f32) -> f32 {
+    let (low, high) = (LOW_HZ.log10(), high_hz.max(LOW_HZ * 2.0).log10());
+    (hz.max(LOW_HZ).log10() - low) / (high - low)
+}
+
+/// Vertical position of `db` as a fraction of the plot, 0 at the floor.
+fn level_fraction(db: f32) -> f32 {
+    ((db - FLOOR_DB) / (CEILING_DB - FLOOR_DB)).clamp(0.0, 1.0)
+}
+
+/// Draw the window and averaging controls and the current spectrum of `analyzer`, whose
+/// frames were recorded at `sample_rate`. Returns whether the user changed `window` or
+/// `averaging`; the caller applies them to the analyzer.
+pub fn show(
+    ui: &mut egui::Ui,
+    analyzer: &SpectrumAnalyzer,
+    sample_rate: f64,
+    window: &mut WindowFunction,
+    averaging: &mut usize,
+) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Window:");
+        egui::ComboBox::from_id_salt("spectrum_window_selector")
+            .selected_text(window.label())
+            .show_ui(ui, |ui| {
+                for option in WindowFunction::ALL {
+                    changed |= ui
+                        .selectable_value(window, option, option.label())
+                        .changed();
+                }
+            });
+        ui.separator();
+        ui.label("Averaging:");
+        changed |= ui
+            .add(
+                egui::DragValue::new(averaging)
+                    .range(1..=32)
+                    .suffix(" frames"),
+            )
+            .changed();
+    });
+
+    let spectrum = analyzer.spectrum_db();
+    let width = ui.available_width().max(100.0);
+    let (rect, response) =
+        ui.allocate_exact_size(egui::vec2(width, CHART_HEIGHT), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+    let nyquist = (sample_rate / 2.0) as f32;
+    let point = |hz: f32, db: f32| {
+        egui::pos2(
+            rect.left() + frequency_fraction(hz, nyquist) * rect.width(),
+            rect.bottom() - level_fraction(db) * rect.height(),
+        )
+    };
+    for hz in [100.0, 1_000.0, 10_000.0] {
+        let x = point(hz, FLOOR_DB).x;
+        painter.vline(
+            x,
+            rect.y_range(),
+            egui::Stroke::new(1.0, egui::Color32::from_gray(60)),
+        );
+    }
+    let frequencies = analyzer.bin_frequencies(sample_rate);
+    // Skip DC: it has no place on a log axis.
+    let line: Vec<egui::Pos2> = frequencies
+        .iter()
+        .zip(spectrum)
+        .skip(1)
+        .map(|(&hz, &db)| point(hz, db))
+        .collect();
+    if line.len() > 1 {
+        painter.add(egui::Shape::line(
+            line,
+            egui::Stroke::new(1.5, egui::Color32::from_rgb(90, 160, 230)),
+        ));
+    }
+
+    if let Some(pos) = response.hover_pos() {
+        let fraction = (pos.x - rect.left()) / rect.width();
+        let (low, high) = (LOW_HZ.log10(), nyquist.max(LOW_HZ * 2.0).log10());
+        let hz = 10f32.powf(low + fraction * (high - low));
+        let bin = ((hz as f64 * analyzer.size() as f64 / sample_rate).round() as usize)
+            .min(spectrum.len().saturating_sub(1));
+        if let Some(db) = spectrum.get(bin) {
+            response.on_hover_text(format!("{hz:.0} Hz: {db:.1} dB"));
+        }
+    }
+    ui.horizontal(|ui| {
+        ui.small("20 Hz");
+        ui.add_space((width - 100.0).max(0.0));
+        ui.small(format!("{:.0} kHz", nyquist / 1000.0));
+    });
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frequencies_use_a_log_scale() {
+        assert_eq!(frequency_fraction(20.0, 20_000.0), 0.0);
+        assert!((frequency_fraction(20_000.0, 20_000.0) - 1.0).abs() < 1e-6);
+        assert!((frequency_fraction(632.456, 20_000.0) - 0.5).abs() < 1e-3);
+        assert_eq!(level_fraction(-200.0), 0.0);
+        assert_eq!(level_fraction(CEILING_DB), 1.0);
+    }
+}
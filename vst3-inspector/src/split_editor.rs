@@ -0,0 +1,263 @@
+//! Keyboard split editor for the virtual keyboard: a colored bar above the keys showing each
+//! region of a `KeyboardSplit`, with draggable boundaries between neighbouring regions, and a
+//! list for editing ranges and channels directly.
+//!
+//! The inspector plays every region on the loaded plugin (region channels pick the instance in
+//! General MIDI mode), so regions always target `PluginHandle(0)`.
+
+use eframe::egui;
+use vst3_host::{KeyboardSplit, MidiChannel, PluginHandle, SplitRegion};
+
+const REGION_COLORS: [egui::Color32; 6] = [
+    egui::Color32::from_rgb(80, 150, 230),
+    egui::Color32::from_rgb(230, 140, 60),
+    egui::Color32::from_rgb(90, 190, 110),
+    egui::Color32::from_rgb(200, 90, 180),
+    egui::Color32::from_rgb(220, 200, 70),
+    egui::Color32::from_rgb(100, 200, 200),
+];
+const BAR_HEIGHT: f32 = 14.0;
+const HANDLE_WIDTH: f32 = 8.0;
+
+/// Display color of region `index`.
+pub fn region_color(index: usize) -> egui::Color32 {
+    REGION_COLORS[index % REGION_COLORS.len()]
+}
+
+/// The split offered when it is first enabled: everything below C3 (MIDI 60) on channel 1,
+/// C3 and up on channel 2.
+pub fn default_split() -> KeyboardSplit {
+    KeyboardSplit {
+        splits: vec![
+            SplitRegion {
+                note_range: 0..=59,
+                target_plugin: PluginHandle(0),
+                channel: MidiChannel::Ch1,
+            },
+            SplitRegion {
+                note_range: 60..=127,
+                target_plugin: PluginHandle(0),
+                channel: MidiChannel::Ch2,
+            },
+        ],
+    }
+}
+
+/// Index of the region `note` falls in (the first, if regions overlap).
+pub fn region_of(split: &KeyboardSplit, note: u8) -> Option<usize> {
+    split
+        .splits
+        .iter()
+        .position(|r| r.note_range.contains(&note))
+}
+
+/// Move the boundary between region `lower` and the region right after it (in note order) so
+/// the upper one starts at `start`. Both regions keep at least one note. Returns whether
+/// anything changed.
+pub fn move_boundary(split: &mut KeyboardSplit, lower: usize, start: u8) -> bool {
+    let Some([low, high]) = split.splits.get_mut(lower..lower + 2) else {
+        return false;
+    };
+    let start = start.clamp(low.note_range.start() + 1, *high.note_range.end());
+    if *high.note_range.start() == start {
+        return false;
+    }
+    low.note_range = *low.note_range.start()..=start - 1;
+    high.note_range = start..=*high.note_range.end();
+    true
+}
+
+/// Split the widest region in half; the new upper half plays on the next channel.
+fn add_region(split: &mut KeyboardSplit) {
+    let Some((index, region)) = split
+        .splits
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, r)| r.note_range.end() - r.note_range.start())
+        .map(|(i, r)| (i, r.clone()))
+    else {
+        split.splits = default_split().splits;
+        return;
+    };
+    let (lo, hi) = (*region.note_range.start(), *region.note_range.end());
+    if hi == lo {
+        return;
+    }
+    let mid = lo + (hi - lo).div_ceil(2);
+    let channel =
+        MidiChannel::from_index((region.channel.as_index() + 1) % 16).unwrap_or(MidiChannel::Ch1);
+    split.splits[index].note_range = lo..=mid - 1;
+    split.splits.insert(
+        index + 1,
+        SplitRegion {
+            note_range: mid..=hi,
+            target_plugin: region.target_plugin,
+            channel,
+        },
+    );
+}
+
+/// Draw the split bar across `width` (aligned with the keyboard drawn right below it).
+/// `note_x` gives a note's left edge relative to the keyboard; `note_at_x` the note under a
+/// relative x. Returns whether a boundary was dragged.
+pub fn show_split_bar(
+    ui: &mut egui::Ui,
+    width: f32,
+    split: &mut KeyboardSplit,
+    note_x: impl Fn(u8) -> f32,
+    note_at_x: impl Fn(f32) -> u8,
+) -> bool {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(width, BAR_HEIGHT), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+    for (i, region) in split.splits.iter().enumerate() {
+        let left = rect.left() + note_x(*region.note_range.start());
+        let right = match region.note_range.end().checked_add(1) {
+            Some(next) => rect.left() + note_x(next),
+            None => rect.right(),
+        };
+        let band = egui::Rect::from_x_y_ranges(left..=right.max(left), rect.top()..=rect.bottom());
+        painter.rect_filled(band, 2.0, region_color(i).gamma_multiply(0.8));
+        painter.text(
+            band.center(),
+            egui::Align2::CENTER_CENTER,
+            format!("Ch {}", region.channel.as_index() + 1),
+            egui::FontId::proportional(10.0),
+            egui::Color32::BLACK,
+        );
+    }
+
+    // Boundaries between regions that touch.
+    let mut changed = false;
+    for lower in 0..split.splits.len().saturating_sub(1) {
+        let start = *split.splits[lower + 1].note_range.start();
+        if split.splits[lower].note_range.end().checked_add(1) != Some(start) {
+            continue;
+        }
+        let x = rect.left() + note_x(start);
+        let handle = egui::Rect::from_center_size(
+            egui::pos2(x, rect.center().y),
+            egui::vec2(HANDLE_WIDTH, BAR_HEIGHT),
+        );
+        let response = ui
+            .interact(
+                handle,
+                ui.id().with(("split_boundary", lower)),
+                egui::Sense::drag(),
+            )
+            .on_hover_cursor(egui::CursorIcon::ResizeHorizontal);
+        let stroke = if response.hovered() || response.dragged() {
+            egui::Stroke::new(3.0, ui.visuals().strong_text_color())
+        } else {
+            egui::Stroke::new(2.0, egui::Color32::BLACK)
+        };
+        painter.vline(x, rect.y_range(), stroke);
+        if response.dragged() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                changed |= move_boundary(split, lower, note_at_x(pos.x - rect.left()));
+            }
+        }
+    }
+    changed
+}
+
+/// The region list: range and channel per region, plus add/remove. Returns whether the split
+/// changed.
+pub fn show_region_list(ui: &mut egui::Ui, split: &mut KeyboardSplit) -> bool {
+    let mut changed = false;
+    let mut remove = None;
+    for (i, region) in split.splits.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            let (swatch, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+            ui.painter().rect_filled(swatch, 2.0, region_color(i));
+
+            let (mut lo, mut hi) = (*region.note_range.start(), *region.note_range.end());
+            ui.label("From");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut lo)
+                        .range(0..=127)
+                        .custom_formatter(|n, _| crate::midi_note_to_name(n as u8)),
+                )
+                .changed();
+            ui.label("to");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut hi)
+                        .range(0..=127)
+                        .custom_formatter(|n, _| crate::midi_note_to_name(n as u8)),
+                )
+                .changed();
+            region.note_range = lo.min(hi)..=hi.max(lo);
+
+            let mut channel = region.channel.as_index();
+            egui::ComboBox::from_id_salt(("split_channel", i))
+                .selected_text(format!("Ch {}", channel + 1))
+                .width(60.0)
+                .show_ui(ui, |ui| {
+                    for c in 0..16u8 {
+                        changed |= ui
+                            .selectable_value(&mut channel, c, format!("Ch {}", c + 1))
+                            .changed();
+                    }
+                });
+            region.channel = MidiChannel::from_index(channel).unwrap_or(region.channel);
+
+            if ui
+                .small_button("\u{2715}")
+                .on_hover_text("Remove region")
+                .clicked()
+            {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        split.splits.remove(i);
+        changed = true;
+    }
+    if ui
+        .button("Add Region")
+        .on_hover_text("Split the widest region in two")
+        .clicked()
+    {
+        add_region(split);
+        changed = true;
+    }
+    if changed {
+        split
+            .splits
+            .sort_by_key(|r| (*r.note_range.start(), *r.note_range.end()));
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boundary_moves_but_keeps_both_regions_non_empty() {
+        let mut split = default_split();
+        assert!(move_boundary(&mut split, 0, 48));
+        assert_eq!(split.splits[0].note_range, 0..=47);
+        assert_eq!(split.splits[1].note_range, 48..=127);
+
+        // Dragged past the lower region's start: it keeps its first note.
+        assert!(move_boundary(&mut split, 0, 0));
+        assert_eq!(split.splits[0].note_range, 0..=0);
+        assert!(!move_boundary(&mut split, 0, 1));
+        assert!(!move_boundary(&mut split, 1, 60)); // no region above the last
+    }
+
+    #[test]
+    fn add_region_halves_the_widest_region() {
+        let mut split = default_split();
+        add_region(&mut split);
+        assert_eq!(split.splits.len(), 3);
+        assert_eq!(split.splits[1].note_range, 60..=93);
+        assert_eq!(split.splits[2].note_range, 94..=127);
+        assert_eq!(split.splits[2].channel, MidiChannel::Ch3);
+        assert_eq!(region_of(&split, 100), Some(2));
+    }
+}
@@ -0,0 +1,148 @@
+//! The Processing tab's callback timing: how far each audio callback landed from its expected
+//! period, as a horizontal bar chart with one row per occupied jitter bin.
+
+use eframe::egui;
+use vst3_host::{TimingStats, JITTER_OVERFLOW_BIN_US};
+
+/// Height of one row of the chart, in points.
+const ROW_HEIGHT: f32 = 12.0;
+/// Width of the row labels, in points.
+const LABEL_WIDTH: f32 = 90.0;
+
+/// One chart row: the bin's range in µs, its upper edge open when `None`, and its count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Row {
+    low_us: u64,
+    high_us: Option<u64>,
+    count: u64,
+}
+
+impl Row {
+    fn label(&self) -> String {
+        match self.high_us {
+            Some(high) if high == self.low_us + 1 => format!("{} µs", self.low_us),
+            Some(high) => format!("{}\u{2013}{} µs", self.low_us, high - 1),
+            None => format!("\u{2265} {} µs", self.low_us),
+        }
+    }
+}
+
+/// The occupied bins of `stats`, fine 1 µs bins first, then the coarse overflow bins.
+fn rows(stats: &TimingStats) -> Vec<Row> {
+    let fine = stats
+        .jitter_histogram
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| Row {
+            low_us: i as u64,
+            high_us: Some(i as u64 + 1),
+            count,
+        });
+    let last = stats.overflow_histogram.len() - 1;
+    let coarse = stats
+        .overflow_histogram
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| {
+            let low_us = (i as u64 + 1) * JITTER_OVERFLOW_BIN_US;
+            Row {
+                low_us,
+                high_us: (i < last).then_some(low_us + JITTER_OVERFLOW_BIN_US),
+                count,
+            }
+        });
+    fine.chain(coarse).filter(|row| row.count > 0).collect()
+}
+
+/// Draw `stats`: the figures on one line, the jitter distribution below.
+pub fn show(ui: &mut egui::Ui, stats: &TimingStats) {
+    if stats.callbacks == 0 {
+        ui.label("No callbacks timed yet \u{2014} start audio to measure callback jitter.");
+        return;
+    }
+    ui.horizontal(|ui| {
+        ui.label(format!("Period: {:.0} µs", stats.expected_period_us));
+        ui.separator();
+        ui.label(format!("Callbacks: {}", stats.callbacks));
+        ui.separator();
+        ui.label(format!("p99: {:.0} µs", stats.p99_jitter_us));
+        ui.separator();
+        ui.label(format!("p99.9: {:.0} µs", stats.p999_jitter_us));
+        ui.separator();
+        ui.label(format!("Max: {} µs", stats.max_jitter_us));
+    });
+    if stats.is_jittery() {
+        ui.colored_label(
+            egui::Color32::YELLOW,
+            format!(
+                "\u{26A0} Jittery callbacks: p99 is {:.0} µs off the expected period",
+                stats.p99_jitter_us
+            ),
+        );
+    }
+
+    let rows = rows(stats);
+    let width = ui.available_width().max(150.0);
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(width, rows.len() as f32 * ROW_HEIGHT),
+        egui::Sense::hover(),
+    );
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+    let max = rows.iter().map(|row| row.count).max().unwrap_or(0).max(1);
+    let bar_space = (rect.width() - LABEL_WIDTH).max(1.0);
+    for (i, row) in rows.iter().enumerate() {
+        let top = rect.top() + i as f32 * ROW_HEIGHT;
+        painter.text(
+            egui::pos2(rect.left() + LABEL_WIDTH - 6.0, top + ROW_HEIGHT / 2.0),
+            egui::Align2::RIGHT_CENTER,
+            row.label(),
+            egui::FontId::monospace(10.0),
+            ui.visuals().weak_text_color(),
+        );
+        let length = (row.count as f32 / max as f32 * bar_space).max(1.0);
+        let bar = egui::Rect::from_min_size(
+            egui::pos2(rect.left() + LABEL_WIDTH, top + 1.0),
+            egui::vec2(length, ROW_HEIGHT - 2.0),
+        );
+        let color = if row.low_us as f64 >= vst3_host::JITTER_WARN_P99_US {
+            egui::Color32::from_rgb(230, 120, 90)
+        } else {
+            egui::Color32::from_rgb(90, 160, 230)
+        };
+        painter.rect_filled(bar, 0.0, color);
+    }
+
+    if let Some(pos) = response.hover_pos() {
+        let index = ((pos.y - rect.top()) / ROW_HEIGHT) as usize;
+        if let Some(row) = rows.get(index) {
+            let share = row.count as f64 / stats.callbacks as f64 * 100.0;
+            response.on_hover_text(format!(
+                "{}: {} callbacks ({share:.2}%)",
+                row.label(),
+                row.count
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_list_occupied_fine_then_overflow_bins() {
+        let mut stats = TimingStats::default();
+        stats.jitter_histogram[3] = 5;
+        stats.overflow_histogram[0] = 2;
+        let last = stats.overflow_histogram.len() - 1;
+        stats.overflow_histogram[last] = 1;
+
+        let rows = rows(&stats);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].label(), "3 µs");
+        assert_eq!(rows[1].label(), "64\u{2013}127 µs");
+        assert_eq!(rows[2].high_us, None);
+        assert_eq!(rows[2].low_us, 64 * 64);
+    }
+}
@@ -0,0 +1,105 @@
+//! The Parameters tab's undo timeline: a horizontal strip of the recorded parameter edits,
+//! oldest first, with the current position highlighted. Clicking a step travels back (or
+//! forward) to the parameter values just after it.
+
+use eframe::egui;
+use std::time::{Duration, Instant};
+use vst3_host::UndoStack;
+
+/// Width of one step's card, in points.
+const CARD_WIDTH: f32 = 130.0;
+
+/// How long ago `age` was, coarsely: "5s ago", "3m ago", "2h ago".
+fn age_label(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{secs}s ago"),
+        60..=3599 => format!("{}m ago", secs / 60),
+        _ => format!("{}h ago", secs / 3600),
+    }
+}
+
+/// One card on the strip; returns whether it was clicked.
+fn card(ui: &mut egui::Ui, current: bool, lines: [String; 3]) -> bool {
+    let fill = if current {
+        ui.visuals().selection.bg_fill
+    } else {
+        ui.visuals().faint_bg_color
+    };
+    let response = egui::Frame::group(ui.style())
+        .fill(fill)
+        .show(ui, |ui| {
+            ui.set_width(CARD_WIDTH);
+            ui.vertical(|ui| {
+                ui.strong(&lines[0]);
+                ui.small(&lines[1]);
+                ui.label(egui::RichText::new(&lines[2]).small().weak());
+            });
+        })
+        .response
+        .interact(egui::Sense::click());
+    response
+        .on_hover_text("Restore the parameters to this point")
+        .clicked()
+}
+
+/// Draw `stack`'s timeline, naming parameters with `name_of`. Returns the position (see
+/// [`UndoStack::position`]) whose card was clicked.
+pub fn show(
+    ui: &mut egui::Ui,
+    stack: &UndoStack,
+    name_of: impl Fn(u32) -> String,
+) -> Option<usize> {
+    if stack.history().is_empty() {
+        ui.label("No parameter edits yet.");
+        return None;
+    }
+    let now = Instant::now();
+    let mut clicked = None;
+    egui::ScrollArea::horizontal()
+        .id_salt("undo_history_scroll")
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                let start = [
+                    "Start".to_string(),
+                    "Before the first edit".to_string(),
+                    String::new(),
+                ];
+                if card(ui, stack.position() == 0, start) {
+                    clicked = Some(0);
+                }
+                for (i, step) in stack.history().iter().enumerate() {
+                    let first = step
+                        .changes
+                        .first()
+                        .map(|&(id, _)| name_of(id))
+                        .unwrap_or_default();
+                    let lines = [
+                        format!("#{}", step.index + 1),
+                        format!(
+                            "{} \u{00B7} {} param(s)",
+                            age_label(now.saturating_duration_since(step.timestamp)),
+                            step.changes.len()
+                        ),
+                        first,
+                    ];
+                    if card(ui, stack.position() == i + 1, lines) {
+                        clicked = Some(i + 1);
+                    }
+                }
+            });
+        });
+    clicked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ages_round_down_to_the_largest_unit() {
+        assert_eq!(age_label(Duration::from_secs(5)), "5s ago");
+        assert_eq!(age_label(Duration::from_secs(125)), "2m ago");
+        assert_eq!(age_label(Duration::from_secs(7300)), "2h ago");
+    }
+}
@@ -0,0 +1,133 @@
+//! The MIDI tab's velocity curve editor: a 128×128 canvas with played velocity across and the
+//! velocity the plugin receives up, one pixel per velocity. Drag a breakpoint to move it,
+//! click empty space to add one, right-click a breakpoint to remove it.
+
+use eframe::egui;
+use vst3_host::VelocityCurve;
+
+const CANVAS_SIZE: f32 = 128.0;
+/// How close (in points) the pointer must be to grab a breakpoint.
+const GRAB_RADIUS: f32 = 5.0;
+
+/// The velocity pair under canvas-relative position `pos` (y grows downward).
+fn point_at(pos: egui::Vec2) -> (u8, u8) {
+    let clamp = |v: f32| v.round().clamp(0.0, 127.0) as u8;
+    (clamp(pos.x), clamp(CANVAS_SIZE - 1.0 - pos.y))
+}
+
+/// Canvas-relative position of velocity pair `(input, output)`.
+fn position_of((input, output): (u8, u8)) -> egui::Vec2 {
+    egui::vec2(f32::from(input), CANVAS_SIZE - 1.0 - f32::from(output))
+}
+
+/// Index of the breakpoint within [`GRAB_RADIUS`] of canvas-relative `pos`, the nearest if
+/// several are.
+fn breakpoint_near(curve: &VelocityCurve, pos: egui::Vec2) -> Option<usize> {
+    curve
+        .breakpoints
+        .iter()
+        .enumerate()
+        .map(|(i, &p)| (i, (position_of(p) - pos).length()))
+        .filter(|&(_, distance)| distance <= GRAB_RADIUS)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+/// Draw the preset picker and the curve canvas. Returns whether the user changed `curve`.
+pub fn show(ui: &mut egui::Ui, curve: &mut VelocityCurve) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Preset:");
+        for (name, preset) in VelocityCurve::presets() {
+            let selected = *curve == preset;
+            if ui.selectable_label(selected, name).clicked() && !selected {
+                *curve = preset;
+                changed = true;
+            }
+        }
+    });
+
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(CANVAS_SIZE, CANVAS_SIZE),
+        egui::Sense::click_and_drag(),
+    );
+    let drag_id = ui.id().with("velocity_curve_drag");
+    let pointer = response
+        .interact_pointer_pos()
+        .or(response.hover_pos())
+        .map(|pos| pos - rect.min);
+
+    if response.drag_started() {
+        let grabbed = pointer.and_then(|pos| breakpoint_near(curve, pos));
+        ui.data_mut(|d| d.insert_temp(drag_id, grabbed));
+    }
+    let dragging = ui.data(|d| d.get_temp::<Option<usize>>(drag_id)).flatten();
+    if let (true, Some(index), Some(pos)) = (response.dragged(), dragging, pointer) {
+        let moved = curve.move_point(index, point_at(pos));
+        ui.data_mut(|d| d.insert_temp(drag_id, moved));
+        changed = true;
+    }
+    if response.drag_stopped() {
+        ui.data_mut(|d| d.remove::<Option<usize>>(drag_id));
+    }
+    if let Some(pos) = pointer {
+        if response.clicked() && breakpoint_near(curve, pos).is_none() {
+            curve.insert(point_at(pos));
+            changed = true;
+        }
+        if response.secondary_clicked() {
+            if let Some(index) = breakpoint_near(curve, pos) {
+                curve.remove(index);
+                changed = true;
+            }
+        }
+    }
+
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+    painter.line_segment(
+        [rect.left_bottom(), rect.right_top()],
+        egui::Stroke::new(1.0, egui::Color32::from_gray(60)),
+    );
+    let line: Vec<egui::Pos2> = (0..=127u8)
+        .map(|v| rect.min + position_of((v, curve.apply(v))))
+        .collect();
+    painter.add(egui::Shape::line(
+        line,
+        egui::Stroke::new(1.5, egui::Color32::from_rgb(90, 160, 230)),
+    ));
+    let hovered = pointer.and_then(|pos| breakpoint_near(curve, pos));
+    for (i, &point) in curve.breakpoints.iter().enumerate() {
+        let color = if hovered == Some(i) || dragging == Some(i) {
+            ui.visuals().strong_text_color()
+        } else {
+            egui::Color32::from_rgb(230, 140, 60)
+        };
+        painter.circle_filled(rect.min + position_of(point), 3.5, color);
+    }
+    if let Some(pos) = response.hover_pos() {
+        let (input, _) = point_at(pos - rect.min);
+        response.on_hover_text(format!(
+            "Velocity {input} \u{2192} {}\nClick to add a point, right-click to remove one",
+            curve.apply(input)
+        ));
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canvas_positions_round_trip_and_grab_nearby_points() {
+        assert_eq!(point_at(position_of((0, 0))), (0, 0));
+        assert_eq!(point_at(position_of((100, 30))), (100, 30));
+        assert_eq!(point_at(egui::vec2(-5.0, 500.0)), (0, 0));
+
+        let curve = VelocityCurve::new([(64, 127), (100, 50)]);
+        let near = position_of((66, 126));
+        assert_eq!(breakpoint_near(&curve, near), Some(0));
+        assert_eq!(breakpoint_near(&curve, position_of((80, 80))), None);
+    }
+}
@@ -0,0 +1,131 @@
+//! The MIDI Monitor tab's voice monitor: a piano-roll grid of the notes the plugin is holding
+//! in its MIDI output, pitch across and channel down, each cell colored by its note-on
+//! velocity. Hovering a lit cell names the note and how long it has been held.
+
+use eframe::egui;
+use vst3_host::{MidiChannel, PolyphonyTracker};
+
+/// Height of one channel's row, in points.
+const ROW_HEIGHT: f32 = 9.0;
+/// Width reserved left of the grid for the channel numbers, in points.
+const LABEL_WIDTH: f32 = 24.0;
+
+/// Whether `note` falls on a black key.
+fn is_black_key(note: u8) -> bool {
+    matches!(note % 12, 1 | 3 | 6 | 8 | 10)
+}
+
+/// A cell's color: dim blue for the softest notes through to bright orange for the hardest.
+fn velocity_color(velocity: u8) -> egui::Color32 {
+    let t = f32::from(velocity.min(127)) / 127.0;
+    egui::Color32::from_rgb(
+        egui::lerp(40.0..=255.0, t) as u8,
+        egui::lerp(90.0..=170.0, t) as u8,
+        egui::lerp(200.0..=40.0, t) as u8,
+    )
+}
+
+/// The `(channel, note)` cell under grid-relative position `pos` in a grid `width` wide.
+fn cell_at(width: f32, pos: egui::Vec2) -> Option<(MidiChannel, u8)> {
+    if pos.x < 0.0 || pos.y < 0.0 || pos.x >= width {
+        return None;
+    }
+    let note = (pos.x / (width / 128.0)) as u8;
+    let channel = MidiChannel::from_index((pos.y / ROW_HEIGHT) as u8)?;
+    Some((channel, note.min(127)))
+}
+
+/// Draw the voice count and the grid of `tracker`'s active notes.
+pub fn show(ui: &mut egui::Ui, tracker: &PolyphonyTracker) {
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(tracker.active_voice_count().to_string())
+                .size(28.0)
+                .strong(),
+        );
+        ui.label("active voices");
+    });
+
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), 16.0 * ROW_HEIGHT),
+        egui::Sense::hover(),
+    );
+    let grid = egui::Rect::from_min_max(rect.min + egui::vec2(LABEL_WIDTH, 0.0), rect.max);
+    let column = grid.width() / 128.0;
+    let cell = |channel: MidiChannel, note: u8| {
+        egui::Rect::from_min_size(
+            grid.min
+                + egui::vec2(
+                    f32::from(note) * column,
+                    f32::from(channel.as_index()) * ROW_HEIGHT,
+                ),
+            egui::vec2(column, ROW_HEIGHT),
+        )
+    };
+
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(grid, 0.0, ui.visuals().extreme_bg_color);
+    for note in (0..128u8).filter(|&n| is_black_key(n)) {
+        let x = grid.left() + f32::from(note) * column;
+        painter.rect_filled(
+            egui::Rect::from_x_y_ranges(x..=x + column, grid.y_range()),
+            0.0,
+            egui::Color32::from_black_alpha(60),
+        );
+    }
+    for index in 0..16u8 {
+        let y = grid.top() + f32::from(index) * ROW_HEIGHT;
+        if index % 4 == 0 {
+            painter.text(
+                egui::pos2(rect.left() + 2.0, y + ROW_HEIGHT / 2.0),
+                egui::Align2::LEFT_CENTER,
+                (index + 1).to_string(),
+                egui::FontId::monospace(8.0),
+                ui.visuals().weak_text_color(),
+            );
+        }
+        painter.hline(
+            grid.x_range(),
+            y,
+            egui::Stroke::new(0.5, egui::Color32::from_gray(50)),
+        );
+    }
+    for (&(channel, note), state) in tracker.active_notes() {
+        painter.rect_filled(cell(channel, note), 1.0, velocity_color(state.velocity));
+    }
+
+    let hovered = response
+        .hover_pos()
+        .and_then(|pos| cell_at(grid.width(), pos - grid.min))
+        .and_then(|key| Some((key, tracker.active_notes().get(&key)?)));
+    if let Some(((channel, note), state)) = hovered {
+        response.on_hover_text(format!(
+            "{} on {} — velocity {}, held {:.1}s",
+            vst3_host::midi::note_to_name(note),
+            channel,
+            state.velocity,
+            state.note_on_time.elapsed().as_secs_f32()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_positions_map_to_channel_and_pitch() {
+        let width = 128.0 * 4.0;
+        assert_eq!(
+            cell_at(width, egui::vec2(0.0, 0.0)),
+            Some((MidiChannel::Ch1, 0))
+        );
+        assert_eq!(
+            cell_at(width, egui::vec2(60.0 * 4.0 + 1.0, ROW_HEIGHT * 9.5)),
+            Some((MidiChannel::Ch10, 60))
+        );
+        assert_eq!(cell_at(width, egui::vec2(width, 0.0)), None);
+        assert_eq!(cell_at(width, egui::vec2(0.0, ROW_HEIGHT * 16.0)), None);
+        assert!(is_black_key(61) && !is_black_key(60));
+    }
+}
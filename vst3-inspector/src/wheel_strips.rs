@@ -0,0 +1,152 @@
+//! The pitch bend and mod wheel strips under the piano keyboard: tall bars dragged up and down.
+//! The pitch bend strip is bipolar (-1..=1) and springs back to center on release; the mod
+//! wheel (0..=1) stays where it's left. While dragged, each shows its value beside the handle.
+
+use eframe::egui;
+
+/// Height of a strip, in points.
+pub const STRIP_HEIGHT: f32 = 100.0;
+/// Inset of a strip's travel from its top and bottom edges, so the handle stays inside.
+const HANDLE_INSET: f32 = 4.0;
+
+/// How a strip maps height to value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StripKind {
+    /// -1.0 at the bottom, 0.0 in the middle, +1.0 at the top; springs back to 0.0
+    PitchBend,
+    /// 0.0 at the bottom to 1.0 at the top; holds its value
+    ModWheel,
+}
+
+impl StripKind {
+    fn range(self) -> (f64, f64) {
+        match self {
+            StripKind::PitchBend => (-1.0, 1.0),
+            StripKind::ModWheel => (0.0, 1.0),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            StripKind::PitchBend => "Bend",
+            StripKind::ModWheel => "Mod",
+        }
+    }
+
+    /// The floating readout: the signed 14-bit bend, or the CC 1 value.
+    fn readout(self, value: f64) -> String {
+        match self {
+            StripKind::PitchBend => format!("{:+}", pitch_bend_offset(value)),
+            StripKind::ModWheel => format!("CC1 {}", mod_wheel_cc(value)),
+        }
+    }
+}
+
+/// Signed distance from center (-8192..=8191) of the bend `value` encodes as.
+pub fn pitch_bend_offset(value: f64) -> i32 {
+    match vst3_host::MidiEvent::pitch_bend(vst3_host::MidiChannel::Ch1, value) {
+        vst3_host::MidiEvent::PitchBend { value, .. } => i32::from(value) - 8192,
+        _ => 0,
+    }
+}
+
+/// The 7-bit CC 1 value of mod wheel position `value` (0..=1).
+pub fn mod_wheel_cc(value: f64) -> u8 {
+    (value.clamp(0.0, 1.0) * 127.0).round() as u8
+}
+
+/// Value at `y` points down from the top of a strip `height` tall.
+fn value_at(kind: StripKind, height: f32, y: f32) -> f64 {
+    let travel = (height - 2.0 * HANDLE_INSET).max(1.0);
+    let up = f64::from(1.0 - ((y - HANDLE_INSET) / travel).clamp(0.0, 1.0));
+    let (min, max) = kind.range();
+    min + up * (max - min)
+}
+
+/// Distance from the top of a strip `height` tall of the handle at `value`.
+fn y_of(kind: StripKind, height: f32, value: f64) -> f32 {
+    let (min, max) = kind.range();
+    let up = ((value - min) / (max - min)).clamp(0.0, 1.0) as f32;
+    HANDLE_INSET + (1.0 - up) * (height - 2.0 * HANDLE_INSET)
+}
+
+/// Draw one strip `width` wide and apply this frame's drag to `value`. Returns whether
+/// `value` changed, including the pitch bend strip snapping back to center on release.
+pub fn show(ui: &mut egui::Ui, kind: StripKind, width: f32, value: &mut f64) -> bool {
+    let (rect, response) =
+        ui.allocate_exact_size(egui::vec2(width, STRIP_HEIGHT), egui::Sense::drag());
+    let before = *value;
+    if let Some(pos) = response.interact_pointer_pos() {
+        *value = value_at(kind, rect.height(), pos.y - rect.top());
+    }
+    if kind == StripKind::PitchBend && response.drag_stopped() {
+        *value = 0.0;
+    }
+
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 3.0, ui.visuals().extreme_bg_color);
+    if kind == StripKind::PitchBend {
+        let center = rect.top() + y_of(kind, rect.height(), 0.0);
+        painter.hline(
+            rect.x_range(),
+            center,
+            egui::Stroke::new(1.0, egui::Color32::from_gray(70)),
+        );
+    }
+    let handle_y = rect.top() + y_of(kind, rect.height(), *value);
+    let handle = egui::Rect::from_center_size(
+        egui::pos2(rect.center().x, handle_y),
+        egui::vec2(width - 4.0, 6.0),
+    );
+    let handle_color = if response.dragged() {
+        ui.visuals().strong_text_color()
+    } else {
+        egui::Color32::from_rgb(90, 160, 230)
+    };
+    painter.rect_filled(handle, 2.0, handle_color);
+    painter.text(
+        egui::pos2(rect.center().x, rect.bottom() - 8.0),
+        egui::Align2::CENTER_CENTER,
+        kind.label(),
+        egui::FontId::proportional(10.0),
+        ui.visuals().weak_text_color(),
+    );
+
+    if response.dragged() {
+        // Drawn on the tooltip layer so it can float past the strip's edge.
+        let readout = ui.ctx().layer_painter(egui::LayerId::new(
+            egui::Order::Tooltip,
+            response.id.with("readout"),
+        ));
+        readout.text(
+            egui::pos2(rect.right() + 6.0, handle_y),
+            egui::Align2::LEFT_CENTER,
+            kind.readout(*value),
+            egui::FontId::monospace(11.0),
+            ui.visuals().strong_text_color(),
+        );
+    }
+    *value != before
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_heights_map_to_values_and_back() {
+        let bend = StripKind::PitchBend;
+        assert_eq!(value_at(bend, STRIP_HEIGHT, 0.0), 1.0);
+        assert_eq!(value_at(bend, STRIP_HEIGHT, STRIP_HEIGHT), -1.0);
+        assert_eq!(value_at(bend, STRIP_HEIGHT, STRIP_HEIGHT / 2.0), 0.0);
+        assert_eq!(y_of(bend, STRIP_HEIGHT, 0.0), STRIP_HEIGHT / 2.0);
+        assert_eq!(
+            value_at(StripKind::ModWheel, STRIP_HEIGHT, STRIP_HEIGHT),
+            0.0
+        );
+
+        assert_eq!(pitch_bend_offset(1.0), 8191);
+        assert_eq!(pitch_bend_offset(-1.0), -8192);
+        assert_eq!(mod_wheel_cc(0.5), 64);
+    }
+}